@@ -0,0 +1,665 @@
+//! Planar subdivision (also known as arrangement) of a set of paths.
+//!
+//! The paths are flattened and split at all of their intersections. The resulting
+//! edges partition the plane into faces, each of which has a winding number per
+//! input path. This is the general machinery behind boolean operations, merging
+//! overlapping layers or coloring the regions of line art.
+//!
+//! The arrangement is stored as a half-edge data structure: each edge is
+//! represented by two half-edges going in opposite directions, and each half-edge
+//! has the face on its left side.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate lyon_algorithms;
+//! # use lyon_algorithms::path::default::Path;
+//! # use lyon_algorithms::path::builder::*;
+//! # use lyon_algorithms::math::point;
+//! # use lyon_algorithms::arrangement::Arrangement;
+//! # fn main() {
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(2.0, 0.0));
+//! builder.line_to(point(2.0, 2.0));
+//! builder.line_to(point(0.0, 2.0));
+//! builder.close();
+//! builder.move_to(point(1.0, 1.0));
+//! builder.line_to(point(3.0, 1.0));
+//! builder.line_to(point(3.0, 3.0));
+//! builder.line_to(point(1.0, 3.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let arrangement = Arrangement::from_paths(Some(path.path_iter()), 0.01);
+//!
+//! // The unbounded face, the overlap and the two non-overlapping parts.
+//! assert_eq!(arrangement.num_faces(), 4);
+//! # }
+//! ```
+
+use path::iterator::PathIterator;
+use path::FlattenedEvent;
+use path::default::Path;
+use path::builder::FlatPathBuilder;
+use math::Point;
+use geom::LineSegment;
+
+use std::collections::HashMap;
+use std::cmp::Ordering;
+
+/// Identifies a vertex of an `Arrangement`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexId(pub u32);
+
+/// Identifies a half-edge of an `Arrangement`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HalfEdgeId(pub u32);
+
+/// Identifies a face of an `Arrangement`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FaceId(pub u32);
+
+#[derive(Copy, Clone, Debug)]
+struct HalfEdge {
+    origin: VertexId,
+    next: HalfEdgeId,
+    face: FaceId,
+}
+
+#[derive(Clone, Debug)]
+struct Face {
+    outer: Option<HalfEdgeId>,
+    holes: Vec<HalfEdgeId>,
+}
+
+/// The planar subdivision induced by a set of paths.
+///
+/// Sub-paths are implicitly closed, like when filling.
+#[derive(Clone, Debug)]
+pub struct Arrangement {
+    points: Vec<Point>,
+    half_edges: Vec<HalfEdge>,
+    // For each edge (pair of half-edges), the range in `contributions`.
+    edge_contributions: Vec<(u32, u32)>,
+    // (path index, winding delta along the even half-edge of the edge).
+    contributions: Vec<(u32, i32)>,
+    faces: Vec<Face>,
+    // num_faces * num_paths winding numbers.
+    windings: Vec<i32>,
+    num_paths: usize,
+}
+
+impl Arrangement {
+    /// Computes the arrangement of a set of paths.
+    ///
+    /// Curves are flattened with the provided tolerance and points that are closer
+    /// than a tenth of the tolerance are merged. All pairs of edges are tested for
+    /// intersections, so the cost is quadratic in the number of flattened edges.
+    pub fn from_paths<Paths>(paths: Paths, tolerance: f32) -> Self
+    where
+        Paths: IntoIterator,
+        Paths::Item: PathIterator,
+    {
+        let mut input = Vec::new();
+        let mut num_paths = 0;
+        for path in paths {
+            collect_edges(path, tolerance, num_paths as u32, &mut input);
+            num_paths += 1;
+        }
+
+        let snap = tolerance * 0.1;
+        let splits = compute_splits(&input, snap);
+
+        // Build the edges between snapped vertices.
+        let mut points = Vec::new();
+        let mut snapper = VertexSnapper { cells: HashMap::new(), snap };
+        let mut edge_map: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut edges: Vec<(u32, u32)> = Vec::new();
+        let mut edge_paths: Vec<Vec<(u32, i32)>> = Vec::new();
+        for (edge, ts) in input.iter().zip(splits.iter()) {
+            let mut prev = snapper.vertex(&mut points, edge.segment.from);
+            for &t in ts.iter().chain(Some(1.0).iter()) {
+                let p = if t == 1.0 { edge.segment.to } else { edge.segment.sample(t) };
+                let v = snapper.vertex(&mut points, p);
+                if v == prev {
+                    continue;
+                }
+
+                let (key, delta) = if prev < v { ((prev, v), 1) } else { ((v, prev), -1) };
+                let idx = *edge_map.entry(key).or_insert_with(|| {
+                    edges.push(key);
+                    edge_paths.push(Vec::new());
+                    edges.len() - 1
+                });
+                add_contribution(&mut edge_paths[idx], edge.path, delta);
+
+                prev = v;
+            }
+        }
+
+        let mut contributions = Vec::new();
+        let mut edge_contributions = Vec::with_capacity(edges.len());
+        for paths in &edge_paths {
+            let start = contributions.len() as u32;
+            contributions.extend(paths.iter().cloned());
+            edge_contributions.push((start, contributions.len() as u32));
+        }
+
+        // Link the half-edges around each vertex.
+        let mut outgoing: Vec<Vec<(f32, u32)>> = vec![Vec::new(); points.len()];
+        for (k, &(a, b)) in edges.iter().enumerate() {
+            let (pa, pb) = (points[a as usize], points[b as usize]);
+            outgoing[a as usize].push(((pb - pa).angle_from_x_axis().radians, 2 * k as u32));
+            outgoing[b as usize].push(((pa - pb).angle_from_x_axis().radians, 2 * k as u32 + 1));
+        }
+
+        let num_half_edges = edges.len() * 2;
+        let mut position = vec![0; num_half_edges];
+        for list in &mut outgoing {
+            list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+            for (i, &(_, h)) in list.iter().enumerate() {
+                position[h as usize] = i;
+            }
+        }
+
+        let origin = |h: usize| -> u32 {
+            if h & 1 == 0 { edges[h / 2].0 } else { edges[h / 2].1 }
+        };
+
+        let mut half_edges = Vec::with_capacity(num_half_edges);
+        for h in 0..num_half_edges {
+            // The next half-edge is the one that comes right before the twin
+            // in counter-clockwise order around the destination.
+            let twin = h ^ 1;
+            let list = &outgoing[origin(twin) as usize];
+            let idx = (position[twin] + list.len() - 1) % list.len();
+            half_edges.push(HalfEdge {
+                origin: VertexId(origin(h)),
+                next: HalfEdgeId(list[idx].1),
+                face: FaceId(0),
+            });
+        }
+
+        // Find the cycles of half-edges.
+        let mut cycle_of = vec![u32::MAX; num_half_edges];
+        let mut cycles: Vec<(usize, f32)> = Vec::new();
+        for h in 0..num_half_edges {
+            if cycle_of[h] != u32::MAX {
+                continue;
+            }
+            let mut area = 0.0;
+            let mut e = h;
+            loop {
+                cycle_of[e] = cycles.len() as u32;
+                let p = points[origin(e) as usize];
+                let q = points[origin(e ^ 1) as usize];
+                area += p.x * q.y - q.x * p.y;
+                e = half_edges[e].next.0 as usize;
+                if e == h {
+                    break;
+                }
+            }
+            cycles.push((h, area * 0.5));
+        }
+
+        // Connected components, to avoid matching a hole with a face of its own component.
+        let mut components: Vec<u32> = (0..points.len() as u32).collect();
+        for &(a, b) in &edges {
+            let ra = find_root(&mut components, a);
+            let rb = find_root(&mut components, b);
+            components[ra as usize] = rb;
+        }
+
+        // Cycles with a positive area are the outer boundaries of the bounded faces,
+        // the others are the outer boundaries of connected components and are holes
+        // in the face that contains them.
+        let mut faces = vec![Face { outer: None, holes: Vec::new() }];
+        let mut face_of_cycle = vec![FaceId(0); cycles.len()];
+        for (c, &(h, area)) in cycles.iter().enumerate() {
+            if area > 0.0 {
+                face_of_cycle[c] = FaceId(faces.len() as u32);
+                faces.push(Face { outer: Some(HalfEdgeId(h as u32)), holes: Vec::new() });
+            }
+        }
+
+        for (c, &(h, area)) in cycles.iter().enumerate() {
+            if area > 0.0 {
+                continue;
+            }
+
+            let p = points[origin(h) as usize];
+            let component = find_root(&mut components, origin(h));
+            let mut best: Option<(usize, f32)> = None;
+            for (c2, &(h2, area2)) in cycles.iter().enumerate() {
+                if area2 <= 0.0 || best.is_some_and(|(_, a)| a <= area2) {
+                    continue;
+                }
+                if find_root(&mut components, origin(h2)) == component {
+                    continue;
+                }
+                if cycle_contains_point(&half_edges, &points, h2, p) {
+                    best = Some((c2, area2));
+                }
+            }
+
+            let face = match best {
+                Some((c2, _)) => face_of_cycle[c2],
+                None => FaceId(0),
+            };
+            face_of_cycle[c] = face;
+            faces[face.0 as usize].holes.push(HalfEdgeId(h as u32));
+        }
+
+        for h in 0..num_half_edges {
+            half_edges[h].face = face_of_cycle[cycle_of[h] as usize];
+        }
+
+        // Propagate the winding numbers from the unbounded face, crossing one edge
+        // at a time.
+        let num_faces = faces.len();
+        let mut face_half_edges: Vec<Vec<usize>> = vec![Vec::new(); num_faces];
+        for h in 0..num_half_edges {
+            face_half_edges[half_edges[h].face.0 as usize].push(h);
+        }
+
+        let mut windings = vec![0; num_faces * num_paths];
+        let mut visited = vec![false; num_faces];
+        let mut stack = vec![0];
+        visited[0] = true;
+        while let Some(f) = stack.pop() {
+            for &h in &face_half_edges[f] {
+                let g = half_edges[h ^ 1].face.0 as usize;
+                if visited[g] {
+                    continue;
+                }
+                visited[g] = true;
+
+                for p in 0..num_paths {
+                    windings[g * num_paths + p] = windings[f * num_paths + p];
+                }
+                // The winding number of the face on the left of a half-edge is the one
+                // on the right plus the half-edge's contribution.
+                let sign = if h % 2 == 0 { 1 } else { -1 };
+                let (start, end) = edge_contributions[h / 2];
+                for &(path, delta) in &contributions[start as usize..end as usize] {
+                    windings[g * num_paths + path as usize] -= delta * sign;
+                }
+
+                stack.push(g);
+            }
+        }
+
+        Arrangement {
+            points,
+            half_edges,
+            edge_contributions,
+            contributions,
+            faces,
+            windings,
+            num_paths,
+        }
+    }
+
+    /// Number of input paths.
+    pub fn num_paths(&self) -> usize { self.num_paths }
+
+    pub fn num_vertices(&self) -> usize { self.points.len() }
+
+    pub fn num_half_edges(&self) -> usize { self.half_edges.len() }
+
+    /// Number of faces, including the unbounded face.
+    pub fn num_faces(&self) -> usize { self.faces.len() }
+
+    /// The face that extends to infinity.
+    pub fn unbounded_face(&self) -> FaceId { FaceId(0) }
+
+    pub fn vertex_position(&self, id: VertexId) -> Point {
+        self.points[id.0 as usize]
+    }
+
+    /// The vertex at the start of a half-edge.
+    pub fn origin(&self, id: HalfEdgeId) -> VertexId {
+        self.half_edges[id.0 as usize].origin
+    }
+
+    /// The half-edge going in the opposite direction.
+    pub fn twin(&self, id: HalfEdgeId) -> HalfEdgeId {
+        HalfEdgeId(id.0 ^ 1)
+    }
+
+    /// The next half-edge along the boundary of the face.
+    pub fn next(&self, id: HalfEdgeId) -> HalfEdgeId {
+        self.half_edges[id.0 as usize].next
+    }
+
+    /// The face on the left side of a half-edge.
+    pub fn face(&self, id: HalfEdgeId) -> FaceId {
+        self.half_edges[id.0 as usize].face
+    }
+
+    /// The winding number of a path along a half-edge: positive if the path goes
+    /// in the same direction, negative otherwise, and zero if the half-edge isn't
+    /// part of the path.
+    pub fn edge_winding(&self, id: HalfEdgeId, path: usize) -> i32 {
+        let sign = if id.0 & 1 == 0 { 1 } else { -1 };
+        let (start, end) = self.edge_contributions[id.0 as usize / 2];
+        for &(p, delta) in &self.contributions[start as usize..end as usize] {
+            if p as usize == path {
+                return delta * sign;
+            }
+        }
+
+        0
+    }
+
+    /// The winding number of a path in a face.
+    pub fn winding(&self, face: FaceId, path: usize) -> i32 {
+        self.windings[face.0 as usize * self.num_paths + path]
+    }
+
+    /// The winding numbers of each path in a face.
+    pub fn windings(&self, face: FaceId) -> &[i32] {
+        let start = face.0 as usize * self.num_paths;
+        &self.windings[start..start + self.num_paths]
+    }
+
+    /// A half-edge of the outer boundary of a face, or `None` for the unbounded face.
+    pub fn outer_boundary(&self, face: FaceId) -> Option<HalfEdgeId> {
+        self.faces[face.0 as usize].outer
+    }
+
+    /// A half-edge of the boundary of each hole in a face.
+    pub fn holes(&self, face: FaceId) -> &[HalfEdgeId] {
+        &self.faces[face.0 as usize].holes
+    }
+
+    /// The points along the cycle of half-edges starting at a given half-edge.
+    pub fn boundary_points(&self, first: HalfEdgeId) -> Vec<Point> {
+        let mut result = Vec::new();
+        let mut h = first;
+        loop {
+            result.push(self.vertex_position(self.origin(h)));
+            h = self.next(h);
+            if h == first {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// The outer boundary of a face as a polygon (empty for the unbounded face).
+    pub fn face_polygon(&self, face: FaceId) -> Vec<Point> {
+        match self.outer_boundary(face) {
+            Some(h) => self.boundary_points(h),
+            None => Vec::new(),
+        }
+    }
+
+    /// Builds a path with the outer boundary and the holes of a face.
+    pub fn face_path(&self, face: FaceId) -> Path {
+        let mut builder = Path::builder();
+        let boundaries = self.outer_boundary(face).into_iter()
+            .chain(self.holes(face).iter().cloned());
+        for h in boundaries {
+            let points = self.boundary_points(h);
+            builder.move_to(points[0]);
+            for p in &points[1..] {
+                builder.line_to(*p);
+            }
+            builder.close();
+        }
+
+        builder.build()
+    }
+
+    /// The faces that share an edge with a given face.
+    pub fn adjacent_faces(&self, face: FaceId) -> Vec<FaceId> {
+        let mut result = Vec::new();
+        let boundaries = self.outer_boundary(face).into_iter()
+            .chain(self.holes(face).iter().cloned());
+        for first in boundaries {
+            let mut h = first;
+            loop {
+                let other = self.face(self.twin(h));
+                if other != face && !result.contains(&other) {
+                    result.push(other);
+                }
+                h = self.next(h);
+                if h == first {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+struct InputEdge {
+    segment: LineSegment<f32>,
+    path: u32,
+}
+
+fn collect_edges<Iter: PathIterator>(path: Iter, tolerance: f32, index: u32, output: &mut Vec<InputEdge>) {
+    let mut push = |from: Point, to: Point| {
+        if from != to && from.x.is_finite() && from.y.is_finite() && to.x.is_finite() && to.y.is_finite() {
+            output.push(InputEdge { segment: LineSegment { from, to }, path: index });
+        }
+    };
+
+    let mut first = Point::new(0.0, 0.0);
+    let mut current = first;
+    for evt in path.flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                push(current, first);
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::LineTo(to) => {
+                push(current, to);
+                current = to;
+            }
+            FlattenedEvent::Close => {
+                push(current, first);
+                current = first;
+            }
+        }
+    }
+    push(current, first);
+}
+
+// Computes the sorted t parameters at which each edge must be split.
+fn compute_splits(edges: &[InputEdge], snap: f32) -> Vec<Vec<f32>> {
+    let mut splits = vec![Vec::new(); edges.len()];
+    for i in 0..edges.len() {
+        let a = &edges[i].segment;
+        let (a_min_x, a_max_x) = a.bounding_range_x();
+        let (a_min_y, a_max_y) = a.bounding_range_y();
+        for j in (i + 1)..edges.len() {
+            let b = &edges[j].segment;
+            let (b_min_x, b_max_x) = b.bounding_range_x();
+            let (b_min_y, b_max_y) = b.bounding_range_y();
+            if a_min_x > b_max_x + snap || b_min_x > a_max_x + snap
+                || a_min_y > b_max_y + snap || b_min_y > a_max_y + snap {
+                continue;
+            }
+
+            if let Some((ta, tb)) = a.intersection_t(b) {
+                splits[i].push(ta);
+                splits[j].push(tb);
+            }
+
+            // T-junctions and overlapping edges.
+            split_at_point(a, b.from, snap, &mut splits[i]);
+            split_at_point(a, b.to, snap, &mut splits[i]);
+            split_at_point(b, a.from, snap, &mut splits[j]);
+            split_at_point(b, a.to, snap, &mut splits[j]);
+        }
+    }
+
+    for ts in &mut splits {
+        ts.retain(|&t| t > 0.0 && t < 1.0);
+        ts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    }
+
+    splits
+}
+
+fn split_at_point(segment: &LineSegment<f32>, p: Point, snap: f32, splits: &mut Vec<f32>) {
+    let v = segment.to_vector();
+    let t = (p - segment.from).dot(v) / v.square_length();
+    if t > 0.0 && t < 1.0 && (segment.sample(t) - p).square_length() <= snap * snap {
+        splits.push(t);
+    }
+}
+
+fn add_contribution(contributions: &mut Vec<(u32, i32)>, path: u32, delta: i32) {
+    for c in contributions.iter_mut() {
+        if c.0 == path {
+            c.1 += delta;
+            return;
+        }
+    }
+    contributions.push((path, delta));
+}
+
+// Merges points that are closer than the snap distance.
+struct VertexSnapper {
+    cells: HashMap<(i32, i32), Vec<u32>>,
+    snap: f32,
+}
+
+impl VertexSnapper {
+    fn vertex(&mut self, points: &mut Vec<Point>, p: Point) -> u32 {
+        let cell_size = if self.snap > 0.0 { self.snap } else { 1.0 };
+        let cx = (p.x / cell_size).floor() as i32;
+        let cy = (p.y / cell_size).floor() as i32;
+        for dx in -1..2 {
+            for dy in -1..2 {
+                if let Some(ids) = self.cells.get(&(cx + dx, cy + dy)) {
+                    for &id in ids {
+                        if (points[id as usize] - p).square_length() <= self.snap * self.snap {
+                            return id;
+                        }
+                    }
+                }
+            }
+        }
+
+        let id = points.len() as u32;
+        points.push(p);
+        self.cells.entry((cx, cy)).or_default().push(id);
+
+        id
+    }
+}
+
+fn find_root(parents: &mut [u32], mut v: u32) -> u32 {
+    while parents[v as usize] != v {
+        let grand_parent = parents[parents[v as usize] as usize];
+        parents[v as usize] = grand_parent;
+        v = grand_parent;
+    }
+
+    v
+}
+
+fn cycle_contains_point(half_edges: &[HalfEdge], points: &[Point], first: usize, p: Point) -> bool {
+    let mut inside = false;
+    let mut h = first;
+    loop {
+        let a = points[half_edges[h].origin.0 as usize];
+        let b = points[half_edges[h ^ 1].origin.0 as usize];
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y);
+            if x > p.x {
+                inside = !inside;
+            }
+        }
+        h = half_edges[h].next.0 as usize;
+        if h == first {
+            break;
+        }
+    }
+
+    inside
+}
+
+#[test]
+fn overlapping_squares() {
+    use math::point;
+
+    fn square(x: f32, y: f32, size: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.move_to(point(x, y));
+        builder.line_to(point(x + size, y));
+        builder.line_to(point(x + size, y + size));
+        builder.line_to(point(x, y + size));
+        builder.close();
+        builder.build()
+    }
+
+    let a = square(0.0, 0.0, 2.0);
+    let b = square(1.0, 1.0, 2.0);
+    let arrangement = Arrangement::from_paths(vec![a.path_iter(), b.path_iter()], 0.01);
+
+    assert_eq!(arrangement.num_vertices(), 10);
+    assert_eq!(arrangement.num_faces(), 4);
+    assert_eq!(arrangement.windings(arrangement.unbounded_face()), &[0, 0]);
+
+    let mut windings = Vec::new();
+    let mut sizes = Vec::new();
+    for i in 1..arrangement.num_faces() {
+        let face = FaceId(i as u32);
+        windings.push(arrangement.windings(face).to_vec());
+        sizes.push(arrangement.face_polygon(face).len());
+        // The overlap is surrounded by the two other faces, which border the unbounded one.
+        let is_overlap = arrangement.windings(face) == [1, 1];
+        assert_eq!(arrangement.adjacent_faces(face).contains(&arrangement.unbounded_face()), !is_overlap);
+    }
+    windings.sort();
+    sizes.sort();
+    assert_eq!(windings, vec![vec![0, 1], vec![1, 0], vec![1, 1]]);
+    // The overlap is a square and the other two faces are L-shaped.
+    assert_eq!(sizes, vec![4, 6, 6]);
+}
+
+#[test]
+fn nested_squares() {
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    // Opposite winding order.
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(2.0, 4.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.line_to(point(4.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    let arrangement = Arrangement::from_paths(Some(path.path_iter()), 0.01);
+
+    assert_eq!(arrangement.num_faces(), 3);
+    let mut ring = None;
+    for i in 1..3 {
+        let face = FaceId(i);
+        match arrangement.winding(face, 0) {
+            1 => { ring = Some(face); }
+            0 => { assert_eq!(arrangement.holes(face).len(), 0); }
+            w => { panic!("unexpected winding {}", w); }
+        }
+    }
+
+    let ring = ring.unwrap();
+    assert_eq!(arrangement.holes(ring).len(), 1);
+    assert_eq!(arrangement.face_path(ring).iter().filter(|evt| *evt == ::path::PathEvent::Close).count(), 2);
+    assert_eq!(arrangement.adjacent_faces(ring).len(), 2);
+}
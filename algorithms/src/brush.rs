@@ -0,0 +1,577 @@
+//! Generate geometry by repeating or bending a profile path along a guide path.
+//!
+//! The profile is expressed in a coordinate system where the x axis follows the
+//! direction of the guide path and the y axis is perpendicular to it (pointing
+//! toward the left of the guide path in a y-up coordinate system). Copies of the
+//! profile are placed one after the other along each sub-path of the guide, which
+//! makes it possible to create decorative brushes, ribbons or map symbols such as
+//! railway tracks.
+//!
+//! The result is written into a path builder, and can be tessellated like any
+//! other path.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::brush::{brush_along_path, BrushOptions, BrushMode};
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! // The guide path.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(100.0, 0.0));
+//! builder.line_to(point(100.0, 100.0));
+//! let guide = builder.build();
+//!
+//! // A railway tie: a 2 by 10 rectangle centered on the guide path.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, -5.0));
+//! builder.line_to(point(2.0, -5.0));
+//! builder.line_to(point(2.0, 5.0));
+//! builder.line_to(point(0.0, 5.0));
+//! builder.close();
+//! let tie = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! brush_along_path(
+//!     guide.path_iter(),
+//!     tie.as_slice(),
+//!     &BrushOptions::mode(BrushMode::Stamp).with_spacing(8.0),
+//!     &mut builder,
+//! );
+//! let ties = builder.build();
+//! ```
+
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use path::builder::PathBuilder;
+use path::default::PathSlice;
+use math::{Point, Vector, Angle, Transform2D, vector};
+use markers::{Marker, MarkerKind, rigid_transform_event};
+use aabb::bounding_rect;
+
+/// How the copies of the profile follow the guide path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum BrushMode {
+    /// Each copy of the profile is bent to follow the guide path.
+    ///
+    /// The profile is flattened and its edges are split at the vertices of the
+    /// flattened guide path so that the copies follow its corners.
+    Bend,
+    /// A single copy of the profile is stretched and bent along each sub-path.
+    Stretch,
+    /// Each copy of the profile is rotated and translated without being deformed,
+    /// following the direction of the guide path at the center of the copy.
+    Stamp,
+}
+
+/// Parameters for `brush_along_path`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct BrushOptions {
+    /// How the copies of the profile follow the guide path.
+    ///
+    /// Default value: `BrushMode::Bend`.
+    pub mode: BrushMode,
+
+    /// Distance between two consecutive copies of the profile.
+    ///
+    /// Default value: `0.0`.
+    pub spacing: f32,
+
+    /// Distance between the start of each sub-path and its first copy.
+    ///
+    /// Default value: `0.0`.
+    pub start_offset: f32,
+
+    /// Maximum allowed distance to the path when building an approximation.
+    ///
+    /// Default value: `BrushOptions::DEFAULT_TOLERANCE`.
+    pub tolerance: f32,
+
+    /// Limits how far the bent profile extends at sharp corners of the guide path,
+    /// as a ratio of its distance to the guide path.
+    ///
+    /// Default value: `BrushOptions::DEFAULT_MITER_LIMIT`.
+    pub miter_limit: f32,
+}
+
+impl Default for BrushOptions {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+impl BrushOptions {
+    /// Default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    /// Default miter limit.
+    pub const DEFAULT_MITER_LIMIT: f32 = 4.0;
+
+    pub const DEFAULT: Self = BrushOptions {
+        mode: BrushMode::Bend,
+        spacing: 0.0,
+        start_offset: 0.0,
+        tolerance: Self::DEFAULT_TOLERANCE,
+        miter_limit: Self::DEFAULT_MITER_LIMIT,
+    };
+
+    #[inline]
+    pub fn mode(mode: BrushMode) -> Self {
+        Self::DEFAULT.with_mode(mode)
+    }
+
+    #[inline]
+    pub fn tolerance(tolerance: f32) -> Self {
+        Self::DEFAULT.with_tolerance(tolerance)
+    }
+
+    #[inline]
+    pub fn with_mode(mut self, mode: BrushMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[inline]
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    #[inline]
+    pub fn with_start_offset(mut self, offset: f32) -> Self {
+        self.start_offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    #[inline]
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        assert!(limit >= 1.0);
+        self.miter_limit = limit;
+        self
+    }
+}
+
+/// Places copies of the `profile` path along each sub-path of the `guide` path and
+/// writes the result into `builder`.
+///
+/// The extent of the profile along the x axis defines the length of each copy.
+/// Copies are only placed where they fit entirely in the sub-path.
+pub fn brush_along_path<Iter, Builder>(
+    guide: Iter,
+    profile: PathSlice,
+    options: &BrushOptions,
+    builder: &mut Builder,
+)
+where
+    Iter: PathIterator,
+    Builder: PathBuilder,
+{
+    let bounds = bounding_rect(profile.path_iter());
+    let min_x = bounds.origin.x;
+    let width = bounds.size.width;
+
+    let mut flat_profile = Vec::new();
+    if options.mode != BrushMode::Stamp {
+        flat_profile.extend(profile.path_iter().flattened(options.tolerance));
+    }
+
+    let mut polyline = Polyline::new();
+    for evt in guide.flattened(options.tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                polyline.brush(&flat_profile, profile, min_x, width, options, builder);
+                polyline.clear();
+                polyline.push(to);
+            }
+            FlattenedEvent::LineTo(to) => {
+                polyline.push(to);
+            }
+            FlattenedEvent::Close => {
+                let first = polyline.points[0];
+                polyline.push(first);
+                polyline.closed = true;
+                polyline.brush(&flat_profile, profile, min_x, width, options, builder);
+                polyline.clear();
+                polyline.push(first);
+            }
+        }
+    }
+
+    polyline.brush(&flat_profile, profile, min_x, width, options, builder);
+}
+
+// A flattened sub-path of the guide.
+struct Polyline {
+    points: Vec<Point>,
+    // Distance along the sub-path at each point.
+    distances: Vec<f32>,
+    // Direction in which the profile's y axis is mapped at each point.
+    miters: Vec<Vector>,
+    closed: bool,
+}
+
+impl Polyline {
+    fn new() -> Self {
+        Polyline {
+            points: Vec::new(),
+            distances: Vec::new(),
+            miters: Vec::new(),
+            closed: false,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.distances.clear();
+        self.miters.clear();
+        self.closed = false;
+    }
+
+    fn push(&mut self, p: Point) {
+        let distance = match self.points.last() {
+            Some(prev) => {
+                let d = (p - *prev).length();
+                if d < 1e-5 {
+                    return;
+                }
+                self.distances[self.distances.len() - 1] + d
+            }
+            None => 0.0,
+        };
+        self.points.push(p);
+        self.distances.push(distance);
+    }
+
+    fn length(&self) -> f32 {
+        self.distances.last().cloned().unwrap_or(0.0)
+    }
+
+    fn normal(&self, segment: usize) -> Vector {
+        let v = (self.points[segment + 1] - self.points[segment]).normalize();
+        vector(-v.y, v.x)
+    }
+
+    fn compute_miters(&mut self, miter_limit: f32) {
+        let n = self.points.len();
+        self.miters.clear();
+        for i in 0..n {
+            let miter = if i > 0 && i < n - 1 {
+                miter(self.normal(i - 1), self.normal(i), miter_limit)
+            } else if self.closed {
+                miter(self.normal(n - 2), self.normal(0), miter_limit)
+            } else if i == 0 {
+                self.normal(0)
+            } else {
+                self.normal(n - 2)
+            };
+            self.miters.push(miter);
+        }
+    }
+
+    // The index of the segment containing the distance.
+    fn segment_at(&self, distance: f32) -> usize {
+        let last_segment = self.points.len() - 2;
+        match self.distances.binary_search_by(|d| d.partial_cmp(&distance).unwrap()) {
+            Ok(i) => ::std::cmp::min(i, last_segment),
+            Err(i) => ::std::cmp::min(i.saturating_sub(1), last_segment),
+        }
+    }
+
+    // Maps a point of the profile, expressed as a distance along the sub-path and an
+    // offset perpendicular to it.
+    fn map(&self, distance: f32, offset: f32) -> Point {
+        let i = self.segment_at(distance);
+        let length = self.distances[i + 1] - self.distances[i];
+        let t = (distance - self.distances[i]) / length;
+        let position = self.points[i].lerp(self.points[i + 1], t);
+        let miter = self.miters[i] * (1.0 - t) + self.miters[i + 1] * t;
+
+        position + miter * offset
+    }
+
+    // The position and direction at a given distance.
+    fn sample(&self, distance: f32) -> (Point, Vector) {
+        let i = self.segment_at(distance);
+        let t = (distance - self.distances[i]) / (self.distances[i + 1] - self.distances[i]);
+        let tangent = self.points[i + 1] - self.points[i];
+
+        (self.points[i].lerp(self.points[i + 1], t), tangent)
+    }
+
+    fn brush<Builder: PathBuilder>(
+        &mut self,
+        flat_profile: &[FlattenedEvent],
+        profile: PathSlice,
+        min_x: f32,
+        width: f32,
+        options: &BrushOptions,
+        builder: &mut Builder,
+    ) {
+        if self.points.len() < 2 {
+            return;
+        }
+
+        let length = self.length();
+
+        if options.mode == BrushMode::Stretch {
+            if width > 0.0 {
+                self.compute_miters(options.miter_limit);
+                self.bend(flat_profile, min_x, length / width, 0.0, builder);
+            }
+            return;
+        }
+
+        let step = width + options.spacing;
+        let mut start = options.start_offset.max(0.0);
+        while start + width <= length + 1e-5 {
+            match options.mode {
+                BrushMode::Stamp => {
+                    self.stamp(profile, start - min_x + width * 0.5, min_x + width * 0.5, builder);
+                }
+                _ => {
+                    if self.miters.is_empty() {
+                        self.compute_miters(options.miter_limit);
+                    }
+                    self.bend(flat_profile, min_x, 1.0, start, builder);
+                }
+            }
+
+            // Avoid looping forever with empty profiles.
+            if step <= 1e-5 {
+                break;
+            }
+            start += step;
+        }
+    }
+
+    fn stamp<Builder: PathBuilder>(
+        &self,
+        profile: PathSlice,
+        distance: f32,
+        center_x: f32,
+        builder: &mut Builder,
+    ) {
+        let (position, tangent) = self.sample(distance.min(self.length()));
+        let marker = Marker {
+            position,
+            angle: Angle::radians(tangent.y.atan2(tangent.x)),
+            kind: MarkerKind::Mid,
+        };
+        let transform = Transform2D::create_translation(-center_x, 0.0).post_mul(&marker.transform());
+        for evt in profile.path_iter() {
+            builder.path_event(rigid_transform_event(evt, &transform, marker.angle));
+        }
+    }
+
+    // Adds a copy of the flattened profile, mapping its x coordinates to
+    // `(x - min_x) * scale + start` along the sub-path.
+    fn bend<Builder: PathBuilder>(
+        &self,
+        flat_profile: &[FlattenedEvent],
+        min_x: f32,
+        scale: f32,
+        start: f32,
+        builder: &mut Builder,
+    ) {
+        let length = self.length();
+        let to_distance = |p: Point| ((p.x - min_x) * scale + start).max(0.0).min(length);
+
+        let mut first = Point::new(0.0, 0.0);
+        let mut prev = first;
+        for evt in flat_profile {
+            match *evt {
+                FlattenedEvent::MoveTo(to) => {
+                    builder.move_to(self.map(to_distance(to), to.y));
+                    first = to;
+                    prev = to;
+                }
+                FlattenedEvent::LineTo(to) => {
+                    self.bend_edge(prev, to, &to_distance, builder);
+                    prev = to;
+                }
+                FlattenedEvent::Close => {
+                    self.bend_edge(prev, first, &to_distance, builder);
+                    builder.close();
+                    prev = first;
+                }
+            }
+        }
+    }
+
+    // Adds an edge of the profile, split at each vertex of the sub-path it crosses.
+    fn bend_edge<Builder, F>(&self, from: Point, to: Point, to_distance: &F, builder: &mut Builder)
+    where
+        Builder: PathBuilder,
+        F: Fn(Point) -> f32,
+    {
+        let d0 = to_distance(from);
+        let d1 = to_distance(to);
+        let a = self.segment_at(d0);
+        let b = self.segment_at(d1);
+
+        {
+            let mut split = |distance: f32| {
+                let t = if d1 == d0 { 0.0 } else { (distance - d0) / (d1 - d0) };
+                let offset = from.y + (to.y - from.y) * t;
+                builder.line_to(self.map(distance, offset));
+            };
+
+            if a < b {
+                for i in (a + 1)..(b + 1) {
+                    split(self.distances[i]);
+                }
+            } else if b < a {
+                for i in ((b + 1)..(a + 1)).rev() {
+                    split(self.distances[i]);
+                }
+            }
+        }
+
+        builder.line_to(self.map(d1, to.y));
+    }
+}
+
+// The vector that is perpendicular to the bisector of two segments and whose projection
+// on the normal of each segment has a length of one.
+fn miter(n0: Vector, n1: Vector, limit: f32) -> Vector {
+    let sum = n0 + n1;
+    let sum_length = sum.length();
+    if sum_length < 1e-5 {
+        return n1;
+    }
+
+    let direction = sum / sum_length;
+    let cos = direction.dot(n0);
+    let length = if cos * limit < 1.0 { limit } else { 1.0 / cos };
+
+    direction * length
+}
+
+#[cfg(test)]
+use path::default::Path;
+#[cfg(test)]
+use path::builder::FlatPathBuilder;
+#[cfg(test)]
+use math::point;
+
+#[cfg(test)]
+fn rectangle(x: f32, y: f32, w: f32, h: f32) -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(x, y));
+    builder.line_to(point(x + w, y));
+    builder.line_to(point(x + w, y + h));
+    builder.line_to(point(x, y + h));
+    builder.close();
+    builder.build()
+}
+
+#[cfg(test)]
+fn assert_points(path: &Path, expected: &[Point]) {
+    assert_eq!(path.points().len(), expected.len(), "{:?}", path.points());
+    for (p, e) in path.points().iter().zip(expected.iter()) {
+        assert!((*p - *e).length() < 0.0001, "{:?} != {:?}", p, e);
+    }
+}
+
+#[test]
+fn brush_stamp() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let guide = builder.build();
+
+    let profile = rectangle(0.0, -1.0, 2.0, 2.0);
+
+    let mut builder = Path::builder();
+    brush_along_path(
+        guide.path_iter(),
+        profile.as_slice(),
+        &BrushOptions::mode(BrushMode::Stamp).with_spacing(4.0).with_start_offset(1.0),
+        &mut builder,
+    );
+    let result = builder.build();
+
+    // Copies start at 1, 7 and 13, and the one at 19 doesn't fit.
+    assert_points(&result, &[
+        point(1.0, -1.0), point(3.0, -1.0), point(3.0, 1.0), point(1.0, 1.0),
+        point(7.0, -1.0), point(9.0, -1.0), point(9.0, 1.0), point(7.0, 1.0),
+        point(11.0, 3.0), point(11.0, 5.0), point(9.0, 5.0), point(9.0, 3.0),
+    ]);
+}
+
+#[test]
+fn brush_bend_corner() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let guide = builder.build();
+
+    // A single copy of the profile crosses the corner.
+    let profile = rectangle(0.0, -1.0, 20.0, 2.0);
+
+    let mut builder = Path::builder();
+    brush_along_path(
+        guide.path_iter(),
+        profile.as_slice(),
+        &BrushOptions::mode(BrushMode::Bend),
+        &mut builder,
+    );
+    let result = builder.build();
+
+    assert_points(&result, &[
+        point(0.0, -1.0),
+        point(11.0, -1.0),
+        point(11.0, 10.0),
+        point(9.0, 10.0),
+        point(9.0, 1.0),
+        point(0.0, 1.0),
+        point(0.0, -1.0),
+    ]);
+}
+
+#[test]
+fn brush_stretch_and_repeat() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let guide = builder.build();
+
+    let profile = rectangle(0.0, 0.0, 4.0, 1.0);
+
+    let mut builder = Path::builder();
+    brush_along_path(
+        guide.path_iter(),
+        profile.as_slice(),
+        &BrushOptions::mode(BrushMode::Stretch),
+        &mut builder,
+    );
+    let result = builder.build();
+    assert_points(&result, &[
+        point(0.0, 0.0), point(10.0, 0.0), point(10.0, 1.0), point(0.0, 1.0), point(0.0, 0.0),
+    ]);
+
+    // Two copies fit in the guide path with the bend mode.
+    let mut builder = Path::builder();
+    brush_along_path(
+        guide.path_iter(),
+        profile.as_slice(),
+        &BrushOptions::mode(BrushMode::Bend).with_spacing(1.0),
+        &mut builder,
+    );
+    let result = builder.build();
+    assert_points(&result, &[
+        point(0.0, 0.0), point(4.0, 0.0), point(4.0, 1.0), point(0.0, 1.0), point(0.0, 0.0),
+        point(5.0, 0.0), point(9.0, 0.0), point(9.0, 1.0), point(5.0, 1.0), point(5.0, 0.0),
+    ]);
+}
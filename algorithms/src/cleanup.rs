@@ -0,0 +1,257 @@
+//! Remove the degenerate and redundant parts of a path.
+//!
+//! Paths imported from other formats often contain zero-length segments, repeated
+//! points, empty sub-paths or long runs of collinear line segments. None of these
+//! change the shape but they make every later step slower and some of them (nearly
+//! coincident points in particular) are a source of numerical instability.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::cleanup::cleanup;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(5.0, 0.0));
+//! builder.line_to(point(5.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.close();
+//! builder.move_to(point(3.0, 3.0));
+//! let path = builder.build();
+//!
+//! let (clean, report) = cleanup(path.as_slice(), 0.01);
+//!
+//! assert_eq!(clean.points(), &[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)]);
+//! assert_eq!(report.zero_length_segments, 1);
+//! assert_eq!(report.merged_segments, 1);
+//! assert_eq!(report.empty_sub_paths, 1);
+//! ```
+
+use path::PathEvent;
+use path::builder::{FlatPathBuilder, PathBuilder};
+use path::default::{Path, PathSlice, Builder};
+use math::{Point, Vector, Angle};
+use geom::{Arc, LineSegment};
+
+/// What was removed from a path by `cleanup`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Segments shorter than the tolerance, including repeated points.
+    pub zero_length_segments: u32,
+    /// Line segments merged with the previous one because they are collinear.
+    pub merged_segments: u32,
+    /// Sub-paths without any remaining segment.
+    pub empty_sub_paths: u32,
+}
+
+impl CleanupReport {
+    /// Returns true if nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        *self == CleanupReport::default()
+    }
+}
+
+/// Builds a copy of a path without its degenerate and redundant parts.
+///
+/// - Segments for which all points are within `tolerance` of the start of the segment are
+///   removed.
+/// - Consecutive line segments are merged if the point between them is within `tolerance`
+///   of the merged segment and the path does not go back on itself.
+/// - Sub-paths that don't contain any segment are removed.
+///
+/// Curves and arcs are otherwise left untouched.
+pub fn cleanup(path: PathSlice, tolerance: f32) -> (Path, CleanupReport) {
+    let mut cleaner = Cleaner {
+        builder: Path::builder(),
+        report: CleanupReport::default(),
+        square_tolerance: tolerance * tolerance,
+        tolerance,
+        start: Point::new(0.0, 0.0),
+        current: Point::new(0.0, 0.0),
+        pending_line: None,
+        in_sub_path: false,
+        has_sub_path: false,
+    };
+
+    for event in path.iter() {
+        match event {
+            PathEvent::MoveTo(to) => {
+                cleaner.end_sub_path(false);
+                cleaner.start = to;
+                cleaner.current = to;
+                cleaner.has_sub_path = true;
+            }
+            PathEvent::LineTo(to) => {
+                cleaner.line_to(to);
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                if cleaner.is_degenerate(&[ctrl, to]) {
+                    cleaner.report.zero_length_segments += 1;
+                    continue;
+                }
+                cleaner.begin_segment();
+                cleaner.builder.quadratic_bezier_to(ctrl, to);
+                cleaner.current = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                if cleaner.is_degenerate(&[ctrl1, ctrl2, to]) {
+                    cleaner.report.zero_length_segments += 1;
+                    continue;
+                }
+                cleaner.begin_segment();
+                cleaner.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+                cleaner.current = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                cleaner.arc(center, radii, sweep_angle, x_rotation);
+            }
+            PathEvent::Close => {
+                cleaner.end_sub_path(true);
+            }
+        }
+    }
+    cleaner.end_sub_path(false);
+
+    (cleaner.builder.build(), cleaner.report)
+}
+
+struct Cleaner {
+    builder: Builder,
+    report: CleanupReport,
+    tolerance: f32,
+    square_tolerance: f32,
+    start: Point,
+    // The end of the last segment, including the pending line.
+    current: Point,
+    // The end of the last line segment if it may still be merged with the next one.
+    pending_line: Option<Point>,
+    // Whether the move_to of the current sub-path was sent to the builder.
+    in_sub_path: bool,
+    // Whether a move_to was received since the end of the previous sub-path.
+    has_sub_path: bool,
+}
+
+impl Cleaner {
+    fn is_degenerate(&self, points: &[Point]) -> bool {
+        points.iter().all(|p| (*p - self.current).square_length() <= self.square_tolerance)
+    }
+
+    fn begin_segment(&mut self) {
+        if !self.in_sub_path {
+            self.builder.move_to(self.start);
+            self.in_sub_path = true;
+        }
+        self.flush_line();
+    }
+
+    fn flush_line(&mut self) {
+        if let Some(to) = self.pending_line.take() {
+            self.builder.line_to(to);
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if self.is_degenerate(&[to]) {
+            self.report.zero_length_segments += 1;
+            return;
+        }
+
+        if let Some(middle) = self.pending_line {
+            let from = self.builder.current_position();
+            let segment = LineSegment { from, to };
+            if (middle - from).dot(to - middle) > 0.0
+                && segment.to_line().distance_to_point(&middle) <= self.tolerance {
+                self.pending_line = Some(to);
+                self.current = to;
+                self.report.merged_segments += 1;
+                return;
+            }
+        }
+
+        self.begin_segment();
+        self.pending_line = Some(to);
+        self.current = to;
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        let start_angle = (self.current - center).angle_from_x_axis() - x_rotation;
+        let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+        let max_radius = radii.x.abs().max(radii.y.abs());
+        if max_radius * sweep_angle.get().abs() <= self.tolerance {
+            self.report.zero_length_segments += 1;
+            return;
+        }
+
+        self.begin_segment();
+        self.builder.arc(center, radii, sweep_angle, x_rotation);
+        self.current = arc.to();
+    }
+
+    fn end_sub_path(&mut self, close: bool) {
+        if self.in_sub_path {
+            // A line back to the start is redundant with the closing segment.
+            if close && self.pending_line == Some(self.start) {
+                self.pending_line = None;
+                self.report.zero_length_segments += 1;
+            }
+            self.flush_line();
+            if close {
+                self.builder.close();
+            }
+        } else if self.has_sub_path {
+            self.report.empty_sub_paths += 1;
+        }
+
+        self.current = self.start;
+        self.in_sub_path = false;
+        self.has_sub_path = false;
+    }
+}
+
+#[test]
+fn cleanup_path() {
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    builder.move_to(point(0.0, 0.0));
+    // Collinear.
+    builder.line_to(point(2.0, 0.001));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(6.0, 0.0));
+    // Goes back on itself.
+    builder.line_to(point(3.0, 0.0));
+    builder.cubic_bezier_to(point(3.0, 0.0), point(3.0, 0.0), point(3.0, 0.0));
+    builder.quadratic_bezier_to(point(3.0, 5.0), point(3.0, 10.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    let (clean, report) = cleanup(path.as_slice(), 0.01);
+
+    let events: Vec<PathEvent> = clean.iter().collect();
+    assert_eq!(events, vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(6.0, 0.0)),
+        PathEvent::LineTo(point(3.0, 0.0)),
+        PathEvent::QuadraticTo(point(3.0, 5.0), point(3.0, 10.0)),
+        PathEvent::Close,
+    ]);
+
+    assert_eq!(report, CleanupReport {
+        zero_length_segments: 3,
+        merged_segments: 2,
+        empty_sub_paths: 2,
+    });
+    assert!(!report.is_empty());
+
+    let (_, report) = cleanup(clean.as_slice(), 0.01);
+    assert!(report.is_empty());
+}
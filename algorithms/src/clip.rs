@@ -0,0 +1,483 @@
+//! Clip paths against rectangles, convex polygons and arbitrary paths.
+//!
+//! Individual segments can be clipped with the `clip_to_rect` methods of the
+//! segment types of `lyon_geom`.
+//!
+//! ## Clipping before tessellation
+//!
+//! The fill tessellator has no notion of clip path. To render a shape restricted
+//! to a clip path (for example CSS's `clip-path`), compute the intersection with
+//! `clip_path_to_path` and tessellate the result, which has no self-intersections
+//! and can be filled with either fill rule.
+
+use path::{PathEvent, Segment, FillRule};
+use path::iterator::PathIterator;
+use path::builder::PathBuilder;
+use path::default::{Path, PathSlice};
+use math::{Point, Rect, point};
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc, Line, LineEquation};
+use geom::arrayvec::ArrayVec;
+use arrangement::{Arrangement, FaceId};
+use offset::faces_outline;
+
+use std::mem::swap;
+use std::cmp::Ordering;
+
+/// Clips a path to a rectangle, for example to split content into tiles.
+///
+/// Sub-paths are treated as closed shapes: the parts of a sub-path that are outside
+/// of the rectangle are replaced with the corresponding portions of the rectangle's
+/// boundary (similarly to the Sutherland-Hodgman algorithm), so that the result
+/// can be directly filled. Curves are split rather than flattened.
+pub fn clip_path_to_rect<Iter, Builder>(path: Iter, rect: &Rect, builder: Builder) -> Builder::PathType
+where
+    Iter: PathIterator,
+    Builder: PathBuilder,
+{
+    let half_planes = [
+        HalfPlane::X { value: rect.min_x(), keep_greater: true },
+        HalfPlane::X { value: rect.max_x(), keep_greater: false },
+        HalfPlane::Y { value: rect.min_y(), keep_greater: true },
+        HalfPlane::Y { value: rect.max_y(), keep_greater: false },
+    ];
+
+    clip_path(path, &half_planes, builder)
+}
+
+/// Clips a path to a convex polygon, for example a rotated viewport.
+///
+/// Works like `clip_path_to_rect`: the parts of each sub-path that are outside
+/// of the polygon are replaced with the corresponding portions of the polygon's
+/// boundary. The polygon can be in either winding order but must be convex,
+/// otherwise the result is undefined. Nothing is produced if the polygon has
+/// no area.
+pub fn clip_path_to_convex_polygon<Iter, Builder>(
+    path: Iter,
+    polygon: &[Point],
+    builder: Builder,
+) -> Builder::PathType
+where
+    Iter: PathIterator,
+    Builder: PathBuilder,
+{
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        area += a.to_vector().cross(b.to_vector());
+    }
+
+    let mut half_planes = Vec::with_capacity(polygon.len());
+    if area != 0.0 {
+        for i in 0..polygon.len() {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % polygon.len()];
+            if a == b {
+                continue;
+            }
+            let line = Line { point: a, vector: b - a };
+            let mut equation = line.equation();
+            // The inside of the polygon is on the positive side of each edge.
+            if area < 0.0 {
+                equation = equation.invert();
+            }
+            half_planes.push(HalfPlane::Line { line, equation });
+        }
+    }
+
+    if half_planes.is_empty() {
+        return builder.build();
+    }
+
+    clip_path(path, &half_planes, builder)
+}
+
+/// Computes the intersection of the filled area of a path and of a clip path.
+///
+/// Both paths can be arbitrarily complex: they are flattened with the provided
+/// tolerance and their sub-paths are implicitly closed. The result is made of
+/// non-intersecting polygons, the holes going in the opposite direction of the
+/// outer boundaries, so it produces the same shape with both fill rules.
+///
+/// This is a separate pass over the two paths: the edges are intersected in
+/// quadratic time before building the output, so it is best suited to clip paths
+/// with a moderate number of edges.
+pub fn clip_path_to_path(
+    path: PathSlice,
+    path_rule: FillRule,
+    clip: PathSlice,
+    clip_rule: FillRule,
+    tolerance: f32,
+) -> Path {
+    let arrangement = Arrangement::from_paths(vec![path.path_iter(), clip.path_iter()], tolerance);
+
+    faces_outline(&arrangement, |face: FaceId| {
+        face != arrangement.unbounded_face()
+            && path_rule.is_in(arrangement.winding(face, 0))
+            && clip_rule.is_in(arrangement.winding(face, 1))
+    })
+}
+
+fn clip_path<Iter, Builder>(mut path: Iter, half_planes: &[HalfPlane], mut builder: Builder) -> Builder::PathType
+where
+    Iter: PathIterator,
+    Builder: PathBuilder,
+{
+    let mut segments = Vec::new();
+    let mut tmp = Vec::new();
+
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(_)) | Some(PathEvent::Close) => {
+                clip_sub_path(&mut segments, &mut tmp, half_planes, &mut builder);
+            }
+            Some(PathEvent::LineTo(to)) => {
+                segments.push(Segment::Line(LineSegment { from, to }));
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                segments.push(Segment::Quadratic(QuadraticBezierSegment { from, ctrl, to }));
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                segments.push(Segment::Cubic(CubicBezierSegment { from, ctrl1, ctrl2, to }));
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                segments.push(Segment::Arc(Arc {
+                    center, radii,
+                    start_angle, sweep_angle,
+                    x_rotation,
+                }));
+            }
+            None => {
+                clip_sub_path(&mut segments, &mut tmp, half_planes, &mut builder);
+                break;
+            }
+        }
+    }
+
+    builder.build()
+}
+
+#[derive(Copy, Clone, Debug)]
+enum HalfPlane {
+    X { value: f32, keep_greater: bool },
+    Y { value: f32, keep_greater: bool },
+    /// The points on the positive side of the line.
+    Line { line: Line<f32>, equation: LineEquation<f32> },
+}
+
+impl HalfPlane {
+    fn is_inside(&self, p: Point) -> bool {
+        self.signed_distance(p) >= 0.0
+    }
+
+    fn project(&self, p: Point) -> Point {
+        match *self {
+            HalfPlane::X { value, .. } => point(value, p.y),
+            HalfPlane::Y { value, .. } => point(p.x, value),
+            HalfPlane::Line { ref equation, .. } => equation.project_point(&p),
+        }
+    }
+
+    // Positive inside, negative outside.
+    fn signed_distance(&self, p: Point) -> f32 {
+        match *self {
+            HalfPlane::X { value, keep_greater: true } => p.x - value,
+            HalfPlane::X { value, keep_greater: false } => value - p.x,
+            HalfPlane::Y { value, keep_greater: true } => p.y - value,
+            HalfPlane::Y { value, keep_greater: false } => value - p.y,
+            HalfPlane::Line { ref equation, .. } => equation.signed_distance_to_point(&p),
+        }
+    }
+
+    fn crossings(&self, segment: &Segment, crossings: &mut ArrayVec<[f32; 3]>) {
+        match (*segment, *self) {
+            (Segment::Line(s), _) => {
+                let a = self.signed_distance(s.from);
+                let b = self.signed_distance(s.to);
+                if a * b < 0.0 {
+                    crossings.push(a / (a - b));
+                }
+            }
+            (Segment::Quadratic(s), HalfPlane::X { value, .. }) => { crossings.extend(s.solve_t_for_x(value)); }
+            (Segment::Quadratic(s), HalfPlane::Y { value, .. }) => { crossings.extend(s.solve_t_for_y(value)); }
+            (Segment::Quadratic(s), HalfPlane::Line { ref line, .. }) => { crossings.extend(s.line_intersections_t(line)); }
+            (Segment::Cubic(s), HalfPlane::X { value, .. }) => { crossings.extend(s.solve_t_for_x(value)); }
+            (Segment::Cubic(s), HalfPlane::Y { value, .. }) => { crossings.extend(s.solve_t_for_y(value)); }
+            (Segment::Cubic(s), HalfPlane::Line { ref line, .. }) => { crossings.extend(s.line_intersections_t(line)); }
+            (Segment::Arc(s), HalfPlane::X { value, .. }) => { crossings.extend(s.solve_t_for_x(value)); }
+            (Segment::Arc(s), HalfPlane::Y { value, .. }) => { crossings.extend(s.solve_t_for_y(value)); }
+            (Segment::Arc(s), HalfPlane::Line { ref line, .. }) => { crossings.extend(s.line_intersections_t(line)); }
+        }
+    }
+}
+
+fn clip_sub_path<Builder: PathBuilder>(
+    segments: &mut Vec<Segment>,
+    tmp: &mut Vec<Segment>,
+    half_planes: &[HalfPlane],
+    builder: &mut Builder,
+) {
+    if segments.is_empty() {
+        return;
+    }
+
+    // Close the sub-path.
+    let first = from(&segments[0]);
+    let last = to(&segments[segments.len() - 1]);
+    if first != last {
+        segments.push(Segment::Line(LineSegment { from: last, to: first }));
+    }
+
+    for half_plane in half_planes {
+        // Start from a point inside of the half-plane if possible so that the boundary
+        // isn't split into two segments at the start of the sub-path.
+        let n = segments.len();
+        let start = segments.iter().position(|segment| {
+            half_plane.signed_distance(from(segment)) > 0.0
+        }).unwrap_or(0);
+
+        tmp.clear();
+        let mut on_boundary = false;
+        for i in 0..n {
+            clip_segment(&segments[(start + i) % n], half_plane, tmp, &mut on_boundary);
+        }
+        swap(segments, tmp);
+    }
+
+    // Skip sub-paths that were reduced to nothing.
+    let mut min = point(f32::MAX, f32::MAX);
+    let mut max = point(f32::MIN, f32::MIN);
+    for segment in segments.iter() {
+        let p = to(segment);
+        min = min.min(p);
+        max = max.max(p);
+    }
+
+    if !segments.is_empty() && min.x < max.x && min.y < max.y {
+        builder.move_to(from(&segments[0]));
+        for segment in segments.iter() {
+            match *segment {
+                Segment::Line(s) => { builder.line_to(s.to); }
+                Segment::Quadratic(s) => { builder.quadratic_bezier_to(s.ctrl, s.to); }
+                Segment::Cubic(s) => { builder.cubic_bezier_to(s.ctrl1, s.ctrl2, s.to); }
+                Segment::Arc(s) => { builder.arc(s.center, s.radii, s.sweep_angle, s.x_rotation); }
+            }
+        }
+        builder.close();
+    }
+
+    segments.clear();
+}
+
+fn clip_segment(
+    segment: &Segment,
+    half_plane: &HalfPlane,
+    output: &mut Vec<Segment>,
+    on_boundary: &mut bool,
+) {
+    let mut crossings: ArrayVec<[f32; 3]> = ArrayVec::new();
+    half_plane.crossings(segment, &mut crossings);
+    // The bézier curve vs line intersections aren't sorted.
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut t0 = 0.0;
+    for &t1 in crossings.iter().chain(Some(1.0).iter()) {
+        if t1 <= t0 {
+            continue;
+        }
+
+        let piece = split_range(segment, t0, t1);
+        if half_plane.is_inside(sample(segment, (t0 + t1) * 0.5)) {
+            output.push(piece);
+            *on_boundary = false;
+        } else {
+            let from = half_plane.project(from(&piece));
+            let to = half_plane.project(to(&piece));
+            if from != to {
+                // Consecutive portions of the boundary are merged into a single line.
+                let mut merged = false;
+                if *on_boundary {
+                    if let Some(&mut Segment::Line(ref mut line)) = output.last_mut() {
+                        line.to = to;
+                        merged = true;
+                    }
+                }
+                if !merged {
+                    output.push(Segment::Line(LineSegment { from, to }));
+                }
+                *on_boundary = true;
+            }
+        }
+
+        t0 = t1;
+    }
+}
+
+fn from(segment: &Segment) -> Point {
+    match *segment {
+        Segment::Line(s) => s.from,
+        Segment::Quadratic(s) => s.from,
+        Segment::Cubic(s) => s.from,
+        Segment::Arc(s) => s.from(),
+    }
+}
+
+fn to(segment: &Segment) -> Point {
+    match *segment {
+        Segment::Line(s) => s.to,
+        Segment::Quadratic(s) => s.to,
+        Segment::Cubic(s) => s.to,
+        Segment::Arc(s) => s.to(),
+    }
+}
+
+fn sample(segment: &Segment, t: f32) -> Point {
+    match *segment {
+        Segment::Line(s) => s.sample(t),
+        Segment::Quadratic(s) => s.sample(t),
+        Segment::Cubic(s) => s.sample(t),
+        Segment::Arc(s) => s.sample(t),
+    }
+}
+
+fn split_range(segment: &Segment, t0: f32, t1: f32) -> Segment {
+    match *segment {
+        Segment::Line(s) => Segment::Line(s.split_range(t0..t1)),
+        Segment::Quadratic(s) => Segment::Quadratic(s.split_range(t0..t1)),
+        Segment::Cubic(s) => Segment::Cubic(s.split_range(t0..t1)),
+        Segment::Arc(s) => Segment::Arc(s.split_range(t0..t1)),
+    }
+}
+
+#[test]
+fn clip_square() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+    use math::rect;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(-5.0, -5.0));
+    builder.line_to(point(5.0, -5.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(-5.0, 5.0));
+    builder.close();
+    // Entirely outside.
+    builder.move_to(point(20.0, 20.0));
+    builder.line_to(point(30.0, 20.0));
+    builder.line_to(point(30.0, 30.0));
+    builder.close();
+    let path = builder.build();
+
+    let clipped = clip_path_to_rect(path.path_iter(), &rect(0.0, 0.0, 10.0, 10.0), Path::builder());
+
+    let mut count = 0;
+    for evt in clipped.iter() {
+        match evt {
+            PathEvent::MoveTo(p) | PathEvent::LineTo(p) => {
+                assert!(p.x >= 0.0 && p.x <= 5.0 && p.y >= 0.0 && p.y <= 5.0);
+                count += 1;
+            }
+            PathEvent::Close => {}
+            _ => { panic!(); }
+        }
+    }
+    assert_eq!(count, 5);
+    assert_eq!(clipped.iter().filter(|evt| *evt == PathEvent::Close).count(), 1);
+}
+
+#[test]
+fn clip_convex_polygon() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut diamond = vec![point(5.0, -2.0), point(12.0, 5.0), point(5.0, 12.0), point(-2.0, 5.0)];
+
+    for _ in 0..2 {
+        let clipped = clip_path_to_convex_polygon(path.path_iter(), &diamond, Path::builder());
+
+        // The square's corners are cut off, which gives an octagon.
+        assert_eq!(clipped.points().len(), 9);
+        for p in clipped.points() {
+            assert!(p.x >= -0.001 && p.x <= 10.001 && p.y >= -0.001 && p.y <= 10.001);
+            let d = (p.x - 5.0).abs() + (p.y - 5.0).abs();
+            assert!(d <= 7.001);
+        }
+
+        // Same result with the opposite winding order.
+        diamond.reverse();
+    }
+
+    let flat = [point(0.0, 0.0), point(1.0, 1.0), point(2.0, 2.0)];
+    let clipped = clip_path_to_convex_polygon(path.path_iter(), &flat, Path::builder());
+    assert!(clipped.points().is_empty());
+}
+
+#[test]
+fn clip_to_path() {
+    use path::builder::FlatPathBuilder;
+
+    fn square(builder: &mut ::path::default::Builder, x: f32, y: f32, size: f32) {
+        builder.move_to(point(x, y));
+        builder.line_to(point(x + size, y));
+        builder.line_to(point(x + size, y + size));
+        builder.line_to(point(x, y + size));
+        builder.close();
+    }
+
+    fn area(path: &Path) -> f32 {
+        let mut area = 0.0;
+        let mut first = point(0.0, 0.0);
+        let mut prev = point(0.0, 0.0);
+        for evt in path.iter() {
+            match evt {
+                PathEvent::MoveTo(p) => { first = p; prev = p; }
+                PathEvent::LineTo(p) => {
+                    area += prev.to_vector().cross(p.to_vector());
+                    prev = p;
+                }
+                PathEvent::Close => {
+                    area += prev.to_vector().cross(first.to_vector());
+                    prev = first;
+                }
+                _ => { panic!(); }
+            }
+        }
+        (area * 0.5).abs()
+    }
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    let path = builder.build();
+
+    // A square with a hole, shifted so that it overlaps the corner of the path.
+    let mut builder = Path::builder();
+    square(&mut builder, 5.0, 5.0, 10.0);
+    square(&mut builder, 7.0, 7.0, 2.0);
+    let clip = builder.build();
+
+    let clipped = clip_path_to_path(path.as_slice(), FillRule::NonZero, clip.as_slice(), FillRule::EvenOdd, 0.01);
+    for p in clipped.points() {
+        assert!(p.x >= 4.999 && p.x <= 10.001 && p.y >= 4.999 && p.y <= 10.001);
+    }
+    assert!((area(&clipped) - 21.0).abs() < 0.01);
+
+    // With the non-zero rule, the hole is filled since both squares have the same winding.
+    let clipped = clip_path_to_path(path.as_slice(), FillRule::NonZero, clip.as_slice(), FillRule::NonZero, 0.01);
+    assert!((area(&clipped) - 25.0).abs() < 0.01);
+
+    let mut builder = Path::builder();
+    square(&mut builder, 20.0, 20.0, 1.0);
+    let outside = builder.build();
+    let clipped = clip_path_to_path(path.as_slice(), FillRule::EvenOdd, outside.as_slice(), FillRule::EvenOdd, 0.01);
+    assert!(clipped.points().is_empty());
+}
@@ -0,0 +1,186 @@
+//! Check whether the area of a path fully contains another path.
+//!
+//! When assembling polygons with holes from contours that come in no particular order (glyph
+//! outlines, CAD exports), each contour has to be classified as a hole inside of another
+//! contour or as a separate island. `contains_path` answers this for a pair of paths.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::contains_path;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::{point, Point};
+//!
+//! fn square(origin: Point, size: f32) -> Path {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(origin);
+//!     builder.line_to(point(origin.x + size, origin.y));
+//!     builder.line_to(point(origin.x + size, origin.y + size));
+//!     builder.line_to(point(origin.x, origin.y + size));
+//!     builder.close();
+//!     builder.build()
+//! }
+//!
+//! let outer = square(point(0.0, 0.0), 10.0);
+//! let hole = square(point(2.0, 2.0), 5.0);
+//! let overlapping = square(point(8.0, 8.0), 5.0);
+//!
+//! assert!(contains_path(outer.as_slice(), hole.as_slice(), 0.01));
+//! assert!(!contains_path(outer.as_slice(), overlapping.as_slice(), 0.01));
+//! assert!(!contains_path(hole.as_slice(), outer.as_slice(), 0.01));
+//! ```
+
+use path::FlattenedEvent;
+use path::default::PathSlice;
+use path::iterator::PathIterator;
+use math::{Point, point, Rect};
+use geom::LineSegment;
+use aabb::fast_bounding_rect;
+use winding::winding_number;
+
+/// Returns true if the area filled by `outer` with the non-zero fill rule contains the whole
+/// area of `inner`.
+///
+/// The paths are flattened with the provided tolerance to look for intersections: if any
+/// part of the two paths touch or cross each other, `inner` is not considered to be
+/// contained. Otherwise, each sub-path of `inner` must be inside of `outer`, and no
+/// sub-path of `outer` can be inside of `inner` (which would be the case if `inner`
+/// surrounded a hole of `outer`).
+///
+/// Sub-paths are implicitly closed. An empty `inner` path is contained in any path.
+pub fn contains_path(outer: PathSlice, inner: PathSlice, tolerance: f32) -> bool {
+    let mut inner_edges = Vec::new();
+    let inner_starts = collect_edges(inner, tolerance, &mut inner_edges);
+    if inner_starts.is_empty() {
+        return true;
+    }
+
+    let mut outer_edges = Vec::new();
+    let outer_starts = collect_edges(outer, tolerance, &mut outer_edges);
+
+    // Only the edges of the outer path that are near the inner one can intersect it.
+    let inner_rect = fast_bounding_rect(inner.iter());
+    for outer_edge in &outer_edges {
+        if !overlaps(&outer_edge.bounding_rect(), &inner_rect) {
+            continue;
+        }
+        for inner_edge in &inner_edges {
+            if outer_edge.intersects(inner_edge) {
+                return false;
+            }
+        }
+    }
+
+    // Without intersections, each sub-path is either completely inside or completely
+    // outside of the other path, so testing one point per sub-path is enough.
+    for p in &inner_starts {
+        if winding_number(*p, outer.iter()) == 0 {
+            return false;
+        }
+    }
+
+    for p in &outer_starts {
+        if winding_number(*p, inner.iter()) != 0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Flattens the path into line segments, closing each sub-path, and returns the first point
+// of each sub-path.
+fn collect_edges(path: PathSlice, tolerance: f32, output: &mut Vec<LineSegment<f32>>) -> Vec<Point> {
+    let mut starts = Vec::new();
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+    for evt in path.path_iter().flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                if current != first {
+                    output.push(LineSegment { from: current, to: first });
+                }
+                starts.push(to);
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::LineTo(to) => {
+                output.push(LineSegment { from: current, to });
+                current = to;
+            }
+            FlattenedEvent::Close => {
+                if current != first {
+                    output.push(LineSegment { from: current, to: first });
+                }
+                current = first;
+            }
+        }
+    }
+    if current != first {
+        output.push(LineSegment { from: current, to: first });
+    }
+
+    starts
+}
+
+// Unlike `Rect::intersects`, rectangles that only touch (or that have an empty size, like
+// the bounding rectangle of a horizontal segment) are considered to overlap.
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    a.min_x() <= b.max_x() && b.min_x() <= a.max_x()
+        && a.min_y() <= b.max_y() && b.min_y() <= a.max_y()
+}
+
+#[test]
+fn contains_path_nested() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::{vector, Angle};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.line_to(point(20.0, 20.0));
+    builder.line_to(point(0.0, 20.0));
+    builder.close();
+    // A hole in the outer path.
+    builder.move_to(point(5.0, 5.0));
+    builder.line_to(point(5.0, 15.0));
+    builder.line_to(point(15.0, 15.0));
+    builder.line_to(point(15.0, 5.0));
+    builder.close();
+    let outer = builder.build();
+
+    let circle = |center: Point, radius: f32| {
+        let mut builder = Path::builder();
+        builder.move_to(center + vector(radius, 0.0));
+        builder.arc(center, vector(radius, radius), Angle::two_pi(), Angle::zero());
+        builder.close();
+        builder.build()
+    };
+
+    // In the ring between the outer square and the hole.
+    let island = circle(point(2.5, 10.0), 2.0);
+    // Inside of the hole.
+    let in_hole = circle(point(10.0, 10.0), 2.0);
+    // Around the hole.
+    let around_hole = circle(point(10.0, 10.0), 8.0);
+    // Crossing the border of the hole.
+    let crossing = circle(point(5.0, 10.0), 1.0);
+    // Touching the outer square from the inside.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 10.0));
+    builder.line_to(point(3.0, 8.0));
+    builder.line_to(point(3.0, 12.0));
+    builder.close();
+    let touching = builder.build();
+
+    assert!(contains_path(outer.as_slice(), island.as_slice(), 0.01));
+    assert!(!contains_path(outer.as_slice(), in_hole.as_slice(), 0.01));
+    assert!(!contains_path(outer.as_slice(), around_hole.as_slice(), 0.01));
+    assert!(!contains_path(outer.as_slice(), crossing.as_slice(), 0.01));
+    assert!(!contains_path(outer.as_slice(), touching.as_slice(), 0.01));
+    assert!(!contains_path(island.as_slice(), outer.as_slice(), 0.01));
+    assert!(contains_path(around_hole.as_slice(), in_hole.as_slice(), 0.01));
+    assert!(contains_path(outer.as_slice(), Path::new().as_slice(), 0.01));
+}
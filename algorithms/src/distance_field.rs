@@ -0,0 +1,441 @@
+//! Signed distance fields of paths.
+//!
+//! The distance fields are computed on the CPU into a grid provided by the caller, from
+//! the exact distance to each segment of the flattened path. This is typically used to
+//! render glyphs and icons that remain sharp when scaled.
+//!
+//! Multi-channel distance fields (MSDF) are also supported. Their three channels store
+//! distances to different subsets of the edges, so that the median of the channels
+//! preserves the sharp corners of the shape when the field is sampled with bilinear
+//! filtering.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::distance_field::signed_distance_field;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::{point, vector, Transform2D};
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(0.5, 1.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! // Map the triangle to the 32x32 grid.
+//! let transform = Transform2D::create_scale(24.0, 24.0).post_translate(vector(4.0, 4.0));
+//! let mut grid = vec![0.0; 32 * 32];
+//! signed_distance_field(triangle.as_slice(), &transform, 0.1, 32, 32, &mut grid);
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use path::default::PathSlice;
+use math::{Point, Vector, Transform2D, point};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+
+use std::f32;
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const WHITE: u8 = RED | GREEN | BLUE;
+const PALETTE: [u8; 3] = [GREEN | BLUE, RED | BLUE, RED | GREEN];
+
+/// Computes the signed distance field of a path filled with the non-zero fill rule.
+///
+/// The transform maps the path to the grid, in which the cells have a size of one unit.
+/// The distances are measured from the center of each cell, in grid units, and are
+/// negative inside of the path. The output is stored row by row.
+///
+/// The path is flattened with the provided tolerance, in grid units. The cost is
+/// proportional to the number of cells times the number of flattened segments.
+pub fn signed_distance_field(
+    path: PathSlice,
+    transform: &Transform2D,
+    tolerance: f32,
+    width: usize,
+    height: usize,
+    output: &mut [f32],
+) {
+    assert!(output.len() >= width * height);
+
+    let contours = collect_contours(path.path_iter().transformed(transform), tolerance);
+    let mut segments = Vec::new();
+    for contour in &contours {
+        for piece in contour {
+            for i in 1..piece.points.len() {
+                segments.push((piece.points[i - 1], piece.points[i]));
+            }
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let p = point(x as f32 + 0.5, y as f32 + 0.5);
+            let mut square_distance = f32::MAX;
+            let mut winding = 0;
+            for &(from, to) in &segments {
+                let (d, _) = segment_distance(from, to, p);
+                square_distance = square_distance.min(d);
+                winding += winding_contribution(from, to, p);
+            }
+
+            let distance = square_distance.sqrt();
+            output[y * width + x] = if winding != 0 { -distance } else { distance };
+        }
+    }
+}
+
+/// Computes the multi-channel signed distance field of a path.
+///
+/// The parameters and the output follow the conventions of `signed_distance_field`.
+/// The sharp corners of each sub-path split it into edges that are assigned different
+/// combinations of two channels, and each channel stores the distance to the edges it
+/// is assigned to, extended beyond the corners. The median of the three channels gives
+/// the distance to the shape with preserved corners.
+///
+/// Like with most MSDF generators, the sign of the distances is deduced from the
+/// orientation of the edges, so holes must have the opposite winding order of the
+/// sub-paths that contain them.
+pub fn multi_channel_distance_field(
+    path: PathSlice,
+    transform: &Transform2D,
+    tolerance: f32,
+    width: usize,
+    height: usize,
+    output: &mut [[f32; 3]],
+) {
+    assert!(output.len() >= width * height);
+
+    let contours = collect_contours(path.path_iter().transformed(transform), tolerance);
+
+    // The orientation of the largest sub-path tells which side of the edges is inside.
+    let mut orientation = 1.0;
+    let mut max_area = 0.0;
+    for contour in &contours {
+        let mut area = 0.0;
+        for piece in contour {
+            for i in 1..piece.points.len() {
+                area += piece.points[i - 1].to_vector().cross(piece.points[i].to_vector());
+            }
+        }
+        if area.abs() > max_area {
+            max_area = area.abs();
+            orientation = area.signum();
+        }
+    }
+
+    let mut segments = Vec::new();
+    for contour in &contours {
+        let colors = edge_colors(contour);
+        for (piece_index, piece) in contour.iter().enumerate() {
+            let n = piece.points.len();
+            let next_piece = (piece_index + 1) % contour.len();
+            for i in 1..n {
+                segments.push(ColoredSegment {
+                    from: piece.points[i - 1],
+                    to: piece.points[i],
+                    color: colors[piece_index],
+                    corner_at_from: i == 1 && colors[piece_index] != colors[(piece_index + contour.len() - 1) % contour.len()],
+                    corner_at_to: i == n - 1 && colors[piece_index] != colors[next_piece],
+                });
+            }
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let p = point(x as f32 + 0.5, y as f32 + 0.5);
+            let mut pixel = [0.0; 3];
+            for (channel, &mask) in [RED, GREEN, BLUE].iter().enumerate() {
+                let mut best: Option<(usize, f32, f32, f32)> = None;
+                for (i, segment) in segments.iter().enumerate() {
+                    if segment.color & mask == 0 {
+                        continue;
+                    }
+
+                    let (d, t) = segment_distance(segment.from, segment.to, p);
+                    let d = d.sqrt();
+                    let alignment = if t <= 0.0 || t >= 1.0 {
+                        let endpoint = if t <= 0.0 { segment.from } else { segment.to };
+                        let v = p - endpoint;
+                        let dir = segment.to - segment.from;
+                        if v.square_length() > 0.0 {
+                            (v.dot(dir) / (v.length() * dir.length())).abs()
+                        } else {
+                            0.0
+                        }
+                    } else {
+                        0.0
+                    };
+
+                    let better = match best {
+                        None => true,
+                        Some((_, best_d, best_alignment, _)) => {
+                            d < best_d - 1e-5 || (d <= best_d + 1e-5 && alignment < best_alignment)
+                        }
+                    };
+                    if better {
+                        best = Some((i, d, alignment, t));
+                    }
+                }
+
+                pixel[channel] = match best {
+                    Some((i, d, _, t)) => {
+                        let segment = &segments[i];
+                        let dir = (segment.to - segment.from).normalize();
+                        let side = dir.cross(p - segment.from);
+                        let mut distance = if side > 0.0 { -d } else { d };
+                        let extended = (t < 0.0 && segment.corner_at_from)
+                            || (t > 1.0 && segment.corner_at_to);
+                        if extended && side.abs() <= d {
+                            // The distance to the line that extends the edge.
+                            distance = -side;
+                        }
+                        distance * orientation
+                    }
+                    None => f32::MAX,
+                };
+            }
+
+            output[y * width + x] = pixel;
+        }
+    }
+}
+
+struct ColoredSegment {
+    from: Point,
+    to: Point,
+    color: u8,
+    corner_at_from: bool,
+    corner_at_to: bool,
+}
+
+// The flattened approximation of a segment of the path, along with the tangents at
+// the extremities of the original segment.
+struct Piece {
+    points: Vec<Point>,
+    start_tangent: Vector,
+    end_tangent: Vector,
+}
+
+// Flattens the path into sub-paths of pieces. The sub-paths are implicitly closed.
+fn collect_contours<Iter: PathIterator>(mut iter: Iter, tolerance: f32) -> Vec<Vec<Piece>> {
+    let mut contours = Vec::new();
+    let mut contour: Vec<Piece> = Vec::new();
+    loop {
+        let from = iter.get_state().current;
+        let evt = iter.next();
+        let piece = match evt {
+            Some(PathEvent::LineTo(to)) => {
+                Piece { points: vec![from, to], start_tangent: to - from, end_tangent: to - from }
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let mut points = vec![from];
+                QuadraticBezierSegment { from, ctrl, to }.for_each_flattened(tolerance, &mut |p| { points.push(p); });
+                Piece {
+                    points,
+                    start_tangent: first_non_zero(&[ctrl - from, to - from]),
+                    end_tangent: first_non_zero(&[to - ctrl, to - from]),
+                }
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                let mut points = vec![from];
+                CubicBezierSegment { from, ctrl1, ctrl2, to }.for_each_flattened(tolerance, &mut |p| { points.push(p); });
+                Piece {
+                    points,
+                    start_tangent: first_non_zero(&[ctrl1 - from, ctrl2 - from, to - from]),
+                    end_tangent: first_non_zero(&[to - ctrl2, to - ctrl1, to - from]),
+                }
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let mut points = vec![from];
+                Arc { center, radii, start_angle, sweep_angle, x_rotation }
+                    .for_each_flattened(tolerance, &mut |p| { points.push(p); });
+                let n = points.len();
+                Piece {
+                    start_tangent: points[1] - points[0],
+                    end_tangent: points[n - 1] - points[n - 2],
+                    points,
+                }
+            }
+            Some(PathEvent::MoveTo(_)) | Some(PathEvent::Close) | None => {
+                finish_contour(&mut contour, &mut contours);
+                if evt.is_none() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if piece.points.len() > 1 && piece.points[0] != piece.points[piece.points.len() - 1] {
+            contour.push(piece);
+        }
+    }
+
+    contours
+}
+
+fn finish_contour(contour: &mut Vec<Piece>, contours: &mut Vec<Vec<Piece>>) {
+    if contour.is_empty() {
+        return;
+    }
+
+    let first = contour[0].points[0];
+    let last = *contour[contour.len() - 1].points.last().unwrap();
+    if first != last {
+        contour.push(Piece { points: vec![last, first], start_tangent: first - last, end_tangent: first - last });
+    }
+
+    contours.push(::std::mem::take(contour));
+}
+
+fn first_non_zero(vectors: &[Vector]) -> Vector {
+    for v in vectors {
+        if v.square_length() > 1e-12 {
+            return *v;
+        }
+    }
+
+    vectors[vectors.len() - 1]
+}
+
+fn is_corner(a: Vector, b: Vector) -> bool {
+    let a = a.normalize();
+    let b = b.normalize();
+    // Angles of more than about 8 degrees.
+    a.dot(b) <= 0.0 || a.cross(b).abs() > 0.14
+}
+
+// Assigns channels to the pieces of a sub-path, so that the pieces on each side of a
+// corner have different colors.
+fn edge_colors(contour: &[Piece]) -> Vec<u8> {
+    let n = contour.len();
+    let mut colors = vec![WHITE; n];
+    let corners: Vec<usize> = (0..n).filter(|&i| {
+        is_corner(contour[(i + n - 1) % n].end_tangent, contour[i].start_tangent)
+    }).collect();
+
+    match corners.len() {
+        0 => {}
+        1 => {
+            // A teardrop shape, the middle of it doesn't need to be split.
+            if n > 1 {
+                let corner = corners[0];
+                colors[corner] = PALETTE[1];
+                colors[(corner + n - 1) % n] = PALETTE[2];
+            }
+        }
+        num_corners => {
+            for (spline, &start) in corners.iter().enumerate() {
+                let mut color = PALETTE[spline % 3];
+                if spline == num_corners - 1 && spline % 3 == 0 {
+                    // Avoid using the color of the first spline again.
+                    color = PALETTE[1];
+                }
+                let end = corners[(spline + 1) % num_corners];
+                let mut i = start;
+                loop {
+                    colors[i] = color;
+                    i = (i + 1) % n;
+                    if i == end {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    colors
+}
+
+// The square distance between a point and a segment, and the parameter of the projection
+// of the point on the line that contains the segment.
+fn segment_distance(from: Point, to: Point, p: Point) -> (f32, f32) {
+    let v = to - from;
+    let length2 = v.square_length();
+    let t = if length2 > 0.0 { (p - from).dot(v) / length2 } else { 0.0 };
+    let closest = from + v * t.clamp(0.0, 1.0);
+
+    ((p - closest).square_length(), t)
+}
+
+fn winding_contribution(from: Point, to: Point, p: Point) -> i32 {
+    let side = (to - from).cross(p - from);
+    if from.y <= p.y {
+        if to.y > p.y && side > 0.0 {
+            return 1;
+        }
+    } else if to.y <= p.y && side < 0.0 {
+        return -1;
+    }
+
+    0
+}
+
+#[cfg(test)]
+use path::default::Path;
+#[cfg(test)]
+use path::builder::FlatPathBuilder;
+#[cfg(test)]
+use math::vector;
+
+#[cfg(test)]
+fn square() -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(4.0, 1.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.line_to(point(1.0, 4.0));
+    builder.close();
+    builder.build()
+}
+
+#[test]
+fn square_distance_field() {
+    let transform = Transform2D::create_scale(2.0, 2.0);
+    let mut grid = vec![0.0; 100];
+    signed_distance_field(square().as_slice(), &transform, 0.01, 10, 10, &mut grid);
+
+    // The square covers [2, 8] in the grid.
+    assert!((grid[4 * 10 + 4] + 2.5).abs() < 0.0001);
+    assert!((grid[0] - 1.5 * (2.0f32).sqrt()).abs() < 0.0001);
+    assert!((grid[4 * 10 + 9] - 1.5).abs() < 0.0001);
+
+    // The winding order doesn't matter.
+    let reversed = Transform2D::create_scale(-2.0, 2.0).post_translate(vector(10.0, 0.0));
+    let mut grid2 = vec![0.0; 100];
+    signed_distance_field(square().as_slice(), &reversed, 0.01, 10, 10, &mut grid2);
+    for y in 0..10 {
+        for x in 0..10 {
+            assert!((grid[y * 10 + x] - grid2[y * 10 + 9 - x]).abs() < 0.0001);
+        }
+    }
+}
+
+#[test]
+fn square_multi_channel_distance_field() {
+    fn median(c: [f32; 3]) -> f32 {
+        c[0].min(c[1]).max(c[0].max(c[1]).min(c[2]))
+    }
+
+    let transform = Transform2D::create_scale(2.0, 2.0);
+    let mut grid = vec![[0.0; 3]; 100];
+    multi_channel_distance_field(square().as_slice(), &transform, 0.01, 10, 10, &mut grid);
+
+    assert!((median(grid[4 * 10 + 4]) + 2.5).abs() < 0.0001);
+    assert!((median(grid[4 * 10 + 9]) - 1.5).abs() < 0.0001);
+    // The corners are sharp.
+    assert!((median(grid[0]) - 1.5).abs() < 0.0001);
+
+    for y in 0..10 {
+        for x in 0..10 {
+            let inside = (2..8).contains(&x) && (2..8).contains(&y);
+            assert_eq!(median(grid[y * 10 + x]) < 0.0, inside);
+        }
+    }
+}
@@ -17,6 +17,29 @@ pub mod raycast;
 pub mod walk;
 pub mod aabb;
 pub mod fit;
+pub mod clip;
+pub mod arrangement;
+pub mod markers;
+pub mod brush;
+pub mod morph;
+pub mod rounded_corners;
+pub mod offset;
+pub mod minkowski;
+pub mod skeleton;
+pub mod distance_field;
+pub mod occlusion;
+pub mod snap;
+pub mod cleanup;
+pub mod winding;
+pub mod containment;
+pub mod nesting;
+pub mod trace;
+pub mod marching_squares;
+pub mod sampling;
+
+pub use rounded_corners::round_corners;
+pub use winding::winding_number;
+pub use containment::contains_path;
 
 pub use path::math;
 pub use path::geom;
@@ -0,0 +1,262 @@
+//! Extract the contours of a scalar field with the marching squares algorithm.
+//!
+//! The values are sampled on a regular grid and the contours separate the areas where the
+//! values are above an iso value from the areas where they are below. This is how contour
+//! lines of terrains, heat maps and metaballs are usually rendered.
+//!
+//! The positions of the contours on the edges of the grid cells are linearly interpolated
+//! between the values at the corners of the cells. The area outside of the grid is
+//! considered to be below the iso value, so that all contours are closed.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::marching_squares::{iso_contours, Smoothing};
+//! use lyon_algorithms::math::{vector, Transform2D};
+//! use lyon_algorithms::path::iterator::PathIterator;
+//!
+//! // Two metaballs on a 32x32 grid.
+//! let mut values = vec![0.0; 32 * 32];
+//! for y in 0..32 {
+//!     for x in 0..32 {
+//!         let (x, y) = (x as f32, y as f32);
+//!         let a = 30.0 / ((x - 10.0) * (x - 10.0) + (y - 16.0) * (y - 16.0) + 1.0);
+//!         let b = 30.0 / ((x - 20.0) * (x - 20.0) + (y - 16.0) * (y - 16.0) + 1.0);
+//!         values[y as usize * 32 + x as usize] = a + b;
+//!     }
+//! }
+//!
+//! let path = iso_contours(&values, 32, 32, 1.0, Smoothing::Splines { tension: 0.0 });
+//!
+//! // The contours are in grid coordinates, transform them to scale them up.
+//! let transform = Transform2D::create_scale(10.0, 10.0).post_translate(vector(5.0, 5.0));
+//! for evt in path.path_iter().transformed(&transform) {
+//!     // ...
+//! }
+//! ```
+
+use path::default::Path;
+use path::builder::*;
+use math::{Point, point};
+
+
+/// How the interpolated points of the contours are connected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Line segments between the points.
+    Polygons,
+    /// Cubic bézier curves going through the points, forming a cardinal spline with the
+    /// provided tension (see `SplineBuilder::points_through`).
+    Splines { tension: f32 },
+}
+
+/// Builds the closed contours separating the values that are greater than or equal to
+/// `iso_value` from the smaller ones.
+///
+/// `values` contains `width * height` samples in row-major order. The sample at `(x, y)` is
+/// at the position `(x, y)` in the resulting path. The contours go clockwise around the
+/// areas above the iso value (on screen, with the y axis pointing down) and
+/// counter-clockwise around the holes in these areas.
+pub fn iso_contours(values: &[f32], width: usize, height: usize, iso_value: f32, smoothing: Smoothing) -> Path {
+    assert_eq!(values.len(), width * height);
+
+    let grid = Grid { values, width, height, iso_value };
+
+    // Each crossing of the contours with an edge of the grid has one successor, stored
+    // at the index of its edge.
+    let mut next = vec![usize::MAX; grid.num_edges()];
+    for y in -1..(height as i32) {
+        for x in -1..(width as i32) {
+            grid.link_cell(x, y, &mut next);
+        }
+    }
+
+    let mut builder = Path::builder();
+    let mut points = Vec::new();
+    for start in 0..next.len() {
+        if next[start] == usize::MAX {
+            continue;
+        }
+
+        points.clear();
+        let mut edge = start;
+        while next[edge] != usize::MAX {
+            let p = grid.crossing(edge);
+            if points.last() != Some(&p) {
+                points.push(p);
+            }
+            let e = next[edge];
+            next[edge] = usize::MAX;
+            edge = e;
+        }
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 3 {
+            continue;
+        }
+
+        match smoothing {
+            Smoothing::Polygons => {
+                builder.polygon(&points);
+            }
+            Smoothing::Splines { tension } => {
+                builder.points_through(&points, tension, true);
+            }
+        }
+    }
+
+    builder.build()
+}
+
+// The grid is padded with a row and a column of samples below the iso value on each side.
+// Edges are identified by the index of their first node in the padded grid, times two,
+// plus one for vertical edges.
+struct Grid<'l> {
+    values: &'l [f32],
+    width: usize,
+    height: usize,
+    iso_value: f32,
+}
+
+impl<'l> Grid<'l> {
+    fn num_edges(&self) -> usize {
+        (self.width + 2) * (self.height + 2) * 2
+    }
+
+    fn value(&self, x: i32, y: i32) -> Option<f32> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        Some(self.values[y as usize * self.width + x as usize])
+    }
+
+    fn is_inside(&self, x: i32, y: i32) -> bool {
+        match self.value(x, y) {
+            Some(v) => v >= self.iso_value,
+            None => false,
+        }
+    }
+
+    fn edge_id(&self, x: i32, y: i32, vertical: bool) -> usize {
+        let node = (y + 1) as usize * (self.width + 2) + (x + 1) as usize;
+        node * 2 + vertical as usize
+    }
+
+    // The position of the contour on an edge.
+    fn crossing(&self, edge: usize) -> Point {
+        let node = edge / 2;
+        let x = (node % (self.width + 2)) as i32 - 1;
+        let y = (node / (self.width + 2)) as i32 - 1;
+        let (x2, y2) = if edge % 2 == 1 { (x, y + 1) } else { (x + 1, y) };
+
+        let p1 = point(x as f32, y as f32);
+        let p2 = point(x2 as f32, y2 as f32);
+        match (self.value(x, y), self.value(x2, y2)) {
+            (Some(v1), Some(v2)) => {
+                let t = ((self.iso_value - v1) / (v2 - v1)).clamp(0.0, 1.0);
+                p1.lerp(p2, t)
+            }
+            // The contour goes through the sample next to the padding.
+            (Some(_), None) => p1,
+            _ => p2,
+        }
+    }
+
+    // Links the crossings of the cell whose top-left corner is at (x, y).
+    //
+    // Going around the cell clockwise, the contour leaves the area above the iso value at
+    // the "exit" edges and enters it at the "entry" edges. Keeping this area on the right
+    // side, each segment goes from an exit to an entry. In the ambiguous cases (two exits
+    // and two entries), the average of the corners decides whether the area above the iso
+    // value is connected through the center of the cell.
+    fn link_cell(&self, x: i32, y: i32, next: &mut [usize]) {
+        let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+        let inside = [
+            self.is_inside(corners[0].0, corners[0].1),
+            self.is_inside(corners[1].0, corners[1].1),
+            self.is_inside(corners[2].0, corners[2].1),
+            self.is_inside(corners[3].0, corners[3].1),
+        ];
+        // Top, right, bottom and left edges.
+        let edges = [
+            self.edge_id(x, y, false),
+            self.edge_id(x + 1, y, true),
+            self.edge_id(x, y + 1, false),
+            self.edge_id(x, y, true),
+        ];
+
+        let mut sum = 0.0;
+        let mut center_inside = true;
+        for &(cx, cy) in &corners {
+            match self.value(cx, cy) {
+                Some(v) => { sum += v; }
+                None => { center_inside = false; }
+            }
+        }
+        center_inside = center_inside && sum * 0.25 >= self.iso_value;
+
+        for i in 0..4 {
+            let is_exit = inside[i] && !inside[(i + 1) % 4];
+            if !is_exit {
+                continue;
+            }
+            for k in 1..4 {
+                let j = if center_inside { (i + k) % 4 } else { (i + 4 - k) % 4 };
+                let is_entry = !inside[j] && inside[(j + 1) % 4];
+                if is_entry {
+                    next[edges[i]] = edges[j];
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn iso_contours_rings() {
+    use path::PathEvent;
+    use winding::winding_number;
+
+    // A ring between the radii 4 and 8, touching nothing.
+    let mut values = vec![0.0; 24 * 24];
+    for y in 0..24 {
+        for x in 0..24 {
+            let d = ((x as f32 - 12.0).powi(2) + (y as f32 - 12.0).powi(2)).sqrt();
+            values[y * 24 + x] = 2.0 - (d - 6.0).abs();
+        }
+    }
+
+    let path = iso_contours(&values, 24, 24, 0.0, Smoothing::Polygons);
+    assert_eq!(path.iter().filter(|evt| *evt == PathEvent::Close).count(), 2);
+    assert_eq!(winding_number(point(12.0, 12.0), path.iter()), 0);
+    assert_eq!(winding_number(point(12.0, 18.0), path.iter()), 1);
+    assert_eq!(winding_number(point(12.0, 21.0), path.iter()), 0);
+
+    // The interpolated points are on the circles.
+    for evt in path.iter() {
+        if let PathEvent::LineTo(p) = evt {
+            let d = (p - point(12.0, 12.0)).length();
+            assert!((d - 4.0).abs() < 0.1 || (d - 8.0).abs() < 0.1);
+        }
+    }
+
+    let smooth = iso_contours(&values, 24, 24, 0.0, Smoothing::Splines { tension: 0.0 });
+    assert_eq!(smooth.iter().filter(|evt| *evt == PathEvent::Close).count(), 2);
+    assert_eq!(winding_number(point(12.0, 18.0), smooth.iter()), 1);
+
+    // Values above the iso value on the border of the grid are closed along the border.
+    let path = iso_contours(&[1.0; 4 * 3], 4, 3, 0.5, Smoothing::Polygons);
+    assert_eq!(path.points(), &[
+        point(0.0, 0.0), point(1.0, 0.0), point(2.0, 0.0), point(3.0, 0.0),
+        point(3.0, 1.0), point(3.0, 2.0), point(2.0, 2.0), point(1.0, 2.0),
+        point(0.0, 2.0), point(0.0, 1.0),
+    ]);
+
+    // The saddle cases are decided by the average of the corners.
+    let connected = iso_contours(&[1.0, 0.0, 0.0, 1.0], 2, 2, 0.4, Smoothing::Polygons);
+    assert_eq!(connected.iter().filter(|evt| *evt == PathEvent::Close).count(), 1);
+    let separated = iso_contours(&[1.0, 0.0, 0.0, 1.0], 2, 2, 0.6, Smoothing::Polygons);
+    assert_eq!(separated.iter().filter(|evt| *evt == PathEvent::Close).count(), 2);
+}
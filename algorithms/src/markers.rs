@@ -0,0 +1,376 @@
+//! Compute the position and orientation of markers along a path.
+//!
+//! Markers are placed on the vertices of a path (the end points of its segments),
+//! following the semantics of SVG's `marker-start`, `marker-mid` and `marker-end`
+//! properties with `orient="auto"`.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::markers::{compute_markers, build_markers, MarkerKind};
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! // A polyline.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! let path = builder.build();
+//!
+//! // An arrow head pointing toward the positive x axis.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(-2.0, -1.0));
+//! builder.line_to(point(0.0, 0.0));
+//! builder.line_to(point(-2.0, 1.0));
+//! builder.close();
+//! let arrow = builder.build();
+//!
+//! let markers = compute_markers(path.path_iter());
+//! let end_markers: Vec<_> = markers.into_iter().filter(|m| m.kind == MarkerKind::End).collect();
+//!
+//! let mut builder = Path::builder();
+//! build_markers(&end_markers, arrow.as_slice(), &mut builder);
+//! let arrows = builder.build();
+//! ```
+
+use path::{PathEvent, Segment};
+use path::iterator::PathIterator;
+use path::builder::PathBuilder;
+use path::default::PathSlice;
+use math::{Point, Vector, Angle, Transform2D, vector};
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc};
+
+use std::f32::consts::PI;
+
+/// Where a marker is placed along the path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MarkerKind {
+    /// The first vertex of the path.
+    Start,
+    /// Any vertex other than the first and the last ones.
+    Mid,
+    /// The last vertex of the path.
+    End,
+}
+
+/// The position and orientation of a marker.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub position: Point,
+    /// The angle between the x axis and the direction of the path at this vertex.
+    ///
+    /// At vertices between two segments, this is the bisector of the incoming and
+    /// outgoing directions.
+    pub angle: Angle,
+    pub kind: MarkerKind,
+}
+
+impl Marker {
+    /// The transform from the marker's coordinate system to the path's.
+    ///
+    /// In the marker's coordinate system, the vertex is at the origin and the
+    /// direction of the path is the positive x axis.
+    pub fn transform(&self) -> Transform2D {
+        // Built from the direction vector because euclid's `create_rotation` turns the
+        // other way.
+        let (sin, cos) = self.angle.get().sin_cos();
+        Transform2D::row_major(cos, sin, -sin, cos, self.position.x, self.position.y)
+    }
+}
+
+struct Vertex {
+    position: Point,
+    incoming: Option<Vector>,
+    outgoing: Option<Vector>,
+}
+
+/// Computes the markers at each vertex of a path.
+///
+/// A closed sub-path has a vertex at its start and one at its end, and their
+/// orientation takes both the first and the last segments into account.
+pub fn compute_markers<Iter: PathIterator>(mut path: Iter) -> Vec<Marker> {
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut sub_path_start = 0;
+
+    loop {
+        let from = path.get_state().current;
+        let segment = match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                sub_path_start = vertices.len();
+                vertices.push(Vertex { position: to, incoming: None, outgoing: None });
+                continue;
+            }
+            Some(PathEvent::LineTo(to)) => {
+                Segment::Line(LineSegment { from, to })
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                Segment::Quadratic(QuadraticBezierSegment { from, ctrl, to })
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                Segment::Cubic(CubicBezierSegment { from, ctrl1, ctrl2, to })
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                Segment::Arc(Arc {
+                    center, radii,
+                    start_angle, sweep_angle,
+                    x_rotation,
+                })
+            }
+            Some(PathEvent::Close) => {
+                if sub_path_start >= vertices.len() {
+                    continue;
+                }
+                let first = vertices[sub_path_start].position;
+                if from != first {
+                    add_segment(&mut vertices, &Segment::Line(LineSegment { from, to: first }));
+                }
+                // Connect the end of the sub-path with its start.
+                let last = vertices.len() - 1;
+                if last != sub_path_start {
+                    vertices[last].outgoing = vertices[sub_path_start].outgoing;
+                    vertices[sub_path_start].incoming = vertices[last].incoming;
+                }
+                continue;
+            }
+            None => {
+                break;
+            }
+        };
+
+        if vertices.is_empty() {
+            vertices.push(Vertex { position: from, incoming: None, outgoing: None });
+        }
+
+        add_segment(&mut vertices, &segment);
+    }
+
+    let mut markers = Vec::with_capacity(vertices.len() + 1);
+    let last = vertices.len().saturating_sub(1);
+    for (i, vertex) in vertices.iter().enumerate() {
+        let angle = match (vertex.incoming, vertex.outgoing) {
+            (Some(a), Some(b)) => bisector(a, b),
+            (Some(v), None) | (None, Some(v)) => v.y.atan2(v.x),
+            (None, None) => 0.0,
+        };
+        let kinds: &[MarkerKind] = if vertices.len() == 1 {
+            &[MarkerKind::Start, MarkerKind::End]
+        } else if i == 0 {
+            &[MarkerKind::Start]
+        } else if i == last {
+            &[MarkerKind::End]
+        } else {
+            &[MarkerKind::Mid]
+        };
+        for &kind in kinds {
+            markers.push(Marker {
+                position: vertex.position,
+                angle: Angle::radians(angle),
+                kind,
+            });
+        }
+    }
+
+    markers
+}
+
+/// Appends a copy of `marker_path` transformed into place at each marker.
+pub fn build_markers<Builder: PathBuilder>(
+    markers: &[Marker],
+    marker_path: PathSlice,
+    builder: &mut Builder,
+) {
+    for marker in markers {
+        let transform = marker.transform();
+        for evt in marker_path.path_iter() {
+            builder.path_event(rigid_transform_event(evt, &transform, marker.angle));
+        }
+    }
+}
+
+// Applies a transform made of a rotation by `angle` and a translation to an event.
+pub(crate) fn rigid_transform_event(evt: PathEvent, transform: &Transform2D, angle: Angle) -> PathEvent {
+    match evt {
+        PathEvent::MoveTo(to) => {
+            PathEvent::MoveTo(transform.transform_point(&to))
+        }
+        PathEvent::LineTo(to) => {
+            PathEvent::LineTo(transform.transform_point(&to))
+        }
+        PathEvent::QuadraticTo(ctrl, to) => {
+            PathEvent::QuadraticTo(
+                transform.transform_point(&ctrl),
+                transform.transform_point(&to),
+            )
+        }
+        PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+            PathEvent::CubicTo(
+                transform.transform_point(&ctrl1),
+                transform.transform_point(&ctrl2),
+                transform.transform_point(&to),
+            )
+        }
+        // Rotations and translations preserve the shape of arcs.
+        PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+            PathEvent::Arc(
+                transform.transform_point(&center),
+                radii,
+                sweep_angle,
+                x_rotation + angle,
+            )
+        }
+        PathEvent::Close => PathEvent::Close,
+    }
+}
+
+fn add_segment(vertices: &mut Vec<Vertex>, segment: &Segment) {
+    let (start_direction, end_direction, to) = match *segment {
+        Segment::Line(s) => (s.to - s.from, s.to - s.from, s.to),
+        Segment::Quadratic(s) => (
+            first_non_zero(&[s.ctrl - s.from, s.to - s.from]),
+            first_non_zero(&[s.to - s.ctrl, s.to - s.from]),
+            s.to,
+        ),
+        Segment::Cubic(s) => (
+            first_non_zero(&[s.ctrl1 - s.from, s.ctrl2 - s.from, s.to - s.from]),
+            first_non_zero(&[s.to - s.ctrl2, s.to - s.ctrl1, s.to - s.from]),
+            s.to,
+        ),
+        Segment::Arc(s) => {
+            let sign = if s.sweep_angle.get() < 0.0 { -1.0 } else { 1.0 };
+            (s.sample_tangent(0.0) * sign, s.sample_tangent(1.0) * sign, s.to())
+        }
+    };
+
+    if let Some(last) = vertices.last_mut() {
+        if start_direction != vector(0.0, 0.0) {
+            last.outgoing = Some(start_direction);
+        }
+    }
+
+    vertices.push(Vertex {
+        position: to,
+        incoming: if end_direction != vector(0.0, 0.0) { Some(end_direction) } else { None },
+        outgoing: None,
+    });
+}
+
+fn first_non_zero(vectors: &[Vector]) -> Vector {
+    for v in vectors {
+        if *v != vector(0.0, 0.0) {
+            return *v;
+        }
+    }
+
+    vector(0.0, 0.0)
+}
+
+fn bisector(incoming: Vector, outgoing: Vector) -> f32 {
+    let a = incoming.y.atan2(incoming.x);
+    let b = outgoing.y.atan2(outgoing.x);
+    let mut diff = b - a;
+    if diff > PI {
+        diff -= 2.0 * PI;
+    } else if diff < -PI {
+        diff += 2.0 * PI;
+    }
+
+    a + diff * 0.5
+}
+
+#[cfg(test)]
+fn assert_marker(marker: &Marker, position: Point, angle: f32, kind: MarkerKind) {
+    assert_eq!(marker.kind, kind);
+    assert!((marker.position - position).length() < 0.0001, "{:?} != {:?}", marker.position, position);
+    let (sin, cos) = marker.angle.get().sin_cos();
+    assert!((sin - angle.sin()).abs() < 0.0001 && (cos - angle.cos()).abs() < 0.0001,
+        "{:?} != {:?}", marker.angle.get(), angle);
+}
+
+#[test]
+fn markers_polyline() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let markers = compute_markers(path.path_iter());
+    assert_eq!(markers.len(), 3);
+    assert_marker(&markers[0], point(0.0, 0.0), 0.0, MarkerKind::Start);
+    assert_marker(&markers[1], point(10.0, 0.0), PI * 0.25, MarkerKind::Mid);
+    assert_marker(&markers[2], point(10.0, 10.0), PI * 0.5, MarkerKind::End);
+
+    // A triangle pointing toward the positive x axis.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, -1.0));
+    builder.line_to(point(2.0, 0.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let arrow = builder.build();
+
+    let mut builder = Path::builder();
+    build_markers(&markers[2..], arrow.as_slice(), &mut builder);
+    let arrows = builder.build();
+
+    let expected = [point(11.0, 10.0), point(10.0, 12.0), point(9.0, 10.0)];
+    assert_eq!(arrows.points().len(), 3);
+    for (p, e) in arrows.points().iter().zip(expected.iter()) {
+        assert!((*p - *e).length() < 0.0001, "{:?} != {:?}", p, e);
+    }
+}
+
+#[test]
+fn markers_closed_path() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let markers = compute_markers(path.path_iter());
+    assert_eq!(markers.len(), 5);
+    assert_marker(&markers[0], point(0.0, 0.0), -PI * 0.25, MarkerKind::Start);
+    assert_marker(&markers[1], point(10.0, 0.0), PI * 0.25, MarkerKind::Mid);
+    assert_marker(&markers[2], point(10.0, 10.0), PI * 0.75, MarkerKind::Mid);
+    assert_marker(&markers[3], point(0.0, 10.0), PI * 1.25, MarkerKind::Mid);
+    assert_marker(&markers[4], point(0.0, 0.0), -PI * 0.25, MarkerKind::End);
+}
+
+#[test]
+fn markers_degenerate_tangents() {
+    use path::default::Path;
+    use path::builder::{FlatPathBuilder, PathBuilder};
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0));
+    let path = builder.build();
+
+    let markers = compute_markers(path.path_iter());
+    assert_eq!(markers.len(), 2);
+    assert_marker(&markers[0], point(0.0, 0.0), 0.0, MarkerKind::Start);
+    assert_marker(&markers[1], point(10.0, 10.0), PI * 0.5, MarkerKind::End);
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 2.0));
+    let path = builder.build();
+
+    let markers = compute_markers(path.path_iter());
+    assert_eq!(markers.len(), 2);
+    assert_marker(&markers[0], point(1.0, 2.0), 0.0, MarkerKind::Start);
+    assert_marker(&markers[1], point(1.0, 2.0), 0.0, MarkerKind::End);
+}
@@ -0,0 +1,182 @@
+//! Minkowski sums of paths and convex shapes.
+//!
+//! The Minkowski sum of a path and a shape is the area swept by the shape when its
+//! origin moves over the filled path. A typical use is padding obstacles with the
+//! shape of a robot or object, so that planning its motion only involves a point.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::minkowski::{minkowski_sum, minkowski_sum_disc};
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(5.0, 10.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! let robot = [point(-1.0, -1.0), point(1.0, -1.0), point(0.0, 1.0)];
+//! let padded = minkowski_sum(triangle.as_slice(), &robot, 0.1);
+//! let rounded = minkowski_sum_disc(triangle.as_slice(), 2.0, 0.1);
+//! ```
+
+use path::default::{Path, PathSlice};
+use path::builder::FlatPathBuilder;
+use math::{Point, Vector, vector};
+use offset::{offset_path, Join, collect_polygons, polygon_area, add_polygon, filled_outline, remove_collinear_points};
+
+/// Computes the Minkowski sum of a path and a convex polygon.
+///
+/// The path is flattened with the provided tolerance and filled with the non-zero fill
+/// rule. Open sub-paths are swept by the polygon along their length. The vertices of
+/// the polygon are relative to its origin and can be in either winding order.
+pub fn minkowski_sum(path: PathSlice, convex_polygon: &[Point], tolerance: f32) -> Path {
+    let mut shape = convex_polygon.to_vec();
+    remove_collinear_points(&mut shape);
+    if polygon_area(&shape) < 0.0 {
+        shape.reverse();
+    }
+    if shape.is_empty() {
+        return Path::new();
+    }
+
+    let mut polygons = collect_polygons(path, tolerance);
+    let mut orientation = 1.0;
+    let mut max_area = 0.0;
+    for polygon in &polygons {
+        let area = polygon_area(polygon);
+        if area.abs() > max_area {
+            max_area = area.abs();
+            orientation = area.signum();
+        }
+    }
+
+    // Make the outer boundaries go in the same direction as the shape.
+    if orientation < 0.0 {
+        for polygon in &mut polygons {
+            polygon.reverse();
+        }
+    }
+
+    let mut raw = Path::builder();
+    for polygon in &polygons {
+        let outline = convolution(polygon, &shape);
+        add_polygon(&outline, &mut raw);
+    }
+    let raw = raw.build();
+
+    filled_outline(&raw, 1.0, tolerance)
+}
+
+/// Computes the Minkowski sum of a path and a disc centered on the origin.
+///
+/// This is equivalent to offsetting the path by `radius` with round joins.
+pub fn minkowski_sum_disc(path: PathSlice, radius: f32, tolerance: f32) -> Path {
+    offset_path(path, radius.abs(), Join::Round, tolerance)
+}
+
+// The convolution of a polygon and a convex shape, both with a positive area.
+//
+// Each edge of the polygon is translated by the vertex of the shape that is the furthest
+// along its outward normal. At convex corners the outline follows the edges of the shape
+// forward and at concave corners backward, creating loops with a negative winding number
+// that are removed with the other self-intersections.
+fn convolution(polygon: &[Point], shape: &[Point]) -> Vec<Point> {
+    let n = polygon.len();
+    let m = shape.len();
+    let mut result = Vec::new();
+    for i in 0..n {
+        let p = polygon[i];
+        let d0 = (p - polygon[(i + n - 1) % n]).normalize();
+        let d1 = (polygon[(i + 1) % n] - p).normalize();
+        let mut j = support(shape, vector(d0.y, -d0.x));
+        let end = support(shape, vector(d1.y, -d1.x));
+
+        result.push(p + shape[j].to_vector());
+        let forward = d0.cross(d1) > 0.0 || (d0.cross(d1).abs() < 1e-5 && d0.dot(d1) < 0.0);
+        while j != end {
+            j = if forward { (j + 1) % m } else { (j + m - 1) % m };
+            result.push(p + shape[j].to_vector());
+        }
+    }
+
+    result
+}
+
+// The index of the vertex of a shape that is the furthest in a given direction.
+fn support(shape: &[Point], direction: Vector) -> usize {
+    let mut best = 0;
+    let mut best_dot = shape[0].to_vector().dot(direction);
+    for (i, p) in shape.iter().enumerate().skip(1) {
+        let dot = p.to_vector().dot(direction);
+        if dot > best_dot {
+            best = i;
+            best_dot = dot;
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+use math::point;
+
+#[cfg(test)]
+fn unit_square() -> [Point; 4] {
+    [point(-1.0, -1.0), point(1.0, -1.0), point(1.0, 1.0), point(-1.0, 1.0)]
+}
+
+#[test]
+fn minkowski_sum_concave_polygon() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 5.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(5.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut shape = unit_square();
+    // The winding order of the shape doesn't matter.
+    shape.reverse();
+    let sum = minkowski_sum(path.as_slice(), &shape, 0.01);
+    let polygons = collect_polygons(sum.as_slice(), 0.01);
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].len(), 6);
+    assert!((polygon_area(&polygons[0]) - 119.0).abs() < 0.001);
+    assert!(polygons[0].iter().any(|p| (*p - point(6.0, 6.0)).length() < 0.001));
+
+    // The shape doesn't have to contain its origin.
+    let shape = [point(5.0, 5.0), point(6.0, 5.0), point(6.0, 6.0), point(5.0, 6.0)];
+    let sum = minkowski_sum(path.as_slice(), &shape, 0.01);
+    let polygons = collect_polygons(sum.as_slice(), 0.01);
+    assert_eq!(polygons.len(), 1);
+    assert!(polygons[0].iter().any(|p| (*p - point(16.0, 5.0)).length() < 0.001));
+    assert!(polygons[0].iter().any(|p| (*p - point(16.0, 11.0)).length() < 0.001));
+    assert!(polygons[0].iter().all(|p| p.x >= 4.999 && p.y >= 4.999));
+}
+
+#[test]
+fn minkowski_sum_open_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let sum = minkowski_sum(path.as_slice(), &unit_square(), 0.01);
+    let polygons = collect_polygons(sum.as_slice(), 0.01);
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].len(), 4);
+    assert!((polygon_area(&polygons[0]) - 24.0).abs() < 0.001);
+
+    let sum = minkowski_sum_disc(path.as_slice(), 1.0, 0.01);
+    let polygons = collect_polygons(sum.as_slice(), 0.01);
+    let expected = 20.0 + ::std::f32::consts::PI;
+    assert!((polygon_area(&polygons[0]) - expected).abs() < 0.1);
+}
@@ -0,0 +1,335 @@
+//! Interpolate between paths, for example to animate shape morphing.
+//!
+//! Two paths can be interpolated if they have the same structure, that is the same
+//! sequence of events. Paths that don't can be made compatible with `make_compatible`.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::morph::{lerp_paths, make_compatible};
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! // A triangle.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(5.0, 10.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! // A square.
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let square = builder.build();
+//!
+//! // The triangle and the square don't have the same number of edges.
+//! assert!(lerp_paths(triangle.as_slice(), square.as_slice(), 0.5).is_none());
+//!
+//! let (from, to) = make_compatible(triangle.as_slice(), square.as_slice());
+//! let halfway = lerp_paths(from.as_slice(), to.as_slice(), 0.5).unwrap();
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use path::builder::{FlatPathBuilder, PathBuilder};
+use path::default::{Path, PathSlice, Builder};
+use math::{Point, Angle};
+use geom::{CubicBezierSegment, QuadraticBezierSegment, Arc};
+
+/// Interpolates between two paths that have the same structure.
+///
+/// Returns `None` if the sequences of events of the two paths differ.
+pub fn lerp_paths(a: PathSlice, b: PathSlice, t: f32) -> Option<Path> {
+    if a.verbs() != b.verbs() {
+        return None;
+    }
+
+    let lerp_angle = |a: Angle, b: Angle| Angle::radians(a.get() + (b.get() - a.get()) * t);
+
+    let mut builder = Path::builder();
+    for (evt_a, evt_b) in a.iter().zip(b.iter()) {
+        builder.path_event(match (evt_a, evt_b) {
+            (PathEvent::MoveTo(a), PathEvent::MoveTo(b)) => {
+                PathEvent::MoveTo(a.lerp(b, t))
+            }
+            (PathEvent::LineTo(a), PathEvent::LineTo(b)) => {
+                PathEvent::LineTo(a.lerp(b, t))
+            }
+            (PathEvent::QuadraticTo(ctrl_a, a), PathEvent::QuadraticTo(ctrl_b, b)) => {
+                PathEvent::QuadraticTo(ctrl_a.lerp(ctrl_b, t), a.lerp(b, t))
+            }
+            (PathEvent::CubicTo(ctrl1_a, ctrl2_a, a), PathEvent::CubicTo(ctrl1_b, ctrl2_b, b)) => {
+                PathEvent::CubicTo(ctrl1_a.lerp(ctrl1_b, t), ctrl2_a.lerp(ctrl2_b, t), a.lerp(b, t))
+            }
+            (PathEvent::Arc(center_a, radii_a, sweep_a, rot_a), PathEvent::Arc(center_b, radii_b, sweep_b, rot_b)) => {
+                PathEvent::Arc(
+                    center_a.lerp(center_b, t),
+                    radii_a + (radii_b - radii_a) * t,
+                    lerp_angle(sweep_a, sweep_b),
+                    lerp_angle(rot_a, rot_b),
+                )
+            }
+            (PathEvent::Close, PathEvent::Close) => PathEvent::Close,
+            _ => {
+                unreachable!();
+            }
+        });
+    }
+
+    Some(builder.build())
+}
+
+/// Builds a pair of paths with the same structure that can be interpolated with `lerp_paths`.
+///
+/// All segments are converted to cubic bézier curves, and the segments of the sub-path
+/// that has the fewest are split until both sub-paths have the same number of segments.
+/// The sub-paths of the two paths are matched in order, and the path that has the fewest
+/// sub-paths receives additional ones that are collapsed into a single point.
+/// If one of two matching sub-paths is closed, both are closed.
+pub fn make_compatible(a: PathSlice, b: PathSlice) -> (Path, Path) {
+    let mut sub_paths_a = sub_paths(a);
+    let mut sub_paths_b = sub_paths(b);
+
+    while sub_paths_a.len() < sub_paths_b.len() {
+        let collapsed = sub_paths_b[sub_paths_a.len()].collapsed();
+        sub_paths_a.push(collapsed);
+    }
+    while sub_paths_b.len() < sub_paths_a.len() {
+        let collapsed = sub_paths_a[sub_paths_b.len()].collapsed();
+        sub_paths_b.push(collapsed);
+    }
+
+    let mut builder_a = Path::builder();
+    let mut builder_b = Path::builder();
+    for (sub_path_a, sub_path_b) in sub_paths_a.iter_mut().zip(sub_paths_b.iter_mut()) {
+        if sub_path_a.closed || sub_path_b.closed {
+            sub_path_a.close();
+            sub_path_b.close();
+        }
+
+        let num_segments = ::std::cmp::max(sub_path_a.segments.len(), sub_path_b.segments.len());
+        sub_path_a.subdivide(num_segments);
+        sub_path_b.subdivide(num_segments);
+
+        sub_path_a.build(&mut builder_a);
+        sub_path_b.build(&mut builder_b);
+    }
+
+    (builder_a.build(), builder_b.build())
+}
+
+struct SubPath {
+    start: Point,
+    segments: Vec<CubicBezierSegment<f32>>,
+    closed: bool,
+}
+
+impl SubPath {
+    fn end(&self) -> Point {
+        self.segments.last().map(|s| s.to).unwrap_or(self.start)
+    }
+
+    // A sub-path collapsed into the average of the end points of this one.
+    fn collapsed(&self) -> SubPath {
+        let mut sum = self.start.to_vector();
+        for segment in &self.segments {
+            sum += segment.to.to_vector();
+        }
+        let center = (sum / (self.segments.len() + 1) as f32).to_point();
+
+        SubPath {
+            start: center,
+            segments: Vec::new(),
+            closed: self.closed,
+        }
+    }
+
+    fn close(&mut self) {
+        let end = self.end();
+        if end != self.start {
+            let start = self.start;
+            self.segments.push(line(end, start));
+        }
+        self.closed = true;
+    }
+
+    // Splits the longest segments in half until there are `num_segments` segments.
+    fn subdivide(&mut self, num_segments: usize) {
+        while self.segments.len() < num_segments {
+            if self.segments.is_empty() {
+                let start = self.start;
+                self.segments.push(line(start, start));
+                continue;
+            }
+
+            let mut longest = 0;
+            let mut max_length = -1.0;
+            for (i, segment) in self.segments.iter().enumerate() {
+                let length = (segment.ctrl1 - segment.from).length()
+                    + (segment.ctrl2 - segment.ctrl1).length()
+                    + (segment.to - segment.ctrl2).length();
+                if length > max_length {
+                    max_length = length;
+                    longest = i;
+                }
+            }
+
+            let (first, second) = self.segments[longest].split(0.5);
+            self.segments[longest] = first;
+            self.segments.insert(longest + 1, second);
+        }
+    }
+
+    fn build(&self, builder: &mut Builder) {
+        builder.move_to(self.start);
+        for segment in &self.segments {
+            builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        }
+        if self.closed {
+            builder.close();
+        }
+    }
+}
+
+fn line(from: Point, to: Point) -> CubicBezierSegment<f32> {
+    CubicBezierSegment {
+        from,
+        ctrl1: from.lerp(to, 1.0 / 3.0),
+        ctrl2: from.lerp(to, 2.0 / 3.0),
+        to,
+    }
+}
+
+// Splits a path into sub-paths made of cubic bézier curves.
+fn sub_paths(path: PathSlice) -> Vec<SubPath> {
+    let mut sub_paths: Vec<SubPath> = Vec::new();
+    let mut iter = path.path_iter();
+    loop {
+        let from = iter.get_state().current;
+        let evt = match iter.next() {
+            Some(evt) => evt,
+            None => {
+                break;
+            }
+        };
+
+        if let PathEvent::MoveTo(to) = evt {
+            sub_paths.push(SubPath { start: to, segments: Vec::new(), closed: false });
+            continue;
+        }
+
+        if sub_paths.last().map(|s| s.closed).unwrap_or(true) {
+            sub_paths.push(SubPath { start: from, segments: Vec::new(), closed: false });
+        }
+        let sub_path = sub_paths.last_mut().unwrap();
+
+        match evt {
+            PathEvent::LineTo(to) => {
+                sub_path.segments.push(line(from, to));
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                sub_path.segments.push(QuadraticBezierSegment { from, ctrl, to }.to_cubic());
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                sub_path.segments.push(CubicBezierSegment { from, ctrl1, ctrl2, to });
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |segment| {
+                    sub_path.segments.push(segment.to_cubic());
+                });
+            }
+            PathEvent::Close => {
+                sub_path.close();
+            }
+            PathEvent::MoveTo(_) => {}
+        }
+    }
+
+    sub_paths
+}
+
+#[cfg(test)]
+use math::point;
+
+#[test]
+fn lerp_compatible_paths() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.close();
+    let a = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(20.0, 10.0));
+    builder.quadratic_bezier_to(point(0.0, 10.0), point(0.0, 0.0));
+    builder.close();
+    let b = builder.build();
+
+    let path = lerp_paths(a.as_slice(), b.as_slice(), 0.5).unwrap();
+    assert_eq!(path.verbs(), a.verbs());
+    assert_eq!(
+        path.points(),
+        &[point(5.0, 0.0), point(15.0, 5.0), point(5.0, 10.0), point(0.0, 5.0)]
+    );
+
+    assert_eq!(lerp_paths(a.as_slice(), b.as_slice(), 0.0).unwrap().points(), a.points());
+    assert_eq!(lerp_paths(a.as_slice(), b.as_slice(), 1.0).unwrap().points(), b.points());
+
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(20.0, 10.0));
+    builder.close();
+    let c = builder.build();
+    assert!(lerp_paths(a.as_slice(), c.as_slice(), 0.5).is_none());
+}
+
+#[test]
+fn make_paths_compatible() {
+    // A triangle with an explicit closing edge, and a square with an implicit one.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(5.0, 10.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.close();
+    let triangle = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 20.0));
+    builder.line_to(point(30.0, 20.0));
+    let square = builder.build();
+
+    let (a, b) = make_compatible(triangle.as_slice(), square.as_slice());
+    assert_eq!(a.verbs(), b.verbs());
+    // Two sub-paths: a closed one with four segments and an open one with one segment.
+    assert_eq!(a.verbs().len(), 1 + 4 + 1 + 1 + 1);
+
+    // The end points of the interpolated paths match the original ones.
+    let from = lerp_paths(a.as_slice(), b.as_slice(), 0.0).unwrap();
+    let to = lerp_paths(a.as_slice(), b.as_slice(), 1.0).unwrap();
+    for p in &[point(0.0, 0.0), point(10.0, 0.0), point(5.0, 10.0)] {
+        assert!(from.points().contains(p));
+    }
+    for p in &[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0), point(30.0, 20.0)] {
+        assert!(to.points().contains(p));
+    }
+
+    // The missing sub-path of the triangle is collapsed into a point.
+    let collapsed = point(25.0, 20.0);
+    assert!(a.points()[a.points().len() - 4..].iter().all(|p| (*p - collapsed).length() < 0.0001));
+}
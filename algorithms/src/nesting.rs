@@ -0,0 +1,358 @@
+//! Containment hierarchy of a set of closed contours.
+//!
+//! Tracing, clipping and font libraries often produce a "soup" of closed contours without
+//! telling which ones are holes of which. `NestingTree` sorts this out: each contour's
+//! parent is the smallest contour containing it. Contours at an even depth are solid and
+//! contours at an odd depth are holes (this is the even-odd rule), and each solid contour
+//! with its direct children makes a polygon with holes.
+//!
+//! The contours are expected not to intersect each other.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::nesting::NestingTree;
+//! use lyon_algorithms::winding_number;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::{point, Point};
+//!
+//! fn square(origin: Point, size: f32) -> Path {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(origin);
+//!     builder.line_to(point(origin.x + size, origin.y));
+//!     builder.line_to(point(origin.x + size, origin.y + size));
+//!     builder.line_to(point(origin.x, origin.y + size));
+//!     builder.close();
+//!     builder.build()
+//! }
+//!
+//! let contours = [
+//!     square(point(2.0, 2.0), 6.0),
+//!     square(point(0.0, 0.0), 10.0),
+//!     square(point(4.0, 4.0), 2.0),
+//! ];
+//! let slices: Vec<_> = contours.iter().map(|c| c.as_slice()).collect();
+//!
+//! let tree = NestingTree::new(&slices, 0.01);
+//! assert_eq!(tree.roots(), &[1]);
+//! assert_eq!(tree.node(0).parent, Some(1));
+//! assert!(tree.node(0).is_hole());
+//! assert_eq!(tree.node(2).depth, 2);
+//!
+//! // Two polygons: the big square with a hole, and the small square inside of the hole.
+//! let polygons = tree.polygons();
+//! assert_eq!(polygons.len(), 2);
+//!
+//! // A path in which the holes go in the opposite direction of the solid contours, so
+//! // that it is filled the same way with the even-odd and non-zero fill rules.
+//! let mut builder = Path::builder();
+//! tree.build_polygons(&slices, &mut builder);
+//! let path = builder.build();
+//! assert_eq!(winding_number(point(3.0, 3.0), path.iter()), 0);
+//! ```
+
+use path::{PathEvent, FlattenedEvent};
+use path::default::PathSlice;
+use path::iterator::PathIterator;
+use path::builder::PathBuilder;
+use math::{Point, point};
+
+use std::cmp::Ordering;
+use std::f32;
+
+/// A contour in a `NestingTree`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContourNode {
+    /// The index of the smallest contour containing this one.
+    pub parent: Option<usize>,
+    /// The indices of the contours directly contained in this one.
+    pub children: Vec<usize>,
+    /// The number of contours containing this one.
+    pub depth: u32,
+    /// The signed area of the flattened contour, positive if the contour goes clockwise
+    /// on screen (with the y axis pointing down).
+    pub signed_area: f32,
+}
+
+impl ContourNode {
+    /// Returns true if the contour is a hole according to the even-odd rule.
+    pub fn is_hole(&self) -> bool {
+        self.depth % 2 == 1
+    }
+}
+
+/// A solid contour and its holes, identified by their indices in the `NestingTree`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    pub exterior: usize,
+    pub holes: Vec<usize>,
+}
+
+/// The containment hierarchy of a set of contours.
+#[derive(Clone, Debug)]
+pub struct NestingTree {
+    nodes: Vec<ContourNode>,
+    roots: Vec<usize>,
+}
+
+impl NestingTree {
+    /// Builds the hierarchy of a set of contours.
+    ///
+    /// Each path is considered to be a single closed contour. The contours are flattened with
+    /// the provided tolerance to compare them.
+    pub fn new(contours: &[PathSlice], tolerance: f32) -> Self {
+        let polygons: Vec<FlatContour> = contours.iter()
+            .map(|contour| FlatContour::new(*contour, tolerance))
+            .collect();
+
+        let mut nodes: Vec<ContourNode> = polygons.iter().map(|polygon| ContourNode {
+            parent: None,
+            children: Vec::new(),
+            depth: 0,
+            signed_area: polygon.signed_area,
+        }).collect();
+        let mut roots: Vec<usize> = Vec::new();
+
+        // A contour can only be contained in a larger one, so inserting the contours from
+        // the largest to the smallest guarantees that the parents are inserted first.
+        let mut order: Vec<usize> = (0..contours.len()).collect();
+        order.sort_by(|a, b| {
+            let a = polygons[*a].signed_area.abs();
+            let b = polygons[*b].signed_area.abs();
+            b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+        });
+
+        for &idx in &order {
+            // Walk down the tree, from the outermost contours containing this one to the
+            // innermost.
+            let mut parent: Option<usize> = None;
+            loop {
+                let next = {
+                    let siblings = match parent {
+                        Some(p) => &nodes[p].children,
+                        None => &roots,
+                    };
+                    siblings.iter().cloned().find(|&c| polygons[c].contains(&polygons[idx]))
+                };
+                match next {
+                    Some(c) => { parent = Some(c); }
+                    None => { break; }
+                }
+            }
+
+            match parent {
+                Some(p) => {
+                    nodes[idx].depth = nodes[p].depth + 1;
+                    nodes[p].children.push(idx);
+                }
+                None => {
+                    roots.push(idx);
+                }
+            }
+            nodes[idx].parent = parent;
+        }
+
+        for node in &mut nodes {
+            node.children.sort();
+        }
+        roots.sort();
+
+        NestingTree { nodes, roots }
+    }
+
+    /// The contours, in the order they were provided.
+    pub fn nodes(&self) -> &[ContourNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, idx: usize) -> &ContourNode {
+        &self.nodes[idx]
+    }
+
+    /// The contours that aren't contained in any other contour.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Returns each solid contour with its holes.
+    pub fn polygons(&self) -> Vec<Polygon> {
+        let mut polygons = Vec::new();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if !node.is_hole() {
+                polygons.push(Polygon {
+                    exterior: idx,
+                    holes: node.children.clone(),
+                });
+            }
+        }
+
+        polygons
+    }
+
+    /// Adds the contours to a path builder, polygon by polygon, with the solid contours going
+    /// clockwise and the holes going counter-clockwise (on screen, with the y axis pointing
+    /// down).
+    ///
+    /// `contours` must be the contours the tree was built from.
+    pub fn build_polygons<Builder: PathBuilder>(&self, contours: &[PathSlice], builder: &mut Builder) {
+        assert_eq!(contours.len(), self.nodes.len());
+
+        for polygon in self.polygons() {
+            add_contour(contours[polygon.exterior], self.nodes[polygon.exterior].signed_area < 0.0, builder);
+            for &hole in &polygon.holes {
+                add_contour(contours[hole], self.nodes[hole].signed_area > 0.0, builder);
+            }
+        }
+    }
+}
+
+fn add_contour<Builder: PathBuilder>(contour: PathSlice, reverse: bool, builder: &mut Builder) {
+    let events: Box<dyn Iterator<Item=PathEvent>> = if reverse {
+        Box::new(contour.path_iter().reversed())
+    } else {
+        Box::new(contour.iter())
+    };
+
+    let mut closed = true;
+    for evt in events {
+        closed = evt == PathEvent::Close;
+        builder.path_event(evt);
+    }
+
+    if !closed {
+        builder.close();
+    }
+}
+
+// A flattened contour with its bounding box.
+struct FlatContour {
+    points: Vec<Point>,
+    min: Point,
+    max: Point,
+    signed_area: f32,
+}
+
+impl FlatContour {
+    fn new(contour: PathSlice, tolerance: f32) -> Self {
+        let mut points = Vec::new();
+        for evt in contour.path_iter().flattened(tolerance) {
+            match evt {
+                FlattenedEvent::MoveTo(p) | FlattenedEvent::LineTo(p) => {
+                    if points.last() != Some(&p) {
+                        points.push(p);
+                    }
+                }
+                FlattenedEvent::Close => {}
+            }
+        }
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        let mut min = point(f32::MAX, f32::MAX);
+        let mut max = point(f32::MIN, f32::MIN);
+        let mut area = 0.0;
+        for (i, p) in points.iter().enumerate() {
+            let next = points[(i + 1) % points.len()];
+            area += p.x * next.y - next.x * p.y;
+            min = min.min(*p);
+            max = max.max(*p);
+        }
+
+        FlatContour {
+            points,
+            min,
+            max,
+            signed_area: area * 0.5,
+        }
+    }
+
+    // Assumes that the contours don't intersect, so testing a single point is enough.
+    fn contains(&self, other: &FlatContour) -> bool {
+        let p = match other.points.first() {
+            Some(p) => *p,
+            None => { return false; }
+        };
+
+        if p.x < self.min.x || p.x > self.max.x || p.y < self.min.y || p.y > self.max.y {
+            return false;
+        }
+
+        let mut inside = false;
+        for (i, a) in self.points.iter().enumerate() {
+            let b = self.points[(i + 1) % self.points.len()];
+            if (a.y > p.y) != (b.y > p.y) {
+                let x = a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y);
+                if x > p.x {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+}
+
+#[test]
+fn nesting_tree() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::{vector, Angle};
+    use winding::winding_number;
+
+    // All contours go clockwise.
+    let square = |x: f32, y: f32, size: f32| {
+        let mut builder = Path::builder();
+        builder.move_to(point(x, y));
+        builder.line_to(point(x + size, y));
+        builder.line_to(point(x + size, y + size));
+        builder.line_to(point(x, y + size));
+        builder.close();
+        builder.build()
+    };
+    let circle = |x: f32, y: f32, radius: f32| {
+        let mut builder = Path::builder();
+        builder.move_to(point(x + radius, y));
+        builder.arc(point(x, y), vector(radius, radius), Angle::two_pi(), Angle::zero());
+        builder.build()
+    };
+
+    let contours = [
+        circle(50.0, 50.0, 1.0),
+        square(0.0, 0.0, 100.0),
+        square(40.0, 40.0, 20.0),
+        square(10.0, 10.0, 10.0),
+        square(200.0, 0.0, 10.0),
+        square(11.0, 11.0, 2.0),
+    ];
+    let slices: Vec<PathSlice> = contours.iter().map(|c| c.as_slice()).collect();
+
+    let tree = NestingTree::new(&slices, 0.01);
+    assert_eq!(tree.roots(), &[1, 4]);
+    let parents: Vec<Option<usize>> = tree.nodes().iter().map(|n| n.parent).collect();
+    assert_eq!(parents, vec![Some(2), None, Some(1), Some(1), None, Some(3)]);
+    let depths: Vec<u32> = tree.nodes().iter().map(|n| n.depth).collect();
+    assert_eq!(depths, vec![2, 0, 1, 1, 0, 2]);
+    assert_eq!(tree.node(1).children, vec![2, 3]);
+    assert!(tree.node(0).signed_area > 0.0);
+
+    assert_eq!(tree.polygons(), vec![
+        Polygon { exterior: 0, holes: vec![] },
+        Polygon { exterior: 1, holes: vec![2, 3] },
+        Polygon { exterior: 4, holes: vec![] },
+        Polygon { exterior: 5, holes: vec![] },
+    ]);
+
+    let mut builder = Path::builder();
+    tree.build_polygons(&slices, &mut builder);
+    let path = builder.build();
+
+    // With the holes reversed, the non-zero rule gives the same result as the even-odd one.
+    assert_eq!(winding_number(point(5.0, 5.0), path.iter()), 1);
+    assert_eq!(winding_number(point(45.0, 45.0), path.iter()), 0);
+    assert_eq!(winding_number(point(15.0, 15.0), path.iter()), 0);
+    assert_eq!(winding_number(point(12.0, 12.0), path.iter()), 1);
+    assert_eq!(winding_number(point(50.0, 50.5), path.iter()), 1);
+    assert_eq!(winding_number(point(205.0, 5.0), path.iter()), 1);
+}
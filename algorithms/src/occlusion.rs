@@ -0,0 +1,130 @@
+//! Remove the hidden parts of stacked opaque paths.
+//!
+//! When many opaque shapes are drawn on top of each other (for example the layers of a
+//! map), most of the rasterized pixels end up being overwritten. Resolving the overlaps
+//! on the CPU gives for each path only the region where it is visible. These regions do
+//! not overlap, so they can be tessellated and rendered in any order without overdraw.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::occlusion::{Layer, visible_regions};
+//! use lyon_algorithms::path::FillRule;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let background = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(2.0, 2.0));
+//! builder.line_to(point(8.0, 2.0));
+//! builder.line_to(point(5.0, 8.0));
+//! builder.close();
+//! let foreground = builder.build();
+//!
+//! let regions = visible_regions(&[
+//!     Layer { path: background.as_slice(), fill_rule: FillRule::NonZero },
+//!     Layer { path: foreground.as_slice(), fill_rule: FillRule::EvenOdd },
+//! ], 0.1);
+//!
+//! // The background now has a triangular hole.
+//! assert_eq!(regions.len(), 2);
+//! assert_eq!(regions[0].points().len(), 7);
+//! assert_eq!(regions[1].points().len(), 3);
+//! ```
+
+use path::FillRule;
+use path::default::{Path, PathSlice};
+use arrangement::{Arrangement, FaceId};
+use offset::faces_outline;
+
+/// A path and the fill rule it is rendered with.
+#[derive(Copy, Clone, Debug)]
+pub struct Layer<'l> {
+    pub path: PathSlice<'l>,
+    pub fill_rule: FillRule,
+}
+
+/// Computes the visible region of each layer.
+///
+/// The layers are ordered back to front: a layer hides the parts of the layers that come
+/// before it. The regions are returned in the same order as the layers, an empty path
+/// meaning that the layer is entirely hidden.
+///
+/// The paths are flattened with the provided tolerance and their sub-paths implicitly
+/// closed. Each region is made of non-intersecting polygons, the holes going in the
+/// opposite direction of the outer boundaries, so it can be filled with either fill
+/// rule.
+pub fn visible_regions(layers: &[Layer], tolerance: f32) -> Vec<Path> {
+    let arrangement = Arrangement::from_paths(
+        layers.iter().map(|layer| layer.path.path_iter()),
+        tolerance,
+    );
+
+    // The top-most layer covering each face.
+    let mut owners = vec![None; arrangement.num_faces()];
+    for (f, owner) in owners.iter_mut().enumerate() {
+        let face = FaceId(f as u32);
+        if face == arrangement.unbounded_face() {
+            continue;
+        }
+        *owner = (0..layers.len()).rev().find(|&i| {
+            layers[i].fill_rule.is_in(arrangement.winding(face, i))
+        });
+    }
+
+    (0..layers.len()).map(|i| {
+        faces_outline(&arrangement, |face: FaceId| owners[face.0 as usize] == Some(i))
+    }).collect()
+}
+
+#[test]
+fn stacked_squares() {
+    use path::builder::FlatPathBuilder;
+    use math::point;
+
+    fn square(x: f32, y: f32, size: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.move_to(point(x, y));
+        builder.line_to(point(x + size, y));
+        builder.line_to(point(x + size, y + size));
+        builder.line_to(point(x, y + size));
+        builder.close();
+        builder.build()
+    }
+
+    let bottom = square(0.0, 0.0, 10.0);
+    let middle = square(5.0, 0.0, 10.0);
+    let top = square(0.0, 0.0, 20.0);
+    let small = square(2.0, 2.0, 1.0);
+
+    let regions = visible_regions(&[
+        Layer { path: bottom.as_slice(), fill_rule: FillRule::EvenOdd },
+        Layer { path: middle.as_slice(), fill_rule: FillRule::NonZero },
+    ], 0.01);
+
+    assert_eq!(regions.len(), 2);
+    for p in regions[0].points() {
+        assert!(p.x <= 5.001);
+    }
+    assert_eq!(regions[0].points().len(), 4);
+    assert_eq!(regions[1].points().len(), 4);
+
+    // The top layer hides everything else.
+    let regions = visible_regions(&[
+        Layer { path: bottom.as_slice(), fill_rule: FillRule::EvenOdd },
+        Layer { path: small.as_slice(), fill_rule: FillRule::NonZero },
+        Layer { path: top.as_slice(), fill_rule: FillRule::NonZero },
+    ], 0.01);
+
+    assert!(regions[0].points().is_empty());
+    assert!(regions[1].points().is_empty());
+    assert_eq!(regions[2].points().len(), 4);
+}
@@ -0,0 +1,386 @@
+//! Grow or shrink the outline of a path.
+//!
+//! This is useful to generate halos around shapes, larger hit-test areas or the
+//! successive passes of a tool path.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::offset::{offset_path, Join};
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(5.0, 10.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! let halo = offset_path(triangle.as_slice(), 2.0, Join::Round, 0.1);
+//! let inset = offset_path(triangle.as_slice(), -1.0, Join::Miter(4.0), 0.1);
+//! ```
+
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use path::builder::FlatPathBuilder;
+use path::default::{Path, PathSlice, Builder};
+use math::{Point, Vector, vector};
+use arrangement::{Arrangement, FaceId, HalfEdgeId};
+
+/// The shape of the outer corners of an offset path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Join {
+    /// Sharp corners, replaced with bevels when the ratio between the length of the
+    /// miter and the offset distance exceeds the provided limit.
+    Miter(f32),
+    /// Circular arcs, approximated with the tolerance passed to `offset_path`.
+    Round,
+    Bevel,
+}
+
+/// Computes the outline of a path grown by `delta`, or shrunk if `delta` is negative.
+///
+/// The path is flattened with the provided tolerance and filled with the non-zero fill
+/// rule, which means that holes must have the opposite winding order of the sub-paths
+/// that contain them. Open sub-paths are outlined on both sides when `delta` is positive,
+/// with the join style also used for the caps, and vanish otherwise.
+///
+/// The self-intersections of the offset outlines are resolved, so the result has no
+/// overlapping sub-paths. The outer boundaries of the result go in the direction of
+/// increasing angles and the holes in the opposite direction.
+pub fn offset_path(path: PathSlice, delta: f32, join: Join, tolerance: f32) -> Path {
+    let polygons = collect_polygons(path, tolerance);
+
+    // The orientation of the largest polygon tells which side is the outside.
+    let mut orientation = 1.0;
+    let mut max_area = 0.0;
+    for polygon in &polygons {
+        let area = polygon_area(polygon);
+        if area.abs() > max_area {
+            max_area = area.abs();
+            orientation = area.signum();
+        }
+    }
+
+    // Offset along the right side of the edges, which is the outside of polygons
+    // that have a positive area.
+    let k = delta * orientation;
+
+    let mut raw = Path::builder();
+    for polygon in &polygons {
+        let outline = offset_polygon(polygon, k, join, tolerance);
+        add_polygon(&outline, &mut raw);
+    }
+    let raw = raw.build();
+
+    // Resolve the self-intersections and keep the boundaries between the filled
+    // and the empty faces.
+    filled_outline(&raw, orientation, tolerance)
+}
+
+// Resolves the self-intersections of a set of polygons and builds the boundaries between
+// the faces that have a winding number of the sign of `orientation` and the others.
+pub(crate) fn filled_outline(raw: &Path, orientation: f32, tolerance: f32) -> Path {
+    let arrangement = Arrangement::from_paths(Some(raw.path_iter()), tolerance);
+    faces_outline(&arrangement, |face: FaceId| {
+        face != arrangement.unbounded_face()
+            && arrangement.winding(face, 0) as f32 * orientation > 0.0
+    })
+}
+
+// Builds the boundaries between the faces of an arrangement for which `filled` returns
+// true and the others.
+pub(crate) fn faces_outline<F>(arrangement: &Arrangement, filled: F) -> Path
+where
+    F: Fn(FaceId) -> bool,
+{
+    let is_boundary = |h: HalfEdgeId| {
+        filled(arrangement.face(h)) && !filled(arrangement.face(arrangement.twin(h)))
+    };
+
+    let mut builder = Path::builder();
+    let mut visited = vec![false; arrangement.num_half_edges()];
+    for i in 0..arrangement.num_half_edges() {
+        let first = HalfEdgeId(i as u32);
+        if visited[i] || !is_boundary(first) {
+            continue;
+        }
+
+        let mut points = Vec::new();
+        let mut h = first;
+        loop {
+            visited[h.0 as usize] = true;
+            points.push(arrangement.vertex_position(arrangement.origin(h)));
+
+            // Turn around the end vertex through the filled faces until the next
+            // boundary half-edge.
+            let mut next = arrangement.next(h);
+            while !is_boundary(next) {
+                next = arrangement.next(arrangement.twin(next));
+            }
+            h = next;
+            if h == first {
+                break;
+            }
+        }
+
+        remove_collinear_points(&mut points);
+        add_polygon(&points, &mut builder);
+    }
+
+    builder.build()
+}
+
+// Flattens the sub-paths. Open sub-paths are represented as degenerate polygons
+// going back and forth along the polyline.
+pub(crate) fn collect_polygons(path: PathSlice, tolerance: f32) -> Vec<Vec<Point>> {
+    let mut polygons = Vec::new();
+    let mut current: Vec<Point> = Vec::new();
+    let mut closed = false;
+    for evt in path.path_iter().flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                finish_polygon(&mut current, closed, &mut polygons);
+                closed = false;
+                current.push(to);
+            }
+            FlattenedEvent::LineTo(to) => {
+                if closed {
+                    let first = current[0];
+                    finish_polygon(&mut current, closed, &mut polygons);
+                    closed = false;
+                    current.push(first);
+                }
+                if current.last().is_none_or(|last| (*last - to).square_length() > 1e-12) {
+                    current.push(to);
+                }
+            }
+            FlattenedEvent::Close => {
+                closed = true;
+            }
+        }
+    }
+    finish_polygon(&mut current, closed, &mut polygons);
+
+    polygons
+}
+
+fn finish_polygon(points: &mut Vec<Point>, closed: bool, polygons: &mut Vec<Vec<Point>>) {
+    if points.len() > 1 && (points[0] - points[points.len() - 1]).square_length() <= 1e-12 {
+        points.pop();
+    }
+
+    if points.len() > 1 {
+        let mut polygon = points.clone();
+        if !closed {
+            let n = points.len();
+            for i in (1..n - 1).rev() {
+                polygon.push(points[i]);
+            }
+        }
+        polygons.push(polygon);
+    }
+
+    points.clear();
+}
+
+// Offsets each edge of a polygon along its right side and connects the offset edges.
+//
+// Concave corners go through the original vertex so that the small loops they create
+// have the opposite winding and are removed with the other self-intersections.
+fn offset_polygon(polygon: &[Point], k: f32, join: Join, tolerance: f32) -> Vec<Point> {
+    let n = polygon.len();
+    let mut result = Vec::new();
+    for i in 0..n {
+        let p = polygon[i];
+        let d0 = (p - polygon[(i + n - 1) % n]).normalize();
+        let d1 = (polygon[(i + 1) % n] - p).normalize();
+        let n0 = vector(d0.y, -d0.x);
+        let n1 = vector(d1.y, -d1.x);
+        let cross = d0.cross(d1);
+        let dot = d0.dot(d1);
+
+        if cross.abs() < 1e-5 && dot > 0.0 {
+            // No corner.
+            result.push(p + n0 * k);
+            continue;
+        }
+
+        let outer = cross * k > 0.0 || cross.abs() < 1e-5;
+        if !outer {
+            result.push(p + n0 * k);
+            result.push(p);
+            result.push(p + n1 * k);
+            continue;
+        }
+
+        match join {
+            Join::Miter(limit) => {
+                // The ratio between the miter length and the offset is 1 / cos(θ / 2),
+                // with 1 + cos(θ) = 2 cos²(θ / 2).
+                let cos = n0.dot(n1);
+                if (1.0 + cos) * limit * limit >= 2.0 {
+                    result.push(p + (n0 + n1) * (k / (1.0 + cos)));
+                } else {
+                    result.push(p + n0 * k);
+                    result.push(p + n1 * k);
+                }
+            }
+            Join::Bevel => {
+                result.push(p + n0 * k);
+                result.push(p + n1 * k);
+            }
+            Join::Round => {
+                add_arc(p, n0, n1, k, tolerance, &mut result);
+            }
+        }
+    }
+
+    result
+}
+
+// Adds the points of an arc of radius `|k|` around `center` from the direction `n0`
+// to the direction `n1`, turning in the direction given by the sign of `k`.
+fn add_arc(center: Point, n0: Vector, n1: Vector, k: f32, tolerance: f32, output: &mut Vec<Point>) {
+    let radius = k.abs();
+    let sweep = n0.dot(n1).clamp(-1.0, 1.0).acos() * k.signum();
+    let from = n0 * k;
+
+    let step = if tolerance < radius {
+        2.0 * (1.0 - tolerance / radius).acos()
+    } else {
+        ::std::f32::consts::PI * 0.5
+    };
+    let count = (sweep.abs() / step).ceil().max(1.0) as u32;
+
+    output.push(center + from);
+    for i in 1..count {
+        let (sin, cos) = (sweep * i as f32 / count as f32).sin_cos();
+        output.push(center + vector(from.x * cos - from.y * sin, from.x * sin + from.y * cos));
+    }
+    output.push(center + n1 * k);
+}
+
+pub(crate) fn remove_collinear_points(points: &mut Vec<Point>) {
+    let mut i = 0;
+    while points.len() > 2 && i < points.len() {
+        let n = points.len();
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        let a = points[i] - prev;
+        let b = next - points[i];
+        if a.cross(b).abs() <= 1e-6 * a.length() * b.length() && a.dot(b) >= 0.0 {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+pub(crate) fn add_polygon(points: &[Point], builder: &mut Builder) {
+    if points.len() < 2 {
+        return;
+    }
+
+    builder.move_to(points[0]);
+    for p in &points[1..] {
+        builder.line_to(*p);
+    }
+    builder.close();
+}
+
+// Twice the signed area would be enough to compare polygons, but the actual area
+// is convenient in the tests.
+pub(crate) fn polygon_area(points: &[Point]) -> f32 {
+    let mut area = 0.0;
+    let n = points.len();
+    for i in 0..n {
+        let p = points[i];
+        let q = points[(i + 1) % n];
+        area += p.x * q.y - q.x * p.y;
+    }
+
+    area * 0.5
+}
+
+#[cfg(test)]
+use math::point;
+
+#[cfg(test)]
+fn square() -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.build()
+}
+
+#[cfg(test)]
+fn sub_path_polygons(path: &Path) -> Vec<Vec<Point>> {
+    collect_polygons(path.as_slice(), 0.01)
+}
+
+#[test]
+fn grow_square() {
+    let grown = offset_path(square().as_slice(), 1.0, Join::Miter(4.0), 0.01);
+    let polygons = sub_path_polygons(&grown);
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].len(), 4);
+    assert!((polygon_area(&polygons[0]) - 144.0).abs() < 0.001);
+    for p in &polygons[0] {
+        assert!((p.x + 1.0).abs() < 0.001 || (p.x - 11.0).abs() < 0.001);
+        assert!((p.y + 1.0).abs() < 0.001 || (p.y - 11.0).abs() < 0.001);
+    }
+
+    let beveled = offset_path(square().as_slice(), 1.0, Join::Bevel, 0.01);
+    let polygons = sub_path_polygons(&beveled);
+    assert_eq!(polygons[0].len(), 8);
+    assert!((polygon_area(&polygons[0]) - 142.0).abs() < 0.001);
+
+    // The miter of a right angle is √2 times the offset.
+    let clipped = offset_path(square().as_slice(), 1.0, Join::Miter(1.4), 0.01);
+    assert_eq!(sub_path_polygons(&clipped)[0].len(), 8);
+
+    let rounded = offset_path(square().as_slice(), 1.0, Join::Round, 0.01);
+    let polygons = sub_path_polygons(&rounded);
+    let expected = 100.0 + 40.0 + ::std::f32::consts::PI;
+    assert!((polygon_area(&polygons[0]) - expected).abs() < 0.1);
+}
+
+#[test]
+fn shrink_square() {
+    let shrunk = offset_path(square().as_slice(), -2.0, Join::Round, 0.01);
+    let polygons = sub_path_polygons(&shrunk);
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].len(), 4);
+    assert!((polygon_area(&polygons[0]) - 36.0).abs() < 0.001);
+    for p in &polygons[0] {
+        assert!((p.x - 2.0).abs() < 0.001 || (p.x - 8.0).abs() < 0.001);
+        assert!((p.y - 2.0).abs() < 0.001 || (p.y - 8.0).abs() < 0.001);
+    }
+
+    // Shrinking more than the half of the size removes the shape.
+    let empty = offset_path(square().as_slice(), -6.0, Join::Miter(4.0), 0.01);
+    assert!(sub_path_polygons(&empty).is_empty());
+}
+
+#[test]
+fn grow_open_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let line = builder.build();
+
+    let outline = offset_path(line.as_slice(), 1.0, Join::Bevel, 0.01);
+    let polygons = sub_path_polygons(&outline);
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].len(), 4);
+    assert!((polygon_area(&polygons[0]) - 20.0).abs() < 0.001);
+
+    assert!(sub_path_polygons(&offset_path(line.as_slice(), -1.0, Join::Bevel, 0.01)).is_empty());
+}
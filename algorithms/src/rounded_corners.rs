@@ -0,0 +1,262 @@
+//! Round the corners of a path.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::round_corners;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(5.0, 10.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! let rounded = round_corners(triangle.as_slice(), 2.0);
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use path::builder::{FlatPathBuilder, PathBuilder};
+use path::default::{Path, PathSlice, Builder};
+use math::{Point, Vector};
+
+use std::f32::consts::PI;
+
+/// Builds a copy of the path where the corners between consecutive line segments are
+/// replaced with circular arcs of the given radius, approximated with cubic bézier curves.
+///
+/// The radius is reduced at corners where it doesn't fit in half of the length of the
+/// adjacent segments. The joints that involve curves are left unchanged.
+pub fn round_corners(path: PathSlice, radius: f32) -> Path {
+    let mut builder = Path::builder();
+    let mut sub_path = Vec::new();
+    let mut closed = false;
+
+    let mut iter = path.path_iter();
+    loop {
+        let from = iter.get_state().current;
+        match iter.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                build_sub_path(&sub_path, closed, radius, &mut builder);
+                sub_path.clear();
+                closed = false;
+                sub_path.push(Segment { from: to, to, event: None });
+            }
+            Some(PathEvent::Close) => {
+                if let Some(first) = sub_path.first().map(|s| s.from) {
+                    if from != first {
+                        sub_path.push(Segment { from, to: first, event: None });
+                    }
+                }
+                closed = true;
+            }
+            Some(evt) => {
+                if closed {
+                    // A segment after a close starts a new sub-path.
+                    build_sub_path(&sub_path, closed, radius, &mut builder);
+                    sub_path.clear();
+                    closed = false;
+                    sub_path.push(Segment { from, to: from, event: None });
+                }
+                let to = iter.get_state().current;
+                if let PathEvent::LineTo(_) = evt {
+                    if to != from {
+                        sub_path.push(Segment { from, to, event: None });
+                    }
+                } else {
+                    sub_path.push(Segment { from, to, event: Some(evt) });
+                }
+            }
+            None => {
+                break;
+            }
+        }
+    }
+
+    build_sub_path(&sub_path, closed, radius, &mut builder);
+
+    builder.build()
+}
+
+// A segment of a sub-path. Line segments don't have an event.
+// The first segment of each sub-path is a placeholder for its start point.
+struct Segment {
+    from: Point,
+    to: Point,
+    event: Option<PathEvent>,
+}
+
+struct Corner {
+    start: Point,
+    ctrl1: Point,
+    ctrl2: Point,
+    end: Point,
+}
+
+// Computes the rounded corner between two line segments.
+fn corner(a: &Segment, b: &Segment, radius: f32) -> Option<Corner> {
+    if a.event.is_some() || b.event.is_some() || radius <= 0.0 {
+        return None;
+    }
+
+    let p = a.to;
+    let in_length = (a.from - p).length();
+    let out_length = (b.to - p).length();
+    if in_length == 0.0 || out_length == 0.0 {
+        return None;
+    }
+    let u: Vector = (a.from - p) / in_length;
+    let v: Vector = (b.to - p) / out_length;
+
+    // Angle between the two segments.
+    let angle = u.dot(v).clamp(-1.0, 1.0).acos();
+    if !(1e-4..=PI - 1e-4).contains(&angle) {
+        return None;
+    }
+
+    let half_tan = (angle * 0.5).tan();
+    let distance = (radius / half_tan).min(in_length * 0.5).min(out_length * 0.5);
+    let radius = distance * half_tan;
+
+    // The length of the handles of a cubic bézier approximating an arc.
+    let sweep = PI - angle;
+    let handle = 4.0 / 3.0 * (sweep * 0.25).tan() * radius;
+
+    Some(Corner {
+        start: p + u * distance,
+        ctrl1: p + u * (distance - handle),
+        ctrl2: p + v * (distance - handle),
+        end: p + v * distance,
+    })
+}
+
+fn build_sub_path(segments: &[Segment], closed: bool, radius: f32, builder: &mut Builder) {
+    if segments.is_empty() {
+        return;
+    }
+
+    // segments[0] is the start point placeholder.
+    let edges = &segments[1..];
+    let n = edges.len();
+
+    // corners[i] is the corner at the end of edges[i].
+    let mut corners: Vec<Option<Corner>> = Vec::with_capacity(n);
+    for i in 0..n {
+        let next = if i + 1 < n {
+            Some(&edges[i + 1])
+        } else if closed && n > 1 {
+            Some(&edges[0])
+        } else {
+            None
+        };
+        corners.push(next.and_then(|next| corner(&edges[i], next, radius)));
+    }
+
+    let start = match corners.last() {
+        Some(Some(c)) if closed => c.end,
+        _ => segments[0].from,
+    };
+    builder.move_to(start);
+
+    for (i, edge) in edges.iter().enumerate() {
+        match edge.event {
+            Some(evt) => {
+                builder.path_event(evt);
+            }
+            None => {
+                let end = match corners[i] {
+                    Some(ref c) => c.start,
+                    None => edge.to,
+                };
+                if (end - builder.current_position()).square_length() > 1e-10 {
+                    builder.line_to(end);
+                }
+            }
+        }
+
+        if let Some(ref c) = corners[i] {
+            builder.cubic_bezier_to(c.ctrl1, c.ctrl2, c.end);
+        }
+    }
+
+    if closed {
+        builder.close();
+    }
+}
+
+#[cfg(test)]
+use path::default::Verb;
+#[cfg(test)]
+use math::point;
+
+#[cfg(test)]
+fn square() -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.build()
+}
+
+#[cfg(test)]
+fn assert_approx_eq(a: Point, b: Point) {
+    assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn round_square_corners() {
+    let path = round_corners(square().as_slice(), 2.0);
+    assert_eq!(
+        path.verbs(),
+        &[
+            Verb::MoveTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::Close,
+        ]
+    );
+
+    let points = path.points();
+    assert_approx_eq(points[0], point(2.0, 0.0));
+    assert_approx_eq(points[1], point(8.0, 0.0));
+    let k = 4.0 / 3.0 * (PI / 8.0).tan() * 2.0;
+    assert_approx_eq(points[2], point(8.0 + k, 0.0));
+    assert_approx_eq(points[3], point(10.0, 2.0 - k));
+    assert_approx_eq(points[4], point(10.0, 2.0));
+    assert_approx_eq(points[points.len() - 1], point(2.0, 0.0));
+
+    // The radius is clamped so that corners meet at the middle of the edges.
+    let path = round_corners(square().as_slice(), 20.0);
+    assert_eq!(path.verbs().len(), 1 + 4 + 1);
+    assert_approx_eq(path.points()[0], point(5.0, 0.0));
+    assert_approx_eq(path.points()[3], point(10.0, 5.0));
+}
+
+#[test]
+fn round_open_path_corners() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(5.0, 15.0), point(0.0, 10.0));
+    let path = builder.build();
+
+    let rounded = round_corners(path.as_slice(), 1.0);
+    assert_eq!(
+        rounded.verbs(),
+        &[Verb::MoveTo, Verb::LineTo, Verb::CubicTo, Verb::LineTo, Verb::QuadraticTo]
+    );
+    assert_approx_eq(rounded.points()[0], point(0.0, 0.0));
+    assert_approx_eq(rounded.points()[1], point(9.0, 0.0));
+    assert_approx_eq(rounded.points()[4], point(10.0, 1.0));
+    assert_approx_eq(rounded.points()[5], point(10.0, 10.0));
+    assert_approx_eq(rounded.points()[7], point(0.0, 10.0));
+}
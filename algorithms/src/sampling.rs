@@ -0,0 +1,292 @@
+//! Generate points inside of the filled area of a path.
+//!
+//! Three distributions are available:
+//!
+//! - `grid_points` places the points on a regular grid.
+//! - `jittered_grid_points` places one point at a random position in each cell of a regular
+//!   grid, which looks less regular while keeping the density even.
+//! - `poisson_disk_points` places points randomly with a minimum distance between them
+//!   (using Bridson's algorithm), which is what stippling and vegetation scattering
+//!   usually want.
+//!
+//! The candidate points are kept if they are inside of the path according to its winding
+//! number and a fill rule. The random distributions are deterministic for a given seed.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::sampling::poisson_disk_points;
+//! use lyon_algorithms::path::FillRule;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(20.0, 0.0));
+//! builder.line_to(point(10.0, 20.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! let points = poisson_disk_points(triangle.as_slice(), FillRule::NonZero, 1.5, 42);
+//! for (i, a) in points.iter().enumerate() {
+//!     for b in &points[i + 1..] {
+//!         assert!((*a - *b).length() >= 1.5);
+//!     }
+//! }
+//! ```
+
+use path::default::PathSlice;
+use math::{Point, Vector, Rect, vector};
+use aabb::bounding_rect;
+use path::FillRule;
+use winding::winding_number;
+
+use std::f32::consts::PI;
+
+/// Returns the centers of the cells of a grid that are inside of the path.
+///
+/// The grid is aligned with the top-left corner of the path's bounding rectangle, and its
+/// cells are squares of size `spacing`.
+pub fn grid_points(path: PathSlice, fill_rule: FillRule, spacing: f32) -> Vec<Point> {
+    sample_grid(path, fill_rule, spacing, || vector(0.5, 0.5))
+}
+
+/// Returns one point at a random position in each cell of a grid, if it is inside of the
+/// path.
+///
+/// The grid is aligned with the top-left corner of the path's bounding rectangle, and its
+/// cells are squares of size `spacing`.
+pub fn jittered_grid_points(path: PathSlice, fill_rule: FillRule, spacing: f32, seed: u32) -> Vec<Point> {
+    let mut rng = Rng::new(seed);
+    sample_grid(path, fill_rule, spacing, || vector(rng.next_f32(), rng.next_f32()))
+}
+
+/// Returns random points inside of the path, at least `min_distance` apart from each other.
+///
+/// The points are added until there is no room left for another one, including in parts of
+/// the path that are disconnected from each other.
+pub fn poisson_disk_points(path: PathSlice, fill_rule: FillRule, min_distance: f32, seed: u32) -> Vec<Point> {
+    assert!(min_distance > 0.0);
+
+    // The number of candidates tried around each point before giving up on it.
+    const NUM_CANDIDATES: u32 = 30;
+
+    let rect = bounding_rect(path.iter());
+    let inside = |p: Point| fill_rule.is_in(winding_number(p, path.iter()));
+
+    let mut grid = BackgroundGrid::new(&rect, min_distance);
+    let mut rng = Rng::new(seed);
+    let mut active = Vec::new();
+
+    // Cover each cell of the background grid, to reach the parts of the path that can't be
+    // reached from the other points.
+    for cy in 0..grid.height {
+        for cx in 0..grid.width {
+            if grid.cells[cy * grid.width + cx] != usize::MAX {
+                continue;
+            }
+
+            for _ in 0..NUM_CANDIDATES {
+                let p = grid.origin + vector(
+                    (cx as f32 + rng.next_f32()) * grid.cell_size,
+                    (cy as f32 + rng.next_f32()) * grid.cell_size,
+                );
+                if grid.fits(&rect, p) && inside(p) {
+                    active.push(grid.add(p));
+                    break;
+                }
+            }
+
+            // Add points around the active points until they are surrounded.
+            while !active.is_empty() {
+                let a = (rng.next_f32() * active.len() as f32) as usize % active.len();
+                let center = grid.points[active[a]];
+
+                let mut found = false;
+                for _ in 0..NUM_CANDIDATES {
+                    let angle = rng.next_f32() * 2.0 * PI;
+                    let distance = min_distance * (1.0 + rng.next_f32());
+                    let p = center + vector(angle.cos(), angle.sin()) * distance;
+                    if grid.fits(&rect, p) && inside(p) {
+                        active.push(grid.add(p));
+                        found = true;
+                        break;
+                    }
+                }
+
+                if !found {
+                    active.swap_remove(a);
+                }
+            }
+        }
+    }
+
+    grid.points
+}
+
+fn sample_grid<F>(path: PathSlice, fill_rule: FillRule, spacing: f32, mut offset: F) -> Vec<Point>
+where
+    F: FnMut() -> Vector,
+{
+    assert!(spacing > 0.0);
+
+    let rect = bounding_rect(path.iter());
+    let columns = (rect.size.width / spacing).ceil() as u32;
+    let rows = (rect.size.height / spacing).ceil() as u32;
+
+    let mut points = Vec::new();
+    for y in 0..rows {
+        for x in 0..columns {
+            let cell = rect.origin + vector(x as f32, y as f32) * spacing;
+            let p = cell + offset() * spacing;
+            if fill_rule.is_in(winding_number(p, path.iter())) {
+                points.push(p);
+            }
+        }
+    }
+
+    points
+}
+
+// Accelerates the distance checks of the Poisson-disk sampling. The size of the cells
+// guarantees that each cell contains at most one point.
+struct BackgroundGrid {
+    origin: Point,
+    cell_size: f32,
+    min_distance: f32,
+    width: usize,
+    height: usize,
+    cells: Vec<usize>,
+    points: Vec<Point>,
+}
+
+impl BackgroundGrid {
+    fn new(rect: &Rect, min_distance: f32) -> Self {
+        let cell_size = min_distance / 2.0f32.sqrt();
+        let width = (rect.size.width / cell_size).ceil() as usize + 1;
+        let height = (rect.size.height / cell_size).ceil() as usize + 1;
+
+        BackgroundGrid {
+            origin: rect.origin,
+            cell_size,
+            min_distance,
+            width,
+            height,
+            cells: vec![usize::MAX; width * height],
+            points: Vec::new(),
+        }
+    }
+
+    fn cell(&self, p: Point) -> (usize, usize) {
+        let v = (p - self.origin) / self.cell_size;
+        (v.x as usize, v.y as usize)
+    }
+
+    fn fits(&self, rect: &Rect, p: Point) -> bool {
+        if p.x < rect.min_x() || p.x > rect.max_x() || p.y < rect.min_y() || p.y > rect.max_y() {
+            return false;
+        }
+
+        let (cx, cy) = self.cell(p);
+        let min_sq_dist = self.min_distance * self.min_distance;
+        for y in cy.saturating_sub(2)..(cy + 3).min(self.height) {
+            for x in cx.saturating_sub(2)..(cx + 3).min(self.width) {
+                let idx = self.cells[y * self.width + x];
+                if idx != usize::MAX && (self.points[idx] - p).square_length() < min_sq_dist {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn add(&mut self, p: Point) -> usize {
+        let (cx, cy) = self.cell(p);
+        let idx = self.points.len();
+        self.cells[cy * self.width + cx] = idx;
+        self.points.push(p);
+
+        idx
+    }
+}
+
+// A xorshift random number generator.
+struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        let state = seed ^ 0x9E37_79B9;
+        Rng { state: if state == 0 { 1 } else { state } }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+
+        x
+    }
+
+    // Returns a number between 0 (included) and 1 (excluded).
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+#[test]
+fn sampling_distributions() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::{Angle, point};
+
+    // A square with a hole going in the same direction.
+    let mut builder = Path::builder();
+    for &(origin, size) in &[(point(0.0, 0.0), 10.0), (point(3.0, 3.0), 4.0)] {
+        builder.move_to(origin);
+        builder.line_to(origin + vector(size, 0.0));
+        builder.line_to(origin + vector(size, size));
+        builder.line_to(origin + vector(0.0, size));
+        builder.close();
+    }
+    let square = builder.build();
+
+    assert_eq!(grid_points(square.as_slice(), FillRule::NonZero, 1.0).len(), 100);
+    let points = grid_points(square.as_slice(), FillRule::EvenOdd, 1.0);
+    assert_eq!(points.len(), 84);
+    assert_eq!(points[0], point(0.5, 0.5));
+
+    let points = jittered_grid_points(square.as_slice(), FillRule::EvenOdd, 1.0, 3);
+    assert_eq!(points.len(), 84);
+    for p in &points {
+        assert!(p.x >= 0.0 && p.x <= 10.0 && p.y >= 0.0 && p.y <= 10.0);
+        assert!(p.x <= 3.0 || p.x >= 7.0 || p.y <= 3.0 || p.y >= 7.0);
+    }
+
+    // Two discs far from each other.
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(10.0, 10.0), Angle::two_pi(), Angle::zero());
+    builder.close();
+    builder.move_to(point(32.0, 0.0));
+    builder.arc(point(30.0, 0.0), vector(2.0, 2.0), Angle::two_pi(), Angle::zero());
+    builder.close();
+    let discs = builder.build();
+
+    let points = poisson_disk_points(discs.as_slice(), FillRule::NonZero, 1.0, 1);
+    assert!(points.len() > 150);
+    assert!(points.iter().any(|p| p.x > 20.0));
+    for (i, a) in points.iter().enumerate() {
+        assert!((*a - point(0.0, 0.0)).length() < 10.0 || (*a - point(30.0, 0.0)).length() < 2.0);
+        for b in &points[i + 1..] {
+            assert!((*a - *b).length() >= 0.999);
+        }
+    }
+
+    assert_eq!(points, poisson_disk_points(discs.as_slice(), FillRule::NonZero, 1.0, 1));
+}
@@ -0,0 +1,367 @@
+//! Approximate medial axis of a filled path.
+//!
+//! The medial axis is the set of points inside a shape that have more than one closest
+//! point on its boundary, along with their distance to the boundary. It is useful to
+//! place labels inside polygons, to generate roofs or ribbons of variable width.
+//!
+//! The outline of the path is sampled at regular intervals and the medial axis is
+//! approximated with the edges of the Voronoi diagram of the samples that are inside of
+//! the path, computed from their Delaunay triangulation.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::skeleton::medial_axis;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(20.0, 0.0));
+//! builder.line_to(point(20.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let rectangle = builder.build();
+//!
+//! let skeleton = medial_axis(rectangle.as_slice(), 0.5, 0.1);
+//! let (label_position, _) = skeleton.deepest_point().unwrap();
+//! ```
+
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use path::default::PathSlice;
+use math::{Point, point};
+
+use std::collections::{HashMap, HashSet};
+
+/// An edge of the medial axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SkeletonEdge {
+    pub from: Point,
+    pub to: Point,
+    /// Distance between `from` and the boundary of the path.
+    pub from_distance: f32,
+    /// Distance between `to` and the boundary of the path.
+    pub to_distance: f32,
+}
+
+/// The approximate medial axis of a path, as a set of edges.
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+    edges: Vec<SkeletonEdge>,
+}
+
+impl Skeleton {
+    pub fn edges(&self) -> &[SkeletonEdge] { &self.edges }
+
+    /// The point of the skeleton that is the furthest from the boundary and its distance
+    /// to the boundary, which is the center and radius of the largest inscribed circle.
+    pub fn deepest_point(&self) -> Option<(Point, f32)> {
+        let mut result: Option<(Point, f32)> = None;
+        for edge in &self.edges {
+            for &(p, d) in &[(edge.from, edge.from_distance), (edge.to, edge.to_distance)] {
+                if result.is_none_or(|(_, best)| d > best) {
+                    result = Some((p, d));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Computes the approximate medial axis of a path filled with the non-zero fill rule.
+///
+/// The path is flattened with the provided tolerance and its outline is sampled every
+/// `spacing` units. The distances to the boundary are measured to the closest samples,
+/// so they are overestimated by up to half of the spacing. Sharp convex corners produce
+/// short branches going toward them, like in the exact medial axis.
+///
+/// The cost is quadratic in the number of samples.
+pub fn medial_axis(path: PathSlice, spacing: f32, tolerance: f32) -> Skeleton {
+    let polygons = flattened_polygons(path, tolerance);
+    let spacing = spacing.max(tolerance);
+
+    // Sample the outline, remembering which pairs of samples are consecutive along it.
+    let mut samples = Vec::new();
+    let mut boundary = HashSet::new();
+    for polygon in &polygons {
+        let start = samples.len();
+        let n = polygon.len();
+        for i in 0..n {
+            let from = polygon[i];
+            let to = polygon[(i + 1) % n];
+            let count = ((to - from).length() / spacing).ceil().max(1.0) as u32;
+            for j in 0..count {
+                samples.push(from.lerp(to, j as f32 / count as f32));
+            }
+        }
+        let end = samples.len();
+        for i in start..end {
+            boundary.insert(edge_key(i, if i + 1 < end { i + 1 } else { start }));
+        }
+    }
+
+    let triangles = delaunay(&samples);
+
+    // Each triangle corresponds to a vertex of the Voronoi diagram at the center of
+    // its circumcircle.
+    let inside: Vec<bool> = triangles.iter().map(|t| {
+        winding_number(&polygons, point(t.center.0 as f32, t.center.1 as f32)) != 0
+    }).collect();
+
+    let mut adjacency: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for e in 0..3 {
+            let key = edge_key(t.vertices[e], t.vertices[(e + 1) % 3]);
+            adjacency.entry(key).or_default().push(i);
+        }
+    }
+
+    // The Voronoi edges between adjacent triangles, except the ones that cross the outline.
+    let mut edges = Vec::new();
+    for (key, neighbors) in &adjacency {
+        if neighbors.len() != 2 || boundary.contains(key) {
+            continue;
+        }
+        let (a, b) = (neighbors[0], neighbors[1]);
+        if !inside[a] || !inside[b] {
+            continue;
+        }
+
+        let ta = &triangles[a];
+        let tb = &triangles[b];
+        let from = point(ta.center.0 as f32, ta.center.1 as f32);
+        let to = point(tb.center.0 as f32, tb.center.1 as f32);
+        if (to - from).square_length() <= (spacing * 1e-4) * (spacing * 1e-4) {
+            continue;
+        }
+
+        edges.push(SkeletonEdge {
+            from,
+            to,
+            from_distance: ta.radius2.sqrt() as f32,
+            to_distance: tb.radius2.sqrt() as f32,
+        });
+    }
+
+    Skeleton { edges }
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+// The sub-paths as polygons, implicitly closed like when filling.
+fn flattened_polygons(path: PathSlice, tolerance: f32) -> Vec<Vec<Point>> {
+    let mut polygons = Vec::new();
+    let mut current: Vec<Point> = Vec::new();
+    for evt in path.path_iter().flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                finish_polygon(&mut current, &mut polygons);
+                current.push(to);
+            }
+            FlattenedEvent::LineTo(to) => {
+                if current.last().is_none_or(|last| (*last - to).square_length() > 1e-12) {
+                    current.push(to);
+                }
+            }
+            FlattenedEvent::Close => {
+                let first = current.first().cloned();
+                finish_polygon(&mut current, &mut polygons);
+                if let Some(first) = first {
+                    current.push(first);
+                }
+            }
+        }
+    }
+    finish_polygon(&mut current, &mut polygons);
+
+    polygons
+}
+
+fn finish_polygon(points: &mut Vec<Point>, polygons: &mut Vec<Vec<Point>>) {
+    if points.len() > 1 && (points[0] - points[points.len() - 1]).square_length() <= 1e-12 {
+        points.pop();
+    }
+    if points.len() > 2 {
+        polygons.push(points.clone());
+    }
+    points.clear();
+}
+
+fn winding_number(polygons: &[Vec<Point>], p: Point) -> i32 {
+    let mut winding = 0;
+    for polygon in polygons {
+        let n = polygon.len();
+        for i in 0..n {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % n];
+            let side = (b - a).cross(p - a);
+            if a.y <= p.y {
+                if b.y > p.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= p.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+
+    winding
+}
+
+struct Triangle {
+    vertices: [usize; 3],
+    center: (f64, f64),
+    radius2: f64,
+}
+
+fn triangle(points: &[(f64, f64)], a: usize, b: usize, c: usize) -> Triangle {
+    let (ax, ay) = points[a];
+    let (bx, by) = points[b];
+    let (cx, cy) = points[c];
+    let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    if d == 0.0 {
+        // Degenerate triangles are removed by the next insertion.
+        return Triangle { vertices: [a, b, c], center: (ax, ay), radius2: f64::INFINITY };
+    }
+
+    let a2 = ax * ax + ay * ay;
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+    let ux = (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d;
+    let uy = (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d;
+
+    Triangle {
+        vertices: [a, b, c],
+        center: (ux, uy),
+        radius2: (ax - ux) * (ax - ux) + (ay - uy) * (ay - uy),
+    }
+}
+
+// Bowyer-Watson triangulation. The triangles that touch the vertices of the initial
+// super-triangle are not part of the result.
+fn delaunay(samples: &[Point]) -> Vec<Triangle> {
+    if samples.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut points: Vec<(f64, f64)> = samples.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (points[0].0, points[0].1, points[0].0, points[0].1);
+    for &(x, y) in &points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let size = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (cx, cy) = ((min_x + max_x) * 0.5, (min_y + max_y) * 0.5);
+    let n = points.len();
+    points.push((cx - 20.0 * size, cy - size));
+    points.push((cx + 20.0 * size, cy - size));
+    points.push((cx, cy + 20.0 * size));
+
+    let mut triangles = vec![triangle(&points, n, n + 1, n + 2)];
+    let mut cavity: Vec<(usize, usize)> = Vec::new();
+    for i in 0..n {
+        let (x, y) = points[i];
+        let is_bad = |t: &Triangle| {
+            let dx = x - t.center.0;
+            let dy = y - t.center.1;
+            dx * dx + dy * dy < t.radius2
+        };
+
+        // The boundary of the union of the triangles whose circumcircle contains the
+        // point. Shared edges appear in both directions.
+        cavity.clear();
+        for t in triangles.iter().filter(|t| is_bad(t)) {
+            for e in 0..3 {
+                cavity.push((t.vertices[e], t.vertices[(e + 1) % 3]));
+            }
+        }
+        if cavity.is_empty() {
+            // Duplicate point.
+            continue;
+        }
+
+        triangles.retain(|t| !is_bad(t));
+        for &(a, b) in &cavity {
+            if !cavity.contains(&(b, a)) {
+                triangles.push(triangle(&points, a, b, i));
+            }
+        }
+    }
+
+    triangles.retain(|t| t.vertices.iter().all(|&v| v < n));
+
+    triangles
+}
+
+#[cfg(test)]
+use path::default::Path;
+#[cfg(test)]
+use path::builder::FlatPathBuilder;
+
+#[test]
+fn rectangle_medial_axis() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.line_to(point(20.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let skeleton = medial_axis(path.as_slice(), 0.5, 0.01);
+    assert!(!skeleton.edges().is_empty());
+
+    let (center, radius) = skeleton.deepest_point().unwrap();
+    assert!((radius - 5.0).abs() < 0.1);
+    assert!((center.y - 5.0).abs() < 0.1);
+    assert!(center.x > 4.9 && center.x < 15.1);
+
+    for edge in skeleton.edges() {
+        for &(p, d) in &[(edge.from, edge.from_distance), (edge.to, edge.to_distance)] {
+            assert!(p.x >= 0.0 && p.x <= 20.0 && p.y >= 0.0 && p.y <= 10.0);
+            // The distance to the closest side of the rectangle.
+            let exact = p.x.min(20.0 - p.x).min(p.y).min(10.0 - p.y);
+            assert!(d >= exact - 0.01 && d <= exact + 0.3);
+        }
+    }
+
+    // The branches going toward the corners.
+    let corner = point(0.5, 0.5);
+    assert!(skeleton.edges().iter().any(|e| (e.from - corner).length() < 1.0 || (e.to - corner).length() < 1.0));
+}
+
+#[test]
+fn medial_axis_with_hole() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    let skeleton = medial_axis(path.as_slice(), 0.25, 0.01);
+    for edge in skeleton.edges() {
+        for p in &[edge.from, edge.to] {
+            // Nothing in the hole.
+            assert!(!(p.x > 2.01 && p.x < 7.99 && p.y > 2.01 && p.y < 7.99));
+        }
+    }
+
+    // Half of the width of the ring, a bit more where the diagonals meet the corners
+    // of the hole.
+    let (_, radius) = skeleton.deepest_point().unwrap();
+    assert!(radius > 0.95 && radius < 1.25);
+}
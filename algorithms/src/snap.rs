@@ -0,0 +1,213 @@
+//! Snap the points of a path to a grid.
+//!
+//! Snapping coordinates to a grid before running boolean operations or tessellation
+//! removes nearly coincident points and nearly degenerate segments, which are the
+//! usual sources of numerical trouble. It is also the first step of pipelines that
+//! work with integer coordinates, like font hinting or tile rendering.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::snap::snap_to_grid;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.1, 0.2));
+//! builder.line_to(point(9.8, 0.1));
+//! builder.line_to(point(9.9, 0.3));
+//! builder.line_to(point(5.1, 10.2));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let snapped = snap_to_grid(path.path_iter(), 1.0, Path::builder());
+//!
+//! // The second and third points are snapped to the same position.
+//! assert_eq!(snapped.points(), &[point(0.0, 0.0), point(10.0, 0.0), point(5.0, 10.0)]);
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use path::builder::PathBuilder;
+use math::{Point, point};
+use geom::Arc;
+
+/// Snaps the endpoints and control points of a path to a grid.
+///
+/// Segments that become degenerate are removed: segments of zero length, quadratic
+/// bézier curves that end where they start, and sub-paths that collapse to a single
+/// point. Curves for which all control points are snapped onto the endpoints are
+/// replaced with line segments. Arcs are approximated with quadratic bézier curves
+/// before being snapped.
+///
+/// Panics if `grid_size` is not strictly positive.
+pub fn snap_to_grid<Iter, Builder>(mut path: Iter, grid_size: f32, mut builder: Builder) -> Builder::PathType
+where
+    Iter: PathIterator,
+    Builder: PathBuilder,
+{
+    assert!(grid_size > 0.0);
+
+    let mut snapper = Snapper {
+        builder: &mut builder,
+        grid_size,
+        start: point(0.0, 0.0),
+        current: point(0.0, 0.0),
+        in_sub_path: false,
+    };
+
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                snapper.move_to(to);
+            }
+            Some(PathEvent::LineTo(to)) => {
+                snapper.line_to(to);
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                snapper.quadratic_bezier_to(ctrl, to);
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                snapper.cubic_bezier_to(ctrl1, ctrl2, to);
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    snapper.quadratic_bezier_to(curve.ctrl, curve.to);
+                });
+            }
+            Some(PathEvent::Close) => {
+                snapper.close();
+            }
+            None => {
+                break;
+            }
+        }
+    }
+
+    builder.build()
+}
+
+struct Snapper<'l, Builder: 'l> {
+    builder: &'l mut Builder,
+    grid_size: f32,
+    // The snapped position of the last move_to.
+    start: Point,
+    current: Point,
+    // Whether the move_to of the current sub-path was sent to the builder.
+    in_sub_path: bool,
+}
+
+impl<'l, Builder: PathBuilder> Snapper<'l, Builder> {
+    fn snap(&self, p: Point) -> Point {
+        point(
+            (p.x / self.grid_size).round() * self.grid_size,
+            (p.y / self.grid_size).round() * self.grid_size,
+        )
+    }
+
+    fn move_to(&mut self, to: Point) {
+        // The move_to is only sent to the builder with the first segment of the sub-path.
+        self.start = self.snap(to);
+        self.current = self.start;
+        self.in_sub_path = false;
+    }
+
+    fn begin_segment(&mut self) {
+        if !self.in_sub_path {
+            self.builder.move_to(self.start);
+            self.in_sub_path = true;
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let to = self.snap(to);
+        if to == self.current {
+            return;
+        }
+        self.begin_segment();
+        self.builder.line_to(to);
+        self.current = to;
+    }
+
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        let ctrl = self.snap(ctrl);
+        let to = self.snap(to);
+        if to == self.current {
+            return;
+        }
+        if ctrl == self.current || ctrl == to {
+            self.line_to(to);
+            return;
+        }
+        self.begin_segment();
+        self.builder.quadratic_bezier_to(ctrl, to);
+        self.current = to;
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        let from = self.current;
+        let ctrl1 = self.snap(ctrl1);
+        let ctrl2 = self.snap(ctrl2);
+        let to = self.snap(to);
+        let on_endpoints = |p: Point| p == from || p == to;
+        if on_endpoints(ctrl1) && on_endpoints(ctrl2) {
+            // Either a straight line or a single point.
+            self.line_to(to);
+            return;
+        }
+        self.begin_segment();
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+        self.current = to;
+    }
+
+    fn close(&mut self) {
+        if self.in_sub_path {
+            self.builder.close();
+        }
+        self.current = self.start;
+        self.in_sub_path = false;
+    }
+}
+
+#[test]
+fn snap_degenerate_segments() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+
+    let mut builder = Path::builder();
+    // Collapses to a point.
+    builder.move_to(point(0.1, 0.1));
+    builder.line_to(point(0.3, 0.0));
+    builder.quadratic_bezier_to(point(0.2, 0.4), point(0.0, 0.2));
+    builder.close();
+    builder.move_to(point(0.0, 0.0));
+    // Straight lines after snapping.
+    builder.quadratic_bezier_to(point(0.2, 0.1), point(10.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 0.2), point(10.1, 9.8), point(10.0, 10.0));
+    // Back to the current position.
+    builder.quadratic_bezier_to(point(20.0, 20.0), point(10.2, 9.9));
+    builder.cubic_bezier_to(point(0.0, 12.0), point(0.0, 8.0), point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let snapped = snap_to_grid(path.path_iter(), 1.0, Path::builder());
+
+    let events: Vec<PathEvent> = snapped.iter().collect();
+    assert_eq!(events, vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(10.0, 0.0)),
+        PathEvent::LineTo(point(10.0, 10.0)),
+        PathEvent::CubicTo(point(0.0, 12.0), point(0.0, 8.0), point(0.0, 10.0)),
+        PathEvent::Close,
+    ]);
+
+    let snapped = snap_to_grid(path.path_iter(), 0.25, Path::builder());
+    for p in snapped.points() {
+        assert_eq!((p.x * 4.0).fract(), 0.0);
+        assert_eq!((p.y * 4.0).fract(), 0.0);
+    }
+}
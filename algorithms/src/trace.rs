@@ -0,0 +1,289 @@
+//! Convert bitmaps into smooth paths.
+//!
+//! This is a simplified version of the algorithm of [potrace](http://potrace.sourceforge.net):
+//!
+//! - The boundaries between filled and empty pixels are traced into closed staircase
+//!   polygons. Outer boundaries go clockwise and the boundaries of holes go counter-clockwise
+//!   (on screen, with the y axis pointing down), so the resulting path can be filled with
+//!   either fill rule. Filled pixels that only touch by a corner are considered connected.
+//! - Contours enclosing small areas (speckles) are discarded.
+//! - Each staircase is simplified into a polygon that stays within a tolerance of the pixel
+//!   boundaries.
+//! - The polygons are smoothed: the polygons' vertices are either kept as corners or
+//!   replaced with cubic bézier curves going from the middle of an edge to the middle of the
+//!   next one, depending on how sharp the vertex is compared to the size of the pixels.
+//!
+//! The paths are in pixel coordinates: the pixel at `(x, y)` covers the square between
+//! `(x, y)` and `(x + 1, y + 1)`.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::trace::{trace_bitmap, TraceOptions};
+//!
+//! // A 16x16 disc.
+//! let mut mask = vec![false; 16 * 16];
+//! for y in 0..16 {
+//!     for x in 0..16 {
+//!         let dx = x as f32 + 0.5 - 8.0;
+//!         let dy = y as f32 + 0.5 - 8.0;
+//!         mask[y * 16 + x] = dx * dx + dy * dy < 36.0;
+//!     }
+//! }
+//!
+//! let path = trace_bitmap(&mask, 16, 16, &TraceOptions::default());
+//! ```
+
+use path::default::{Path, Builder};
+use path::builder::*;
+use math::{Point, point};
+use geom::Line;
+
+/// Parameters of `trace_bitmap`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TraceOptions {
+    /// Contours enclosing an area of at most this many pixels are discarded.
+    ///
+    /// Default value: `2.0`.
+    pub min_area: f32,
+    /// Maximum distance in pixels between the polygons and the pixel boundaries.
+    ///
+    /// Default value: `0.75`.
+    pub tolerance: f32,
+    /// Vertices sharper than this threshold are kept as corners (this is potrace's `alphamax`
+    /// parameter). `0.0` only produces corners and values above `4/3` only produce curves.
+    ///
+    /// Default value: `1.0`.
+    pub corner_threshold: f32,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        TraceOptions {
+            min_area: 2.0,
+            tolerance: 0.75,
+            corner_threshold: 1.0,
+        }
+    }
+}
+
+/// Traces the filled pixels of a bitmap into a path.
+///
+/// `mask` contains `width * height` pixels in row-major order, `true` meaning filled.
+pub fn trace_bitmap(mask: &[bool], width: usize, height: usize, options: &TraceOptions) -> Path {
+    assert_eq!(mask.len(), width * height);
+
+    let mut builder = Path::builder();
+    for contour in trace_contours(mask, width, height) {
+        if signed_area(&contour).abs() <= options.min_area {
+            continue;
+        }
+
+        let mut polygon = simplify(&contour, options.tolerance);
+        if polygon.len() < 3 {
+            polygon = contour;
+        }
+
+        smooth(&polygon, options.corner_threshold, &mut builder);
+    }
+
+    builder.build()
+}
+
+// The directions of the edges between pixels, in clockwise order.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+// Returns the corners of the pixel boundaries.
+//
+// Edges are oriented with the filled pixel on their right. At each vertex, the next edge is
+// searched turning left first, so that filled pixels touching by a corner are connected.
+fn trace_contours(mask: &[bool], width: usize, height: usize) -> Vec<Vec<Point>> {
+    let filled = |x: i32, y: i32| {
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height
+            && mask[y as usize * width + x as usize]
+    };
+    // Whether there is an edge going from the vertex at (x, y) in the direction d.
+    let has_edge = |x: i32, y: i32, d: usize| {
+        match d {
+            0 => filled(x, y) && !filled(x, y - 1),
+            1 => filled(x - 1, y) && !filled(x, y),
+            2 => filled(x - 1, y - 1) && !filled(x - 1, y),
+            _ => filled(x, y - 1) && !filled(x - 1, y - 1),
+        }
+    };
+
+    let stride = width + 1;
+    let mut visited = vec![false; stride * (height + 1) * 4];
+    let edge_index = |x: i32, y: i32, d: usize| (y as usize * stride + x as usize) * 4 + d;
+
+    let mut contours = Vec::new();
+    for start_y in 0..(height as i32 + 1) {
+        for start_x in 0..(width as i32 + 1) {
+            for start_d in 0..4 {
+                if visited[edge_index(start_x, start_y, start_d)] || !has_edge(start_x, start_y, start_d) {
+                    continue;
+                }
+
+                // Each edge has exactly one successor and one predecessor, so following the
+                // edges leads back to the first one.
+                let mut contour = Vec::new();
+                let (mut x, mut y, mut d) = (start_x, start_y, start_d);
+                while !visited[edge_index(x, y, d)] {
+                    visited[edge_index(x, y, d)] = true;
+                    x += DIRECTIONS[d].0;
+                    y += DIRECTIONS[d].1;
+                    let next = [(d + 3) % 4, d, (d + 1) % 4];
+                    let next_d = next.iter().cloned().find(|&nd| has_edge(x, y, nd)).unwrap();
+                    if next_d != d {
+                        contour.push(point(x as f32, y as f32));
+                    }
+                    d = next_d;
+                }
+                contours.push(contour);
+            }
+        }
+    }
+
+    contours
+}
+
+fn signed_area(polygon: &[Point]) -> f32 {
+    let mut area = 0.0;
+    for (i, p) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        area += p.x * next.y - next.x * p.y;
+    }
+
+    area * 0.5
+}
+
+// Simplifies a closed polygon with the Douglas-Peucker algorithm, starting from the first
+// point and the point the furthest from it.
+fn simplify(polygon: &[Point], tolerance: f32) -> Vec<Point> {
+    let n = polygon.len();
+    let mut furthest = 0;
+    let mut max_dist = 0.0;
+    for (i, p) in polygon.iter().enumerate() {
+        let d = (*p - polygon[0]).square_length();
+        if d > max_dist {
+            max_dist = d;
+            furthest = i;
+        }
+    }
+
+    if furthest == 0 {
+        return polygon.to_vec();
+    }
+
+    let mut output = vec![polygon[0]];
+    simplify_range(polygon, 0, furthest, tolerance, &mut output);
+    output.push(polygon[furthest]);
+    simplify_range(polygon, furthest, n, tolerance, &mut output);
+
+    output
+}
+
+// Adds the points between `first` and `last` (excluded) that are needed to stay within the
+// tolerance. The indices wrap around the polygon.
+fn simplify_range(polygon: &[Point], first: usize, last: usize, tolerance: f32, output: &mut Vec<Point>) {
+    let n = polygon.len();
+    let from = polygon[first % n];
+    let line = Line { point: from, vector: polygon[last % n] - from };
+
+    let mut split = None;
+    let mut max_dist = tolerance;
+    for i in (first + 1)..last {
+        let d = line.distance_to_point(&polygon[i % n]);
+        if d > max_dist {
+            max_dist = d;
+            split = Some(i);
+        }
+    }
+
+    if let Some(i) = split {
+        simplify_range(polygon, first, i, tolerance, output);
+        output.push(polygon[i % n]);
+        simplify_range(polygon, i, last, tolerance, output);
+    }
+}
+
+// Replaces the vertices of a polygon with corners or curves between the middle of its edges,
+// the same way potrace does.
+fn smooth(polygon: &[Point], corner_threshold: f32, builder: &mut Builder) {
+    let n = polygon.len();
+    let mid = |a: Point, b: Point| a.lerp(b, 0.5);
+
+    builder.move_to(mid(polygon[n - 1], polygon[0]));
+    for j in 0..n {
+        let prev = polygon[(j + n - 1) % n];
+        let vertex = polygon[j];
+        let next = polygon[(j + 1) % n];
+
+        // The distance between the vertex and the line between its neighbors, relative to
+        // the L1 length of that line.
+        let chord = next - prev;
+        let denom = chord.x.abs() + chord.y.abs();
+        let mut alpha = if denom > 0.0 {
+            let dd = (vertex - prev).cross(chord).abs() / denom;
+            let alpha = if dd > 1.0 { 1.0 - 1.0 / dd } else { 0.0 };
+            alpha / 0.75
+        } else {
+            4.0 / 3.0
+        };
+
+        let from = mid(prev, vertex);
+        let to = mid(vertex, next);
+        if alpha >= corner_threshold {
+            builder.line_to(vertex);
+            builder.line_to(to);
+        } else {
+            alpha = alpha.clamp(0.55, 1.0);
+            builder.cubic_bezier_to(from.lerp(vertex, alpha), to.lerp(vertex, alpha), to);
+        }
+    }
+    builder.close();
+}
+
+#[test]
+fn trace_shapes() {
+    use path::PathEvent;
+    use winding::winding_number;
+
+    fn mask(f: &dyn Fn(f32, f32) -> bool) -> Vec<bool> {
+        let mut mask = vec![false; 24 * 24];
+        for y in 0..24 {
+            for x in 0..24 {
+                mask[y * 24 + x] = f(x as f32 + 0.5, y as f32 + 0.5);
+            }
+        }
+        mask
+    }
+
+    // A rectangle and a lone pixel.
+    let mut rectangle = mask(&|x, y| x > 4.0 && x < 20.0 && y > 4.0 && y < 16.0);
+    rectangle[20 * 24 + 20] = true;
+    let path = trace_bitmap(&rectangle, 24, 24, &TraceOptions::default());
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert!(events.contains(&PathEvent::LineTo(point(20.0, 4.0))));
+    assert!(events.contains(&PathEvent::LineTo(point(4.0, 16.0))));
+    assert_eq!(events.iter().filter(|evt| **evt == PathEvent::Close).count(), 1);
+    assert_eq!(winding_number(point(12.0, 10.0), path.iter()), 1);
+
+    // A ring, traced with curves.
+    let ring = mask(&|x, y| {
+        let d = (x - 12.0) * (x - 12.0) + (y - 12.0) * (y - 12.0);
+        d > 16.0 && d < 81.0
+    });
+    let path = trace_bitmap(&ring, 24, 24, &TraceOptions::default());
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.iter().filter(|evt| **evt == PathEvent::Close).count(), 2);
+    assert!(!events.iter().any(|evt| matches!(*evt, PathEvent::LineTo(..))));
+    assert_eq!(winding_number(point(12.0, 12.0), path.iter()), 0);
+    assert_eq!(winding_number(point(12.0, 18.5), path.iter()), 1);
+    assert_eq!(winding_number(point(12.0, 21.5), path.iter()), 0);
+
+    // A diagonal line of pixels is a single contour.
+    let diagonal = mask(&|x, y| { let d = x - y; d > 0.0 && d < 2.0 && y > 3.0 && y < 15.0 });
+    let path = trace_bitmap(&diagonal, 24, 24, &TraceOptions::default());
+    assert_eq!(path.iter().filter(|evt| *evt == PathEvent::Close).count(), 1);
+}
@@ -38,10 +38,25 @@
 //!
 //! ```
 //!
+//! ## Measuring distances on curves
+//!
+//! `walk_along_path` measures distances on the flattened path, which is always a bit
+//! shorter than the curves it approximates. The difference accumulates along the path, so
+//! that a dash pattern progressively drifts away from where it should be on long and curvy
+//! paths.
+//!
+//! `walk_along_curves` instead measures distances on the curves themselves using numerical
+//! integration, with an error bounded by the tolerance threshold on each segment of the
+//! path (the error doesn't grow with the number of steps along a segment).
+//!
 
 use math::*;
-use path::builder::FlatPathBuilder;
-use path::FlattenedEvent;
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::traits::{Segment, FlattenedForEach};
+use path::builder::{FlatPathBuilder, PathBuilder};
+use path::{FlattenedEvent, PathEvent};
+use path::iterator::PathIterator;
+use path::default::{Path, PathSlice, Builder};
 
 use std::f32;
 
@@ -54,6 +69,388 @@ where Iter: Iterator<Item=FlattenedEvent> {
     }
 }
 
+/// Invokes a callback with the position and tangent of `n` points evenly distributed along
+/// the path, the first one at the start of the path and the last one at its end.
+pub fn sample_evenly(path: PathSlice, n: u32, tolerance: f32, callback: &mut dyn FnMut(Point, Vector)) {
+    if n == 0 {
+        return;
+    }
+
+    let length = flattened_length(path.path_iter().flattened(tolerance));
+    let interval = if n > 1 { length / (n - 1) as f32 } else { 0.0 };
+
+    let mut count = 0;
+    let mut last_tangent = vector(0.0, 0.0);
+    let end;
+    {
+        let mut pattern = |position: Point, tangent: Vector, _distance: f32| {
+            if count >= n {
+                return None;
+            }
+            callback(position, tangent);
+            count += 1;
+            Some(interval)
+        };
+        let mut walker = PathWalker::new(0.0, &mut pattern);
+        let mut first = point(0.0, 0.0);
+        for evt in path.path_iter().flattened(tolerance) {
+            let from = walker.current_position();
+            let to = match evt {
+                FlattenedEvent::MoveTo(to) => {
+                    first = to;
+                    None
+                }
+                FlattenedEvent::LineTo(to) => Some(to),
+                FlattenedEvent::Close => Some(first),
+            };
+            if let Some(to) = to {
+                let v = to - from;
+                if v.length() >= 1e-5 {
+                    last_tangent = v.normalize();
+                }
+            }
+            walker.flat_event(evt);
+        }
+        end = walker.current_position();
+    }
+
+    // Rounding errors can make the walker stop just before the end of the path.
+    while count < n {
+        callback(end, last_tangent);
+        count += 1;
+    }
+}
+
+/// Splits the path into `n` paths of (approximately) equal lengths.
+///
+/// Curves are split rather than flattened, and close events are replaced with line
+/// segments going back to the start of the sub-path.
+pub fn split_into_equal_arc_lengths(path: PathSlice, n: u32, tolerance: f32) -> Vec<Path> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut splitter = EqualLengthSplitter {
+        builder: Path::builder(),
+        parts: Vec::with_capacity(n as usize),
+        interval: flattened_length(path.path_iter().flattened(tolerance)) / n as f32,
+        distance: 0.0,
+        num_parts: n as usize,
+        tolerance,
+    };
+
+    let mut iter = path.path_iter();
+    let mut first = point(0.0, 0.0);
+    loop {
+        let from = iter.get_state().current;
+        match iter.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                first = to;
+                splitter.builder.move_to(to);
+            }
+            Some(PathEvent::LineTo(to)) => {
+                splitter.segment(LineSegment { from, to }, &|builder, s| builder.line_to(s.to));
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                splitter.segment(
+                    QuadraticBezierSegment { from, ctrl, to },
+                    &|builder, s| builder.quadratic_bezier_to(s.ctrl, s.to),
+                );
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                splitter.segment(
+                    CubicBezierSegment { from, ctrl1, ctrl2, to },
+                    &|builder, s| builder.cubic_bezier_to(s.ctrl1, s.ctrl2, s.to),
+                );
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                splitter.segment(
+                    Arc { center, radii, start_angle, sweep_angle, x_rotation },
+                    &|builder, s| builder.arc(s.center, s.radii, s.sweep_angle, s.x_rotation),
+                );
+            }
+            Some(PathEvent::Close) => {
+                if from != first {
+                    splitter.segment(LineSegment { from, to: first }, &|builder, s| builder.line_to(s.to));
+                }
+            }
+            None => {
+                break;
+            }
+        }
+    }
+
+    let mut parts = splitter.parts;
+    parts.push(splitter.builder.build());
+
+    parts
+}
+
+struct EqualLengthSplitter {
+    builder: Builder,
+    parts: Vec<Path>,
+    interval: f32,
+    // The length of the part being built.
+    distance: f32,
+    num_parts: usize,
+    tolerance: f32,
+}
+
+impl EqualLengthSplitter {
+    fn segment<T>(&mut self, mut segment: T, add: &dyn Fn(&mut Builder, &T))
+    where T: FlattenedForEach<Scalar = f32> {
+        loop {
+            let length = segment.approximate_length(self.tolerance);
+            // The last part takes whatever remains of the path.
+            if self.distance + length < self.interval || self.parts.len() + 1 == self.num_parts {
+                add(&mut self.builder, &segment);
+                self.distance += length;
+                return;
+            }
+
+            let t = t_at_length(&segment, self.interval - self.distance, self.tolerance);
+            let (before, after) = segment.split(t);
+            add(&mut self.builder, &before);
+            self.parts.push(self.builder.build_and_reset());
+            self.builder.move_to(after.from());
+            self.distance = 0.0;
+            segment = after;
+        }
+    }
+}
+
+// Returns the t of the point at a given distance from the start of the segment, measured
+// along its flattened approximation.
+fn t_at_length<T>(segment: &T, length: f32, tolerance: f32) -> f32
+where T: FlattenedForEach<Scalar = f32> {
+    let mut result = None;
+    let mut distance = 0.0;
+    let mut prev = segment.from();
+    let mut prev_t = 0.0;
+    segment.for_each_flattened_with_t(tolerance, &mut |p, t| {
+        if result.is_some() {
+            return;
+        }
+        let d = (p - prev).length();
+        if distance + d >= length {
+            result = Some(if d > 0.0 { prev_t + (t - prev_t) * (length - distance) / d } else { t });
+            return;
+        }
+        distance += d;
+        prev = p;
+        prev_t = t;
+    });
+
+    result.unwrap_or(1.0)
+}
+
+fn flattened_length<Iter>(path: Iter) -> f32
+where Iter: Iterator<Item=FlattenedEvent> {
+    let mut length = 0.0;
+    let mut first = point(0.0, 0.0);
+    let mut prev = point(0.0, 0.0);
+    for evt in path {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                first = to;
+                prev = to;
+            }
+            FlattenedEvent::LineTo(to) => {
+                length += (to - prev).length();
+                prev = to;
+            }
+            FlattenedEvent::Close => {
+                length += (first - prev).length();
+                prev = first;
+            }
+        }
+    }
+
+    length
+}
+
+/// Walks along the curves of the path staring at offset `start` and applies a `Pattern`.
+///
+/// Unlike `walk_along_path`, distances are measured on the curves rather than on a
+/// flattened approximation of the path. The distance between the steps and their expected
+/// position is below `tolerance` on each segment. Tangents are computed from the
+/// derivative of the curves.
+pub fn walk_along_curves<Iter>(mut path: Iter, start: f32, tolerance: f32, pattern: &mut dyn Pattern)
+where Iter: PathIterator {
+    let mut walker = CurveWalker {
+        remaining: f32::max(start, 0.0),
+        advancement: 0.0,
+        tolerance: f32::max(tolerance, 1e-6),
+        done: false,
+        pattern,
+    };
+
+    let mut first = point(0.0, 0.0);
+    while !walker.done {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                first = to;
+            }
+            Some(PathEvent::LineTo(to)) => {
+                walker.segment(&LineSegment { from, to });
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                walker.segment(&QuadraticBezierSegment { from, ctrl, to });
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                walker.segment(&CubicBezierSegment { from, ctrl1, ctrl2, to });
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                walker.segment(&Arc { center, radii, start_angle, sweep_angle, x_rotation });
+            }
+            Some(PathEvent::Close) => {
+                walker.segment(&LineSegment { from, to: first });
+            }
+            None => {
+                break;
+            }
+        }
+    }
+}
+
+struct CurveWalker<'l> {
+    // The distance to walk before the next step.
+    remaining: f32,
+    advancement: f32,
+    tolerance: f32,
+    done: bool,
+    pattern: &'l mut dyn Pattern,
+}
+
+impl<'l> CurveWalker<'l> {
+    fn segment<T: Segment<Scalar = f32>>(&mut self, segment: &T) {
+        let mut rest = arc_length(segment, 0.0, 1.0, self.tolerance);
+        if rest < 1e-5 {
+            return;
+        }
+
+        let mut t = 0.0;
+        while !self.done && rest >= self.remaining {
+            t = t_at_arc_length(segment, t, self.remaining, rest, self.tolerance);
+            rest -= self.remaining;
+            self.advancement += self.remaining;
+
+            let mut tangent = segment.derivative(t);
+            if tangent.square_length() < 1e-12 {
+                tangent = segment.to() - segment.from();
+            }
+            if tangent.square_length() > 0.0 {
+                tangent = tangent.normalize();
+            }
+
+            match self.pattern.next(segment.sample(t), tangent, self.advancement) {
+                Some(distance) => {
+                    self.remaining = distance;
+                }
+                None => {
+                    self.done = true;
+                }
+            }
+        }
+
+        if !self.done {
+            self.remaining -= rest;
+            self.advancement += rest;
+        }
+    }
+}
+
+const MAX_QUADRATURE_DEPTH: u32 = 12;
+
+// Length of a segment between two values of t, computed with an adaptive Gauss-Legendre
+// quadrature of the length of the derivative.
+fn arc_length<T: Segment<Scalar = f32>>(segment: &T, t0: f32, t1: f32, tolerance: f32) -> f32 {
+    let estimate = gauss_legendre(segment, t0, t1);
+    adaptive_arc_length(segment, t0, t1, estimate, tolerance, 0)
+}
+
+fn adaptive_arc_length<T: Segment<Scalar = f32>>(
+    segment: &T,
+    t0: f32,
+    t1: f32,
+    estimate: f32,
+    tolerance: f32,
+    depth: u32,
+) -> f32 {
+    let mid = (t0 + t1) * 0.5;
+    let a = gauss_legendre(segment, t0, mid);
+    let b = gauss_legendre(segment, mid, t1);
+    if depth >= MAX_QUADRATURE_DEPTH || (a + b - estimate).abs() <= tolerance {
+        return a + b;
+    }
+
+    adaptive_arc_length(segment, t0, mid, a, tolerance * 0.5, depth + 1)
+        + adaptive_arc_length(segment, mid, t1, b, tolerance * 0.5, depth + 1)
+}
+
+// Five points Gauss-Legendre quadrature of the length of the derivative.
+fn gauss_legendre<T: Segment<Scalar = f32>>(segment: &T, t0: f32, t1: f32) -> f32 {
+    const NODES: [(f32, f32); 5] = [
+        (0.0, 0.568_888_9),
+        (-0.538_469_3, 0.478_628_7),
+        (0.538_469_3, 0.478_628_7),
+        (-0.906_179_8, 0.236_926_9),
+        (0.906_179_8, 0.236_926_9),
+    ];
+
+    let half = (t1 - t0) * 0.5;
+    let mid = (t0 + t1) * 0.5;
+    let mut sum = 0.0;
+    for &(x, w) in &NODES {
+        sum += w * segment.derivative(mid + half * x).length();
+    }
+
+    sum * half
+}
+
+// Finds the t at which the length of the segment between t0 and t is `length`, with
+// Newton's method falling back to bisection.
+//
+// `rest` is the length of the segment between t0 and 1.
+fn t_at_arc_length<T: Segment<Scalar = f32>>(
+    segment: &T,
+    t0: f32,
+    length: f32,
+    rest: f32,
+    tolerance: f32,
+) -> f32 {
+    if length <= 0.0 {
+        return t0;
+    }
+    if length >= rest {
+        return 1.0;
+    }
+
+    let mut low = t0;
+    let mut high = 1.0;
+    let mut t = t0 + (1.0 - t0) * length / rest;
+    for _ in 0..16 {
+        let error = arc_length(segment, t0, t, tolerance) - length;
+        if error.abs() <= tolerance {
+            break;
+        }
+        if error > 0.0 {
+            high = t;
+        } else {
+            low = t;
+        }
+
+        let speed = segment.derivative(t).length();
+        let newton = if speed > 0.0 { t - error / speed } else { -1.0 };
+        t = if newton > low && newton < high { newton } else { (low + high) * 0.5 };
+    }
+
+    t
+}
+
 /// Types implementing the `Pattern` can be used to walk along a path
 /// at constant speed.
 ///
@@ -279,6 +676,72 @@ fn walk_with_leftover() {
     walker.build();
 }
 
+#[test]
+fn walk_along_arc() {
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(10.0, 10.0), Angle::radians(f32::consts::PI * 0.5), Angle::radians(0.0));
+    builder.line_to(point(0.0, 20.0));
+    let path = builder.build();
+
+    let mut steps = Vec::new();
+    let mut pattern = RegularPattern {
+        interval: 1.0,
+        callback: |position, tangent, distance| {
+            steps.push((position, tangent, distance));
+            true
+        },
+    };
+    walk_along_curves(path.path_iter(), 0.0, 0.0001, &mut pattern);
+
+    // A quarter of a circle of length 15.7 followed by a line of length 10.
+    assert_eq!(steps.len(), 26);
+    for (i, &(position, tangent, distance)) in steps.iter().enumerate() {
+        assert!((distance - i as f32).abs() < 0.001);
+        let expected = if i <= 15 {
+            let angle = distance / 10.0;
+            (point(10.0 * angle.cos(), 10.0 * angle.sin()), vector(-angle.sin(), angle.cos()))
+        } else {
+            (point(0.0, 10.0 + distance - f32::consts::PI * 5.0), vector(0.0, 1.0))
+        };
+        assert!((position - expected.0).length() < 0.001);
+        assert!((tangent - expected.1).length() < 0.001);
+    }
+}
+
+#[test]
+fn walk_along_curves_matches_polylines() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(5.0, 0.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(0.0, 5.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut expected = Vec::new();
+    walk_along_path(path.path_iter().flattened(0.01), 1.0, &mut RegularPattern {
+        interval: 3.0,
+        callback: |position, tangent, distance| {
+            expected.push((position, tangent, distance));
+            true
+        },
+    });
+
+    let mut i = 0;
+    walk_along_curves(path.path_iter(), 1.0, 0.0001, &mut RegularPattern {
+        interval: 3.0,
+        callback: |position: Point, tangent: Vector, distance: f32| {
+            assert!((position - expected[i].0).length() < 0.001);
+            assert!((tangent - expected[i].1).length() < 0.001);
+            assert!((distance - expected[i].2).abs() < 0.001);
+            i += 1;
+            true
+        },
+    });
+    assert_eq!(i, expected.len());
+}
+
 #[test]
 fn walk_starting_after() {
     // With a starting distance that is greater than the path, the
@@ -290,3 +753,49 @@ fn walk_starting_after() {
     walker.line_to(point(5.0, 0.0));
     walker.build();
 }
+
+#[test]
+fn sample_square_evenly() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(6.0, 0.0));
+    builder.line_to(point(6.0, 6.0));
+    builder.line_to(point(0.0, 6.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut samples = Vec::new();
+    sample_evenly(path.as_slice(), 5, 0.01, &mut |p, v| samples.push((p, v)));
+    assert_eq!(samples, vec![
+        (point(0.0, 0.0), vector(1.0, 0.0)),
+        (point(6.0, 0.0), vector(1.0, 0.0)),
+        (point(6.0, 6.0), vector(0.0, 1.0)),
+        (point(0.0, 6.0), vector(-1.0, 0.0)),
+        (point(0.0, 0.0), vector(0.0, -1.0)),
+    ]);
+
+    let parts = split_into_equal_arc_lengths(path.as_slice(), 3, 0.01);
+    assert_eq!(parts.len(), 3);
+    for part in &parts {
+        let length = flattened_length(part.path_iter().flattened(0.01));
+        assert!((length - 8.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn split_curve_evenly() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(5.0, 10.0), point(10.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    let path = builder.build();
+
+    let tolerance = 0.001;
+    let length = flattened_length(path.path_iter().flattened(tolerance));
+    let parts = split_into_equal_arc_lengths(path.as_slice(), 4, tolerance);
+    assert_eq!(parts.len(), 4);
+    for part in &parts {
+        let part_length = flattened_length(part.path_iter().flattened(tolerance));
+        assert!((part_length - length / 4.0).abs() < 0.01);
+    }
+}
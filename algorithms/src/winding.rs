@@ -0,0 +1,224 @@
+//! Winding number of a path at a given point.
+//!
+//! The winding number counts how many times the path goes around a point. Fill rules are
+//! defined in terms of it (the non-zero rule fills the points with a non-zero winding number
+//! and the even-odd rule the points with an odd one), and it also tells how deep a point is
+//! in a set of nested rings, which boolean hit testing can't.
+//!
+//! Sub-paths are implicitly closed, as they are when filling. Curves and arcs are not
+//! flattened: the crossings between the curves and a horizontal ray are computed on the
+//! curves themselves, so the result doesn't depend on a tolerance. The winding number of
+//! a point that is exactly on the path is not specified.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::winding_number;
+//! use lyon_algorithms::path::default::Path;
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! builder.move_to(point(2.0, 2.0));
+//! builder.line_to(point(8.0, 2.0));
+//! builder.line_to(point(8.0, 8.0));
+//! builder.line_to(point(2.0, 8.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! assert_eq!(winding_number(point(1.0, 5.0), path.iter()), 1);
+//! assert_eq!(winding_number(point(5.0, 5.0), path.iter()), 2);
+//! assert_eq!(winding_number(point(20.0, 5.0), path.iter()), 0);
+//! ```
+
+use path::PathEvent;
+use math::{Point, point};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+
+use std::ops::Range;
+
+/// Computes the winding number of a path at a given point.
+///
+/// With the y axis pointing down, sub-paths going clockwise on screen contribute positively
+/// and sub-paths going counter-clockwise contribute negatively.
+pub fn winding_number<Iter>(p: Point, path: Iter) -> i32
+where
+    Iter: Iterator<Item=PathEvent>,
+{
+    let mut counter = WindingCounter { point: p, winding: 0 };
+
+    let mut prev = point(0.0, 0.0);
+    let mut first = point(0.0, 0.0);
+
+    for evt in path {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                counter.line(prev, first);
+                prev = to;
+                first = to;
+            }
+            PathEvent::LineTo(to) => {
+                counter.line(prev, to);
+                prev = to;
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                let curve = QuadraticBezierSegment { from: prev, ctrl, to }.to_cubic();
+                counter.cubic(&curve);
+                prev = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                counter.cubic(&CubicBezierSegment { from: prev, ctrl1, ctrl2, to });
+                prev = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let start_angle = (prev - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                prev = counter.arc(&arc, prev);
+            }
+            PathEvent::Close => {
+                counter.line(prev, first);
+                prev = first;
+            }
+        }
+    }
+    counter.line(prev, first);
+
+    counter.winding
+}
+
+// Counts the signed crossings between the path and a horizontal ray going from the point
+// towards the positive x axis.
+//
+// The curves are split into parts that are monotonic on both axes. A part crosses the ray
+// if exactly one of its end points is strictly below the point, which makes sure that a
+// crossing at the end point shared by two parts is counted once.
+struct WindingCounter {
+    point: Point,
+    winding: i32,
+}
+
+impl WindingCounter {
+    fn line(&mut self, from: Point, to: Point) {
+        let p = self.point;
+        if (from.y > p.y) == (to.y > p.y) {
+            return;
+        }
+
+        let x = from.x + (p.y - from.y) * (to.x - from.x) / (to.y - from.y);
+        if x > p.x {
+            self.add_crossing(from, to);
+        }
+    }
+
+    fn cubic(&mut self, curve: &CubicBezierSegment<f32>) {
+        // Sampling at the same t gives the same point, so consecutive parts are connected.
+        curve.for_each_monotonic_range(|range| {
+            let from = if range.start == 0.0 { curve.from } else { curve.sample(range.start) };
+            let to = if range.end == 1.0 { curve.to } else { curve.sample(range.end) };
+            self.monotonic_curve(from, to, range, &|t| curve.sample(t));
+        });
+    }
+
+    // Returns the end point of the arc.
+    fn arc(&mut self, arc: &Arc<f32>, from: Point) -> Point {
+        let end = arc.sample(1.0);
+        arc.for_each_monotonic_range(|range| {
+            let p0 = if range.start == 0.0 { from } else { arc.sample(range.start) };
+            let p1 = if range.end == 1.0 { end } else { arc.sample(range.end) };
+            self.monotonic_curve(p0, p1, range, &|t| arc.sample(t));
+        });
+
+        end
+    }
+
+    fn monotonic_curve(&mut self, from: Point, to: Point, t_range: Range<f32>, sample: &dyn Fn(f32) -> Point) {
+        let p = self.point;
+        let ascending = to.y > p.y;
+        if (from.y > p.y) == ascending {
+            return;
+        }
+
+        if p.x >= from.x.max(to.x) {
+            return;
+        }
+
+        if p.x > from.x.min(to.x) {
+            // Find where the curve crosses the ray by bisection, which converges to the
+            // precision of f32 in 24 steps.
+            let mut t0 = t_range.start;
+            let mut t1 = t_range.end;
+            for _ in 0..24 {
+                let t = (t0 + t1) * 0.5;
+                if (sample(t).y > p.y) == ascending {
+                    t1 = t;
+                } else {
+                    t0 = t;
+                }
+            }
+
+            if sample((t0 + t1) * 0.5).x <= p.x {
+                return;
+            }
+        }
+
+        self.add_crossing(from, to);
+    }
+
+    fn add_crossing(&mut self, from: Point, to: Point) {
+        self.winding += if to.y > from.y { 1 } else { -1 };
+    }
+}
+
+#[test]
+fn winding_number_curves() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::{Angle, vector};
+
+    // A quadratic bézier curve peaking at (10, 10), closed by a straight line.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 20.0), point(20.0, 0.0));
+    let path = builder.build();
+
+    assert_eq!(winding_number(point(10.0, 9.99), path.iter()), -1);
+    assert_eq!(winding_number(point(10.0, 10.01), path.iter()), 0);
+    assert_eq!(winding_number(point(0.1, 0.1), path.iter()), -1);
+    assert_eq!(winding_number(point(0.1, 0.3), path.iter()), 0);
+    assert_eq!(winding_number(point(10.0, -1.0), path.iter()), 0);
+
+    // The same shape with a cubic bézier curve, in the opposite direction.
+    let mut builder = Path::builder();
+    builder.move_to(point(20.0, 0.0));
+    builder.cubic_bezier_to(point(20.0, 10.0), point(0.0, 10.0), point(0.0, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    assert_eq!(winding_number(point(10.0, 7.49), path.iter()), 1);
+    assert_eq!(winding_number(point(10.0, 7.51), path.iter()), 0);
+
+    // A full rotated ellipse containing a circle going in the same direction.
+    let rotation = Angle::radians(::std::f32::consts::FRAC_PI_4);
+    let axis = vector(1.0, 1.0) / 2.0f32.sqrt();
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0) + axis * 10.0);
+    builder.arc(point(0.0, 0.0), vector(10.0, 5.0), Angle::two_pi(), rotation);
+    builder.close();
+    builder.move_to(point(2.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(2.0, 2.0), Angle::two_pi(), Angle::zero());
+    builder.close();
+    let path = builder.build();
+
+    assert_eq!(winding_number(point(0.0, 0.0) + axis * 9.99, path.iter()), 1);
+    assert_eq!(winding_number(point(0.0, 0.0) - axis * 9.99, path.iter()), 1);
+    assert_eq!(winding_number(point(0.0, 0.0) + axis * 10.01, path.iter()), 0);
+    assert_eq!(winding_number(point(0.0, 0.0) + vector(1.0, -1.0) * 0.99 * 5.0 / 2.0f32.sqrt(), path.iter()), 1);
+    assert_eq!(winding_number(point(0.0, 0.0) + vector(1.0, -1.0) * 1.01 * 5.0 / 2.0f32.sqrt(), path.iter()), 0);
+    assert_eq!(winding_number(point(0.0, 1.99), path.iter()), 2);
+    assert_eq!(winding_number(point(-1.0, 0.0), path.iter()), 2);
+}
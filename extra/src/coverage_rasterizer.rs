@@ -0,0 +1,237 @@
+use path::math::Point;
+use path::iterator::PathIterator;
+use path_rasterizer::{FillRule, collect_edges};
+use image::MutableImageSlice;
+
+/// A scanline rasterizer that fills a path into an 8-bit mask with exact area coverage.
+///
+/// This is an alternative to tessellation for targets that consume coverage masks
+/// rather than triangles, for example CPU compositors.
+///
+/// Pixel (x, y) covers the area between (x, y) and (x + 1, y + 1). The signed area
+/// covered by each edge is accumulated in each pixel, so the coverage is exact unless
+/// several edges overlap in the same pixel, in which case the accumulated winding is
+/// clamped (`NonZero`) or folded (`EvenOdd`). Sub-paths are implicitly closed.
+pub fn rasterize_path_coverage<Iter: PathIterator>(
+    path: Iter,
+    fill_rule: FillRule,
+    tolerance: f32,
+    target: &mut MutableImageSlice<u8>,
+) {
+    let width = target.width;
+    let height = target.height;
+    // Two extra cells per row for the contributions on the right side of the image.
+    let stride = width + 2;
+    let mut accumulator = vec![0.0f32; stride * height];
+
+    for (from, to) in collect_edges(path, tolerance) {
+        // Split the edges at the left and right sides of the image, so that the parts
+        // that are outside can be projected on the sides.
+        let mut points = [from, from, from, to];
+        let mut count = 1;
+        let mut ts = [0.0; 2];
+        let mut num_ts = 0;
+        for &x in &[0.0, width as f32] {
+            if (from.x - x) * (to.x - x) < 0.0 {
+                ts[num_ts] = (x - from.x) / (to.x - from.x);
+                num_ts += 1;
+            }
+        }
+        if num_ts == 2 && ts[0] > ts[1] {
+            ts.swap(0, 1);
+        }
+        for &t in &ts[..num_ts] {
+            points[count] = from.lerp(to, t);
+            count += 1;
+        }
+        points[count] = to;
+
+        for i in 0..count {
+            let a = Point::new(points[i].x.max(0.0).min(width as f32), points[i].y);
+            let b = Point::new(points[i + 1].x.max(0.0).min(width as f32), points[i + 1].y);
+            accumulate_line(&mut accumulator, stride, height, a, b);
+        }
+    }
+
+    for row in 0..height {
+        let mut acc = 0.0;
+        for x in 0..width {
+            acc += accumulator[row * stride + x];
+            let coverage = match fill_rule {
+                FillRule::NonZero => acc.abs().min(1.0),
+                FillRule::EvenOdd => {
+                    let a = acc.abs() % 2.0;
+                    if a > 1.0 { 2.0 - a } else { a }
+                }
+            };
+            let offset = target.pixel_offset(x, row);
+            target.pixels[offset] = (coverage * 255.0).round() as u8;
+        }
+    }
+}
+
+// Adds the signed area between a line and the right side of each row to the cells
+// that it crosses, such that summing the cells from left to right gives the coverage.
+//
+// The x coordinates must be between zero and the width of the image.
+fn accumulate_line(accumulator: &mut [f32], stride: usize, height: usize, from: Point, to: Point) {
+    if from.y == to.y {
+        return;
+    }
+
+    let (dir, p0, p1) = if from.y < to.y { (1.0, from, to) } else { (-1.0, to, from) };
+    let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+
+    let y_start = p0.y.max(0.0);
+    let y_end = p1.y.min(height as f32);
+    if y_start >= y_end {
+        return;
+    }
+
+    let mut x = p0.x + (y_start - p0.y) * dxdy;
+    let mut y = y_start;
+    while y < y_end {
+        let row = y.floor();
+        let next_y = (row + 1.0).min(y_end);
+        let dy = next_y - y;
+        let next_x = x + dxdy * dy;
+        let d = dy * dir;
+
+        let line = row as usize * stride;
+        let (x0, x1) = if x < next_x { (x, next_x) } else { (next_x, x) };
+        let x0_floor = x0.floor();
+        let x0i = x0_floor as usize;
+        let x1_ceil = x1.ceil();
+        let x1i = x1_ceil as usize;
+
+        if x1i <= x0i + 1 {
+            // The line stays in one cell.
+            let xm = 0.5 * (x + next_x) - x0_floor;
+            accumulator[line + x0i] += d - d * xm;
+            accumulator[line + x0i + 1] += d * xm;
+        } else {
+            let s = 1.0 / (x1 - x0);
+            let x0f = x0 - x0_floor;
+            let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+            let x1f = x1 - x1_ceil + 1.0;
+            let am = 0.5 * s * x1f * x1f;
+            accumulator[line + x0i] += d * a0;
+            if x1i == x0i + 2 {
+                accumulator[line + x0i + 1] += d * (1.0 - a0 - am);
+            } else {
+                let a1 = s * (1.5 - x0f);
+                accumulator[line + x0i + 1] += d * (a1 - a0);
+                for xi in x0i + 2..x1i - 1 {
+                    accumulator[line + xi] += d * s;
+                }
+                let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                accumulator[line + x1i - 1] += d * (1.0 - a2 - am);
+            }
+            accumulator[line + x1i] += d * am;
+        }
+
+        x = next_x;
+        y = next_y;
+    }
+}
+
+#[cfg(test)]
+use path::default::Path;
+#[cfg(test)]
+use path::builder::FlatPathBuilder;
+#[cfg(test)]
+use path::math::point;
+
+#[test]
+fn coverage_rectangles() {
+    let mut builder = Path::builder();
+    builder.move_to(point(1.5, 1.0));
+    builder.line_to(point(3.5, 1.0));
+    builder.line_to(point(3.5, 3.0));
+    builder.line_to(point(1.5, 3.0));
+    builder.close();
+    // Overlaps the first rectangle and goes past the sides of the image.
+    builder.move_to(point(-2.0, 2.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.line_to(point(8.0, 4.0));
+    builder.line_to(point(-2.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut even_odd = vec![0u8; 5 * 5];
+    let mut non_zero = vec![0u8; 5 * 5];
+    rasterize_path_coverage(
+        path.path_iter(), FillRule::EvenOdd, 0.01,
+        &mut MutableImageSlice::new(5, 5, &mut even_odd),
+    );
+    rasterize_path_coverage(
+        path.path_iter(), FillRule::NonZero, 0.01,
+        &mut MutableImageSlice::new(5, 5, &mut non_zero),
+    );
+
+    let at = |x: usize, y: usize| x + y * 5;
+
+    assert_eq!(non_zero[at(0, 0)], 0);
+    assert_eq!(non_zero[at(0, 1)], 0);
+    assert_eq!(non_zero[at(1, 1)], 128);
+    assert_eq!(non_zero[at(2, 1)], 255);
+    assert_eq!(non_zero[at(3, 1)], 128);
+    assert_eq!(non_zero[at(4, 1)], 0);
+    for x in 0..5 {
+        assert_eq!(non_zero[at(x, 2)], 255);
+        assert_eq!(non_zero[at(x, 3)], 255);
+        assert_eq!(non_zero[at(x, 4)], 0);
+    }
+
+    assert_eq!(even_odd[at(1, 2)], 128);
+    assert_eq!(even_odd[at(2, 2)], 0);
+    assert_eq!(even_odd[at(0, 2)], 255);
+    assert_eq!(even_odd[at(2, 1)], 255);
+}
+
+#[test]
+fn coverage_triangle() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    builder.line_to(point(0.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut mask = vec![0u8; 4];
+    rasterize_path_coverage(
+        path.path_iter(), FillRule::NonZero, 0.01,
+        &mut MutableImageSlice::new(2, 2, &mut mask),
+    );
+
+    assert_eq!(mask, vec![255, 128, 128, 0]);
+}
+
+#[test]
+fn coverage_matches_reference_rasterizer() {
+    use path_rasterizer::{rasterize_path, count_different_pixels};
+
+    let mut builder = Path::builder();
+    let n = 64;
+    for i in 0..n {
+        let angle = i as f32 * 2.0 * ::std::f32::consts::PI / n as f32;
+        let p = point(16.0 + 12.3 * angle.cos(), 15.7 + 12.3 * angle.sin());
+        if i == 0 { builder.move_to(p); } else { builder.line_to(p); }
+    }
+    builder.close();
+    let path = builder.build();
+
+    let mut reference = vec![0u8; 32 * 32];
+    let mut mask = vec![0u8; 32 * 32];
+    rasterize_path(
+        path.path_iter(), FillRule::NonZero, 0.01,
+        &mut MutableImageSlice::new(32, 32, &mut reference),
+    );
+    rasterize_path_coverage(
+        path.path_iter(), FillRule::NonZero, 0.01,
+        &mut MutableImageSlice::new(32, 32, &mut mask),
+    );
+
+    // The reference rasterizer only has 16 samples per pixel vertically.
+    assert_eq!(count_different_pixels(&reference, &mask, 10), 0);
+}
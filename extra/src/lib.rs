@@ -6,11 +6,19 @@
 
 extern crate lyon_path as path;
 extern crate lyon_svg as svg;
+#[cfg(feature = "debugger")]
+extern crate lyon_tessellation as tessellation;
 
 pub use path::geom::math;
 pub use path::geom::euclid;
 
 pub mod rust_logo;
 pub mod triangle_rasterizer;
+pub mod path_rasterizer;
+pub mod coverage_rasterizer;
 pub mod debugging;
 pub mod image;
+pub mod rand_path;
+pub mod lottie;
+#[cfg(feature = "debugger")]
+pub mod sweep_trace;
@@ -0,0 +1,413 @@
+//! Build paths from the shape primitives of Lottie (Bodymovin) animations.
+//!
+//! The parameters of the shapes are expressed in the units used by Lottie files: rotations
+//! are in degrees and the roundness of stars and polygons is in percents. The generated paths
+//! have the same start point and winding as the ones generated by Lottie players, so that
+//! trim paths and other modifiers behave consistently.
+
+use path::math::{Point, Vector, Size, point};
+use path::default::Path;
+use path::builder::{FlatPathBuilder, PathBuilder};
+
+use std::f32::consts::PI;
+
+// Length of the control point handles of a quarter of a circle of radius one,
+// as used by Lottie players.
+const ROUND_CORNER: f32 = 0.5519;
+
+/// The winding of a shape (the `d` property of Lottie shapes).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Clockwise in a y-down coordinate system.
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Direction {
+    /// Converts the value of the `d` property, where `3` means counter-clockwise.
+    pub fn from_lottie(d: u32) -> Self {
+        if d == 3 { Direction::CounterClockwise } else { Direction::Clockwise }
+    }
+}
+
+/// A rectangle (`rc` shape).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rectangle {
+    /// The center of the rectangle.
+    pub position: Point,
+    pub size: Size,
+    /// The radius of the rounded corners.
+    pub roundness: f32,
+    pub direction: Direction,
+}
+
+/// An ellipse (`el` shape).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ellipse {
+    /// The center of the ellipse.
+    pub position: Point,
+    pub size: Size,
+    pub direction: Direction,
+}
+
+/// The type of a `PolyStar` (the `sy` property).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StarType {
+    Star,
+    Polygon,
+}
+
+/// A star or a regular polygon (`sr` shape).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PolyStar {
+    pub star_type: StarType,
+    /// The center of the shape.
+    pub position: Point,
+    /// The number of points of the star or of vertices of the polygon.
+    ///
+    /// The fractional part is ignored.
+    pub points: f32,
+    /// Rotation in degrees. With no rotation the first point is at the top.
+    pub rotation: f32,
+    pub outer_radius: f32,
+    /// Roundness of the outer points in percents.
+    pub outer_roundness: f32,
+    /// Only used by stars.
+    pub inner_radius: f32,
+    /// Roundness of the inner points in percents. Only used by stars.
+    pub inner_roundness: f32,
+    pub direction: Direction,
+}
+
+/// A bézier shape (`sh` shape).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BezierShape<'l> {
+    pub vertices: &'l [Point],
+    /// The incoming control points, relative to the vertices.
+    pub in_tangents: &'l [Vector],
+    /// The outgoing control points, relative to the vertices.
+    pub out_tangents: &'l [Vector],
+    pub closed: bool,
+}
+
+/// Any of the supported shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shape<'l> {
+    Rectangle(Rectangle),
+    Ellipse(Ellipse),
+    PolyStar(PolyStar),
+    Bezier(BezierShape<'l>),
+}
+
+// A vertex with absolute control points, similar to the representation used by Lottie.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Vertex {
+    position: Point,
+    in_ctrl: Point,
+    out_ctrl: Point,
+}
+
+fn vertex(position: Point, in_ctrl: Point, out_ctrl: Point) -> Vertex {
+    Vertex { position, in_ctrl, out_ctrl }
+}
+
+fn corner(position: Point) -> Vertex {
+    Vertex { position, in_ctrl: position, out_ctrl: position }
+}
+
+fn is_line(from: &Vertex, to: &Vertex) -> bool {
+    from.out_ctrl == from.position && to.in_ctrl == to.position
+}
+
+fn build_segment<Builder: PathBuilder>(from: &Vertex, to: &Vertex, builder: &mut Builder) {
+    if is_line(from, to) {
+        builder.line_to(to.position);
+    } else {
+        builder.cubic_bezier_to(from.out_ctrl, to.in_ctrl, to.position);
+    }
+}
+
+// Adds a sub-path going through the vertices. Segments without control points are added
+// as lines, and a closing line is left implicit.
+fn build_vertices<Builder: PathBuilder>(vertices: &[Vertex], closed: bool, builder: &mut Builder) {
+    if vertices.is_empty() {
+        return;
+    }
+
+    builder.move_to(vertices[0].position);
+    for i in 1..vertices.len() {
+        build_segment(&vertices[i - 1], &vertices[i], builder);
+    }
+
+    if closed {
+        let last = &vertices[vertices.len() - 1];
+        if !is_line(last, &vertices[0]) {
+            build_segment(last, &vertices[0], builder);
+        }
+        builder.close();
+    }
+}
+
+// Adds a closed sub-path, reversing the order of the vertices after the first one
+// for counter-clockwise shapes.
+fn build_loop<Builder: PathBuilder>(mut vertices: Vec<Vertex>, direction: Direction, builder: &mut Builder) {
+    if direction == Direction::CounterClockwise && vertices.len() > 1 {
+        vertices[1..].reverse();
+        for v in &mut vertices {
+            ::std::mem::swap(&mut v.in_ctrl, &mut v.out_ctrl);
+        }
+    }
+
+    build_vertices(&vertices, true, builder);
+}
+
+impl Rectangle {
+    /// Adds the rectangle to a path.
+    ///
+    /// Clockwise rectangles start at the top of the right edge and go down.
+    pub fn build<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        let w = self.size.width * 0.5;
+        let h = self.size.height * 0.5;
+        let (x, y) = (self.position.x, self.position.y);
+        let (left, right, top, bottom) = (x - w, x + w, y - h, y + h);
+        let r = self.roundness.max(0.0).min(w).min(h);
+
+        let vertices = if r == 0.0 {
+            vec![
+                corner(point(right, top)),
+                corner(point(right, bottom)),
+                corner(point(left, bottom)),
+                corner(point(left, top)),
+            ]
+        } else {
+            let k = r * ROUND_CORNER;
+            vec![
+                vertex(point(right, top + r), point(right, top + r - k), point(right, top + r)),
+                vertex(point(right, bottom - r), point(right, bottom - r), point(right, bottom - r + k)),
+                vertex(point(right - r, bottom), point(right - r + k, bottom), point(right - r, bottom)),
+                vertex(point(left + r, bottom), point(left + r, bottom), point(left + r - k, bottom)),
+                vertex(point(left, bottom - r), point(left, bottom - r + k), point(left, bottom - r)),
+                vertex(point(left, top + r), point(left, top + r), point(left, top + r - k)),
+                vertex(point(left + r, top), point(left + r - k, top), point(left + r, top)),
+                vertex(point(right - r, top), point(right - r, top), point(right - r + k, top)),
+            ]
+        };
+
+        build_loop(vertices, self.direction, builder);
+    }
+}
+
+impl Ellipse {
+    /// Adds the ellipse to a path.
+    ///
+    /// The ellipse starts at its top.
+    pub fn build<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        let rx = self.size.width * 0.5;
+        let ry = self.size.height * 0.5;
+        let kx = rx * ROUND_CORNER;
+        let ky = ry * ROUND_CORNER;
+        let (x, y) = (self.position.x, self.position.y);
+
+        let vertices = vec![
+            vertex(point(x, y - ry), point(x - kx, y - ry), point(x + kx, y - ry)),
+            vertex(point(x + rx, y), point(x + rx, y - ky), point(x + rx, y + ky)),
+            vertex(point(x, y + ry), point(x + kx, y + ry), point(x - kx, y + ry)),
+            vertex(point(x - rx, y), point(x - rx, y + ky), point(x - rx, y - ky)),
+        ];
+
+        build_loop(vertices, self.direction, builder);
+    }
+}
+
+impl PolyStar {
+    /// Adds the star or polygon to a path.
+    pub fn build<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        let points = self.points.floor().max(0.0) as usize;
+        if points == 0 {
+            return;
+        }
+
+        // Stars alternate between outer and inner points.
+        let num_vertices = match self.star_type {
+            StarType::Star => points * 2,
+            StarType::Polygon => points,
+        };
+        // The length of the handles at 100% roundness, relative to the radius.
+        let handle_scale = 2.0 * PI / (points as f32 * 4.0);
+
+        let step = 2.0 * PI / num_vertices as f32;
+        let mut angle = -PI * 0.5 + self.rotation.to_radians();
+        let mut vertices = Vec::with_capacity(num_vertices);
+        for i in 0..num_vertices {
+            let outer = self.star_type == StarType::Polygon || i % 2 == 0;
+            let (radius, roundness) = if outer {
+                (self.outer_radius, self.outer_roundness)
+            } else {
+                (self.inner_radius, self.inner_roundness)
+            };
+
+            let (sin, cos) = angle.sin_cos();
+            let v = Vector::new(cos, sin) * radius;
+            // The handles are perpendicular to the direction from the center.
+            let tangent = if radius == 0.0 { Vector::new(0.0, 0.0) } else { Vector::new(-sin, cos) };
+            let handle = tangent * radius * handle_scale * roundness * 0.01;
+            let position = self.position + v;
+            vertices.push(vertex(position, position - handle, position + handle));
+
+            angle += step;
+        }
+
+        build_loop(vertices, self.direction, builder);
+    }
+}
+
+impl<'l> BezierShape<'l> {
+    /// Adds the shape to a path.
+    ///
+    /// Missing tangents are considered to be zero.
+    pub fn build<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        let zero = Vector::new(0.0, 0.0);
+        let vertices: Vec<Vertex> = self.vertices.iter().enumerate().map(|(i, &position)| {
+            let in_tangent = self.in_tangents.get(i).cloned().unwrap_or(zero);
+            let out_tangent = self.out_tangents.get(i).cloned().unwrap_or(zero);
+            vertex(position, position + in_tangent, position + out_tangent)
+        }).collect();
+
+        build_vertices(&vertices, self.closed, builder);
+    }
+}
+
+impl<'l> Shape<'l> {
+    /// Adds the shape to a path.
+    pub fn build<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        match *self {
+            Shape::Rectangle(ref shape) => shape.build(builder),
+            Shape::Ellipse(ref shape) => shape.build(builder),
+            Shape::PolyStar(ref shape) => shape.build(builder),
+            Shape::Bezier(ref shape) => shape.build(builder),
+        }
+    }
+}
+
+/// Builds a path containing a sub-path for each shape of a group.
+pub fn group_to_path(shapes: &[Shape]) -> Path {
+    let mut builder = Path::builder();
+    for shape in shapes {
+        shape.build(&mut builder);
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+fn assert_approx_eq(a: Point, b: Point) {
+    assert!((a - b).length() < 0.001, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn lottie_rectangle() {
+    use path::default::Verb;
+    use path::math::size;
+
+    let rect = Rectangle {
+        position: point(10.0, 10.0),
+        size: size(20.0, 10.0),
+        roundness: 0.0,
+        direction: Direction::Clockwise,
+    };
+    let path = group_to_path(&[Shape::Rectangle(rect)]);
+    assert_eq!(path.points(), &[point(20.0, 5.0), point(20.0, 15.0), point(0.0, 15.0), point(0.0, 5.0)]);
+
+    let ccw = Rectangle { direction: Direction::CounterClockwise, .. rect };
+    let path = group_to_path(&[Shape::Rectangle(ccw)]);
+    assert_eq!(path.points(), &[point(20.0, 5.0), point(0.0, 5.0), point(0.0, 15.0), point(20.0, 15.0)]);
+
+    // The roundness is clamped to half of the smallest side.
+    let rounded = Rectangle { roundness: 100.0, .. rect };
+    let path = group_to_path(&[Shape::Rectangle(rounded)]);
+    assert_eq!(
+        path.verbs(),
+        &[
+            Verb::MoveTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::LineTo, Verb::CubicTo,
+            Verb::Close,
+        ]
+    );
+    assert_eq!(path.points()[0], point(20.0, 10.0));
+}
+
+#[test]
+fn lottie_poly_star() {
+    let star = PolyStar {
+        star_type: StarType::Star,
+        position: point(0.0, 0.0),
+        points: 5.0,
+        rotation: 0.0,
+        outer_radius: 10.0,
+        outer_roundness: 0.0,
+        inner_radius: 5.0,
+        inner_roundness: 0.0,
+        direction: Direction::Clockwise,
+    };
+    let path = group_to_path(&[Shape::PolyStar(star)]);
+    // Ten vertices.
+    assert_eq!(path.points().len(), 10);
+    assert_approx_eq(path.points()[0], point(0.0, -10.0));
+    // The second vertex is an inner one, on the right in a y-down coordinate system.
+    let angle = -PI * 0.5 + PI / 5.0;
+    assert_approx_eq(path.points()[1], point(angle.cos() * 5.0, angle.sin() * 5.0));
+    assert!(path.points()[1].x > 0.0);
+
+    let polygon = PolyStar {
+        star_type: StarType::Polygon,
+        points: 4.0,
+        rotation: 45.0,
+        outer_roundness: 50.0,
+        .. star
+    };
+    let path = group_to_path(&[Shape::PolyStar(polygon)]);
+    // Four cubic bézier curves.
+    assert_eq!(path.points().len(), 1 + 4 * 3);
+    let d = 10.0 * (0.5f32).sqrt();
+    assert_approx_eq(path.points()[0], point(d, -d));
+    assert_approx_eq(path.points()[3], point(d, d));
+    // The handles are perpendicular to the radius.
+    let handle = path.points()[1] - path.points()[0];
+    assert!(handle.dot(path.points()[0].to_vector()).abs() < 0.001);
+    assert!((handle.length() - 10.0 * 2.0 * PI / 16.0 * 0.5).abs() < 0.001);
+}
+
+#[test]
+fn lottie_bezier_group() {
+    use path::default::Verb;
+
+    let vertices = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)];
+    let in_tangents = [Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), Vector::new(0.0, -5.0)];
+    let out_tangents = [Vector::new(0.0, 0.0), Vector::new(5.0, 0.0), Vector::new(0.0, 0.0)];
+    let shape = BezierShape {
+        vertices: &vertices,
+        in_tangents: &in_tangents,
+        out_tangents: &out_tangents,
+        closed: true,
+    };
+    let ellipse = Ellipse {
+        position: point(0.0, 0.0),
+        size: Size::new(2.0, 2.0),
+        direction: Direction::Clockwise,
+    };
+
+    let path = group_to_path(&[Shape::Bezier(shape), Shape::Ellipse(ellipse)]);
+    assert_eq!(
+        path.verbs(),
+        &[
+            Verb::MoveTo, Verb::LineTo, Verb::CubicTo, Verb::Close,
+            Verb::MoveTo, Verb::CubicTo, Verb::CubicTo, Verb::CubicTo, Verb::CubicTo, Verb::Close,
+        ]
+    );
+    assert_eq!(&path.points()[2..5], &[point(15.0, 0.0), point(10.0, 5.0), point(10.0, 10.0)]);
+    assert_eq!(path.points()[5], point(0.0, -1.0));
+    assert_eq!(path.points()[8], point(1.0, 0.0));
+}
@@ -0,0 +1,165 @@
+use path::math::Point;
+use path::iterator::PathIterator;
+use path::FlattenedEvent;
+use image::MutableImageSlice;
+
+pub use path::FillRule;
+
+use std::cmp::Ordering;
+
+/// Number of sub-scanlines per row of pixels.
+const SAMPLES_PER_PIXEL: u32 = 16;
+
+/// A simple and slow scanline rasterizer that fills a path into an 8-bit coverage mask.
+///
+/// Intended as a ground truth to compare the output of the tessellators against
+/// (see also `triangle_rasterizer`), not for production rendering.
+///
+/// Pixel (x, y) covers the area between (x, y) and (x + 1, y + 1). The coverage
+/// is computed exactly along the x axis and with 16 samples along the y axis.
+/// Sub-paths are implicitly closed.
+pub fn rasterize_path<Iter: PathIterator>(
+    path: Iter,
+    fill_rule: FillRule,
+    tolerance: f32,
+    target: &mut MutableImageSlice<u8>,
+) {
+    let edges = collect_edges(path, tolerance);
+
+    let mut accumulator = vec![0.0f32; target.width];
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+    for row in 0..target.height {
+        for v in accumulator.iter_mut() {
+            *v = 0.0;
+        }
+
+        for sample in 0..SAMPLES_PER_PIXEL {
+            let y = row as f32 + (sample as f32 + 0.5) / SAMPLES_PER_PIXEL as f32;
+
+            crossings.clear();
+            for &(a, b) in &edges {
+                let (upper, lower, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+                if y < upper.y || y >= lower.y {
+                    continue;
+                }
+                let x = upper.x + (y - upper.y) * (lower.x - upper.x) / (lower.y - upper.y);
+                crossings.push((x, winding));
+            }
+
+            crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+            let mut winding = 0;
+            for i in 0..crossings.len() {
+                winding += crossings[i].1;
+                if fill_rule.is_in(winding) && i + 1 < crossings.len() {
+                    add_span(&mut accumulator, crossings[i].0, crossings[i + 1].0);
+                }
+            }
+        }
+
+        for (x, value) in accumulator.iter().enumerate() {
+            let coverage = value / SAMPLES_PER_PIXEL as f32;
+            let offset = target.pixel_offset(x, row);
+            target.pixels[offset] = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// Returns the number of pixels that differ by more than a given threshold between two masks.
+pub fn count_different_pixels(a: &[u8], b: &[u8], threshold: u8) -> usize {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b.iter()).filter(|&(a, b)| {
+        (*a as i32 - *b as i32).abs() > threshold as i32
+    }).count()
+}
+
+pub(crate) fn collect_edges<Iter: PathIterator>(path: Iter, tolerance: f32) -> Vec<(Point, Point)> {
+    let mut edges = Vec::new();
+    let mut first = Point::new(0.0, 0.0);
+    let mut current = first;
+    for evt in path.flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                edges.push((current, first));
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::LineTo(to) => {
+                edges.push((current, to));
+                current = to;
+            }
+            FlattenedEvent::Close => {
+                edges.push((current, first));
+                current = first;
+            }
+        }
+    }
+    edges.push((current, first));
+
+    edges.retain(|&(a, b)| a.y != b.y);
+
+    edges
+}
+
+fn add_span(accumulator: &mut [f32], x0: f32, x1: f32) {
+    let x0 = x0.max(0.0);
+    let x1 = x1.min(accumulator.len() as f32);
+    if x0 >= x1 {
+        return;
+    }
+
+    let start = x0.floor() as usize;
+    let end = x1.ceil() as usize;
+    for (px, value) in accumulator.iter_mut().enumerate().take(end).skip(start) {
+        let left = x0.max(px as f32);
+        let right = x1.min(px as f32 + 1.0);
+        *value += right - left;
+    }
+}
+
+#[test]
+fn rasterize_rectangles() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+    use path::math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(5.0, 1.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(1.0, 5.0));
+    builder.close();
+    // Overlaps the right half of the first rectangle, in the same direction.
+    builder.move_to(point(3.0, 1.0));
+    builder.line_to(point(7.5, 1.0));
+    builder.line_to(point(7.5, 5.0));
+    builder.line_to(point(3.0, 5.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut even_odd = vec![0u8; 8 * 8];
+    let mut non_zero = vec![0u8; 8 * 8];
+    rasterize_path(
+        path.path_iter(), FillRule::EvenOdd, 0.01,
+        &mut MutableImageSlice::new(8, 8, &mut even_odd),
+    );
+    rasterize_path(
+        path.path_iter(), FillRule::NonZero, 0.01,
+        &mut MutableImageSlice::new(8, 8, &mut non_zero),
+    );
+
+    let at = |x: usize, y: usize| x + y * 8;
+
+    assert_eq!(even_odd[at(0, 0)], 0);
+    assert_eq!(even_odd[at(1, 2)], 255);
+    assert_eq!(even_odd[at(3, 2)], 0);
+    assert_eq!(even_odd[at(6, 2)], 255);
+    assert_eq!(even_odd[at(7, 2)], 128);
+    assert_eq!(even_odd[at(2, 5)], 0);
+
+    assert_eq!(non_zero[at(3, 2)], 255);
+    assert_eq!(non_zero[at(7, 2)], 128);
+
+    assert_eq!(count_different_pixels(&even_odd, &non_zero, 0), 8);
+}
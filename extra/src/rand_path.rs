@@ -0,0 +1,222 @@
+//! Seeded random path generation, intended for fuzzing and property testing.
+//!
+//! The same seed and options always produce the same path.
+
+use path::math::{Point, point, vector};
+use path::default::Path;
+use path::builder::PathBuilder;
+
+/// Parameters of the random path generator.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RandomPathOptions {
+    /// Maximum number of sub-paths (at least one sub-path is generated).
+    pub max_sub_paths: u32,
+    /// Maximum number of segments per sub-path (at least one segment is generated).
+    pub max_segments: u32,
+
+    /// Relative probability of generating a line segment.
+    pub line_weight: u32,
+    /// Relative probability of generating a quadratic bézier segment.
+    pub quadratic_weight: u32,
+    /// Relative probability of generating a cubic bézier segment.
+    pub cubic_weight: u32,
+
+    /// Lower bound of the generated coordinates.
+    pub min: Point,
+    /// Upper bound of the generated coordinates.
+    pub max: Point,
+    /// Snap the coordinates to integers, which makes coincident points and
+    /// intersections at vertices much more likely.
+    pub snap_to_grid: bool,
+
+    /// Probability for each segment to end at the current position.
+    pub coincident_points: f32,
+    /// Probability for each segment to be a line extending the previous segment.
+    pub collinear_edges: f32,
+    /// Probability for each segment to be followed by a tiny loop.
+    pub tiny_loops: f32,
+    /// Probability for each sub-path to be explicitly closed.
+    pub close: f32,
+}
+
+impl RandomPathOptions {
+    /// Only generate line segments.
+    pub fn lines_only() -> Self {
+        RandomPathOptions {
+            quadratic_weight: 0,
+            cubic_weight: 0,
+            tiny_loops: 0.0,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for RandomPathOptions {
+    fn default() -> Self {
+        RandomPathOptions {
+            max_sub_paths: 4,
+            max_segments: 16,
+            line_weight: 4,
+            quadratic_weight: 1,
+            cubic_weight: 1,
+            min: point(0.0, 0.0),
+            max: point(100.0, 100.0),
+            snap_to_grid: false,
+            coincident_points: 0.05,
+            collinear_edges: 0.05,
+            tiny_loops: 0.02,
+            close: 0.8,
+        }
+    }
+}
+
+/// A small and fast pseudo-random number generator (xorshift64*).
+///
+/// Not suitable for anything that needs good statistical properties, but
+/// deterministic across platforms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Rng { state: seed ^ 0x9E37_79B9_7F4A_7C15 | 1 }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 32) as u32
+    }
+
+    /// Returns a number in [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Returns a number in [0, max), or zero if max is zero.
+    pub fn below(&mut self, max: u32) -> u32 {
+        if max == 0 {
+            return 0;
+        }
+        self.next_u32() % max
+    }
+
+    /// Returns true with the provided probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+/// Generates a random path.
+pub fn random_path(seed: u64, options: &RandomPathOptions) -> Path {
+    build_random_path(Path::builder(), &mut Rng::new(seed), options)
+}
+
+/// Generates a random path using the provided builder and random number generator.
+pub fn build_random_path<Builder: PathBuilder>(
+    mut builder: Builder,
+    rng: &mut Rng,
+    options: &RandomPathOptions,
+) -> Builder::PathType {
+    let num_sub_paths = 1 + rng.below(options.max_sub_paths);
+    let total_weight = options.line_weight + options.quadratic_weight + options.cubic_weight;
+    let tiny = (options.max.x - options.min.x).abs().max((options.max.y - options.min.y).abs()) * 0.001;
+
+    for _ in 0..num_sub_paths {
+        let first = random_point(rng, options);
+        let mut previous = first;
+        let mut current = first;
+        builder.move_to(first);
+
+        let num_segments = 1 + rng.below(options.max_segments);
+        for _ in 0..num_segments {
+            let to = if rng.chance(options.coincident_points) {
+                current
+            } else if rng.chance(options.collinear_edges) && previous != current {
+                let t = 0.25 + rng.next_f32() * 2.0;
+                clamp(current + (current - previous) * t, options)
+            } else {
+                random_point(rng, options)
+            };
+
+            let mut segment_type = rng.below(total_weight);
+            if segment_type < options.line_weight || total_weight == 0 {
+                builder.line_to(to);
+            } else {
+                segment_type -= options.line_weight;
+                if segment_type < options.quadratic_weight {
+                    let ctrl = random_point(rng, options);
+                    builder.quadratic_bezier_to(ctrl, to);
+                } else {
+                    let ctrl1 = random_point(rng, options);
+                    let ctrl2 = random_point(rng, options);
+                    builder.cubic_bezier_to(ctrl1, ctrl2, to);
+                }
+            }
+
+            previous = current;
+            current = to;
+
+            if rng.chance(options.tiny_loops) {
+                builder.cubic_bezier_to(
+                    clamp(current + vector(tiny, tiny), options),
+                    clamp(current + vector(-tiny, tiny), options),
+                    current,
+                );
+            }
+        }
+
+        if rng.chance(options.close) {
+            builder.close();
+        }
+    }
+
+    builder.build()
+}
+
+fn clamp(p: Point, options: &RandomPathOptions) -> Point {
+    point(
+        p.x.max(options.min.x).min(options.max.x),
+        p.y.max(options.min.y).min(options.max.y),
+    )
+}
+
+fn random_point(rng: &mut Rng, options: &RandomPathOptions) -> Point {
+    let x = options.min.x + rng.next_f32() * (options.max.x - options.min.x);
+    let y = options.min.y + rng.next_f32() * (options.max.y - options.min.y);
+    if options.snap_to_grid {
+        return point(x.round(), y.round());
+    }
+
+    point(x, y)
+}
+
+#[test]
+fn deterministic() {
+    let options = RandomPathOptions::default();
+    for seed in 0..10 {
+        let a = random_path(seed, &options);
+        let b = random_path(seed, &options);
+        assert_eq!(a.points(), b.points());
+        for p in a.points() {
+            assert!(p.x >= options.min.x && p.x <= options.max.x);
+            assert!(p.y >= options.min.y && p.y <= options.max.y);
+        }
+    }
+}
+
+#[test]
+fn lines_only() {
+    for seed in 0..10 {
+        for evt in random_path(seed, &RandomPathOptions::lines_only()).path_iter() {
+            match evt {
+                ::path::PathEvent::QuadraticTo(..) | ::path::PathEvent::CubicTo(..) => { panic!(); }
+                _ => {}
+            }
+        }
+    }
+}
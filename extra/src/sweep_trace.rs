@@ -0,0 +1,228 @@
+//! Visual dumps of the fill tessellator's sweep line, to help diagnosing failing paths.
+//!
+//! Requires the `debugger` feature.
+//!
+//! ```ignore
+//! let (result, trace) = record_fill_trace(path.as_slice(), &FillOptions::default());
+//! if result.is_err() {
+//!     write_svg_frames(&trace, Some(path.as_slice()), "sweep_line_").unwrap();
+//! }
+//! ```
+
+use path::math::Point;
+use path::default::PathSlice;
+use path::builder::{FlatPathBuilder, PathBuilder};
+use svg::path_utils::PathSerializer;
+use tessellation::{FillTessellator, FillOptions, FillResult, FillVertex, VertexBuffers};
+use tessellation::geometry_builder::simple_builder;
+use tessellation::debugger::{debugger_channel, Color, DebuggerMsg, Trace};
+
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Tessellates a path with a debugger installed and returns the recorded trace.
+///
+/// The trace contains a frame per sweep line position with the active edges and
+/// the edges about to be inserted into the sweep line.
+pub fn record_fill_trace(path: PathSlice, options: &FillOptions) -> (FillResult, Trace) {
+    let (tx, rx) = debugger_channel();
+
+    let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+    let mut tess = FillTessellator::new();
+    tess.install_debugger(Box::new(tx));
+
+    let result = tess.tessellate_path(
+        path.path_iter(),
+        options,
+        &mut simple_builder(&mut buffers),
+    );
+
+    (result, rx.collect())
+}
+
+/// Generates an SVG document for each frame of a trace.
+///
+/// If provided, the path is drawn in the background of each frame.
+pub fn trace_to_svg(trace: &Trace, path: Option<PathSlice>) -> Vec<String> {
+    let mut min = Point::new(f32::MAX, f32::MAX);
+    let mut max = Point::new(f32::MIN, f32::MIN);
+    {
+        let mut add_point = |p: &Point| {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        };
+        for msg in &trace.messages {
+            match *msg {
+                DebuggerMsg::Point { ref position, .. } => { add_point(position); }
+                DebuggerMsg::Edge { ref from, ref to, .. } => {
+                    add_point(from);
+                    add_point(to);
+                }
+                _ => {}
+            }
+        }
+        if let Some(path) = path {
+            for p in path.points() {
+                add_point(p);
+            }
+        }
+    }
+
+    if min.x > max.x {
+        min = Point::new(0.0, 0.0);
+        max = Point::new(1.0, 1.0);
+    }
+
+    let size = (max.x - min.x).max(max.y - min.y).max(1.0);
+    let margin = size * 0.05;
+    let stroke_width = size * 0.002;
+
+    let background = path.map(|path| {
+        let mut serializer = PathSerializer::new();
+        for evt in path.path_iter() {
+            serializer.path_event(evt);
+        }
+        format!(
+            "  <path d=\"{}\" fill=\"rgb(220,220,220)\" stroke=\"none\"/>\n",
+            serializer.build()
+        )
+    });
+
+    let header = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min.x - margin,
+        min.y - margin,
+        max.x - min.x + 2.0 * margin,
+        max.y - min.y + 2.0 * margin,
+    );
+
+    let mut frames = Vec::new();
+    let mut current = None;
+    let mut text_y = min.y;
+
+    for msg in &trace.messages {
+        if let DebuggerMsg::NewFrame { .. } = *msg {
+            if let Some(frame) = current.take() {
+                frames.push(end_frame(frame));
+            }
+        }
+
+        let svg = current.get_or_insert_with(|| {
+            text_y = min.y;
+            let mut svg = header.clone();
+            if let Some(ref background) = background {
+                svg.push_str(background);
+            }
+            svg
+        });
+
+        match *msg {
+            DebuggerMsg::Point { position, color, .. } => {
+                svg.push_str(&format!(
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                    position.x, position.y, stroke_width * 3.0, css_color(color),
+                ));
+            }
+            DebuggerMsg::Edge { from, to, color, .. } => {
+                svg.push_str(&format!(
+                    "  <path d=\"M {} {} L {} {}\" stroke=\"{}\" stroke-width=\"{}\" fill=\"none\"/>\n",
+                    from.x, from.y, to.x, to.y, css_color(color), stroke_width,
+                ));
+            }
+            DebuggerMsg::String { ref string, .. } => {
+                text_y += size * 0.03;
+                svg.push_str(&format!(
+                    "  <text x=\"{}\" y=\"{}\" font-size=\"{}\">{}</text>\n",
+                    min.x, text_y, size * 0.025, escape(string),
+                ));
+            }
+            DebuggerMsg::Error { .. } | DebuggerMsg::NewFrame { .. } => {}
+        }
+    }
+
+    if let Some(frame) = current.take() {
+        frames.push(end_frame(frame));
+    }
+
+    frames
+}
+
+/// Writes each frame of a trace into a separate SVG file named `<prefix><frame index>.svg`.
+///
+/// Returns the number of files written.
+pub fn write_svg_frames(trace: &Trace, path: Option<PathSlice>, prefix: &str) -> io::Result<usize> {
+    let frames = trace_to_svg(trace, path);
+    for (i, frame) in frames.iter().enumerate() {
+        let mut file = File::create(format!("{}{:04}.svg", prefix, i))?;
+        file.write_all(frame.as_bytes())?;
+    }
+
+    Ok(frames.len())
+}
+
+fn end_frame(mut svg: String) -> String {
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn css_color(color: Color) -> String {
+    format!("rgba({},{},{},{})", color.r, color.g, color.b, color.a as f32 / 255.0)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[test]
+fn simple_polygon_trace() {
+    use path::default::Path;
+    use path::math::point;
+    use tessellation::dbg;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let (result, trace) = record_fill_trace(path.as_slice(), &FillOptions::default());
+    assert!(result.is_ok());
+
+    // One frame per vertex, from top to bottom.
+    let positions: Vec<Point> = trace.messages.iter().filter_map(|msg| match *msg {
+        DebuggerMsg::Point { position, flags, .. } if flags == dbg::SWEEP_LINE => Some(position),
+        _ => None,
+    }).collect();
+    assert_eq!(positions, vec![
+        point(0.0, 0.0), point(10.0, 0.0), point(0.0, 10.0), point(10.0, 10.0),
+    ]);
+
+    // The two edges starting at the first vertex are pending in the first frame, and
+    // are active in the second one.
+    let edges_with_flag = |frame: usize, flag: u32| -> Vec<(Point, Point)> {
+        trace.messages.split(|msg| matches!(*msg, DebuggerMsg::NewFrame { .. })).nth(frame + 1).unwrap().iter().filter_map(|msg| match *msg {
+            DebuggerMsg::Edge { from, to, flags, .. } if flags == flag => Some((from, to)),
+            _ => None,
+        }).collect()
+    };
+    let mut pending = edges_with_flag(0, dbg::PENDING_EDGE);
+    pending.sort_by(|a, b| a.1.x.partial_cmp(&b.1.x).unwrap());
+    assert_eq!(pending, vec![
+        (point(0.0, 0.0), point(0.0, 10.0)),
+        (point(0.0, 0.0), point(10.0, 0.0)),
+    ]);
+    assert!(edges_with_flag(0, dbg::ACTIVE_EDGE).is_empty());
+    assert_eq!(edges_with_flag(1, dbg::ACTIVE_EDGE).len(), 2);
+
+    let frames = trace_to_svg(&trace, Some(path.as_slice()));
+    assert_eq!(frames.len(), 4);
+    for frame in &frames {
+        assert!(frame.starts_with("<svg"));
+        assert!(frame.ends_with("</svg>\n"));
+        assert!(frame.contains("<path d=\"M 0 0 L 10 0 L 10 10 L 0 10 Z\""));
+    }
+}
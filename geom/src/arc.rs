@@ -3,16 +3,22 @@
 use std::ops::Range;
 use std::mem::swap;
 
-use Line;
+use {Line, LineSegment};
 use scalar::{Scalar, Float, cast};
 use generic_math::{Point, point, Vector, vector, Rotation2D, Transform2D, Angle, Rect};
 use segment::{Segment, FlattenedForEach, FlatteningStep, BoundingRect};
 use segment;
-use QuadraticBezierSegment;
+use {QuadraticBezierSegment, CubicBezierSegment};
+use utils::for_each_t_range_inside_rect;
+use monotonic::Monotonic;
+use arrayvec::ArrayVec;
 
 /// A flattening iterator for arc segments.
 pub type Flattened<S> = segment::Flattened<S, Arc<S>>;
 
+/// An arc that is monotonic in x and y.
+pub type MonotonicArc<S> = Monotonic<Arc<S>>;
+
 /// An elliptic arc curve segment using the SVG's end-point notation.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -157,6 +163,41 @@ impl<S: Scalar> Arc<S> {
         }
     }
 
+    /// Create a circular arc from the "bulge" notation used by CAD formats such as DXF.
+    ///
+    /// The bulge is the tangent of a quarter of the sweep angle: a bulge of `1` is a half
+    /// circle and a positive bulge means the arc goes in the direction of positive angles.
+    /// The bulge must not be zero (the segment is then a straight line) and the endpoints
+    /// must be distinct.
+    pub fn from_bulge(from: Point<S>, to: Point<S>, bulge: S) -> Self {
+        debug_assert!(bulge != S::ZERO);
+        debug_assert!(from != to);
+
+        let chord = to - from;
+        let half_chord = chord.length() * S::HALF;
+        // Signed distance between the middle of the chord and the center.
+        let offset = half_chord * (S::ONE - bulge * bulge) / (S::TWO * bulge);
+        let normal = vector(-chord.y, chord.x) / chord.length();
+        let center = from.lerp(to, S::HALF) + normal * offset;
+        let radius = (half_chord * half_chord + offset * offset).sqrt();
+
+        let v = from - center;
+        Arc {
+            center,
+            radii: vector(radius, radius),
+            start_angle: Angle::radians(S::atan2(v.y, v.x)),
+            sweep_angle: Angle::radians(S::FOUR * bulge.atan()),
+            x_rotation: Angle::zero(),
+        }
+    }
+
+    /// Returns the arc's sweep angle in the "bulge" notation used by CAD formats.
+    ///
+    /// Only meaningful for circular arcs. See `from_bulge`.
+    pub fn bulge(&self) -> S {
+        Float::tan(self.sweep_angle.get() / S::FOUR)
+    }
+
     /// Approximate the arc with a sequence of quadratic bézier curves.
     #[inline]
     pub fn for_each_quadratic_bezier<F>(&self, cb: &mut F)
@@ -166,6 +207,17 @@ impl<S: Scalar> Arc<S> {
         arc_to_to_quadratic_beziers(self, cb);
     }
 
+    /// Approximate the arc with a sequence of cubic bézier curves.
+    ///
+    /// The arc is split in pieces of at most a quarter of a turn, and in more pieces if
+    /// needed to keep the distance between the curves and the arc under the tolerance.
+    pub fn for_each_cubic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        arc_to_cubic_beziers(self, tolerance, cb);
+    }
+
     /// Sample the curve at t (expecting t between 0 and 1).
     #[inline]
     pub fn sample(&self, t: S) -> Point<S> {
@@ -185,6 +237,23 @@ impl<S: Scalar> Arc<S> {
         self.tangent_at_angle(self.get_angle(t))
     }
 
+    /// Sample the curve's derivative at t (expecting t between 0 and 1).
+    ///
+    /// Unlike `sample_tangent`, this takes the sweep angle into account so that the
+    /// result is the derivative with respect to `t`, like for the bézier segments.
+    #[inline]
+    pub fn derivative(&self, t: S) -> Vector<S> {
+        self.sample_tangent(t) * self.sweep_angle.radians
+    }
+
+    /// Sample the x coordinate of the curve's derivative at t (expecting t between 0 and 1).
+    #[inline]
+    pub fn dx(&self, t: S) -> S { self.derivative(t).x }
+
+    /// Sample the y coordinate of the curve's derivative at t (expecting t between 0 and 1).
+    #[inline]
+    pub fn dy(&self, t: S) -> S { self.derivative(t).y }
+
     /// Sample the curve's angle at t (expecting t between 0 and 1).
     #[inline]
     pub fn get_angle(&self, t: S) -> Angle<S> {
@@ -206,6 +275,197 @@ impl<S: Scalar> Arc<S> {
         self.sample(S::ONE)
     }
 
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// arc that have a given x coordinate.
+    pub fn solve_t_for_x(&self, x: S) -> ArrayVec<[S; 2]> {
+        let (sin, cos) = Float::sin_cos(self.x_rotation.radians);
+        self.solve_t(self.radii.x * cos, -self.radii.y * sin, x - self.center.x)
+    }
+
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// arc that have a given y coordinate.
+    pub fn solve_t_for_y(&self, y: S) -> ArrayVec<[S; 2]> {
+        let (sin, cos) = Float::sin_cos(self.x_rotation.radians);
+        self.solve_t(self.radii.x * sin, self.radii.y * cos, y - self.center.y)
+    }
+
+    /// Computes the `t` parameters (in increasing order) of the intersections
+    /// between this arc and a line.
+    pub fn line_intersections_t(&self, line: &Line<S>) -> ArrayVec<[S; 2]> {
+        if line.vector.square_length() < S::EPSILON {
+            return ArrayVec::new();
+        }
+
+        let eq = line.equation();
+        let (sin, cos) = Float::sin_cos(self.x_rotation.radians);
+        self.solve_t(
+            self.radii.x * (eq.a() * cos + eq.b() * sin),
+            self.radii.y * (eq.b() * cos - eq.a() * sin),
+            -eq.signed_distance_to_point(&self.center),
+        )
+    }
+
+    /// Computes the intersection points (if any) between this arc and a line.
+    pub fn line_intersections(&self, line: &Line<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut result = ArrayVec::new();
+        for t in self.line_intersections_t(line) {
+            result.push(self.sample(t));
+        }
+
+        result
+    }
+
+    /// Computes the intersections (if any) between this arc and a line segment.
+    ///
+    /// The result is provided in the form of the `t` parameters of each
+    /// point along the arc and the segment. To get the intersection points, sample
+    /// the segments at the corresponding values.
+    pub fn line_segment_intersections_t(&self, segment: &LineSegment<S>) -> ArrayVec<[(S, S); 2]> {
+        let mut result = ArrayVec::new();
+        let v = segment.to_vector();
+        let square_length = v.square_length();
+        if square_length < S::EPSILON {
+            return result;
+        }
+
+        for t in self.line_intersections_t(&segment.to_line()) {
+            let segment_t = (self.sample(t) - segment.from).dot(v) / square_length;
+            if segment_t >= S::ZERO && segment_t <= S::ONE {
+                result.push((t, segment_t));
+            }
+        }
+
+        result
+    }
+
+    /// Computes the intersection points (if any) between this arc and a line segment.
+    pub fn line_segment_intersections(&self, segment: &LineSegment<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut result = ArrayVec::new();
+        for (t, _) in self.line_segment_intersections_t(segment) {
+            result.push(self.sample(t));
+        }
+
+        result
+    }
+
+    /// Find the advancement of the x-most position in the curve.
+    ///
+    /// This returns the advancement along the curve, not the actual x position.
+    pub fn x_maximum_t(&self) -> S {
+        self.extremum_t(true, true)
+    }
+
+    /// Find the advancement of the x-least position in the curve.
+    ///
+    /// This returns the advancement along the curve, not the actual x position.
+    pub fn x_minimum_t(&self) -> S {
+        self.extremum_t(true, false)
+    }
+
+    /// Find the advancement of the y-most position in the curve.
+    ///
+    /// This returns the advancement along the curve, not the actual y position.
+    pub fn y_maximum_t(&self) -> S {
+        self.extremum_t(false, true)
+    }
+
+    /// Find the advancement of the y-least position in the curve.
+    ///
+    /// This returns the advancement along the curve, not the actual y position.
+    pub fn y_minimum_t(&self) -> S {
+        self.extremum_t(false, false)
+    }
+
+    // Picks the best t among the end points and the local extrema.
+    fn extremum_t(&self, x_axis: bool, maximum: bool) -> S {
+        let value = |t: S| if x_axis { self.x(t) } else { self.y(t) };
+        let is_better = |a: S, b: S| if maximum { a > b } else { a < b };
+
+        let mut candidates: ArrayVec<[S; 2]> = ArrayVec::new();
+        if x_axis {
+            self.for_each_local_x_extremum_t(&mut |t| { candidates.push(t); });
+        } else {
+            self.for_each_local_y_extremum_t(&mut |t| { candidates.push(t); });
+        }
+
+        let mut best_t = S::ZERO;
+        let mut best = value(S::ZERO);
+        for t in Some(S::ONE).into_iter().chain(candidates) {
+            let v = value(t);
+            if is_better(v, best) {
+                best_t = t;
+                best = v;
+            }
+        }
+
+        best_t
+    }
+
+    #[inline]
+    pub fn baseline(&self) -> LineSegment<S> {
+        LineSegment { from: self.from(), to: self.to() }
+    }
+
+    // Finds the t values for which a * cos(angle) + b * sin(angle) = c.
+    fn solve_t(&self, a: S, b: S, c: S) -> ArrayVec<[S; 2]> {
+        let mut result = ArrayVec::new();
+
+        let r = S::sqrt(a * a + b * b);
+        if r < S::EPSILON || S::abs(c) > r {
+            return result;
+        }
+
+        // a * cos(angle) + b * sin(angle) = r * cos(angle - phase)
+        let phase = S::atan2(b, a);
+        let delta = S::acos(S::min(S::max(c / r, -S::ONE), S::ONE));
+
+        let two_pi = S::TWO * S::PI();
+        let sweep = self.sweep_angle.radians;
+        let mut add_angle = |angle: S| {
+            let mut d = (angle - self.start_angle.radians) % two_pi;
+            if d < S::ZERO {
+                d += two_pi;
+            }
+            if sweep < S::ZERO && d > S::ZERO {
+                d -= two_pi;
+            }
+            let t = d / sweep;
+            if t >= S::ZERO && t <= S::ONE {
+                result.push(t);
+            }
+        };
+
+        add_angle(phase - delta);
+        if delta > S::EPSILON {
+            add_angle(phase + delta);
+        }
+
+        if result.len() == 2 && result[0] > result[1] {
+            result.swap(0, 1);
+        }
+
+        result
+    }
+
+    /// Computes the parts of this arc that are inside a rectangle.
+    pub fn clip_to_rect(&self, rect: &Rect<S>) -> ArrayVec<[Self; 5]> {
+        let mut crossings: ArrayVec<[S; 8]> = ArrayVec::new();
+        crossings.extend(self.solve_t_for_x(rect.min_x()));
+        crossings.extend(self.solve_t_for_x(rect.max_x()));
+        crossings.extend(self.solve_t_for_y(rect.min_y()));
+        crossings.extend(self.solve_t_for_y(rect.max_y()));
+
+        let mut result = ArrayVec::new();
+        for_each_t_range_inside_rect(
+            &mut crossings,
+            rect,
+            |t| self.sample(t),
+            &mut |range| { result.push(self.split_range(range)); },
+        );
+
+        result
+    }
+
     /// Return the sub-curve inside a given range of t.
     ///
     /// This is equivalent splitting at the range's end points.
@@ -283,22 +543,41 @@ impl<S: Scalar> Arc<S> {
 
     /// Finds the interval of the beginning of the curve that can be approximated with a
     /// line segment.
+    ///
+    /// The arc is split in `num_flattening_steps` steps of equal angle, so calling this
+    /// repeatedly on the remaining part of the arc produces evenly spaced points.
     pub fn flattening_step(&self, tolerance: S) -> S {
-        // cos(theta) = (r - tolerance) / r
-        // angle = 2 * theta
-        // s = angle / sweep
+        let n = self.num_flattening_steps(tolerance);
+        if n <= 1 {
+            return S::ONE;
+        }
 
-        // Here we make the approximation that for small tolerance values we consider
-        // the radius to be constant over each approximated segment.
-        let r = (self.from() - self.center).length();
-        let a = S::TWO * S::acos((r - tolerance) / r);
-        let result = S::min(a / self.sweep_angle.radians, S::ONE);
+        S::ONE / cast::<u32, S>(n).unwrap()
+    }
 
-        if result < S::EPSILON {
-            return S::ONE;
+    /// Returns the smallest number of line segments of equal angle that approximate the arc
+    /// within the tolerance threshold.
+    ///
+    /// An elliptic arc is an affine transformation of a circular arc of radius 1, so the
+    /// distance between the arc and a chord spanning an angle `theta` is at most
+    /// `r * (1 - cos(theta / 2))` where `r` is the largest radius. Solving for `theta` gives
+    /// the maximum angle of each step.
+    pub fn num_flattening_steps(&self, tolerance: S) -> u32 {
+        let r = S::max(S::abs(self.radii.x), S::abs(self.radii.y));
+        if tolerance >= S::TWO * r {
+            return 1;
         }
 
-        result
+        // 2 * acos(1 - tolerance / r), written in a way that doesn't lose precision when
+        // the tolerance is much smaller than the radius.
+        let max_angle = S::FOUR * S::asin(S::sqrt(tolerance / (S::TWO * r)));
+        let n = S::ceil(S::abs(self.sweep_angle.radians) / max_angle);
+
+        if n.is_nan() || n <= S::ONE {
+            return 1;
+        }
+
+        n.to_u32().unwrap_or(u32::MAX)
     }
 
     /// Returns the flattened representation of the curve as an iterator, starting *after* the
@@ -382,14 +661,79 @@ impl<S: Scalar> Arc<S> {
             }
         } else  {
             if a1 > two_pi - abs_sweep {
-                cb(a1 / abs_sweep);
+                cb((two_pi - a1) / abs_sweep);
             }
             if a2 > two_pi - abs_sweep {
-                cb(a2 / abs_sweep);
+                cb((two_pi - a2) / abs_sweep);
+            }
+        }
+    }
+
+    /// Invokes a callback between each monotonic part of the arc.
+    pub fn for_each_monotonic_t<F>(&self, mut cb: F)
+    where
+        F: FnMut(S),
+    {
+        let mut extrema: ArrayVec<[S; 4]> = ArrayVec::new();
+        self.for_each_local_x_extremum_t(&mut|t| { extrema.push(t); });
+        self.for_each_local_y_extremum_t(&mut|t| { extrema.push(t); });
+        extrema.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        for t in extrema {
+            if t > S::ZERO && t < S::ONE {
+                cb(t);
             }
         }
     }
 
+    /// Invokes a callback for each monotonic part of the arc.
+    pub fn for_each_monotonic_range<F>(&self, mut cb: F)
+    where
+        F: FnMut(Range<S>),
+    {
+        let mut t0 = S::ZERO;
+        self.for_each_monotonic_t(|t| {
+            cb(t0..t);
+            t0 = t;
+        });
+        cb(t0..S::ONE);
+    }
+
+    /// Invokes a callback for each monotonic part of the arc.
+    pub fn for_each_monotonic<F>(&self, cb: &mut F)
+    where
+        F: FnMut(&MonotonicArc<S>)
+    {
+        self.for_each_monotonic_range(|range| {
+            cb(&self.split_range(range).assume_monotonic())
+        });
+    }
+
+    /// Cast this arc into a monotonic arc without checking that the monotonicity
+    /// assumption is correct.
+    pub fn assume_monotonic(&self) -> MonotonicArc<S> {
+        MonotonicArc { segment: *self }
+    }
+
+    /// Returns whether this arc is monotonic on the x axis.
+    pub fn is_x_monotonic(&self) -> bool {
+        let mut found = false;
+        self.for_each_local_x_extremum_t(&mut |t|{ found |= t > S::ZERO && t < S::ONE; });
+        !found
+    }
+
+    /// Returns whether this arc is monotonic on the y axis.
+    pub fn is_y_monotonic(&self) -> bool {
+        let mut found = false;
+        self.for_each_local_y_extremum_t(&mut |t|{ found |= t > S::ZERO && t < S::ONE; });
+        !found
+    }
+
+    /// Returns whether this arc is fully monotonic.
+    pub fn is_monotonic(&self) -> bool {
+        self.is_x_monotonic() && self.is_y_monotonic()
+    }
+
     pub fn bounding_range_x(&self) -> (S, S) {
         let r = self.bounding_rect();
         (r.min_x(), r.max_x())
@@ -515,6 +859,46 @@ where
     }
 }
 
+fn arc_to_cubic_beziers<S, F>(
+    arc: &Arc<S>,
+    tolerance: S,
+    callback: &mut F,
+)
+where
+    S: Scalar,
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let sweep_angle = arc.sweep_angle.get().max(-S::PI() * S::TWO).min(S::PI() * S::TWO);
+    let quarter = S::PI() * S::HALF;
+
+    // The error of the approximation of a quarter of a circle is about 2.7e-4 times the
+    // radius, and it grows with the sixth power of the angle.
+    let max_radius = S::max(S::abs(arc.radii.x), S::abs(arc.radii.y));
+    let ratio = tolerance / (max_radius * S::value(2.7e-4));
+    let max_step = if ratio < S::ONE {
+        quarter * Float::powf(ratio, S::ONE / S::SIX)
+    } else {
+        quarter
+    };
+
+    let n_steps = S::max(S::ceil(S::abs(sweep_angle) / max_step), S::ONE);
+    let step = Angle::radians(sweep_angle / n_steps);
+    // Length of the control vectors relative to the derivative with respect to the angle.
+    let k = S::FOUR / S::THREE * Float::tan(step.get() / S::FOUR);
+
+    for i in 0..cast::<S, i32>(n_steps).unwrap() {
+        let a1 = arc.start_angle + step * cast(i).unwrap();
+        let a2 = arc.start_angle + step * cast(i + 1).unwrap();
+
+        let from = arc.center + sample_ellipse(arc.radii, arc.x_rotation, a1).to_vector();
+        let to = arc.center + sample_ellipse(arc.radii, arc.x_rotation, a2).to_vector();
+        let ctrl1 = from + arc.tangent_at_angle(a1) * k;
+        let ctrl2 = to - arc.tangent_at_angle(a2) * k;
+
+        callback(&CubicBezierSegment { from, ctrl1, ctrl2, to });
+    }
+}
+
 fn sample_ellipse<S: Scalar>(radii: Vector<S>, x_rotation: Angle<S>, angle: Angle<S>) -> Point<S> {
     Rotation2D::new(x_rotation).transform_point(
         &point(radii.x * Float::cos(angle.get()), radii.y * Float::sin(angle.get()))
@@ -528,7 +912,9 @@ impl<S: Scalar> Segment for Arc<S> {
     fn sample(&self, t: S) -> Point<S> { self.sample(t) }
     fn x(&self, t: S) -> S { self.x(t) }
     fn y(&self, t: S) -> S { self.y(t) }
-    fn derivative(&self, t: S) -> Vector<S> { self.sample_tangent(t) }
+    fn derivative(&self, t: S) -> Vector<S> { self.derivative(t) }
+    fn dx(&self, t: S) -> S { self.dx(t) }
+    fn dy(&self, t: S) -> S { self.dy(t) }
     fn split_range(&self, t_range: Range<S>) -> Self { self.split_range(t_range) }
     fn split(&self, t: S) -> (Self, Self) { self.split(t) }
     fn before_split(&self, t: S) -> Self { self.before_split(t) }
@@ -537,6 +923,7 @@ impl<S: Scalar> Segment for Arc<S> {
     fn approximate_length(&self, tolerance: S) -> S {
         self.approximate_length(tolerance)
     }
+    fn for_each_monotonic_t<F: FnMut(S)>(&self, cb: F) { self.for_each_monotonic_t(cb) }
 }
 
 impl<S: Scalar> BoundingRect for Arc<S> {
@@ -811,3 +1198,225 @@ fn negative_flattening_step() {
 
     arc.for_each_flattened(0.100000001, &mut|_|{});
 }
+
+#[test]
+fn arc_solve_t() {
+    let arc = Arc {
+        center: point(0.0, 0.0),
+        radii: vector(10.0, 10.0),
+        start_angle: Angle::radians(0.0),
+        sweep_angle: Angle::radians(::std::f32::consts::PI),
+        x_rotation: Angle::radians(0.0),
+    };
+
+    let t = arc.solve_t_for_x(5.0);
+    assert_eq!(t.len(), 1);
+    assert!((arc.x(t[0]) - 5.0).abs() < 0.001);
+
+    let t = arc.solve_t_for_y(5.0);
+    assert_eq!(t.len(), 2);
+    assert!(t[0] < t[1]);
+    for t in t {
+        assert!((arc.y(t) - 5.0).abs() < 0.001);
+    }
+
+    assert!(arc.solve_t_for_y(-5.0).is_empty());
+
+    let reversed = arc.flip();
+    let t = reversed.solve_t_for_x(5.0);
+    assert_eq!(t.len(), 1);
+    assert!((reversed.x(t[0]) - 5.0).abs() < 0.001);
+
+    let pieces = arc.clip_to_rect(&::generic_math::rect(-20.0, 0.0, 40.0, 5.0));
+    assert_eq!(pieces.len(), 2);
+    assert!((pieces[0].from() - arc.from()).length() < 0.001);
+    assert!((pieces[1].to() - arc.to()).length() < 0.001);
+}
+
+#[test]
+fn arc_line_intersections() {
+    let arc = Arc {
+        center: point(1.0, 2.0),
+        radii: vector(10.0, 5.0),
+        start_angle: Angle::radians(0.1),
+        sweep_angle: Angle::radians(6.1),
+        x_rotation: Angle::radians(0.7),
+    };
+
+    let line = Line { point: point(0.0, 3.0), vector: vector(2.0, 1.0) };
+    let t = arc.line_intersections_t(&line);
+    assert_eq!(t.len(), 2);
+    assert!(t[0] < t[1]);
+    for t in t {
+        assert!(line.distance_to_point(&arc.sample(t)) < 0.001);
+    }
+
+    let far = Line { point: point(0.0, 30.0), vector: vector(1.0, 0.0) };
+    assert!(arc.line_intersections_t(&far).is_empty());
+}
+
+#[test]
+fn monotonic_arcs() {
+    for &sweep in &[5.0, -5.0] {
+        let arc = Arc {
+            center: point(1.0, 2.0),
+            radii: vector(10.0, 5.0),
+            start_angle: Angle::radians(0.3),
+            sweep_angle: Angle::radians(sweep),
+            x_rotation: Angle::radians(0.7),
+        };
+
+        let mut count = 0;
+        let mut prev = arc.from();
+        arc.for_each_monotonic(&mut |m| {
+            let d = m.to() - m.from();
+            for i in 0..10 {
+                let a = m.sample(i as f32 / 10.0);
+                let b = m.sample((i + 1) as f32 / 10.0);
+                assert!((b.x - a.x) * d.x >= -0.0001);
+                assert!((b.y - a.y) * d.y >= -0.0001);
+            }
+            assert!((m.from() - prev).length() < 0.001);
+            prev = m.to();
+            count += 1;
+
+            for i in 0..10 {
+                let t = i as f32 / 9.0;
+                let p = m.sample(t);
+                let tx = m.solve_t_for_x(p.x, 0.0..1.0, 0.0001);
+                let ty = m.solve_t_for_y(p.y, 0.0..1.0, 0.0001);
+                assert!((m.x(tx) - p.x).abs() <= 0.0001);
+                assert!((m.y(ty) - p.y).abs() <= 0.0001);
+            }
+        });
+        assert!((arc.to() - prev).length() < 0.001);
+        assert!(count >= 3);
+    }
+}
+
+#[test]
+fn bulge() {
+    let from = point(1.0f64, 2.0);
+    let to = point(5.0f64, -1.0);
+    for &bulge in &[1.0, -1.0, 0.2, -0.05, 3.0] {
+        let arc = Arc::from_bulge(from, to, bulge);
+        assert!((arc.from() - from).length() < 1e-9);
+        assert!((arc.to() - to).length() < 1e-9);
+        assert!((arc.bulge() - bulge).abs() < 1e-9);
+        assert_eq!(arc.sweep_angle.get() > 0.0, bulge > 0.0);
+    }
+
+    // A positive bulge goes in the direction of positive angles.
+    let arc = Arc::from_bulge(point(0.0f32, 0.0), point(2.0, 0.0), 1.0);
+    assert!((arc.center - point(1.0, 0.0)).length() < 1e-6);
+    assert!((arc.sample(0.5) - point(1.0, -1.0)).length() < 1e-6);
+}
+
+#[test]
+fn arc_segment_parity() {
+    let arc = Arc {
+        center: point(1.0f64, 2.0),
+        radii: vector(10.0, 5.0),
+        start_angle: Angle::radians(0.3),
+        sweep_angle: Angle::radians(-4.0),
+        x_rotation: Angle::radians(0.7),
+    };
+
+    // The derivative is with respect to t, like for béziers.
+    let h = 1e-6;
+    for &t in &[0.0, 0.25, 0.5, 0.9] {
+        let d = arc.derivative(t);
+        let fd = (arc.sample(t + h) - arc.sample(t)) / h;
+        assert!((d - fd).length() < 1e-3);
+        assert_eq!(<Arc<f64> as Segment>::derivative(&arc, t), d);
+    }
+
+    // The extrema agree with the bounding rectangle.
+    let rect = arc.bounding_rect();
+    assert!((arc.x(arc.x_minimum_t()) - rect.min_x()).abs() < 1e-9);
+    assert!((arc.x(arc.x_maximum_t()) - rect.max_x()).abs() < 1e-9);
+    assert!((arc.y(arc.y_minimum_t()) - rect.min_y()).abs() < 1e-9);
+    assert!((arc.y(arc.y_maximum_t()) - rect.max_y()).abs() < 1e-9);
+
+    let segment = LineSegment { from: point(1.0, 2.0), to: point(20.0, 2.0) };
+    let intersections = arc.line_segment_intersections_t(&segment);
+    assert_eq!(intersections.len(), 1);
+    let (t1, t2) = intersections[0];
+    assert!((arc.sample(t1) - segment.sample(t2)).length() < 1e-6);
+    assert_eq!(arc.line_segment_intersections(&segment).len(), 1);
+
+    assert_eq!(arc.baseline().to, arc.to());
+}
+
+#[test]
+fn arc_flattening_tolerance() {
+    let arc = Arc {
+        center: point(5.0f64, -3.0),
+        radii: vector(1000.0, 200.0),
+        start_angle: Angle::radians(0.2),
+        sweep_angle: Angle::radians(5.5),
+        x_rotation: Angle::radians(0.4),
+    };
+    let tolerance = 0.05;
+
+    let mut t = vec![0.0];
+    let mut remaining = arc;
+    let mut done = 0.0;
+    loop {
+        let step = remaining.flattening_step(tolerance);
+        if step >= 1.0 {
+            t.push(1.0);
+            break;
+        }
+        done += (1.0 - done) * step;
+        t.push(done);
+        remaining = remaining.after_split(step);
+    }
+    assert_eq!(t.len() as u32, arc.num_flattening_steps(tolerance) + 1);
+
+    // Every point of the arc is within the tolerance of its chord.
+    for w in t.windows(2) {
+        let chord = LineSegment { from: arc.sample(w[0]), to: arc.sample(w[1]) }.to_line();
+        for i in 1..20 {
+            let p = arc.sample(w[0] + (w[1] - w[0]) * i as f64 / 20.0);
+            assert!(chord.distance_to_point(&p) <= tolerance);
+        }
+    }
+
+    assert_eq!(arc.flattened(tolerance).count(), t.len() - 1);
+
+    // A small tolerance relative to a large radius used to produce a single segment
+    // because of the loss of precision in 1 - tolerance / radius.
+    let circle = Arc::circle(point(0.0f32, 0.0), 100000.0);
+    assert!(circle.flattened(0.001).count() > 10000);
+}
+
+#[test]
+fn arc_to_cubics() {
+    let arc = Arc {
+        center: point(1.0f64, 2.0),
+        radii: vector(100.0, 50.0),
+        start_angle: Angle::radians(0.5),
+        sweep_angle: Angle::radians(-4.0),
+        x_rotation: Angle::radians(0.3),
+    };
+
+    for &tolerance in &[1.0, 0.01, 0.0001] {
+        let mut curves = Vec::new();
+        arc.for_each_cubic_bezier(tolerance, &mut |c| { curves.push(*c); });
+
+        assert!(curves.len() >= 3);
+        assert!((curves[0].from - arc.from()).length() < 1e-9);
+        assert!((curves.last().unwrap().to - arc.to()).length() < 1e-9);
+
+        // Measure the distance to the ellipse in the space where it is a unit circle.
+        let to_unit_circle = Rotation2D::new(-arc.x_rotation);
+        for curve in &curves {
+            for j in 0..11 {
+                let v = to_unit_circle.transform_vector(&(curve.sample(j as f64 / 10.0) - arc.center));
+                let error = (Vector::new(v.x / arc.radii.x, v.y / arc.radii.y).length() - 1.0).abs();
+                assert!(error * 100.0 <= tolerance);
+            }
+        }
+    }
+}
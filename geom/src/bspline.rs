@@ -0,0 +1,293 @@
+//! Conversion of cubic B-splines and NURBS curves into cubic bézier segments.
+//!
+//! Non-rational B-splines are converted exactly by inserting knots until each knot
+//! span is a bézier segment (Boehm's algorithm). Rational curves (NURBS) can't be
+//! represented exactly with cubic bézier segments so they are approximated within
+//! a tolerance.
+
+use scalar::{Scalar, cast};
+use generic_math::{Point, point};
+use CubicBezierSegment;
+
+const DEGREE: usize = 3;
+
+/// Converts a uniform cubic B-spline into a sequence of cubic bézier segments.
+///
+/// The curve doesn't go through the first and last control points. Use
+/// `bspline_to_beziers` with `clamped_uniform_knots` for that.
+pub fn uniform_bspline_to_beziers<S: Scalar, F>(control_points: &[Point<S>], cb: &mut F)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let sixth = S::ONE / S::SIX;
+    let third = S::ONE / S::THREE;
+    for w in control_points.windows(4) {
+        let (p0, p1, p2, p3) = (w[0].to_vector(), w[1].to_vector(), w[2].to_vector(), w[3].to_vector());
+        cb(&CubicBezierSegment {
+            from: ((p0 + p1 * S::FOUR + p2) * sixth).to_point(),
+            ctrl1: ((p1 * S::TWO + p2) * third).to_point(),
+            ctrl2: ((p1 + p2 * S::TWO) * third).to_point(),
+            to: ((p1 + p2 * S::FOUR + p3) * sixth).to_point(),
+        });
+    }
+}
+
+/// Computes the knot vector of a clamped uniform cubic B-spline, for which the curve
+/// starts at the first control point and ends at the last one.
+pub fn clamped_uniform_knots<S: Scalar>(num_control_points: usize) -> Vec<S> {
+    assert!(num_control_points > DEGREE);
+    let num_spans = num_control_points - DEGREE;
+    let mut knots = Vec::with_capacity(num_control_points + DEGREE + 1);
+    for _ in 0..DEGREE {
+        knots.push(S::ZERO);
+    }
+    for i in 0..(num_spans + 1) {
+        knots.push(cast::<usize, S>(i).unwrap());
+    }
+    for _ in 0..DEGREE {
+        knots.push(cast::<usize, S>(num_spans).unwrap());
+    }
+
+    knots
+}
+
+/// Converts a non-uniform cubic B-spline into a sequence of cubic bézier segments.
+///
+/// The knot vector must be non-decreasing and contain four more values than the
+/// number of control points.
+pub fn bspline_to_beziers<S: Scalar, F>(control_points: &[Point<S>], knots: &[S], cb: &mut F)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let points: Vec<[S; 3]> = control_points.iter().map(|p| [p.x, p.y, S::ONE]).collect();
+    decompose(points, knots, &mut |segment| {
+        cb(&project(segment));
+    });
+}
+
+/// Approximates a cubic NURBS curve with a sequence of cubic bézier segments.
+///
+/// The weights must be positive, with one weight per control point. The knot vector
+/// must be non-decreasing and contain four more values than the number of control points.
+///
+/// The approximation has the same end points and end tangents as each rational segment,
+/// and the distance between the approximation and the curve is checked at a few sample
+/// points against the tolerance threshold.
+pub fn nurbs_to_beziers<S: Scalar, F>(
+    control_points: &[Point<S>],
+    weights: &[S],
+    knots: &[S],
+    tolerance: S,
+    cb: &mut F
+)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    assert_eq!(control_points.len(), weights.len());
+
+    let points: Vec<[S; 3]> = control_points.iter().zip(weights.iter()).map(|(p, &w)| {
+        assert!(w > S::ZERO);
+        [p.x * w, p.y * w, w]
+    }).collect();
+
+    decompose(points, knots, &mut |segment| {
+        approximate_rational(segment, tolerance, 0, cb);
+    });
+}
+
+// Inserts knots until all knots of the domain have a multiplicity of three and calls the
+// callback with the homogeneous control points of each bézier segment.
+fn decompose<S: Scalar, F>(mut points: Vec<[S; 3]>, knots: &[S], cb: &mut F)
+where
+    F: FnMut(&[[S; 3]])
+{
+    assert!(points.len() > DEGREE);
+    assert_eq!(knots.len(), points.len() + DEGREE + 1);
+
+    let start = knots[DEGREE];
+    let end = knots[points.len()];
+
+    let mut knots = knots.to_vec();
+    let mut distinct_knots = Vec::new();
+    for &u in &knots {
+        if u >= start && u <= end && distinct_knots.last() != Some(&u) {
+            distinct_knots.push(u);
+        }
+    }
+
+    for &u in &distinct_knots {
+        while multiplicity(&knots, u) < DEGREE {
+            insert_knot(&mut points, &mut knots, u);
+        }
+    }
+
+    for range in distinct_knots.windows(2) {
+        // The index of the last occurrence of the start of the span.
+        let span = knots.iter().rposition(|&k| k == range[0]).unwrap();
+        cb(&points[(span - DEGREE)..(span + 1)]);
+    }
+}
+
+fn multiplicity<S: Scalar>(knots: &[S], u: S) -> usize {
+    knots.iter().filter(|&&k| k == u).count()
+}
+
+// Inserts a knot once, without changing the shape of the curve.
+fn insert_knot<S: Scalar>(points: &mut Vec<[S; 3]>, knots: &mut Vec<S>, u: S) {
+    let k = knots.iter().rposition(|&k| k <= u).unwrap();
+    let s = multiplicity(knots, u);
+
+    let mut new_points = Vec::with_capacity(points.len() + 1);
+    for i in 0..(points.len() + 1) {
+        if i + DEGREE <= k {
+            new_points.push(points[i]);
+        } else if i + s <= k {
+            let alpha = (u - knots[i]) / (knots[i + DEGREE] - knots[i]);
+            let a = points[i];
+            let b = points[i - 1];
+            new_points.push([
+                a[0] * alpha + b[0] * (S::ONE - alpha),
+                a[1] * alpha + b[1] * (S::ONE - alpha),
+                a[2] * alpha + b[2] * (S::ONE - alpha),
+            ]);
+        } else {
+            new_points.push(points[i - 1]);
+        }
+    }
+
+    *points = new_points;
+    knots.insert(k + 1, u);
+}
+
+fn project<S: Scalar>(h: &[[S; 3]]) -> CubicBezierSegment<S> {
+    let p = |i: usize| point(h[i][0] / h[i][2], h[i][1] / h[i][2]);
+    CubicBezierSegment { from: p(0), ctrl1: p(1), ctrl2: p(2), to: p(3) }
+}
+
+fn lerp3<S: Scalar>(a: [S; 3], b: [S; 3], t: S) -> [S; 3] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
+}
+
+// Splits a homogeneous bézier segment using de Casteljau's algorithm.
+fn split_rational<S: Scalar>(h: &[[S; 3]], t: S) -> ([[S; 3]; 4], [[S; 3]; 4]) {
+    let a = lerp3(h[0], h[1], t);
+    let b = lerp3(h[1], h[2], t);
+    let c = lerp3(h[2], h[3], t);
+    let ab = lerp3(a, b, t);
+    let bc = lerp3(b, c, t);
+    let mid = lerp3(ab, bc, t);
+
+    ([h[0], a, ab, mid], [mid, bc, c, h[3]])
+}
+
+fn approximate_rational<S: Scalar, F>(h: &[[S; 3]], tolerance: S, depth: u32, cb: &mut F)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let approximation = project(h);
+
+    let mut error = S::ZERO;
+    for &t in &[S::value(0.25), S::HALF, S::value(0.75)] {
+        let (before, _) = split_rational(h, t);
+        let on_curve = point(before[3][0] / before[3][2], before[3][1] / before[3][2]);
+        error = S::max(error, (approximation.sample(t) - on_curve).length());
+    }
+
+    if error <= tolerance || depth >= 16 {
+        cb(&approximation);
+        return;
+    }
+
+    let (a, b) = split_rational(h, S::HALF);
+    approximate_rational(&a, tolerance, depth + 1, cb);
+    approximate_rational(&b, tolerance, depth + 1, cb);
+}
+
+#[cfg(test)]
+fn assert_approx_eq(a: Point<f32>, b: Point<f32>) {
+    assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn uniform_bspline() {
+    let points = [point(0.0, 0.0), point(6.0, 6.0), point(12.0, 0.0), point(18.0, 6.0), point(24.0, 0.0)];
+
+    let mut segments = Vec::new();
+    uniform_bspline_to_beziers(&points, &mut |s| segments.push(*s));
+    assert_eq!(segments.len(), 2);
+    assert_approx_eq(segments[0].from, point(6.0, 4.0));
+    assert_approx_eq(segments[0].ctrl1, point(8.0, 4.0));
+    assert_approx_eq(segments[0].ctrl2, point(10.0, 2.0));
+    assert_approx_eq(segments[0].to, point(12.0, 2.0));
+    assert_approx_eq(segments[1].from, segments[0].to);
+
+    // The same curve expressed with an explicit uniform knot vector.
+    let knots = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    let mut general = Vec::new();
+    bspline_to_beziers(&points, &knots, &mut |s| general.push(*s));
+    assert_eq!(general.len(), 2);
+    for (a, b) in segments.iter().zip(general.iter()) {
+        assert_approx_eq(a.from, b.from);
+        assert_approx_eq(a.ctrl1, b.ctrl1);
+        assert_approx_eq(a.ctrl2, b.ctrl2);
+        assert_approx_eq(a.to, b.to);
+    }
+}
+
+#[test]
+fn clamped_bspline() {
+    let points = [point(0.0, 0.0), point(0.0, 10.0), point(10.0, 10.0), point(10.0, 0.0)];
+    let knots = clamped_uniform_knots(points.len());
+    assert_eq!(knots, vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+
+    // With four control points, the clamped B-spline is a bézier curve.
+    let mut segments = Vec::new();
+    bspline_to_beziers(&points, &knots, &mut |s| segments.push(*s));
+    assert_eq!(segments, vec![CubicBezierSegment {
+        from: points[0], ctrl1: points[1], ctrl2: points[2], to: points[3]
+    }]);
+
+    let points = [point(0.0, 0.0), point(0.0, 10.0), point(10.0, 10.0), point(10.0, 0.0), point(20.0, 0.0)];
+    let knots = clamped_uniform_knots(points.len());
+    let mut segments = Vec::new();
+    bspline_to_beziers(&points, &knots, &mut |s| segments.push(*s));
+    assert_eq!(segments.len(), 2);
+    assert_approx_eq(segments[0].from, points[0]);
+    assert_approx_eq(segments[0].ctrl1, points[1]);
+    assert_approx_eq(segments[1].ctrl2, points[3]);
+    assert_approx_eq(segments[1].to, points[4]);
+    assert_approx_eq(segments[0].to, segments[1].from);
+}
+
+#[test]
+fn nurbs_circle_quadrant() {
+    // A quarter of a unit circle as a rational quadratic bézier curve, elevated to cubic.
+    let w = (0.5f64).sqrt();
+    let (p0, p1, p2) = ([1.0, 0.0, 1.0], [w, w, w], [0.0, 1.0, 1.0]);
+    let elevate = |a: [f64; 3], b: [f64; 3]| [
+        (a[0] + 2.0 * b[0]) / 3.0,
+        (a[1] + 2.0 * b[1]) / 3.0,
+        (a[2] + 2.0 * b[2]) / 3.0,
+    ];
+    let h = [p0, elevate(p0, p1), elevate(p2, p1), p2];
+    let points: Vec<Point<f64>> = h.iter().map(|h| point(h[0] / h[2], h[1] / h[2])).collect();
+    let weights: Vec<f64> = h.iter().map(|h| h[2]).collect();
+    let knots = clamped_uniform_knots(4);
+
+    let tolerance = 0.0001;
+    let mut segments = Vec::new();
+    nurbs_to_beziers(&points, &weights, &knots, tolerance, &mut |s| segments.push(*s));
+    assert!(segments.len() > 1);
+    for segment in &segments {
+        for i in 0..11 {
+            let p = segment.sample(i as f64 / 10.0);
+            assert!((p.to_vector().length() - 1.0).abs() < tolerance * 2.0);
+        }
+    }
+    assert_eq!(segments[0].from, point(1.0, 0.0));
+    assert_eq!(segments[segments.len() - 1].to, point(0.0, 1.0));
+}
@@ -0,0 +1,259 @@
+//! Circles and ellipses.
+
+use scalar::{Scalar, Float};
+use generic_math::{Point, Vector, vector, Angle, Rect, Size, Rotation2D};
+use arrayvec::ArrayVec;
+use {Arc, Line, LineSegment};
+
+/// A circle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Circle<S> {
+    pub center: Point<S>,
+    pub radius: S,
+}
+
+impl<S: Scalar> Circle<S> {
+    #[inline]
+    pub fn new(center: Point<S>, radius: S) -> Self {
+        Circle { center, radius }
+    }
+
+    /// Returns whether the point is inside the circle (or on its edge).
+    #[inline]
+    pub fn contains_point(&self, p: Point<S>) -> bool {
+        (p - self.center).square_length() <= self.radius * self.radius
+    }
+
+    pub fn bounding_rect(&self) -> Rect<S> {
+        Rect::new(
+            self.center - vector(self.radius, self.radius),
+            Size::new(self.radius * S::TWO, self.radius * S::TWO),
+        )
+    }
+
+    /// Returns an arc going around the circle, starting on the positive x axis.
+    pub fn to_arc(&self) -> Arc<S> {
+        Arc::circle(self.center, self.radius)
+    }
+
+    pub fn to_ellipse(&self) -> Ellipse<S> {
+        Ellipse {
+            center: self.center,
+            radii: vector(self.radius, self.radius),
+            x_rotation: Angle::zero(),
+        }
+    }
+
+    /// Computes the intersection points (if any) between the circle and a line, in the
+    /// order of the line's direction.
+    pub fn line_intersections(&self, line: &Line<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut result = ArrayVec::new();
+        for t in self.line_intersections_t(line.point, line.vector) {
+            result.push(line.point + line.vector * t);
+        }
+
+        result
+    }
+
+    /// Computes the intersection points (if any) between the circle and a line segment,
+    /// in the order of the segment's direction.
+    pub fn line_segment_intersections(&self, segment: &LineSegment<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut result = ArrayVec::new();
+        for t in self.line_intersections_t(segment.from, segment.to_vector()) {
+            if t >= S::ZERO && t <= S::ONE {
+                result.push(segment.sample(t));
+            }
+        }
+
+        result
+    }
+
+    /// Computes the intersection points (if any) between two circles.
+    ///
+    /// Concentric circles don't have intersections, even if they have the same radius.
+    pub fn circle_intersections(&self, other: &Circle<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut result = ArrayVec::new();
+
+        let v = other.center - self.center;
+        let d = v.length();
+        if d < S::EPSILON
+            || d > self.radius + other.radius
+            || d < S::abs(self.radius - other.radius) {
+            return result;
+        }
+
+        // Distance from this circle's center to the line going through the intersections.
+        let a = (self.radius * self.radius - other.radius * other.radius + d * d) / (S::TWO * d);
+        let h = S::sqrt(S::max(self.radius * self.radius - a * a, S::ZERO));
+        let mid = self.center + v * (a / d);
+        let n = vector(-v.y, v.x) * (h / d);
+
+        result.push(mid - n);
+        if h > S::ZERO {
+            result.push(mid + n);
+        }
+
+        result
+    }
+
+    /// Returns the two lines going through a point and tangent to the circle, as segments
+    /// going from the point to where they touch the circle.
+    ///
+    /// Returns `None` if the point is inside the circle.
+    pub fn tangents_from_point(&self, p: Point<S>) -> Option<(LineSegment<S>, LineSegment<S>)> {
+        let v = p - self.center;
+        let d2 = v.square_length();
+        let r2 = self.radius * self.radius;
+        if d2 < r2 || d2 == S::ZERO {
+            return None;
+        }
+
+        let base = self.center + v * (r2 / d2);
+        let n = vector(-v.y, v.x) * (self.radius * S::sqrt(d2 - r2) / d2);
+
+        Some((
+            LineSegment { from: p, to: base + n },
+            LineSegment { from: p, to: base - n },
+        ))
+    }
+
+    // Solves |from + t * v - center| = radius.
+    fn line_intersections_t(&self, from: Point<S>, v: Vector<S>) -> ArrayVec<[S; 2]> {
+        let mut result = ArrayVec::new();
+
+        let a = v.square_length();
+        if a < S::EPSILON {
+            return result;
+        }
+
+        let w = from - self.center;
+        let half_b = v.dot(w);
+        let c = w.square_length() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < S::ZERO {
+            return result;
+        }
+
+        let sqrt = S::sqrt(discriminant);
+        result.push((-half_b - sqrt) / a);
+        if sqrt > S::ZERO {
+            result.push((-half_b + sqrt) / a);
+        }
+
+        result
+    }
+}
+
+/// An ellipse.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Ellipse<S> {
+    pub center: Point<S>,
+    pub radii: Vector<S>,
+    pub x_rotation: Angle<S>,
+}
+
+impl<S: Scalar> Ellipse<S> {
+    /// Returns whether the point is inside the ellipse (or on its edge).
+    pub fn contains_point(&self, p: Point<S>) -> bool {
+        let local = Rotation2D::new(-self.x_rotation).transform_vector(&(p - self.center));
+        let x = local.x / self.radii.x;
+        let y = local.y / self.radii.y;
+
+        x * x + y * y <= S::ONE
+    }
+
+    pub fn bounding_rect(&self) -> Rect<S> {
+        let (sin, cos) = Float::sin_cos(self.x_rotation.radians);
+        let rx = self.radii.x;
+        let ry = self.radii.y;
+        let half_size = vector(
+            S::sqrt(rx * rx * cos * cos + ry * ry * sin * sin),
+            S::sqrt(rx * rx * sin * sin + ry * ry * cos * cos),
+        );
+
+        Rect::new(self.center - half_size, (half_size * S::TWO).to_size())
+    }
+
+    /// Returns an arc going around the ellipse, starting at the end of the first radius.
+    pub fn to_arc(&self) -> Arc<S> {
+        Arc {
+            center: self.center,
+            radii: self.radii,
+            start_angle: Angle::zero(),
+            sweep_angle: Angle::two_pi(),
+            x_rotation: self.x_rotation,
+        }
+    }
+
+    /// Computes the intersection points (if any) between the ellipse and a line.
+    pub fn line_intersections(&self, line: &Line<S>) -> ArrayVec<[Point<S>; 2]> {
+        self.to_arc().line_intersections(line)
+    }
+
+    /// Computes the intersection points (if any) between the ellipse and a line segment.
+    pub fn line_segment_intersections(&self, segment: &LineSegment<S>) -> ArrayVec<[Point<S>; 2]> {
+        self.to_arc().line_segment_intersections(segment)
+    }
+}
+
+#[test]
+fn circle_intersections() {
+    use generic_math::point;
+
+    let circle = Circle::new(point(1.0f64, 1.0), 2.0);
+    assert!(circle.contains_point(point(2.0, 2.0)));
+    assert!(!circle.contains_point(point(3.0, 3.0)));
+
+    let line = Line { point: point(-5.0, 1.0), vector: vector(1.0, 0.0) };
+    let points = circle.line_intersections(&line);
+    assert_eq!(points.as_slice(), &[point(-1.0, 1.0), point(3.0, 1.0)]);
+
+    let segment = LineSegment { from: point(1.0, 1.0), to: point(1.0, 10.0) };
+    let points = circle.line_segment_intersections(&segment);
+    assert_eq!(points.len(), 1);
+    assert!((points[0] - point(1.0, 3.0)).length() < 1e-9);
+
+    let other = Circle::new(point(3.0, 1.0), 2.0);
+    let points = circle.circle_intersections(&other);
+    assert_eq!(points.len(), 2);
+    for p in &points {
+        assert!(((*p - circle.center).length() - 2.0).abs() < 1e-9);
+        assert!(((*p - other.center).length() - 2.0).abs() < 1e-9);
+    }
+    assert!(circle.circle_intersections(&Circle::new(point(10.0, 1.0), 1.0)).is_empty());
+    assert!(circle.circle_intersections(&Circle::new(point(1.0, 1.0), 1.0)).is_empty());
+
+    let (a, b) = circle.tangents_from_point(point(1.0, 5.0)).unwrap();
+    for tangent in &[a, b] {
+        assert!(((tangent.to - circle.center).length() - 2.0).abs() < 1e-9);
+        assert!((tangent.to - circle.center).dot(tangent.to_vector()).abs() < 1e-9);
+    }
+    assert!(circle.tangents_from_point(point(1.5, 1.0)).is_none());
+}
+
+#[test]
+fn ellipse() {
+    use generic_math::point;
+
+    let ellipse = Ellipse {
+        center: point(0.0f64, 0.0),
+        radii: vector(4.0, 1.0),
+        x_rotation: Angle::radians(::std::f64::consts::FRAC_PI_2),
+    };
+
+    assert!(ellipse.contains_point(point(0.0, 3.5)));
+    assert!(!ellipse.contains_point(point(3.5, 0.0)));
+
+    let rect = ellipse.bounding_rect();
+    assert!((rect.size.width - 2.0).abs() < 1e-9);
+    assert!((rect.size.height - 8.0).abs() < 1e-9);
+
+    let line = Line { point: point(0.0, 0.0), vector: vector(1.0, 0.0) };
+    let points = ellipse.line_intersections(&line);
+    assert_eq!(points.len(), 2);
+    for p in &points {
+        assert!((p.x.abs() - 1.0).abs() < 1e-9);
+    }
+}
@@ -2,11 +2,11 @@ use {Line, LineSegment, LineEquation};
 use scalar::Scalar;
 use generic_math::{Point, Vector, Rect, rect, Transform2D};
 use arrayvec::ArrayVec;
-use flatten_cubic::{flatten_cubic_bezier, find_cubic_bezier_inflection_points};
+use flatten_cubic::{flatten_cubic_bezier, flatten_cubic_bezier_with_t, find_cubic_bezier_inflection_points};
 pub use flatten_cubic::Flattened;
 use cubic_to_quadratic::*;
 use monotonic::Monotonic;
-use utils::{min_max, cubic_polynomial_roots};
+use utils::{min_max, cubic_polynomial_roots, for_each_t_range_inside_rect};
 use segment::{Segment, FlattenedForEach, approximate_length_from_flattening, BoundingRect};
 use QuadraticBezierSegment;
 
@@ -178,6 +178,41 @@ impl<S: Scalar> CubicBezierSegment<S> {
             && line.distance_to_point(&self.ctrl2) < tolerance
     }
 
+    /// Returns true if the curve is contained in a circle of radius `tolerance`
+    /// around its start point.
+    pub fn is_a_point(&self, tolerance: S) -> bool {
+        let tolerance_squared = tolerance * tolerance;
+        // Use <= so that tolerance can be zero.
+        (self.from - self.to).square_length() <= tolerance_squared
+            && (self.from - self.ctrl1).square_length() <= tolerance_squared
+            && (self.from - self.ctrl2).square_length() <= tolerance_squared
+    }
+
+    /// Returns the baseline of the curve if the curve can be approximated with it
+    /// within the tolerance threshold.
+    ///
+    /// Unlike `is_linear`, this returns `None` for curves that are straight but overshoot
+    /// their end points.
+    pub fn to_line_segment_if_linear(&self, tolerance: S) -> Option<LineSegment<S>> {
+        let baseline = self.baseline();
+        if baseline.is_close_to_point(&self.ctrl1, tolerance)
+            && baseline.is_close_to_point(&self.ctrl2, tolerance) {
+            return Some(baseline);
+        }
+
+        None
+    }
+
+    /// Returns a quadratic bézier curve approximating this curve if the approximation
+    /// error is within the tolerance threshold.
+    pub fn to_quadratic_if_close(&self, tolerance: S) -> Option<QuadraticBezierSegment<S>> {
+        if single_curve_approximation_error(self) <= tolerance {
+            return Some(single_curve_approximation(self));
+        }
+
+        None
+    }
+
     /// Computes a "fat line" of this segment.
     ///
     /// A fat line is two convervative lines between which the segment
@@ -289,8 +324,22 @@ impl<S: Scalar> CubicBezierSegment<S> {
         cb(t0..S::ONE);
     }
 
+    /// Invokes a callback for each monotonic part of the segment.
+    pub fn for_each_monotonic<F>(&self, cb: &mut F)
+    where
+        F: FnMut(&MonotonicCubicBezierSegment<S>)
+    {
+        self.for_each_monotonic_range(|range| {
+            cb(&self.split_range(range).assume_monotonic())
+        });
+    }
+
     /// Approximates the cubic bézier curve with sequence of quadratic ones,
     /// invoking a callback at each step.
+    ///
+    /// The maximum distance between the cubic curve and the quadratic ones is
+    /// guaranteed to be under the tolerance threshold, using the smallest number
+    /// of evenly distributed quadratic curves that satisfies this constraint.
     pub fn for_each_quadratic_bezier<F>(&self, tolerance: S, cb: &mut F)
     where
         F: FnMut(&QuadraticBezierSegment<S>)
@@ -312,16 +361,124 @@ impl<S: Scalar> CubicBezierSegment<S> {
         flatten_cubic_bezier(*self, tolerance, call_back);
     }
 
+    /// Iterates through the curve invoking a callback at each point, along with the
+    /// value of `t` at that point.
+    pub fn for_each_flattened_with_t<F: FnMut(Point<S>, S)>(&self, tolerance: S, call_back: &mut F) {
+        flatten_cubic_bezier_with_t(*self, tolerance, call_back);
+    }
+
     /// Compute the length of the segment using a flattened approximation.
     pub fn approximate_length(&self, tolerance: S) -> S {
         approximate_length_from_flattening(self, tolerance)
     }
 
+    /// Invokes a callback for each inflection point of the curve, passing the
+    /// parameter `t` of the inflection in increasing order.
+    ///
+    /// A curve that is entirely straight is reported as having a single inflection
+    /// point at `t = 0`.
     pub fn for_each_inflection_t<F>(&self, cb: &mut F)
     where F: FnMut(S) {
         find_cubic_bezier_inflection_points(self, cb);
     }
 
+    /// Returns the parameters `t` of the inflection points of the curve (at most two),
+    /// in increasing order.
+    ///
+    /// See `for_each_inflection_t`.
+    pub fn inflection_points_t(&self) -> ArrayVec<[S; 2]> {
+        let mut result = ArrayVec::new();
+        find_cubic_bezier_inflection_points(self, &mut|t| { result.push(t); });
+
+        result
+    }
+
+    /// Splits the curve at its inflection points.
+    ///
+    /// The sign of the curvature is consistent along each of the resulting sub-curves.
+    /// Returns the curve unchanged if it does not have inflection points.
+    pub fn split_at_inflections(&self) -> ArrayVec<[CubicBezierSegment<S>; 3]> {
+        let mut result = ArrayVec::new();
+        let mut t0 = S::ZERO;
+        for t in self.inflection_points_t() {
+            if t <= t0 || t >= S::ONE {
+                continue;
+            }
+            result.push(self.split_range(t0..t));
+            t0 = t;
+        }
+        result.push(self.split_range(t0..S::ONE));
+
+        result
+    }
+
+    /// Classifies the curve following the terminology of Loop and Blinn's
+    /// "Resolution Independent Curve Rendering using Programmable Graphics Hardware".
+    ///
+    /// The parameters provided with each variant are not restricted to the `0..1` range
+    /// and must be checked by the caller if only the visible part of the curve matters.
+    pub fn classify(&self) -> CubicBezierType<S> {
+        let scale = {
+            let r = self.fast_bounding_rect();
+            S::max(r.size.width, r.size.height)
+        };
+
+        if scale <= S::EPSILON {
+            return CubicBezierType::Point;
+        }
+
+        // The a1, a2 and a3 terms of the paper are the determinants of the
+        // homogeneous control points, which boil down to triangle areas.
+        let a1 = (self.to - self.from).cross(self.ctrl2 - self.from);
+        let a2 = (self.from - self.ctrl1).cross(self.to - self.ctrl1);
+        let a3 = (self.ctrl1 - self.ctrl2).cross(self.from - self.ctrl2);
+
+        let mut d1 = a1 - S::TWO * a2 + S::THREE * a3;
+        let mut d2 = S::THREE * a3 - a2;
+        let mut d3 = S::THREE * a3;
+
+        // The d terms scale with the square of the size of the curve.
+        let max = S::max(S::abs(d1), S::max(S::abs(d2), S::abs(d3)));
+        if max <= S::EPSILON * scale * scale {
+            return CubicBezierType::Line;
+        }
+
+        d1 /= max;
+        d2 /= max;
+        d3 /= max;
+
+        if S::abs(d1) <= S::EPSILON {
+            if S::abs(d2) <= S::EPSILON {
+                return CubicBezierType::Quadratic;
+            }
+
+            return CubicBezierType::CuspAtInfinity { t: d3 / (S::THREE * d2) };
+        }
+
+        let discriminant = S::THREE * d2 * d2 - S::FOUR * d1 * d3;
+
+        if S::abs(discriminant) <= S::EPSILON {
+            return CubicBezierType::Cusp { t: d2 / (S::TWO * d1) };
+        }
+
+        if discriminant > S::ZERO {
+            let sqrt = S::sqrt(S::THREE * discriminant);
+            let (t1, t2) = min_max(
+                (S::THREE * d2 - sqrt) / (S::SIX * d1),
+                (S::THREE * d2 + sqrt) / (S::SIX * d1),
+            );
+            return CubicBezierType::Serpentine { t1, t2 };
+        }
+
+        let sqrt = S::sqrt(-discriminant);
+        let (t1, t2) = min_max(
+            (d2 - sqrt) / (S::TWO * d1),
+            (d2 + sqrt) / (S::TWO * d1),
+        );
+
+        CubicBezierType::Loop { t1, t2 }
+    }
+
     /// Return local x extrema or None if this curve is monotonic.
     ///
     /// This returns the advancements along the curve, not the actual x position.
@@ -550,6 +707,59 @@ impl<S: Scalar> CubicBezierSegment<S> {
         self.is_x_monotonic() && self.is_y_monotonic()
     }
 
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// curve that have a given x coordinate.
+    ///
+    /// Unlike its `Monotonic` counterpart, this works with any curve.
+    pub fn solve_t_for_x(&self, x: S) -> ArrayVec<[S; 3]> {
+        Self::solve_t(self.from.x, self.ctrl1.x, self.ctrl2.x, self.to.x, x)
+    }
+
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// curve that have a given y coordinate.
+    ///
+    /// Unlike its `Monotonic` counterpart, this works with any curve.
+    pub fn solve_t_for_y(&self, y: S) -> ArrayVec<[S; 3]> {
+        Self::solve_t(self.from.y, self.ctrl1.y, self.ctrl2.y, self.to.y, y)
+    }
+
+    fn solve_t(from: S, ctrl1: S, ctrl2: S, to: S, value: S) -> ArrayVec<[S; 3]> {
+        let a = to - from + (ctrl1 - ctrl2) * S::THREE;
+        let b = (from + ctrl2 - ctrl1 * S::TWO) * S::THREE;
+        let c = (ctrl1 - from) * S::THREE;
+        let d = from - value;
+
+        let mut result: ArrayVec<[S; 3]> = ArrayVec::new();
+        for t in cubic_polynomial_roots(a, b, c, d) {
+            if t >= S::ZERO && t <= S::ONE {
+                result.push(t);
+            }
+        }
+
+        result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        result
+    }
+
+    /// Computes the parts of this curve that are inside a rectangle.
+    pub fn clip_to_rect(&self, rect: &Rect<S>) -> ArrayVec<[Self; 7]> {
+        let mut crossings: ArrayVec<[S; 12]> = ArrayVec::new();
+        crossings.extend(self.solve_t_for_x(rect.min_x()));
+        crossings.extend(self.solve_t_for_x(rect.max_x()));
+        crossings.extend(self.solve_t_for_y(rect.min_y()));
+        crossings.extend(self.solve_t_for_y(rect.max_y()));
+
+        let mut result = ArrayVec::new();
+        for_each_t_range_inside_rect(
+            &mut crossings,
+            rect,
+            |t| self.sample(t),
+            &mut |range| { result.push(self.split_range(range)); },
+        );
+
+        result
+    }
+
     /// Computes the intersections (if any) between this segment a line.
     ///
     /// The result is provided in the form of the `t` parameters of each
@@ -642,7 +852,11 @@ impl<S: Scalar> CubicBezierSegment<S> {
     }
 }
 
-impl<S: Scalar> Segment for CubicBezierSegment<S> { impl_segment!(S); }
+impl<S: Scalar> Segment for CubicBezierSegment<S> {
+    impl_segment!(S);
+
+    fn for_each_monotonic_t<F: FnMut(S)>(&self, cb: F) { self.for_each_monotonic_t(cb) }
+}
 
 impl<S: Scalar> BoundingRect for CubicBezierSegment<S> {
     type Scalar = S;
@@ -658,6 +872,29 @@ impl<S: Scalar> FlattenedForEach for CubicBezierSegment<S> {
     fn for_each_flattened<F: FnMut(Point<S>)>(&self, tolerance: S, call_back: &mut F) {
         self.for_each_flattened(tolerance, call_back);
     }
+
+    fn for_each_flattened_with_t<F: FnMut(Point<S>, S)>(&self, tolerance: S, call_back: &mut F) {
+        self.for_each_flattened_with_t(tolerance, call_back);
+    }
+}
+
+/// The different kinds of cubic bézier curves, see `CubicBezierSegment::classify`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CubicBezierType<S> {
+    /// The curve has two distinct inflection points at `t1` and `t2`.
+    Serpentine { t1: S, t2: S },
+    /// The curve intersects itself at `t1` and `t2`.
+    Loop { t1: S, t2: S },
+    /// The curve has a cusp at `t`.
+    Cusp { t: S },
+    /// The curve has a single inflection point at `t` (the other one is at infinity).
+    CuspAtInfinity { t: S },
+    /// The curve is a quadratic bézier curve expressed as a cubic one.
+    Quadratic,
+    /// All control points are aligned.
+    Line,
+    /// All control points are at the same position.
+    Point,
 }
 
 /// A monotonically increasing in x and y quadratic bézier curve segment
@@ -902,3 +1139,235 @@ fn test_monotonic() {
         assert!(sub_curve.is_monotonic());
     });
 }
+
+#[test]
+fn inflection_points() {
+    use math::point;
+
+    // An s-shaped curve with a single inflection in the middle.
+    let curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 0.0),
+        ctrl2: point(0.0, 1.0),
+        to: point(1.0, 1.0),
+    };
+
+    let inflections = curve.inflection_points_t();
+    assert_eq!(inflections.len(), 1);
+    assert!((inflections[0] - 0.5f32).abs() < 0.0001);
+
+    // A curve with a loop does not have inflection points.
+    let curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 5.0),
+        ctrl2: point(-5.0, 5.0),
+        to: point(5.0, 0.0),
+    };
+
+    assert!(curve.inflection_points_t().is_empty());
+    assert_eq!(curve.split_at_inflections().len(), 1);
+}
+
+#[test]
+fn split_at_inflections() {
+    use math::point;
+
+    let curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 0.0),
+        ctrl2: point(0.0, 1.0),
+        to: point(1.0, 1.0),
+    };
+
+    let pieces = curve.split_at_inflections();
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(pieces[0].from, curve.from);
+    assert_eq!(pieces[1].to, curve.to);
+    assert!((pieces[0].to - pieces[1].from).length() < 0.0001);
+
+    for piece in pieces.iter() {
+        // The sign of the curvature should not change within a piece.
+        let mut sign = 0.0f32;
+        for i in 1..10 {
+            let t = i as f32 / 10.0;
+            let d1 = piece.derivative(t);
+            let d2 = piece.derivative(t + 0.001) - d1;
+            let cross = d1.cross(d2);
+            if sign != 0.0 {
+                assert!(cross * sign >= 0.0);
+            }
+            sign = cross.signum();
+        }
+    }
+}
+
+#[test]
+fn classify() {
+    use math::point;
+
+    let serpentine = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(3.0, 2.0),
+        ctrl2: point(5.0, -4.0),
+        to: point(9.0, 1.0),
+    };
+    match serpentine.classify() {
+        CubicBezierType::Serpentine { t1, t2 } => {
+            assert!((t1 + 10.6145f32).abs() < 0.001);
+            assert!((t2 - 0.4145f32).abs() < 0.001);
+        }
+        other => { panic!("{:?}", other); }
+    }
+
+    let looping = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 5.0),
+        ctrl2: point(-5.0, 5.0),
+        to: point(5.0, 0.0),
+    };
+    match looping.classify() {
+        CubicBezierType::Loop { t1, t2 } => {
+            assert!(t1 < t2);
+            assert!((looping.sample(t1) - looping.sample(t2)).length() < 0.001);
+        }
+        other => { panic!("{:?}", other); }
+    }
+
+    let cusp = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(2.0, 2.0),
+        ctrl2: point(0.0, 2.0),
+        to: point(2.0, 0.0),
+    };
+    match cusp.classify() {
+        CubicBezierType::Cusp { t } => { assert!((t - 0.5f32).abs() < 0.001); }
+        other => { panic!("{:?}", other); }
+    }
+
+    let s_curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 0.0),
+        ctrl2: point(0.0, 1.0),
+        to: point(1.0, 1.0),
+    };
+    match s_curve.classify() {
+        CubicBezierType::CuspAtInfinity { t } => { assert!((t - 0.5f32).abs() < 0.001); }
+        other => { panic!("{:?}", other); }
+    }
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(1.5, 3.0),
+        to: point(3.0, 0.0),
+    };
+    assert_eq!(quadratic.to_cubic().classify(), CubicBezierType::Quadratic);
+
+    let line = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 1.0),
+        ctrl2: point(3.0, 3.0),
+        to: point(2.0, 2.0),
+    };
+    assert_eq!(line.classify(), CubicBezierType::Line);
+
+    let p = point(1.0, 1.0);
+    let degenerate = CubicBezierSegment { from: p, ctrl1: p, ctrl2: p, to: p };
+    assert_eq!(degenerate.classify(), CubicBezierType::Point);
+}
+
+#[test]
+fn degree_reduction() {
+    use math::point;
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(1.0, 2.0),
+        ctrl: point(10.0, 5.0),
+        to: point(0.0, 1.0),
+    };
+
+    let cubic = quadratic.to_cubic();
+    let reduced = cubic.to_quadratic_if_close(0.001).unwrap();
+    assert!((reduced.ctrl - quadratic.ctrl).length() < 0.001);
+    assert!(cubic.to_line_segment_if_linear(0.001).is_none());
+    assert!(!cubic.is_a_point(0.001));
+
+    let s_curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 0.0),
+        ctrl2: point(0.0, 10.0),
+        to: point(10.0, 10.0),
+    };
+    assert!(s_curve.to_quadratic_if_close(0.1).is_none());
+
+    let line = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 1.0),
+        ctrl2: point(2.0, 2.0),
+        to: point(3.0, 3.0),
+    };
+    assert_eq!(
+        line.to_line_segment_if_linear(0.001),
+        Some(LineSegment { from: line.from, to: line.to })
+    );
+
+    let p = point(1.0, 1.0);
+    let degenerate = CubicBezierSegment { from: p, ctrl1: p, ctrl2: p, to: p };
+    assert!(degenerate.is_a_point(0.0));
+}
+
+#[test]
+fn solve_t_for_x_and_y() {
+    let curve = CubicBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl1: Point::new(1.0, 1.0),
+        ctrl2: Point::new(-1.0, 2.0),
+        to: Point::new(0.0, 3.0),
+    };
+
+    let t = curve.solve_t_for_x(0.1);
+    assert_eq!(t.len(), 2);
+    assert!(t[0] < t[1]);
+    for t in t {
+        assert!((curve.x(t) - 0.1f32).abs() < 0.0001);
+    }
+
+    let t = curve.solve_t_for_y(1.5);
+    assert_eq!(t.len(), 1);
+    assert!((t[0] - 0.5f32).abs() < 0.0001);
+
+    // A quadratic curve elevated to a cubic one.
+    let quadratic = QuadraticBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl: Point::new(10.0, 10.0),
+        to: Point::new(0.0, 20.0),
+    };
+    let t = quadratic.to_cubic().solve_t_for_x(3.2);
+    assert_eq!(t.len(), 2);
+    assert!((t[0] - 0.2f32).abs() < 0.0001);
+    assert!((t[1] - 0.8f32).abs() < 0.0001);
+}
+
+#[test]
+fn monotonic_solve_t_for_y() {
+    use math::point;
+
+    // Decreasing in y, with a vanishing derivative at the start.
+    let curve = CubicBezierSegment {
+        from: point(0.0, 10.0),
+        ctrl1: point(1.0, 10.0),
+        ctrl2: point(5.0, 3.0),
+        to: point(10.0, 0.0),
+    }.assume_monotonic();
+
+    let tolerance = 0.0001;
+    for i in 0..10u32 {
+        let y = i as f32;
+        let t = curve.solve_t_for_y(y, 0.0..1.0, tolerance);
+        assert!((curve.y(t) - y).abs() <= tolerance);
+    }
+
+    // Out of range values are clamped to the range.
+    assert_eq!(curve.solve_t_for_y(20.0, 0.2..0.8, tolerance), 0.2);
+    assert_eq!(curve.solve_t_for_y(-5.0, 0.2..0.8, tolerance), 0.8);
+    assert_eq!(curve.solve_t_for_x(-1.0, 0.0..1.0, tolerance), 0.0);
+}
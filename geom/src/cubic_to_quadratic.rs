@@ -1,10 +1,13 @@
-use scalar::Scalar;
+use scalar::{Scalar, cast};
 use CubicBezierSegment;
 use QuadraticBezierSegment;
 use monotonic::Monotonic;
 use math::point;
 
 /// Approximates a cubic bézier segment with a sequence of quadratic béziers.
+///
+/// The maximum distance between the cubic curve and its approximation is guaranteed
+/// to be under the tolerance threshold.
 pub fn cubic_to_quadratics<S: Scalar, F>(
     curve: &CubicBezierSegment<S>,
     tolerance: S,
@@ -15,20 +18,24 @@ where
 {
     debug_assert!(tolerance >= S::EPSILON);
 
-    let mut sub_curve = curve.clone();
-    let mut range = S::ZERO..S::ONE;
-    loop {
-        if single_curve_approximation_error(&sub_curve) <= tolerance {
-            cb(&single_curve_approximation(&sub_curve));
-            if range.end >= S::ONE {
-                return;
-            }
-            range.start = range.end;
-            range.end = S::ONE;
+    // The error of the single curve approximation over a sub-curve spanning an
+    // interval of length h along the curve is proportional to h³, which lets us
+    // directly compute the number of uniform subdivisions required to meet the
+    // tolerance threshold instead of bisecting until we get there.
+    let error = single_curve_approximation_error(curve);
+    let n = S::max(S::ceil(S::cbrt(error / tolerance)), S::ONE);
+    let num_quadratics: u32 = cast(n).unwrap_or(1);
+    let step = S::ONE / n;
+
+    let mut t0 = S::ZERO;
+    for i in 1..(num_quadratics + 1) {
+        let t1 = if i == num_quadratics {
+            S::ONE
         } else {
-            range.end = (range.start + range.end) * S::HALF;
-        }
-        sub_curve = curve.split_range(range.clone());
+            cast::<u32, S>(i).unwrap() * step
+        };
+        cb(&single_curve_approximation(&curve.split_range(t0..t1)));
+        t0 = t1;
     }
 }
 
@@ -188,3 +195,30 @@ fn test_cubic_to_monotonic_quadratics() {
     assert!(count < 10);
     assert!(count > 4);
 }
+
+#[test]
+fn test_cubic_to_quadratics_tolerance() {
+    let cubic = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(100.0, 0.0),
+        ctrl2: point(0.0, 100.0),
+        to: point(100.0, 100.0),
+    };
+
+    for &tolerance in &[0.01, 0.1, 1.0, 10.0] {
+        let mut quadratics = Vec::new();
+        cubic_to_quadratics(&cubic, tolerance, &mut|c| { quadratics.push(*c); });
+
+        // The quadratics should be evenly distributed along the cubic curve.
+        let n = quadratics.len();
+        for (i, quadratic) in quadratics.iter().enumerate() {
+            let t0 = i as f32 / n as f32;
+            let t1 = (i + 1) as f32 / n as f32;
+            for j in 0..10 {
+                let t = j as f32 / 9.0;
+                let d = (quadratic.sample(t) - cubic.sample(t0 + t * (t1 - t0))).length();
+                assert!(d <= tolerance * 1.01);
+            }
+        }
+    }
+}
@@ -11,6 +11,7 @@ use scalar::Scalar;
 use generic_math::Point;
 use arrayvec::ArrayVec;
 use std::mem::swap;
+use std::ops::Range;
 
 /// An iterator over a cubic bezier segment that yields line segments approximating the
 /// curve for a given approximation threshold.
@@ -113,6 +114,14 @@ impl<S: Scalar> Iterator for Flattened<S> {
 }
 
 pub fn flatten_cubic_bezier<S: Scalar, F: FnMut(Point<S>)>(
+    bezier: CubicBezierSegment<S>,
+    tolerance: S,
+    call_back: &mut F,
+) {
+    flatten_cubic_bezier_with_t(bezier, tolerance, &mut |p, _| call_back(p));
+}
+
+pub fn flatten_cubic_bezier_with_t<S: Scalar, F: FnMut(Point<S>, S)>(
     mut bezier: CubicBezierSegment<S>,
     tolerance: S,
     call_back: &mut F,
@@ -120,32 +129,38 @@ pub fn flatten_cubic_bezier<S: Scalar, F: FnMut(Point<S>)>(
     let mut inflections: ArrayVec<[S; 2]> = ArrayVec::new();
     find_cubic_bezier_inflection_points(&bezier, &mut|t| { inflections.push(t); });
 
+    // The t of the start of the remaining part of the curve.
+    let mut t0 = S::ZERO;
     if let Some(&t1) = inflections.get(0) {
-        bezier = flatten_including_inflection(&bezier, t1, tolerance, call_back);
+        bezier = flatten_including_inflection(&bezier, t1, tolerance, &mut t0, call_back);
         if let Some(&t2) = inflections.get(1) {
             // Adjust the second inflection since we removed the part before the
             // first inflection from the bezier curve.
             let t2 = (t2 - t1) / (S::ONE - t1);
-            bezier = flatten_including_inflection(&bezier, t2, tolerance, call_back);
+            bezier = flatten_including_inflection(&bezier, t2, tolerance, &mut t0, call_back);
         }
     }
 
-    flatten_cubic_no_inflection(bezier, tolerance, call_back);
+    flatten_cubic_no_inflection(bezier, tolerance, t0..S::ONE, call_back);
 }
 
 // Flatten the curve up to the the inflection point and its approximation range included.
-fn flatten_including_inflection<S: Scalar, F: FnMut(Point<S>)>(
+fn flatten_including_inflection<S: Scalar, F: FnMut(Point<S>, S)>(
     bezier: &CubicBezierSegment<S>,
     up_to_t: S,
     tolerance: S,
+    t0: &mut S,
     call_back: &mut F,
 ) -> CubicBezierSegment<S> {
     let (before, mut after) = bezier.split(up_to_t);
-    flatten_cubic_no_inflection(before, tolerance, call_back);
+    let split_t = *t0 + (S::ONE - *t0) * up_to_t;
+    flatten_cubic_no_inflection(before, tolerance, *t0..split_t, call_back);
+    *t0 = split_t;
 
     if let Some(tf) = inflection_approximation_range(&after, tolerance) {
         after = after.after_split(tf);
-        call_back(after.from);
+        *t0 = *t0 + (S::ONE - *t0) * tf;
+        call_back(after.from, *t0);
     }
 
     after
@@ -159,14 +174,18 @@ fn flatten_including_inflection<S: Scalar, F: FnMut(Point<S>)>(
 // equation of a cubic bezier curve is insignificantly small. This can
 // then be approximated by a quadratic equation for which the maximum
 // difference from a linear approximation can be much more easily determined.
-fn flatten_cubic_no_inflection<S: Scalar, F: FnMut(Point<S>)>(
+//
+// t_range is the range of the original curve that the bezier segment corresponds to.
+fn flatten_cubic_no_inflection<S: Scalar, F: FnMut(Point<S>, S)>(
     mut bezier: CubicBezierSegment<S>,
     tolerance: S,
+    t_range: Range<S>,
     call_back: &mut F,
 ) {
     let end = bezier.to;
 
     let mut t = S::ZERO;
+    let mut t0 = t_range.start;
     while t < S::ONE {
         t = no_inflection_flattening_step(&bezier, tolerance);
 
@@ -174,10 +193,11 @@ fn flatten_cubic_no_inflection<S: Scalar, F: FnMut(Point<S>)>(
             break;
         }
         bezier = bezier.after_split(t);
-        call_back(bezier.from);
+        t0 = t0 + (t_range.end - t0) * t;
+        call_back(bezier.from, t0);
     }
 
-    call_back(end);
+    call_back(end, t_range.end);
 }
 
 fn no_inflection_flattening_step<S: Scalar>(bezier: &CubicBezierSegment<S>, tolerance: S) -> S {
@@ -253,6 +273,7 @@ where
 
     fn in_range<S: Scalar>(t: S) -> bool { t >= S::ZERO && t < S::ONE }
 
+    // The inflections are the roots of a * t² + b * t + c.
     let discriminant = b * b - S::FOUR * a * c;
 
     if discriminant < S::ZERO {
@@ -270,7 +291,8 @@ where
     }
 
     let discriminant_sqrt = S::sqrt(discriminant);
-    let q = if b < S::ZERO { b - discriminant_sqrt } else { b + discriminant_sqrt } * S::EPSILON;
+    let sign = if b < S::ZERO { -S::ONE } else { S::ONE };
+    let q = -S::HALF * (b + sign * discriminant_sqrt);
 
     let mut first_inflection = q / a;
     let mut second_inflection = c / q;
@@ -0,0 +1,101 @@
+//! Conversions between the segment types of this crate and the ones of `kurbo`.
+//!
+//! This module requires the `kurbo` feature.
+//!
+//! Kurbo works with `f64` coordinates. The conversions are lossless for `f64` segments and
+//! round to the nearest value for `f32` segments.
+
+use kurbo;
+
+use scalar::{Scalar, cast};
+use generic_math::{Point, point};
+use {LineSegment, QuadraticBezierSegment, CubicBezierSegment};
+
+/// Converts a kurbo point.
+pub fn from_kurbo_point<S: Scalar>(p: kurbo::Point) -> Point<S> {
+    point(cast(p.x).unwrap(), cast(p.y).unwrap())
+}
+
+/// Converts a point into a kurbo point.
+pub fn to_kurbo_point<S: Scalar>(p: Point<S>) -> kurbo::Point {
+    kurbo::Point::new(p.x.to_f64().unwrap(), p.y.to_f64().unwrap())
+}
+
+impl<S: Scalar> From<kurbo::Line> for LineSegment<S> {
+    fn from(line: kurbo::Line) -> Self {
+        LineSegment {
+            from: from_kurbo_point(line.p0),
+            to: from_kurbo_point(line.p1),
+        }
+    }
+}
+
+impl<S: Scalar> From<LineSegment<S>> for kurbo::Line {
+    fn from(segment: LineSegment<S>) -> Self {
+        kurbo::Line::new(to_kurbo_point(segment.from), to_kurbo_point(segment.to))
+    }
+}
+
+impl<S: Scalar> From<kurbo::QuadBez> for QuadraticBezierSegment<S> {
+    fn from(curve: kurbo::QuadBez) -> Self {
+        QuadraticBezierSegment {
+            from: from_kurbo_point(curve.p0),
+            ctrl: from_kurbo_point(curve.p1),
+            to: from_kurbo_point(curve.p2),
+        }
+    }
+}
+
+impl<S: Scalar> From<QuadraticBezierSegment<S>> for kurbo::QuadBez {
+    fn from(curve: QuadraticBezierSegment<S>) -> Self {
+        kurbo::QuadBez::new(
+            to_kurbo_point(curve.from),
+            to_kurbo_point(curve.ctrl),
+            to_kurbo_point(curve.to),
+        )
+    }
+}
+
+impl<S: Scalar> From<kurbo::CubicBez> for CubicBezierSegment<S> {
+    fn from(curve: kurbo::CubicBez) -> Self {
+        CubicBezierSegment {
+            from: from_kurbo_point(curve.p0),
+            ctrl1: from_kurbo_point(curve.p1),
+            ctrl2: from_kurbo_point(curve.p2),
+            to: from_kurbo_point(curve.p3),
+        }
+    }
+}
+
+impl<S: Scalar> From<CubicBezierSegment<S>> for kurbo::CubicBez {
+    fn from(curve: CubicBezierSegment<S>) -> Self {
+        kurbo::CubicBez::new(
+            to_kurbo_point(curve.from),
+            to_kurbo_point(curve.ctrl1),
+            to_kurbo_point(curve.ctrl2),
+            to_kurbo_point(curve.to),
+        )
+    }
+}
+
+#[test]
+fn kurbo_segments() {
+    let cubic = CubicBezierSegment {
+        from: point(0.0f64, 0.1),
+        ctrl1: point(1.0, 2.0),
+        ctrl2: point(3.0, -1.0),
+        to: point(4.5, 0.0),
+    };
+    let kurbo_cubic: kurbo::CubicBez = cubic.into();
+    assert_eq!(kurbo_cubic.p3, kurbo::Point::new(4.5, 0.0));
+    let round_trip: CubicBezierSegment<f64> = kurbo_cubic.into();
+    assert_eq!(round_trip, cubic);
+
+    let quadratic: QuadraticBezierSegment<f32> = kurbo::QuadBez::new((0.0, 0.0), (1.0, 1.0), (2.0, 0.0)).into();
+    assert_eq!(quadratic.ctrl, point(1.0, 1.0));
+
+    let line = LineSegment { from: point(0.5f32, 1.0), to: point(2.0, 3.0) };
+    let kurbo_line: kurbo::Line = line.into();
+    let round_trip: LineSegment<f32> = kurbo_line.into();
+    assert_eq!(round_trip, line);
+}
@@ -12,6 +12,8 @@
 //! - lines and line segments,
 //! - quadratic and cubic bézier curves,
 //! - elliptic arcs,
+//! - circles and ellipses,
+//! - rectangles with rounded corners,
 //! - triangles.
 //!
 //! # Flattening
@@ -82,12 +84,24 @@ extern crate num_traits;
 #[macro_use]
 pub extern crate serde;
 
+#[cfg(feature = "kurbo")]
+pub extern crate kurbo;
+
 #[macro_use] mod segment;
 pub mod quadratic_bezier;
 pub mod cubic_bezier;
 pub mod arc;
 pub mod utils;
 pub mod cubic_to_quadratic;
+pub mod bspline;
+pub mod superellipse;
+pub mod spiral;
+pub mod circle;
+pub mod rounded_rect;
+pub mod predicates;
+pub mod typed;
+#[cfg(feature = "kurbo")]
+pub mod kurbo_interop;
 mod flatten_cubic;
 mod triangle;
 mod line;
@@ -100,10 +114,17 @@ pub use cubic_bezier::CubicBezierSegment;
 #[doc(inline)]
 pub use triangle::{Triangle};
 #[doc(inline)]
-pub use line::{LineSegment, Line, LineEquation};
+pub use line::{LineSegment, Line, LineEquation, LineSide};
 #[doc(inline)]
 pub use arc::{Arc, SvgArc, ArcFlags};
 #[doc(inline)]
+pub use superellipse::Superellipse;
+pub use spiral::{ArchimedeanSpiral, LogarithmicSpiral, CircleInvolute};
+#[doc(inline)]
+pub use circle::{Circle, Ellipse};
+#[doc(inline)]
+pub use rounded_rect::{RoundedRect, CornerRadii};
+#[doc(inline)]
 pub use segment::Segment;
 #[doc(inline)]
 pub use monotonic::Monotonic;
@@ -254,6 +275,21 @@ pub mod math {
     /// An angle in radians (f32).
     pub type Angle = euclid::Angle<f32>;
 
+    /// Alias for ```euclid::TypedPoint2D<f32, Unit>```.
+    pub type TypedPoint<Unit> = euclid::TypedPoint2D<f32, Unit>;
+
+    /// Alias for ```euclid::TypedVector2D<f32, Unit>```.
+    pub type TypedVector<Unit> = euclid::TypedVector2D<f32, Unit>;
+
+    /// Alias for ```euclid::TypedSize2D<f32, Unit>```.
+    pub type TypedSize<Unit> = euclid::TypedSize2D<f32, Unit>;
+
+    /// Alias for ```euclid::TypedRect<f32, Unit>```.
+    pub type TypedRect<Unit> = euclid::TypedRect<f32, Unit>;
+
+    /// Alias for ```euclid::TypedTransform2D<f32, Src, Dst>```.
+    pub type TypedTransform2D<Src, Dst> = euclid::TypedTransform2D<f32, Src, Dst>;
+
     /// Shorthand for `Rect::new(Point::new(x, y), Size::new(w, h))`.
     pub use euclid::rect;
 
@@ -274,6 +310,5 @@ pub mod math {
 
 
 pub mod traits {
-    pub use segment::{Segment, FlattenedForEach, FlatteningStep};
-    //pub use monotonic::MonotonicSegment;
+    pub use segment::{Segment, FlattenedForEach, FlatteningStep, BoundingRect};
 }
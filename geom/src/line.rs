@@ -1,8 +1,8 @@
-use scalar::Scalar;
-use generic_math::{Point, point, Vector, vector, Rect, Size, Transform2D};
+use scalar::{Scalar, cast};
+use generic_math::{Point, point, Vector, vector, Rect, Size, Transform2D, Angle};
 use segment::{Segment, FlatteningStep, BoundingRect};
-use monotonic::MonotonicSegment;
 use utils::min_max;
+use predicates::orient2d;
 use std::mem::swap;
 
 use std::ops::Range;
@@ -120,16 +120,76 @@ impl<S: Scalar> LineSegment<S> {
         Rect::new(Point::new(min_x, min_y), Size::new(width, height))
     }
 
+    /// Returns the smallest range of x this segment is contained in.
     #[inline]
-    fn bounding_range_x(&self) -> (S, S) {
+    pub fn bounding_range_x(&self) -> (S, S) {
         min_max(self.from.x, self.to.x)
     }
 
+    /// Returns the smallest range of y this segment is contained in.
     #[inline]
-    fn bounding_range_y(&self) -> (S, S) {
+    pub fn bounding_range_y(&self) -> (S, S) {
         min_max(self.from.y, self.to.y)
     }
 
+    /// Returns true if the length of the segment is smaller than the tolerance threshold.
+    #[inline]
+    pub fn is_a_point(&self, tolerance: S) -> bool {
+        (self.to - self.from).square_length() <= tolerance * tolerance
+    }
+
+    /// Returns true if the distance between a point and this segment is smaller
+    /// than the tolerance threshold.
+    pub(crate) fn is_close_to_point(&self, p: &Point<S>, tolerance: S) -> bool {
+        let v = self.to - self.from;
+        let square_length = v.square_length();
+        let closest = if square_length <= S::EPSILON {
+            self.from
+        } else {
+            let t = (*p - self.from).dot(v) / square_length;
+            self.from + v * S::max(S::ZERO, S::min(t, S::ONE))
+        };
+
+        (*p - closest).square_length() <= tolerance * tolerance
+    }
+
+    /// Computes the part of this segment that is inside a rectangle, if any.
+    pub fn clip_to_rect(&self, rect: &Rect<S>) -> Option<Self> {
+        // Liang-Barsky.
+        let v = self.to_vector();
+        let mut t0 = S::ZERO;
+        let mut t1 = S::ONE;
+        let sides = [
+            (-v.x, self.from.x - rect.min_x()),
+            (v.x, rect.max_x() - self.from.x),
+            (-v.y, self.from.y - rect.min_y()),
+            (v.y, rect.max_y() - self.from.y),
+        ];
+        for &(p, q) in &sides {
+            if p == S::ZERO {
+                if q < S::ZERO {
+                    return None;
+                }
+                continue;
+            }
+
+            let r = q / p;
+            if p < S::ZERO {
+                if r > t1 {
+                    return None;
+                }
+                t0 = S::max(t0, r);
+            } else {
+                if r < t0 {
+                    return None;
+                }
+                t1 = S::min(t1, r);
+            }
+        }
+
+        Some(self.split_range(t0..t1))
+    }
+
     /// Returns the vector between this segment's `from` and `to` points.
     #[inline]
     pub fn to_vector(&self) -> Vector<S> {
@@ -180,17 +240,14 @@ impl<S: Scalar> LineSegment<S> {
     /// The result is provided in the form of the `t` parameter of each
     /// segment. To get the intersection point, sample one of the segments
     /// at the corresponding value.
+    ///
+    /// With the `robust_predicates` feature, this is `robust_intersection_t`.
     pub fn intersection_t(&self, other: &Self) -> Option<(S, S)> {
-        let (min1, max1) = self.bounding_range_x();
-        let (min2, max2) = other.bounding_range_x();
-        if min1 > max2 || max1 < min2 {
-            return None;
+        if cfg!(feature = "robust_predicates") {
+            return self.robust_intersection_t(other);
         }
 
-        if self.to == other.to
-            || self.from == other.from
-            || self.from == other.to
-            || self.to == other.from {
+        if !self.may_intersect(other) {
             return None;
         }
 
@@ -225,6 +282,53 @@ impl<S: Scalar> LineSegment<S> {
         ))
     }
 
+    /// Computes the intersection (if any) between this segment and another one, using
+    /// exact orientation predicates.
+    ///
+    /// Whether the segments intersect is decided exactly, which guarantees consistent
+    /// results for nearly collinear or nearly touching segments, regardless of the order
+    /// of the segments and of their endpoints. Only the position of the intersection is
+    /// subject to rounding errors. As with `intersection_t`, collinear segments and
+    /// segments sharing an endpoint are not considered intersecting.
+    ///
+    /// See the `predicates` module.
+    pub fn robust_intersection_t(&self, other: &Self) -> Option<(S, S)> {
+        if !self.may_intersect(other) {
+            return None;
+        }
+
+        let o1 = orient2d(self.from, self.to, other.from);
+        let o2 = orient2d(self.from, self.to, other.to);
+        let o3 = orient2d(other.from, other.to, self.from);
+        let o4 = orient2d(other.from, other.to, self.to);
+
+        if o1 == o2 || o3 == o4
+            || (o1 > 0.0 && o2 > 0.0) || (o1 < 0.0 && o2 < 0.0)
+            || (o3 > 0.0 && o4 > 0.0) || (o3 < 0.0 && o4 < 0.0) {
+            return None;
+        }
+
+        // The orientations are proportional to the distances of the endpoints to the
+        // other segment's line.
+        let t = o3 / (o3 - o4);
+        let u = o1 / (o1 - o2);
+
+        Some((cast(t).unwrap(), cast(u).unwrap()))
+    }
+
+    fn may_intersect(&self, other: &Self) -> bool {
+        let (min1, max1) = self.bounding_range_x();
+        let (min2, max2) = other.bounding_range_x();
+        if min1 > max2 || max1 < min2 {
+            return false;
+        }
+
+        !(self.to == other.to
+            || self.from == other.from
+            || self.from == other.to
+            || self.to == other.from)
+    }
+
     #[inline]
     pub fn intersection(&self, other: &Self) -> Option<Point<S>> {
         self.intersection_t(other).map(|(t, _)| self.sample(t))
@@ -344,6 +448,7 @@ impl<S: Scalar> Segment for LineSegment<S> {
     fn after_split(&self, t: S) -> Self { self.after_split(t) }
     fn flip(&self) -> Self { self.flip() }
     fn approximate_length(&self, _tolerance: S) -> S { self.length() }
+    fn for_each_monotonic_t<F: FnMut(S)>(&self, _cb: F) {}
 }
 
 impl<S: Scalar> BoundingRect for LineSegment<S> {
@@ -356,13 +461,6 @@ impl<S: Scalar> BoundingRect for LineSegment<S> {
     fn fast_bounding_range_y(&self) -> (S, S) { self.bounding_range_y() }
 }
 
-impl<S: Scalar> MonotonicSegment for LineSegment<S> {
-    type Scalar = S;
-    fn solve_t_for_x(&self, x: S, _t_range: Range<S>, _tolerance: S) -> S {
-        self.solve_t_for_x(x)
-    }
-}
-
 impl<S: Scalar> FlatteningStep for LineSegment<S> {
     fn flattening_step(&self, _tolerance: S) -> S { S::ONE }
 }
@@ -419,6 +517,69 @@ impl<S: Scalar> Line<S> {
 
         LineEquation::new(a, b, c)
     }
+
+    /// Returns the closest point on the line.
+    pub fn project_point(&self, p: &Point<S>) -> Point<S> {
+        let t = (*p - self.point).dot(self.vector) / self.vector.square_length();
+        self.point + self.vector * t
+    }
+
+    /// Returns on which side of the line a point is, considering points at a distance of
+    /// less than `epsilon` to be on the line.
+    pub fn side_of_point(&self, p: &Point<S>, epsilon: S) -> LineSide {
+        LineSide::from_signed_distance(self.signed_distance_to_point(p), epsilon)
+    }
+
+    /// Returns the signed angle going from the direction of this line to the direction of
+    /// another one, between `-π` and `π`.
+    pub fn angle_to(&self, other: &Self) -> Angle<S> {
+        Angle::radians(S::atan2(self.vector.cross(other.vector), self.vector.dot(other.vector)))
+    }
+
+    /// Returns the line parallel to this one, going through a point.
+    #[inline]
+    pub fn parallel_line(&self, p: &Point<S>) -> Self {
+        Line { point: *p, vector: self.vector }
+    }
+
+    /// Returns the line perpendicular to this one, going through a point.
+    ///
+    /// The direction of the new line is this line's direction rotated by 90 degrees
+    /// counter-clockwise (in a y-up coordinate system).
+    #[inline]
+    pub fn perpendicular_line(&self, p: &Point<S>) -> Self {
+        Line { point: *p, vector: vector(-self.vector.y, self.vector.x) }
+    }
+
+    /// Returns whether two lines are parallel, within an angle of `angle_epsilon` radians.
+    pub fn is_parallel(&self, other: &Self, angle_epsilon: S) -> bool {
+        let a = S::abs(self.angle_to(other).radians);
+        a <= angle_epsilon || S::PI() - a <= angle_epsilon
+    }
+}
+
+/// The side of a line on which a point is.
+///
+/// `Left` is the side of positive signed distances, which is on the left of the line's direction
+/// in a y-up coordinate system (and on the right in a y-down one).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum LineSide {
+    Left,
+    On,
+    Right,
+}
+
+impl LineSide {
+    fn from_signed_distance<S: Scalar>(d: S, epsilon: S) -> Self {
+        if d > epsilon {
+            LineSide::Left
+        } else if d < -epsilon {
+            LineSide::Right
+        } else {
+            LineSide::On
+        }
+    }
 }
 
 /// A line defined by the equation
@@ -454,11 +615,54 @@ impl<S: Scalar> LineEquation<S> {
         )
     }
 
+    /// Same as `project_point`.
+    #[inline]
+    pub fn projection_of_point(&self, p: &Point<S>) -> Point<S> {
+        self.project_point(p)
+    }
+
     #[inline]
     pub fn signed_distance_to_point(&self, p: &Point<S>) -> S {
         self.a * p.x + self.b * p.y + self.c
     }
 
+    /// Returns on which side of the line a point is, considering points at a distance of
+    /// less than `epsilon` to be on the line.
+    #[inline]
+    pub fn side_of_point(&self, p: &Point<S>, epsilon: S) -> LineSide {
+        LineSide::from_signed_distance(self.signed_distance_to_point(p), epsilon)
+    }
+
+    /// Returns the intersection of two lines, or `None` if they are parallel.
+    pub fn intersection(&self, other: &Self) -> Option<Point<S>> {
+        let det = self.a * other.b - self.b * other.a;
+        if S::abs(det) <= S::EPSILON {
+            return None;
+        }
+
+        Some(point(
+            (self.b * other.c - other.b * self.c) / det,
+            (other.a * self.c - self.a * other.c) / det,
+        ))
+    }
+
+    /// Returns the signed angle going from the tangent of this line to the tangent of
+    /// another one, between `-π` and `π`.
+    pub fn angle_to(&self, other: &Self) -> Angle<S> {
+        let t1 = self.tangent();
+        let t2 = other.tangent();
+        Angle::radians(S::atan2(t1.cross(t2), t1.dot(t2)))
+    }
+
+    /// Returns the line perpendicular to this one, going through a point.
+    #[inline]
+    pub fn perpendicular_line(&self, p: &Point<S>) -> Self {
+        // The normal of the new line is the tangent of this one.
+        let a = self.b;
+        let b = -self.a;
+        LineEquation { a, b, c: -(a * p.x + b * p.y) }
+    }
+
     #[inline]
     pub fn distance_to_point(&self, p: &Point<S>) -> S {
         S::abs(self.signed_distance_to_point(p))
@@ -810,4 +1014,85 @@ fn contains_segment() {
             }
         )
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn is_a_point() {
+    let s = LineSegment { from: point(1.0, 1.0), to: point(1.0, 1.001) };
+    assert!(s.is_a_point(0.01));
+    assert!(!s.is_a_point(0.0001));
+
+    let s = LineSegment { from: point(1.0, 1.0), to: point(1.0, 1.0) };
+    assert!(s.is_a_point(0.0));
+}
+
+#[test]
+fn clip_to_rect() {
+    let r = rect(0.0, 0.0, 10.0, 10.0);
+
+    let l = LineSegment { from: point(-5.0, 5.0), to: point(15.0, 5.0) };
+    assert_eq!(l.clip_to_rect(&r), Some(LineSegment { from: point(0.0, 5.0), to: point(10.0, 5.0) }));
+
+    let l = LineSegment { from: point(2.0, 2.0), to: point(3.0, 4.0) };
+    assert_eq!(l.clip_to_rect(&r), Some(l));
+
+    let l = LineSegment { from: point(-5.0, 6.0), to: point(4.0, 15.0) };
+    assert_eq!(l.clip_to_rect(&r), None);
+
+    let l = LineSegment { from: point(5.0, -5.0), to: point(5.0, 5.0) };
+    assert_eq!(l.clip_to_rect(&r), Some(LineSegment { from: point(5.0, 0.0), to: point(5.0, 5.0) }));
+}
+
+#[test]
+fn robust_intersection() {
+    let l1 = LineSegment { from: point(0.0f32, 0.0), to: point(2.0, 2.0) };
+    let l2 = LineSegment { from: point(0.0f32, 2.0), to: point(2.0, 0.0) };
+    let (t, u) = l1.robust_intersection_t(&l2).unwrap();
+    assert!((t - 0.5).abs() < 1e-6 && (u - 0.5).abs() < 1e-6);
+
+    // The endpoint of l4 is extremely close to l3, the result must not depend on the
+    // order or direction of the segments.
+    let l3 = LineSegment { from: point(0.1f64, 0.1), to: point(12.3, 12.3) };
+    for &y in &[0.7 - 1e-16, 0.7, 0.7 + 1e-16] {
+        let l4 = LineSegment { from: point(0.7f64, y), to: point(5.0, -3.0) };
+        let expected = l3.robust_intersection_t(&l4).is_some();
+        assert_eq!(l4.robust_intersection_t(&l3).is_some(), expected);
+        assert_eq!(l3.flip().robust_intersection_t(&l4).is_some(), expected);
+        assert_eq!(l3.robust_intersection_t(&l4.flip()).is_some(), expected);
+    }
+
+    // Collinear.
+    let l5 = LineSegment { from: point(1.0f32, 1.0), to: point(3.0, 3.0) };
+    assert!(l1.robust_intersection_t(&l5).is_none());
+    // Disjoint.
+    let l6 = LineSegment { from: point(3.0f32, 0.0), to: point(3.0, 5.0) };
+    assert!(l1.robust_intersection_t(&l6).is_none());
+}
+
+#[test]
+fn line_predicates() {
+    let line = Line { point: point(1.0f64, 1.0), vector: vector(2.0, 0.0) };
+    let p = point(3.0, 4.0);
+
+    assert_eq!(line.project_point(&p), point(3.0, 1.0));
+    assert!((line.equation().projection_of_point(&p) - point(3.0, 1.0)).length() < 1e-9);
+
+    assert_eq!(line.side_of_point(&p, 0.01), LineSide::Left);
+    assert_eq!(line.side_of_point(&point(3.0, 1.001), 0.01), LineSide::On);
+    assert_eq!(line.side_of_point(&point(3.0, 0.0), 0.01), LineSide::Right);
+    assert_eq!(line.equation().side_of_point(&p, 0.01), LineSide::Left);
+
+    let perpendicular = line.perpendicular_line(&p);
+    assert!((line.angle_to(&perpendicular).radians - ::std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    assert!((line.intersection(&perpendicular).unwrap() - point(3.0, 1.0)).length() < 1e-9);
+    assert!(line.is_parallel(&line.parallel_line(&p), 1e-6));
+    assert!(!line.is_parallel(&perpendicular, 1e-6));
+    assert!(line.is_parallel(&Line { point: p, vector: vector(-1.0, 0.0) }, 1e-6));
+
+    let eq = line.equation();
+    let eq_perpendicular = eq.perpendicular_line(&p);
+    assert!(eq_perpendicular.distance_to_point(&p) < 1e-9);
+    assert!((eq.intersection(&eq_perpendicular).unwrap() - point(3.0, 1.0)).length() < 1e-9);
+    assert!((eq.angle_to(&eq_perpendicular).radians.abs() - ::std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    assert!(eq.intersection(&eq.offset(1.0)).is_none());
+}
@@ -3,15 +3,10 @@ use scalar::{Scalar, NumCast};
 use generic_math::{Point, Vector, Rect};
 use std::ops::Range;
 use arrayvec::ArrayVec;
-use {QuadraticBezierSegment, CubicBezierSegment};
+use {QuadraticBezierSegment, CubicBezierSegment, Arc};
 
 use std::f64;
 
-pub(crate) trait MonotonicSegment {
-    type Scalar: Scalar;
-    fn solve_t_for_x(&self, x: Self::Scalar, t_range: Range<Self::Scalar>, tolerance: Self::Scalar) -> Self::Scalar;
-}
-
 /// A x and y monotonic curve segment, for example `Monotonic<QuadraticBezierSegment>`.
 #[derive(Copy, Clone, Debug)]
 pub struct Monotonic<T> {
@@ -64,7 +59,11 @@ impl<T: Segment> Monotonic<T> {
     }
 }
 
-impl<T: Segment> Segment for Monotonic<T> { impl_segment!(T::Scalar); }
+impl<T: Segment> Segment for Monotonic<T> {
+    impl_segment!(T::Scalar);
+
+    fn for_each_monotonic_t<F: FnMut(T::Scalar)>(&self, _cb: F) {}
+}
 
 impl<T: BoundingRect> BoundingRect for Monotonic<T> {
     type Scalar = T::Scalar;
@@ -90,59 +89,38 @@ impl<T: BoundingRect> BoundingRect for Monotonic<T> {
     }
 }
 
-impl<S: Scalar> Monotonic<QuadraticBezierSegment<S>> {
-    pub fn solve_t_for_x(&self, x: S) -> S {
-        Self::solve_t(
-            NumCast::from(self.segment.from.x).unwrap(),
-            NumCast::from(self.segment.ctrl.x).unwrap(),
-            NumCast::from(self.segment.to.x).unwrap(),
-            NumCast::from(x).unwrap(),
-        )
-    }
-
-    pub fn solve_t_for_y(&self, y: S) -> S {
-        Self::solve_t(
-            NumCast::from(self.segment.from.y).unwrap(),
-            NumCast::from(self.segment.ctrl.y).unwrap(),
-            NumCast::from(self.segment.to.y).unwrap(),
-            NumCast::from(y).unwrap(),
-        )
-    }
-
-    fn solve_t(from: f64, ctrl: f64, to: f64, x: f64) -> S {
-        let a = from - 2.0 * ctrl + to;
-        let b = -2.0 * from + 2.0 * ctrl;
-        let c = from - x;
-
-        let t = 2.0 * c / (-b - f64::sqrt(b * b - 4.0 * a * c));
-
-        NumCast::from(t.max(0.0).min(1.0)).unwrap()
-    }
-
-    #[inline]
-    pub fn split_at_x(&self, x: S) -> (Self, Self) {
-        self.split(self.solve_t_for_x(x))
-    }
-
-    pub fn intersections_t(
+impl<S: Scalar, T: Segment<Scalar=S>> Monotonic<T> {
+    /// Computes the intersections between two monotonic segments of any type.
+    ///
+    /// The result is provided in the form of pairs of `t` parameters (one for each
+    /// segment), sorted by the `t` parameter of this segment.
+    ///
+    /// The points at the returned parameters are at most `tolerance` away from each
+    /// other along each axis. Intersections that are closer than the tolerance to
+    /// each other are merged into one. If the segments overlap, some points
+    /// along the overlap are returned.
+    pub fn intersections_t<U: Segment<Scalar=S>>(
         &self, self_t_range: Range<S>,
-        other: &Self, other_t_range: Range<S>,
+        other: &Monotonic<U>, other_t_range: Range<S>,
         tolerance: S,
-    ) -> ArrayVec<[(S, S);2]> {
+    ) -> ArrayVec<[(S, S); 9]> {
         monotonic_segment_intersecions(
-            self, self_t_range,
-            other, other_t_range,
+            &self.segment, self_t_range,
+            &other.segment, other_t_range,
             tolerance
         )
     }
 
-    pub fn intersections(
+    /// Computes the intersection points between two monotonic segments of any type.
+    ///
+    /// See `intersections_t`.
+    pub fn intersections<U: Segment<Scalar=S>>(
         &self, self_t_range: Range<S>,
-        other: &Self, other_t_range: Range<S>,
+        other: &Monotonic<U>, other_t_range: Range<S>,
         tolerance: S,
-    ) -> ArrayVec<[Point<S>;2]> {
-        let intersections = monotonic_segment_intersecions(
-            self, self_t_range,
+    ) -> ArrayVec<[Point<S>; 9]> {
+        let intersections = self.intersections_t(
+            self_t_range,
             other, other_t_range,
             tolerance
         );
@@ -154,224 +132,349 @@ impl<S: Scalar> Monotonic<QuadraticBezierSegment<S>> {
         result
     }
 
-    pub fn first_intersection_t(
+    /// Computes the intersection with the smallest `t` parameter on this segment.
+    ///
+    /// See `intersections_t`.
+    pub fn first_intersection_t<U: Segment<Scalar=S>>(
         &self, self_t_range: Range<S>,
-        other: &Self, other_t_range: Range<S>,
+        other: &Monotonic<U>, other_t_range: Range<S>,
         tolerance: S,
     ) -> Option<(S, S)> {
-        first_monotonic_segment_intersecion(
-            self, self_t_range,
+        self.intersections_t(
+            self_t_range,
             other, other_t_range,
             tolerance
-        )
+        ).first().cloned()
     }
 
-    pub fn first_intersection(
+    /// Computes the intersection point with the smallest `t` parameter on this segment.
+    ///
+    /// See `intersections_t`.
+    pub fn first_intersection<U: Segment<Scalar=S>>(
         &self, self_t_range: Range<S>,
-        other: &Self, other_t_range: Range<S>,
+        other: &Monotonic<U>, other_t_range: Range<S>,
         tolerance: S,
     ) -> Option<Point<S>> {
-        first_monotonic_segment_intersecion(
-            self, self_t_range,
+        self.first_intersection_t(
+            self_t_range,
             other, other_t_range,
             tolerance
         ).map(|(t, _)|{ self.sample(t) })
     }
 }
 
-impl<S: Scalar> MonotonicSegment for Monotonic<QuadraticBezierSegment<S>> {
-    type Scalar = S;
-    fn solve_t_for_x(&self, x: S, _t_range: Range<S>, _tolerance: S) -> S {
-        self.solve_t_for_x(x)
+impl<S: Scalar> Monotonic<QuadraticBezierSegment<S>> {
+    pub fn solve_t_for_x(&self, x: S) -> S {
+        Self::solve_t(
+            NumCast::from(self.segment.from.x).unwrap(),
+            NumCast::from(self.segment.ctrl.x).unwrap(),
+            NumCast::from(self.segment.to.x).unwrap(),
+            NumCast::from(x).unwrap(),
+        )
     }
-}
 
-impl<S: Scalar> Monotonic<CubicBezierSegment<S>> {
-    pub fn solve_t_for_x(&self, x: S, t_range: Range<S>, tolerance: S) -> S {
-        debug_assert!(t_range.start <= t_range.end);
-        let from = self.x(t_range.start);
-        let to = self.x(t_range.end);
-        if x <= from {
-            return t_range.start;
-        }
-        if x >= to {
-            return t_range.end;
-        }
-
-        // Newton's method.
-        let mut t = x - from / (to - from);
-        for _ in 0..8 {
-            let x2 = self.x(t);
+    pub fn solve_t_for_y(&self, y: S) -> S {
+        Self::solve_t(
+            NumCast::from(self.segment.from.y).unwrap(),
+            NumCast::from(self.segment.ctrl.y).unwrap(),
+            NumCast::from(self.segment.to.y).unwrap(),
+            NumCast::from(y).unwrap(),
+        )
+    }
 
-            if S::abs(x2 - x) <= tolerance {
-                return t
-            }
+    fn solve_t(from: f64, ctrl: f64, to: f64, x: f64) -> S {
+        let a = from - 2.0 * ctrl + to;
+        let b = -2.0 * from + 2.0 * ctrl;
+        let c = from - x;
 
-            let dx = self.dx(t);
+        let t = 2.0 * c / (-b - f64::sqrt(b * b - 4.0 * a * c));
 
-            if dx <= S::EPSILON {
-                break
-            }
+        NumCast::from(t.max(0.0).min(1.0)).unwrap()
+    }
 
-            t = t - (x2 - x) / dx;
-        }
+    #[inline]
+    pub fn split_at_x(&self, x: S) -> (Self, Self) {
+        self.split(self.solve_t_for_x(x))
+    }
+}
 
-        // Fall back to binary search.
-        let mut min = t_range.start;
-        let mut max = t_range.end;
-        let mut t = S::HALF;
+impl<S: Scalar> Monotonic<CubicBezierSegment<S>> {
+    /// Finds the `t` parameter of the point that has a given x coordinate
+    /// within a range of `t`.
+    ///
+    /// If x is outside of the range, the closest end of the range is returned.
+    pub fn solve_t_for_x(&self, x: S, t_range: Range<S>, tolerance: S) -> S {
+        solve_t_monotonic(
+            x, t_range, tolerance,
+            |t| self.x(t),
+            |t| self.dx(t),
+        )
+    }
 
-        while min < max {
-            let x2 = self.x(t);
+    /// Finds the `t` parameter of the point that has a given y coordinate
+    /// within a range of `t`.
+    ///
+    /// If y is outside of the range, the closest end of the range is returned.
+    pub fn solve_t_for_y(&self, y: S, t_range: Range<S>, tolerance: S) -> S {
+        solve_t_monotonic(
+            y, t_range, tolerance,
+            |t| self.y(t),
+            |t| self.dy(t),
+        )
+    }
 
-            if S::abs(x2 - x) < tolerance {
-                return t;
-            }
+    #[inline]
+    pub fn split_at_x(&self, x: S) -> (Self, Self) {
+        // TODO tolerance param.
+        self.split(self.solve_t_for_x(x, S::ZERO..S::ONE, S::value(0.001)))
+    }
 
-            if x > x2 {
-                min = t;
-            } else {
-                max = t;
-            }
+    #[inline]
+    pub fn split_at_y(&self, y: S) -> (Self, Self) {
+        self.split(self.solve_t_for_y(y, S::ZERO..S::ONE, S::value(0.001)))
+    }
+}
 
-            t = (max - min) * S::HALF + min;
-        }
+impl<S: Scalar> Monotonic<Arc<S>> {
+    /// Finds the `t` parameter of the point that has a given x coordinate
+    /// within a range of `t`.
+    ///
+    /// If x is outside of the range, the closest end of the range is returned.
+    pub fn solve_t_for_x(&self, x: S, t_range: Range<S>, tolerance: S) -> S {
+        let sweep = self.segment.sweep_angle.radians;
+        solve_t_monotonic(
+            x, t_range, tolerance,
+            |t| self.x(t),
+            |t| self.segment.sample_tangent(t).x * sweep,
+        )
+    }
 
-        return t;
+    /// Finds the `t` parameter of the point that has a given y coordinate
+    /// within a range of `t`.
+    ///
+    /// If y is outside of the range, the closest end of the range is returned.
+    pub fn solve_t_for_y(&self, y: S, t_range: Range<S>, tolerance: S) -> S {
+        let sweep = self.segment.sweep_angle.radians;
+        solve_t_monotonic(
+            y, t_range, tolerance,
+            |t| self.y(t),
+            |t| self.segment.sample_tangent(t).y * sweep,
+        )
     }
 
     #[inline]
     pub fn split_at_x(&self, x: S) -> (Self, Self) {
-        // TODO tolerance param.
         self.split(self.solve_t_for_x(x, S::ZERO..S::ONE, S::value(0.001)))
     }
-}
 
-impl<S: Scalar> MonotonicSegment for Monotonic<CubicBezierSegment<S>> {
-    type Scalar = S;
-    fn solve_t_for_x(&self, x: S, t_range: Range<S>, tolerance: S) -> S {
-        self.solve_t_for_x(x, t_range, tolerance)
+    #[inline]
+    pub fn split_at_y(&self, y: S) -> (Self, Self) {
+        self.split(self.solve_t_for_y(y, S::ZERO..S::ONE, S::value(0.001)))
     }
 }
 
-/// Return the first intersection point (if any) of two monotonic curve
-/// segments.
+/// Finds the `t` for which `f(t) = value` with a monotonic function `f`,
+/// combining Newton's method with a bisection fallback.
 ///
-/// Both segments must be monotonically increasing in x.
-pub(crate) fn first_monotonic_segment_intersecion<S: Scalar, A, B>(
-    a: &A, a_t_range: Range<S>,
-    b: &B, b_t_range: Range<S>,
+/// Newton steps that leave the current bracket are replaced with bisection
+/// steps so that the bracket shrinks at every iteration, which guarantees
+/// convergence even where the derivative vanishes (for example at the end
+/// points of a monotonic piece).
+fn solve_t_monotonic<S, F, D>(
+    value: S,
+    t_range: Range<S>,
     tolerance: S,
-) -> Option<(S, S)>
+    f: F,
+    df: D,
+) -> S
 where
-    A: Segment<Scalar=S> + MonotonicSegment<Scalar=S> + BoundingRect<Scalar=S>,
-    B: Segment<Scalar=S> + MonotonicSegment<Scalar=S> + BoundingRect<Scalar=S>,
+    S: Scalar,
+    F: Fn(S) -> S,
+    D: Fn(S) -> S,
 {
-    debug_assert!(a.from().x <= a.to().x);
-    debug_assert!(b.from().x <= b.to().x);
-
-    // We need to have a stricter tolerance in solve_t_for_x otherwise
-    // the error accumulation becomes pretty bad.
-    let tx_tolerance = tolerance / S::TEN;
-
-    let (a_min, a_max) = a.split_range(a_t_range).fast_bounding_range_x();
-    let (b_min, b_max) = b.split_range(b_t_range).fast_bounding_range_x();
-
-    if a_min > b_max || a_max < b_min {
-        return None;
-    }
-
-    let mut min_x = S::max(a_min, b_min);
-    let mut max_x = S::min(a_max, b_max);
-
-    let mut t_min_a = a.solve_t_for_x(min_x, S::ZERO..S::ONE, tx_tolerance);
-    let mut t_max_a = a.solve_t_for_x(max_x, t_min_a..S::ONE, tx_tolerance);
-    let mut t_min_b = b.solve_t_for_x(min_x, S::ZERO..S::ONE, tx_tolerance);
-    let mut t_max_b = b.solve_t_for_x(max_x, t_min_b..S::ONE, tx_tolerance);
-
-    const MAX_ITERATIONS: u32 = 32;
-    for _ in 0..MAX_ITERATIONS {
-
-        let y_max_a = a.y(t_max_a);
-        let y_max_b = b.y(t_max_b);
-        // It would seem more sensible to use the mid point instead of
-        // the max point, but using the mid point means we don't know whether
-        // the approximation will be slightly before or slightly after the
-        // point.
-        // Using the max point ensures that the we return an approximation
-        // that is always slightly after the real intersection, which
-        // means that if we search for intersections after the one we
-        // found, we are not going to converge towards it again.
-        if S::abs(y_max_a - y_max_b) < tolerance {
-            return Some((t_max_a, t_max_b));
-        }
+    debug_assert!(t_range.start <= t_range.end);
+    let mut min = t_range.start;
+    let mut max = t_range.end;
+    let f_min = f(min);
+    let f_max = f(max);
+    let increasing = f_max >= f_min;
+
+    if (value <= f_min) == increasing {
+        return min;
+    }
+    if (value >= f_max) == increasing {
+        return max;
+    }
 
-        let mid_x = (min_x + max_x) * S::HALF;
-        let t_mid_a = a.solve_t_for_x(mid_x, t_min_a..t_max_a, tx_tolerance);
-        let t_mid_b = b.solve_t_for_x(mid_x, t_min_b..t_max_b, tx_tolerance);
+    let mut t = min + (max - min) * (value - f_min) / (f_max - f_min);
 
-        let y_mid_a = a.y(t_mid_a);
-        let y_min_a = a.y(t_min_a);
+    // Enough iterations for the bisection to exhaust the precision of f64.
+    for _ in 0..64 {
+        let v = f(t);
+        if S::abs(v - value) <= tolerance {
+            return t;
+        }
 
-        let y_mid_b = b.y(t_mid_b);
-        let y_min_b = b.y(t_min_b);
+        if (v < value) == increasing {
+            min = t;
+        } else {
+            max = t;
+        }
 
-        let min_sign = S::signum(y_min_a - y_min_b);
-        let mid_sign = S::signum(y_mid_a - y_mid_b);
-        let max_sign = S::signum(y_max_a - y_max_b);
+        let mut next = t - (v - value) / df(t);
+        if !(next > min && next < max) {
+            next = (min + max) * S::HALF;
+        }
 
-        if min_sign != mid_sign {
-            max_x = mid_x;
-            t_max_a = t_mid_a;
-            t_max_b = t_mid_b;
-        } else if max_sign != mid_sign {
-            min_x = mid_x;
-            t_min_a = t_mid_a;
-            t_min_b = t_mid_b;
-        } else {
-            // TODO: This is not always correct: if the min, max and mid
-            // points are all on the same side, we consider that there is
-            // no intersection, but there could be a pair of intersections
-            // between the min/max and the mid point.
+        if next == t {
             break;
         }
+
+        t = next;
     }
 
-    None
+    t
 }
 
-/// Return the intersection points (if any) of two monotonic curve
-/// segments.
+/// Computes the intersections of two segments that are monotonic in x and y
+/// by recursively subdividing them.
 ///
-/// Both segments must be monotonically increasing in x.
+/// The monotonicity guarantees that the bounding rectangle of any portion of a
+/// segment is given by its end points, so portions with disjoint rectangles are
+/// discarded early and the others are split until they are smaller than the
+/// tolerance.
 pub(crate) fn monotonic_segment_intersecions<S: Scalar, A, B>(
     a: &A, a_t_range: Range<S>,
     b: &B, b_t_range: Range<S>,
     tolerance: S,
-) -> ArrayVec<[(S, S); 2]>
+) -> ArrayVec<[(S, S); 9]>
 where
-    A: Segment<Scalar=S> + MonotonicSegment<Scalar=S> + BoundingRect<Scalar=S>,
-    B: Segment<Scalar=S> + MonotonicSegment<Scalar=S> + BoundingRect<Scalar=S>,
+    A: Segment<Scalar=S>,
+    B: Segment<Scalar=S>,
 {
-    let (t1, t2) = match first_monotonic_segment_intersecion(
-        a, a_t_range.clone(),
-        b, b_t_range.clone(),
-        tolerance
-    ) {
-        Some(intersection) => { intersection }
-        None => { return ArrayVec::new(); }
+    let mut result = ArrayVec::new();
+    subdivide_intersections(a, a_t_range, b, b_t_range, tolerance, 0, &mut result);
+    result.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    result
+}
+
+fn subdivide_intersections<S: Scalar, A, B>(
+    a: &A, a_t_range: Range<S>,
+    b: &B, b_t_range: Range<S>,
+    tolerance: S,
+    depth: u32,
+    result: &mut ArrayVec<[(S, S); 9]>,
+)
+where
+    A: Segment<Scalar=S>,
+    B: Segment<Scalar=S>,
+{
+    if result.is_full() {
+        return;
+    }
+
+    let a_rect = end_points_rect(a, &a_t_range);
+    let b_rect = end_points_rect(b, &b_t_range);
+
+    if a_rect.0.x > b_rect.1.x || a_rect.1.x < b_rect.0.x
+        || a_rect.0.y > b_rect.1.y || a_rect.1.y < b_rect.0.y {
+        return;
+    }
+
+    // Past a certain depth, we are at the limit of the floating point precision.
+    const MAX_DEPTH: u32 = 48;
+    // Both pieces are within their rectangle, so splitting them down to half of the
+    // tolerance keeps the intersection points within the tolerance of each other.
+    let split_a = depth < MAX_DEPTH && is_larger_than(&a_rect, tolerance * S::HALF);
+    let split_b = depth < MAX_DEPTH && is_larger_than(&b_rect, tolerance * S::HALF);
+
+    if !split_a && !split_b {
+        // Nearby pieces can have overlapping rectangles without crossing, especially
+        // if the segments intersect at a shallow angle, so the chords of the pieces
+        // decide whether there is an intersection.
+        if let Some((t_a, t_b)) = chord_intersection(a, &a_t_range, b, &b_t_range) {
+            // An intersection at the boundary between two pieces is found in both of
+            // them, and these are not necessarily processed one after the other.
+            let p = a.sample(t_a);
+            let duplicate = result.iter().any(|&(t, _)| {
+                let v = a.sample(t) - p;
+                S::abs(v.x) <= tolerance && S::abs(v.y) <= tolerance
+            });
+            if !duplicate {
+                result.push((t_a, t_b));
+            }
+        }
+        return;
+    }
+
+    let a_ranges = split_range_in_half(a_t_range, split_a);
+    let b_ranges = split_range_in_half(b_t_range, split_b);
+    for a_range in &a_ranges {
+        for b_range in &b_ranges {
+            subdivide_intersections(
+                a, a_range.clone(),
+                b, b_range.clone(),
+                tolerance,
+                depth + 1,
+                result,
+            );
+        }
+    }
+}
+
+fn chord_intersection<S: Scalar, A, B>(
+    a: &A, a_t_range: &Range<S>,
+    b: &B, b_t_range: &Range<S>,
+) -> Option<(S, S)>
+where
+    A: Segment<Scalar=S>,
+    B: Segment<Scalar=S>,
+{
+    let a_from = a.sample(a_t_range.start);
+    let b_from = b.sample(b_t_range.start);
+    let a_v = a.sample(a_t_range.end) - a_from;
+    let b_v = b.sample(b_t_range.end) - b_from;
+
+    let denom = a_v.cross(b_v);
+    let (ta, tb) = if denom == S::ZERO {
+        // Parallel chords with overlapping rectangles: the segments overlap.
+        (S::HALF, S::HALF)
+    } else {
+        let v = b_from - a_from;
+        let ta = v.cross(b_v) / denom;
+        let tb = v.cross(a_v) / denom;
+        if ta < S::ZERO || ta > S::ONE || tb < S::ZERO || tb > S::ONE {
+            return None;
+        }
+        (ta, tb)
     };
 
-    let mut result = ArrayVec::new();
-    result.push((t1, t2));
+    Some((
+        a_t_range.start + (a_t_range.end - a_t_range.start) * ta,
+        b_t_range.start + (b_t_range.end - b_t_range.start) * tb,
+    ))
+}
+
+fn end_points_rect<S: Scalar, T: Segment<Scalar=S>>(segment: &T, t_range: &Range<S>) -> (Point<S>, Point<S>) {
+    let from = segment.sample(t_range.start);
+    let to = segment.sample(t_range.end);
+
+    (Point::min(from, to), Point::max(from, to))
+}
+
+fn is_larger_than<S: Scalar>(rect: &(Point<S>, Point<S>), tolerance: S) -> bool {
+    rect.1.x - rect.0.x > tolerance || rect.1.y - rect.0.y > tolerance
+}
 
-    match first_monotonic_segment_intersecion(
-        a, t1..a_t_range.end,
-        b, t2..b_t_range.end,
-        tolerance
-    ) {
-        Some(intersection) => { result.push(intersection); }
-        None => {}
+fn split_range_in_half<S: Scalar>(range: Range<S>, split: bool) -> ArrayVec<[Range<S>; 2]> {
+    let mut result = ArrayVec::new();
+    if split {
+        let mid = (range.start + range.end) * S::HALF;
+        result.push(range.start..mid);
+        result.push(mid..range.end);
+    } else {
+        result.push(range);
     }
 
     result
@@ -403,3 +506,66 @@ fn two_intersections() {
     assert!(intersections[0].0 < 0.1, "{:?} < 0.1", intersections[0].0);
     assert!(intersections[1].1 > 0.9, "{:?} > 0.9", intersections[0].1);
 }
+
+#[test]
+fn intersections_between_segment_types() {
+    use math::{point, vector};
+    use generic_math::Angle;
+
+    // Decreasing in x.
+    let arc = Arc {
+        center: point(0.0, 0.0),
+        radii: vector(10.0, 10.0),
+        start_angle: Angle::radians(0.0),
+        sweep_angle: Angle::radians(::std::f32::consts::PI * 0.5),
+        x_rotation: Angle::radians(0.0),
+    }.assume_monotonic();
+
+    let cubic = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(3.0, 3.0),
+        ctrl2: point(6.0, 6.0),
+        to: point(10.0, 10.0),
+    }.assume_monotonic();
+
+    let tolerance = 0.001;
+    let intersections = arc.intersections_t(0.0..1.0, &cubic, 0.0..1.0, tolerance);
+    assert_eq!(intersections.len(), 1);
+    let (t1, t2) = intersections[0];
+    let expected = point(1.0, 1.0) * 10.0 * ::std::f32::consts::FRAC_1_SQRT_2;
+    assert!((arc.sample(t1) - expected).length() < 0.01);
+    assert!((cubic.sample(t2) - expected).length() < 0.01);
+
+    // Symmetric.
+    let intersections = cubic.intersections_t(0.0..1.0, &arc, 0.0..1.0, tolerance);
+    assert_eq!(intersections.len(), 1);
+
+    // No intersection.
+    assert!(arc.first_intersection_t(0.0..0.3, &cubic, 0.0..1.0, tolerance).is_none());
+}
+
+#[test]
+fn intersection_at_subdivision_boundary() {
+    use math::point;
+    use LineSegment;
+
+    // The curve crosses the line at the middle of the line, where the line is
+    // subdivided, and a second time before that.
+    let line = Monotonic {
+        segment: LineSegment { from: point(0.0, 0.0), to: point(10.0, 10.0) },
+    };
+    let curve = QuadraticBezierSegment {
+        from: point(7.0, 8.0),
+        ctrl: point(3.738095, 2.357143),
+        to: point(0.0, 1.0),
+    }.assume_monotonic();
+
+    let tolerance = 0.001f32;
+    let intersections = line.intersections_t(0.0..1.0, &curve, 0.0..1.0, tolerance);
+    assert_eq!(intersections.len(), 2);
+    for &(t1, t2) in &intersections {
+        let v = line.sample(t1) - curve.sample(t2);
+        assert!(v.x.abs() <= tolerance && v.y.abs() <= tolerance);
+    }
+    assert!((line.sample(intersections[1].0) - point(5.0, 5.0)).length() < 0.01);
+}
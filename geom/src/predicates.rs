@@ -0,0 +1,139 @@
+//! Robust geometric predicates.
+//!
+//! Floating point rounding errors make the naive orientation test return inconsistent
+//! results for nearly collinear points: the same three points may be reported as
+//! clockwise or counter-clockwise depending on the order in which they are passed.
+//! Algorithms that make topological decisions based on these tests (sweep lines,
+//! triangulations, etc.) can then end up in impossible states.
+//!
+//! The predicates in this module are computed with adaptive precision arithmetic as
+//! described in Jonathan Richard Shewchuk's paper "Adaptive Precision Floating-Point
+//! Arithmetic and Fast Robust Geometric Predicates": a fast approximation is used when
+//! it is provably correct and an exact computation otherwise. The computations are done
+//! with `f64` numbers, which represent `f32` coordinates exactly.
+//!
+//! The `robust_predicates` feature makes `LineSegment::intersection_t` and the functions
+//! based on it use these predicates.
+
+use scalar::Scalar;
+use generic_math::Point;
+
+// Bound on the relative error of the fast orientation test (see Shewchuk's paper).
+const ORIENT2D_ERROR_BOUND: f64 = (3.0 + 16.0 * f64::EPSILON * 0.5) * f64::EPSILON * 0.5;
+
+/// Returns a positive value if `a`, `b` and `c` are in counter-clockwise order (assuming
+/// the y axis points upwards), a negative value if they are in clockwise order and zero
+/// if they are collinear.
+///
+/// The sign of the result is exact. Its value approximates twice the signed area of the
+/// triangle.
+pub fn orient2d<S: Scalar>(a: Point<S>, b: Point<S>, c: Point<S>) -> f64 {
+    let (ax, ay) = (to_f64(a.x), to_f64(a.y));
+    let (bx, by) = (to_f64(b.x), to_f64(b.y));
+    let (cx, cy) = (to_f64(c.x), to_f64(c.y));
+
+    let left = (ax - cx) * (by - cy);
+    let right = (ay - cy) * (bx - cx);
+    let det = left - right;
+
+    if det.abs() >= ORIENT2D_ERROR_BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+
+    orient2d_exact(ax, ay, bx, by, cx, cy)
+}
+
+/// Returns true if the three points are exactly collinear.
+pub fn collinear<S: Scalar>(a: Point<S>, b: Point<S>, c: Point<S>) -> bool {
+    orient2d(a, b, c) == 0.0
+}
+
+fn orient2d_exact(ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64) -> f64 {
+    // Expanding the determinant, the terms in cx * cy cancel out.
+    let products = [
+        two_product(ax, by),
+        two_product(-ax, cy),
+        two_product(-cx, by),
+        two_product(-ay, bx),
+        two_product(ay, cx),
+        two_product(cy, bx),
+    ];
+
+    let mut expansion = Vec::with_capacity(12);
+    for &(hi, lo) in &products {
+        grow_expansion(&mut expansion, lo);
+        grow_expansion(&mut expansion, hi);
+    }
+
+    // The components are non-overlapping and sorted by increasing magnitude, so the sign
+    // of the sum is the sign of the last one.
+    expansion.last().cloned().unwrap_or(0.0)
+}
+
+// Exact sum of two numbers, as the rounded sum and the rounding error.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let error = (a - a_virtual) + (b - b_virtual);
+
+    (x, error)
+}
+
+// Exact product of two numbers, as the rounded product and the rounding error.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+
+    (x, a.mul_add(b, -x))
+}
+
+// Adds a number to an expansion, eliminating the zero components.
+fn grow_expansion(expansion: &mut Vec<f64>, value: f64) {
+    let mut q = value;
+    let mut n = 0;
+    for i in 0..expansion.len() {
+        let (sum, error) = two_sum(q, expansion[i]);
+        q = sum;
+        if error != 0.0 {
+            expansion[n] = error;
+            n += 1;
+        }
+    }
+    expansion.truncate(n);
+    if q != 0.0 {
+        expansion.push(q);
+    }
+}
+
+fn to_f64<S: Scalar>(v: S) -> f64 {
+    v.to_f64().unwrap()
+}
+
+#[test]
+fn nearly_collinear() {
+    use generic_math::point;
+
+    let q = point(12.0f64, 12.0);
+    let r = point(24.0f64, 24.0);
+    let ulp = 0.5 * f64::EPSILON;
+
+    let sign = |v: f64| if v > 0.0 { 1 } else if v < 0.0 { -1 } else { 0 };
+
+    // The naive computation gives inconsistent results for these points.
+    for i in -64..64 {
+        let p = point(0.5 + i as f64 * ulp, 0.5);
+        let expected = -sign(i as f64);
+
+        for &(a, b, c) in &[(p, q, r), (q, r, p), (r, p, q)] {
+            assert_eq!(sign(orient2d(a, b, c)), expected);
+            assert_eq!(sign(orient2d(a, c, b)), -expected);
+        }
+        assert_eq!(collinear(p, q, r), i == 0);
+    }
+
+    let a = point(0.0f32, 0.0);
+    let b = point(1.0f32, 0.0);
+    assert!(orient2d(a, b, point(0.5, 1e-30)) > 0.0);
+    assert!(orient2d(a, b, point(0.5, -1e-30)) < 0.0);
+    assert_eq!(orient2d(a, b, point(3.0, 0.0)), 0.0);
+}
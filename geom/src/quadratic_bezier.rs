@@ -5,6 +5,7 @@ use monotonic::Monotonic;
 use arrayvec::ArrayVec;
 use segment::{Segment, FlatteningStep, FlattenedForEach, BoundingRect};
 use segment;
+use utils::for_each_t_range_inside_rect;
 
 use std::ops::Range;
 use std::mem;
@@ -239,6 +240,29 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         line.distance_to_point(&self.ctrl) < tolerance
     }
 
+    /// Returns true if the curve is contained in a circle of radius `tolerance`
+    /// around its start point.
+    pub fn is_a_point(&self, tolerance: S) -> bool {
+        let tolerance_squared = tolerance * tolerance;
+        // Use <= so that tolerance can be zero.
+        (self.from - self.to).square_length() <= tolerance_squared
+            && (self.from - self.ctrl).square_length() <= tolerance_squared
+    }
+
+    /// Returns the baseline of the curve if the curve can be approximated with it
+    /// within the tolerance threshold.
+    ///
+    /// Unlike `is_linear`, this returns `None` for curves that are straight but overshoot
+    /// their end points.
+    pub fn to_line_segment_if_linear(&self, tolerance: S) -> Option<LineSegment<S>> {
+        let baseline = self.baseline();
+        if baseline.is_close_to_point(&self.ctrl, tolerance) {
+            return Some(baseline);
+        }
+
+        None
+    }
+
     /// Computes a "fat line" of this segment.
     ///
     /// A fat line is two convervative lines between which the segment
@@ -300,7 +324,7 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
     where
         F: FnMut(S),
     {
-        let mut t0 = self.local_x_extremum_t();
+        let mut t0 = self.local_y_extremum_t();
         let mut t1 = self.local_x_extremum_t();
 
         let swap = match (t0, t1) {
@@ -427,6 +451,77 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         self.is_x_monotonic() && self.is_y_monotonic()
     }
 
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// curve that have a given x coordinate.
+    ///
+    /// Unlike its `Monotonic` counterpart, this works with any curve.
+    pub fn solve_t_for_x(&self, x: S) -> ArrayVec<[S; 2]> {
+        Self::solve_t(self.from.x, self.ctrl.x, self.to.x, x)
+    }
+
+    /// Computes the `t` parameters (in increasing order) of the points of the
+    /// curve that have a given y coordinate.
+    ///
+    /// Unlike its `Monotonic` counterpart, this works with any curve.
+    pub fn solve_t_for_y(&self, y: S) -> ArrayVec<[S; 2]> {
+        Self::solve_t(self.from.y, self.ctrl.y, self.to.y, y)
+    }
+
+    fn solve_t(from: S, ctrl: S, to: S, value: S) -> ArrayVec<[S; 2]> {
+        let a = from - S::TWO * ctrl + to;
+        let b = S::TWO * (ctrl - from);
+        let c = from - value;
+
+        let mut roots: ArrayVec<[S; 2]> = ArrayVec::new();
+        if S::abs(a) < S::EPSILON {
+            if S::abs(b) >= S::EPSILON {
+                roots.push(-c / b);
+            }
+        } else {
+            let delta = b * b - S::FOUR * a * c;
+            if S::abs(delta) < S::EPSILON {
+                roots.push(-b / (S::TWO * a));
+            } else if delta > S::ZERO {
+                // Numerically stable form of the quadratic formula.
+                let sign = if b < S::ZERO { -S::ONE } else { S::ONE };
+                let q = -S::HALF * (b + sign * S::sqrt(delta));
+                roots.push(q / a);
+                roots.push(c / q);
+            }
+        }
+
+        let mut result = ArrayVec::new();
+        for t in roots {
+            if t >= S::ZERO && t <= S::ONE {
+                result.push(t);
+            }
+        }
+        if result.len() == 2 && result[0] > result[1] {
+            result.swap(0, 1);
+        }
+
+        result
+    }
+
+    /// Computes the parts of this curve that are inside a rectangle.
+    pub fn clip_to_rect(&self, rect: &Rect<S>) -> ArrayVec<[Self; 5]> {
+        let mut crossings: ArrayVec<[S; 8]> = ArrayVec::new();
+        crossings.extend(self.solve_t_for_x(rect.min_x()));
+        crossings.extend(self.solve_t_for_x(rect.max_x()));
+        crossings.extend(self.solve_t_for_y(rect.min_y()));
+        crossings.extend(self.solve_t_for_y(rect.max_y()));
+
+        let mut result = ArrayVec::new();
+        for_each_t_range_inside_rect(
+            &mut crossings,
+            rect,
+            |t| self.sample(t),
+            &mut |range| { result.push(self.split_range(range)); },
+        );
+
+        result
+    }
+
     /// Computes the intersections (if any) between this segment a line.
     ///
     /// The result is provided in the form of the `t` parameters of each
@@ -496,7 +591,11 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
     }
 }
 
-impl<S: Scalar> Segment for QuadraticBezierSegment<S> { impl_segment!(S); }
+impl<S: Scalar> Segment for QuadraticBezierSegment<S> {
+    impl_segment!(S);
+
+    fn for_each_monotonic_t<F: FnMut(S)>(&self, cb: F) { self.for_each_monotonic_t(cb) }
+}
 
 impl<S: Scalar> BoundingRect for QuadraticBezierSegment<S> {
     type Scalar = S;
@@ -803,3 +902,78 @@ fn test_flattening() {
     check_tolerance(&c3, 0.001);
     check_tolerance(&c3, 0.0001);
 }
+
+#[test]
+fn degenerate_curves() {
+    use math::point;
+
+    let p = point(1.0, 1.0);
+    let curve = QuadraticBezierSegment { from: p, ctrl: p, to: p };
+    assert!(curve.is_a_point(0.0));
+    assert!(curve.to_line_segment_if_linear(0.0).is_some());
+
+    let curve = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(1.0, 0.001),
+        to: point(2.0, 0.0),
+    };
+    assert!(!curve.is_a_point(0.01));
+    assert_eq!(
+        curve.to_line_segment_if_linear(0.01),
+        Some(LineSegment { from: curve.from, to: curve.to })
+    );
+    assert!(curve.to_line_segment_if_linear(0.0001).is_none());
+
+    // Straight but overshooting the end point.
+    let curve = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(4.0, 0.0),
+        to: point(2.0, 0.0),
+    };
+    assert!(curve.is_linear(0.01));
+    assert!(curve.to_line_segment_if_linear(0.01).is_none());
+}
+
+#[test]
+fn solve_t_for_x_and_y() {
+    let curve = QuadraticBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl: Point::new(10.0, 10.0),
+        to: Point::new(0.0, 20.0),
+    };
+
+    let t = curve.solve_t_for_x(3.2);
+    assert_eq!(t.len(), 2);
+    assert!((t[0] - 0.2f32).abs() < 0.0001);
+    assert!((t[1] - 0.8f32).abs() < 0.0001);
+
+    let t = curve.solve_t_for_y(10.0);
+    assert_eq!(t.len(), 1);
+    assert!((t[0] - 0.5f32).abs() < 0.0001);
+
+    assert!(curve.solve_t_for_x(11.0).is_empty());
+    assert!(curve.solve_t_for_y(-1.0).is_empty());
+}
+
+#[test]
+fn clip_to_rect() {
+    let curve = QuadraticBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl: Point::new(10.0, 10.0),
+        to: Point::new(0.0, 20.0),
+    };
+
+    let pieces = curve.clip_to_rect(&rect(-1.0, -1.0, 4.2, 5.0));
+    assert_eq!(pieces.len(), 1);
+    assert!((pieces[0].from - curve.from).length() < 0.0001);
+    assert!((pieces[0].to - Point::new(3.2, 4.0)).length() < 0.0001);
+
+    // The curve goes out of the rectangle and comes back in.
+    let pieces = curve.clip_to_rect(&rect(-1.0, -1.0, 4.2, 30.0));
+    assert_eq!(pieces.len(), 2);
+    assert!((pieces[1].from - Point::new(3.2, 16.0)).length() < 0.0001);
+    assert!((pieces[1].to - curve.to).length() < 0.0001);
+
+    assert_eq!(curve.clip_to_rect(&rect(0.0, 0.0, 20.0, 20.0)).len(), 1);
+    assert!(curve.clip_to_rect(&rect(-10.0, -10.0, 5.0, 5.0)).is_empty());
+}
@@ -0,0 +1,300 @@
+//! Rectangles with rounded corners.
+
+use scalar::Scalar;
+use generic_math::{Point, point, vector, Angle, Rect, Size};
+use arrayvec::ArrayVec;
+use {Arc, LineSegment};
+
+/// The radius of each corner of a rounded rectangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct CornerRadii<S> {
+    pub top_left: S,
+    pub top_right: S,
+    pub bottom_left: S,
+    pub bottom_right: S,
+}
+
+impl<S: Scalar> CornerRadii<S> {
+    pub fn new(top_left: S, top_right: S, bottom_left: S, bottom_right: S) -> Self {
+        CornerRadii {
+            top_left: S::abs(top_left),
+            top_right: S::abs(top_right),
+            bottom_left: S::abs(bottom_left),
+            bottom_right: S::abs(bottom_right),
+        }
+    }
+
+    pub fn new_all_same(radius: S) -> Self {
+        let r = S::abs(radius);
+        CornerRadii {
+            top_left: r,
+            top_right: r,
+            bottom_left: r,
+            bottom_right: r,
+        }
+    }
+}
+
+/// A rectangle with rounded corners.
+///
+/// The top of the rectangle is on the side of the smallest y coordinates.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(bound(deserialize = "S: ::serde::Deserialize<'de> + Copy")))]
+pub struct RoundedRect<S> {
+    pub rect: Rect<S>,
+    pub radii: CornerRadii<S>,
+}
+
+// Can't derive these because euclid's Rect only implements them when S is Copy.
+impl<S: Copy> Copy for RoundedRect<S> {}
+
+impl<S: Copy> Clone for RoundedRect<S> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<S: Scalar> RoundedRect<S> {
+    pub fn new(rect: Rect<S>, radii: CornerRadii<S>) -> Self {
+        RoundedRect { rect, radii }
+    }
+
+    /// Returns the radii, reduced if they don't fit in the rectangle.
+    ///
+    /// This is the same logic as `lyon_tessellation::basic_shapes::fill_rounded_rectangle`.
+    pub fn clamped_radii(&self) -> CornerRadii<S> {
+        let w = self.rect.size.width;
+        let h = self.rect.size.height;
+        let min_wh = S::min(w, h);
+        let mut tl = S::min(S::abs(self.radii.top_left), min_wh);
+        let mut tr = S::min(S::abs(self.radii.top_right), min_wh);
+        let mut bl = S::min(S::abs(self.radii.bottom_left), min_wh);
+        let mut br = S::min(S::abs(self.radii.bottom_right), min_wh);
+
+        if tl + tr > w {
+            let x = (tl + tr - w) * S::HALF;
+            tl -= x;
+            tr -= x;
+        }
+        if bl + br > w {
+            let x = (bl + br - w) * S::HALF;
+            bl -= x;
+            br -= x;
+        }
+        if tr + br > h {
+            let x = (tr + br - h) * S::HALF;
+            tr -= x;
+            br -= x;
+        }
+        if tl + bl > h {
+            let x = (tl + bl - h) * S::HALF;
+            tl -= x;
+            bl -= x;
+        }
+
+        CornerRadii { top_left: tl, top_right: tr, bottom_left: bl, bottom_right: br }
+    }
+
+    /// Returns whether a point is inside the rounded rectangle (or on its edge).
+    pub fn contains_point(&self, p: Point<S>) -> bool {
+        let r = &self.rect;
+        if p.x < r.min_x() || p.x > r.max_x() || p.y < r.min_y() || p.y > r.max_y() {
+            return false;
+        }
+
+        for arc in &self.corners() {
+            let c = arc.center;
+            let radius = arc.radii.x;
+            // The corner of the bounding rectangle that is cut by this arc.
+            let corner = arc.sample(S::HALF) - c;
+            let in_corner = (p.x - c.x) * corner.x > S::ZERO && (p.y - c.y) * corner.y > S::ZERO;
+            if radius > S::ZERO && in_corner {
+                return (p - c).square_length() <= radius * radius;
+            }
+        }
+
+        true
+    }
+
+    /// Moves the edges outwards by `d` (or inwards if `d` is negative).
+    ///
+    /// Outward, the rounded corners grow by the same amount so that the outline stays at the
+    /// same distance from the original one, while sharp corners stay sharp. Inward, the radii
+    /// shrink until the corners become sharp.
+    pub fn inflate(&self, d: S) -> Self {
+        let radius = |r: S| if r > S::ZERO { S::max(r + d, S::ZERO) } else { r };
+        let radii = self.clamped_radii();
+        let rect = self.rect.inflate(d, d);
+
+        RoundedRect {
+            rect: Rect::new(
+                rect.origin,
+                Size::new(S::max(rect.size.width, S::ZERO), S::max(rect.size.height, S::ZERO)),
+            ),
+            radii: CornerRadii {
+                top_left: radius(radii.top_left),
+                top_right: radius(radii.top_right),
+                bottom_left: radius(radii.bottom_left),
+                bottom_right: radius(radii.bottom_right),
+            },
+        }
+    }
+
+    /// Computes the intersection points (if any) between the outline of the rounded rectangle
+    /// and a line segment, in the order of the segment's direction.
+    pub fn line_segment_intersections(&self, segment: &LineSegment<S>) -> ArrayVec<[Point<S>; 2]> {
+        let mut candidates: ArrayVec<[S; 16]> = ArrayVec::new();
+        for edge in &self.edges() {
+            if let Some((_, t)) = edge.intersection_t(segment) {
+                candidates.push(t);
+            }
+        }
+        for arc in &self.corners() {
+            if arc.radii.x > S::ZERO {
+                for (_, t) in arc.line_segment_intersections_t(segment) {
+                    candidates.push(t);
+                }
+            }
+        }
+
+        // The shape is convex so a segment crosses its outline at most twice. The end points
+        // of the edges and the arcs can be reported twice.
+        let mut result = ArrayVec::new();
+        if candidates.is_empty() {
+            return result;
+        }
+        let mut min = candidates[0];
+        let mut max = candidates[0];
+        for &t in &candidates[1..] {
+            min = S::min(min, t);
+            max = S::max(max, t);
+        }
+
+        result.push(segment.sample(min));
+        if (max - min) * segment.length() > S::EPSILON {
+            result.push(segment.sample(max));
+        }
+
+        result
+    }
+
+    /// The straight edges of the outline, in clockwise order (in a y-down coordinate system)
+    /// starting with the top edge.
+    ///
+    /// Some of the edges can be empty if the corners are large.
+    pub fn edges(&self) -> [LineSegment<S>; 4] {
+        let radii = self.clamped_radii();
+        let r = &self.rect;
+        [
+            LineSegment {
+                from: point(r.min_x() + radii.top_left, r.min_y()),
+                to: point(r.max_x() - radii.top_right, r.min_y()),
+            },
+            LineSegment {
+                from: point(r.max_x(), r.min_y() + radii.top_right),
+                to: point(r.max_x(), r.max_y() - radii.bottom_right),
+            },
+            LineSegment {
+                from: point(r.max_x() - radii.bottom_right, r.max_y()),
+                to: point(r.min_x() + radii.bottom_left, r.max_y()),
+            },
+            LineSegment {
+                from: point(r.min_x(), r.max_y() - radii.bottom_left),
+                to: point(r.min_x(), r.min_y() + radii.top_left),
+            },
+        ]
+    }
+
+    /// The arcs of the corners, in clockwise order (in a y-down coordinate system) starting
+    /// with the top right corner, so that each arc follows the edge of the same index.
+    ///
+    /// The arcs of sharp corners have a radius of zero.
+    pub fn corners(&self) -> [Arc<S>; 4] {
+        let radii = self.clamped_radii();
+        let r = &self.rect;
+        let quarter = Angle::radians(S::PI() * S::HALF);
+        let corner = |center: Point<S>, radius: S, start_angle: S| Arc {
+            center,
+            radii: vector(radius, radius),
+            start_angle: Angle::radians(start_angle),
+            sweep_angle: quarter,
+            x_rotation: Angle::zero(),
+        };
+
+        [
+            corner(
+                point(r.max_x() - radii.top_right, r.min_y() + radii.top_right),
+                radii.top_right,
+                -S::PI() * S::HALF,
+            ),
+            corner(
+                point(r.max_x() - radii.bottom_right, r.max_y() - radii.bottom_right),
+                radii.bottom_right,
+                S::ZERO,
+            ),
+            corner(
+                point(r.min_x() + radii.bottom_left, r.max_y() - radii.bottom_left),
+                radii.bottom_left,
+                S::PI() * S::HALF,
+            ),
+            corner(
+                point(r.min_x() + radii.top_left, r.min_y() + radii.top_left),
+                radii.top_left,
+                S::PI(),
+            ),
+        ]
+    }
+}
+
+#[test]
+fn rounded_rect() {
+    use generic_math::rect;
+
+    let rr = RoundedRect::new(
+        rect(0.0f64, 0.0, 100.0, 50.0),
+        CornerRadii::new(10.0, 0.0, 20.0, 40.0),
+    );
+
+    assert_eq!(rr.clamped_radii(), rr.radii);
+    let too_large = RoundedRect::new(rect(0.0f64, 0.0, 100.0, 50.0), CornerRadii::new_all_same(40.0));
+    assert_eq!(too_large.clamped_radii(), CornerRadii::new_all_same(25.0));
+
+    assert!(rr.contains_point(point(50.0, 25.0)));
+    assert!(rr.contains_point(point(99.0, 1.0)));
+    assert!(!rr.contains_point(point(1.0, 1.0)));
+    assert!(rr.contains_point(point(5.0, 5.0)));
+    assert!(!rr.contains_point(point(99.0, 49.0)));
+    assert!(!rr.contains_point(point(101.0, 25.0)));
+
+    // The edges and the corners connect.
+    let edges = rr.edges();
+    let corners = rr.corners();
+    for i in 0..4 {
+        assert!((edges[i].to - corners[i].from()).length() < 1e-9);
+        assert!((corners[i].to() - edges[(i + 1) % 4].from).length() < 1e-9);
+    }
+
+    let segment = LineSegment { from: point(-10.0, 25.0), to: point(110.0, 25.0) };
+    let points = rr.line_segment_intersections(&segment);
+    assert_eq!(points.len(), 2);
+    assert!((points[0] - point(0.0, 25.0)).length() < 1e-9);
+    // On the bottom right corner.
+    assert!((points[1] - point(60.0 + 1375.0f64.sqrt(), 25.0)).length() < 1e-9);
+
+    // Through the rounded corner.
+    let segment = LineSegment { from: point(0.0, 0.0), to: point(10.0, 10.0) };
+    let points = rr.line_segment_intersections(&segment);
+    assert_eq!(points.len(), 1);
+    let expected = 10.0 - 10.0 / 2.0f64.sqrt();
+    assert!((points[0] - point(expected, expected)).length() < 1e-9);
+
+    let outer = rr.inflate(5.0);
+    assert_eq!(outer.rect, rect(-5.0, -5.0, 110.0, 60.0));
+    assert_eq!(outer.radii.top_left, 15.0);
+    assert_eq!(outer.radii.top_right, 0.0);
+
+    let inner = rr.inflate(-15.0);
+    assert_eq!(inner.rect.size, Size::new(70.0, 20.0));
+    assert_eq!(inner.radii.top_left, 0.0);
+    assert_eq!(inner.radii.bottom_right, 25.0);
+}
@@ -1,5 +1,6 @@
-use scalar::{Scalar, One};
+use scalar::{Scalar, Float, One};
 use generic_math::{Point, Vector, Rect};
+use arrayvec::ArrayVec;
 
 use std::ops::Range;
 
@@ -50,6 +51,136 @@ pub trait Segment: Copy + Sized {
 
     /// Compute the length of the segment using a flattened approximation.
     fn approximate_length(&self, tolerance: Self::Scalar) -> Self::Scalar;
+
+    /// Invokes a callback, in increasing order, for each `t` strictly between 0 and 1 at which
+    /// the segment changes direction along the x or y axis.
+    ///
+    /// The default implementation looks for sign changes of the derivative at regular
+    /// intervals and refines them with a bisection, so it can miss extrema that are very
+    /// close to each other. The segment types of this crate compute them exactly.
+    fn for_each_monotonic_t<F: FnMut(Self::Scalar)>(&self, mut cb: F) {
+        const NUM_SAMPLES: u32 = 32;
+
+        let mut extrema: ArrayVec<[Self::Scalar; 64]> = ArrayVec::new();
+        for axis in 0..2 {
+            let derivative = |t| if axis == 0 { self.dx(t) } else { self.dy(t) };
+            let step = Self::Scalar::ONE / Self::Scalar::value(NUM_SAMPLES as f32);
+            let mut t0 = Self::Scalar::ZERO;
+            let mut d0 = derivative(t0);
+            for i in 1..(NUM_SAMPLES + 1) {
+                let t1 = if i == NUM_SAMPLES { Self::Scalar::ONE } else { step * Self::Scalar::value(i as f32) };
+                let d1 = derivative(t1);
+                if (d0 < Self::Scalar::ZERO && d1 > Self::Scalar::ZERO)
+                    || (d0 > Self::Scalar::ZERO && d1 < Self::Scalar::ZERO) {
+                    let (mut a, mut b) = (t0, t1);
+                    for _ in 0..48 {
+                        let mid = (a + b) * Self::Scalar::HALF;
+                        if (derivative(mid) > Self::Scalar::ZERO) == (d0 > Self::Scalar::ZERO) {
+                            a = mid;
+                        } else {
+                            b = mid;
+                        }
+                    }
+                    extrema.push((a + b) * Self::Scalar::HALF);
+                }
+                t0 = t1;
+                d0 = d1;
+            }
+        }
+
+        extrema.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for t in extrema {
+            if t > Self::Scalar::ZERO && t < Self::Scalar::ONE {
+                cb(t);
+            }
+        }
+    }
+
+    /// Invokes a callback for each monotonic part of the segment.
+    fn for_each_monotonic_range<F: FnMut(Range<Self::Scalar>)>(&self, mut cb: F) {
+        let mut t0 = Self::Scalar::ZERO;
+        self.for_each_monotonic_t(|t| {
+            cb(t0..t);
+            t0 = t;
+        });
+        cb(t0..Self::Scalar::ONE);
+    }
+
+    /// Splits the segment at the first point where it crosses the vertical line at `x`.
+    ///
+    /// Returns `None` if the segment doesn't reach `x`.
+    fn split_at_x(&self, x: Self::Scalar) -> Option<(Self, Self)> {
+        first_crossing_t(self, x, &|s: &Self, t| s.x(t)).map(|t| self.split(t))
+    }
+
+    /// Splits the segment at the first point where it crosses the horizontal line at `y`.
+    ///
+    /// Returns `None` if the segment doesn't reach `y`.
+    fn split_at_y(&self, y: Self::Scalar) -> Option<(Self, Self)> {
+        first_crossing_t(self, y, &|s: &Self, t| s.y(t)).map(|t| self.split(t))
+    }
+
+    /// Invokes a callback with the position and the derivative (tangent) of the segment at
+    /// `n` values of `t` evenly distributed between 0 and 1, both included.
+    ///
+    /// The points are evenly spaced in the parameter space of the segment which, except
+    /// for line segments and circular arcs, does not mean they are evenly spaced along the
+    /// curve. See `FlattenedForEach::for_each_equal_arc_length_t` for that.
+    fn sample_evenly<F: FnMut(Point<Self::Scalar>, Vector<Self::Scalar>)>(&self, n: u32, mut cb: F) {
+        if n == 0 {
+            return;
+        }
+        if n == 1 {
+            cb(self.from(), self.derivative(Self::Scalar::ZERO));
+            return;
+        }
+
+        let step = Self::Scalar::ONE / Self::Scalar::value((n - 1) as f32);
+        for i in 0..n {
+            let t = if i == n - 1 { Self::Scalar::ONE } else { step * Self::Scalar::value(i as f32) };
+            cb(self.sample(t), self.derivative(t));
+        }
+    }
+}
+
+// Finds the first t at which `coord(t) == value`, looking at each monotonic part.
+fn first_crossing_t<T, F>(segment: &T, value: T::Scalar, coord: &F) -> Option<T::Scalar>
+where
+    T: Segment,
+    F: Fn(&T, T::Scalar) -> T::Scalar,
+{
+    let mut result = None;
+    segment.for_each_monotonic_range(|range| {
+        if result.is_some() {
+            return;
+        }
+
+        let (mut a, mut b) = (range.start, range.end);
+        let mut va = coord(segment, a);
+        let vb = coord(segment, b);
+        if va == value {
+            result = Some(a);
+            return;
+        }
+        if (va - value) * (vb - value) > T::Scalar::ZERO {
+            return;
+        }
+
+        for _ in 0..48 {
+            let mid = (a + b) * T::Scalar::HALF;
+            let v = coord(segment, mid);
+            if (v - value) * (va - value) > T::Scalar::ZERO {
+                a = mid;
+                va = v;
+            } else {
+                b = mid;
+            }
+        }
+
+        result = Some((a + b) * T::Scalar::HALF);
+    });
+
+    result
 }
 
 pub trait BoundingRect {
@@ -80,6 +211,119 @@ pub trait BoundingRect {
 pub trait FlattenedForEach: Segment {
     /// Iterates through the curve invoking a callback at each point.
     fn for_each_flattened<F: FnMut(Point<Self::Scalar>)>(&self, tolerance: Self::Scalar, call_back: &mut F);
+
+    /// Iterates through the curve invoking a callback at each point, along with the value of
+    /// `t` of the point on the curve.
+    ///
+    /// The default implementation splits regular intervals of the curve in half until the
+    /// middle of each interval is within the tolerance of its chord, so it can miss details
+    /// that are small compared to the intervals. The segment types of this crate implement
+    /// it with their own flattening algorithm.
+    fn for_each_flattened_with_t<F: FnMut(Point<Self::Scalar>, Self::Scalar)>(&self, tolerance: Self::Scalar, call_back: &mut F) {
+        const NUM_INTERVALS: u32 = 4;
+
+        let step = Self::Scalar::ONE / Self::Scalar::value(NUM_INTERVALS as f32);
+        let mut from = (Self::Scalar::ZERO, self.from());
+        for i in 1..(NUM_INTERVALS + 1) {
+            let to = if i == NUM_INTERVALS {
+                (Self::Scalar::ONE, self.to())
+            } else {
+                let t = step * Self::Scalar::value(i as f32);
+                (t, self.sample(t))
+            };
+            flatten_interval_with_t(self, from, to, tolerance, 0, call_back);
+            from = to;
+        }
+    }
+
+    /// Invokes a callback with the `n + 1` values of `t` that split the curve into `n` parts
+    /// of equal lengths, starting with 0 and ending with 1.
+    ///
+    /// The lengths are measured on the flattened approximation of the curve, and `t` is
+    /// linearly interpolated between the flattened points.
+    fn for_each_equal_arc_length_t<F: FnMut(Self::Scalar)>(&self, n: u32, tolerance: Self::Scalar, mut cb: F) {
+        if n == 0 {
+            return;
+        }
+
+        let step = approximate_length_from_flattening(self, tolerance) / Self::Scalar::value(n as f32);
+
+        cb(Self::Scalar::ZERO);
+
+        let mut k = 1;
+        let mut target = step;
+        let mut distance = Self::Scalar::ZERO;
+        let mut prev = self.from();
+        let mut prev_t = Self::Scalar::ZERO;
+        self.for_each_flattened_with_t(tolerance, &mut |p, t| {
+            let d = (p - prev).length();
+            while k < n && distance + d >= target {
+                let ratio = if d > Self::Scalar::ZERO { (target - distance) / d } else { Self::Scalar::ZERO };
+                cb(prev_t + (t - prev_t) * ratio);
+                k += 1;
+                target = step * Self::Scalar::value(k as f32);
+            }
+            distance += d;
+            prev = p;
+            prev_t = t;
+        });
+
+        // Rounding errors can leave the last split points unvisited.
+        while k < n {
+            cb(Self::Scalar::ONE);
+            k += 1;
+        }
+
+        cb(Self::Scalar::ONE);
+    }
+
+    /// Splits the curve into `n` parts of (approximately) equal lengths, invoking a callback
+    /// with each of them in order.
+    ///
+    /// See `for_each_equal_arc_length_t`.
+    fn split_into_equal_arc_lengths<F: FnMut(Self)>(&self, n: u32, tolerance: Self::Scalar, mut cb: F) {
+        let mut t0 = None;
+        self.for_each_equal_arc_length_t(n, tolerance, |t| {
+            if let Some(start) = t0 {
+                cb(self.split_range(start..t));
+            }
+            t0 = Some(t);
+        });
+    }
+}
+
+// Invokes the callback with the end of the interval once the curve is flat enough between
+// `from` and `to`, otherwise splits the interval in half.
+fn flatten_interval_with_t<T, F>(
+    segment: &T,
+    from: (T::Scalar, Point<T::Scalar>),
+    to: (T::Scalar, Point<T::Scalar>),
+    tolerance: T::Scalar,
+    depth: u32,
+    call_back: &mut F,
+)
+where
+    T: Segment,
+    F: FnMut(Point<T::Scalar>, T::Scalar),
+{
+    const MAX_DEPTH: u32 = 16;
+
+    let t = (from.0 + to.0) * T::Scalar::HALF;
+    let mid = segment.sample(t);
+    let chord = to.1 - from.1;
+    let chord_length = chord.length();
+    let distance = if chord_length > T::Scalar::ZERO {
+        chord.cross(mid - from.1).abs() / chord_length
+    } else {
+        (mid - from.1).length()
+    };
+
+    if depth < MAX_DEPTH && distance > tolerance {
+        flatten_interval_with_t(segment, from, (t, mid), tolerance, depth + 1, call_back);
+        flatten_interval_with_t(segment, (t, mid), to, tolerance, depth + 1, call_back);
+    } else {
+        call_back(to.1, to.0);
+    }
 }
 
 /// Types that implement local flattening approximation at the start of the curve.
@@ -110,6 +354,21 @@ where T: FlatteningStep
             call_back(iter.from());
         }
     }
+
+    fn for_each_flattened_with_t<F: FnMut(Point<Self::Scalar>, Self::Scalar)>(&self, tolerance: Self::Scalar, call_back: &mut F) {
+        let mut iter = *self;
+        let mut t0 = Self::Scalar::ZERO;
+        loop {
+            let t = iter.flattening_step(tolerance);
+            if t >= Self::Scalar::one() {
+                call_back(iter.to(), Self::Scalar::ONE);
+                break;
+            }
+            iter = iter.after_split(t);
+            t0 = t0 + (Self::Scalar::ONE - t0) * t;
+            call_back(iter.from(), t0);
+        }
+    }
 }
 
 /// An iterator over a generic curve segment that yields line segments approximating the
@@ -184,3 +443,144 @@ macro_rules! impl_segment {
         }
     )
 }
+
+#[test]
+fn generic_segment_utilities() {
+    use {QuadraticBezierSegment, CubicBezierSegment, Arc};
+    use generic_math::{point, vector, Angle};
+
+    fn check<T: FlattenedForEach<Scalar = f64>>(segment: &T) {
+        let mut previous = 0.0;
+        segment.for_each_flattened_with_t(0.01, &mut |p, t| {
+            assert!(t > previous);
+            assert!((p - segment.sample(t)).length() < 1e-6);
+            previous = t;
+        });
+        assert_eq!(previous, 1.0);
+
+        let mut num_ranges = 0;
+        let mut end = 0.0;
+        segment.for_each_monotonic_range(|range| {
+            assert_eq!(range.start, end);
+            end = range.end;
+            num_ranges += 1;
+        });
+        assert_eq!(end, 1.0);
+        assert!(num_ranges > 1);
+
+        let (before, after) = segment.split_at_x(0.5).unwrap();
+        assert!((before.to().x - 0.5).abs() < 1e-6);
+        assert!((after.from() - before.to()).length() < 1e-6);
+        assert!(segment.split_at_x(10.0).is_none());
+    }
+
+    check(&QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(1.0, 1.0),
+        to: point(2.0, 0.0),
+    });
+    check(&CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(2.0, 2.0),
+        ctrl2: point(-1.0, 2.0),
+        to: point(1.0, 0.0),
+    });
+    check(&Arc {
+        center: point(1.0, 0.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Angle::radians(::std::f64::consts::PI),
+        sweep_angle: Angle::radians(::std::f64::consts::PI),
+        x_rotation: Angle::zero(),
+    });
+}
+
+#[test]
+fn default_flattening_with_t() {
+    use QuadraticBezierSegment;
+    use generic_math::point;
+
+    // Only provides for_each_flattened, so that for_each_flattened_with_t uses the
+    // default implementation.
+    #[derive(Copy, Clone)]
+    struct Curve(QuadraticBezierSegment<f64>);
+
+    impl Segment for Curve {
+        type Scalar = f64;
+        fn from(&self) -> Point<f64> { self.0.from }
+        fn to(&self) -> Point<f64> { self.0.to }
+        fn sample(&self, t: f64) -> Point<f64> { self.0.sample(t) }
+        fn derivative(&self, t: f64) -> Vector<f64> { self.0.derivative(t) }
+        fn split(&self, t: f64) -> (Self, Self) {
+            let (a, b) = self.0.split(t);
+            (Curve(a), Curve(b))
+        }
+        fn before_split(&self, t: f64) -> Self { Curve(self.0.before_split(t)) }
+        fn after_split(&self, t: f64) -> Self { Curve(self.0.after_split(t)) }
+        fn split_range(&self, t_range: Range<f64>) -> Self { Curve(self.0.split_range(t_range)) }
+        fn flip(&self) -> Self { Curve(self.0.flip()) }
+        fn approximate_length(&self, tolerance: f64) -> f64 { self.0.approximate_length(tolerance) }
+    }
+
+    impl FlattenedForEach for Curve {
+        fn for_each_flattened<F: FnMut(Point<f64>)>(&self, tolerance: f64, call_back: &mut F) {
+            self.0.for_each_flattened(tolerance, call_back);
+        }
+    }
+
+    let curve = Curve(QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(10.0, 10.0),
+        to: point(20.0, 0.0),
+    });
+
+    let mut previous = (0.0, curve.from());
+    let mut num_points = 0;
+    curve.for_each_flattened_with_t(0.01, &mut |p, t| {
+        assert!(t > previous.0);
+        assert!((p - curve.sample(t)).length() < 1e-9);
+        let mid = curve.sample((previous.0 + t) * 0.5);
+        let chord = p - previous.1;
+        assert!(chord.cross(mid - previous.1).abs() / chord.length() <= 0.01);
+        previous = (t, p);
+        num_points += 1;
+    });
+    assert_eq!(previous.0, 1.0);
+    assert!(num_points > 4);
+}
+
+#[test]
+fn evenly_spaced_samples() {
+    use {QuadraticBezierSegment, CubicBezierSegment};
+    use generic_math::point;
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(1.0, 2.0),
+        to: point(2.0, 0.0),
+    };
+    let mut samples = Vec::new();
+    quadratic.sample_evenly(5, |p, v| samples.push((p, v)));
+    assert_eq!(samples.len(), 5);
+    assert_eq!(samples[0], (quadratic.from, quadratic.derivative(0.0)));
+    assert_eq!(samples[2].0, quadratic.sample(0.5));
+    assert_eq!(samples[4], (quadratic.to, quadratic.derivative(1.0)));
+
+    let cubic = CubicBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl1: point(0.0, 10.0),
+        ctrl2: point(1.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+    let tolerance = 0.0001;
+    let expected = cubic.approximate_length(tolerance) / 4.0;
+    let mut num_parts = 0;
+    let mut end = cubic.from;
+    cubic.split_into_equal_arc_lengths(4, tolerance, |part| {
+        assert!((part.from - end).length() < 1e-9);
+        assert!((part.approximate_length(tolerance) - expected).abs() < 0.01);
+        end = part.to;
+        num_parts += 1;
+    });
+    assert_eq!(num_parts, 4);
+    assert!((end - cubic.to).length() < 1e-9);
+}
@@ -0,0 +1,347 @@
+//! Spirals and circle involutes.
+//!
+//! All curves of this module are parameterized by `t` going from `0` to `1`, and can be
+//! approximated with sequences of cubic bézier segments.
+
+use scalar::{Scalar, Float};
+use generic_math::{Point, point, Vector, vector, Angle};
+use CubicBezierSegment;
+
+/// A spiral which radius grows linearly with the angle.
+///
+/// Successive turns of the spiral are separated by a constant distance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ArchimedeanSpiral<S> {
+    pub center: Point<S>,
+    pub start_radius: S,
+    pub end_radius: S,
+    pub start_angle: Angle<S>,
+    /// The angle between the start and the end of the spiral, can be larger than a full turn.
+    ///
+    /// Negative angles go in the other direction.
+    pub sweep_angle: Angle<S>,
+}
+
+impl<S: Scalar> ArchimedeanSpiral<S> {
+    /// Sample the curve at t (expecting t between 0 and 1).
+    pub fn sample(&self, t: S) -> Point<S> {
+        let (sin, cos) = Float::sin_cos(self.angle(t));
+        self.center + vector(cos, sin) * self.radius(t)
+    }
+
+    /// Sample the derivative at t (expecting t between 0 and 1).
+    pub fn derivative(&self, t: S) -> Vector<S> {
+        let (sin, cos) = Float::sin_cos(self.angle(t));
+        let dr = self.end_radius - self.start_radius;
+        let r = self.radius(t) * self.sweep_angle.radians;
+
+        vector(dr * cos - r * sin, dr * sin + r * cos)
+    }
+
+    pub fn from(&self) -> Point<S> { self.sample(S::ZERO) }
+
+    pub fn to(&self) -> Point<S> { self.sample(S::ONE) }
+
+    /// Approximates the curve with a sequence of cubic bézier segments.
+    pub fn for_each_cubic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        approximate(self, self.sweep_angle.radians, tolerance, cb);
+    }
+
+    fn angle(&self, t: S) -> S {
+        self.start_angle.radians + self.sweep_angle.radians * t
+    }
+
+    fn radius(&self, t: S) -> S {
+        self.start_radius + (self.end_radius - self.start_radius) * t
+    }
+}
+
+/// A spiral which radius grows exponentially with the angle.
+///
+/// The angle between the tangent and the radial line is the same everywhere on the curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct LogarithmicSpiral<S> {
+    pub center: Point<S>,
+    /// Expected to be greater than zero.
+    pub start_radius: S,
+    /// Expected to be greater than zero.
+    pub end_radius: S,
+    pub start_angle: Angle<S>,
+    /// The angle between the start and the end of the spiral, can be larger than a full turn.
+    ///
+    /// Negative angles go in the other direction.
+    pub sweep_angle: Angle<S>,
+}
+
+impl<S: Scalar> LogarithmicSpiral<S> {
+    /// Sample the curve at t (expecting t between 0 and 1).
+    pub fn sample(&self, t: S) -> Point<S> {
+        let (sin, cos) = Float::sin_cos(self.angle(t));
+        self.center + vector(cos, sin) * self.radius(t)
+    }
+
+    /// Sample the derivative at t (expecting t between 0 and 1).
+    pub fn derivative(&self, t: S) -> Vector<S> {
+        let (sin, cos) = Float::sin_cos(self.angle(t));
+        let r = self.radius(t);
+        let dr = r * Float::ln(self.end_radius / self.start_radius);
+        let r = r * self.sweep_angle.radians;
+
+        vector(dr * cos - r * sin, dr * sin + r * cos)
+    }
+
+    pub fn from(&self) -> Point<S> { self.sample(S::ZERO) }
+
+    pub fn to(&self) -> Point<S> { self.sample(S::ONE) }
+
+    /// Approximates the curve with a sequence of cubic bézier segments.
+    pub fn for_each_cubic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        if self.start_radius <= S::ZERO || self.end_radius <= S::ZERO {
+            return;
+        }
+
+        approximate(self, self.sweep_angle.radians, tolerance, cb);
+    }
+
+    fn angle(&self, t: S) -> S {
+        self.start_angle.radians + self.sweep_angle.radians * t
+    }
+
+    fn radius(&self, t: S) -> S {
+        self.start_radius * Float::powf(self.end_radius / self.start_radius, t)
+    }
+}
+
+/// The involute of a circle: the curve traced by the end of a string unwound from the
+/// circle while being kept taut.
+///
+/// This is the profile of the teeth of most gears.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct CircleInvolute<S> {
+    pub center: Point<S>,
+    /// The radius of the circle the string is unwound from.
+    pub radius: S,
+    /// The angle of the point of the circle where the curve starts.
+    pub start_angle: Angle<S>,
+    /// The angle of string that is unwound.
+    ///
+    /// Positive angles unwind the string in the direction of positive angles.
+    pub sweep_angle: Angle<S>,
+}
+
+impl<S: Scalar> CircleInvolute<S> {
+    /// Sample the curve at t (expecting t between 0 and 1).
+    pub fn sample(&self, t: S) -> Point<S> {
+        let theta = self.sweep_angle.radians * t;
+        let (sin, cos) = Float::sin_cos(self.start_angle.radians + theta);
+
+        point(
+            self.center.x + self.radius * (cos + theta * sin),
+            self.center.y + self.radius * (sin - theta * cos),
+        )
+    }
+
+    /// Sample the derivative at t (expecting t between 0 and 1).
+    pub fn derivative(&self, t: S) -> Vector<S> {
+        let theta = self.sweep_angle.radians * t;
+        let (sin, cos) = Float::sin_cos(self.start_angle.radians + theta);
+
+        vector(cos, sin) * (self.radius * theta * self.sweep_angle.radians)
+    }
+
+    pub fn from(&self) -> Point<S> { self.sample(S::ZERO) }
+
+    pub fn to(&self) -> Point<S> { self.sample(S::ONE) }
+
+    /// The length of the curve.
+    pub fn length(&self) -> S {
+        let theta = self.sweep_angle.radians;
+        S::abs(self.radius) * theta * theta * S::HALF
+    }
+
+    /// Approximates the curve with a sequence of cubic bézier segments.
+    pub fn for_each_cubic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        approximate(self, self.sweep_angle.radians, tolerance, cb);
+    }
+}
+
+trait Parametric<S: Scalar> {
+    fn sample(&self, t: S) -> Point<S>;
+    fn derivative(&self, t: S) -> Vector<S>;
+}
+
+impl<S: Scalar> Parametric<S> for ArchimedeanSpiral<S> {
+    fn sample(&self, t: S) -> Point<S> { self.sample(t) }
+    fn derivative(&self, t: S) -> Vector<S> { self.derivative(t) }
+}
+
+impl<S: Scalar> Parametric<S> for LogarithmicSpiral<S> {
+    fn sample(&self, t: S) -> Point<S> { self.sample(t) }
+    fn derivative(&self, t: S) -> Vector<S> { self.derivative(t) }
+}
+
+impl<S: Scalar> Parametric<S> for CircleInvolute<S> {
+    fn sample(&self, t: S) -> Point<S> { self.sample(t) }
+    fn derivative(&self, t: S) -> Vector<S> { self.derivative(t) }
+}
+
+const MAX_DEPTH: u32 = 10;
+const MAX_QUARTER_TURNS: u32 = 4096;
+
+// Starts with one cubic bézier segment per quarter turn and subdivides them until they
+// are within the tolerance threshold.
+fn approximate<S: Scalar, C: Parametric<S>, F>(curve: &C, sweep_angle: S, tolerance: S, cb: &mut F)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let quarter = S::PI() * S::HALF;
+    let mut n = 1;
+    while n < MAX_QUARTER_TURNS && S::value(n as f32) * quarter < S::abs(sweep_angle) {
+        n += 1;
+    }
+
+    let step = S::ONE / S::value(n as f32);
+    let mut t0 = S::ZERO;
+    for i in 1..(n + 1) {
+        let t1 = if i == n { S::ONE } else { step * S::value(i as f32) };
+        approximate_range(curve, t0, t1, tolerance, 0, cb);
+        t0 = t1;
+    }
+}
+
+// Builds a cubic bézier segment from the end points and derivatives of the curve, and
+// subdivides if it is too far from the curve.
+fn approximate_range<S: Scalar, C: Parametric<S>, F>(curve: &C, t0: S, t1: S, tolerance: S, depth: u32, cb: &mut F)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let dt = t1 - t0;
+    let from = curve.sample(t0);
+    let to = curve.sample(t1);
+    let segment = CubicBezierSegment {
+        from,
+        ctrl1: from + curve.derivative(t0) * (dt / S::THREE),
+        ctrl2: to - curve.derivative(t1) * (dt / S::THREE),
+        to,
+    };
+
+    let mut error = S::ZERO;
+    for &t in &[S::value(0.25), S::HALF, S::value(0.75)] {
+        error = S::max(error, (segment.sample(t) - curve.sample(t0 + dt * t)).length());
+    }
+
+    if error <= tolerance || depth >= MAX_DEPTH {
+        cb(&segment);
+        return;
+    }
+
+    let mid = (t0 + t1) * S::HALF;
+    approximate_range(curve, t0, mid, tolerance, depth + 1, cb);
+    approximate_range(curve, mid, t1, tolerance, depth + 1, cb);
+}
+
+#[cfg(test)]
+fn approximate_length<F>(for_each: F) -> (f64, Point<f64>, Point<f64>)
+where
+    F: Fn(&mut dyn FnMut(&CubicBezierSegment<f64>))
+{
+    let mut length = 0.0;
+    let mut first = None;
+    let mut last = point(0.0, 0.0);
+    for_each(&mut |segment| {
+        if first.is_some() {
+            assert!((segment.from - last).length() < 1e-9);
+        } else {
+            first = Some(segment.from);
+        }
+        length += segment.approximate_length(0.00001);
+        last = segment.to;
+    });
+
+    (length, first.unwrap(), last)
+}
+
+#[test]
+fn archimedean_spiral() {
+    use std::f64::consts::PI;
+
+    // r = θ over two turns.
+    let spiral = ArchimedeanSpiral {
+        center: point(1.0, 1.0),
+        start_radius: 0.0,
+        end_radius: 4.0 * PI,
+        start_angle: Angle::radians(0.0),
+        sweep_angle: Angle::radians(4.0 * PI),
+    };
+
+    let (length, from, to) = approximate_length(|cb| spiral.for_each_cubic_bezier(0.001, &mut |s| cb(s)));
+    assert_eq!(from, point(1.0, 1.0));
+    assert!((to - point(1.0 + 4.0 * PI, 1.0)).length() < 1e-9);
+    // The length of r = θ is (θ√(1 + θ²) + asinh(θ)) / 2.
+    assert!((length - 80.819_316).abs() < 0.01);
+
+    let mut count = 0;
+    spiral.for_each_cubic_bezier(0.001, &mut |segment| {
+        for i in 0..11 {
+            let p = segment.sample(i as f64 / 10.0) - point(1.0, 1.0);
+            // Find the closest turn.
+            let angle = p.y.atan2(p.x);
+            let angle = if angle < 0.0 { angle + 2.0 * PI } else { angle };
+            let r = p.length();
+            let d = (0..3).map(|k| (r - angle - 2.0 * PI * k as f64).abs()).fold(r, f64::min);
+            assert!(d < 0.01);
+        }
+        count += 1;
+    });
+    assert!(count >= 8);
+}
+
+#[test]
+fn logarithmic_spiral() {
+    use std::f64::consts::PI;
+
+    let spiral = LogarithmicSpiral {
+        center: point(0.0, 0.0),
+        start_radius: 1.0,
+        end_radius: 8.0,
+        start_angle: Angle::radians(0.0),
+        sweep_angle: Angle::radians(-3.0 * PI),
+    };
+
+    let (length, from, to) = approximate_length(|cb| spiral.for_each_cubic_bezier(0.001, &mut |s| cb(s)));
+    assert_eq!(from, point(1.0, 0.0));
+    assert!((to - point(-8.0, 0.0)).length() < 1e-9);
+    // The length of r = e^(kθ) between two radii is (r1 - r0)√(1 + k²) / k.
+    let k: f64 = 8.0f64.ln() / (3.0 * PI);
+    let expected = 7.0 * (1.0 + k * k).sqrt() / k;
+    assert!((length - expected).abs() < 0.01);
+}
+
+#[test]
+fn circle_involute() {
+    use std::f64::consts::PI;
+
+    let involute = CircleInvolute {
+        center: point(0.0, 0.0),
+        radius: 2.0,
+        start_angle: Angle::radians(PI * 0.5),
+        sweep_angle: Angle::radians(PI),
+    };
+
+    let (length, from, to) = approximate_length(|cb| involute.for_each_cubic_bezier(0.001, &mut |s| cb(s)));
+    assert!((from - point(0.0, 2.0)).length() < 1e-9);
+    assert!((to - point(0.0, -2.0) - vector(-2.0 * PI, 0.0)).length() < 1e-9);
+    assert!((length - involute.length()).abs() < 0.01);
+}
@@ -0,0 +1,198 @@
+//! Superellipses, including the "squircle" shape often used for rounded icons.
+
+use scalar::{Scalar, Float};
+use generic_math::{Point, point, Vector, vector};
+use CubicBezierSegment;
+
+/// A superellipse, the curve defined by `|x / a|ⁿ + |y / b|ⁿ = 1`.
+///
+/// An exponent of `2` gives an ellipse, and larger exponents give shapes that get closer
+/// to a rectangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Superellipse<S> {
+    pub center: Point<S>,
+    pub radii: Vector<S>,
+    /// The exponent, expected to be greater or equal to one.
+    pub exponent: S,
+}
+
+impl<S: Scalar> Superellipse<S> {
+    /// Creates a squircle, the superellipse with equal radii and an exponent of four.
+    pub fn squircle(center: Point<S>, radius: S) -> Self {
+        Superellipse {
+            center,
+            radii: vector(radius, radius),
+            exponent: S::FOUR,
+        }
+    }
+
+    /// Sample the curve at a given parameter between `0` and `2π`.
+    ///
+    /// The curve starts on the positive x axis and goes toward the positive y axis.
+    pub fn sample(&self, angle: S) -> Point<S> {
+        let e = S::TWO / self.exponent();
+        let (sin, cos) = Float::sin_cos(angle);
+        point(
+            self.center.x + self.radii.x * cos.signum() * cos.abs().powf(e),
+            self.center.y + self.radii.y * sin.signum() * sin.abs().powf(e),
+        )
+    }
+
+    /// The direction of the curve at a given parameter, not normalized.
+    ///
+    /// Unlike the derivative, it is defined everywhere including on the axes.
+    pub fn sample_tangent(&self, angle: S) -> Vector<S> {
+        let e = S::TWO - S::TWO / self.exponent();
+        let (sin, cos) = Float::sin_cos(angle);
+        vector(
+            -self.radii.x * sin.signum() * sin.abs().powf(e),
+            self.radii.y * cos.signum() * cos.abs().powf(e),
+        )
+    }
+
+    /// Approximates the curve with a sequence of cubic bézier segments.
+    pub fn for_each_cubic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        if self.radii.x == S::ZERO || self.radii.y == S::ZERO {
+            return;
+        }
+
+        // The ends of the quadrants are on the axes. Computing them from the angles would
+        // amplify the imprecision of the sine and cosine near zero.
+        let c = self.center;
+        let r = self.radii;
+        let axis_points = [
+            point(c.x + r.x, c.y),
+            point(c.x, c.y + r.y),
+            point(c.x - r.x, c.y),
+            point(c.x, c.y - r.y),
+        ];
+
+        let quarter = S::PI() * S::HALF;
+        let mut start = S::ZERO;
+        for i in 0..4 {
+            let end = start + quarter;
+            let (from, to) = (axis_points[i], axis_points[(i + 1) % 4]);
+            self.approximate((start, end), from, to, tolerance, 0, cb);
+            start = end;
+        }
+    }
+
+    fn exponent(&self) -> S {
+        S::max(self.exponent, S::ONE)
+    }
+
+    fn approximate<F>(
+        &self,
+        (a0, a1): (S, S),
+        from: Point<S>, to: Point<S>,
+        tolerance: S,
+        depth: u32,
+        cb: &mut F,
+    )
+    where
+        F: FnMut(&CubicBezierSegment<S>)
+    {
+        let t0 = self.sample_tangent(a0).normalize();
+        let t1 = self.sample_tangent(a1).normalize();
+
+        // Choose the length of the handles so that the middle of the bézier curve is
+        // on the superellipse.
+        let mid_angle = (a0 + a1) * S::HALF;
+        let mid = self.sample(mid_angle);
+        let d = (mid - from.lerp(to, S::HALF)) * (S::EIGHT / S::THREE);
+        let det = t1.cross(t0);
+        let chord = (to - from).length() / S::THREE;
+        let (mut l0, mut l1) = if det.abs() > S::EPSILON {
+            ((t1.x * d.y - d.x * t1.y) / det, (t0.x * d.y - d.x * t0.y) / det)
+        } else {
+            (chord, chord)
+        };
+        if l0 < S::ZERO || l1 < S::ZERO {
+            l0 = chord;
+            l1 = chord;
+        }
+
+        let curve = CubicBezierSegment {
+            from,
+            ctrl1: from + t0 * l0,
+            ctrl2: to - t1 * l1,
+            to,
+        };
+
+        let mut error = S::ZERO;
+        for &t in &[S::value(0.25), S::HALF, S::value(0.75)] {
+            error = S::max(error, self.radial_distance(curve.sample(t)));
+        }
+
+        if error <= tolerance || depth >= 8 {
+            cb(&curve);
+            return;
+        }
+
+        self.approximate((a0, mid_angle), from, mid, tolerance, depth + 1, cb);
+        self.approximate((mid_angle, a1), mid, to, tolerance, depth + 1, cb);
+    }
+
+    // The distance between a point and the superellipse along the line going through the
+    // center of the superellipse.
+    fn radial_distance(&self, p: Point<S>) -> S {
+        let v = p - self.center;
+        let length = v.length();
+        if length == S::ZERO {
+            return S::max(self.radii.x, self.radii.y);
+        }
+
+        let n = self.exponent();
+        let cos = v.x / length;
+        let sin = v.y / length;
+        let r = ((cos / self.radii.x).abs().powf(n) + (sin / self.radii.y).abs().powf(n)).powf(-S::ONE / n);
+
+        (length - r).abs()
+    }
+}
+
+#[test]
+fn superellipse_circle() {
+    let circle = Superellipse { center: point(1.0, 2.0), radii: vector(10.0, 10.0), exponent: 2.0 };
+    let mut segments = Vec::new();
+    circle.for_each_cubic_bezier(0.01, &mut |s| segments.push(*s));
+    assert_eq!(segments.len(), 4);
+    assert_eq!(segments[0].from, point(11.0, 2.0));
+    // The handles of the usual cubic approximation of a quarter of a circle.
+    assert!((segments[0].ctrl1.y - 2.0 - 5.5228).abs() < 0.01);
+    for segment in &segments {
+        for i in 0..11 {
+            let p = segment.sample(i as f32 / 10.0);
+            assert!(((p - point(1.0, 2.0)).length() - 10.0).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn superellipse_squircle() {
+    let tolerance = 0.01;
+    let squircle = Superellipse::squircle(point(0.0, 0.0), 10.0);
+    let mut segments: Vec<CubicBezierSegment<f32>> = Vec::new();
+    squircle.for_each_cubic_bezier(tolerance, &mut |s| segments.push(*s));
+    assert!(segments.len() >= 4);
+    assert_eq!(segments[0].from, point(10.0, 0.0));
+    assert_eq!(segments[segments.len() - 1].to, point(10.0, 0.0));
+    for pair in segments.windows(2) {
+        assert_eq!(pair[0].to, pair[1].from);
+    }
+    for segment in &segments {
+        for i in 0..11 {
+            let p = segment.sample(i as f32 / 10.0);
+            assert!((p.x.powi(4) + p.y.powi(4)).powf(0.25) - 10.0 < tolerance * 2.0);
+            assert!(10.0 - (p.x.powi(4) + p.y.powi(4)).powf(0.25) < tolerance * 2.0);
+        }
+    }
+
+    // The squircle is between the inscribed circle and the square.
+    let corner = squircle.sample(::std::f32::consts::PI * 0.25);
+    assert!(corner.x > 10.0 * (0.5f32).sqrt() && corner.x < 10.0);
+}
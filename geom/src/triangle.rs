@@ -1,6 +1,6 @@
 use scalar::Scalar;
 use generic_math::{Point, Rect, Size, Transform2D};
-use LineSegment;
+use {LineSegment, Circle};
 
 /// A 2D triangle defined by three points `a`, `b` and `c`.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -111,6 +111,150 @@ impl<S: Scalar> Triangle<S> {
             || self.ac().intersects(segment)
             || self.contains_point(segment.from);
     }
+
+    /// Returns the signed area of the triangle, positive if the points are in
+    /// counter-clockwise order in a y-up coordinate system (clockwise in a y-down one).
+    #[inline]
+    pub fn signed_area(&self) -> S {
+        (self.b - self.a).cross(self.c - self.a) * S::HALF
+    }
+
+    #[inline]
+    pub fn area(&self) -> S {
+        S::abs(self.signed_area())
+    }
+
+    /// Returns whether the point is inside the triangle or at a distance of less than
+    /// `tolerance` from its edges.
+    pub fn contains_point_with_tolerance(&self, point: Point<S>, tolerance: S) -> bool {
+        if self.contains_point(point) {
+            return true;
+        }
+
+        let tolerance_squared = tolerance * tolerance;
+        for edge in &[self.ab(), self.bc(), self.ca()] {
+            if square_distance_to_segment(point, edge) <= tolerance_squared {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Computes the barycentric coordinates of a point, that is the weights of `a`, `b` and
+    /// `c` such that the point is `a * u + b * v + c * w` and `u + v + w = 1`.
+    ///
+    /// The point is inside the triangle if the three weights are positive. The weights are
+    /// not finite if the triangle is degenerate.
+    pub fn barycentric_coordinates(&self, point: Point<S>) -> (S, S, S) {
+        let d = (self.b - self.a).cross(self.c - self.a);
+        let u = (self.b - point).cross(self.c - point) / d;
+        let v = (self.c - point).cross(self.a - point) / d;
+
+        (u, v, S::ONE - u - v)
+    }
+
+    /// Returns the point with the given barycentric coordinates.
+    pub fn from_barycentric_coordinates(&self, u: S, v: S, w: S) -> Point<S> {
+        (self.a.to_vector() * u + self.b.to_vector() * v + self.c.to_vector() * w).to_point()
+    }
+
+    /// Returns the circle going through the three points of the triangle, or `None` if the
+    /// triangle is degenerate.
+    pub fn circumcircle(&self) -> Option<Circle<S>> {
+        // Computed relatively to a to limit the loss of precision.
+        let b = self.b - self.a;
+        let c = self.c - self.a;
+        let d = S::TWO * b.cross(c);
+        if S::abs(d) <= S::EPSILON * S::max(b.square_length(), c.square_length()) {
+            return None;
+        }
+
+        let b2 = b.square_length();
+        let c2 = c.square_length();
+        let offset = ::generic_math::vector(c.y * b2 - b.y * c2, b.x * c2 - c.x * b2) / d;
+
+        Some(Circle {
+            center: self.a + offset,
+            radius: offset.length(),
+        })
+    }
+
+    /// Returns the point at the same distance of the three edges of the triangle.
+    pub fn incenter(&self) -> Point<S> {
+        let la = (self.c - self.b).length();
+        let lb = (self.a - self.c).length();
+        let lc = (self.b - self.a).length();
+        let perimeter = la + lb + lc;
+        if perimeter == S::ZERO {
+            return self.a;
+        }
+
+        self.from_barycentric_coordinates(la / perimeter, lb / perimeter, lc / perimeter)
+    }
+
+    /// Returns the largest circle contained in the triangle.
+    pub fn incircle(&self) -> Circle<S> {
+        let perimeter = (self.c - self.b).length()
+            + (self.a - self.c).length()
+            + (self.b - self.a).length();
+        let radius = if perimeter > S::ZERO {
+            S::TWO * self.area() / perimeter
+        } else {
+            S::ZERO
+        };
+
+        Circle { center: self.incenter(), radius }
+    }
+
+    /// Returns the part of a line segment that is inside the triangle, if any.
+    pub fn clip_line_segment(&self, segment: &LineSegment<S>) -> Option<LineSegment<S>> {
+        let orientation = S::signum(self.signed_area());
+        if self.signed_area() == S::ZERO {
+            return None;
+        }
+
+        let v = segment.to_vector();
+        let mut t_min = S::ZERO;
+        let mut t_max = S::ONE;
+        for edge in &[self.ab(), self.bc(), self.ca()] {
+            let e = edge.to_vector();
+            // The point at t is inside this edge's half-plane if n + t * d >= 0.
+            let n = e.cross(segment.from - edge.from) * orientation;
+            let d = e.cross(v) * orientation;
+            if d == S::ZERO {
+                if n < S::ZERO {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = -n / d;
+            if d > S::ZERO {
+                t_min = S::max(t_min, t);
+            } else {
+                t_max = S::min(t_max, t);
+            }
+        }
+
+        if t_min > t_max {
+            return None;
+        }
+
+        Some(segment.split_range(t_min..t_max))
+    }
+}
+
+fn square_distance_to_segment<S: Scalar>(p: Point<S>, segment: &LineSegment<S>) -> S {
+    let v = segment.to_vector();
+    let square_length = v.square_length();
+    let t = if square_length > S::ZERO {
+        S::min(S::max((p - segment.from).dot(v) / square_length, S::ZERO), S::ONE)
+    } else {
+        S::ZERO
+    };
+
+    (segment.sample(t) - p).square_length()
 }
 
 #[cfg(test)]
@@ -268,3 +412,44 @@ fn test_bounding_rect() {
         assert_eq!(tri.bounding_rect(), r);
     }
 }
+
+#[test]
+fn test_triangle_utilities() {
+    let triangle = Triangle {
+        a: point(0.0f64, 0.0),
+        b: point(4.0, 0.0),
+        c: point(0.0, 3.0),
+    };
+
+    assert_eq!(triangle.signed_area(), 6.0);
+    assert_eq!(Triangle { a: triangle.a, b: triangle.c, c: triangle.b }.signed_area(), -6.0);
+
+    let circle = triangle.circumcircle().unwrap();
+    assert!((circle.center - point(2.0, 1.5)).length() < 1e-9);
+    assert!((circle.radius - 2.5).abs() < 1e-9);
+    assert!(Triangle { a: point(0.0, 0.0), b: point(1.0, 1.0), c: point(2.0, 2.0) }.circumcircle().is_none());
+
+    let incircle = triangle.incircle();
+    assert!((incircle.center - point(1.0, 1.0)).length() < 1e-9);
+    assert!((incircle.radius - 1.0).abs() < 1e-9);
+
+    let p = point(1.0, 1.0);
+    let (u, v, w) = triangle.barycentric_coordinates(p);
+    assert!((u - 5.0 / 12.0).abs() < 1e-9);
+    assert!((v - 0.25).abs() < 1e-9);
+    assert!((w - 1.0 / 3.0).abs() < 1e-9);
+    assert!((triangle.from_barycentric_coordinates(u, v, w) - p).length() < 1e-9);
+
+    assert!(!triangle.contains_point(point(2.0, -0.01)));
+    assert!(triangle.contains_point_with_tolerance(point(2.0, -0.01), 0.1));
+    assert!(!triangle.contains_point_with_tolerance(point(2.0, -1.0), 0.1));
+
+    let clipped = triangle.clip_line_segment(&LineSegment { from: point(-1.0, 1.0), to: point(5.0, 1.0) }).unwrap();
+    assert!((clipped.from - point(0.0, 1.0)).length() < 1e-9);
+    assert!((clipped.to - point(8.0 / 3.0, 1.0)).length() < 1e-9);
+    assert!(triangle.clip_line_segment(&LineSegment { from: point(3.0, 3.0), to: point(5.0, 1.0) }).is_none());
+
+    // Fully inside.
+    let inside = LineSegment { from: point(0.5, 0.5), to: point(1.0, 1.0) };
+    assert_eq!(triangle.clip_line_segment(&inside), Some(inside));
+}
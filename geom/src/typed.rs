@@ -0,0 +1,180 @@
+//! Segments tagged with a unit.
+//!
+//! euclid's typed points, vectors and rectangles carry a unit type parameter which prevents
+//! mixing values from different coordinate spaces (for example logical and device pixels)
+//! at compile time. The segment types of this crate don't have a unit parameter, but can be
+//! wrapped in `Typed` which provides the common queries with typed results and only allows
+//! transformations from the right coordinate space.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_geom::LineSegment;
+//! use lyon_geom::typed::Typed;
+//! use lyon_geom::euclid::{TypedTransform2D, TypedPoint2D, point2};
+//!
+//! struct LogicalPixels;
+//! struct DevicePixels;
+//!
+//! let segment: Typed<_, LogicalPixels> = Typed::new(LineSegment {
+//!     from: point2(0.0f32, 0.0),
+//!     to: point2(10.0, 5.0),
+//! });
+//!
+//! let hidpi: TypedTransform2D<f32, LogicalPixels, DevicePixels> = TypedTransform2D::create_scale(2.0, 2.0);
+//! let device_segment = segment.transform(&hidpi);
+//!
+//! let end: TypedPoint2D<f32, DevicePixels> = device_segment.to();
+//! assert_eq!(end.to_untyped(), point2(20.0, 10.0));
+//! ```
+
+use std::marker::PhantomData;
+use std::fmt;
+
+use euclid::{TypedPoint2D, TypedVector2D, TypedRect, TypedTransform2D};
+use scalar::Scalar;
+use generic_math::Transform2D;
+use segment::{Segment, BoundingRect};
+use {LineSegment, QuadraticBezierSegment, CubicBezierSegment, Triangle};
+
+/// A value (typically a segment) expressed in the coordinate space `U`.
+pub struct Typed<T, U> {
+    pub value: T,
+    _unit: PhantomData<U>,
+}
+
+impl<T, U> Typed<T, U> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Typed { value, _unit: PhantomData }
+    }
+
+    #[inline]
+    pub fn untyped(&self) -> &T { &self.value }
+
+    #[inline]
+    pub fn to_untyped(self) -> T { self.value }
+
+    /// Changes the unit without changing the value.
+    #[inline]
+    pub fn cast_unit<V>(self) -> Typed<T, V> { Typed::new(self.value) }
+}
+
+impl<T: Segment, U> Typed<T, U> {
+    #[inline]
+    pub fn from(&self) -> TypedPoint2D<T::Scalar, U> {
+        TypedPoint2D::from_untyped(&self.value.from())
+    }
+
+    #[inline]
+    pub fn to(&self) -> TypedPoint2D<T::Scalar, U> {
+        TypedPoint2D::from_untyped(&self.value.to())
+    }
+
+    #[inline]
+    pub fn sample(&self, t: T::Scalar) -> TypedPoint2D<T::Scalar, U> {
+        TypedPoint2D::from_untyped(&self.value.sample(t))
+    }
+
+    #[inline]
+    pub fn derivative(&self, t: T::Scalar) -> TypedVector2D<T::Scalar, U> {
+        TypedVector2D::from_untyped(&self.value.derivative(t))
+    }
+
+    #[inline]
+    pub fn split(&self, t: T::Scalar) -> (Self, Self) {
+        let (a, b) = self.value.split(t);
+        (Typed::new(a), Typed::new(b))
+    }
+
+    #[inline]
+    pub fn flip(&self) -> Self {
+        Typed::new(self.value.flip())
+    }
+
+    #[inline]
+    pub fn approximate_length(&self, tolerance: T::Scalar) -> T::Scalar {
+        self.value.approximate_length(tolerance)
+    }
+}
+
+impl<T: BoundingRect, U> Typed<T, U> {
+    #[inline]
+    pub fn bounding_rect(&self) -> TypedRect<T::Scalar, U> {
+        TypedRect::from_untyped(&self.value.bounding_rect())
+    }
+}
+
+impl<T: Transformable, U> Typed<T, U> {
+    /// Applies a transformation that goes from the coordinate space of this value to
+    /// another one.
+    #[inline]
+    pub fn transform<Dst>(&self, transform: &TypedTransform2D<T::Scalar, U, Dst>) -> Typed<T, Dst> {
+        Typed::new(self.value.transform(&transform.to_untyped()))
+    }
+}
+
+/// Types that can be transformed by `Typed::transform`.
+pub trait Transformable {
+    type Scalar: Scalar;
+    fn transform(&self, transform: &Transform2D<Self::Scalar>) -> Self;
+}
+
+macro_rules! impl_transformable {
+    ($($ty:ident),*) => {$(
+        impl<S: Scalar> Transformable for $ty<S> {
+            type Scalar = S;
+            #[inline]
+            fn transform(&self, transform: &Transform2D<S>) -> Self {
+                $ty::transform(self, transform)
+            }
+        }
+    )*}
+}
+
+impl_transformable!(LineSegment, QuadraticBezierSegment, CubicBezierSegment, Triangle);
+
+// Implemented manually to avoid requiring the unit to implement the traits.
+
+impl<T: Copy, U> Copy for Typed<T, U> {}
+
+impl<T: Clone, U> Clone for Typed<T, U> {
+    fn clone(&self) -> Self { Typed::new(self.value.clone()) }
+}
+
+impl<T: PartialEq, U> PartialEq for Typed<T, U> {
+    fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl<T: fmt::Debug, U> fmt::Debug for Typed<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[test]
+fn typed_segments() {
+    use generic_math::point;
+
+    struct Local;
+    struct World;
+
+    let curve: Typed<_, Local> = Typed::new(QuadraticBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl: point(1.0, 2.0),
+        to: point(2.0, 0.0),
+    });
+
+    let to_world: TypedTransform2D<f32, Local, World> = TypedTransform2D::create_translation(10.0, 0.0);
+    let world_curve: Typed<_, World> = curve.transform(&to_world);
+    assert_eq!(world_curve.from().to_untyped(), point(10.0, 0.0));
+    assert_eq!(world_curve.sample(0.5).to_untyped(), point(11.0, 1.0));
+
+    let rect = world_curve.bounding_rect();
+    assert_eq!(rect.to_untyped(), curve.untyped().bounding_rect().translate(&::generic_math::vector(10.0, 0.0)));
+
+    let (a, b) = world_curve.split(0.5);
+    assert_eq!(a.to(), b.from());
+    assert_eq!(world_curve.flip().to(), world_curve.from());
+    assert_eq!(curve.cast_unit::<World>().to_untyped(), *curve.untyped());
+}
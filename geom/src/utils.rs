@@ -1,7 +1,9 @@
 use scalar::{Scalar, Float};
-use generic_math::{Point, Vector, vector};
+use generic_math::{Point, Vector, vector, Rect};
 use arrayvec::ArrayVec;
 
+use std::ops::Range;
+
 #[inline]
 pub fn min_max<S: Float>(a: S, b: S) -> (S, S) {
     if a < b { (a, b) } else { (b, a) }
@@ -50,18 +52,67 @@ pub fn directed_angle2<S: Scalar>(center: Point<S>, a: Point<S>, b: Point<S>) ->
     directed_angle(a - center, b - center)
 }
 
+/// Calls the callback with each range of t for which a curve is inside a rectangle, given
+/// the t values where the curve intersects the rectangle's sides.
+pub(crate) fn for_each_t_range_inside_rect<S, F, Cb>(
+    crossings: &mut [S],
+    rect: &Rect<S>,
+    sample: F,
+    cb: &mut Cb,
+) where
+    S: Scalar,
+    F: Fn(S) -> Point<S>,
+    Cb: FnMut(Range<S>),
+{
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut range_start = None;
+    let mut prev = S::ZERO;
+    for &t in crossings.iter().chain(Some(S::ONE).iter()) {
+        if t <= prev {
+            continue;
+        }
+
+        let p = sample((prev + t) * S::HALF);
+        let inside = p.x >= rect.min_x() && p.x <= rect.max_x()
+            && p.y >= rect.min_y() && p.y <= rect.max_y();
+
+        match (inside, range_start) {
+            (true, None) => { range_start = Some(prev); }
+            (false, Some(start)) => {
+                cb(start..prev);
+                range_start = None;
+            }
+            _ => {}
+        }
+
+        prev = t;
+    }
+
+    if let Some(start) = range_start {
+        cb(start..S::ONE);
+    }
+}
+
 pub fn cubic_polynomial_roots<S: Scalar>(a: S, b: S, c: S, d: S) -> ArrayVec<[S; 3]> {
     let mut result = ArrayVec::new();
 
     if S::abs(a) < S::EPSILON {
+        if S::abs(b) < S::EPSILON {
+            // linear equation
+            if S::abs(c) >= S::EPSILON {
+                result.push(-d / c);
+            }
+            return result;
+        }
         // quadratic equation
-        let delta = b * b - S::FOUR * a * c;
+        let delta = c * c - S::FOUR * b * d;
         if delta > S::ZERO {
             let sqrt_delta = S::sqrt(delta);
-            result.push((-b - sqrt_delta) / (S::TWO * a));
-            result.push((-b + sqrt_delta) / (S::TWO * a));
+            result.push((-c - sqrt_delta) / (S::TWO * b));
+            result.push((-c + sqrt_delta) / (S::TWO * b));
         } else if S::abs(delta) < S::EPSILON {
-            result.push(-b / (S::TWO * a));
+            result.push(-c / (S::TWO * b));
         }
         return result;
     }
@@ -116,4 +167,6 @@ fn cubic_polynomial() {
     assert_approx_eq(cubic_polynomial_roots(2.0, -4.0, 2.0, 0.0), &[0.0, 1.0], 0.0000001);
     assert_approx_eq(cubic_polynomial_roots(-1.0, 1.0, -1.0, 1.0), &[1.0], 0.000001);
     assert_approx_eq(cubic_polynomial_roots(-2.0, 2.0, -1.0, 10.0), &[2.0], 0.00005);
+    assert_approx_eq(cubic_polynomial_roots(0.0, 1.0, -3.0, 2.0), &[1.0, 2.0], 0.000001);
+    assert_approx_eq(cubic_polynomial_roots(0.0, 0.0, 2.0, -1.0), &[0.5], 0.000001);
 }
@@ -76,7 +76,10 @@
 
 use math::*;
 use events::{PathEvent, FlattenedEvent, SvgEvent};
-use geom::{CubicBezierSegment, QuadraticBezierSegment, SvgArc, Arc, ArcFlags};
+use geom::{CubicBezierSegment, QuadraticBezierSegment, SvgArc, Arc, ArcFlags, Superellipse, Circle, Ellipse, RoundedRect};
+use geom::{ArchimedeanSpiral, LogarithmicSpiral, CircleInvolute};
+
+use std::ops::Range;
 
 /// The most basic path building interface. Does not handle any kind of curve.
 pub trait FlatPathBuilder: ::std::marker::Sized {
@@ -156,6 +159,93 @@ pub trait PathBuilder: FlatPathBuilder {
 
     /// Returns a builder that support svg commands.
     fn with_svg(self) -> SvgPathBuilder<Self> { SvgPathBuilder::new(self) }
+
+    /// Starts a new sub-path and approximates the parametric curve `f` for `t` going from
+    /// `t_range.start` to `t_range.end` with cubic bézier curves.
+    ///
+    /// The curve is sampled adaptively: each bézier curve is built from the positions and
+    /// (numerically estimated) derivatives of `f` at its end points, and is subdivided until
+    /// it is within `tolerance` of `f` at a few intermediate values of `t`. This works well
+    /// for smooth functions, while discontinuities and cusps are subdivided up to a fixed
+    /// depth. Features that are much smaller than an eighth of the range can be missed.
+    fn curve_from_fn<F>(&mut self, f: F, t_range: Range<f32>, tolerance: f32)
+    where
+        F: Fn(f32) -> Point,
+    {
+        debug_assert!(t_range.start <= t_range.end);
+
+        let start = f(t_range.start);
+        self.move_to(start);
+        if t_range.start >= t_range.end {
+            return;
+        }
+
+        let n = FN_CURVE_INITIAL_SUBDIVISIONS;
+        let step = (t_range.end - t_range.start) / n as f32;
+        let mut from = FnSample { t: t_range.start, position: start, derivative: fn_derivative(&f, t_range.start, &t_range) };
+        for i in 1..(n + 1) {
+            let t = if i == n { t_range.end } else { t_range.start + step * i as f32 };
+            let to = FnSample { t, position: f(t), derivative: fn_derivative(&f, t, &t_range) };
+            fit_fn_curve(self, &f, &from, &to, &t_range, tolerance, 0);
+            from = to;
+        }
+    }
+}
+
+const FN_CURVE_INITIAL_SUBDIVISIONS: u32 = 8;
+const FN_CURVE_MAX_DEPTH: u32 = 16;
+
+struct FnSample {
+    t: f32,
+    position: Point,
+    derivative: Vector,
+}
+
+// Estimates the derivative of f at t with central differences (one-sided at the ends of
+// the range).
+fn fn_derivative<F: Fn(f32) -> Point>(f: &F, t: f32, t_range: &Range<f32>) -> Vector {
+    let h = (t_range.end - t_range.start) * 1e-4;
+    let a = f32::max(t - h, t_range.start);
+    let b = f32::min(t + h, t_range.end);
+
+    (f(b) - f(a)) / (b - a)
+}
+
+// Adds a cubic bézier curve between two samples of f, or subdivides if it is too far from f.
+fn fit_fn_curve<Builder, F>(
+    builder: &mut Builder,
+    f: &F,
+    from: &FnSample,
+    to: &FnSample,
+    t_range: &Range<f32>,
+    tolerance: f32,
+    depth: u32,
+)
+where
+    Builder: PathBuilder,
+    F: Fn(f32) -> Point,
+{
+    let dt = to.t - from.t;
+    let curve = CubicBezierSegment {
+        from: from.position,
+        ctrl1: from.position + from.derivative * (dt / 3.0),
+        ctrl2: to.position - to.derivative * (dt / 3.0),
+        to: to.position,
+    };
+
+    let within_tolerance = depth >= FN_CURVE_MAX_DEPTH || [0.25, 0.5, 0.75].iter().all(|&s| {
+        (curve.sample(s) - f(from.t + dt * s)).square_length() <= tolerance * tolerance
+    });
+
+    if within_tolerance {
+        builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        return;
+    }
+
+    let t = from.t + dt * 0.5;
+    let mid = FnSample { t, position: f(t), derivative: fn_derivative(f, t, t_range) };
+    fit_fn_curve(builder, f, from, &mid, t_range, tolerance, depth + 1);
+    fit_fn_curve(builder, f, &mid, to, t_range, tolerance, depth + 1);
 }
 
 /// A path building interface that tries to stay close to SVG's path specification.
@@ -255,6 +345,81 @@ pub trait PolygonBuilder {
     fn polygon(&mut self, points: &[Point]);
 }
 
+/// Build smooth curves going through a list of points.
+pub trait SplineBuilder {
+    /// Adds a sub-path made of cubic bézier curves going through all of the points.
+    ///
+    /// The curves form a cardinal spline: a `tension` of `0.0` produces a Catmull-Rom
+    /// spline and a `tension` of `1.0` produces straight lines between the points.
+    fn points_through(&mut self, points: &[Point], tension: f32, closed: bool);
+}
+
+/// Build superellipses, for example squircles.
+pub trait SuperellipseBuilder {
+    /// Adds a closed sub-path approximating the superellipse with cubic bézier curves.
+    fn superellipse(&mut self, superellipse: &Superellipse<f32>, tolerance: f32);
+}
+
+/// Build spirals and circle involutes.
+pub trait SpiralBuilder {
+    /// Adds an open sub-path approximating the spiral with cubic bézier curves.
+    fn archimedean_spiral(&mut self, spiral: &ArchimedeanSpiral<f32>, tolerance: f32);
+    /// Adds an open sub-path approximating the spiral with cubic bézier curves.
+    fn logarithmic_spiral(&mut self, spiral: &LogarithmicSpiral<f32>, tolerance: f32);
+    /// Adds an open sub-path approximating the involute with cubic bézier curves.
+    fn circle_involute(&mut self, involute: &CircleInvolute<f32>, tolerance: f32);
+}
+
+/// Build circles and ellipses.
+pub trait EllipseBuilder {
+    /// Adds a closed sub-path going around the circle, starting on the positive x axis.
+    fn circle(&mut self, circle: &Circle<f32>);
+    /// Adds a closed sub-path going around the ellipse, starting at the end of its first radius.
+    fn ellipse(&mut self, ellipse: &Ellipse<f32>);
+}
+
+/// Build rectangles with rounded corners.
+pub trait RoundedRectBuilder {
+    /// Adds a closed sub-path going clockwise (in a y-down coordinate system) around the
+    /// rounded rectangle, starting at the beginning of the top edge.
+    fn rounded_rect(&mut self, rect: &RoundedRect<f32>);
+}
+
+/// Where the vertical lines of a step line are placed between two points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StepPosition {
+    /// The value changes at the x coordinate of the previous point (vertical line first).
+    Before,
+    /// The value changes halfway between the two points.
+    Middle,
+    /// The value changes at the x coordinate of the next point (horizontal line first).
+    After,
+}
+
+/// Build the shapes of common charts from series of data points.
+///
+/// The points of a series are expected to be sorted along the x axis. Empty series don't
+/// add anything to the path.
+pub trait ChartBuilder {
+    /// Adds an open sub-path going through the points with only horizontal and vertical
+    /// lines.
+    fn step_line(&mut self, points: &[Point], step: StepPosition);
+
+    /// Adds a closed sub-path enclosing the area between the series and a horizontal
+    /// baseline at `baseline_y`.
+    fn area_to_baseline(&mut self, points: &[Point], baseline_y: f32);
+
+    /// Same as `area_to_baseline` with the series drawn as a step line.
+    fn step_area_to_baseline(&mut self, points: &[Point], step: StepPosition, baseline_y: f32);
+
+    /// Adds a closed sub-path enclosing the area between two series, for example the
+    /// bounds of a confidence interval or the two sides of a stacked area.
+    ///
+    /// The outline follows `upper` and comes back along `lower` in reverse order, so that
+    /// the sub-path only crosses itself where the series do.
+    fn area_between(&mut self, upper: &[Point], lower: &[Point]);
+}
+
 /// Implements the Svg building interface on top of a PathBuilder.
 pub struct SvgPathBuilder<Builder: PathBuilder> {
     builder: Builder,
@@ -494,3 +659,173 @@ impl<Builder: FlatPathBuilder> PolygonBuilder for Builder {
         self.close();
     }
 }
+
+impl<Builder: PathBuilder> SplineBuilder for Builder {
+    fn points_through(&mut self, points: &[Point], tension: f32, closed: bool) {
+        assert!(!points.is_empty());
+
+        let n = points.len() as isize;
+        let at = |i: isize| -> Point {
+            if closed {
+                points[((i % n + n) % n) as usize]
+            } else {
+                points[i.max(0).min(n - 1) as usize]
+            }
+        };
+
+        // The tangent at each point is (1 - tension) * (next - prev) / 2, and the
+        // control points are placed at a third of the tangents.
+        let scale = (1.0 - tension) / 6.0;
+
+        self.move_to(points[0]);
+        let num_segments = if closed { n } else { n - 1 };
+        for i in 0..num_segments {
+            let p0 = at(i - 1);
+            let p1 = at(i);
+            let p2 = at(i + 1);
+            let p3 = at(i + 2);
+            self.cubic_bezier_to(
+                p1 + (p2 - p0) * scale,
+                p2 - (p3 - p1) * scale,
+                p2,
+            );
+        }
+
+        if closed {
+            self.close();
+        }
+    }
+}
+
+impl<Builder: PathBuilder> EllipseBuilder for Builder {
+    fn circle(&mut self, circle: &Circle<f32>) {
+        self.ellipse(&circle.to_ellipse());
+    }
+
+    fn ellipse(&mut self, ellipse: &Ellipse<f32>) {
+        let arc = ellipse.to_arc();
+        self.move_to(arc.from());
+        self.arc(arc.center, arc.radii, arc.sweep_angle, arc.x_rotation);
+        self.close();
+    }
+}
+
+impl<Builder: PathBuilder> RoundedRectBuilder for Builder {
+    fn rounded_rect(&mut self, rect: &RoundedRect<f32>) {
+        let edges = rect.edges();
+        let corners = rect.corners();
+        self.move_to(edges[0].from);
+        for (edge, corner) in edges.iter().zip(corners.iter()) {
+            self.line_to(edge.to);
+            if corner.radii.x > 0.0 {
+                self.arc(corner.center, corner.radii, corner.sweep_angle, corner.x_rotation);
+            }
+        }
+        self.close();
+    }
+}
+
+impl<Builder: FlatPathBuilder> ChartBuilder for Builder {
+    fn step_line(&mut self, points: &[Point], step: StepPosition) {
+        if points.is_empty() {
+            return;
+        }
+
+        self.move_to(points[0]);
+        for_each_step_point(points, step, &mut |p| self.line_to(p));
+    }
+
+    fn area_to_baseline(&mut self, points: &[Point], baseline_y: f32) {
+        if points.is_empty() {
+            return;
+        }
+
+        self.move_to(point(points[0].x, baseline_y));
+        for p in points {
+            self.line_to(*p);
+        }
+        self.line_to(point(points[points.len() - 1].x, baseline_y));
+        self.close();
+    }
+
+    fn step_area_to_baseline(&mut self, points: &[Point], step: StepPosition, baseline_y: f32) {
+        if points.is_empty() {
+            return;
+        }
+
+        self.move_to(point(points[0].x, baseline_y));
+        self.line_to(points[0]);
+        for_each_step_point(points, step, &mut |p| self.line_to(p));
+        self.line_to(point(points[points.len() - 1].x, baseline_y));
+        self.close();
+    }
+
+    fn area_between(&mut self, upper: &[Point], lower: &[Point]) {
+        if upper.is_empty() || lower.is_empty() {
+            return;
+        }
+
+        self.move_to(upper[0]);
+        for p in &upper[1..] {
+            self.line_to(*p);
+        }
+        for p in lower.iter().rev() {
+            self.line_to(*p);
+        }
+        self.close();
+    }
+}
+
+// Invokes the callback for each point of a step line after the first one.
+fn for_each_step_point<F: FnMut(Point)>(points: &[Point], step: StepPosition, cb: &mut F) {
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        match step {
+            StepPosition::Before => {
+                cb(point(a.x, b.y));
+            }
+            StepPosition::Middle => {
+                let x = (a.x + b.x) * 0.5;
+                cb(point(x, a.y));
+                cb(point(x, b.y));
+            }
+            StepPosition::After => {
+                cb(point(b.x, a.y));
+            }
+        }
+        cb(b);
+    }
+}
+
+impl<Builder: PathBuilder> SpiralBuilder for Builder {
+    fn archimedean_spiral(&mut self, spiral: &ArchimedeanSpiral<f32>, tolerance: f32) {
+        self.move_to(spiral.from());
+        spiral.for_each_cubic_bezier(tolerance, &mut |curve| {
+            self.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        });
+    }
+
+    fn logarithmic_spiral(&mut self, spiral: &LogarithmicSpiral<f32>, tolerance: f32) {
+        self.move_to(spiral.from());
+        spiral.for_each_cubic_bezier(tolerance, &mut |curve| {
+            self.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        });
+    }
+
+    fn circle_involute(&mut self, involute: &CircleInvolute<f32>, tolerance: f32) {
+        self.move_to(involute.from());
+        involute.for_each_cubic_bezier(tolerance, &mut |curve| {
+            self.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        });
+    }
+}
+
+impl<Builder: PathBuilder> SuperellipseBuilder for Builder {
+    fn superellipse(&mut self, superellipse: &Superellipse<f32>, tolerance: f32) {
+        self.move_to(superellipse.sample(0.0));
+        superellipse.for_each_cubic_bezier(tolerance, &mut |curve| {
+            self.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        });
+        self.close();
+    }
+}
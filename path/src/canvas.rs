@@ -0,0 +1,406 @@
+//! Interoperability with the path API of the HTML canvas.
+//!
+//! `CanvasPathBuilder` mirrors the methods of the canvas 2D context (and of `Path2D`) on
+//! top of a path builder, following their semantics: for example `arc` connects the current
+//! point to the beginning of the arc with a line, and `line_to` starts a new sub-path if there
+//! is none. In the other direction, `path_to_canvas_commands` converts a path into a sequence
+//! of canvas calls that can be replayed on a canvas context (for example through
+//! wasm-bindgen), or serialized with their `Display` implementation.
+//!
+//! Angles are in radians and the "clockwise" direction is the one of increasing angles,
+//! which is clockwise in the y-down coordinate system of the canvas.
+//!
+//! This module requires the `canvas` feature.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::canvas::{CanvasPathBuilder, path_to_canvas_commands};
+//! use lyon_path::default::Path;
+//! use std::f32::consts::PI;
+//!
+//! let mut canvas = CanvasPathBuilder::new(Path::builder());
+//! canvas.move_to(0.0, 0.0);
+//! canvas.arc_to(10.0, 0.0, 10.0, 10.0, 2.0);
+//! canvas.arc(20.0, 20.0, 5.0, 0.0, PI, false);
+//! canvas.rect(30.0, 0.0, 10.0, 10.0);
+//! let path = canvas.build();
+//!
+//! for command in path_to_canvas_commands(path.path_iter()) {
+//!     println!("ctx.{};", command);
+//! }
+//! ```
+
+use math::{Point, point, vector, Angle};
+use geom::Arc;
+use builder::PathBuilder;
+use iterator::PathIterator;
+use PathEvent;
+
+use std::f32::consts::PI;
+use std::fmt;
+
+/// A path builder with the interface of the canvas 2D path API.
+pub struct CanvasPathBuilder<Builder> {
+    builder: Builder,
+    current: Point,
+    first: Point,
+    has_subpath: bool,
+}
+
+impl<Builder: PathBuilder> CanvasPathBuilder<Builder> {
+    pub fn new(builder: Builder) -> Self {
+        CanvasPathBuilder {
+            builder,
+            current: point(0.0, 0.0),
+            first: point(0.0, 0.0),
+            has_subpath: false,
+        }
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        let to = point(x, y);
+        self.builder.move_to(to);
+        self.current = to;
+        self.first = to;
+        self.has_subpath = true;
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        if !self.ensure_subpath(x, y) {
+            return;
+        }
+        let to = point(x, y);
+        self.builder.line_to(to);
+        self.current = to;
+    }
+
+    pub fn quadratic_curve_to(&mut self, cpx: f32, cpy: f32, x: f32, y: f32) {
+        self.ensure_subpath(cpx, cpy);
+        let to = point(x, y);
+        self.builder.quadratic_bezier_to(point(cpx, cpy), to);
+        self.current = to;
+    }
+
+    pub fn bezier_curve_to(&mut self, cp1x: f32, cp1y: f32, cp2x: f32, cp2y: f32, x: f32, y: f32) {
+        self.ensure_subpath(cp1x, cp1y);
+        let to = point(x, y);
+        self.builder.cubic_bezier_to(point(cp1x, cp1y), point(cp2x, cp2y), to);
+        self.current = to;
+    }
+
+    /// Adds a circular arc, connected to the current point with a line.
+    pub fn arc(&mut self, x: f32, y: f32, radius: f32, start_angle: f32, end_angle: f32, anticlockwise: bool) {
+        self.ellipse(x, y, radius, radius, 0.0, start_angle, end_angle, anticlockwise);
+    }
+
+    /// Adds a line towards `(x1, y1)` and an arc of the given radius tangent to the lines
+    /// from the current point to `(x1, y1)` and from `(x1, y1)` to `(x2, y2)`.
+    pub fn arc_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) {
+        if !self.ensure_subpath(x1, y1) {
+            // The new sub-path starts at (x1, y1), there is nothing to connect.
+            return;
+        }
+
+        let p0 = self.current;
+        let p1 = point(x1, y1);
+        let p2 = point(x2, y2);
+        let u = p0 - p1;
+        let v = p2 - p1;
+        let cross = u.cross(v);
+        if radius == 0.0 || p0 == p1 || p1 == p2 || cross.abs() < 1e-6 * u.length() * v.length() {
+            self.line_to(x1, y1);
+            return;
+        }
+
+        let u = u.normalize();
+        let v = v.normalize();
+        // The angle of the corner at (x1, y1).
+        let angle = u.dot(v).clamp(-1.0, 1.0).acos();
+        let tangent_distance = radius / (angle * 0.5).tan();
+        let start = p1 + u * tangent_distance;
+        let center = p1 + (u + v).normalize() * (radius / (angle * 0.5).sin());
+        // Turning right when going through the corner means increasing angles.
+        let sweep_angle = if cross < 0.0 { PI - angle } else { angle - PI };
+
+        self.builder.line_to(start);
+        self.builder.arc(center, vector(radius, radius), Angle::radians(sweep_angle), Angle::zero());
+        self.current = p1 + v * tangent_distance;
+    }
+
+    /// Adds an elliptic arc, connected to the current point with a line.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ellipse(
+        &mut self,
+        x: f32,
+        y: f32,
+        radius_x: f32,
+        radius_y: f32,
+        rotation: f32,
+        start_angle: f32,
+        end_angle: f32,
+        anticlockwise: bool,
+    ) {
+        let arc = Arc {
+            center: point(x, y),
+            radii: vector(radius_x, radius_y),
+            start_angle: Angle::radians(start_angle),
+            sweep_angle: Angle::radians(canvas_sweep_angle(start_angle, end_angle, anticlockwise)),
+            x_rotation: Angle::radians(rotation),
+        };
+
+        let from = arc.from();
+        if self.has_subpath {
+            if (from - self.current).square_length() > 1e-10 {
+                self.builder.line_to(from);
+            }
+        } else {
+            self.move_to(from.x, from.y);
+        }
+        self.builder.arc(arc.center, arc.radii, arc.sweep_angle, arc.x_rotation);
+        self.current = arc.to();
+    }
+
+    /// Adds a closed rectangle sub-path.
+    pub fn rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.move_to(x, y);
+        self.builder.line_to(point(x + w, y));
+        self.builder.line_to(point(x + w, y + h));
+        self.builder.line_to(point(x, y + h));
+        self.close_path();
+    }
+
+    pub fn close_path(&mut self) {
+        if !self.has_subpath {
+            return;
+        }
+        self.builder.close();
+        self.current = self.first;
+    }
+
+    /// Returns the underlying path builder.
+    pub fn into_inner(self) -> Builder { self.builder }
+
+    pub fn build(self) -> Builder::PathType { self.builder.build() }
+
+    // Starts a sub-path at the given position if there is none, returns whether there
+    // already was one.
+    fn ensure_subpath(&mut self, x: f32, y: f32) -> bool {
+        if self.has_subpath {
+            return true;
+        }
+        self.move_to(x, y);
+
+        false
+    }
+}
+
+// Computes the sweep angle of an arc as specified by the canvas API.
+fn canvas_sweep_angle(start_angle: f32, end_angle: f32, anticlockwise: bool) -> f32 {
+    let two_pi = 2.0 * PI;
+    if !anticlockwise && end_angle - start_angle >= two_pi {
+        return two_pi;
+    }
+    if anticlockwise && start_angle - end_angle >= two_pi {
+        return -two_pi;
+    }
+
+    let mut sweep = (end_angle - start_angle) % two_pi;
+    if anticlockwise {
+        if sweep > 0.0 {
+            sweep -= two_pi;
+        }
+    } else if sweep < 0.0 {
+        sweep += two_pi;
+    }
+
+    sweep
+}
+
+/// A call to the path API of a canvas 2D context.
+///
+/// The `Display` implementation writes the call with the JavaScript syntax, for example
+/// `lineTo(1, 2)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CanvasCommand {
+    MoveTo { x: f32, y: f32 },
+    LineTo { x: f32, y: f32 },
+    QuadraticCurveTo { cpx: f32, cpy: f32, x: f32, y: f32 },
+    BezierCurveTo { cp1x: f32, cp1y: f32, cp2x: f32, cp2y: f32, x: f32, y: f32 },
+    Ellipse {
+        x: f32,
+        y: f32,
+        radius_x: f32,
+        radius_y: f32,
+        rotation: f32,
+        start_angle: f32,
+        end_angle: f32,
+        anticlockwise: bool,
+    },
+    ClosePath,
+}
+
+impl CanvasCommand {
+    /// Applies the command to a canvas path builder.
+    pub fn replay<Builder: PathBuilder>(&self, builder: &mut CanvasPathBuilder<Builder>) {
+        match *self {
+            CanvasCommand::MoveTo { x, y } => builder.move_to(x, y),
+            CanvasCommand::LineTo { x, y } => builder.line_to(x, y),
+            CanvasCommand::QuadraticCurveTo { cpx, cpy, x, y } => {
+                builder.quadratic_curve_to(cpx, cpy, x, y)
+            }
+            CanvasCommand::BezierCurveTo { cp1x, cp1y, cp2x, cp2y, x, y } => {
+                builder.bezier_curve_to(cp1x, cp1y, cp2x, cp2y, x, y)
+            }
+            CanvasCommand::Ellipse { x, y, radius_x, radius_y, rotation, start_angle, end_angle, anticlockwise } => {
+                builder.ellipse(x, y, radius_x, radius_y, rotation, start_angle, end_angle, anticlockwise)
+            }
+            CanvasCommand::ClosePath => builder.close_path(),
+        }
+    }
+}
+
+impl fmt::Display for CanvasCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CanvasCommand::MoveTo { x, y } => write!(f, "moveTo({}, {})", x, y),
+            CanvasCommand::LineTo { x, y } => write!(f, "lineTo({}, {})", x, y),
+            CanvasCommand::QuadraticCurveTo { cpx, cpy, x, y } => {
+                write!(f, "quadraticCurveTo({}, {}, {}, {})", cpx, cpy, x, y)
+            }
+            CanvasCommand::BezierCurveTo { cp1x, cp1y, cp2x, cp2y, x, y } => {
+                write!(f, "bezierCurveTo({}, {}, {}, {}, {}, {})", cp1x, cp1y, cp2x, cp2y, x, y)
+            }
+            CanvasCommand::Ellipse { x, y, radius_x, radius_y, rotation, start_angle, end_angle, anticlockwise } => {
+                write!(
+                    f, "ellipse({}, {}, {}, {}, {}, {}, {}, {})",
+                    x, y, radius_x, radius_y, rotation, start_angle, end_angle, anticlockwise,
+                )
+            }
+            CanvasCommand::ClosePath => write!(f, "closePath()"),
+        }
+    }
+}
+
+/// Converts a path into a sequence of canvas calls.
+///
+/// Arcs become `ellipse` calls, which start at the current point.
+pub fn path_to_canvas_commands<Iter: PathIterator>(mut path: Iter) -> Vec<CanvasCommand> {
+    let mut commands = Vec::new();
+    loop {
+        let from = path.get_state().current;
+        commands.push(match path.next() {
+            Some(PathEvent::MoveTo(to)) => CanvasCommand::MoveTo { x: to.x, y: to.y },
+            Some(PathEvent::LineTo(to)) => CanvasCommand::LineTo { x: to.x, y: to.y },
+            Some(PathEvent::QuadraticTo(ctrl, to)) => CanvasCommand::QuadraticCurveTo {
+                cpx: ctrl.x, cpy: ctrl.y,
+                x: to.x, y: to.y,
+            },
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => CanvasCommand::BezierCurveTo {
+                cp1x: ctrl1.x, cp1y: ctrl1.y,
+                cp2x: ctrl2.x, cp2y: ctrl2.y,
+                x: to.x, y: to.y,
+            },
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                CanvasCommand::Ellipse {
+                    x: center.x,
+                    y: center.y,
+                    radius_x: radii.x,
+                    radius_y: radii.y,
+                    rotation: x_rotation.get(),
+                    start_angle: start_angle.get(),
+                    end_angle: (start_angle + sweep_angle).get(),
+                    anticlockwise: sweep_angle.get() < 0.0,
+                }
+            }
+            Some(PathEvent::Close) => CanvasCommand::ClosePath,
+            None => {
+                return commands;
+            }
+        });
+    }
+}
+
+#[test]
+fn canvas_arc_to() {
+    use default::Path;
+
+    let mut canvas = CanvasPathBuilder::new(Path::builder());
+    canvas.move_to(0.0, 0.0);
+    canvas.arc_to(10.0, 0.0, 10.0, 10.0, 2.0);
+    canvas.line_to(10.0, 10.0);
+    let path = canvas.build();
+
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[1], PathEvent::LineTo(point(8.0, 0.0)));
+    match events[2] {
+        PathEvent::Arc(center, radii, sweep_angle, _) => {
+            assert!((center - point(8.0, 2.0)).length() < 1e-5);
+            assert_eq!(radii, vector(2.0, 2.0));
+            assert!((sweep_angle.get() - PI * 0.5).abs() < 1e-5);
+        }
+        _ => panic!(),
+    }
+
+    // Collinear points produce a line.
+    let mut canvas = CanvasPathBuilder::new(Path::builder());
+    canvas.arc_to(0.0, 0.0, 10.0, 0.0, 2.0);
+    canvas.arc_to(5.0, 0.0, 10.0, 0.0, 2.0);
+    let events: Vec<PathEvent> = canvas.build().iter().collect();
+    assert_eq!(events, vec![PathEvent::MoveTo(point(0.0, 0.0)), PathEvent::LineTo(point(5.0, 0.0))]);
+}
+
+#[test]
+fn canvas_round_trip() {
+    use default::Path;
+
+    assert_eq!(canvas_sweep_angle(0.0, PI, false), PI);
+    assert_eq!(canvas_sweep_angle(0.0, PI, true), -PI);
+    assert_eq!(canvas_sweep_angle(0.0, 5.0 * PI, false), 2.0 * PI);
+    assert!((canvas_sweep_angle(0.0, -0.5 * PI, false) - 1.5 * PI).abs() < 1e-5);
+
+    let mut canvas = CanvasPathBuilder::new(Path::builder());
+    canvas.line_to(1.0, 1.0);
+    canvas.bezier_curve_to(2.0, 1.0, 3.0, 2.0, 3.0, 3.0);
+    canvas.arc(3.0, 5.0, 2.0, -0.5 * PI, 0.5 * PI, true);
+    canvas.rect(10.0, 10.0, 5.0, 5.0);
+    canvas.quadratic_curve_to(20.0, 10.0, 20.0, 20.0);
+    let path = canvas.build();
+
+    let commands = path_to_canvas_commands(path.path_iter());
+    assert_eq!(commands[0], CanvasCommand::MoveTo { x: 1.0, y: 1.0 });
+    // The arc starts where the curve ends, there is no line in between.
+    assert_eq!(commands.len(), 9);
+    match commands[2] {
+        CanvasCommand::Ellipse { radius_x, anticlockwise, .. } => {
+            assert_eq!(radius_x, 2.0);
+            assert!(anticlockwise);
+        }
+        _ => panic!(),
+    }
+    assert_eq!(format!("{}", commands[7]), "closePath()");
+    assert_eq!(format!("{}", commands[8]), "quadraticCurveTo(20, 10, 20, 20)");
+
+    let mut replayed = CanvasPathBuilder::new(Path::builder());
+    for command in &commands {
+        command.replay(&mut replayed);
+    }
+    let replayed_commands = path_to_canvas_commands(replayed.build().path_iter());
+    assert_eq!(replayed_commands.len(), commands.len());
+    for (a, b) in commands.iter().zip(replayed_commands.iter()) {
+        match (*a, *b) {
+            (
+                CanvasCommand::Ellipse { start_angle: start_a, end_angle: end_a, .. },
+                CanvasCommand::Ellipse { start_angle: start_b, end_angle: end_b, .. },
+            ) => {
+                assert!((start_a - start_b).abs() < 1e-5);
+                assert!((end_a - end_b).abs() < 1e-5);
+            }
+            _ => {
+                assert_eq!(a, b);
+            }
+        }
+    }
+}
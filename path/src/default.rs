@@ -1,10 +1,11 @@
 use builder::{FlatPathBuilder, PathBuilder, SvgPathBuilder, FlatteningBuilder};
-use iterator::PathIter;
+use iterator::{PathIter, PathIterator, SegmentEvents};
 
 use PathEvent;
 use math::*;
 
-use std::iter::IntoIterator;
+use std::iter::{IntoIterator, FromIterator};
+use std::sync::Arc;
 
 /// Enumeration corresponding to the [PathEvent](https://docs.rs/lyon_core/*/lyon_core/events/enum.PathEvent.html) enum
 /// without the parameters.
@@ -22,6 +23,18 @@ pub enum Verb {
     Close,
 }
 
+impl Verb {
+    /// The number of points stored in the path for this verb.
+    pub fn num_points(self) -> usize {
+        match self {
+            Verb::MoveTo | Verb::LineTo => 1,
+            Verb::QuadraticTo => 2,
+            Verb::CubicTo | Verb::Arc => 3,
+            Verb::Close => 0,
+        }
+    }
+}
+
 /// A simple path data structure.
 ///
 /// It can be created using a [Builder](struct.Builder.html), and can be iterated over.
@@ -50,12 +63,26 @@ impl Path {
     }
 
     pub fn with_capacity(cap: usize) -> Path {
+        Path::with_capacities(cap, cap)
+    }
+
+    /// Constructor pre-allocating the point and verb buffers separately.
+    ///
+    /// Curves use more points than verbs, so this allows allocating exactly what a
+    /// path needs when its content is known in advance.
+    pub fn with_capacities(num_points: usize, num_verbs: usize) -> Path {
         Path {
-            points: Vec::with_capacity(cap),
-            verbs: Vec::with_capacity(cap),
+            points: Vec::with_capacity(num_points),
+            verbs: Vec::with_capacity(num_verbs),
         }
     }
 
+    /// Reserves capacity for at least `num_points` more points and `num_verbs` more verbs.
+    pub fn reserve(&mut self, num_points: usize, num_verbs: usize) {
+        self.points.reserve(num_points);
+        self.verbs.reserve(num_verbs);
+    }
+
     pub fn as_slice(&self) -> PathSlice {
         PathSlice {
             points: &self.points[..],
@@ -67,12 +94,53 @@ impl Path {
 
     pub fn path_iter(&self) -> PathIter<Iter> { PathIter::new(self.iter()) }
 
+    /// Returns an iterator over events that contain the starting point of each segment.
+    ///
+    /// See `PathIterator::segment_events`.
+    pub fn segment_events(&self) -> SegmentEvents<PathIter<Iter<'_>>> { self.path_iter().segment_events() }
+
+    /// Returns an iterator over the verbs of the path and the index of their first point.
+    ///
+    /// See `PathSlice::id_iter`.
+    pub fn id_iter(&self) -> IdIter<'_> { self.as_slice().id_iter() }
+
     pub fn points(&self) -> &[Point] { &self.points[..] }
 
     pub fn mut_points(&mut self) -> &mut [Point] { &mut self.points[..] }
 
     pub fn verbs(&self) -> &[Verb] { &self.verbs[..] }
 
+    /// Returns an iterator over the sub-paths of this path.
+    ///
+    /// See `PathSlice::subpaths`.
+    pub fn subpaths(&self) -> Subpaths<'_> { self.as_slice().subpaths() }
+
+    /// Returns the sub-path at a given index.
+    ///
+    /// Panics if the path has less than `index + 1` sub-paths.
+    pub fn subpath(&self, index: usize) -> PathSlice<'_> { self.as_slice().subpath(index) }
+
+    pub fn num_subpaths(&self) -> usize { self.as_slice().num_subpaths() }
+
+    pub fn is_empty(&self) -> bool { self.verbs.is_empty() }
+
+    /// See `PathSlice::first_point`.
+    pub fn first_point(&self) -> Option<Point> { self.as_slice().first_point() }
+
+    /// See `PathSlice::last_point`.
+    pub fn last_point(&self) -> Option<Point> { self.as_slice().last_point() }
+
+    /// See `PathSlice::is_closed`.
+    pub fn is_closed(&self) -> bool { self.as_slice().is_closed() }
+
+    /// Creates a path for each sub-path of this path.
+    pub fn split_subpaths(&self) -> Vec<Path> {
+        self.subpaths().map(|subpath| Path {
+            points: subpath.points.to_vec(),
+            verbs: subpath.verbs.to_vec(),
+        }).collect()
+    }
+
     /// Consumes two paths and builds one that contains them.
     pub fn merge(mut self, other: Self) -> Self {
         if other.verbs.is_empty() {
@@ -89,6 +157,52 @@ impl Path {
 
         self
     }
+
+    /// Builds a path containing a closed sub-path for each of the provided polygons,
+    /// for example the outer rings and holes of multi-polygons.
+    ///
+    /// The storage is allocated once for all polygons. Empty polygons are skipped.
+    pub fn from_polygons<Polygons, Polygon>(polygons: Polygons) -> Path
+    where
+        Polygons: IntoIterator<Item = Polygon>,
+        Polygons::IntoIter: Clone,
+        Polygon: AsRef<[Point]>,
+    {
+        let polygons = polygons.into_iter();
+
+        let mut num_points = 0;
+        let mut num_polygons = 0;
+        for polygon in polygons.clone() {
+            let len = polygon.as_ref().len();
+            if len > 0 {
+                num_points += len;
+                num_polygons += 1;
+            }
+        }
+
+        let mut path = Path {
+            points: Vec::with_capacity(num_points),
+            verbs: Vec::with_capacity(num_points + num_polygons),
+        };
+
+        for polygon in polygons {
+            let points = polygon.as_ref();
+            if points.is_empty() {
+                continue;
+            }
+            for p in points {
+                nan_check(*p);
+            }
+            path.points.extend_from_slice(points);
+            path.verbs.push(Verb::MoveTo);
+            for _ in 1..points.len() {
+                path.verbs.push(Verb::LineTo);
+            }
+            path.verbs.push(Verb::Close);
+        }
+
+        path
+    }
 }
 
 impl<'l> IntoIterator for &'l Path {
@@ -98,6 +212,73 @@ impl<'l> IntoIterator for &'l Path {
     fn into_iter(self) -> Iter<'l> { self.iter() }
 }
 
+/// Builds a path from a sequence of events.
+///
+/// Events containing NaN or infinite coordinates are skipped.
+impl FromIterator<PathEvent> for Path {
+    fn from_iter<Iter: IntoIterator<Item = PathEvent>>(events: Iter) -> Path {
+        let events = events.into_iter();
+        let mut builder = Builder::with_capacity(events.size_hint().0);
+        for evt in events {
+            if evt.is_finite() {
+                builder.path_event(evt);
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// A reference-counted, immutable path that can be cheaply cloned and shared between threads.
+///
+/// Mutating an `ArcPath` with `make_mut` only copies the path if it is shared.
+#[derive(Clone, Debug, Default)]
+pub struct ArcPath {
+    path: Arc<Path>,
+}
+
+impl ArcPath {
+    pub fn new(path: Path) -> Self {
+        ArcPath { path: Arc::new(path) }
+    }
+
+    pub fn as_slice(&self) -> PathSlice<'_> { self.path.as_slice() }
+
+    pub fn iter(&self) -> Iter<'_> { self.path.iter() }
+
+    pub fn path_iter(&self) -> PathIter<Iter<'_>> { self.path.path_iter() }
+
+    pub fn points(&self) -> &[Point] { self.path.points() }
+
+    pub fn verbs(&self) -> &[Verb] { self.path.verbs() }
+
+    /// Returns a mutable reference to the path, copying it first if it is shared
+    /// with other `ArcPath` objects.
+    pub fn make_mut(&mut self) -> &mut Path { Arc::make_mut(&mut self.path) }
+
+    /// Returns the path, copying it if it is shared with other `ArcPath` objects.
+    pub fn into_path(self) -> Path {
+        match Arc::try_unwrap(self.path) {
+            Ok(path) => path,
+            Err(shared) => (*shared).clone(),
+        }
+    }
+
+    /// Returns true if both objects share the same storage.
+    pub fn ptr_eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.path, &other.path) }
+}
+
+impl From<Path> for ArcPath {
+    fn from(path: Path) -> Self { ArcPath::new(path) }
+}
+
+impl<'l> IntoIterator for &'l ArcPath {
+    type Item = PathEvent;
+    type IntoIter = Iter<'l>;
+
+    fn into_iter(self) -> Iter<'l> { self.iter() }
+}
+
 /// An immutable view over a Path.
 impl<'l> PathSlice<'l> {
     pub fn new(points: &'l [Point], verbs: &'l [Verb]) -> PathSlice<'l> {
@@ -111,17 +292,146 @@ impl<'l> PathSlice<'l> {
 
     pub fn path_iter(&self) -> PathIter<Iter> { PathIter::new(self.iter()) }
 
+    /// Returns an iterator over events that contain the starting point of each segment.
+    ///
+    /// See `PathIterator::segment_events`.
+    pub fn segment_events(&self) -> SegmentEvents<PathIter<Iter<'_>>> { self.path_iter().segment_events() }
+
+    /// Returns an iterator over the verbs of the path and the index of their first point.
+    ///
+    /// The points of an event are stored contiguously in `points()`, starting at this index
+    /// (see `Verb::num_points`). This is a compact way to refer to the events of a path
+    /// without copying their points.
+    pub fn id_iter(&self) -> IdIter<'l> {
+        IdIter {
+            verbs: self.verbs.iter(),
+            current_point: 0,
+        }
+    }
+
     pub fn points(&self) -> &[Point] { self.points }
 
     pub fn verbs(&self) -> &[Verb] { self.verbs }
+
+    /// Returns an iterator over the sub-paths of this path.
+    ///
+    /// A new sub-path starts at each `MoveTo` event. If the path doesn't start with a
+    /// `MoveTo` event, the events before the first one form a sub-path as well.
+    pub fn subpaths(&self) -> Subpaths<'l> {
+        Subpaths {
+            points: self.points,
+            verbs: self.verbs,
+        }
+    }
+
+    /// Returns the sub-path at a given index.
+    ///
+    /// Panics if the path has less than `index + 1` sub-paths.
+    pub fn subpath(&self, index: usize) -> PathSlice<'l> {
+        match self.subpaths().nth(index) {
+            Some(subpath) => subpath,
+            None => panic!("Sub-path index {} out of bounds", index),
+        }
+    }
+
+    pub fn num_subpaths(&self) -> usize { self.subpaths().count() }
+
+    pub fn is_empty(&self) -> bool { self.verbs.is_empty() }
+
+    /// Returns the first point of the path, if any.
+    pub fn first_point(&self) -> Option<Point> { self.points.first().cloned() }
+
+    /// Returns the position at the end of the path, if any.
+    ///
+    /// If the path ends with a close event, this is the first point of the last sub-path.
+    pub fn last_point(&self) -> Option<Point> {
+        if self.verbs.last() != Some(&Verb::Close) {
+            return self.points.last().cloned();
+        }
+
+        let last = self.subpaths().last().unwrap();
+        if last.verbs[0] == Verb::MoveTo {
+            Some(last.points[0])
+        } else {
+            // A path that doesn't begin with a move event implicitly starts at the origin.
+            Some(point(0.0, 0.0))
+        }
+    }
+
+    /// Returns whether the path ends with a close event.
+    ///
+    /// This is meant to be used on the individual sub-paths returned by `subpaths`.
+    pub fn is_closed(&self) -> bool { self.verbs.last() == Some(&Verb::Close) }
+}
+
+/// An iterator over the sub-paths of a path.
+///
+/// See `PathSlice::subpaths`.
+#[derive(Clone, Debug)]
+pub struct Subpaths<'l> {
+    points: &'l [Point],
+    verbs: &'l [Verb],
 }
 
-//impl<'l> IntoIterator for PathSlice<'l> {
-//    type Item = PathEvent;
-//    type IntoIter = Iter<'l>;
-//
-//    fn into_iter(self) -> Iter<'l> { self.iter() }
-//}
+impl<'l> Iterator for Subpaths<'l> {
+    type Item = PathSlice<'l>;
+
+    fn next(&mut self) -> Option<PathSlice<'l>> {
+        if self.verbs.is_empty() {
+            return None;
+        }
+
+        let mut num_points = 0;
+        let mut num_verbs = 0;
+        for (i, verb) in self.verbs.iter().enumerate() {
+            if i > 0 && *verb == Verb::MoveTo {
+                break;
+            }
+            num_points += verb.num_points();
+            num_verbs += 1;
+        }
+
+        let subpath = PathSlice {
+            points: &self.points[..num_points],
+            verbs: &self.verbs[..num_verbs],
+        };
+        self.points = &self.points[num_points..];
+        self.verbs = &self.verbs[num_verbs..];
+
+        Some(subpath)
+    }
+}
+
+/// An iterator over the verbs of a path and the index of their first point.
+///
+/// See `PathSlice::id_iter`.
+#[derive(Clone, Debug)]
+pub struct IdIter<'l> {
+    verbs: ::std::slice::Iter<'l, Verb>,
+    current_point: usize,
+}
+
+impl<'l> Iterator for IdIter<'l> {
+    type Item = (Verb, usize);
+
+    fn next(&mut self) -> Option<(Verb, usize)> {
+        let verb = match self.verbs.next() {
+            Some(verb) => *verb,
+            None => return None,
+        };
+        let first_point = self.current_point;
+        self.current_point += verb.num_points();
+
+        Some((verb, first_point))
+    }
+}
+
+impl<'l> IntoIterator for PathSlice<'l> {
+    type Item = PathEvent;
+    type IntoIter = Iter<'l>;
+
+    fn into_iter(self) -> Iter<'l> { Iter::new(self.points, self.verbs) }
+}
 
 /// Builds path object using the FlatPathBuilder interface.
 ///
@@ -137,14 +447,24 @@ impl Builder {
     pub fn new() -> Self { Builder::with_capacity(128) }
 
     pub fn with_capacity(cap: usize) -> Self {
+        Builder::with_capacities(cap, cap)
+    }
+
+    /// Constructor pre-allocating the point and verb buffers separately.
+    pub fn with_capacities(num_points: usize, num_verbs: usize) -> Self {
         Builder {
-            path: Path::with_capacity(cap),
+            path: Path::with_capacities(num_points, num_verbs),
             current_position: Point::new(0.0, 0.0),
             first_position: Point::new(0.0, 0.0),
             building: false,
         }
     }
 
+    /// Reserves capacity for at least `num_points` more points and `num_verbs` more verbs.
+    pub fn reserve(&mut self, num_points: usize, num_verbs: usize) {
+        self.path.reserve(num_points, num_verbs);
+    }
+
     pub fn with_svg(self) -> SvgPathBuilder<Self> { SvgPathBuilder::new(self) }
 
     pub fn flattened(self, tolerance: f32) -> FlatteningBuilder<Self> {
@@ -204,7 +524,7 @@ impl FlatPathBuilder for Builder {
         self.current_position = Point::new(0.0, 0.0);
         self.first_position = Point::new(0.0, 0.0);
         self.building = false;
-        let mut tmp = Path::with_capacity(self.path.verbs.len());
+        let mut tmp = Path::with_capacities(self.path.points.len(), self.path.verbs.len());
         ::std::mem::swap(&mut self.path, &mut tmp);
 
         tmp
@@ -635,3 +955,394 @@ fn test_path_builder_close_empty() {
     let _ = builder.build();
 }
 */
+
+#[test]
+fn test_from_polygons() {
+    let outer = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)];
+    let hole = [point(2.0, 2.0), point(2.0, 8.0), point(8.0, 8.0)];
+
+    let path = Path::from_polygons(vec![&outer[..], &[][..], &hole[..]]);
+
+    let mut builder = Path::builder();
+    builder.move_to(outer[0]);
+    builder.line_to(outer[1]);
+    builder.line_to(outer[2]);
+    builder.line_to(outer[3]);
+    builder.close();
+    builder.move_to(hole[0]);
+    builder.line_to(hole[1]);
+    builder.line_to(hole[2]);
+    builder.close();
+    let expected = builder.build();
+
+    assert_eq!(path.points(), expected.points());
+    assert_eq!(path.verbs(), expected.verbs());
+    assert_eq!(path.points.capacity(), 7);
+}
+
+#[test]
+fn test_path_from_iter() {
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::LineTo(point(f32::NAN, 0.0)),
+        PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0)),
+        PathEvent::Close,
+    ];
+
+    let path: Path = events.iter().cloned().collect();
+
+    let mut it = path.as_slice().into_iter();
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(0.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(point(1.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0))));
+    assert_eq!(it.next(), Some(PathEvent::Close));
+    assert_eq!(it.next(), None);
+
+    let copy: Path = path.iter().collect();
+    assert_eq!(copy.points(), path.points());
+    assert_eq!(copy.verbs(), path.verbs());
+}
+
+#[test]
+fn test_points_through() {
+    use builder::SplineBuilder;
+
+    let points = [point(0.0, 0.0), point(1.0, 1.0), point(2.0, 0.0)];
+
+    let mut builder = Path::builder();
+    builder.points_through(&points, 0.0, false);
+    let path = builder.build();
+    assert_eq!(path.verbs(), &[Verb::MoveTo, Verb::CubicTo, Verb::CubicTo]);
+    let expected = [
+        point(0.0, 0.0),
+        point(1.0 / 6.0, 1.0 / 6.0), point(2.0 / 3.0, 1.0), point(1.0, 1.0),
+        point(4.0 / 3.0, 1.0), point(11.0 / 6.0, 1.0 / 6.0), point(2.0, 0.0),
+    ];
+    for (p, e) in path.points().iter().zip(expected.iter()) {
+        assert!((*p - *e).length() < 0.0001, "{:?} != {:?}", p, e);
+    }
+
+    // With a tension of one, the curves are straight lines.
+    let mut builder = Path::builder();
+    builder.points_through(&points, 1.0, true);
+    let path = builder.build();
+    assert_eq!(path.verbs(), &[Verb::MoveTo, Verb::CubicTo, Verb::CubicTo, Verb::CubicTo, Verb::Close]);
+    assert_eq!(&path.points()[1..4], &[point(0.0, 0.0), point(1.0, 1.0), point(1.0, 1.0)]);
+}
+
+#[test]
+fn test_circle() {
+    use builder::EllipseBuilder;
+    use geom::Circle;
+    use FlattenedEvent;
+    use iterator::PathIterator;
+
+    let mut builder = Path::builder();
+    builder.circle(&Circle::new(point(1.0, 2.0), 10.0));
+    let path = builder.build();
+
+    assert_eq!(path.verbs(), &[Verb::MoveTo, Verb::Arc, Verb::Close]);
+
+    let mut last = point(0.0, 0.0);
+    for evt in path.path_iter().flattened(0.01) {
+        match evt {
+            FlattenedEvent::MoveTo(p) => { assert_eq!(p, point(11.0, 2.0)); }
+            FlattenedEvent::LineTo(p) => {
+                assert!(((p - point(1.0, 2.0)).length() - 10.0).abs() < 0.01);
+                last = p;
+            }
+            FlattenedEvent::Close => {}
+        }
+    }
+    assert!((last - point(11.0, 2.0)).length() < 0.001);
+}
+
+#[test]
+fn test_rounded_rect() {
+    use builder::RoundedRectBuilder;
+    use geom::{RoundedRect, CornerRadii};
+
+    let mut builder = Path::builder();
+    builder.rounded_rect(&RoundedRect::new(
+        rect(0.0, 0.0, 100.0, 50.0),
+        CornerRadii::new(10.0, 0.0, 0.0, 0.0),
+    ));
+    let path = builder.build();
+
+    // The sharp corners don't add arcs.
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.len(), 7);
+    assert_eq!(&events[..5], &[
+        PathEvent::MoveTo(point(10.0, 0.0)),
+        PathEvent::LineTo(point(100.0, 0.0)),
+        PathEvent::LineTo(point(100.0, 50.0)),
+        PathEvent::LineTo(point(0.0, 50.0)),
+        PathEvent::LineTo(point(0.0, 10.0)),
+    ]);
+    match events[5] {
+        PathEvent::Arc(center, radii, sweep_angle, _) => {
+            assert_eq!(center, point(10.0, 10.0));
+            assert_eq!(radii, vector(10.0, 10.0));
+            assert!((sweep_angle.get() - ::std::f32::consts::FRAC_PI_2).abs() < 0.0001);
+        }
+        evt => panic!("expected an arc, got {:?}", evt),
+    }
+    assert_eq!(events[6], PathEvent::Close);
+}
+
+#[test]
+fn test_chart_shapes() {
+    use builder::{ChartBuilder, StepPosition};
+
+    let series = [point(0.0, 1.0), point(2.0, 3.0), point(4.0, 2.0)];
+
+    let mut builder = Path::builder();
+    builder.step_line(&series, StepPosition::After);
+    builder.step_line(&series, StepPosition::Before);
+    builder.step_line(&series, StepPosition::Middle);
+    builder.step_line(&[], StepPosition::Middle);
+    let path = builder.build();
+    assert_eq!(
+        &path.points()[..5],
+        &[point(0.0, 1.0), point(2.0, 1.0), point(2.0, 3.0), point(4.0, 3.0), point(4.0, 2.0)]
+    );
+    assert_eq!(
+        &path.points()[5..10],
+        &[point(0.0, 1.0), point(0.0, 3.0), point(2.0, 3.0), point(2.0, 2.0), point(4.0, 2.0)]
+    );
+    assert_eq!(
+        &path.points()[10..],
+        &[
+            point(0.0, 1.0), point(1.0, 1.0), point(1.0, 3.0), point(2.0, 3.0),
+            point(3.0, 3.0), point(3.0, 2.0), point(4.0, 2.0),
+        ]
+    );
+
+    let mut builder = Path::builder();
+    builder.area_to_baseline(&series, 0.0);
+    let path = builder.build();
+    assert_eq!(
+        path.points(),
+        &[point(0.0, 0.0), point(0.0, 1.0), point(2.0, 3.0), point(4.0, 2.0), point(4.0, 0.0)]
+    );
+    assert_eq!(*path.verbs().last().unwrap(), Verb::Close);
+
+    let mut builder = Path::builder();
+    builder.step_area_to_baseline(&series, StepPosition::After, 0.0);
+    let path = builder.build();
+    assert_eq!(path.points()[..2], [point(0.0, 0.0), point(0.0, 1.0)]);
+    assert_eq!(path.points()[path.points().len() - 1], point(4.0, 0.0));
+    assert_eq!(*path.verbs().last().unwrap(), Verb::Close);
+
+    let lower = [point(0.0, 0.0), point(4.0, 1.0)];
+    let mut builder = Path::builder();
+    builder.area_between(&series, &lower);
+    let path = builder.build();
+    assert_eq!(
+        path.points(),
+        &[point(0.0, 1.0), point(2.0, 3.0), point(4.0, 2.0), point(4.0, 1.0), point(0.0, 0.0)]
+    );
+    assert_eq!(*path.verbs().last().unwrap(), Verb::Close);
+}
+
+#[test]
+fn test_curve_from_fn() {
+    use geom::CubicBezierSegment;
+
+    let mut builder = Path::builder();
+    builder.curve_from_fn(
+        |t| point(f32::cos(t), f32::sin(t)),
+        0.0..::std::f32::consts::PI * 2.0,
+        0.001,
+    );
+    let path = builder.build();
+
+    assert_eq!(path.points()[0], point(1.0, 0.0));
+    assert!((*path.points().last().unwrap() - point(1.0, 0.0)).length() < 1e-5);
+
+    let mut from = point(1.0, 0.0);
+    let mut num_curves = 0;
+    for evt in path.iter() {
+        if let PathEvent::CubicTo(ctrl1, ctrl2, to) = evt {
+            let curve = CubicBezierSegment { from, ctrl1, ctrl2, to };
+            for i in 0..10 {
+                let p = curve.sample(i as f32 / 10.0);
+                assert!((p.to_vector().length() - 1.0).abs() < 0.002);
+            }
+            from = to;
+            num_curves += 1;
+        }
+    }
+    assert!(num_curves >= 8);
+    assert!(num_curves <= 16);
+}
+
+#[test]
+fn test_spiral() {
+    use builder::SpiralBuilder;
+    use geom::ArchimedeanSpiral;
+
+    let mut builder = Path::builder();
+    builder.archimedean_spiral(
+        &ArchimedeanSpiral {
+            center: point(0.0, 0.0),
+            start_radius: 1.0,
+            end_radius: 3.0,
+            start_angle: Angle::radians(0.0),
+            sweep_angle: Angle::radians(::std::f32::consts::PI * 4.0),
+        },
+        0.01,
+    );
+    let path = builder.build();
+
+    assert_eq!(path.points()[0], point(1.0, 0.0));
+    assert_eq!(path.verbs()[0], Verb::MoveTo);
+    assert!(path.verbs()[1..].iter().all(|verb| *verb == Verb::CubicTo));
+    assert!((*path.points().last().unwrap() - point(3.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn test_subpaths() {
+    let mut builder = Path::builder();
+    builder.line_to(point(1.0, 0.0));
+    builder.move_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(11.0, 0.0), point(11.0, 1.0));
+    builder.close();
+    builder.line_to(point(12.0, 0.0));
+    builder.move_to(point(20.0, 0.0));
+    builder.cubic_bezier_to(point(21.0, 0.0), point(21.0, 1.0), point(20.0, 1.0));
+    builder.arc(point(20.0, 2.0), vector(1.0, 1.0), Angle::radians(1.0), Angle::radians(0.0));
+    builder.move_to(point(30.0, 0.0));
+    let path = builder.build();
+
+    let subpaths: Vec<PathSlice> = path.subpaths().collect();
+    assert_eq!(subpaths.len(), 4);
+    assert_eq!(subpaths[0].verbs(), &[Verb::LineTo]);
+    assert_eq!(subpaths[0].points(), &[point(1.0, 0.0)]);
+    assert_eq!(subpaths[1].verbs(), &[Verb::MoveTo, Verb::QuadraticTo, Verb::Close, Verb::LineTo]);
+    assert_eq!(subpaths[1].points().len(), 4);
+    assert_eq!(subpaths[2].verbs(), &[Verb::MoveTo, Verb::CubicTo, Verb::Arc]);
+    assert_eq!(subpaths[2].points().len(), 7);
+    assert_eq!(subpaths[3].points(), &[point(30.0, 0.0)]);
+
+    assert_eq!(path.subpath(2).points()[0], point(20.0, 0.0));
+
+    let paths = path.split_subpaths();
+    assert_eq!(paths.len(), 4);
+    assert_eq!(paths[1].iter().collect::<Vec<_>>(), subpaths[1].iter().collect::<Vec<_>>());
+    let num_points: usize = paths.iter().map(|p| p.points().len()).sum();
+    assert_eq!(num_points, path.points().len());
+
+    assert_eq!(Path::new().subpaths().count(), 0);
+}
+
+#[test]
+fn test_endpoint_queries() {
+    let path = Path::new();
+    assert!(path.is_empty());
+    assert_eq!(path.num_subpaths(), 0);
+    assert_eq!(path.first_point(), None);
+    assert_eq!(path.last_point(), None);
+    assert!(!path.is_closed());
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(11.0, 0.0), point(11.0, 1.0));
+    let path = builder.build();
+
+    assert!(!path.is_empty());
+    assert_eq!(path.num_subpaths(), 2);
+    assert_eq!(path.first_point(), Some(point(1.0, 0.0)));
+    assert_eq!(path.last_point(), Some(point(11.0, 1.0)));
+    assert!(!path.is_closed());
+    assert!(path.subpath(0).is_closed());
+    assert_eq!(path.subpath(0).last_point(), Some(point(1.0, 0.0)));
+    assert!(!path.subpath(1).is_closed());
+    assert_eq!(path.subpath(1).first_point(), Some(point(10.0, 0.0)));
+}
+
+#[test]
+#[should_panic]
+fn test_subpath_out_of_bounds() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.build().subpath(1);
+}
+
+#[test]
+fn builder_with_capacities() {
+    let mut p = Builder::with_capacities(4, 3);
+    p.move_to(point(0.0, 0.0));
+    p.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0));
+    p.line_to(point(0.0, 1.0));
+    let path = p.build();
+
+    // The exact amount of memory was allocated up front.
+    assert_eq!(path.points.capacity(), 4);
+    assert_eq!(path.verbs.capacity(), 3);
+    assert_eq!(path.points.len(), 4);
+    assert_eq!(path.verbs.len(), 3);
+
+    let mut path = Path::with_capacities(0, 0);
+    path.reserve(10, 5);
+    assert!(path.points.capacity() >= 10);
+    assert!(path.verbs.capacity() >= 5);
+}
+
+#[test]
+fn arc_path_copy_on_write() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    let path = ArcPath::new(builder.build());
+    assert_send_sync(&path);
+    assert_send_sync(&path.as_slice());
+
+    let mut copy = path.clone();
+    assert!(copy.ptr_eq(&path));
+
+    copy.make_mut().mut_points()[1] = point(2.0, 0.0);
+    assert!(!copy.ptr_eq(&path));
+    assert_eq!(path.points()[1], point(1.0, 0.0));
+    assert_eq!(copy.points()[1], point(2.0, 0.0));
+
+    let shared = path.clone();
+    assert_eq!(path.into_path().points()[1], point(1.0, 0.0));
+    assert_eq!(shared.into_path().verbs(), &[Verb::MoveTo, Verb::LineTo]);
+}
+
+#[test]
+fn test_id_iter() {
+    use SegmentEvent;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    builder.move_to(point(5.0, 5.0));
+    builder.cubic_bezier_to(point(6.0, 5.0), point(6.0, 6.0), point(5.0, 6.0));
+    let path = builder.build();
+
+    let ids: Vec<(Verb, usize)> = path.id_iter().collect();
+    assert_eq!(ids, vec![
+        (Verb::MoveTo, 0),
+        (Verb::QuadraticTo, 1),
+        (Verb::LineTo, 3),
+        (Verb::Close, 4),
+        (Verb::MoveTo, 4),
+        (Verb::CubicTo, 5),
+    ]);
+    assert_eq!(path.points()[3], point(0.0, 1.0));
+
+    let num_segments = path.segment_events().filter(|evt| matches!(*evt, SegmentEvent::Segment(..))).count();
+    assert_eq!(num_segments, 3);
+}
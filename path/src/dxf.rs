@@ -0,0 +1,234 @@
+//! Conversions between paths and the polylines of CAD exchange formats.
+//!
+//! DXF's `LWPOLYLINE` entity (and equivalent primitives in other CAD formats) is a
+//! sequence of vertices connected by line segments or circular arcs. The arc between
+//! a vertex and the next one is described by the vertex's "bulge": the tangent of a
+//! quarter of the arc's sweep angle, zero meaning a straight line.
+//!
+//! This module requires the `dxf` feature.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::dxf::{LwPolyline, LwPolylineVertex, build_lw_polyline, path_to_lw_polylines};
+//! use lyon_path::default::Path;
+//! use lyon_path::builder::*;
+//! use lyon_path::math::point;
+//!
+//! // A 10x10 square with a half circle on the right side.
+//! let polyline = LwPolyline {
+//!     vertices: vec![
+//!         LwPolylineVertex { position: point(0.0, 0.0), bulge: 0.0 },
+//!         LwPolylineVertex { position: point(10.0, 0.0), bulge: 1.0 },
+//!         LwPolylineVertex { position: point(10.0, 10.0), bulge: 0.0 },
+//!         LwPolylineVertex { position: point(0.0, 10.0), bulge: 0.0 },
+//!     ],
+//!     closed: true,
+//! };
+//!
+//! let mut builder = Path::builder();
+//! build_lw_polyline(&polyline, &mut builder);
+//! let path = builder.build();
+//!
+//! let polylines = path_to_lw_polylines(path.path_iter(), 0.01);
+//! assert_eq!(polylines.len(), 1);
+//! assert_eq!(polylines[0].vertices.len(), 4);
+//! ```
+
+use math::Point;
+use geom::{Arc, QuadraticBezierSegment, CubicBezierSegment};
+use builder::PathBuilder;
+use iterator::PathIterator;
+use PathEvent;
+
+use std::f32::consts::PI;
+
+/// A vertex of a `LwPolyline`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LwPolylineVertex {
+    pub position: Point,
+    /// Describes the segment between this vertex and the next one.
+    ///
+    /// Zero for a line segment, otherwise the tangent of a quarter of the sweep angle
+    /// of a circular arc, positive if the arc goes in the direction of positive angles.
+    pub bulge: f32,
+}
+
+/// A sequence of line segments and circular arcs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LwPolyline {
+    pub vertices: Vec<LwPolylineVertex>,
+    /// Whether the last vertex is connected to the first one.
+    ///
+    /// The bulge of the last vertex is only used if the polyline is closed.
+    pub closed: bool,
+}
+
+/// Adds a polyline to a path builder as a sub-path.
+pub fn build_lw_polyline<Builder: PathBuilder>(polyline: &LwPolyline, builder: &mut Builder) {
+    let vertices = &polyline.vertices;
+    if vertices.is_empty() {
+        return;
+    }
+
+    builder.move_to(vertices[0].position);
+
+    let n = vertices.len();
+    let num_segments = if polyline.closed { n } else { n - 1 };
+    for i in 0..num_segments {
+        let from = vertices[i];
+        let to = vertices[(i + 1) % n].position;
+        if from.bulge == 0.0 || from.position == to {
+            // The closing line segment is implied by close().
+            if i + 1 < n {
+                builder.line_to(to);
+            }
+            continue;
+        }
+
+        let arc = Arc::from_bulge(from.position, to, from.bulge);
+        builder.arc(arc.center, arc.radii, arc.sweep_angle, arc.x_rotation);
+    }
+
+    if polyline.closed {
+        builder.close();
+    }
+}
+
+/// Converts each sub-path of a path into a polyline.
+///
+/// Line segments and circular arcs are converted exactly (arcs sweeping more than a half
+/// circle are split). Bézier curves and elliptic arcs are flattened with the provided
+/// tolerance. Sub-paths that have no segment are skipped.
+pub fn path_to_lw_polylines<Iter: PathIterator>(mut path: Iter, tolerance: f32) -> Vec<LwPolyline> {
+    let mut polylines = Vec::new();
+    let mut current = LwPolyline::default();
+
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                finish_polyline(&mut current, &mut polylines);
+                add_vertex(&mut current, to);
+            }
+            Some(PathEvent::LineTo(to)) => {
+                add_vertex(&mut current, to);
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let curve = QuadraticBezierSegment { from, ctrl, to };
+                curve.for_each_flattened(tolerance, &mut |p| add_vertex(&mut current, p));
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                let curve = CubicBezierSegment { from, ctrl1, ctrl2, to };
+                curve.for_each_flattened(tolerance, &mut |p| add_vertex(&mut current, p));
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                if (radii.x - radii.y).abs() > tolerance {
+                    arc.for_each_flattened(tolerance, &mut |p| add_vertex(&mut current, p));
+                    continue;
+                }
+
+                let num_parts = (sweep_angle.get().abs() / PI - 0.001).ceil().max(1.0);
+                let step = 1.0 / num_parts;
+                for i in 0..(num_parts as u32) {
+                    let part = arc.split_range((i as f32 * step)..((i + 1) as f32 * step));
+                    if let Some(last) = current.vertices.last_mut() {
+                        last.bulge = part.bulge();
+                    }
+                    add_vertex(&mut current, part.to());
+                }
+            }
+            Some(PathEvent::Close) => {
+                let n = current.vertices.len();
+                let square_tolerance = tolerance * tolerance;
+                if n > 1 && (current.vertices[n - 1].position - current.vertices[0].position).square_length() <= square_tolerance {
+                    // The closing segment is zero-length. Dropping the last vertex makes the
+                    // previous segment go back to the start.
+                    current.vertices.pop();
+                }
+                current.closed = true;
+                let first = current.vertices.first().map(|v| v.position);
+                finish_polyline(&mut current, &mut polylines);
+                if let Some(first) = first {
+                    add_vertex(&mut current, first);
+                }
+            }
+            None => {
+                finish_polyline(&mut current, &mut polylines);
+                return polylines;
+            }
+        }
+    }
+}
+
+fn add_vertex(polyline: &mut LwPolyline, position: Point) {
+    polyline.vertices.push(LwPolylineVertex { position, bulge: 0.0 });
+}
+
+fn finish_polyline(polyline: &mut LwPolyline, polylines: &mut Vec<LwPolyline>) {
+    if polyline.vertices.len() > 1 {
+        polylines.push(::std::mem::take(polyline));
+    } else {
+        polyline.vertices.clear();
+        polyline.closed = false;
+    }
+}
+
+#[test]
+fn lw_polyline_round_trip() {
+    use default::Path;
+    use builder::FlatPathBuilder;
+    use math::point;
+
+    let polyline = LwPolyline {
+        vertices: vec![
+            LwPolylineVertex { position: point(0.0, 0.0), bulge: 0.0 },
+            LwPolylineVertex { position: point(10.0, 0.0), bulge: 0.5 },
+            LwPolylineVertex { position: point(10.0, 10.0), bulge: 0.0 },
+            LwPolylineVertex { position: point(0.0, 10.0), bulge: -2.0 },
+        ],
+        closed: true,
+    };
+
+    let mut builder = Path::builder();
+    build_lw_polyline(&polyline, &mut builder);
+    let path = builder.build();
+
+    let polylines = path_to_lw_polylines(path.path_iter(), 0.01);
+    assert_eq!(polylines.len(), 1);
+    let result = &polylines[0];
+    assert!(result.closed);
+
+    // The last arc sweeps more than a half circle and is split in two.
+    assert_eq!(result.vertices.len(), 5);
+    for (a, b) in polyline.vertices.iter().zip(result.vertices.iter()).take(4) {
+        assert!((a.position - b.position).length() < 0.001);
+    }
+    assert!((result.vertices[1].bulge - 0.5).abs() < 0.001);
+    assert_eq!(result.vertices[2].bulge, 0.0);
+
+    // Both halves of the split arc have the same sweep angle.
+    let sweep = 4.0 * (-2.0f32).atan();
+    let half_bulge = (sweep / 8.0).tan();
+    assert!((result.vertices[3].bulge - half_bulge).abs() < 0.001);
+    assert!((result.vertices[4].bulge - half_bulge).abs() < 0.001);
+
+    // An open polyline, the bulge of the last vertex is ignored.
+    let polyline = LwPolyline {
+        vertices: vec![
+            LwPolylineVertex { position: point(0.0, 0.0), bulge: 1.0 },
+            LwPolylineVertex { position: point(2.0, 0.0), bulge: 1.0 },
+        ],
+        closed: false,
+    };
+    let mut builder = Path::builder();
+    build_lw_polyline(&polyline, &mut builder);
+    let path = builder.build();
+    let polylines = path_to_lw_polylines(path.path_iter(), 0.01);
+    assert!(!polylines[0].closed);
+    assert_eq!(polylines[0].vertices.len(), 2);
+    assert!((polylines[0].vertices[0].bulge - 1.0).abs() < 0.001);
+    assert_eq!(polylines[0].vertices[1].bulge, 0.0);
+}
@@ -37,6 +37,26 @@ pub enum PathEvent {
     Close,
 }
 
+impl PathEvent {
+    /// Returns false if any of the event's coordinates is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        fn finite(p: &Point) -> bool { p.x.is_finite() && p.y.is_finite() }
+        match *self {
+            PathEvent::MoveTo(ref to) | PathEvent::LineTo(ref to) => finite(to),
+            PathEvent::QuadraticTo(ref ctrl, ref to) => finite(ctrl) && finite(to),
+            PathEvent::CubicTo(ref ctrl1, ref ctrl2, ref to) => {
+                finite(ctrl1) && finite(ctrl2) && finite(to)
+            }
+            PathEvent::Arc(ref center, ref radii, ref sweep_angle, ref x_rotation) => {
+                finite(center)
+                    && radii.x.is_finite() && radii.y.is_finite()
+                    && sweep_angle.radians.is_finite() && x_rotation.radians.is_finite()
+            }
+            PathEvent::Close => true,
+        }
+    }
+}
+
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum QuadraticEvent {
@@ -101,7 +121,7 @@ impl QuadraticEvent {
 }
 
 // TODO: serialization
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Segment {
     Line(LineSegment<f32>),
     Quadratic(QuadraticBezierSegment<f32>),
@@ -109,6 +129,22 @@ pub enum Segment {
     Arc(Arc<f32>),
 }
 
+/// Path events that don't depend on the previous ones.
+///
+/// Unlike `PathEvent`, each segment contains its starting point, so the events can be
+/// processed independently (for example in parallel) without tracking the current position.
+/// Sub-paths are delimited by a `Begin` and an `End` event.
+///
+/// See `PathIterator::segment_events`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SegmentEvent {
+    Begin { at: Point },
+    Segment(Segment),
+    /// Ends the current sub-path. If `close` is true, the sub-path is closed with a line
+    /// from `last` to `first`.
+    End { last: Point, first: Point, close: bool },
+}
+
 impl Transform for FlattenedEvent {
     fn transform(&self, mat: &Transform2D) -> Self {
         match self {
@@ -0,0 +1,510 @@
+//! A path representation using fixed-point integer coordinates.
+//!
+//! Coordinates are stored in 26.6 fixed-point format (like FreeType): signed 32 bits
+//! integers where the 6 least significant bits are the fractional part, which gives a
+//! precision of 1/64th of a unit.
+//!
+//! Float computations can produce different results depending on the platform, the
+//! compiler or the optimization level. Once a path is converted to fixed-point, the
+//! operations provided here (flattening, orientation and intersection predicates) only
+//! use integer arithmetic and produce the same results everywhere, which is what
+//! lockstep simulations and reproducible builds need. Note that the conversion from
+//! floats itself is only deterministic if the float input is.
+//!
+//! Arcs don't have an exact fixed-point representation and are approximated with
+//! quadratic bézier curves when converting to a `FixedPath`.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::fixed::{FixedPath, FixedPoint, FixedEvent};
+//! use lyon_path::default::Path;
+//! use lyon_path::builder::*;
+//! use lyon_path::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.quadratic_bezier_to(point(1.0, 2.0), point(2.0, 0.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let fixed_path = FixedPath::from_path(path.path_iter());
+//! assert_eq!(fixed_path.points()[2], FixedPoint::new(128, 0));
+//!
+//! // Flatten with a tolerance of 1/16th of a unit.
+//! let flattened = fixed_path.flattened(4);
+//! for event in flattened.iter() {
+//!     match event {
+//!         FixedEvent::MoveTo(_) | FixedEvent::LineTo(_) | FixedEvent::Close => {}
+//!         _ => panic!(),
+//!     }
+//! }
+//! ```
+
+use math::{Point, point};
+use geom::Arc;
+use default::{Path, Verb};
+use builder::{FlatPathBuilder, PathBuilder};
+use iterator::PathIterator;
+use PathEvent;
+
+use std::cmp::Ordering;
+use std::iter::{IntoIterator, FromIterator};
+use std::ops::{Add, Sub};
+
+/// Number of bits of the fractional part of the coordinates.
+pub const FRACTIONAL_BITS: u32 = 6;
+
+const ONE: f64 = (1 << FRACTIONAL_BITS) as f64;
+
+/// Beyond this depth, curves are not subdivided further when flattening.
+const MAX_SUBDIVISION_DEPTH: u32 = 16;
+
+/// A point with 26.6 fixed-point coordinates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct FixedPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl FixedPoint {
+    /// Constructor taking the raw fixed-point values.
+    #[inline]
+    pub fn new(x: i32, y: i32) -> Self {
+        FixedPoint { x, y }
+    }
+
+    /// Converts a float point, rounding to the nearest representable value.
+    ///
+    /// Values outside of the representable range are clamped.
+    #[inline]
+    pub fn from_point(p: Point) -> Self {
+        FixedPoint {
+            x: to_fixed(p.x),
+            y: to_fixed(p.y),
+        }
+    }
+
+    #[inline]
+    pub fn to_point(self) -> Point {
+        point(
+            (f64::from(self.x) / ONE) as f32,
+            (f64::from(self.y) / ONE) as f32,
+        )
+    }
+}
+
+impl Add for FixedPoint {
+    type Output = Self;
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        FixedPoint::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for FixedPoint {
+    type Output = Self;
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        FixedPoint::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+fn to_fixed(v: f32) -> i32 {
+    debug_assert!(!v.is_nan());
+    let v = (f64::from(v) * ONE).round();
+    v.max(f64::from(i32::MIN)).min(f64::from(i32::MAX)) as i32
+}
+
+/// The equivalent of `PathEvent` with fixed-point coordinates.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum FixedEvent {
+    MoveTo(FixedPoint),
+    LineTo(FixedPoint),
+    QuadraticTo(FixedPoint, FixedPoint),
+    CubicTo(FixedPoint, FixedPoint, FixedPoint),
+    Close,
+}
+
+impl FixedEvent {
+    pub fn to_path_event(self) -> PathEvent {
+        match self {
+            FixedEvent::MoveTo(to) => PathEvent::MoveTo(to.to_point()),
+            FixedEvent::LineTo(to) => PathEvent::LineTo(to.to_point()),
+            FixedEvent::QuadraticTo(ctrl, to) => PathEvent::QuadraticTo(ctrl.to_point(), to.to_point()),
+            FixedEvent::CubicTo(ctrl1, ctrl2, to) => {
+                PathEvent::CubicTo(ctrl1.to_point(), ctrl2.to_point(), to.to_point())
+            }
+            FixedEvent::Close => PathEvent::Close,
+        }
+    }
+}
+
+/// A path data structure storing 26.6 fixed-point coordinates.
+///
+/// It never contains `Verb::Arc`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct FixedPath {
+    points: Vec<FixedPoint>,
+    verbs: Vec<Verb>,
+}
+
+impl FixedPath {
+    pub fn new() -> Self {
+        FixedPath {
+            points: Vec::new(),
+            verbs: Vec::new(),
+        }
+    }
+
+    /// Converts a float path, rounding the coordinates to the nearest representable values.
+    pub fn from_path<Iter: PathIterator>(mut path: Iter) -> Self {
+        let mut result = FixedPath::new();
+        loop {
+            let from = path.get_state().current;
+            let event = match path.next() {
+                Some(event) => event,
+                None => {
+                    return result;
+                }
+            };
+            match event {
+                PathEvent::MoveTo(to) => {
+                    result.push(FixedEvent::MoveTo(FixedPoint::from_point(to)));
+                }
+                PathEvent::LineTo(to) => {
+                    result.push(FixedEvent::LineTo(FixedPoint::from_point(to)));
+                }
+                PathEvent::QuadraticTo(ctrl, to) => {
+                    result.push(FixedEvent::QuadraticTo(
+                        FixedPoint::from_point(ctrl),
+                        FixedPoint::from_point(to),
+                    ));
+                }
+                PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                    result.push(FixedEvent::CubicTo(
+                        FixedPoint::from_point(ctrl1),
+                        FixedPoint::from_point(ctrl2),
+                        FixedPoint::from_point(to),
+                    ));
+                }
+                PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                    let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                    let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                    arc.for_each_quadratic_bezier(&mut |curve| {
+                        result.push(FixedEvent::QuadraticTo(
+                            FixedPoint::from_point(curve.ctrl),
+                            FixedPoint::from_point(curve.to),
+                        ));
+                    });
+                }
+                PathEvent::Close => {
+                    result.push(FixedEvent::Close);
+                }
+            }
+        }
+    }
+
+    /// Converts into a float path.
+    pub fn to_path(&self) -> Path {
+        let mut builder = Path::builder();
+        for event in self.iter() {
+            builder.path_event(event.to_path_event());
+        }
+
+        builder.build()
+    }
+
+    /// Appends an event at the end of the path.
+    pub fn push(&mut self, event: FixedEvent) {
+        match event {
+            FixedEvent::MoveTo(to) => {
+                self.points.push(to);
+                self.verbs.push(Verb::MoveTo);
+            }
+            FixedEvent::LineTo(to) => {
+                self.points.push(to);
+                self.verbs.push(Verb::LineTo);
+            }
+            FixedEvent::QuadraticTo(ctrl, to) => {
+                self.points.push(ctrl);
+                self.points.push(to);
+                self.verbs.push(Verb::QuadraticTo);
+            }
+            FixedEvent::CubicTo(ctrl1, ctrl2, to) => {
+                self.points.push(ctrl1);
+                self.points.push(ctrl2);
+                self.points.push(to);
+                self.verbs.push(Verb::CubicTo);
+            }
+            FixedEvent::Close => {
+                self.verbs.push(Verb::Close);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> FixedIter<'_> {
+        FixedIter {
+            points: self.points.iter(),
+            verbs: self.verbs.iter(),
+        }
+    }
+
+    pub fn points(&self) -> &[FixedPoint] { &self.points[..] }
+
+    pub fn verbs(&self) -> &[Verb] { &self.verbs[..] }
+
+    /// Approximates the curves with line segments using only integer arithmetic.
+    ///
+    /// `tolerance` is the maximum distance between the curves and the line segments
+    /// in raw fixed-point units (64 per unit), measured along each axis.
+    pub fn flattened(&self, tolerance: i32) -> FixedPath {
+        let tolerance = i64::from(tolerance.max(1));
+        let mut result = FixedPath::new();
+        let mut current = FixedPoint::default();
+        let mut first = FixedPoint::default();
+        for event in self.iter() {
+            match event {
+                FixedEvent::MoveTo(to) => {
+                    result.push(event);
+                    first = to;
+                    current = to;
+                }
+                FixedEvent::LineTo(to) => {
+                    result.push(event);
+                    current = to;
+                }
+                FixedEvent::QuadraticTo(ctrl, to) => {
+                    flatten_quadratic(
+                        [wide(current), wide(ctrl), wide(to)],
+                        tolerance,
+                        0,
+                        &mut result,
+                    );
+                    current = to;
+                }
+                FixedEvent::CubicTo(ctrl1, ctrl2, to) => {
+                    flatten_cubic(
+                        [wide(current), wide(ctrl1), wide(ctrl2), wide(to)],
+                        tolerance,
+                        0,
+                        &mut result,
+                    );
+                    current = to;
+                }
+                FixedEvent::Close => {
+                    result.push(event);
+                    current = first;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<'l> IntoIterator for &'l FixedPath {
+    type Item = FixedEvent;
+    type IntoIter = FixedIter<'l>;
+
+    fn into_iter(self) -> FixedIter<'l> { self.iter() }
+}
+
+impl FromIterator<FixedEvent> for FixedPath {
+    fn from_iter<Iter: IntoIterator<Item = FixedEvent>>(events: Iter) -> FixedPath {
+        let mut path = FixedPath::new();
+        for event in events {
+            path.push(event);
+        }
+
+        path
+    }
+}
+
+/// An iterator over the events of a `FixedPath`.
+#[derive(Clone, Debug)]
+pub struct FixedIter<'l> {
+    points: ::std::slice::Iter<'l, FixedPoint>,
+    verbs: ::std::slice::Iter<'l, Verb>,
+}
+
+impl<'l> Iterator for FixedIter<'l> {
+    type Item = FixedEvent;
+    #[inline]
+    fn next(&mut self) -> Option<FixedEvent> {
+        match self.verbs.next() {
+            Some(&Verb::MoveTo) => {
+                let to = *self.points.next().unwrap();
+                Some(FixedEvent::MoveTo(to))
+            }
+            Some(&Verb::LineTo) => {
+                let to = *self.points.next().unwrap();
+                Some(FixedEvent::LineTo(to))
+            }
+            Some(&Verb::QuadraticTo) => {
+                let ctrl = *self.points.next().unwrap();
+                let to = *self.points.next().unwrap();
+                Some(FixedEvent::QuadraticTo(ctrl, to))
+            }
+            Some(&Verb::CubicTo) => {
+                let ctrl1 = *self.points.next().unwrap();
+                let ctrl2 = *self.points.next().unwrap();
+                let to = *self.points.next().unwrap();
+                Some(FixedEvent::CubicTo(ctrl1, ctrl2, to))
+            }
+            Some(&Verb::Close) => Some(FixedEvent::Close),
+            Some(&Verb::Arc) => unreachable!(),
+            None => None,
+        }
+    }
+}
+
+/// Returns the orientation of the triangle `a`, `b`, `c`, computed exactly.
+///
+/// This is the sign of the cross product `(b - a) x (c - a)`: `Greater` if `c` is on the
+/// side of the positive angles when looking from `a` towards `b`, `Less` on the other side
+/// and `Equal` if the three points are collinear.
+pub fn orient2d(a: FixedPoint, b: FixedPoint, c: FixedPoint) -> Ordering {
+    // The differences need 33 bits and their products 66 bits.
+    let abx = i128::from(b.x) - i128::from(a.x);
+    let aby = i128::from(b.y) - i128::from(a.y);
+    let acx = i128::from(c.x) - i128::from(a.x);
+    let acy = i128::from(c.y) - i128::from(a.y);
+
+    (abx * acy).cmp(&(aby * acx))
+}
+
+/// Returns true if the segments `a1 a2` and `b1 b2` have at least one point in common,
+/// computed exactly.
+pub fn segments_intersect(a1: FixedPoint, a2: FixedPoint, b1: FixedPoint, b2: FixedPoint) -> bool {
+    let o1 = orient2d(a1, a2, b1);
+    let o2 = orient2d(a1, a2, b2);
+    let o3 = orient2d(b1, b2, a1);
+    let o4 = orient2d(b1, b2, a2);
+
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+
+    // Collinear cases.
+    (o1 == Ordering::Equal && in_box(a1, a2, b1))
+        || (o2 == Ordering::Equal && in_box(a1, a2, b2))
+        || (o3 == Ordering::Equal && in_box(b1, b2, a1))
+        || (o4 == Ordering::Equal && in_box(b1, b2, a2))
+}
+
+// Whether p (collinear with a and b) is between a and b.
+fn in_box(a: FixedPoint, b: FixedPoint, p: FixedPoint) -> bool {
+    p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+type WidePoint = (i64, i64);
+
+fn wide(p: FixedPoint) -> WidePoint {
+    (i64::from(p.x), i64::from(p.y))
+}
+
+// Rounds towards negative infinity, which doesn't depend on the sign of the values.
+fn mid(a: WidePoint, b: WidePoint) -> WidePoint {
+    ((a.0 + b.0) >> 1, (a.1 + b.1) >> 1)
+}
+
+// The largest absolute value of the components of a - 2b + c.
+fn second_difference(a: WidePoint, b: WidePoint, c: WidePoint) -> i64 {
+    (a.0 - 2 * b.0 + c.0).abs().max((a.1 - 2 * b.1 + c.1).abs())
+}
+
+fn push_line(p: WidePoint, path: &mut FixedPath) {
+    path.push(FixedEvent::LineTo(FixedPoint::new(p.0 as i32, p.1 as i32)));
+}
+
+fn flatten_quadratic(p: [WidePoint; 3], tolerance: i64, depth: u32, path: &mut FixedPath) {
+    // The distance between a quadratic bézier curve and its chord is at most a quarter
+    // of the second difference of its control points.
+    if depth >= MAX_SUBDIVISION_DEPTH || second_difference(p[0], p[1], p[2]) <= 4 * tolerance {
+        push_line(p[2], path);
+        return;
+    }
+
+    let p01 = mid(p[0], p[1]);
+    let p12 = mid(p[1], p[2]);
+    let m = mid(p01, p12);
+    flatten_quadratic([p[0], p01, m], tolerance, depth + 1, path);
+    flatten_quadratic([m, p12, p[2]], tolerance, depth + 1, path);
+}
+
+fn flatten_cubic(p: [WidePoint; 4], tolerance: i64, depth: u32, path: &mut FixedPath) {
+    // The distance between a cubic bézier curve and its chord is at most three quarters
+    // of the largest second difference of its control points.
+    let dd = second_difference(p[0], p[1], p[2]).max(second_difference(p[1], p[2], p[3]));
+    if depth >= MAX_SUBDIVISION_DEPTH || 3 * dd <= 4 * tolerance {
+        push_line(p[3], path);
+        return;
+    }
+
+    let p01 = mid(p[0], p[1]);
+    let p12 = mid(p[1], p[2]);
+    let p23 = mid(p[2], p[3]);
+    let p012 = mid(p01, p12);
+    let p123 = mid(p12, p23);
+    let m = mid(p012, p123);
+    flatten_cubic([p[0], p01, p012, m], tolerance, depth + 1, path);
+    flatten_cubic([m, p123, p23, p[3]], tolerance, depth + 1, path);
+}
+
+#[test]
+fn fixed_path() {
+    use math::{vector, Angle};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.5, -1.25));
+    builder.line_to(point(10.0, 0.01));
+    builder.cubic_bezier_to(point(12.0, 5.0), point(8.0, 5.0), point(10.0, 10.0));
+    builder.arc(point(5.0, 10.0), vector(5.0, 5.0), Angle::radians(::std::f32::consts::PI), Angle::radians(0.0));
+    builder.close();
+    let path = builder.build();
+
+    let fixed_path = FixedPath::from_path(path.path_iter());
+    assert_eq!(fixed_path.points()[0], FixedPoint::new(32, -80));
+    // 0.01 * 64 = 0.64, rounded to 1.
+    assert_eq!(fixed_path.points()[1], FixedPoint::new(640, 1));
+    assert!(!fixed_path.verbs().contains(&Verb::Arc));
+
+    let events: Vec<FixedEvent> = fixed_path.iter().collect();
+    assert_eq!(events.iter().cloned().collect::<FixedPath>(), fixed_path);
+
+    let round_trip = FixedPath::from_path(fixed_path.to_path().path_iter());
+    assert_eq!(round_trip, fixed_path);
+
+    let flattened = fixed_path.flattened(2);
+    assert!(flattened.verbs().iter().all(|verb| *verb != Verb::QuadraticTo && *verb != Verb::CubicTo));
+    assert!(flattened.verbs().len() > fixed_path.verbs().len());
+    // The end of each curve is preserved exactly.
+    assert!(flattened.points().contains(&FixedPoint::new(640, 640)));
+    assert_eq!(flattened, fixed_path.flattened(2));
+}
+
+#[test]
+fn fixed_predicates() {
+    let p = FixedPoint::new;
+
+    assert_eq!(orient2d(p(0, 0), p(10, 0), p(5, 1)), Ordering::Greater);
+    assert_eq!(orient2d(p(0, 0), p(10, 0), p(5, -1)), Ordering::Less);
+    // Large coordinates that would overflow a 64 bits cross product.
+    let max = i32::MAX;
+    let min = i32::MIN;
+    assert_eq!(orient2d(p(min, min), p(max, max), p(max - 1, max - 1)), Ordering::Equal);
+    assert_eq!(orient2d(p(min, min), p(max, max), p(max - 1, max)), Ordering::Greater);
+
+    assert!(segments_intersect(p(0, 0), p(10, 10), p(0, 10), p(10, 0)));
+    assert!(!segments_intersect(p(0, 0), p(10, 10), p(0, 10), p(4, 5)));
+    // Touching at an endpoint.
+    assert!(segments_intersect(p(0, 0), p(10, 0), p(10, 0), p(20, 5)));
+    // Collinear, overlapping and disjoint.
+    assert!(segments_intersect(p(0, 0), p(10, 0), p(5, 0), p(20, 0)));
+    assert!(!segments_intersect(p(0, 0), p(10, 0), p(11, 0), p(20, 0)));
+}
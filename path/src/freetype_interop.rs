@@ -0,0 +1,159 @@
+//! Building paths from FreeType glyph outlines.
+//!
+//! This module requires the `freetype-rs` feature.
+//!
+//! `OutlineSink` receives the move, line, conic and cubic callbacks of FreeType's outline
+//! decomposition and forwards them to a path builder. FreeType expresses positions in 26.6
+//! fixed point numbers with the y axis pointing up, so by default the positions are divided
+//! by 64. Another transform, for example one flipping the y axis, can be provided with
+//! `OutlineSink::with_transform`.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # extern crate lyon_path;
+//! use lyon_path::freetype::{Library, face::LoadFlag};
+//! use lyon_path::freetype_interop::outline_to_path;
+//!
+//! # fn main() {
+//! let library = Library::init().unwrap();
+//! let face = library.new_face("font.ttf", 0).unwrap();
+//! face.set_char_size(40 * 64, 0, 50, 0).unwrap();
+//! face.load_char('a' as usize, LoadFlag::NO_BITMAP).unwrap();
+//!
+//! let path = outline_to_path(&face.glyph().outline().unwrap());
+//! # }
+//! ```
+
+use freetype::Vector;
+use freetype::outline::{Outline, Curve};
+
+use math::{Point, point, Transform2D};
+use builder::PathBuilder;
+use default::Path;
+
+/// Forwards the callbacks of FreeType's outline decomposition to a path builder.
+///
+/// FreeType's contours are always closed: each contour is closed when the next one begins
+/// and when the path is built.
+pub struct OutlineSink<Builder> {
+    builder: Builder,
+    transform: Transform2D,
+    in_contour: bool,
+}
+
+impl<Builder: PathBuilder> OutlineSink<Builder> {
+    /// Creates a sink converting positions from 26.6 fixed point numbers.
+    pub fn new(builder: Builder) -> Self {
+        OutlineSink::with_transform(builder, Transform2D::create_scale(1.0 / 64.0, 1.0 / 64.0))
+    }
+
+    /// Creates a sink applying a transform to the positions of the outline.
+    pub fn with_transform(builder: Builder, transform: Transform2D) -> Self {
+        OutlineSink {
+            builder,
+            transform,
+            in_contour: false,
+        }
+    }
+
+    pub fn move_to(&mut self, to: &Vector) {
+        self.close_contour();
+        let to = self.point(to);
+        self.builder.move_to(to);
+        self.in_contour = true;
+    }
+
+    pub fn line_to(&mut self, to: &Vector) {
+        let to = self.point(to);
+        self.builder.line_to(to);
+    }
+
+    /// Adds a quadratic bézier curve (a "conic" in FreeType's terms).
+    pub fn conic_to(&mut self, ctrl: &Vector, to: &Vector) {
+        let ctrl = self.point(ctrl);
+        let to = self.point(to);
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    pub fn cubic_to(&mut self, ctrl1: &Vector, ctrl2: &Vector, to: &Vector) {
+        let ctrl1 = self.point(ctrl1);
+        let ctrl2 = self.point(ctrl2);
+        let to = self.point(to);
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    /// Adds all of the contours of an outline.
+    pub fn outline(&mut self, outline: &Outline) {
+        for contour in outline.contours_iter() {
+            self.move_to(&contour.start());
+            for curve in contour {
+                match curve {
+                    Curve::Line(to) => self.line_to(&to),
+                    Curve::Bezier2(ctrl, to) => self.conic_to(&ctrl, &to),
+                    Curve::Bezier3(ctrl1, ctrl2, to) => self.cubic_to(&ctrl1, &ctrl2, &to),
+                }
+            }
+        }
+        self.close_contour();
+    }
+
+    /// Closes the last contour and returns the underlying path builder.
+    pub fn into_inner(mut self) -> Builder {
+        self.close_contour();
+        self.builder
+    }
+
+    /// Closes the last contour and builds the path.
+    pub fn build(self) -> Builder::PathType {
+        self.into_inner().build()
+    }
+
+    fn close_contour(&mut self) {
+        if self.in_contour {
+            self.builder.close();
+            self.in_contour = false;
+        }
+    }
+
+    fn point(&self, v: &Vector) -> Point {
+        self.transform.transform_point(&point(v.x as f32, v.y as f32))
+    }
+}
+
+/// Builds a path from a FreeType outline, in pixels.
+pub fn outline_to_path(outline: &Outline) -> Path {
+    let mut sink = OutlineSink::new(Path::builder());
+    sink.outline(outline);
+
+    sink.build()
+}
+
+#[test]
+fn outline_sink() {
+    use PathEvent;
+
+    let v = |x, y| Vector { x, y };
+
+    let mut sink = OutlineSink::with_transform(
+        Path::builder(),
+        Transform2D::create_scale(1.0 / 64.0, -1.0 / 64.0),
+    );
+    sink.move_to(&v(0, 0));
+    sink.line_to(&v(640, 0));
+    sink.conic_to(&v(640, 640), &v(0, 640));
+    sink.move_to(&v(64, 64));
+    sink.cubic_to(&v(128, 64), &v(128, 128), &v(64, 128));
+    let path = sink.build();
+
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events, vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(10.0, 0.0)),
+        PathEvent::QuadraticTo(point(10.0, -10.0), point(0.0, -10.0)),
+        PathEvent::Close,
+        PathEvent::MoveTo(point(1.0, -1.0)),
+        PathEvent::CubicTo(point(2.0, -1.0), point(2.0, -2.0), point(1.0, -2.0)),
+        PathEvent::Close,
+    ]);
+}
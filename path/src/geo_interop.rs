@@ -0,0 +1,246 @@
+//! Conversions between lyon paths and the types of the `geo-types` crate.
+//!
+//! This module requires the `geo-types` feature.
+//!
+//! Polygons are converted into paths made of one closed sub-path per ring, the holes
+//! after the exterior ring. The order of the points is preserved in both directions, so
+//! data following the GeoJSON convention (counter-clockwise exterior rings, clockwise
+//! holes) can be filled with either fill rule.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_path;
+//! extern crate geo_types;
+//!
+//! use lyon_path::default::Path;
+//! use lyon_path::geo_interop::path_to_multi_polygon;
+//! use geo_types::{LineString, Polygon};
+//!
+//! fn main() {
+//!     let polygon = Polygon::new(
+//!         LineString::from(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0), (0.0, 0.0)]),
+//!         vec![LineString::from(vec![(2.0, 2.0), (2.0, 8.0), (8.0, 8.0), (8.0, 2.0), (2.0, 2.0)])],
+//!     );
+//!
+//!     let path = Path::from(&polygon);
+//!     let multi_polygon = path_to_multi_polygon(path.path_iter(), 0.1);
+//!
+//!     assert_eq!(multi_polygon.0.len(), 1);
+//!     assert_eq!(multi_polygon.0[0].interiors().len(), 1);
+//! }
+//! ```
+
+use geo_types::{Coordinate, LineString, Polygon as GeoPolygon, MultiPolygon};
+
+use math::{Point, point};
+use default::{Path, Builder};
+use builder::FlatPathBuilder;
+use iterator::PathIterator;
+use polygon::Polygon;
+use FlattenedEvent;
+
+impl<'l> From<&'l GeoPolygon<f32>> for Path {
+    fn from(polygon: &'l GeoPolygon<f32>) -> Path {
+        let mut builder = Path::builder();
+        add_polygon(polygon, &mut builder);
+
+        builder.build()
+    }
+}
+
+impl<'l> From<&'l MultiPolygon<f32>> for Path {
+    fn from(multi_polygon: &'l MultiPolygon<f32>) -> Path {
+        let mut builder = Path::builder();
+        for polygon in &multi_polygon.0 {
+            add_polygon(polygon, &mut builder);
+        }
+
+        builder.build()
+    }
+}
+
+/// Line strings are converted into open paths.
+impl<'l> From<&'l LineString<f32>> for Path {
+    fn from(line_string: &'l LineString<f32>) -> Path {
+        let mut builder = Path::builder();
+        let mut first = true;
+        for c in &line_string.0 {
+            if first {
+                builder.move_to(to_point(*c));
+                first = false;
+            } else {
+                builder.line_to(to_point(*c));
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// Closed polygons produce a line string that ends with its first point, as required for
+/// the rings of geo-types polygons.
+impl<'l, 'a> From<&'a Polygon<'l>> for LineString<f32> {
+    fn from(polygon: &'a Polygon<'l>) -> LineString<f32> {
+        let mut coords: Vec<Coordinate<f32>> = polygon.points.iter().map(|p| to_coordinate(*p)).collect();
+        if polygon.closed && !coords.is_empty() && coords[0] != coords[coords.len() - 1] {
+            let first = coords[0];
+            coords.push(first);
+        }
+
+        LineString(coords)
+    }
+}
+
+/// Converts a path into polygons.
+///
+/// Curves are flattened with the provided tolerance and sub-paths are considered closed.
+/// The rings are nested according to the even-odd rule: a ring contained in an odd number
+/// of other rings is a hole of the smallest one of them.
+pub fn path_to_multi_polygon<Iter: PathIterator>(path: Iter, tolerance: f32) -> MultiPolygon<f32> {
+    let mut rings: Vec<Vec<Point>> = Vec::new();
+    let mut current = Vec::new();
+    for event in path.flattened(tolerance) {
+        match event {
+            FlattenedEvent::MoveTo(to) => {
+                finish_ring(&mut current, &mut rings);
+                current.push(to);
+            }
+            FlattenedEvent::LineTo(to) => {
+                current.push(to);
+            }
+            FlattenedEvent::Close => {
+                let first = current.first().cloned();
+                finish_ring(&mut current, &mut rings);
+                current.extend(first);
+            }
+        }
+    }
+    finish_ring(&mut current, &mut rings);
+
+    // For each ring, the rings that contain it.
+    let containers: Vec<Vec<usize>> = (0..rings.len()).map(|i| {
+        (0..rings.len()).filter(|&j| j != i && ring_contains(&rings[j], rings[i][0])).collect()
+    }).collect();
+
+    let mut polygons: Vec<(usize, Vec<LineString<f32>>)> = Vec::new();
+    let mut polygon_of_ring = vec![None; rings.len()];
+    for i in 0..rings.len() {
+        if containers[i].len() % 2 == 0 {
+            polygon_of_ring[i] = Some(polygons.len());
+            polygons.push((i, Vec::new()));
+        }
+    }
+    for i in 0..rings.len() {
+        if containers[i].len() % 2 == 0 {
+            continue;
+        }
+        // The exterior is the container that is nested the deepest.
+        let parent = containers[i].iter()
+            .cloned()
+            .filter(|&j| containers[j].len() + 1 == containers[i].len())
+            .next();
+        if let Some(polygon) = parent.and_then(|j| polygon_of_ring[j]) {
+            polygons[polygon].1.push(to_line_string(&rings[i]));
+        }
+    }
+
+    MultiPolygon(polygons.into_iter().map(|(exterior, interiors)| {
+        GeoPolygon::new(to_line_string(&rings[exterior]), interiors)
+    }).collect())
+}
+
+fn add_polygon(polygon: &GeoPolygon<f32>, builder: &mut Builder) {
+    add_ring(polygon.exterior(), builder);
+    for interior in polygon.interiors() {
+        add_ring(interior, builder);
+    }
+}
+
+fn add_ring(ring: &LineString<f32>, builder: &mut Builder) {
+    let mut coords = &ring.0[..];
+    // The ring's last point is a copy of the first one.
+    if coords.len() > 1 && coords[0] == coords[coords.len() - 1] {
+        coords = &coords[..coords.len() - 1];
+    }
+    if coords.is_empty() {
+        return;
+    }
+
+    builder.move_to(to_point(coords[0]));
+    for c in &coords[1..] {
+        builder.line_to(to_point(*c));
+    }
+    builder.close();
+}
+
+fn finish_ring(points: &mut Vec<Point>, rings: &mut Vec<Vec<Point>>) {
+    if points.len() > 1 && points[0] == points[points.len() - 1] {
+        points.pop();
+    }
+    if points.len() > 2 {
+        rings.push(::std::mem::replace(points, Vec::new()));
+    } else {
+        points.clear();
+    }
+}
+
+fn ring_contains(ring: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    let mut prev = ring[ring.len() - 1];
+    for &q in ring {
+        if (q.y > p.y) != (prev.y > p.y) {
+            let x = prev.x + (p.y - prev.y) * (q.x - prev.x) / (q.y - prev.y);
+            if x > p.x {
+                inside = !inside;
+            }
+        }
+        prev = q;
+    }
+
+    inside
+}
+
+fn to_line_string(ring: &[Point]) -> LineString<f32> {
+    LineString::from(&Polygon::new(ring))
+}
+
+fn to_point(c: Coordinate<f32>) -> Point {
+    point(c.x, c.y)
+}
+
+fn to_coordinate(p: Point) -> Coordinate<f32> {
+    Coordinate { x: p.x, y: p.y }
+}
+
+#[test]
+fn multi_polygon_round_trip() {
+    fn square(x: f32, y: f32, size: f32, clockwise: bool) -> LineString<f32> {
+        let mut points = vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size), (x, y)];
+        if clockwise {
+            points.reverse();
+        }
+        LineString::from(points)
+    }
+
+    let multi_polygon = MultiPolygon(vec![
+        GeoPolygon::new(square(0.0, 0.0, 10.0, false), vec![square(1.0, 1.0, 2.0, true), square(5.0, 5.0, 4.0, true)]),
+        // An island in the second hole.
+        GeoPolygon::new(square(6.0, 6.0, 2.0, false), Vec::new()),
+        GeoPolygon::new(square(20.0, 0.0, 5.0, false), Vec::new()),
+    ]);
+
+    let path = Path::from(&multi_polygon);
+    assert_eq!(path.iter().filter(|evt| *evt == ::PathEvent::Close).count(), 5);
+
+    let result = path_to_multi_polygon(path.path_iter(), 0.1);
+    assert_eq!(result, multi_polygon);
+
+    let open = Path::from(&square(0.0, 0.0, 1.0, false));
+    assert_eq!(open.iter().filter(|evt| *evt == ::PathEvent::Close).count(), 0);
+    assert_eq!(open.points().len(), 5);
+
+    let points = [point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)];
+    assert_eq!(LineString::from(&Polygon::new(&points)).0.len(), 4);
+    assert_eq!(LineString::from(&Polygon::polyline(&points)).0.len(), 3);
+}
@@ -101,8 +101,8 @@
 use std::iter;
 
 use math::*;
-use {PathEvent, SvgEvent, FlattenedEvent, QuadraticEvent, PathState};
-use geom::{QuadraticBezierSegment, CubicBezierSegment, quadratic_bezier, cubic_bezier};
+use {PathEvent, SvgEvent, FlattenedEvent, QuadraticEvent, PathState, Segment, SegmentEvent};
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, SvgArc, quadratic_bezier, cubic_bezier};
 use geom::arc;
 
 /// An extension to the common Iterator interface, that adds information which is useful when
@@ -122,7 +122,51 @@ pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
 
     /// Returns an iterator applying a 2D transform to all of its events.
     fn transformed(self, mat: &Transform2D) -> Transformed<Self> {
-        Transformed::new(mat, self)
+        Transformed::new(mat, self).with_state(path_iter_state)
+    }
+
+    /// Returns an iterator that skips the first `index` events.
+    ///
+    /// The remaining events form a valid path on their own: if needed, a `MoveTo`
+    /// event is inserted at the position where the path is split.
+    fn split_at(self, index: usize) -> SplitAt<Self> {
+        SplitAt::new(index, self)
+    }
+
+    /// Returns an iterator over the events of the path in reverse order.
+    ///
+    /// Note that this iterator has to consume all events of the underlying iterator
+    /// before yielding the first event.
+    fn reversed(self) -> Reversed {
+        Reversed::new(self)
+    }
+
+    /// Returns an iterator over the events of this path followed by the events
+    /// of another path.
+    ///
+    /// If the other path doesn't start with a `MoveTo` event, it starts a new sub-path
+    /// at the current position of this path.
+    fn merged<Other: PathIterator>(self, other: Other) -> Merged<Self, Other> {
+        Merged::new(self, other)
+    }
+
+    /// Returns an iterator over events that contain the starting point of each segment.
+    fn segment_events(self) -> SegmentEvents<Self> {
+        SegmentEvents::new(self)
+    }
+
+    /// Returns an iterator that approximates cubic bézier curves and arcs with quadratic
+    /// bézier curves.
+    fn quadratics(self, tolerance: f32) -> Quadratics<Self> {
+        Quadratics::new(tolerance, self)
+    }
+
+    /// Returns an iterator that turns quadratic bézier curves and arcs into cubic bézier
+    /// curves.
+    ///
+    /// Quadratic curves are converted exactly, the tolerance only applies to arcs.
+    fn cubics(self, tolerance: f32) -> Cubics<Self> {
+        Cubics::new(tolerance, self)
     }
 }
 
@@ -140,6 +184,14 @@ pub trait SvgIterator: Iterator<Item = SvgEvent> + Sized {
 
     /// Returns an iterator of path events.
     fn path_events(self) -> PathEvents<Self> { PathEvents::new(self) }
+
+    /// Returns an iterator of path events, lowering arcs according to a policy.
+    ///
+    /// The policy can be an `ArcLowering` or a closure which receives each arc and a
+    /// callback to send the events that replace it.
+    fn path_events_with<Lowering: LowerArc>(self, arcs: Lowering) -> LoweredPathEvents<Self, Lowering> {
+        LoweredPathEvents::new(arcs, self)
+    }
 }
 
 /// An extension to the common Iterator interface, that adds information which is useful when
@@ -161,7 +213,7 @@ pub trait FlattenedIterator: Iterator<Item = FlattenedEvent> + Sized {
 
     /// Returns an iterator applying a 2D transform to all of its events.
     fn transformed(self, mat: &Transform2D) -> Transformed<Self> {
-        Transformed::new(mat, self)
+        Transformed::new(mat, self).with_state(flattened_iter_state)
     }
 }
 
@@ -184,7 +236,7 @@ pub trait QuadraticPathIterator: Iterator<Item = QuadraticEvent> + Sized {
 
     /// Returns an iterator applying a 2D transform to all of its events.
     fn transformed(self, mat: &Transform2D) -> Transformed<Self> {
-        Transformed::new(mat, self)
+        Transformed::new(mat, self).with_state(quadratic_iter_state)
     }
 }
 
@@ -216,6 +268,138 @@ where
     }
 }
 
+/// How arcs are lowered when iterating over SVG events as path events.
+///
+/// See `SvgIterator::path_events_with`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ArcLowering {
+    /// Arcs are kept as `PathEvent::Arc` events, like with `SvgIterator::path_events`.
+    Arcs,
+    /// Arcs are approximated with cubic bézier curves.
+    Cubics { tolerance: f32 },
+    /// Arcs are approximated with quadratic bézier curves.
+    Quadratics { tolerance: f32 },
+}
+
+/// Converts arcs into path events.
+///
+/// Implemented by `ArcLowering` and by closures taking the arc and a callback that receives
+/// the events replacing it.
+pub trait LowerArc {
+    fn lower_arc(&mut self, arc: &arc::Arc<f32>, output: &mut dyn FnMut(PathEvent));
+}
+
+impl LowerArc for ArcLowering {
+    fn lower_arc(&mut self, arc: &arc::Arc<f32>, output: &mut dyn FnMut(PathEvent)) {
+        match *self {
+            ArcLowering::Arcs => {
+                output(PathEvent::Arc(arc.center, arc.radii, arc.sweep_angle, arc.x_rotation));
+            }
+            ArcLowering::Cubics { tolerance } => {
+                arc.for_each_cubic_bezier(tolerance, &mut |curve| {
+                    output(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to));
+                });
+            }
+            ArcLowering::Quadratics { tolerance } => {
+                // Half of the tolerance for each of the two approximations.
+                let tolerance = tolerance * 0.5;
+                arc.for_each_cubic_bezier(tolerance, &mut |cubic| {
+                    cubic.for_each_quadratic_bezier(tolerance, &mut |curve| {
+                        output(PathEvent::QuadraticTo(curve.ctrl, curve.to));
+                    });
+                });
+            }
+        }
+    }
+}
+
+impl<F> LowerArc for F
+where
+    F: FnMut(&arc::Arc<f32>, &mut dyn FnMut(PathEvent)),
+{
+    fn lower_arc(&mut self, arc: &arc::Arc<f32>, output: &mut dyn FnMut(PathEvent)) {
+        self(arc, output);
+    }
+}
+
+/// An iterator that consumes an SvgIterator and yields PathEvents, lowering arcs
+/// according to a policy.
+///
+/// See `SvgIterator::path_events_with`.
+pub struct LoweredPathEvents<SvgIter, Lowering> {
+    it: SvgIter,
+    lowering: Lowering,
+    events: Vec<PathEvent>,
+    next_event: usize,
+}
+
+impl<SvgIter, Lowering> LoweredPathEvents<SvgIter, Lowering> {
+    pub fn new(lowering: Lowering, it: SvgIter) -> Self {
+        LoweredPathEvents {
+            it,
+            lowering,
+            events: Vec::new(),
+            next_event: 0,
+        }
+    }
+}
+
+impl<SvgIter, Lowering> PathIterator for LoweredPathEvents<SvgIter, Lowering>
+where
+    SvgIter: SvgIterator,
+    Lowering: LowerArc,
+{
+    fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<SvgIter, Lowering> Iterator for LoweredPathEvents<SvgIter, Lowering>
+where
+    SvgIter: SvgIterator,
+    Lowering: LowerArc,
+{
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        if self.next_event < self.events.len() {
+            self.next_event += 1;
+            return Some(self.events[self.next_event - 1]);
+        }
+        self.events.clear();
+        self.next_event = 0;
+
+        // Relative coordinates are expressed from the state before the event.
+        let state = PathState {
+            current: self.it.get_state().current,
+            first: self.it.get_state().first,
+            last_ctrl: self.it.get_state().last_ctrl,
+        };
+
+        let (radii, x_rotation, flags, to) = match self.it.next() {
+            Some(SvgEvent::ArcTo(radii, x_rotation, flags, to)) => {
+                (radii, x_rotation, flags, to)
+            }
+            Some(SvgEvent::RelativeArcTo(radii, x_rotation, flags, to)) => {
+                (radii, x_rotation, flags, state.relative_to_absolute(to))
+            }
+            Some(evt) => {
+                return Some(state.svg_to_path_event(evt));
+            }
+            None => {
+                return None;
+            }
+        };
+
+        let svg_arc = SvgArc { from: state.current, to, radii, x_rotation, flags };
+        if svg_arc.is_straight_line() {
+            return Some(PathEvent::LineTo(to));
+        }
+
+        let events = &mut self.events;
+        self.lowering.lower_arc(&svg_arc.to_arc(), &mut |evt| { events.push(evt); });
+
+        self.next()
+    }
+}
+
 /// An iterator that consumes an PathIterator and yields FlattenedEvents.
 pub struct Flattened<Iter> {
     it: Iter,
@@ -323,6 +507,158 @@ where
 // specialization to implement the Iterator trait depending on the type of
 // event but specialization isn't stable in rust yet.
 
+/// An iterator that consumes a PathIterator and yields QuadraticEvents.
+///
+/// See `PathIterator::quadratics`.
+pub struct Quadratics<Iter> {
+    it: Iter,
+    tolerance: f32,
+    curves: Vec<QuadraticBezierSegment<f32>>,
+    next_curve: usize,
+}
+
+impl<Iter: PathIterator> Quadratics<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        Quadratics {
+            it,
+            tolerance,
+            curves: Vec::new(),
+            next_curve: 0,
+        }
+    }
+}
+
+impl<Iter> QuadraticPathIterator for Quadratics<Iter>
+where
+    Iter: PathIterator,
+{
+    fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<Iter> Iterator for Quadratics<Iter>
+where
+    Iter: PathIterator,
+{
+    type Item = QuadraticEvent;
+    fn next(&mut self) -> Option<QuadraticEvent> {
+        if self.next_curve < self.curves.len() {
+            let curve = self.curves[self.next_curve];
+            self.next_curve += 1;
+            return Some(QuadraticEvent::QuadraticTo(curve.ctrl, curve.to));
+        }
+        self.curves.clear();
+        self.next_curve = 0;
+
+        let current = self.it.get_state().current;
+        match self.it.next() {
+            Some(PathEvent::MoveTo(to)) => Some(QuadraticEvent::MoveTo(to)),
+            Some(PathEvent::LineTo(to)) => Some(QuadraticEvent::LineTo(to)),
+            Some(PathEvent::QuadraticTo(ctrl, to)) => Some(QuadraticEvent::QuadraticTo(ctrl, to)),
+            Some(PathEvent::Close) => Some(QuadraticEvent::Close),
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                let curves = &mut self.curves;
+                CubicBezierSegment {
+                    from: current,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                }.for_each_quadratic_bezier(self.tolerance, &mut |curve| {
+                    curves.push(*curve);
+                });
+
+                self.next()
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                // Half of the tolerance for each of the two approximations.
+                let tolerance = self.tolerance * 0.5;
+                let start_angle = (current - center).angle_from_x_axis() - x_rotation;
+                let curves = &mut self.curves;
+                arc::Arc {
+                    center, radii,
+                    start_angle, sweep_angle,
+                    x_rotation
+                }.for_each_cubic_bezier(tolerance, &mut |cubic| {
+                    cubic.for_each_quadratic_bezier(tolerance, &mut |curve| {
+                        curves.push(*curve);
+                    });
+                });
+
+                self.next()
+            }
+            None => None,
+        }
+    }
+}
+
+/// An iterator that consumes a PathIterator and yields PathEvents without quadratic
+/// bézier curves and arcs.
+///
+/// See `PathIterator::cubics`.
+pub struct Cubics<Iter> {
+    it: Iter,
+    tolerance: f32,
+    curves: Vec<CubicBezierSegment<f32>>,
+    next_curve: usize,
+}
+
+impl<Iter: PathIterator> Cubics<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        Cubics {
+            it,
+            tolerance,
+            curves: Vec::new(),
+            next_curve: 0,
+        }
+    }
+}
+
+impl<Iter> PathIterator for Cubics<Iter>
+where
+    Iter: PathIterator,
+{
+    fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<Iter> Iterator for Cubics<Iter>
+where
+    Iter: PathIterator,
+{
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        if self.next_curve < self.curves.len() {
+            let curve = self.curves[self.next_curve];
+            self.next_curve += 1;
+            return Some(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to));
+        }
+        self.curves.clear();
+        self.next_curve = 0;
+
+        let current = self.it.get_state().current;
+        match self.it.next() {
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let curve = QuadraticBezierSegment { from: current, ctrl, to }.to_cubic();
+                Some(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to))
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (current - center).angle_from_x_axis() - x_rotation;
+                let curves = &mut self.curves;
+                arc::Arc {
+                    center, radii,
+                    start_angle, sweep_angle,
+                    x_rotation
+                }.for_each_cubic_bezier(self.tolerance, &mut |curve| {
+                    curves.push(*curve);
+                });
+
+                self.next()
+            }
+            evt => evt,
+        }
+    }
+}
+
 /// An adapater iterator that implements SvgIterator on top of an Iterator<Item=SvgEvent>.
 pub struct SvgPathIter<Iter> {
     it: Iter,
@@ -425,6 +761,10 @@ fn flattened_to_svg_event(evt: FlattenedEvent) -> SvgEvent { evt.to_svg_event()
 pub struct Transformed<I> {
     it: I,
     transform: Transform2D,
+    // Only set when created through one of the path iterator traits, which expose the
+    // state of the underlying iterator.
+    track_state: Option<fn(&I, &Transform2D) -> PathState>,
+    state: PathState,
 }
 
 impl<I, Event> Transformed<I>
@@ -433,13 +773,25 @@ where
     Event: Transform
 {
     /// Creates a new transformed path iterator from a path iterator.
+    ///
+    /// The state of the iterator is only tracked if it is created with the `transformed`
+    /// method of `PathIterator`, `FlattenedIterator` or `QuadraticPathIterator`.
     #[inline]
     pub fn new(transform: &Transform2D, it: I) -> Transformed<I> {
         Transformed {
             it,
             transform: *transform,
+            track_state: None,
+            state: PathState::new(),
         }
     }
+
+    #[inline]
+    fn with_state(mut self, track_state: fn(&I, &Transform2D) -> PathState) -> Self {
+        self.state = track_state(&self.it, &self.transform);
+        self.track_state = Some(track_state);
+        self
+    }
 }
 
 impl<I, Event> Iterator for Transformed<I>
@@ -451,11 +803,382 @@ where
     fn next(&mut self) -> Option<Event> {
         match self.it.next() {
             None => None,
-            Some(ref evt) => Some(evt.transform(&self.transform)),
+            Some(ref evt) => {
+                if let Some(track_state) = self.track_state {
+                    self.state = track_state(&self.it, &self.transform);
+                }
+                Some(evt.transform(&self.transform))
+            }
+        }
+    }
+}
+
+impl<I> PathIterator for Transformed<I>
+where
+    I: PathIterator,
+{
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+impl<I> FlattenedIterator for Transformed<I>
+where
+    I: FlattenedIterator,
+{
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+impl<I> QuadraticPathIterator for Transformed<I>
+where
+    I: QuadraticPathIterator,
+{
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+fn transform_state(state: &PathState, transform: &Transform2D) -> PathState {
+    PathState {
+        current: transform.transform_point(&state.current),
+        first: transform.transform_point(&state.first),
+        last_ctrl: transform.transform_point(&state.last_ctrl),
+    }
+}
+
+fn path_iter_state<I: PathIterator>(it: &I, transform: &Transform2D) -> PathState {
+    transform_state(PathIterator::get_state(it), transform)
+}
+
+fn flattened_iter_state<I: FlattenedIterator>(it: &I, transform: &Transform2D) -> PathState {
+    transform_state(FlattenedIterator::get_state(it), transform)
+}
+
+fn quadratic_iter_state<I: QuadraticPathIterator>(it: &I, transform: &Transform2D) -> PathState {
+    transform_state(QuadraticPathIterator::get_state(it), transform)
+}
+
+/// An iterator that skips the first events of a path iterator.
+///
+/// See `PathIterator::split_at`.
+pub struct SplitAt<Iter> {
+    it: Iter,
+    skip: usize,
+    pending: Option<PathEvent>,
+    state: PathState,
+}
+
+impl<Iter: PathIterator> SplitAt<Iter> {
+    pub fn new(index: usize, it: Iter) -> Self {
+        SplitAt {
+            it,
+            skip: index,
+            pending: None,
+            state: PathState::new(),
+        }
+    }
+
+    fn next_event(&mut self) -> Option<PathEvent> {
+        if let Some(evt) = self.pending.take() {
+            return Some(evt);
+        }
+
+        if self.skip == 0 {
+            return self.it.next();
+        }
+
+        for _ in 0..self.skip {
+            if self.it.next().is_none() {
+                self.skip = 0;
+                return None;
+            }
+        }
+        self.skip = 0;
+
+        // If we are splitting in the middle of a sub-path, start a new one at the
+        // current position. Closing it will go back to that position rather than
+        // the start of the original sub-path.
+        let position = self.it.get_state().current;
+        match self.it.next() {
+            Some(PathEvent::MoveTo(to)) => Some(PathEvent::MoveTo(to)),
+            Some(evt) => {
+                self.pending = Some(evt);
+                Some(PathEvent::MoveTo(position))
+            }
+            None => None,
+        }
+    }
+}
+
+impl<Iter: PathIterator> Iterator for SplitAt<Iter> {
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        let evt = self.next_event();
+        if let Some(evt) = evt {
+            self.state.path_event(evt);
+        }
+
+        evt
+    }
+}
+
+impl<Iter: PathIterator> PathIterator for SplitAt<Iter> {
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+/// An iterator over the events of a path in reverse order.
+///
+/// See `PathIterator::reversed`.
+pub struct Reversed {
+    events: ::std::vec::IntoIter<PathEvent>,
+    state: PathState,
+}
+
+impl Reversed {
+    pub fn new<Iter: PathIterator>(mut it: Iter) -> Self {
+        let mut sub_paths = Vec::new();
+        let mut segments = Vec::new();
+        let mut first = it.get_state().first;
+
+        loop {
+            let from = it.get_state().current;
+            let evt = it.next();
+            match evt {
+                Some(PathEvent::MoveTo(to)) => {
+                    Reversed::end_sub_path(first, &mut segments, false, &mut sub_paths);
+                    first = to;
+                }
+                Some(PathEvent::Close) => {
+                    Reversed::end_sub_path(first, &mut segments, true, &mut sub_paths);
+                    first = it.get_state().first;
+                }
+                Some(evt) => {
+                    segments.push((from, evt));
+                }
+                None => {
+                    Reversed::end_sub_path(first, &mut segments, false, &mut sub_paths);
+                    break;
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        for sub_path in sub_paths.into_iter().rev() {
+            events.extend(sub_path);
+        }
+
+        Reversed {
+            events: events.into_iter(),
+            state: PathState::new(),
+        }
+    }
+
+    fn end_sub_path(
+        first: Point,
+        segments: &mut Vec<(Point, PathEvent)>,
+        close: bool,
+        sub_paths: &mut Vec<Vec<PathEvent>>,
+    ) {
+        if segments.is_empty() && !close {
+            return;
+        }
+
+        let mut events = Vec::with_capacity(segments.len() + 2);
+        let last = match segments.last() {
+            Some(&(from, evt)) => {
+                let mut state = PathState::new();
+                state.move_to(from);
+                state.path_event(evt);
+                state.current
+            }
+            None => first,
+        };
+        events.push(PathEvent::MoveTo(last));
+        for &(from, evt) in segments.iter().rev() {
+            events.push(match evt {
+                PathEvent::LineTo(_) => PathEvent::LineTo(from),
+                PathEvent::QuadraticTo(ctrl, _) => PathEvent::QuadraticTo(ctrl, from),
+                PathEvent::CubicTo(ctrl1, ctrl2, _) => PathEvent::CubicTo(ctrl2, ctrl1, from),
+                PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                    PathEvent::Arc(center, radii, Angle::radians(-sweep_angle.get()), x_rotation)
+                }
+                PathEvent::MoveTo(_) | PathEvent::Close => unreachable!(),
+            });
+        }
+        if close {
+            events.push(PathEvent::Close);
+        }
+
+        segments.clear();
+        sub_paths.push(events);
+    }
+}
+
+impl Iterator for Reversed {
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        let evt = self.events.next();
+        if let Some(evt) = evt {
+            self.state.path_event(evt);
+        }
+
+        evt
+    }
+}
+
+impl PathIterator for Reversed {
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+/// An iterator over the events of two paths, one after the other.
+///
+/// See `PathIterator::merged`.
+pub struct Merged<A, B> {
+    first: A,
+    second: B,
+    in_second: bool,
+    pending: Option<PathEvent>,
+    state: PathState,
+}
+
+impl<A: PathIterator, B: PathIterator> Merged<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Merged {
+            first,
+            second,
+            in_second: false,
+            pending: None,
+            state: PathState::new(),
+        }
+    }
+
+    fn next_event(&mut self) -> Option<PathEvent> {
+        if let Some(evt) = self.pending.take() {
+            return Some(evt);
+        }
+
+        if self.in_second {
+            return self.second.next();
+        }
+
+        if let Some(evt) = self.first.next() {
+            return Some(evt);
+        }
+
+        self.in_second = true;
+
+        // The second path must not continue the last sub-path of the first one, so it
+        // starts a new sub-path where the first one ended if needed.
+        match self.second.next() {
+            Some(PathEvent::MoveTo(to)) => Some(PathEvent::MoveTo(to)),
+            Some(evt) => {
+                self.pending = Some(evt);
+                Some(PathEvent::MoveTo(self.first.get_state().current))
+            }
+            None => None,
+        }
+    }
+}
+
+impl<A: PathIterator, B: PathIterator> Iterator for Merged<A, B> {
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        let evt = self.next_event();
+        if let Some(evt) = evt {
+            self.state.path_event(evt);
         }
+
+        evt
     }
 }
 
+impl<A: PathIterator, B: PathIterator> PathIterator for Merged<A, B> {
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+/// An iterator over self-contained segment events.
+///
+/// A `Begin` event is inserted if a sub-path doesn't start with a `MoveTo` event, and
+/// every sub-path that has begun ends with an `End` event, even if it isn't closed.
+///
+/// See `PathIterator::segment_events`.
+pub struct SegmentEvents<Iter> {
+    it: Iter,
+    pending: Option<SegmentEvent>,
+    in_subpath: bool,
+}
+
+impl<Iter: PathIterator> SegmentEvents<Iter> {
+    pub fn new(it: Iter) -> Self {
+        SegmentEvents {
+            it,
+            pending: None,
+            in_subpath: false,
+        }
+    }
+}
+
+impl<Iter: PathIterator> Iterator for SegmentEvents<Iter> {
+    type Item = SegmentEvent;
+    fn next(&mut self) -> Option<SegmentEvent> {
+        if let Some(evt) = self.pending.take() {
+            return Some(evt);
+        }
+
+        loop {
+            let from = self.it.get_state().current;
+            let first = self.it.get_state().first;
+            let segment = match self.it.next() {
+                Some(PathEvent::MoveTo(to)) => {
+                    let begin = SegmentEvent::Begin { at: to };
+                    if self.in_subpath {
+                        self.pending = Some(begin);
+                        return Some(SegmentEvent::End { last: from, first, close: false });
+                    }
+                    self.in_subpath = true;
+                    return Some(begin);
+                }
+                Some(PathEvent::Close) => {
+                    if !self.in_subpath {
+                        // Closing an empty sub-path.
+                        continue;
+                    }
+                    self.in_subpath = false;
+                    return Some(SegmentEvent::End { last: from, first, close: true });
+                }
+                Some(PathEvent::LineTo(to)) => {
+                    Segment::Line(LineSegment { from, to })
+                }
+                Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                    Segment::Quadratic(QuadraticBezierSegment { from, ctrl, to })
+                }
+                Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                    Segment::Cubic(CubicBezierSegment { from, ctrl1, ctrl2, to })
+                }
+                Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                    Segment::Arc(arc::Arc {
+                        center, radii,
+                        start_angle: (from - center).angle_from_x_axis() - x_rotation,
+                        sweep_angle,
+                        x_rotation,
+                    })
+                }
+                None => {
+                    if self.in_subpath {
+                        self.in_subpath = false;
+                        return Some(SegmentEvent::End { last: from, first, close: false });
+                    }
+                    return None;
+                }
+            };
+
+            if !self.in_subpath {
+                // The segment continues from the end of the previous sub-path.
+                self.in_subpath = true;
+                self.pending = Some(SegmentEvent::Segment(segment));
+                return Some(SegmentEvent::Begin { at: from });
+            }
+
+            return Some(SegmentEvent::Segment(segment));
+        }
+    }
+}
 
 /// An iterator that consumes an iterator of `Point`s and produces `FlattenedEvent`s.
 ///
@@ -565,3 +1288,236 @@ fn test_from_polyline_closed() {
     assert_eq!(evts.next(), Some(FlattenedEvent::LineTo(point(5.0, 2.0))));
     assert_eq!(evts.next(), Some(FlattenedEvent::Close));
 }
+
+#[cfg(test)]
+fn test_path() -> ::default::Path {
+    use builder::{FlatPathBuilder, PathBuilder};
+
+    let mut builder = ::default::Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(11.0, 0.0), point(12.0, 1.0), point(13.0, 2.0));
+    builder.build()
+}
+
+#[test]
+fn test_transformed_state() {
+    let path = test_path();
+    let mut it = path.path_iter().transformed(&Transform2D::create_translation(1.0, 2.0));
+
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(1.0, 2.0))));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(point(2.0, 2.0))));
+    assert_eq!(it.get_state().current, point(2.0, 2.0));
+    assert_eq!(it.get_state().first, point(1.0, 2.0));
+
+    let mut it = path.path_iter().flattened(0.1).transformed(&Transform2D::create_translation(1.0, 2.0));
+    it.next();
+    assert_eq!(FlattenedIterator::get_state(&it).current, point(1.0, 2.0));
+}
+
+#[test]
+fn test_split_at() {
+    let path = test_path();
+
+    let mut it = path.path_iter().split_at(2);
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(1.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0))));
+    assert_eq!(it.next(), Some(PathEvent::Close));
+    assert_eq!(it.get_state().current, point(1.0, 0.0));
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(10.0, 0.0))));
+
+    let mut it = path.path_iter().split_at(4);
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(10.0, 0.0))));
+
+    assert_eq!(path.path_iter().split_at(100).next(), None);
+}
+
+#[test]
+fn test_reversed() {
+    let path = test_path();
+    let mut it = path.path_iter().reversed();
+
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(13.0, 2.0))));
+    assert_eq!(it.next(), Some(PathEvent::CubicTo(point(12.0, 1.0), point(11.0, 0.0), point(10.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(2.0, 1.0))));
+    assert_eq!(it.next(), Some(PathEvent::QuadraticTo(point(2.0, 0.0), point(1.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(point(0.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::Close));
+    assert_eq!(it.get_state().current, point(2.0, 1.0));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_merged() {
+    let path = test_path();
+    let events: Vec<PathEvent> = path.path_iter().merged(path.path_iter()).collect();
+    let expected: Vec<PathEvent> = path.iter().chain(path.iter()).collect();
+    assert_eq!(events, expected);
+
+    let line = [PathEvent::LineTo(point(5.0, 5.0))];
+    let mut it = path.path_iter().split_at(4).merged(PathIter::new(line.iter().cloned()));
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(10.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::CubicTo(point(11.0, 0.0), point(12.0, 1.0), point(13.0, 2.0))));
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(13.0, 2.0))));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(point(5.0, 5.0))));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_segment_events() {
+    let path = test_path();
+    let mut it = path.path_iter().segment_events();
+
+    assert_eq!(it.next(), Some(SegmentEvent::Begin { at: point(0.0, 0.0) }));
+    assert_eq!(it.next(), Some(SegmentEvent::Segment(Segment::Line(
+        LineSegment { from: point(0.0, 0.0), to: point(1.0, 0.0) }
+    ))));
+    assert_eq!(it.next(), Some(SegmentEvent::Segment(Segment::Quadratic(
+        QuadraticBezierSegment { from: point(1.0, 0.0), ctrl: point(2.0, 0.0), to: point(2.0, 1.0) }
+    ))));
+    assert_eq!(it.next(), Some(SegmentEvent::End { last: point(2.0, 1.0), first: point(0.0, 0.0), close: true }));
+    assert_eq!(it.next(), Some(SegmentEvent::Begin { at: point(10.0, 0.0) }));
+    assert_eq!(it.next(), Some(SegmentEvent::Segment(Segment::Cubic(
+        CubicBezierSegment { from: point(10.0, 0.0), ctrl1: point(11.0, 0.0), ctrl2: point(12.0, 1.0), to: point(13.0, 2.0) }
+    ))));
+    assert_eq!(it.next(), Some(SegmentEvent::End { last: point(13.0, 2.0), first: point(10.0, 0.0), close: false }));
+    assert_eq!(it.next(), None);
+
+    // Segments after a close continue from the first point of the closed sub-path.
+    let events = [
+        PathEvent::MoveTo(point(1.0, 1.0)),
+        PathEvent::LineTo(point(2.0, 1.0)),
+        PathEvent::Close,
+        PathEvent::Close,
+        PathEvent::LineTo(point(1.0, 5.0)),
+    ];
+    let mut it = PathIter::new(events.iter().cloned()).segment_events();
+    assert_eq!(it.next(), Some(SegmentEvent::Begin { at: point(1.0, 1.0) }));
+    it.next();
+    assert_eq!(it.next(), Some(SegmentEvent::End { last: point(2.0, 1.0), first: point(1.0, 1.0), close: true }));
+    assert_eq!(it.next(), Some(SegmentEvent::Begin { at: point(1.0, 1.0) }));
+    assert_eq!(it.next(), Some(SegmentEvent::Segment(Segment::Line(
+        LineSegment { from: point(1.0, 1.0), to: point(1.0, 5.0) }
+    ))));
+    assert_eq!(it.next(), Some(SegmentEvent::End { last: point(1.0, 5.0), first: point(1.0, 1.0), close: false }));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_quadratics_and_cubics() {
+    use builder::{FlatPathBuilder, PathBuilder};
+
+    let mut builder = ::default::Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(1.0, 1.0), Angle::radians(3.0), Angle::zero());
+    builder.line_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(0.0, 1.0), point(1.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 1.0), point(0.0, 2.0), point(2.0, 3.0));
+    let path = builder.build();
+
+    let tolerance = 0.01;
+
+    let events: Vec<QuadraticEvent> = path.path_iter().quadratics(tolerance).collect();
+    assert_eq!(events[0], QuadraticEvent::MoveTo(point(1.0, 0.0)));
+    let line_idx = events.iter().position(|evt| *evt == QuadraticEvent::LineTo(point(0.0, 0.0))).unwrap();
+    assert!(line_idx > 2);
+    // The approximation of the arc stays on the circle.
+    let mut from = point(1.0, 0.0);
+    for evt in &events[1..line_idx] {
+        match *evt {
+            QuadraticEvent::QuadraticTo(ctrl, to) => {
+                let curve = QuadraticBezierSegment { from, ctrl, to };
+                for &t in &[0.0, 0.25, 0.5, 0.75, 1.0] {
+                    assert!((curve.sample(t).to_vector().length() - 1.0).abs() <= tolerance);
+                }
+                from = to;
+            }
+            _ => panic!(),
+        }
+    }
+    assert!((from - point(3.0f32.cos(), 3.0f32.sin())).length() < 1e-5);
+    assert_eq!(events[line_idx + 1], QuadraticEvent::QuadraticTo(point(0.0, 1.0), point(1.0, 1.0)));
+    assert!(events.len() > line_idx + 3);
+
+    let events: Vec<PathEvent> = path.path_iter().cubics(tolerance).collect();
+    // Two cubics for the arc.
+    assert_eq!(events.len(), 6);
+    assert_eq!(events[0], PathEvent::MoveTo(point(1.0, 0.0)));
+    match events[2] {
+        PathEvent::CubicTo(_, _, to) => {
+            assert!((to - point(3.0f32.cos(), 3.0f32.sin())).length() < 1e-5);
+        }
+        _ => panic!(),
+    }
+    assert_eq!(events[3], PathEvent::LineTo(point(0.0, 0.0)));
+    match events[4] {
+        PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+            assert!((ctrl1 - point(0.0, 2.0 / 3.0)).length() < 1e-5);
+            assert!((ctrl2 - point(1.0 / 3.0, 1.0)).length() < 1e-5);
+            assert_eq!(to, point(1.0, 1.0));
+        }
+        _ => panic!(),
+    }
+    assert_eq!(events[5], PathEvent::CubicTo(point(2.0, 1.0), point(0.0, 2.0), point(2.0, 3.0)));
+}
+
+#[test]
+fn test_arc_lowering() {
+    use ArcFlags;
+
+    let events = [
+        SvgEvent::MoveTo(point(0.0, 0.0)),
+        SvgEvent::RelativeArcTo(vector(5.0, 5.0), Angle::zero(), ArcFlags::default(), vector(10.0, 0.0)),
+        SvgEvent::RelativeArcTo(vector(0.0, 5.0), Angle::zero(), ArcFlags::default(), vector(0.0, 10.0)),
+        SvgEvent::Close,
+    ];
+
+    let arcs: Vec<PathEvent> = SvgPathIter::new(events.iter().cloned())
+        .path_events_with(ArcLowering::Arcs)
+        .collect();
+    assert_eq!(arcs.len(), 4);
+    match arcs[1] {
+        PathEvent::Arc(center, radii, sweep_angle, _) => {
+            assert!((center - point(5.0, 0.0)).length() < 1e-5);
+            assert_eq!(radii, vector(5.0, 5.0));
+            assert!((sweep_angle.get().abs() - ::std::f32::consts::PI).abs() < 1e-5);
+        }
+        _ => panic!(),
+    }
+    // Arcs with a zero radius are lines.
+    assert_eq!(arcs[2], PathEvent::LineTo(point(10.0, 10.0)));
+
+    let mut it = SvgPathIter::new(events.iter().cloned()).path_events_with(ArcLowering::Cubics { tolerance: 0.01 });
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(0.0, 0.0))));
+    let mut num_curves = 0;
+    let mut last = point(0.0, 0.0);
+    while let Some(PathEvent::CubicTo(_, _, to)) = it.next() {
+        num_curves += 1;
+        last = to;
+    }
+    assert_eq!(num_curves, 2);
+    assert!((last - point(10.0, 0.0)).length() < 1e-4);
+
+    let quadratics = SvgPathIter::new(events.iter().cloned())
+        .path_events_with(ArcLowering::Quadratics { tolerance: 0.01 })
+        .filter(|evt| matches!(*evt, PathEvent::QuadraticTo(..)))
+        .count();
+    assert!(quadratics >= 2);
+
+    let mut num_arcs = 0;
+    let lines: Vec<PathEvent> = SvgPathIter::new(events.iter().cloned())
+        .path_events_with(|arc: &arc::Arc<f32>, output: &mut dyn FnMut(PathEvent)| {
+            num_arcs += 1;
+            output(PathEvent::LineTo(arc.to()));
+        })
+        .collect();
+    assert_eq!(lines.len(), 4);
+    match lines[1] {
+        PathEvent::LineTo(p) => assert!((p - point(10.0, 0.0)).length() < 1e-4),
+        _ => panic!(),
+    }
+    assert_eq!(num_arcs, 1);
+}
@@ -0,0 +1,142 @@
+//! Conversions between lyon paths and kurbo's `BezPath`.
+//!
+//! This module requires the `kurbo` feature. Segment types are converted by the
+//! `From` implementations of `lyon_geom`'s `kurbo_interop` module.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::default::Path;
+//! use lyon_path::geom::kurbo::{BezPath, PathEl, Point};
+//! use lyon_path::kurbo_interop::path_to_bez_path;
+//!
+//! let mut bez_path = BezPath::new();
+//! bez_path.push(PathEl::MoveTo(Point::new(0.0, 0.0)));
+//! bez_path.push(PathEl::QuadTo(Point::new(1.0, 1.0), Point::new(2.0, 0.0)));
+//! bez_path.push(PathEl::ClosePath);
+//!
+//! let path = Path::from(&bez_path);
+//! let round_trip = path_to_bez_path(path.path_iter());
+//!
+//! let events: Vec<_> = Path::from(&round_trip).iter().collect();
+//! let expected: Vec<_> = path.iter().collect();
+//! assert_eq!(events, expected);
+//! ```
+
+use geom::kurbo::{BezPath, PathEl};
+use geom::kurbo_interop::{from_kurbo_point, to_kurbo_point};
+use geom::Arc;
+use default::Path;
+use iterator::PathIterator;
+use PathEvent;
+
+impl<'l> From<&'l BezPath> for Path {
+    fn from(bez_path: &'l BezPath) -> Path {
+        bez_path.elements().iter().map(from_path_el).collect()
+    }
+}
+
+impl<'l> From<&'l Path> for BezPath {
+    fn from(path: &'l Path) -> BezPath {
+        path_to_bez_path(path.path_iter())
+    }
+}
+
+/// Converts a kurbo path element into a path event.
+pub fn from_path_el(el: &PathEl) -> PathEvent {
+    match *el {
+        PathEl::MoveTo(to) => PathEvent::MoveTo(from_kurbo_point(to)),
+        PathEl::LineTo(to) => PathEvent::LineTo(from_kurbo_point(to)),
+        PathEl::QuadTo(ctrl, to) => PathEvent::QuadraticTo(
+            from_kurbo_point(ctrl),
+            from_kurbo_point(to),
+        ),
+        PathEl::CurveTo(ctrl1, ctrl2, to) => PathEvent::CubicTo(
+            from_kurbo_point(ctrl1),
+            from_kurbo_point(ctrl2),
+            from_kurbo_point(to),
+        ),
+        PathEl::ClosePath => PathEvent::Close,
+    }
+}
+
+/// Converts a path into a kurbo path.
+///
+/// Kurbo paths can't represent arcs, so they are approximated with quadratic bézier curves.
+pub fn path_to_bez_path<Iter: PathIterator>(mut path: Iter) -> BezPath {
+    let mut bez_path = BezPath::new();
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                bez_path.push(PathEl::MoveTo(to_kurbo_point(to)));
+            }
+            Some(PathEvent::LineTo(to)) => {
+                bez_path.push(PathEl::LineTo(to_kurbo_point(to)));
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                bez_path.push(PathEl::QuadTo(to_kurbo_point(ctrl), to_kurbo_point(to)));
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                bez_path.push(PathEl::CurveTo(
+                    to_kurbo_point(ctrl1),
+                    to_kurbo_point(ctrl2),
+                    to_kurbo_point(to),
+                ));
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    bez_path.push(PathEl::QuadTo(to_kurbo_point(curve.ctrl), to_kurbo_point(curve.to)));
+                });
+            }
+            Some(PathEvent::Close) => {
+                bez_path.push(PathEl::ClosePath);
+            }
+            None => {
+                return bez_path;
+            }
+        }
+    }
+}
+
+#[test]
+fn kurbo_path_round_trip() {
+    use builder::*;
+    use math::{point, vector, Angle};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(3.0, 1.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.arc(point(10.0, 1.0), vector(1.0, 1.0), Angle::radians(3.0), Angle::radians(0.0));
+    let path = builder.build();
+
+    let bez_path = BezPath::from(&path);
+    let elements = bez_path.elements();
+    // kurbo's PathEl doesn't implement PartialEq.
+    match elements[2] {
+        PathEl::CurveTo(ctrl1, ctrl2, to) => {
+            assert_eq!(from_kurbo_point(ctrl1), point(2.0, 0.0));
+            assert_eq!(from_kurbo_point(ctrl2), point(2.0, 1.0));
+            assert_eq!(from_kurbo_point(to), point(3.0, 1.0));
+        }
+        _ => { panic!(); }
+    }
+    match elements[3] {
+        PathEl::ClosePath => {}
+        _ => { panic!(); }
+    }
+    assert!(elements.len() > 5);
+    match elements[elements.len() - 1] {
+        PathEl::QuadTo(..) => {}
+        _ => { panic!(); }
+    }
+
+    let events: Vec<PathEvent> = Path::from(&bez_path).iter().collect();
+    let expected: Vec<PathEvent> = path.iter().take(5).collect();
+    assert_eq!(&events[..5], &expected[..]);
+}
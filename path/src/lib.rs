@@ -44,13 +44,55 @@ pub extern crate lyon_geom as geom;
 #[macro_use]
 pub extern crate serde;
 
+#[cfg(feature = "geo-types")]
+pub extern crate geo_types;
+
+#[cfg(feature = "freetype-rs")]
+pub extern crate freetype;
+
 mod events;
 mod path_state;
 pub mod default;
 pub mod iterator;
 pub mod builder;
+pub mod polygon;
+pub mod path_buffer;
+pub mod fixed;
+#[cfg(feature = "dxf")]
+pub mod dxf;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(feature = "canvas")]
+pub mod canvas;
+#[cfg(feature = "geo-types")]
+pub mod geo_interop;
+#[cfg(feature = "kurbo")]
+pub mod kurbo_interop;
+#[cfg(feature = "freetype-rs")]
+pub mod freetype_interop;
 
 pub use events::*;
 pub use path_state::*;
 pub use geom::ArcFlags;
 pub use geom::math as math;
+
+/// The fill rule defines how to determine what is inside and what is outside of the shape.
+///
+/// See the SVG specification.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+impl FillRule {
+    /// Returns whether a point with the provided winding number is inside of the shape.
+    #[inline]
+    pub fn is_in(&self, winding: i32) -> bool {
+        match *self {
+            FillRule::EvenOdd => winding & 1 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Storage for many paths in contiguous buffers.
+//!
+//! Storing a large number of small paths (glyphs, markers, icons) as separate `Path`
+//! objects means two heap allocations per path. A `PathBuffer` stores the points and
+//! verbs of all of its paths in shared vectors and gives access to each path as a
+//! `PathSlice`.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::path_buffer::PathBuffer;
+//! use lyon_path::default::Path;
+//! use lyon_path::builder::*;
+//! use lyon_path::math::point;
+//! use lyon_path::PathEvent;
+//!
+//! let mut buffer = PathBuffer::new();
+//!
+//! let triangle = buffer.push_events(vec![
+//!     PathEvent::MoveTo(point(0.0, 0.0)),
+//!     PathEvent::LineTo(point(1.0, 0.0)),
+//!     PathEvent::LineTo(point(1.0, 1.0)),
+//!     PathEvent::Close,
+//! ]);
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0));
+//! let curve = buffer.push_slice(builder.build().as_slice());
+//!
+//! assert_eq!(buffer.len(), 2);
+//! assert_eq!(buffer.get(triangle).verbs().len(), 4);
+//! assert_eq!(buffer.get(curve).points().len(), 3);
+//!
+//! for path in buffer.iter() {
+//!     for event in path.iter() {
+//!         println!("{:?}", event);
+//!     }
+//! }
+//! ```
+
+use default::{PathSlice, Verb};
+use math::{Point, point};
+use PathEvent;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct PathRange {
+    points: (u32, u32),
+    verbs: (u32, u32),
+}
+
+/// Stores many paths contiguously.
+///
+/// Paths are identified by their index in the buffer, in the order they were added.
+#[derive(Clone, Debug, Default)]
+pub struct PathBuffer {
+    points: Vec<Point>,
+    verbs: Vec<Verb>,
+    paths: Vec<PathRange>,
+}
+
+impl PathBuffer {
+    pub fn new() -> Self {
+        PathBuffer {
+            points: Vec::new(),
+            verbs: Vec::new(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Constructor pre-allocating the total number of points, verbs and paths.
+    pub fn with_capacity(num_points: usize, num_verbs: usize, num_paths: usize) -> Self {
+        PathBuffer {
+            points: Vec::with_capacity(num_points),
+            verbs: Vec::with_capacity(num_verbs),
+            paths: Vec::with_capacity(num_paths),
+        }
+    }
+
+    /// Number of paths in the buffer.
+    pub fn len(&self) -> usize { self.paths.len() }
+
+    pub fn is_empty(&self) -> bool { self.paths.is_empty() }
+
+    /// Returns the path at a given index.
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn get(&self, index: usize) -> PathSlice<'_> {
+        let range = self.paths[index];
+        PathSlice::new(
+            &self.points[range.points.0 as usize..range.points.1 as usize],
+            &self.verbs[range.verbs.0 as usize..range.verbs.1 as usize],
+        )
+    }
+
+    /// Iterates over the paths of the buffer.
+    pub fn iter(&self) -> PathBufferIter<'_> {
+        PathBufferIter { buffer: self, index: 0 }
+    }
+
+    /// Copies a path into the buffer and returns its index.
+    pub fn push_slice(&mut self, path: PathSlice) -> usize {
+        let start = self.begin();
+        self.points.extend_from_slice(path.points());
+        self.verbs.extend_from_slice(path.verbs());
+
+        self.end(start)
+    }
+
+    /// Adds a path from a sequence of events and returns its index.
+    ///
+    /// Events containing NaN or infinite coordinates are skipped.
+    pub fn push_events<Iter>(&mut self, events: Iter) -> usize
+    where
+        Iter: IntoIterator<Item = PathEvent>,
+    {
+        let start = self.begin();
+        for event in events {
+            if !event.is_finite() {
+                continue;
+            }
+            match event {
+                PathEvent::MoveTo(to) => {
+                    self.points.push(to);
+                    self.verbs.push(Verb::MoveTo);
+                }
+                PathEvent::LineTo(to) => {
+                    self.points.push(to);
+                    self.verbs.push(Verb::LineTo);
+                }
+                PathEvent::QuadraticTo(ctrl, to) => {
+                    self.points.push(ctrl);
+                    self.points.push(to);
+                    self.verbs.push(Verb::QuadraticTo);
+                }
+                PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                    self.points.push(ctrl1);
+                    self.points.push(ctrl2);
+                    self.points.push(to);
+                    self.verbs.push(Verb::CubicTo);
+                }
+                PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                    // Same encoding as `Path`.
+                    self.points.push(center);
+                    self.points.push(radii.to_point());
+                    self.points.push(point(sweep_angle.get(), x_rotation.get()));
+                    self.verbs.push(Verb::Arc);
+                }
+                PathEvent::Close => {
+                    self.verbs.push(Verb::Close);
+                }
+            }
+        }
+
+        self.end(start)
+    }
+
+    /// Removes all paths, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.verbs.clear();
+        self.paths.clear();
+    }
+
+    fn begin(&self) -> (u32, u32) {
+        (self.points.len() as u32, self.verbs.len() as u32)
+    }
+
+    fn end(&mut self, start: (u32, u32)) -> usize {
+        self.paths.push(PathRange {
+            points: (start.0, self.points.len() as u32),
+            verbs: (start.1, self.verbs.len() as u32),
+        });
+
+        self.paths.len() - 1
+    }
+}
+
+/// Iterator over the paths of a `PathBuffer`.
+#[derive(Clone)]
+pub struct PathBufferIter<'l> {
+    buffer: &'l PathBuffer,
+    index: usize,
+}
+
+impl<'l> Iterator for PathBufferIter<'l> {
+    type Item = PathSlice<'l>;
+
+    fn next(&mut self) -> Option<PathSlice<'l>> {
+        if self.index >= self.buffer.len() {
+            return None;
+        }
+
+        let path = self.buffer.get(self.index);
+        self.index += 1;
+
+        Some(path)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.buffer.len() - self.index;
+        (n, Some(n))
+    }
+}
+
+impl<'l> ExactSizeIterator for PathBufferIter<'l> {}
+
+impl<'l> IntoIterator for &'l PathBuffer {
+    type Item = PathSlice<'l>;
+    type IntoIter = PathBufferIter<'l>;
+
+    fn into_iter(self) -> PathBufferIter<'l> { self.iter() }
+}
+
+#[test]
+fn path_buffer() {
+    use default::Path;
+    use math::{vector, Angle};
+    use std::iter::FromIterator;
+
+    let events = vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::LineTo(point(f32::NAN, 0.0)),
+        PathEvent::CubicTo(point(2.0, 0.0), point(2.0, 1.0), point(2.0, 2.0)),
+        PathEvent::Arc(point(1.0, 1.0), vector(1.0, 1.0), Angle::radians(1.0), Angle::radians(0.0)),
+        PathEvent::Close,
+    ];
+
+    let mut buffer = PathBuffer::with_capacity(64, 32, 4);
+    assert!(buffer.is_empty());
+    let a = buffer.push_events(events.clone());
+    let b = buffer.push_events(Vec::new());
+    let c = buffer.push_slice(Path::from_iter(events.clone()).as_slice());
+
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.get(b).verbs().len(), 0);
+
+    let expected: Vec<PathEvent> = events.iter().cloned().filter(|e| e.is_finite()).collect();
+    let from_a: Vec<PathEvent> = buffer.get(a).iter().collect();
+    let from_c: Vec<PathEvent> = buffer.get(c).iter().collect();
+    assert_eq!(from_a, expected);
+    assert_eq!(from_c, expected);
+
+    assert_eq!(buffer.iter().len(), 3);
+    assert_eq!(buffer.iter().map(|path| path.verbs().len()).sum::<usize>(), 10);
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+}
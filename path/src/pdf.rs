@@ -0,0 +1,349 @@
+//! Conversions between paths and the path construction operators of PDF content streams.
+//!
+//! PDF paths are made of the operators `m` (move to), `l` (line to), `c`, `v` and `y`
+//! (cubic bézier curves), `re` (rectangle) and `h` (close). Quadratic bézier curves are
+//! converted exactly into cubic ones and arcs are approximated with cubic bézier curves
+//! or line segments.
+//!
+//! This module requires the `pdf` feature.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_path::pdf::{PdfOptions, path_to_pdf, build_from_pdf};
+//! use lyon_path::default::Path;
+//! use lyon_path::builder::*;
+//! use lyon_path::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let operators = path_to_pdf(path.path_iter(), &PdfOptions::default());
+//! assert_eq!(operators, "0 0 m\n10 0 l\n10 6.667 6.667 10 0 10 c\nh\n");
+//!
+//! let mut builder = Path::builder();
+//! build_from_pdf(&operators, &mut builder).unwrap();
+//! let path = builder.build();
+//! assert_eq!(path.verbs().len(), 4);
+//! ```
+
+use math::{Point, Vector, point, vector};
+use geom::{Arc, QuadraticBezierSegment};
+use builder::PathBuilder;
+use iterator::PathIterator;
+use PathEvent;
+
+use std::fmt;
+
+/// Parameters of the conversion of paths into PDF operators.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfOptions {
+    /// Maximum distance between the arcs and their approximation.
+    pub tolerance: f32,
+    /// Approximate arcs with line segments instead of cubic bézier curves.
+    pub flatten_arcs: bool,
+    /// Maximum number of digits after the decimal point.
+    pub precision: usize,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            tolerance: 0.01,
+            flatten_arcs: false,
+            precision: 3,
+        }
+    }
+}
+
+/// An error in the operands of a PDF path operator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PdfParseError {
+    /// An operator doesn't have enough numeric operands.
+    MissingOperands(String),
+}
+
+/// Converts a path into PDF path construction operators, one per line.
+///
+/// Closed sub-paths that are rectangles starting with a horizontal edge are written with
+/// the `re` operator.
+pub fn path_to_pdf<Iter: PathIterator>(path: Iter, options: &PdfOptions) -> String {
+    let mut output = String::new();
+    write_pdf(path, options, &mut output).unwrap();
+
+    output
+}
+
+/// Writes a path as PDF path construction operators, one per line.
+///
+/// See `path_to_pdf`.
+pub fn write_pdf<Iter, Output>(mut path: Iter, options: &PdfOptions, output: &mut Output) -> fmt::Result
+where
+    Iter: PathIterator,
+    Output: fmt::Write,
+{
+    // Lower everything to move, line, cubic and close events.
+    let mut events = Vec::new();
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let curve = QuadraticBezierSegment { from, ctrl, to }.to_cubic();
+                events.push(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to));
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                if options.flatten_arcs {
+                    arc.for_each_flattened(options.tolerance, &mut |p| {
+                        events.push(PathEvent::LineTo(p));
+                    });
+                } else {
+                    arc.for_each_cubic_bezier(options.tolerance, &mut |curve| {
+                        events.push(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to));
+                    });
+                }
+            }
+            Some(evt) => {
+                events.push(evt);
+            }
+            None => {
+                break;
+            }
+        }
+    }
+
+    let precision = options.precision;
+    let mut i = 0;
+    while i < events.len() {
+        if let Some((origin, size, num_events)) = rectangle_at(&events[i..]) {
+            write_numbers(&[origin.x, origin.y, size.x, size.y], precision, output)?;
+            writeln!(output, "re")?;
+            i += num_events;
+            continue;
+        }
+
+        match events[i] {
+            PathEvent::MoveTo(to) => {
+                write_numbers(&[to.x, to.y], precision, output)?;
+                writeln!(output, "m")?;
+            }
+            PathEvent::LineTo(to) => {
+                write_numbers(&[to.x, to.y], precision, output)?;
+                writeln!(output, "l")?;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                write_numbers(&[ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y], precision, output)?;
+                writeln!(output, "c")?;
+            }
+            PathEvent::Close => {
+                writeln!(output, "h")?;
+            }
+            PathEvent::QuadraticTo(..) | PathEvent::Arc(..) => {
+                unreachable!();
+            }
+        }
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Adds the path construction operators of a PDF content stream to a path builder.
+///
+/// Operands are separated by white spaces. The other operators of the content stream
+/// (for example the painting and the graphics state operators) and their operands are
+/// ignored.
+pub fn build_from_pdf<Builder: PathBuilder>(
+    content: &str,
+    builder: &mut Builder,
+) -> Result<(), PdfParseError> {
+    let mut operands: Vec<f32> = Vec::new();
+    let mut current = point(0.0, 0.0);
+    let mut first = point(0.0, 0.0);
+
+    for token in content.split_whitespace() {
+        if let Ok(value) = token.parse::<f32>() {
+            operands.push(value);
+            continue;
+        }
+
+        let num_operands = match token {
+            "m" | "l" => 2,
+            "v" | "y" => 4,
+            "re" => 4,
+            "c" => 6,
+            "h" => 0,
+            _ => {
+                operands.clear();
+                continue;
+            }
+        };
+
+        if operands.len() < num_operands {
+            return Err(PdfParseError::MissingOperands(token.to_string()));
+        }
+
+        {
+            let o = &operands[operands.len() - num_operands..];
+            match token {
+                "m" => {
+                    current = point(o[0], o[1]);
+                    first = current;
+                    builder.move_to(current);
+                }
+                "l" => {
+                    current = point(o[0], o[1]);
+                    builder.line_to(current);
+                }
+                "c" => {
+                    let to = point(o[4], o[5]);
+                    builder.cubic_bezier_to(point(o[0], o[1]), point(o[2], o[3]), to);
+                    current = to;
+                }
+                "v" => {
+                    let to = point(o[2], o[3]);
+                    builder.cubic_bezier_to(current, point(o[0], o[1]), to);
+                    current = to;
+                }
+                "y" => {
+                    let to = point(o[2], o[3]);
+                    builder.cubic_bezier_to(point(o[0], o[1]), to, to);
+                    current = to;
+                }
+                "re" => {
+                    let (x, y, w, h) = (o[0], o[1], o[2], o[3]);
+                    builder.move_to(point(x, y));
+                    builder.line_to(point(x + w, y));
+                    builder.line_to(point(x + w, y + h));
+                    builder.line_to(point(x, y + h));
+                    builder.close();
+                    current = point(x, y);
+                    first = current;
+                }
+                "h" => {
+                    builder.close();
+                    current = first;
+                }
+                _ => {
+                    unreachable!();
+                }
+            }
+        }
+        operands.clear();
+    }
+
+    Ok(())
+}
+
+// Detects a closed sub-path equivalent to the `re` operator, with an optional line back
+// to the start before the close event.
+fn rectangle_at(events: &[PathEvent]) -> Option<(Point, Vector, usize)> {
+    let mut p = [point(0.0, 0.0); 5];
+    let mut n = 0;
+    for (i, evt) in events.iter().take(5).enumerate() {
+        match *evt {
+            PathEvent::MoveTo(to) if i == 0 => { p[0] = to; }
+            PathEvent::LineTo(to) if i > 0 => { p[i] = to; }
+            _ => { break; }
+        }
+        n += 1;
+    }
+
+    if n < 4 || (n == 5 && p[4] != p[0]) || events.get(n) != Some(&PathEvent::Close) {
+        return None;
+    }
+
+    let is_rectangle = p[1].y == p[0].y && p[2].x == p[1].x && p[3].y == p[2].y && p[3].x == p[0].x;
+    if !is_rectangle {
+        return None;
+    }
+
+    Some((p[0], vector(p[1].x - p[0].x, p[2].y - p[1].y), n + 1))
+}
+
+fn write_numbers<Output: fmt::Write>(values: &[f32], precision: usize, output: &mut Output) -> fmt::Result {
+    for value in values {
+        let text = format!("{:.*}", precision, value);
+        let mut text = if text.contains('.') {
+            text.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &text[..]
+        };
+        if text == "-0" {
+            text = "0";
+        }
+        write!(output, "{} ", text)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pdf_round_trip() {
+    use default::Path;
+    use builder::FlatPathBuilder;
+    use math::Angle;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 2.0));
+    builder.line_to(point(11.0, 2.0));
+    builder.line_to(point(11.0, 7.0));
+    builder.line_to(point(1.0, 7.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.arc(point(20.0, 0.0), vector(10.0, 10.0), Angle::radians(1.5), Angle::zero());
+    builder.cubic_bezier_to(point(1.0, 1.0 / 3.0), point(2.0, 2.0), point(-0.00001, 3.0));
+    let path = builder.build();
+
+    let pdf = path_to_pdf(path.path_iter(), &PdfOptions::default());
+    let lines: Vec<&str> = pdf.lines().collect();
+    assert_eq!(lines[0], "1 2 10 5 re");
+    assert_eq!(lines[1], "20 0 m");
+    assert_eq!(lines[2], "30 0 l");
+    assert!(lines[3].ends_with(" c"));
+    assert_eq!(lines[4], "1 0.333 2 2 0 3 c");
+    assert_eq!(lines.len(), 5);
+
+    let flattened = path_to_pdf(
+        path.path_iter(),
+        &PdfOptions { flatten_arcs: true, precision: 1, .. PdfOptions::default() },
+    );
+    assert!(flattened.lines().count() > 6);
+    for token in flattened.split_whitespace() {
+        if let Some(dot) = token.find('.') {
+            assert_eq!(token.len(), dot + 2);
+        }
+    }
+
+    let mut builder = Path::builder();
+    build_from_pdf(&pdf, &mut builder).unwrap();
+    let parsed = builder.build();
+    let events: Vec<PathEvent> = parsed.iter().collect();
+    assert_eq!(events[0], PathEvent::MoveTo(point(1.0, 2.0)));
+    assert_eq!(events[2], PathEvent::LineTo(point(11.0, 7.0)));
+    assert_eq!(events[4], PathEvent::Close);
+    assert_eq!(events.len(), 9);
+
+    // Other operators are skipped, the shorthand curve operators use the current point.
+    let mut builder = Path::builder();
+    build_from_pdf("q 1 0 0 1 5 5 cm 0 0 m 1 1 2 2 v 3 3 4 4 y h f Q", &mut builder).unwrap();
+    let events: Vec<PathEvent> = builder.build().iter().collect();
+    assert_eq!(events, vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::CubicTo(point(0.0, 0.0), point(1.0, 1.0), point(2.0, 2.0)),
+        PathEvent::CubicTo(point(3.0, 3.0), point(4.0, 4.0), point(4.0, 4.0)),
+        PathEvent::Close,
+    ]);
+
+    let mut builder = Path::builder();
+    assert_eq!(
+        build_from_pdf("0 0 m 1 l", &mut builder),
+        Err(PdfParseError::MissingOperands("l".to_string()))
+    );
+}
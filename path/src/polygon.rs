@@ -0,0 +1,181 @@
+//! Lightweight views on sequences of points forming a polygon or a polyline.
+
+use math::Point;
+use events::{PathEvent, FlattenedEvent};
+use iterator::{PathIterator, FlattenedIterator};
+use path_state::PathState;
+
+/// A view on a sequence of points connected by straight lines.
+///
+/// Unlike `Path`, this doesn't require any allocation or conversion, which is
+/// convenient for data that is already flat.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Polygon<'l> {
+    pub points: &'l [Point],
+    /// Whether the last point is connected to the first one.
+    pub closed: bool,
+}
+
+impl<'l> Polygon<'l> {
+    /// A closed polygon.
+    pub fn new(points: &'l [Point]) -> Self {
+        Polygon { points, closed: true }
+    }
+
+    /// An open sequence of line segments.
+    pub fn polyline(points: &'l [Point]) -> Self {
+        Polygon { points, closed: false }
+    }
+
+    /// Returns an iterator over the flattened events of this polygon.
+    pub fn iter(&self) -> PolygonIter<'l> {
+        PolygonIter {
+            points: self.points.iter(),
+            closed: self.closed,
+            first: true,
+            done: self.points.is_empty(),
+            state: PathState::new(),
+        }
+    }
+
+    /// Returns an iterator over the path events of this polygon.
+    pub fn path_iter(&self) -> PolygonPathIter<'l> {
+        PolygonPathIter { it: self.iter() }
+    }
+}
+
+impl<'l> IntoIterator for Polygon<'l> {
+    type Item = FlattenedEvent;
+    type IntoIter = PolygonIter<'l>;
+
+    fn into_iter(self) -> PolygonIter<'l> { self.iter() }
+}
+
+/// A view on a sequence of points connected by straight lines, without a line between
+/// the last point and the first one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Polyline<'l> {
+    pub points: &'l [Point],
+}
+
+impl<'l> Polyline<'l> {
+    pub fn new(points: &'l [Point]) -> Self {
+        Polyline { points }
+    }
+
+    /// Returns an open `Polygon` view on the same points.
+    pub fn to_polygon(&self) -> Polygon<'l> {
+        Polygon::polyline(self.points)
+    }
+
+    /// Returns an iterator over the flattened events of this polyline.
+    pub fn iter(&self) -> PolygonIter<'l> {
+        self.to_polygon().iter()
+    }
+
+    /// Returns an iterator over the path events of this polyline.
+    pub fn path_iter(&self) -> PolygonPathIter<'l> {
+        self.to_polygon().path_iter()
+    }
+}
+
+impl<'l> IntoIterator for Polyline<'l> {
+    type Item = FlattenedEvent;
+    type IntoIter = PolygonIter<'l>;
+
+    fn into_iter(self) -> PolygonIter<'l> { self.iter() }
+}
+
+impl<'l> From<Polyline<'l>> for Polygon<'l> {
+    fn from(polyline: Polyline<'l>) -> Self { polyline.to_polygon() }
+}
+
+/// An iterator of `FlattenedEvent` for `Polygon`.
+pub struct PolygonIter<'l> {
+    points: ::std::slice::Iter<'l, Point>,
+    closed: bool,
+    first: bool,
+    done: bool,
+    state: PathState,
+}
+
+impl<'l> Iterator for PolygonIter<'l> {
+    type Item = FlattenedEvent;
+
+    fn next(&mut self) -> Option<FlattenedEvent> {
+        if self.done {
+            return None;
+        }
+
+        let evt = match self.points.next() {
+            Some(&p) if self.first => {
+                self.first = false;
+                FlattenedEvent::MoveTo(p)
+            }
+            Some(&p) => FlattenedEvent::LineTo(p),
+            None => {
+                self.done = true;
+                if !self.closed {
+                    return None;
+                }
+                FlattenedEvent::Close
+            }
+        };
+
+        self.state.flattened_event(evt);
+
+        Some(evt)
+    }
+}
+
+impl<'l> FlattenedIterator for PolygonIter<'l> {
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+/// An iterator of `PathEvent` for `Polygon`.
+pub struct PolygonPathIter<'l> {
+    it: PolygonIter<'l>,
+}
+
+impl<'l> Iterator for PolygonPathIter<'l> {
+    type Item = PathEvent;
+
+    fn next(&mut self) -> Option<PathEvent> {
+        self.it.next().map(|evt| evt.to_path_event())
+    }
+}
+
+impl<'l> PathIterator for PolygonPathIter<'l> {
+    fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+#[test]
+fn polygon_events() {
+    use math::point;
+
+    let points = [point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)];
+
+    let mut it = Polygon::new(&points).path_iter();
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(points[0])));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(points[1])));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(points[2])));
+    assert_eq!(it.get_state().current, points[2]);
+    assert_eq!(it.next(), Some(PathEvent::Close));
+    assert_eq!(it.get_state().current, points[0]);
+    assert_eq!(it.next(), None);
+
+    let mut it = Polygon::polyline(&points).iter();
+    assert_eq!(it.next(), Some(FlattenedEvent::MoveTo(points[0])));
+    assert_eq!(it.next(), Some(FlattenedEvent::LineTo(points[1])));
+    assert_eq!(it.next(), Some(FlattenedEvent::LineTo(points[2])));
+    assert_eq!(it.next(), None);
+
+    let mut it = Polyline::new(&points).path_iter();
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(points[0])));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(points[1])));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(points[2])));
+    assert_eq!(it.next(), None);
+    assert!(!Polygon::from(Polyline::new(&points)).closed);
+
+    assert_eq!(Polygon::new(&[]).iter().next(), None);
+}
@@ -0,0 +1,642 @@
+//! Loading whole SVG documents.
+//!
+//! This module requires the `document` feature. It reads the document with the
+//! [svgparser](https://crates.io/crates/svgparser) tokenizer and converts each shape
+//! (`path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon`) into a lyon path
+//! with its transform and fill and stroke styles, ready to be tessellated.
+//!
+//! Presentation attributes and the declarations of `style` attributes are inherited from
+//! the groups containing the shapes, and the opacity of the groups is multiplied into the
+//! opacity of the fills and strokes. The content of `defs`, `symbol`, `clipPath`, `mask`,
+//! `pattern`, `marker` and unknown elements is skipped, style sheets are ignored, lengths
+//! relative to the viewport or to the font are not supported and `preserveAspectRatio` is
+//! always treated as `xMidYMid meet`.
+//!
+//! Gradients and patterns are not supported: the paint of a style is `None` in that case,
+//! and it is up to the user to pick a fallback.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # extern crate lyon_svg as svg;
+//! # fn main() {
+//! let shapes = svg::document::load_file("assets/lyon-logo.svg").unwrap();
+//! for shape in &shapes {
+//!     if let Some(ref fill) = shape.fill {
+//!         // Fill shape.path, transformed by shape.transform.
+//!     }
+//!     if let Some(ref stroke) = shape.stroke {
+//!         // Stroke shape.path with a line width of stroke.width.
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path as FilePath;
+use std::f32::consts::FRAC_PI_2;
+
+use parser;
+use parser::{AttributeId, AttributeValue, ElementId, Length, LengthUnit, ValueId, ViewBox};
+use parser::svg::{Tokenizer, Token, ElementEnd, Name};
+use parser::xmlparser::{FromSpan, StrSpan};
+
+use path::default::Path;
+use path::builder::*;
+use path::geom::{Circle, Ellipse};
+use path::math::{Transform2D, Angle, Point, point, vector};
+use path_utils::build_path;
+
+/// An RGB color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+        Color { red, green, blue }
+    }
+}
+
+/// The shape at the end of open sub-paths when they are stroked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+/// The shape of the corners of a stroke.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// A path of an SVG document with its resolved transform and styles.
+#[derive(Clone, Debug)]
+pub struct Shape {
+    /// The path, in the local coordinate space of the shape.
+    pub path: Path,
+    /// Transforms the path into the coordinate space of the document, including the
+    /// transforms of the groups containing it.
+    pub transform: Transform2D,
+    pub fill: Option<FillStyle>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FillStyle {
+    /// The color of the fill, or `None` if it is a gradient or a pattern.
+    pub paint: Option<Color>,
+    pub opacity: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    /// The color of the stroke, or `None` if it is a gradient or a pattern.
+    pub paint: Option<Color>,
+    pub opacity: f32,
+    pub width: f32,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The document is not well-formed.
+    Parse(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self { Error::Io(err) }
+}
+
+/// Loads an SVG file and returns its shapes in rendering order.
+pub fn load_file<P: AsRef<FilePath>>(file_path: P) -> Result<Vec<Shape>, Error> {
+    let mut text = String::new();
+    File::open(file_path)?.read_to_string(&mut text)?;
+
+    parse(&text)
+}
+
+/// Parses an SVG document and returns its shapes in rendering order.
+pub fn parse(text: &str) -> Result<Vec<Shape>, Error> {
+    let mut shapes = Vec::new();
+    // The styles of the open elements, the innermost one last.
+    let mut stack = vec![Style::default()];
+    // The element being read, until the end of its start tag.
+    let mut element = None;
+
+    for token in Tokenizer::from_str(text) {
+        match token.map_err(|err| Error::Parse(err.to_string()))? {
+            Token::ElementStart(tag) => {
+                element = Some(Element {
+                    id: match tag.local {
+                        Name::Svg(id) if tag.prefix.is_empty() => Some(id),
+                        _ => None,
+                    },
+                    attributes: Vec::new(),
+                });
+            }
+            Token::Attribute(name, value) => {
+                if let (Some(element), Name::Svg(id)) = (element.as_mut(), name.local) {
+                    if name.prefix.is_empty() {
+                        element.attributes.push((id, value));
+                    }
+                }
+            }
+            Token::ElementEnd(ElementEnd::Close(..)) => {
+                stack.pop();
+            }
+            Token::ElementEnd(end) => {
+                let element = match element.take() {
+                    Some(element) => element,
+                    None => { continue; }
+                };
+                let style = element.style(stack.last().cloned().unwrap_or_default());
+                if let Some(shape) = element.shape(&style) {
+                    shapes.push(shape);
+                }
+                if end == ElementEnd::Open {
+                    stack.push(style);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(shapes)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Paint {
+    None,
+    Color(Color),
+    CurrentColor,
+    // Gradients and patterns.
+    Server,
+}
+
+// The inherited properties of an element.
+#[derive(Copy, Clone, Debug)]
+struct Style {
+    transform: Transform2D,
+    fill: Paint,
+    fill_opacity: f32,
+    stroke: Paint,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    color: Color,
+    opacity: f32,
+    visible: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            transform: Transform2D::identity(),
+            fill: Paint::Color(Color::new(0, 0, 0)),
+            fill_opacity: 1.0,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            color: Color::new(0, 0, 0),
+            opacity: 1.0,
+            visible: true,
+        }
+    }
+}
+
+impl Style {
+    fn color(&self, paint: Paint) -> Option<Color> {
+        match paint {
+            Paint::Color(color) => Some(color),
+            Paint::CurrentColor => Some(self.color),
+            Paint::None | Paint::Server => None,
+        }
+    }
+}
+
+struct Element<'l> {
+    // None for elements that aren't in the SVG namespace.
+    id: Option<ElementId>,
+    attributes: Vec<(AttributeId, StrSpan<'l>)>,
+}
+
+impl<'l> Element<'l> {
+    // Computes the style of the element from the one of its parent.
+    fn style(&self, parent: Style) -> Style {
+        let mut style = parent;
+        let id = match self.id {
+            Some(id) => id,
+            None => {
+                style.visible = false;
+                return style;
+            }
+        };
+
+        match id {
+            ElementId::Svg | ElementId::G | ElementId::A => {}
+            ElementId::Path | ElementId::Rect | ElementId::Circle | ElementId::Ellipse
+            | ElementId::Line | ElementId::Polyline | ElementId::Polygon => {}
+            _ => { style.visible = false; }
+        }
+
+        // The declarations of the style attribute override the presentation attributes.
+        let mut properties = Vec::new();
+        let mut declarations = Vec::new();
+        for &(attribute, value) in &self.attributes {
+            if attribute == AttributeId::Style {
+                for token in parser::style::Tokenizer::from_span(value) {
+                    match token {
+                        Ok(parser::style::Token::SvgAttribute(attribute, value)) => {
+                            declarations.push((attribute, value));
+                        }
+                        Ok(_) => {}
+                        Err(_) => { break; }
+                    }
+                }
+            } else {
+                properties.push((attribute, value));
+            }
+        }
+        properties.extend(declarations);
+
+        let mut transform = Transform2D::identity();
+        let mut opacity = 1.0;
+        let mut view_box = None;
+        let mut size = (None, None);
+        for (attribute, value) in properties {
+            // Invalid values are ignored, as if the property wasn't specified.
+            let value = match AttributeValue::from_span(id, "", attribute, value) {
+                Ok(value) => value,
+                Err(_) => { continue; }
+            };
+            match (attribute, value) {
+                (AttributeId::Fill, value) => {
+                    if let Some(paint) = paint(value) {
+                        style.fill = paint;
+                    }
+                }
+                (AttributeId::Stroke, value) => {
+                    if let Some(paint) = paint(value) {
+                        style.stroke = paint;
+                    }
+                }
+                (AttributeId::FillOpacity, AttributeValue::Number(n)) => {
+                    style.fill_opacity = n as f32;
+                }
+                (AttributeId::StrokeOpacity, AttributeValue::Number(n)) => {
+                    style.stroke_opacity = n as f32;
+                }
+                (AttributeId::Opacity, AttributeValue::Number(n)) => {
+                    opacity = n as f32;
+                }
+                (AttributeId::StrokeWidth, AttributeValue::Length(l)) => {
+                    if let Some(width) = length(l) {
+                        style.stroke_width = width;
+                    }
+                }
+                (AttributeId::StrokeLinecap, AttributeValue::PredefValue(v)) => {
+                    match v {
+                        ValueId::Butt => { style.line_cap = LineCap::Butt; }
+                        ValueId::Square => { style.line_cap = LineCap::Square; }
+                        ValueId::Round => { style.line_cap = LineCap::Round; }
+                        _ => {}
+                    }
+                }
+                (AttributeId::StrokeLinejoin, AttributeValue::PredefValue(v)) => {
+                    match v {
+                        ValueId::Miter => { style.line_join = LineJoin::Miter; }
+                        ValueId::Round => { style.line_join = LineJoin::Round; }
+                        ValueId::Bevel => { style.line_join = LineJoin::Bevel; }
+                        _ => {}
+                    }
+                }
+                (AttributeId::Color, AttributeValue::Color(c)) => {
+                    style.color = Color::new(c.red, c.green, c.blue);
+                }
+                (AttributeId::Display, AttributeValue::PredefValue(ValueId::None)) => {
+                    style.visible = false;
+                }
+                (AttributeId::Transform, AttributeValue::Transform(tokens)) => {
+                    transform = convert_transform(tokens);
+                }
+                (AttributeId::ViewBox, AttributeValue::ViewBox(vb)) => {
+                    view_box = Some(vb);
+                }
+                (AttributeId::Width, AttributeValue::Length(l)) => { size.0 = length(l); }
+                (AttributeId::Height, AttributeValue::Length(l)) => { size.1 = length(l); }
+                _ => {}
+            }
+        }
+
+        if let (ElementId::Svg, Some(vb)) = (id, view_box) {
+            // The view box maps the content of the element to its viewport.
+            transform = view_box_transform(vb, size).post_mul(&transform);
+        }
+
+        style.transform = transform.post_mul(&parent.transform);
+        style.opacity *= opacity;
+
+        style
+    }
+
+    fn shape(&self, style: &Style) -> Option<Shape> {
+        if !style.visible {
+            return None;
+        }
+
+        let fill = match style.fill {
+            Paint::None => None,
+            paint => Some(FillStyle {
+                paint: style.color(paint),
+                opacity: style.fill_opacity * style.opacity,
+            }),
+        };
+        let stroke = match style.stroke {
+            Paint::None => None,
+            _ if style.stroke_width <= 0.0 => None,
+            paint => Some(StrokeStyle {
+                paint: style.color(paint),
+                opacity: style.stroke_opacity * style.opacity,
+                width: style.stroke_width,
+                line_cap: style.line_cap,
+                line_join: style.line_join,
+            }),
+        };
+        if fill.is_none() && stroke.is_none() {
+            return None;
+        }
+
+        Some(Shape {
+            path: self.path()?,
+            transform: style.transform,
+            fill,
+            stroke,
+        })
+    }
+
+    // Builds the geometry of shape elements.
+    fn path(&self) -> Option<Path> {
+        let id = self.id?;
+        let len = |attribute| self.length(id, attribute);
+        let len_or_zero = |attribute| self.length(id, attribute).unwrap_or(0.0);
+
+        let mut builder = Path::builder();
+        match id {
+            ElementId::Path => {
+                let d = self.attribute(AttributeId::D)?;
+                return build_path(Path::builder().with_svg(), d.to_str()).ok();
+            }
+            ElementId::Rect => {
+                let (x, y) = (len_or_zero(AttributeId::X), len_or_zero(AttributeId::Y));
+                let (w, h) = (len(AttributeId::Width)?, len(AttributeId::Height)?);
+                if w <= 0.0 || h <= 0.0 {
+                    return None;
+                }
+                // If only one of the radii is specified, it is used for both.
+                let (rx, ry) = match (len(AttributeId::Rx), len(AttributeId::Ry)) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0),
+                };
+                let r = vector(rx.min(w * 0.5), ry.min(h * 0.5));
+                if r.x > 0.0 && r.y > 0.0 {
+                    let quarter = Angle::radians(FRAC_PI_2);
+                    builder.move_to(point(x + r.x, y));
+                    builder.line_to(point(x + w - r.x, y));
+                    builder.arc(point(x + w - r.x, y + r.y), r, quarter, Angle::zero());
+                    builder.line_to(point(x + w, y + h - r.y));
+                    builder.arc(point(x + w - r.x, y + h - r.y), r, quarter, Angle::zero());
+                    builder.line_to(point(x + r.x, y + h));
+                    builder.arc(point(x + r.x, y + h - r.y), r, quarter, Angle::zero());
+                    builder.line_to(point(x, y + r.y));
+                    builder.arc(point(x + r.x, y + r.y), r, quarter, Angle::zero());
+                } else {
+                    builder.move_to(point(x, y));
+                    builder.line_to(point(x + w, y));
+                    builder.line_to(point(x + w, y + h));
+                    builder.line_to(point(x, y + h));
+                }
+                builder.close();
+            }
+            ElementId::Circle => {
+                let r = len(AttributeId::R)?;
+                if r <= 0.0 {
+                    return None;
+                }
+                let center = point(len_or_zero(AttributeId::Cx), len_or_zero(AttributeId::Cy));
+                builder.circle(&Circle::new(center, r));
+            }
+            ElementId::Ellipse => {
+                let radii = vector(len(AttributeId::Rx)?, len(AttributeId::Ry)?);
+                if radii.x <= 0.0 || radii.y <= 0.0 {
+                    return None;
+                }
+                builder.ellipse(&Ellipse {
+                    center: point(len_or_zero(AttributeId::Cx), len_or_zero(AttributeId::Cy)),
+                    radii,
+                    x_rotation: Angle::zero(),
+                });
+            }
+            ElementId::Line => {
+                builder.move_to(point(len_or_zero(AttributeId::X1), len_or_zero(AttributeId::Y1)));
+                builder.line_to(point(len_or_zero(AttributeId::X2), len_or_zero(AttributeId::Y2)));
+            }
+            ElementId::Polyline | ElementId::Polygon => {
+                let points = self.points(id);
+                if points.len() < 2 {
+                    return None;
+                }
+                builder.move_to(points[0]);
+                for p in &points[1..] {
+                    builder.line_to(*p);
+                }
+                if id == ElementId::Polygon {
+                    builder.close();
+                }
+            }
+            _ => { return None; }
+        }
+
+        Some(builder.build())
+    }
+
+    fn attribute(&self, attribute: AttributeId) -> Option<StrSpan<'l>> {
+        self.attributes.iter().rev().find(|a| a.0 == attribute).map(|a| a.1)
+    }
+
+    fn length(&self, id: ElementId, attribute: AttributeId) -> Option<f32> {
+        match AttributeValue::from_span(id, "", attribute, self.attribute(attribute)?) {
+            Ok(AttributeValue::Length(l)) => length(l),
+            _ => None,
+        }
+    }
+
+    fn points(&self, id: ElementId) -> Vec<Point> {
+        let value = self.attribute(AttributeId::Points)
+            .and_then(|span| AttributeValue::from_span(id, "", AttributeId::Points, span).ok());
+        match value {
+            Some(AttributeValue::Points(points)) => {
+                points.map(|(x, y)| point(x as f32, y as f32)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Returns None for inherited and unsupported values.
+fn paint(value: AttributeValue) -> Option<Paint> {
+    match value {
+        AttributeValue::Color(c) => Some(Paint::Color(Color::new(c.red, c.green, c.blue))),
+        AttributeValue::PredefValue(ValueId::None) => Some(Paint::None),
+        AttributeValue::PredefValue(ValueId::CurrentColor) => Some(Paint::CurrentColor),
+        AttributeValue::FuncIRI(..) | AttributeValue::FuncIRIWithFallback(..) => Some(Paint::Server),
+        _ => None,
+    }
+}
+
+// Converts absolute lengths to user units (CSS pixels).
+fn length(l: Length) -> Option<f32> {
+    let scale = match l.unit {
+        LengthUnit::None | LengthUnit::Px => 1.0,
+        LengthUnit::In => 96.0,
+        LengthUnit::Cm => 96.0 / 2.54,
+        LengthUnit::Mm => 96.0 / 25.4,
+        LengthUnit::Pt => 4.0 / 3.0,
+        LengthUnit::Pc => 16.0,
+        LengthUnit::Em | LengthUnit::Ex | LengthUnit::Percent => { return None; }
+    };
+
+    Some(l.num as f32 * scale)
+}
+
+fn convert_transform(tokens: parser::transform::Tokenizer) -> Transform2D {
+    use parser::transform::Token;
+
+    let mut transform = Transform2D::identity();
+    for token in tokens {
+        let (a, b, c, d, e, f) = match token {
+            Ok(Token::Matrix { a, b, c, d, e, f }) => (a, b, c, d, e, f),
+            Ok(Token::Translate { tx, ty }) => (1.0, 0.0, 0.0, 1.0, tx, ty),
+            Ok(Token::Scale { sx, sy }) => (sx, 0.0, 0.0, sy, 0.0, 0.0),
+            Ok(Token::Rotate { angle }) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                (cos, sin, -sin, cos, 0.0, 0.0)
+            }
+            Ok(Token::SkewX { angle }) => (1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0),
+            Ok(Token::SkewY { angle }) => (1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0),
+            Err(_) => { break; }
+        };
+        // The transforms of the list are applied from the last one to the first one.
+        transform = transform.pre_mul(&Transform2D::row_major(
+            a as f32, b as f32,
+            c as f32, d as f32,
+            e as f32, f as f32,
+        ));
+    }
+
+    transform
+}
+
+fn view_box_transform(vb: ViewBox, size: (Option<f32>, Option<f32>)) -> Transform2D {
+    let (x, y, w, h) = (vb.x as f32, vb.y as f32, vb.w as f32, vb.h as f32);
+    if w <= 0.0 || h <= 0.0 {
+        return Transform2D::identity();
+    }
+
+    let width = size.0.unwrap_or(w);
+    let height = size.1.unwrap_or(h);
+    let scale = f32::min(width / w, height / h);
+
+    Transform2D::row_major(
+        scale, 0.0,
+        0.0, scale,
+        (width - w * scale) * 0.5 - x * scale,
+        (height - h * scale) * 0.5 - y * scale,
+    )
+}
+
+#[test]
+fn load_lyon_logo() {
+    use path::PathEvent;
+
+    let shapes = load_file(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/lyon-logo.svg")).unwrap();
+    assert_eq!(shapes.len(), 3);
+
+    for shape in &shapes {
+        // The logo's shapes are only filled, with gradients.
+        let fill = shape.fill.unwrap();
+        assert_eq!(fill.paint, None);
+        assert!(shape.stroke.is_none());
+
+        // Each path is in a translated coordinate space.
+        assert_eq!(shape.transform.m11, 1.0);
+        assert_eq!(shape.transform.m22, 1.0);
+        assert!((shape.transform.m32 + 552.362).abs() < 0.001);
+    }
+
+    match shapes[0].path.iter().next() {
+        Some(PathEvent::MoveTo(p)) => {
+            assert!((p - point(396.08, 839.18)).length() < 0.001);
+        }
+        evt => panic!("unexpected first event {:?}", evt),
+    }
+}
+
+#[test]
+fn inherited_styles() {
+    use path::PathEvent;
+
+    let shapes = parse(r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="200" height="100" viewBox="0 0 100 50">
+            <defs><rect width="10" height="10"/></defs>
+            <g fill="red" stroke="#00f" stroke-width="2" opacity="0.5" transform="translate(10 0)">
+                <rect x="1" y="2" width="3" height="4" style="fill-opacity:0.5; stroke-linecap:round"/>
+                <circle cx="5" cy="5" r="2" fill="none" stroke="currentColor" color="lime"/>
+                <polygon points="0,0 1,0 1,1"/>
+            </g>
+            <line x1="0" y1="0" x2="1" y2="1"/>
+            <ellipse cx="0" cy="0" rx="1" ry="2" display="none"/>
+        </svg>
+    "##).unwrap();
+    assert_eq!(shapes.len(), 4);
+
+    let fill = shapes[0].fill.unwrap();
+    assert_eq!(fill.paint, Some(Color::new(255, 0, 0)));
+    assert_eq!(fill.opacity, 0.25);
+    let stroke = shapes[0].stroke.unwrap();
+    assert_eq!(stroke.paint, Some(Color::new(0, 0, 255)));
+    assert_eq!(stroke.opacity, 0.5);
+    assert_eq!(stroke.width, 2.0);
+    assert_eq!(stroke.line_cap, LineCap::Round);
+    assert_eq!(stroke.line_join, LineJoin::Miter);
+    // The group's translation is followed by the view box's scale.
+    let p = shapes[0].transform.transform_point(&point(1.0, 2.0));
+    assert!((p - point(22.0, 4.0)).length() < 0.0001);
+    assert_eq!(shapes[0].path.iter().next(), Some(PathEvent::MoveTo(point(1.0, 2.0))));
+
+    assert!(shapes[1].fill.is_none());
+    assert_eq!(shapes[1].stroke.unwrap().paint, Some(Color::new(0, 255, 0)));
+
+    assert_eq!(shapes[2].path.iter().count(), 4);
+    assert_eq!(shapes[2].fill.unwrap().paint, Some(Color::new(255, 0, 0)));
+
+    assert_eq!(shapes[3].fill.unwrap().paint, Some(Color::new(0, 0, 0)));
+    assert!(shapes[3].stroke.is_none());
+    let p = shapes[3].transform.transform_point(&point(1.0, 1.0));
+    assert!((p - point(2.0, 2.0)).length() < 0.0001);
+}
@@ -6,6 +6,9 @@
 //! At the moment this is mostly a wrapper around the [svgparser](https://crates.io/crates/svgparser)
 //! crate.
 //!
+//! With the `document` feature, whole SVG documents can be loaded into paths with their
+//! transforms and styles, see the `document` module.
+//!
 //! This crate is reexported in [lyon](https://docs.rs/lyon/).
 
 #![allow(dead_code)]
@@ -14,3 +17,5 @@ pub extern crate lyon_path as path;
 pub extern crate svgparser as parser;
 
 pub mod path_utils;
+#[cfg(feature = "document")]
+pub mod document;
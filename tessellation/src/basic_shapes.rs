@@ -31,7 +31,7 @@ use geom::math::*;
 use geom::Arc;
 use path::builder::FlatPathBuilder;
 use path::iterator::FromPolyline;
-use {FillOptions, FillVertex, StrokeVertex, StrokeOptions, Side};
+use {FillOptions, FillVertex, StrokeVertex, StrokeVertexKind, StrokeOptions, Side};
 
 use std::f32::consts::PI;
 
@@ -238,6 +238,7 @@ fn stroke_thin_rectangle(
             normal: vector(-1.0, -1.0),
             advancement: 0.0,
             side: Side::Left,
+            kind: StrokeVertexKind::Edge,
         }
     );
     let b = output.add_vertex(
@@ -246,6 +247,7 @@ fn stroke_thin_rectangle(
             normal: vector(-1.0, 1.0),
             advancement: 0.0,
             side: Side::Left,
+            kind: StrokeVertexKind::Edge,
         }
     );
     let c = output.add_vertex(
@@ -254,6 +256,7 @@ fn stroke_thin_rectangle(
             normal: vector(1.0, 1.0),
             advancement: 1.0,
             side: Side::Right,
+            kind: StrokeVertexKind::Edge,
         }
     );
     let d = output.add_vertex(
@@ -262,6 +265,7 @@ fn stroke_thin_rectangle(
             normal: vector(1.0, -1.0),
             advancement: 1.0,
             side: Side::Right,
+            kind: StrokeVertexKind::Edge,
         }
     );
 
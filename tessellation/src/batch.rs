@@ -0,0 +1,204 @@
+//! Tessellate many small paths into shared vertex and index buffers.
+//!
+//! Text and icon renderers draw a large number of small paths (glyphs for example), and
+//! usually want to store them in a single atlas buffer rather than allocating buffers for
+//! each of them. The functions of this module reuse the same tessellator and the same output
+//! for all of the paths, and return the range of vertices and indices that each path
+//! occupies in the shared buffers.
+//!
+//! The indices are absolute: they refer to positions in the shared vertex buffer, so a
+//! range of indices can be drawn without a base vertex. With `u16` indices the whole batch
+//! must fit in 65536 vertices; use `u32` indices for larger atlases.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{FillTessellator, FillOptions, FillVertex};
+//! use tess::batch::fill_paths;
+//! use tess::geometry_builder::VertexBuffers;
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut glyphs = Vec::new();
+//!     for i in 0..10 {
+//!         let x = i as f32 * 10.0;
+//!         let mut builder = Path::builder();
+//!         builder.move_to(point(x, 0.0));
+//!         builder.line_to(point(x + 8.0, 0.0));
+//!         builder.quadratic_bezier_to(point(x + 8.0, 8.0), point(x, 8.0));
+//!         builder.close();
+//!         glyphs.push(builder.build());
+//!     }
+//!
+//!     let mut atlas: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+//!     let ranges = fill_paths(
+//!         &mut FillTessellator::new(),
+//!         glyphs.iter().map(|glyph| glyph.as_slice()),
+//!         &FillOptions::default(),
+//!         &mut atlas,
+//!         |vertex: FillVertex| vertex,
+//!     ).unwrap();
+//!
+//!     // The geometry of the fourth glyph.
+//!     let range = ranges[3].indices.start as usize..ranges[3].indices.end as usize;
+//!     let indices = &atlas.indices[range];
+//!     assert!(!indices.is_empty());
+//! }
+//! ```
+
+use path::default::PathSlice;
+use geometry_builder::{VertexBuffers, BuffersBuilder, VertexConstructor, VertexId};
+use {FillTessellator, FillOptions, FillVertex, FillError};
+use {StrokeTessellator, StrokeOptions, StrokeVertex};
+
+use std::ops::{Add, Range};
+
+/// The vertices and indices of one path in buffers shared by several paths.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GeometryRange {
+    pub vertices: Range<u32>,
+    pub indices: Range<u32>,
+}
+
+impl GeometryRange {
+    /// Returns true if the path didn't produce any triangle.
+    pub fn is_empty(&self) -> bool {
+        self.indices.start == self.indices.end
+    }
+}
+
+/// Fills each path into the same vertex buffers, and returns their ranges in the order of
+/// the paths.
+///
+/// The geometry is appended to `output`, after anything it already contains. If the
+/// tessellation of a path fails, its geometry is removed from the buffers and the error is
+/// returned. The geometry of the preceding paths is kept.
+pub fn fill_paths<'l, Paths, VertexType, IndexType, Ctor>(
+    tessellator: &mut FillTessellator,
+    paths: Paths,
+    options: &FillOptions,
+    output: &mut VertexBuffers<VertexType, IndexType>,
+    vertex_constructor: Ctor,
+) -> Result<Vec<GeometryRange>, FillError>
+where
+    Paths: IntoIterator<Item = PathSlice<'l>>,
+    VertexType: Clone,
+    IndexType: Add + From<VertexId>,
+    Ctor: VertexConstructor<FillVertex, VertexType>,
+{
+    let paths = paths.into_iter();
+    let mut ranges = Vec::with_capacity(paths.size_hint().0);
+    let mut builder = BuffersBuilder::new(output, vertex_constructor);
+    for path in paths {
+        let (first_vertex, first_index) = buffer_lengths(builder.buffers());
+        let count = tessellator.tessellate_path(path.path_iter(), options, &mut builder)?;
+        ranges.push(GeometryRange {
+            vertices: first_vertex..(first_vertex + count.vertices),
+            indices: first_index..(first_index + count.indices),
+        });
+    }
+
+    Ok(ranges)
+}
+
+/// Strokes each path into the same vertex buffers, and returns their ranges in the order of
+/// the paths.
+///
+/// The geometry is appended to `output`, after anything it already contains.
+pub fn stroke_paths<'l, Paths, VertexType, IndexType, Ctor>(
+    tessellator: &mut StrokeTessellator,
+    paths: Paths,
+    options: &StrokeOptions,
+    output: &mut VertexBuffers<VertexType, IndexType>,
+    vertex_constructor: Ctor,
+) -> Vec<GeometryRange>
+where
+    Paths: IntoIterator<Item = PathSlice<'l>>,
+    VertexType: Clone,
+    IndexType: Add + From<VertexId>,
+    Ctor: VertexConstructor<StrokeVertex, VertexType>,
+{
+    let paths = paths.into_iter();
+    let mut ranges = Vec::with_capacity(paths.size_hint().0);
+    let mut builder = BuffersBuilder::new(output, vertex_constructor);
+    for path in paths {
+        let (first_vertex, first_index) = buffer_lengths(builder.buffers());
+        let count = tessellator.tessellate_path(path.path_iter(), options, &mut builder);
+        ranges.push(GeometryRange {
+            vertices: first_vertex..(first_vertex + count.vertices),
+            indices: first_index..(first_index + count.indices),
+        });
+    }
+
+    ranges
+}
+
+fn buffer_lengths<VertexType, IndexType>(buffers: &VertexBuffers<VertexType, IndexType>) -> (u32, u32) {
+    (buffers.vertices.len() as u32, buffers.indices.len() as u32)
+}
+
+#[test]
+fn batch_ranges() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::point;
+
+    let mut paths = Vec::new();
+    for i in 0..3 {
+        let x = i as f32 * 10.0;
+        let mut builder = Path::builder();
+        builder.move_to(point(x, 0.0));
+        builder.line_to(point(x + 5.0, 0.0));
+        builder.line_to(point(x + 5.0, 5.0));
+        if i != 1 {
+            builder.line_to(point(x, 5.0));
+        }
+        builder.close();
+        paths.push(builder.build());
+    }
+    // An empty path in the middle of the batch.
+    paths.insert(2, Path::builder().build());
+
+    let mut output: VertexBuffers<FillVertex, u32> = VertexBuffers::new();
+    let ranges = fill_paths(
+        &mut FillTessellator::new(),
+        paths.iter().map(|p| p.as_slice()),
+        &FillOptions::default(),
+        &mut output,
+        |v: FillVertex| v,
+    ).unwrap();
+
+    assert_eq!(ranges.len(), 4);
+    assert_eq!(ranges[0].indices.len(), 6);
+    assert_eq!(ranges[1].indices.len(), 3);
+    assert!(ranges[2].is_empty());
+    assert_eq!(ranges[3].indices.len(), 6);
+    assert_eq!(ranges[3].vertices.end as usize, output.vertices.len());
+    assert_eq!(ranges[3].indices.end as usize, output.indices.len());
+
+    for (i, range) in ranges.iter().enumerate() {
+        if i > 0 {
+            assert_eq!(range.vertices.start, ranges[i - 1].vertices.end);
+            assert_eq!(range.indices.start, ranges[i - 1].indices.end);
+        }
+        for idx in range.indices.start..range.indices.end {
+            let vertex = output.indices[idx as usize];
+            assert!(range.vertices.start <= vertex && vertex < range.vertices.end);
+        }
+    }
+
+    let mut stroked: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+    let ranges = stroke_paths(
+        &mut StrokeTessellator::new(),
+        paths.iter().map(|p| p.as_slice()),
+        &StrokeOptions::default(),
+        &mut stroked,
+        |v: StrokeVertex| v,
+    );
+    assert_eq!(ranges.len(), 4);
+    assert!(ranges[2].is_empty());
+    assert_eq!(ranges[3].indices.end as usize, stroked.indices.len());
+}
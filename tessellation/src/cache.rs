@@ -0,0 +1,327 @@
+//! Reuse the tessellation of paths that did not change.
+//!
+//! User interfaces typically render the same shapes every frame. The `TessellationCache`
+//! keeps the geometry of recently tessellated paths, identified by a hash of the path's
+//! content and of the tessellation options, so that only new or modified paths have to
+//! be tessellated.
+//!
+//! When the cache holds more entries than its capacity, the least recently used ones are
+//! evicted.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::cache::TessellationCache;
+//! use tess::{FillTessellator, FillOptions, FillVertex};
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.line_to(point(10.0, 0.0));
+//!     builder.line_to(point(10.0, 10.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     let mut tessellator = FillTessellator::new();
+//!     let mut cache: TessellationCache<FillVertex> = TessellationCache::new(256);
+//!
+//!     for _frame in 0..3 {
+//!         // Only tessellated during the first frame.
+//!         let geometry = cache.fill(&mut tessellator, path.as_slice(), &FillOptions::default()).unwrap();
+//!         assert_eq!(geometry.indices.len(), 3);
+//!     }
+//!
+//!     assert_eq!(cache.len(), 1);
+//! }
+//! ```
+
+use path::default::PathSlice;
+use geometry_builder::{VertexBuffers, simple_builder};
+use {FillTessellator, FillOptions, FillVertex, FillError, FillRule, OnError};
+use {StrokeTessellator, StrokeOptions, StrokeVertex, LineCap, LineJoin, MiterLimitMode, StrokeAlignment};
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// Identifies the tessellation of a path with a given set of options.
+///
+/// The key is a 64 bits hash: two different inputs are extremely unlikely but not
+/// guaranteed to get different keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey(pub u64);
+
+impl CacheKey {
+    /// The key of the fill tessellation of a path.
+    pub fn fill(path: PathSlice, options: &FillOptions) -> Self {
+        let mut hasher = path_hasher(path, 0);
+        hash_f32(&mut hasher, options.tolerance);
+        hasher.write_u8(match options.fill_rule {
+            FillRule::EvenOdd => 0,
+            FillRule::NonZero => 1,
+        });
+        hasher.write_u8(options.compute_normals as u8);
+        hasher.write_u8(options.assume_no_intersections as u8);
+        hash_on_error(&mut hasher, options.on_error);
+        hasher.write_u8(options.deterministic as u8);
+
+        CacheKey(hasher.finish())
+    }
+
+    /// The key of the stroke tessellation of a path.
+    pub fn stroke(path: PathSlice, options: &StrokeOptions) -> Self {
+        let mut hasher = path_hasher(path, 1);
+        hash_line_cap(&mut hasher, options.start_cap);
+        hash_line_cap(&mut hasher, options.end_cap);
+        hasher.write_u8(match options.line_join {
+            LineJoin::Miter => 0,
+            LineJoin::MiterClip => 1,
+            LineJoin::Round => 2,
+            LineJoin::Bevel => 3,
+        });
+        hash_f32(&mut hasher, options.line_width);
+        hash_f32(&mut hasher, options.miter_limit);
+        hasher.write_u8(match options.miter_limit_mode {
+            MiterLimitMode::Svg => 0,
+            MiterLimitMode::HalfWidth => 1,
+        });
+        hash_f32(&mut hasher, options.tolerance);
+        hasher.write_u8(options.apply_line_width as u8);
+        match options.alignment {
+            StrokeAlignment::Center => hasher.write_u8(0),
+            StrokeAlignment::Inner => hasher.write_u8(1),
+            StrokeAlignment::Outer => hasher.write_u8(2),
+            StrokeAlignment::Offset(offset) => {
+                hasher.write_u8(3);
+                hash_f32(&mut hasher, offset);
+            }
+        }
+        hasher.write_u32(options.min_round_segments);
+        hasher.write_u32(options.max_round_segments);
+        hasher.write_u8(options.deterministic as u8);
+
+        CacheKey(hasher.finish())
+    }
+}
+
+struct Entry<VertexType> {
+    geometry: VertexBuffers<VertexType, u16>,
+    last_used: u64,
+}
+
+/// Stores the geometry of recently tessellated paths.
+///
+/// The fill and stroke tessellators produce different vertex types, so fills are cached
+/// in a `TessellationCache<FillVertex>` and strokes in a `TessellationCache<StrokeVertex>`.
+pub struct TessellationCache<VertexType> {
+    entries: HashMap<CacheKey, Entry<VertexType>>,
+    capacity: usize,
+    time: u64,
+}
+
+impl<VertexType> TessellationCache<VertexType> {
+    /// Creates a cache holding at most `capacity` geometries.
+    ///
+    /// A capacity of zero is treated as one.
+    pub fn new(capacity: usize) -> Self {
+        TessellationCache {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            time: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Number of cached geometries.
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Changes the capacity, evicting the least recently used geometries if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict();
+    }
+
+    pub fn contains(&self, key: CacheKey) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    /// Returns the cached geometry for a key and marks it as recently used.
+    pub fn get(&mut self, key: CacheKey) -> Option<&VertexBuffers<VertexType, u16>> {
+        self.time += 1;
+        let time = self.time;
+        match self.entries.get_mut(&key) {
+            Some(entry) => {
+                entry.last_used = time;
+                Some(&entry.geometry)
+            }
+            None => None,
+        }
+    }
+
+    /// Adds or replaces the geometry for a key.
+    pub fn insert(&mut self, key: CacheKey, geometry: VertexBuffers<VertexType, u16>) {
+        self.time += 1;
+        self.entries.insert(key, Entry { geometry, last_used: self.time });
+        self.evict();
+    }
+
+    /// Removes the geometry for a key, returning it if it was cached.
+    pub fn invalidate(&mut self, key: CacheKey) -> Option<VertexBuffers<VertexType, u16>> {
+        self.entries.remove(&key).map(|entry| entry.geometry)
+    }
+
+    /// Removes all geometries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let oldest = self.entries.iter()
+                .min_by_key(|&(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+                .unwrap();
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn get_or_try_insert_with<F, E>(&mut self, key: CacheKey, tessellate: F) -> Result<&VertexBuffers<VertexType, u16>, E>
+    where
+        F: FnOnce(&mut VertexBuffers<VertexType, u16>) -> Result<(), E>,
+    {
+        if !self.contains(key) {
+            let mut geometry = VertexBuffers::new();
+            tessellate(&mut geometry)?;
+            self.insert(key, geometry);
+        }
+
+        Ok(self.get(key).unwrap())
+    }
+}
+
+impl TessellationCache<FillVertex> {
+    /// Returns the fill tessellation of a path, tessellating it if it is not in the cache.
+    ///
+    /// Geometries are not cached if the tessellation fails.
+    pub fn fill(
+        &mut self,
+        tessellator: &mut FillTessellator,
+        path: PathSlice,
+        options: &FillOptions,
+    ) -> Result<&VertexBuffers<FillVertex, u16>, FillError> {
+        self.get_or_try_insert_with(CacheKey::fill(path, options), |geometry| {
+            tessellator.tessellate_path(
+                path.path_iter(),
+                options,
+                &mut simple_builder(geometry),
+            ).map(|_| ())
+        })
+    }
+}
+
+impl TessellationCache<StrokeVertex> {
+    /// Returns the stroke tessellation of a path, tessellating it if it is not in the cache.
+    pub fn stroke(
+        &mut self,
+        tessellator: &mut StrokeTessellator,
+        path: PathSlice,
+        options: &StrokeOptions,
+    ) -> &VertexBuffers<StrokeVertex, u16> {
+        let result: Result<_, ()> = self.get_or_try_insert_with(CacheKey::stroke(path, options), |geometry| {
+            tessellator.tessellate_path(path.path_iter(), options, &mut simple_builder(geometry));
+            Ok(())
+        });
+
+        result.unwrap()
+    }
+}
+
+fn path_hasher(path: PathSlice, kind: u8) -> DefaultHasher {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u8(kind);
+    hasher.write_usize(path.verbs().len());
+    for verb in path.verbs() {
+        hasher.write_u8(*verb as u8);
+    }
+    for p in path.points() {
+        hash_f32(&mut hasher, p.x);
+        hash_f32(&mut hasher, p.y);
+    }
+
+    hasher
+}
+
+fn hash_f32(hasher: &mut DefaultHasher, value: f32) {
+    hasher.write_u32(value.to_bits());
+}
+
+fn hash_line_cap(hasher: &mut DefaultHasher, cap: LineCap) {
+    hasher.write_u8(match cap {
+        LineCap::Butt => 0,
+        LineCap::Square => 1,
+        LineCap::Round => 2,
+    });
+}
+
+fn hash_on_error(hasher: &mut DefaultHasher, on_error: OnError) {
+    hasher.write_u8(match on_error {
+        OnError::Panic => 0,
+        OnError::Stop => 1,
+        OnError::Recover => 2,
+    });
+}
+
+#[test]
+fn cache_eviction() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::point;
+
+    let mut paths = Vec::new();
+    for i in 0..3 {
+        let mut builder = Path::builder();
+        builder.move_to(point(0.0, 0.0));
+        builder.line_to(point(10.0 + i as f32, 0.0));
+        builder.line_to(point(10.0, 10.0));
+        builder.close();
+        paths.push(builder.build());
+    }
+
+    let mut tessellator = StrokeTessellator::new();
+    let mut cache = TessellationCache::new(2);
+    let options = StrokeOptions::default();
+
+    let n = cache.stroke(&mut tessellator, paths[0].as_slice(), &options).indices.len();
+    assert!(n > 0);
+    cache.stroke(&mut tessellator, paths[1].as_slice(), &options);
+    assert_eq!(cache.len(), 2);
+
+    // Different options give a different key.
+    let wide = options.with_line_width(2.0);
+    assert!(CacheKey::stroke(paths[0].as_slice(), &options) != CacheKey::stroke(paths[0].as_slice(), &wide));
+    assert!(CacheKey::stroke(paths[0].as_slice(), &options) != CacheKey::stroke(paths[1].as_slice(), &options));
+    assert!(CacheKey::stroke(paths[0].as_slice(), &options) != CacheKey::stroke(paths[0].as_slice(), &options.deterministic()));
+
+    // Use the first path so that the second one is the least recently used.
+    assert!(cache.get(CacheKey::stroke(paths[0].as_slice(), &options)).is_some());
+    cache.stroke(&mut tessellator, paths[2].as_slice(), &options);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(CacheKey::stroke(paths[0].as_slice(), &options)));
+    assert!(!cache.contains(CacheKey::stroke(paths[1].as_slice(), &options)));
+
+    let removed = cache.invalidate(CacheKey::stroke(paths[0].as_slice(), &options));
+    assert_eq!(removed.map(|geometry| geometry.indices.len()), Some(n));
+    assert_eq!(cache.len(), 1);
+
+    cache.set_capacity(0);
+    assert_eq!(cache.capacity(), 1);
+    cache.clear();
+    assert!(cache.is_empty());
+}
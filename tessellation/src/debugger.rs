@@ -12,6 +12,7 @@ pub const BLACK: Color = Color { r: 0, g: 0, b: 0, a: 255 };
 pub const WHITE: Color = Color { r: 0, g: 0, b: 0, a: 255 };
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -20,6 +21,7 @@ pub struct Color {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum DebuggerMsg {
     Point { position: Point, color: Color, flags: u32 },
     Edge { from: Point, to: Point, color: Color, flags: u32 },
@@ -58,7 +60,8 @@ impl Debugger2D for EmptyDebugger2D {
     fn error(&self, _flags: u32) {}
 }
 
-
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Trace {
     pub messages: Vec<DebuggerMsg>,
 }
@@ -0,0 +1,680 @@
+//! A fill tessellator producing constrained Delaunay triangulations.
+
+use FillVertex as Vertex;
+use {FillOptions, FillResult, FillTessellator};
+use geom::math::*;
+use geometry_builder::{GeometryBuilder, VertexBuffers, BuffersBuilder, Identity, VertexId};
+use path::iterator::PathIterator;
+
+use std::collections::{HashMap, HashSet};
+
+/// Parameters for the `DelaunayFillTessellator`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct DelaunayOptions {
+    /// If set, Steiner points are inserted until no triangle has a larger area.
+    ///
+    /// Default value: `None`.
+    pub max_area: Option<f32>,
+
+    /// If set, interior edges that are longer are split at their middle.
+    ///
+    /// The boundary is not split, so the triangles along a boundary edge longer than this
+    /// can't have only short edges: interior edges are only split if they are also longer
+    /// than the boundary edges of their triangles.
+    ///
+    /// Default value: `None`.
+    pub max_edge_length: Option<f32>,
+
+    /// Maximum number of Steiner points that can be inserted.
+    ///
+    /// Default value: `DelaunayOptions::DEFAULT_MAX_STEINER_POINTS`.
+    pub max_steiner_points: u32,
+}
+
+impl DelaunayOptions {
+    pub const DEFAULT_MAX_STEINER_POINTS: u32 = 10_000;
+
+    pub const DEFAULT: Self = DelaunayOptions {
+        max_area: None,
+        max_edge_length: None,
+        max_steiner_points: Self::DEFAULT_MAX_STEINER_POINTS,
+    };
+
+    #[inline]
+    pub fn max_area(max_area: f32) -> Self {
+        Self::DEFAULT.with_max_area(max_area)
+    }
+
+    #[inline]
+    pub fn with_max_area(mut self, max_area: f32) -> Self {
+        self.max_area = Some(max_area);
+        self
+    }
+
+    #[inline]
+    pub fn max_edge_length(max_edge_length: f32) -> Self {
+        Self::DEFAULT.with_max_edge_length(max_edge_length)
+    }
+
+    #[inline]
+    pub fn with_max_edge_length(mut self, max_edge_length: f32) -> Self {
+        self.max_edge_length = Some(max_edge_length);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_steiner_points(mut self, max_steiner_points: u32) -> Self {
+        self.max_steiner_points = max_steiner_points;
+        self
+    }
+}
+
+impl Default for DelaunayOptions {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+/// A fill tessellator that produces constrained Delaunay triangulations.
+///
+/// The path is first tessellated with the `FillTessellator`, then the edges that
+/// are inside of the filled shape are flipped until the triangulation is Delaunay,
+/// which maximizes the minimum angle of the triangles. The boundary of the shape is
+/// preserved.
+///
+/// Optionally, Steiner points are inserted at the centroid of the triangles that
+/// are larger than a maximum area. This is slower than the `FillTessellator` but
+/// avoids sliver triangles, which is useful when the resulting mesh is deformed
+/// (for example for cloth or soft body simulations).
+///
+/// Interior edges can also be split until they are shorter than a maximum length.
+///
+/// Steiner points have a nil normal.
+pub struct DelaunayFillTessellator {
+    fill: FillTessellator,
+    buffers: VertexBuffers<Vertex, u32>,
+}
+
+impl DelaunayFillTessellator {
+    pub fn new() -> Self {
+        DelaunayFillTessellator {
+            fill: FillTessellator::new(),
+            buffers: VertexBuffers::new(),
+        }
+    }
+
+    /// Compute the tessellation from a path iterator.
+    pub fn tessellate_path<Iter>(
+        &mut self,
+        it: Iter,
+        fill_options: &FillOptions,
+        options: &DelaunayOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> FillResult
+    where
+        Iter: PathIterator,
+    {
+        self.buffers.vertices.clear();
+        self.buffers.indices.clear();
+
+        self.fill.tessellate_path(
+            it,
+            fill_options,
+            &mut BuffersBuilder::new(&mut self.buffers, Identity),
+        )?;
+
+        let mut mesh = Mesh::new(&self.buffers);
+        mesh.make_delaunay();
+        mesh.refine(options);
+
+        output.begin_geometry();
+        for vertex in &mesh.vertices {
+            output.add_vertex(*vertex);
+        }
+        for triangle in &mesh.triangles {
+            output.add_triangle(VertexId(triangle[0]), VertexId(triangle[1]), VertexId(triangle[2]));
+        }
+
+        Ok(output.end_geometry())
+    }
+}
+
+impl Default for DelaunayFillTessellator {
+    fn default() -> Self { Self::new() }
+}
+
+/// Improves the quality of the triangles of a fill tessellation in place.
+///
+/// Interior edges are flipped to make the triangulation Delaunay and Steiner points
+/// are inserted according to the options. The boundary of the mesh is preserved.
+///
+/// Vertices at the same position are merged and degenerate triangles are removed.
+pub fn refine_mesh<Index>(buffers: &mut VertexBuffers<Vertex, Index>, options: &DelaunayOptions)
+where
+    Index: Copy + Into<VertexId> + From<VertexId>,
+{
+    let mut mesh = Mesh::new(buffers);
+    mesh.make_delaunay();
+    mesh.refine(options);
+
+    buffers.vertices = mesh.vertices;
+    buffers.indices.clear();
+    for triangle in &mesh.triangles {
+        for &v in triangle {
+            buffers.indices.push(Index::from(VertexId(v)));
+        }
+    }
+}
+
+// A triangle mesh with counter-clockwise triangles and the adjacency information
+// needed to flip edges.
+struct Mesh {
+    vertices: Vec<Vertex>,
+    triangles: Vec<[u32; 3]>,
+    // Directed edge -> triangle containing it in counter-clockwise order.
+    edges: HashMap<(u32, u32), u32>,
+    // Edges shared by more than two triangles are never flipped.
+    locked: HashSet<(u32, u32)>,
+}
+
+impl Mesh {
+    fn new<Index>(buffers: &VertexBuffers<Vertex, Index>) -> Self
+    where
+        Index: Copy + Into<VertexId>,
+    {
+        // The fill tessellator can produce several vertices at the same position.
+        let mut vertices = Vec::with_capacity(buffers.vertices.len());
+        let mut remap = Vec::with_capacity(buffers.vertices.len());
+        let mut positions = HashMap::new();
+        for vertex in &buffers.vertices {
+            let key = (vertex.position.x.to_bits(), vertex.position.y.to_bits());
+            let id = *positions.entry(key).or_insert_with(|| {
+                vertices.push(*vertex);
+                vertices.len() as u32 - 1
+            });
+            remap.push(id);
+        }
+
+        let mut mesh = Mesh {
+            vertices,
+            triangles: Vec::with_capacity(buffers.indices.len() / 3),
+            edges: HashMap::new(),
+            locked: HashSet::new(),
+        };
+
+        for triangle in buffers.indices.chunks(3) {
+            if triangle.len() < 3 {
+                break;
+            }
+            let vertex = |i: Index| -> u32 {
+                let id: VertexId = i.into();
+                remap[id.offset() as usize]
+            };
+            let a = vertex(triangle[0]);
+            let b = vertex(triangle[1]);
+            let c = vertex(triangle[2]);
+            if a == b || b == c || c == a {
+                continue;
+            }
+            let t = if mesh.orient(a, b, c) < 0.0 { [a, c, b] } else { [a, b, c] };
+            mesh.triangles.push(t);
+        }
+
+        for t in 0..mesh.triangles.len() {
+            let tri = mesh.triangles[t];
+            let (a, b, c) = (tri[0], tri[1], tri[2]);
+            for &edge in &[(a, b), (b, c), (c, a)] {
+                if mesh.edges.insert(edge, t as u32).is_some() {
+                    mesh.locked.insert(edge);
+                    mesh.locked.insert((edge.1, edge.0));
+                }
+            }
+        }
+
+        mesh
+    }
+
+    fn position(&self, v: u32) -> Point {
+        self.vertices[v as usize].position
+    }
+
+    fn orient(&self, a: u32, b: u32, c: u32) -> f64 {
+        let a = self.position(a);
+        let b = self.position(b);
+        let c = self.position(c);
+        (b.x as f64 - a.x as f64) * (c.y as f64 - a.y as f64)
+            - (b.y as f64 - a.y as f64) * (c.x as f64 - a.x as f64)
+    }
+
+    fn area(&self, t: usize) -> f32 {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[0], tri[1], tri[2]);
+        (self.orient(a, b, c) * 0.5) as f32
+    }
+
+    // Whether d is strictly inside of the circumcircle of the counter-clockwise triangle abc.
+    fn in_circle(&self, a: u32, b: u32, c: u32, d: u32) -> bool {
+        let d_pos = self.position(d);
+        let (dx, dy) = (d_pos.x as f64, d_pos.y as f64);
+        let rel = |v: u32| {
+            let p = self.position(v);
+            let x = p.x as f64 - dx;
+            let y = p.y as f64 - dy;
+            (x, y, x * x + y * y)
+        };
+        let (ax, ay, a2) = rel(a);
+        let (bx, by, b2) = rel(b);
+        let (cx, cy, c2) = rel(c);
+
+        let det = ax * (by * c2 - b2 * cy)
+            - ay * (bx * c2 - b2 * cx)
+            + a2 * (bx * cy - by * cx);
+
+        // Ignore the cases that are within the precision of the computation to
+        // avoid flipping back and forth.
+        let scale = (a2 + b2 + c2) * (a2 + b2 + c2);
+        det > scale * 1e-12
+    }
+
+    fn third_vertex(&self, t: u32, a: u32, b: u32) -> u32 {
+        let tri = self.triangles[t as usize];
+        for &v in &tri {
+            if v != a && v != b {
+                return v;
+            }
+        }
+        unreachable!();
+    }
+
+    fn make_delaunay(&mut self) {
+        let stack: Vec<(u32, u32)> = self.edges.keys().cloned().filter(|&(a, b)| a < b).collect();
+        self.legalize(stack);
+    }
+
+    // Lawson's flip algorithm.
+    fn legalize(&mut self, mut stack: Vec<(u32, u32)>) {
+        // Guard against infinite loops caused by precision issues.
+        let mut max_flips = (self.triangles.len() + 16) * 64;
+        while let Some((a, b)) = stack.pop() {
+            if max_flips == 0 {
+                return;
+            }
+
+            if self.locked.contains(&(a, b)) {
+                continue;
+            }
+
+            let (t1, t2) = match (self.edges.get(&(a, b)), self.edges.get(&(b, a))) {
+                (Some(&t1), Some(&t2)) => (t1, t2),
+                // Boundary edge.
+                _ => { continue; }
+            };
+
+            // t1 = (a, b, c), t2 = (b, a, d).
+            let c = self.third_vertex(t1, a, b);
+            let d = self.third_vertex(t2, a, b);
+
+            if !self.in_circle(a, b, c, d) {
+                continue;
+            }
+
+            // The quadrilateral a, d, b, c must be convex.
+            if self.orient(c, a, d) <= 0.0 || self.orient(d, b, c) <= 0.0 {
+                continue;
+            }
+
+            self.triangles[t1 as usize] = [c, a, d];
+            self.triangles[t2 as usize] = [d, b, c];
+            self.edges.remove(&(a, b));
+            self.edges.remove(&(b, a));
+            self.edges.insert((a, d), t1);
+            self.edges.insert((d, c), t1);
+            self.edges.insert((b, c), t2);
+            self.edges.insert((c, d), t2);
+
+            stack.push((a, d));
+            stack.push((d, b));
+            stack.push((b, c));
+            stack.push((c, a));
+
+            max_flips -= 1;
+        }
+    }
+
+    fn refine(&mut self, options: &DelaunayOptions) {
+        let mut budget = options.max_steiner_points;
+        if let Some(max_length) = options.max_edge_length {
+            self.split_long_edges(max_length, &mut budget);
+        }
+        if let Some(max_area) = options.max_area {
+            self.split_large_triangles(max_area, &mut budget);
+        }
+    }
+
+    fn edge_length(&self, a: u32, b: u32) -> f32 {
+        (self.position(b) - self.position(a)).length()
+    }
+
+    // The length above which an interior edge is split: the maximum length, or the longest
+    // boundary edge of the triangles on each side of it if it is longer.
+    fn length_limit(&self, a: u32, b: u32, max_length: f32) -> f32 {
+        let mut limit = max_length;
+        for &t in &[self.edges[&(a, b)], self.edges[&(b, a)]] {
+            let tri = self.triangles[t as usize];
+            for i in 0..3 {
+                let (u, v) = (tri[i], tri[(i + 1) % 3]);
+                if !self.edges.contains_key(&(v, u)) || self.locked.contains(&(u, v)) {
+                    limit = limit.max(self.edge_length(u, v));
+                }
+            }
+        }
+
+        limit
+    }
+
+    fn split_long_edges(&mut self, max_length: f32, budget: &mut u32) {
+        loop {
+            let mut long_edges: Vec<(u32, u32)> = self.edges.keys().cloned().filter(|&(a, b)| {
+                a < b
+                    && self.edges.contains_key(&(b, a))
+                    && !self.locked.contains(&(a, b))
+                    && self.edge_length(a, b) > self.length_limit(a, b, max_length)
+            }).collect();
+
+            if long_edges.is_empty() {
+                return;
+            }
+
+            // Split the longest edges first.
+            long_edges.sort_by(|&(a1, b1), &(a2, b2)| {
+                self.edge_length(a1, b1).partial_cmp(&self.edge_length(a2, b2)).unwrap().reverse()
+            });
+
+            let mut inserted = false;
+            for (a, b) in long_edges {
+                if *budget == 0 {
+                    return;
+                }
+                // Previous splits may have flipped this edge away or changed its triangles.
+                if !self.edges.contains_key(&(a, b)) || !self.edges.contains_key(&(b, a))
+                    || self.edge_length(a, b) <= self.length_limit(a, b, max_length) {
+                    continue;
+                }
+
+                self.split_edge(a, b);
+                *budget -= 1;
+                inserted = true;
+            }
+
+            if !inserted {
+                return;
+            }
+        }
+    }
+
+    fn split_edge(&mut self, a: u32, b: u32) {
+        let t1 = self.edges[&(a, b)];
+        let t2 = self.edges[&(b, a)];
+        let c = self.third_vertex(t1, a, b);
+        let d = self.third_vertex(t2, a, b);
+
+        let m = self.vertices.len() as u32;
+        let position = self.position(a).lerp(self.position(b), 0.5);
+        self.vertices.push(Vertex {
+            position,
+            normal: vector(0.0, 0.0),
+        });
+
+        // t1 = (a, b, c) -> (a, m, c), (m, b, c)
+        // t2 = (b, a, d) -> (b, m, d), (m, a, d)
+        let n1 = self.triangles.len() as u32;
+        let n2 = n1 + 1;
+        self.triangles[t1 as usize] = [a, m, c];
+        self.triangles[t2 as usize] = [b, m, d];
+        self.triangles.push([m, b, c]);
+        self.triangles.push([m, a, d]);
+
+        self.edges.remove(&(a, b));
+        self.edges.remove(&(b, a));
+        self.edges.insert((a, m), t1);
+        self.edges.insert((m, c), t1);
+        self.edges.insert((b, m), t2);
+        self.edges.insert((m, d), t2);
+        self.edges.insert((m, b), n1);
+        self.edges.insert((b, c), n1);
+        self.edges.insert((c, m), n1);
+        self.edges.insert((m, a), n2);
+        self.edges.insert((a, d), n2);
+        self.edges.insert((d, m), n2);
+
+        self.legalize(vec![(b, c), (c, a), (a, d), (d, b)]);
+    }
+
+    // Inserts Steiner points at the centroid of the triangles that are too large.
+    fn split_large_triangles(&mut self, max_area: f32, budget: &mut u32) {
+        loop {
+            let mut inserted = false;
+            for t in 0..self.triangles.len() {
+                if *budget == 0 {
+                    return;
+                }
+                if self.area(t) <= max_area {
+                    continue;
+                }
+
+                self.split_triangle(t);
+                *budget -= 1;
+                inserted = true;
+            }
+
+            if !inserted {
+                return;
+            }
+        }
+    }
+
+    fn split_triangle(&mut self, t: usize) {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[0], tri[1], tri[2]);
+        let position = (
+            self.position(a).to_vector()
+            + self.position(b).to_vector()
+            + self.position(c).to_vector()
+        ) / 3.0;
+
+        let p = self.vertices.len() as u32;
+        self.vertices.push(Vertex {
+            position: position.to_point(),
+            normal: vector(0.0, 0.0),
+        });
+
+        let t1 = self.triangles.len() as u32;
+        let t2 = t1 + 1;
+        self.triangles[t] = [a, b, p];
+        self.triangles.push([b, c, p]);
+        self.triangles.push([c, a, p]);
+
+        let t = t as u32;
+        self.edges.insert((b, p), t);
+        self.edges.insert((p, a), t);
+        self.edges.insert((b, c), t1);
+        self.edges.insert((c, p), t1);
+        self.edges.insert((p, b), t1);
+        self.edges.insert((c, a), t2);
+        self.edges.insert((a, p), t2);
+        self.edges.insert((p, c), t2);
+
+        self.legalize(vec![(a, b), (b, c), (c, a)]);
+    }
+}
+
+#[cfg(test)]
+fn tessellate(path: &::path::default::Path, options: &DelaunayOptions) -> VertexBuffers<Vertex, u16> {
+    use geometry_builder::simple_builder;
+
+    let mut buffers = VertexBuffers::new();
+    DelaunayFillTessellator::new().tessellate_path(
+        path.path_iter(),
+        &FillOptions::tolerance(0.01),
+        options,
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    buffers
+}
+
+#[cfg(test)]
+fn triangle_area(buffers: &VertexBuffers<Vertex, u16>, i: usize) -> f32 {
+    let a = buffers.vertices[buffers.indices[i] as usize].position;
+    let b = buffers.vertices[buffers.indices[i + 1] as usize].position;
+    let c = buffers.vertices[buffers.indices[i + 2] as usize].position;
+    ((b - a).cross(c - a) * 0.5).abs()
+}
+
+#[test]
+fn delaunay_circle() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+
+    // A polygon with all of its vertices on a circle: any triangulation of it is
+    // Delaunay, but slivers show up with the monotone tessellator as soon as the
+    // circle is squashed.
+    let mut builder = Path::builder();
+    for i in 0..32 {
+        let angle = i as f32 * ::std::f32::consts::PI * 2.0 / 32.0;
+        let p = point(angle.cos() * 100.0, angle.sin() * 10.0);
+        if i == 0 { builder.move_to(p); } else { builder.line_to(p); }
+    }
+    builder.close();
+    let path = builder.build();
+
+    let buffers = tessellate(&path, &DelaunayOptions::default());
+
+    assert_eq!(buffers.indices.len(), 30 * 3);
+
+    // Check the empty circumcircle property for each pair of adjacent triangles.
+    let mut mesh = Mesh::new(&buffers);
+    let edges: Vec<(u32, u32)> = mesh.edges.keys().cloned().collect();
+    for (a, b) in edges {
+        if let (Some(&t1), Some(&t2)) = (mesh.edges.get(&(a, b)), mesh.edges.get(&(b, a))) {
+            let c = mesh.third_vertex(t1, a, b);
+            let d = mesh.third_vertex(t2, a, b);
+            assert!(!mesh.in_circle(a, b, c, d));
+        }
+    }
+
+    let mut total = 0.0;
+    for i in 0..buffers.indices.len() / 3 {
+        total += triangle_area(&buffers, i * 3);
+    }
+    mesh.make_delaunay();
+    let mut expected = 0.0;
+    for t in 0..mesh.triangles.len() {
+        expected += mesh.area(t);
+    }
+    assert!((total - expected).abs() < 0.01);
+}
+
+#[test]
+fn delaunay_max_area() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let buffers = tessellate(&path, &DelaunayOptions::max_area(2.0));
+
+    assert!(buffers.vertices.len() > 4);
+    let mut total = 0.0;
+    for i in 0..buffers.indices.len() / 3 {
+        let area = triangle_area(&buffers, i * 3);
+        assert!(area <= 2.0);
+        total += area;
+    }
+    assert!((total - 100.0).abs() < 0.01);
+
+    // The number of Steiner points can be limited.
+    let buffers = tessellate(&path, &DelaunayOptions::max_area(2.0).with_max_steiner_points(3));
+    assert_eq!(buffers.vertices.len(), 7);
+}
+
+#[test]
+fn refine_long_edges() {
+    use path::default::Path;
+    use path::builder::FlatPathBuilder;
+
+    fn refine(path: &Path, max_length: f32) -> Mesh {
+        let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        FillTessellator::new().tessellate_path(
+            path.path_iter(),
+            &FillOptions::default(),
+            &mut ::geometry_builder::simple_builder(&mut buffers),
+        ).unwrap();
+
+        refine_mesh(&mut buffers, &DelaunayOptions::max_edge_length(max_length));
+
+        Mesh::new(&buffers)
+    }
+
+    fn total_area(mesh: &Mesh) -> f32 {
+        let mut total = 0.0;
+        for t in 0..mesh.triangles.len() {
+            total += mesh.area(t);
+        }
+        total
+    }
+
+    // A polygon with short sides.
+    let mut builder = Path::builder();
+    for i in 0..32 {
+        let angle = i as f32 * ::std::f32::consts::PI * 2.0 / 32.0;
+        let p = point(angle.cos() * 10.0, angle.sin() * 10.0);
+        if i == 0 { builder.move_to(p); } else { builder.line_to(p); }
+    }
+    builder.close();
+    let mesh = refine(&builder.build(), 5.0);
+
+    let side = 20.0 * (::std::f32::consts::PI / 32.0).sin();
+    let area = 16.0 * 100.0 * (::std::f32::consts::PI / 16.0).sin();
+    assert!((total_area(&mesh) - area).abs() < 0.01);
+    let mut num_boundary_edges = 0;
+    for &(a, b) in mesh.edges.keys() {
+        if mesh.edges.contains_key(&(b, a)) {
+            assert!(mesh.edge_length(a, b) <= 5.0);
+        } else {
+            // The boundary is preserved.
+            assert!((mesh.edge_length(a, b) - side).abs() < 0.001);
+            num_boundary_edges += 1;
+        }
+    }
+    assert_eq!(num_boundary_edges, 32);
+    assert!(mesh.vertices.len() > 32);
+
+    // The interior edges next to the 10 units long sides can't all be shorter than 5, but
+    // they are shorter than the sides.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let mesh = refine(&builder.build(), 5.0);
+
+    assert!((total_area(&mesh) - 100.0).abs() < 0.01);
+    for &(a, b) in mesh.edges.keys() {
+        if mesh.edges.contains_key(&(b, a)) {
+            assert!(mesh.edge_length(a, b) <= 10.0);
+        } else {
+            let pa = mesh.position(a);
+            let pb = mesh.position(b);
+            assert!(pa.x == pb.x || pa.y == pb.y);
+            assert!(mesh.edge_length(a, b) == 10.0);
+        }
+    }
+}
@@ -4,7 +4,7 @@ use path::builder::{FlatPathBuilder, PathBuilder};
 use path::default::{Path, PathSlice};
 use extra::rust_logo::build_logo_path;
 
-use {FillTessellator, FillError, FillOptions, FillVertex};
+use {FillTessellator, FillError, FillOptions, FillVertex, OnError};
 
 type Vertex = FillVertex;
 
@@ -1010,3 +1010,211 @@ fn test_no_close() {
 fn test_empty_path() {
     test_path_and_count_triangles(Path::new().as_slice(), 0);
 }
+
+#[test]
+fn test_nan_and_inf() {
+    use path::PathEvent;
+    use path::iterator::PathIter;
+
+    // The path builder rejects these values, so the events are fed directly to the
+    // tessellator.
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::LineTo(point(f32::NAN, 1.0)),
+        PathEvent::QuadraticTo(point(1.0, f32::INFINITY), point(1.0, 1.0)),
+        PathEvent::LineTo(point(0.0, 1.0)),
+        PathEvent::Close,
+    ];
+
+    let mut tess = FillTessellator::new();
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+
+    let result = tess.tessellate_path(
+        PathIter::new(events.iter().cloned()),
+        &FillOptions::default().on_error(OnError::Stop),
+        &mut simple_builder(&mut buffers),
+    );
+    match result {
+        Err(FillError::InvalidInput) => {}
+        _ => { panic!("expected an InvalidInput error, got {:?}", result); }
+    }
+
+    // In recover mode, the invalid events are ignored, which leaves a single triangle.
+    tess.tessellate_path(
+        PathIter::new(events.iter().cloned()),
+        &FillOptions::default().on_error(OnError::Recover),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    assert_eq!(buffers.indices.len(), 3);
+    for vertex in &buffers.vertices {
+        assert!(vertex.position.x.is_finite() && vertex.position.y.is_finite());
+    }
+}
+
+#[test]
+fn test_estimate_geometry() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.quadratic_bezier_to(point(8.0, 8.0), point(2.0, 8.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = FillOptions::tolerance(0.05);
+    let estimate = FillTessellator::estimate_geometry(path.path_iter(), &options);
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let count = FillTessellator::new().tessellate_path(
+        path.path_iter(),
+        &options,
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    assert!(count.vertices <= estimate.vertices);
+    assert!(count.indices <= estimate.indices);
+}
+
+#[cfg(feature = "profiling")]
+#[test]
+fn test_stats() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut tess = FillTessellator::new();
+    tess.tessellate_path(
+        path.path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    let stats = tess.stats();
+    assert_eq!(stats.num_events, 4);
+    assert_eq!(stats.num_edges, 4);
+    assert_eq!(stats.num_intersections, 0);
+    assert_eq!(stats.max_active_edges, 2);
+    assert_eq!(stats.num_monotone_spans, 1);
+    assert_eq!(stats.num_triangles, 2);
+}
+
+#[test]
+fn test_polygon() {
+    use path::polygon::Polygon;
+    use path::builder::PolygonBuilder;
+
+    let points = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(5.0, 2.0),
+        point(0.0, 10.0),
+    ];
+
+    let mut builder = Path::builder();
+    builder.polygon(&points);
+    let path = builder.build();
+
+    let options = FillOptions::default();
+    let mut tess = FillTessellator::new();
+
+    let mut from_path: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        path.path_iter(),
+        &options,
+        &mut simple_builder(&mut from_path),
+    ).unwrap();
+
+    let mut from_polygon: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_polygon(
+        Polygon::new(&points),
+        &options,
+        &mut simple_builder(&mut from_polygon),
+    ).unwrap();
+
+    assert_eq!(from_path.vertices.len(), from_polygon.vertices.len());
+    assert_eq!(from_path.indices, from_polygon.indices);
+}
+
+#[test]
+fn test_cancellation() {
+    use CancellationToken;
+
+    let mut path = Path::builder().flattened(0.011).with_svg();
+    build_logo_path(&mut path);
+    let path = path.build();
+
+    let token = CancellationToken::new();
+    let mut tess = FillTessellator::new();
+    tess.set_cancellation_token(Some(token.clone()));
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        path.path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    assert!(!buffers.indices.is_empty());
+
+    token.cancel();
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let result = tess.tessellate_path(
+        path.path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(FillError::Cancelled));
+
+    // The tessellator can be used again once the token is removed.
+    tess.set_cancellation_token(None);
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        path.path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    assert!(!buffers.indices.is_empty());
+}
+
+#[test]
+fn test_limits() {
+    let mut path = Path::builder().flattened(0.011).with_svg();
+    build_logo_path(&mut path);
+    let path = path.build();
+
+    let mut tess = FillTessellator::new();
+    let options = FillOptions::default();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers)).unwrap();
+    let num_vertices = buffers.vertices.len() as u32;
+
+    tess.set_max_vertices(Some(num_vertices / 2));
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let result = tess.tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers));
+    assert_eq!(result, Err(FillError::TooManyVertices));
+
+    tess.set_max_vertices(Some(num_vertices));
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers)).unwrap();
+
+    tess.set_max_vertices(None);
+    tess.set_memory_budget(Some(256));
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let result = tess.tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers));
+    assert_eq!(result, Err(FillError::OutOfMemoryBudget));
+
+    tess.set_memory_budget(Some(64 * 1024 * 1024));
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers)).unwrap();
+}
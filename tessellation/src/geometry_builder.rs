@@ -220,6 +220,9 @@
 //! ```
 
 
+use math::{Point, Rect};
+use optimize::BufferIndex;
+
 use std::marker::PhantomData;
 use std::ops::Add;
 use std::convert::From;
@@ -335,6 +338,99 @@ impl<VertexType, IndexType> VertexBuffers<VertexType, IndexType> {
             indices: Vec::with_capacity(num_indices),
         }
     }
+
+    /// Reserves capacity for at least `num_vertices` more vertices and `num_indices`
+    /// more indices.
+    pub fn reserve(&mut self, num_vertices: usize, num_indices: usize) {
+        self.vertices.reserve(num_vertices);
+        self.indices.reserve(num_indices);
+    }
+
+    /// Removes all vertices and indices, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+    }
+}
+
+/// Statistics about the geometry, mostly useful for debugging and level of detail decisions.
+///
+/// `position` returns the position of a vertex. Trailing indices that don't form a complete
+/// triangle are ignored.
+impl<VertexType, IndexType: BufferIndex> VertexBuffers<VertexType, IndexType> {
+    /// Number of complete triangles in the index buffer.
+    pub fn num_triangles(&self) -> usize { self.indices.len() / 3 }
+
+    /// Sum of the areas of the triangles.
+    ///
+    /// Overlapping triangles are counted several times.
+    pub fn area<Position>(&self, position: Position) -> f32
+    where
+        Position: Fn(&VertexType) -> Point,
+    {
+        let mut area = 0.0;
+        self.for_each_triangle_area(&position, |triangle_area| { area += triangle_area; });
+
+        area
+    }
+
+    /// Counts the triangles by area.
+    ///
+    /// `limits` must be sorted in increasing order. The returned vector has one more element
+    /// than `limits`: the element at index `i` is the number of triangles with an area lower
+    /// than `limits[i]` and greater than or equal to the previous limit, the last one counts
+    /// the triangles that are at least as large as the last limit.
+    pub fn area_histogram<Position>(&self, limits: &[f32], position: Position) -> Vec<u32>
+    where
+        Position: Fn(&VertexType) -> Point,
+    {
+        let mut histogram = vec![0; limits.len() + 1];
+        self.for_each_triangle_area(&position, |area| {
+            let bucket = limits.iter().position(|&limit| area < limit).unwrap_or(limits.len());
+            histogram[bucket] += 1;
+        });
+
+        histogram
+    }
+
+    /// The smallest rectangle containing the vertices referenced by the index buffer.
+    ///
+    /// Returns an empty rectangle at the origin if there is no triangle.
+    pub fn bounding_rect<Position>(&self, position: Position) -> Rect
+    where
+        Position: Fn(&VertexType) -> Point,
+    {
+        let num_indices = self.num_triangles() * 3;
+        if num_indices == 0 {
+            return Rect::zero();
+        }
+
+        let first = position(&self.vertices[self.indices[0].to_usize()]);
+        let (mut min, mut max) = (first, first);
+        for idx in &self.indices[1..num_indices] {
+            let p = position(&self.vertices[idx.to_usize()]);
+            min = min.min(p);
+            max = max.max(p);
+        }
+
+        Rect::new(min, (max - min).to_size())
+    }
+
+    fn for_each_triangle_area<Position, F>(&self, position: &Position, mut cb: F)
+    where
+        Position: Fn(&VertexType) -> Point,
+        F: FnMut(f32),
+    {
+        for triangle in self.indices.chunks(3) {
+            if triangle.len() < 3 {
+                break;
+            }
+            let a = position(&self.vertices[triangle[0].to_usize()]);
+            let b = position(&self.vertices[triangle[1].to_usize()]);
+            let c = position(&self.vertices[triangle[2].to_usize()]);
+            cb((b - a).cross(c - a).abs() * 0.5);
+        }
+    }
 }
 
 /// A temporary view on a `VertexBuffers` object which facilitate the population of vertex and index
@@ -502,6 +598,42 @@ where
     }
 }
 
+/// A `GeometryBuilder` adapter that forwards each vertex along with an identifier.
+///
+/// This makes it possible to tessellate several paths into the same output while keeping
+/// track of which path each vertex comes from: change the identifier with `set_id` between
+/// tessellations.
+pub struct WithId<'l, Input: 'l> {
+    output: &'l mut dyn GeometryBuilder<(Input, u32)>,
+    id: u32,
+}
+
+impl<'l, Input> WithId<'l, Input> {
+    pub fn new(id: u32, output: &'l mut dyn GeometryBuilder<(Input, u32)>) -> Self {
+        WithId { output, id }
+    }
+
+    pub fn set_id(&mut self, id: u32) { self.id = id; }
+
+    pub fn id(&self) -> u32 { self.id }
+}
+
+impl<'l, Input> GeometryBuilder<Input> for WithId<'l, Input> {
+    fn begin_geometry(&mut self) { self.output.begin_geometry(); }
+
+    fn end_geometry(&mut self) -> Count { self.output.end_geometry() }
+
+    fn add_vertex(&mut self, vertex: Input) -> VertexId {
+        self.output.add_vertex((vertex, self.id))
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) { self.output.abort_geometry(); }
+}
+
 /// A geometry builder that does not output any geometry.
 ///
 /// Mostly useful for testing.
@@ -707,3 +839,51 @@ fn test_closure() {
         point(1.0, 1.0),
     ]);
 }
+
+#[test]
+fn test_with_id() {
+    let mut buffers: VertexBuffers<([f32; 2], u32), u16> = VertexBuffers::new();
+    {
+        let mut output = simple_builder(&mut buffers);
+        let mut output = WithId::new(3, &mut output);
+        for &id in &[3, 7] {
+            output.set_id(id);
+            output.begin_geometry();
+            let a = output.add_vertex([0.0, 0.0]);
+            let b = output.add_vertex([1.0, 0.0]);
+            let c = output.add_vertex([1.0, 1.0]);
+            output.add_triangle(a, b, c);
+            output.end_geometry();
+        }
+    }
+
+    let ids: Vec<u32> = buffers.vertices.iter().map(|v| v.1).collect();
+    assert_eq!(ids, vec![3, 3, 3, 7, 7, 7]);
+    assert_eq!(buffers.indices, vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_buffer_statistics() {
+    use math::{point, rect};
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices.extend_from_slice(&[
+        point(0.0, 0.0),
+        point(2.0, 0.0),
+        point(2.0, 2.0),
+        point(0.0, 2.0),
+        point(0.1, 0.0),
+        // Not referenced by the indices.
+        point(100.0, 100.0),
+    ]);
+    buffers.indices.extend_from_slice(&[0, 1, 2, 0, 2, 3, 0, 4, 3, 1]);
+
+    assert_eq!(buffers.num_triangles(), 3);
+    assert_eq!(buffers.area(|p| *p), 2.0 + 2.0 + 0.1);
+    assert_eq!(buffers.area_histogram(&[0.5, 1.0], |p| *p), vec![1, 0, 2]);
+    assert_eq!(buffers.bounding_rect(|p| *p), rect(0.0, 0.0, 2.0, 2.0));
+
+    let empty: VertexBuffers<Point, u16> = VertexBuffers::new();
+    assert_eq!(empty.area(|p| *p), 0.0);
+    assert_eq!(empty.bounding_rect(|p| *p), Rect::zero());
+}
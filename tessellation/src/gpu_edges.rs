@@ -0,0 +1,285 @@
+//! Flattened path edges in a buffer layout for compute shaders.
+//!
+//! This is meant for hybrid pipelines where lyon prepares the paths (flattening, closing
+//! sub-paths, computing bounds) and a compute shader tessellates or rasterizes them.
+//!
+//! ## Layout
+//!
+//! The data is split into two buffers, both of which are tightly packed arrays of
+//! little-endian 32 bits values with a 16 bytes alignment, compatible with the `std430`
+//! and WGSL storage buffer layout rules:
+//!
+//! ```text
+//! // 32 bytes per path.
+//! struct Path {
+//!     first_edge: u32,
+//!     edge_count: u32,
+//!     fill_rule: u32, // 0: even-odd, 1: non-zero.
+//!     reserved: u32,
+//!     min: vec2<f32>,
+//!     max: vec2<f32>,
+//! };
+//!
+//! // 16 bytes per edge.
+//! struct Edge {
+//!     from: vec2<f32>,
+//!     to: vec2<f32>,
+//! };
+//! ```
+//!
+//! This layout is stable: fields may only be added in the reserved space.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::gpu_edges::EdgeBuffer;
+//! use tess::FillRule;
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.line_to(point(10.0, 0.0));
+//!     builder.line_to(point(10.0, 10.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     let mut edges = EdgeBuffer::new();
+//!     edges.add_path(path.path_iter(), 0.1, FillRule::NonZero);
+//!
+//!     let mut path_data = Vec::new();
+//!     let mut edge_data = Vec::new();
+//!     edges.write_paths(&mut path_data);
+//!     edges.write_edges(&mut edge_data);
+//!
+//!     assert_eq!(path_data.len(), 32);
+//!     assert_eq!(edge_data.len(), 3 * 16);
+//! }
+//! ```
+
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use math::{Point, point};
+use FillRule;
+
+/// The value of `GpuPath::fill_rule` for `FillRule::EvenOdd`.
+pub const FILL_RULE_EVEN_ODD: u32 = 0;
+/// The value of `GpuPath::fill_rule` for `FillRule::NonZero`.
+pub const FILL_RULE_NON_ZERO: u32 = 1;
+
+/// The description of a path in the edge buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuPath {
+    /// Index of the first edge of the path.
+    pub first_edge: u32,
+    pub edge_count: u32,
+    /// Either `FILL_RULE_EVEN_ODD` or `FILL_RULE_NON_ZERO`.
+    pub fill_rule: u32,
+    /// Always zero.
+    pub reserved: u32,
+    /// The bounding rectangle of the edges.
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+/// An edge in the edge buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpuEdge {
+    pub from: [f32; 2],
+    pub to: [f32; 2],
+}
+
+/// Accumulates the flattened edges of paths in a layout that compute shaders can consume.
+///
+/// Sub-paths are implicitly closed and edges of zero length are skipped. See the
+/// [module documentation](index.html) for the layout of the data.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeBuffer {
+    paths: Vec<GpuPath>,
+    edges: Vec<GpuEdge>,
+}
+
+impl EdgeBuffer {
+    pub fn new() -> Self {
+        EdgeBuffer {
+            paths: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Flattens a path and adds its edges to the buffer.
+    ///
+    /// Returns the index of the path.
+    pub fn add_path<Iter: PathIterator>(&mut self, path: Iter, tolerance: f32, fill_rule: FillRule) -> u32 {
+        self.add_flattened(path.flattened(tolerance), fill_rule)
+    }
+
+    /// Adds the edges of an already flattened path to the buffer.
+    ///
+    /// Returns the index of the path.
+    pub fn add_flattened<Iter>(&mut self, path: Iter, fill_rule: FillRule) -> u32
+    where
+        Iter: Iterator<Item = FlattenedEvent>,
+    {
+        let first_edge = self.edges.len() as u32;
+        let mut min = point(f32::MAX, f32::MAX);
+        let mut max = point(f32::MIN, f32::MIN);
+        let mut first = point(0.0, 0.0);
+        let mut current = point(0.0, 0.0);
+
+        for event in path {
+            match event {
+                FlattenedEvent::MoveTo(to) => {
+                    self.add_edge(current, first, &mut min, &mut max);
+                    first = to;
+                    current = to;
+                }
+                FlattenedEvent::LineTo(to) => {
+                    self.add_edge(current, to, &mut min, &mut max);
+                    current = to;
+                }
+                FlattenedEvent::Close => {
+                    self.add_edge(current, first, &mut min, &mut max);
+                    current = first;
+                }
+            }
+        }
+        self.add_edge(current, first, &mut min, &mut max);
+
+        let edge_count = self.edges.len() as u32 - first_edge;
+        if edge_count == 0 {
+            min = point(0.0, 0.0);
+            max = point(0.0, 0.0);
+        }
+
+        self.paths.push(GpuPath {
+            first_edge,
+            edge_count,
+            fill_rule: match fill_rule {
+                FillRule::EvenOdd => FILL_RULE_EVEN_ODD,
+                FillRule::NonZero => FILL_RULE_NON_ZERO,
+            },
+            reserved: 0,
+            min: [min.x, min.y],
+            max: [max.x, max.y],
+        });
+
+        self.paths.len() as u32 - 1
+    }
+
+    fn add_edge(&mut self, from: Point, to: Point, min: &mut Point, max: &mut Point) {
+        if from == to {
+            return;
+        }
+
+        for p in &[from, to] {
+            *min = point(min.x.min(p.x), min.y.min(p.y));
+            *max = point(max.x.max(p.x), max.y.max(p.y));
+        }
+
+        self.edges.push(GpuEdge {
+            from: [from.x, from.y],
+            to: [to.x, to.y],
+        });
+    }
+
+    pub fn paths(&self) -> &[GpuPath] {
+        &self.paths
+    }
+
+    pub fn edges(&self) -> &[GpuEdge] {
+        &self.edges
+    }
+
+    /// Removes all paths and edges, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.edges.clear();
+    }
+
+    /// Appends the content of the path buffer to a byte vector.
+    pub fn write_paths(&self, output: &mut Vec<u8>) {
+        output.reserve(self.paths.len() * 32);
+        for path in &self.paths {
+            write_u32(output, path.first_edge);
+            write_u32(output, path.edge_count);
+            write_u32(output, path.fill_rule);
+            write_u32(output, path.reserved);
+            for &v in path.min.iter().chain(path.max.iter()) {
+                write_u32(output, v.to_bits());
+            }
+        }
+    }
+
+    /// Appends the content of the edge buffer to a byte vector.
+    pub fn write_edges(&self, output: &mut Vec<u8>) {
+        output.reserve(self.edges.len() * 16);
+        for edge in &self.edges {
+            for &v in edge.from.iter().chain(edge.to.iter()) {
+                write_u32(output, v.to_bits());
+            }
+        }
+    }
+}
+
+fn write_u32(output: &mut Vec<u8>, value: u32) {
+    output.push(value as u8);
+    output.push((value >> 8) as u8);
+    output.push((value >> 16) as u8);
+    output.push((value >> 24) as u8);
+}
+
+#[test]
+fn edge_buffer_layout() {
+    use path::default::Path;
+    use path::builder::*;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    // Implicitly closed.
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.close();
+    let path1 = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(-1.0, 0.0));
+    builder.quadratic_bezier_to(point(0.0, 1.0), point(1.0, 0.0));
+    builder.close();
+    let path2 = builder.build();
+
+    let mut edges = EdgeBuffer::new();
+    assert_eq!(edges.add_path(path1.path_iter(), 0.1, FillRule::EvenOdd), 0);
+    assert_eq!(edges.add_path(path2.path_iter(), 0.01, FillRule::NonZero), 1);
+
+    let paths = edges.paths();
+    assert_eq!(paths[0].first_edge, 0);
+    assert_eq!(paths[0].edge_count, 5);
+    assert_eq!(paths[0].fill_rule, FILL_RULE_EVEN_ODD);
+    assert_eq!(paths[0].min, [0.0, 0.0]);
+    assert_eq!(paths[0].max, [30.0, 10.0]);
+    assert_eq!(paths[1].first_edge, 5);
+    assert!(paths[1].edge_count > 2);
+    assert_eq!(paths[1].fill_rule, FILL_RULE_NON_ZERO);
+    assert_eq!(edges.edges()[2], GpuEdge { from: [10.0, 10.0], to: [0.0, 0.0] });
+
+    let mut data = Vec::new();
+    edges.write_paths(&mut data);
+    assert_eq!(data.len(), 64);
+    assert_eq!(&data[32..40], &[5, 0, 0, 0, paths[1].edge_count as u8, 0, 0, 0]);
+    assert_eq!(&data[40..44], &[1, 0, 0, 0]);
+
+    let mut data = Vec::new();
+    edges.write_edges(&mut data);
+    assert_eq!(data.len(), edges.edges().len() * 16);
+    // 10.0f32 is 0x41200000.
+    assert_eq!(&data[8..12], &[0, 0, 0x20, 0x41]);
+}
@@ -0,0 +1,316 @@
+//! Helpers to compute per-vertex gradient parameters, following SVG gradient semantics.
+//!
+//! The gradient offset of a linear gradient varies linearly over the geometry so it can be
+//! computed per vertex and interpolated. This is not the case for radial gradients: their
+//! offset is only exact at the vertices and should be computed in the fragment shader
+//! from the interpolated `gradient_position` when the geometry is coarse.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{FillVertex, VertexBuffers, BuffersBuilder, FillOptions};
+//! use tess::gradients::{LinearGradient, WithGradient};
+//! use tess::basic_shapes::fill_rectangle;
+//! use tess::math::{point, rect};
+//!
+//! #[derive(Copy, Clone, Debug)]
+//! struct MyVertex { position: [f32; 2], gradient_offset: f32 }
+//!
+//! fn main() {
+//!     let gradient = LinearGradient::new(point(0.0, 0.0), point(100.0, 0.0));
+//!
+//!     let mut buffers: VertexBuffers<MyVertex, u16> = VertexBuffers::new();
+//!     fill_rectangle(
+//!         &rect(0.0, 0.0, 100.0, 50.0),
+//!         &FillOptions::default(),
+//!         &mut BuffersBuilder::new(
+//!             &mut buffers,
+//!             WithGradient::new(gradient, |vertex: FillVertex, offset: f32| MyVertex {
+//!                 position: vertex.position.to_array(),
+//!                 gradient_offset: offset,
+//!             }),
+//!         ),
+//!     );
+//!
+//!     for vertex in &buffers.vertices {
+//!         assert!((vertex.gradient_offset - vertex.position[0] / 100.0).abs() < 0.0001);
+//!     }
+//! }
+//! ```
+
+use geometry_builder::VertexConstructor;
+use uv::VertexPosition;
+use math::*;
+
+/// How the gradient behaves outside of the [0, 1] offset range.
+///
+/// Corresponds to SVG's `spreadMethod` attribute.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum SpreadMethod {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+impl SpreadMethod {
+    /// Maps a gradient offset to the [0, 1] range.
+    ///
+    /// This is not linear so it must be applied after interpolation (in the fragment shader).
+    pub fn apply(&self, offset: f32) -> f32 {
+        match *self {
+            SpreadMethod::Pad => offset.clamp(0.0, 1.0),
+            SpreadMethod::Repeat => offset - offset.floor(),
+            SpreadMethod::Reflect => {
+                let t = offset.abs() % 2.0;
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+        }
+    }
+}
+
+/// Computes gradient parameters from positions.
+pub trait Gradient {
+    /// The position in the gradient's coordinate system (after applying the inverse
+    /// of the gradient transform and of the bounding box units).
+    fn gradient_position(&self, position: Point) -> Point;
+
+    /// The gradient offset at a given position, before applying the spread method.
+    fn offset(&self, position: Point) -> f32;
+}
+
+// Computes the transform from user space to the gradient's coordinate system.
+//
+// In SVG the gradient transform is applied in the bounding box space if the units
+// are "objectBoundingBox".
+fn inverse_gradient_transform(transform: &Transform2D, bounding_box: &Option<Rect>) -> Transform2D {
+    let mut to_user_space = *transform;
+    if let Some(ref rect) = *bounding_box {
+        to_user_space = to_user_space.post_mul(&Transform2D::row_major(
+            rect.size.width, 0.0,
+            0.0, rect.size.height,
+            rect.origin.x, rect.origin.y,
+        ));
+    }
+
+    // A degenerate transform collapses the gradient to its first stop.
+    to_user_space.inverse().unwrap_or(Transform2D::row_major(0.0, 0.0, 0.0, 0.0, 0.0, 0.0))
+}
+
+/// A linear gradient, equivalent to SVG's `linearGradient` element.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct LinearGradient {
+    from: Point,
+    to: Point,
+    transform: Transform2D,
+    bounding_box: Option<Rect>,
+    inv_transform: Transform2D,
+}
+
+impl LinearGradient {
+    /// Creates a gradient going from `from` (offset 0.0) to `to` (offset 1.0).
+    pub fn new(from: Point, to: Point) -> Self {
+        LinearGradient {
+            from,
+            to,
+            transform: Transform2D::identity(),
+            bounding_box: None,
+            inv_transform: Transform2D::identity(),
+        }
+    }
+
+    /// Sets the gradient transform (SVG's `gradientTransform` attribute).
+    pub fn with_transform(mut self, transform: &Transform2D) -> Self {
+        self.transform = *transform;
+        self.inv_transform = inverse_gradient_transform(&self.transform, &self.bounding_box);
+        self
+    }
+
+    /// Expresses the gradient in the coordinate system of a bounding box, equivalent to
+    /// `gradientUnits="objectBoundingBox"` in SVG.
+    pub fn with_bounding_box(mut self, rect: &Rect) -> Self {
+        self.bounding_box = Some(*rect);
+        self.inv_transform = inverse_gradient_transform(&self.transform, &self.bounding_box);
+        self
+    }
+}
+
+impl Gradient for LinearGradient {
+    fn gradient_position(&self, position: Point) -> Point {
+        self.inv_transform.transform_point(&position)
+    }
+
+    fn offset(&self, position: Point) -> f32 {
+        let v = self.to - self.from;
+        let sq_len = v.square_length();
+        if sq_len == 0.0 {
+            return 0.0;
+        }
+
+        (self.gradient_position(position) - self.from).dot(v) / sq_len
+    }
+}
+
+/// A radial gradient, equivalent to SVG's `radialGradient` element.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct RadialGradient {
+    center: Point,
+    radius: f32,
+    focal: Point,
+    transform: Transform2D,
+    bounding_box: Option<Rect>,
+    inv_transform: Transform2D,
+}
+
+impl RadialGradient {
+    /// Creates a gradient going from the center (offset 0.0) to the circle (offset 1.0).
+    pub fn new(center: Point, radius: f32) -> Self {
+        RadialGradient {
+            center,
+            radius,
+            focal: center,
+            transform: Transform2D::identity(),
+            bounding_box: None,
+            inv_transform: Transform2D::identity(),
+        }
+    }
+
+    /// Sets the focal point (SVG's `fx` and `fy` attributes) where the offset is 0.0.
+    ///
+    /// As specified in SVG 1.1, a focal point outside of the circle is moved on the circle.
+    pub fn with_focal_point(mut self, focal: Point) -> Self {
+        let v = focal - self.center;
+        let max_distance = self.radius * 0.999;
+        self.focal = if v.length() > max_distance {
+            self.center + v.normalize() * max_distance
+        } else {
+            focal
+        };
+        self
+    }
+
+    /// Sets the gradient transform (SVG's `gradientTransform` attribute).
+    pub fn with_transform(mut self, transform: &Transform2D) -> Self {
+        self.transform = *transform;
+        self.inv_transform = inverse_gradient_transform(&self.transform, &self.bounding_box);
+        self
+    }
+
+    /// Expresses the gradient in the coordinate system of a bounding box, equivalent to
+    /// `gradientUnits="objectBoundingBox"` in SVG.
+    pub fn with_bounding_box(mut self, rect: &Rect) -> Self {
+        self.bounding_box = Some(*rect);
+        self.inv_transform = inverse_gradient_transform(&self.transform, &self.bounding_box);
+        self
+    }
+}
+
+impl Gradient for RadialGradient {
+    fn gradient_position(&self, position: Point) -> Point {
+        self.inv_transform.transform_point(&position)
+    }
+
+    fn offset(&self, position: Point) -> f32 {
+        // Find the smallest t such that the position is on the circle of center
+        // lerp(focal, center, t) and radius t * radius.
+        let d = self.gradient_position(position) - self.focal;
+        let e = self.center - self.focal;
+        let a = e.square_length() - self.radius * self.radius;
+        let b = d.dot(e);
+        let dd = d.square_length();
+        if dd == 0.0 {
+            return 0.0;
+        }
+
+        // The focal point is inside of the circle so a is negative and the
+        // discriminant positive.
+        let discriminant = (b * b - a * dd).max(0.0);
+        let denominator = b + discriminant.sqrt();
+        if denominator <= 0.0 {
+            return 0.0;
+        }
+
+        dd / denominator
+    }
+}
+
+/// A `VertexConstructor` that computes the gradient offset of each vertex and
+/// forwards it along with the vertex to a function creating the output vertex.
+pub struct WithGradient<G, F> {
+    gradient: G,
+    ctor: F,
+}
+
+impl<G, F> WithGradient<G, F> {
+    pub fn new(gradient: G, ctor: F) -> Self {
+        WithGradient { gradient, ctor }
+    }
+}
+
+impl<Input, VertexType, G, F> VertexConstructor<Input, VertexType> for WithGradient<G, F>
+where
+    Input: VertexPosition,
+    G: Gradient,
+    F: Fn(Input, f32) -> VertexType,
+{
+    fn new_vertex(&mut self, input: Input) -> VertexType {
+        let offset = self.gradient.offset(input.position());
+        (self.ctor)(input, offset)
+    }
+}
+
+#[cfg(test)]
+fn assert_approx_eq(a: f32, b: f32) {
+    if (a - b).abs() > 0.0001 {
+        panic!("{} != {}", a, b);
+    }
+}
+
+#[test]
+fn linear_gradient() {
+    let gradient = LinearGradient::new(point(0.0, 0.0), point(10.0, 0.0));
+    assert_approx_eq(gradient.offset(point(0.0, 3.0)), 0.0);
+    assert_approx_eq(gradient.offset(point(5.0, 3.0)), 0.5);
+    assert_approx_eq(gradient.offset(point(20.0, -3.0)), 2.0);
+
+    // Rotate the gradient by 90 degrees.
+    let rotated = gradient.with_transform(&Transform2D::row_major(0.0, 1.0, -1.0, 0.0, 0.0, 0.0));
+    assert_approx_eq(rotated.offset(point(0.0, 5.0)), 0.5);
+    assert_approx_eq(rotated.offset(point(5.0, 0.0)), 0.0);
+
+    let bbox = LinearGradient::new(point(0.0, 0.0), point(1.0, 0.0))
+        .with_bounding_box(&rect(10.0, 10.0, 20.0, 20.0));
+    assert_approx_eq(bbox.offset(point(10.0, 15.0)), 0.0);
+    assert_approx_eq(bbox.offset(point(20.0, 15.0)), 0.5);
+    assert_approx_eq(bbox.offset(point(30.0, 15.0)), 1.0);
+
+    // The gradient transform is applied in the bounding box space.
+    let bbox_rotated = LinearGradient::new(point(0.0, 0.0), point(1.0, 0.0))
+        .with_bounding_box(&rect(10.0, 10.0, 20.0, 20.0))
+        .with_transform(&Transform2D::row_major(0.0, 1.0, -1.0, 0.0, 0.0, 0.0));
+    assert_approx_eq(bbox_rotated.offset(point(10.0, 20.0)), 0.5);
+}
+
+#[test]
+fn radial_gradient() {
+    let gradient = RadialGradient::new(point(0.0, 0.0), 10.0);
+    assert_approx_eq(gradient.offset(point(0.0, 0.0)), 0.0);
+    assert_approx_eq(gradient.offset(point(5.0, 0.0)), 0.5);
+    assert_approx_eq(gradient.offset(point(0.0, -10.0)), 1.0);
+    assert_approx_eq(gradient.offset(point(20.0, 0.0)), 2.0);
+
+    let focal = gradient.with_focal_point(point(-5.0, 0.0));
+    assert_approx_eq(focal.offset(point(-5.0, 0.0)), 0.0);
+    assert_approx_eq(focal.offset(point(10.0, 0.0)), 1.0);
+    assert_approx_eq(focal.offset(point(-10.0, 0.0)), 1.0);
+    assert_approx_eq(focal.offset(point(2.5, 0.0)), 0.5);
+    assert_approx_eq(focal.offset(point(-7.5, 0.0)), 0.5);
+
+    assert_approx_eq(SpreadMethod::Pad.apply(1.5), 1.0);
+    assert_approx_eq(SpreadMethod::Repeat.apply(1.25), 0.25);
+    assert_approx_eq(SpreadMethod::Reflect.apply(1.25), 0.75);
+    assert_approx_eq(SpreadMethod::Reflect.apply(-0.25), 0.25);
+}
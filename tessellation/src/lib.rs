@@ -167,6 +167,34 @@
 //!
 //! More explanation about flattening and tolerance in the [lyon_geom crate](https://docs.rs/lyon_geom/#flattening).
 //!
+//! The tolerance is expressed in the coordinate space of the path. If the geometry is rendered
+//! with a transformation, the error on screen is the tolerance multiplied by the scale of the
+//! transformation. `FillOptions::tolerance_for_scale` and `StrokeOptions::tolerance_for_scale`
+//! compute the tolerance to use so that the error on screen stays below a given value.
+//!
+//! ### Deterministic output
+//!
+//! The output of the tessellators can vary slightly between platforms because curves, arcs and
+//! round joins rely on functions like `sin`, `cos` or `powf` which are implemented differently
+//! by each platform's math library. Setting `deterministic` in `FillOptions` or `StrokeOptions`
+//! replaces them with implementations that only use basic arithmetic operations, and flattens
+//! curves with integer arithmetic (see `lyon_path::fixed`), so that the output is bit-exact on
+//! every platform. This is slower and the intermediate points of flattened curves are rounded
+//! to 1/64th of a unit.
+//!
+//! ### Bounding the tessellation cost
+//!
+//! The cost of the fill tessellator grows with the number of intersections in the input, so
+//! a malicious or very large path can take a long time to tessellate. A `CancellationToken`
+//! installed with `FillTessellator::set_cancellation_token` can be cancelled from another
+//! thread or given a deadline, after which the tessellation is aborted and returns
+//! `FillError::Cancelled`.
+//!
+//! Similarly, `FillTessellator::set_max_vertices` and `FillTessellator::set_memory_budget`
+//! make the tessellation fail with `FillError::TooManyVertices` or
+//! `FillError::OutOfMemoryBudget` instead of growing the output and the tessellator's
+//! internal buffers without bounds.
+//!
 //! ## Examples
 //!
 //! - [Tessellating path fills](path_fill/struct.FillTessellator.html#examples).
@@ -192,8 +220,20 @@ extern crate sid;
 pub mod basic_shapes;
 pub mod geometry_builder;
 pub mod debugger;
+pub mod uv;
+pub mod gradients;
+pub mod stencil_cover;
+pub mod loop_blinn;
+pub mod stroke_instances;
+pub mod gpu_edges;
+pub mod cache;
+pub mod optimize;
+pub mod lod;
+pub mod ribbon;
+pub mod batch;
 mod path_fill;
 mod path_stroke;
+mod delaunay_fill;
 mod math_utils;
 mod fixed;
 
@@ -209,15 +249,30 @@ pub use path::math;
 
 pub use path::geom;
 
+use math_utils::deterministic_sin_cos;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::time::{Duration, Instant};
+
+#[doc(inline)]
+pub use path::FillRule;
+
 #[doc(inline)]
 pub use path_fill::*;
 
 #[doc(inline)]
 pub use path_stroke::*;
 
+#[doc(inline)]
+pub use delaunay_fill::*;
+
 #[doc(inline)]
 pub use geometry_builder::{GeometryBuilder, GeometryReceiver, VertexBuffers, BuffersBuilder, VertexConstructor, Count};
 
+#[doc(inline)]
+pub use optimize::optimize_buffers;
+
 /// Left or right.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -237,6 +292,36 @@ impl Side {
     pub fn is_left(self) -> bool { self == Side::Left }
 
     pub fn is_right(self) -> bool { self == Side::Right }
+
+    /// Returns 1.0 for the left side and -1.0 for the right side.
+    pub fn sign(self) -> f32 {
+        match self {
+            Side::Left => 1.0,
+            Side::Right => -1.0,
+        }
+    }
+}
+
+/// The part of a stroke that a vertex belongs to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum StrokeVertexKind {
+    /// A vertex along an edge of the path, in the middle of a flattened curve for example.
+    Edge,
+    /// A vertex of a line join.
+    Join,
+    /// A vertex of the start cap of a sub-path.
+    StartCap,
+    /// A vertex of the end cap of a sub-path.
+    EndCap,
+}
+
+impl StrokeVertexKind {
+    pub fn is_cap(self) -> bool {
+        self == StrokeVertexKind::StartCap || self == StrokeVertexKind::EndCap
+    }
+
+    pub fn is_join(self) -> bool { self == StrokeVertexKind::Join }
 }
 
 /// Vertex produced by the stroke tessellators.
@@ -250,9 +335,14 @@ pub struct StrokeVertex {
     /// produce a stroke of width 2.0 (1.0 on each side). This vector is not normalized.
     pub normal: math::Vector,
     /// How far along the path this vertex is.
+    ///
+    /// This is the length of the path up to the vertex, which is useful to implement
+    /// dashing or textured strokes in shaders.
     pub advancement: f32,
     /// Whether the vertex is on the left or right side of the path.
     pub side: Side,
+    /// Whether the vertex belongs to an edge, a join or a cap.
+    pub kind: StrokeVertexKind,
 }
 
 /// Vertex produced by the fill tessellators.
@@ -321,8 +411,8 @@ pub enum LineJoin {
     /// A sharp corner is to be used to join path segments.
     Miter,
     /// Same as a miter join, but if the miter limit is exceeded,
-    /// the miter is clipped at a miter length equal to the miter limit value
-    /// multiplied by the stroke width.
+    /// the miter is clipped at a distance from the vertex equal to half of
+    /// the miter limit value multiplied by the stroke width.
     MiterClip,
     /// A round corner is to be used to join path segments.
     Round,
@@ -332,6 +422,38 @@ pub enum LineJoin {
     Bevel,
 }
 
+/// The position of the stroke relative to the path.
+///
+/// Similar to CSS's `stroke-alignment` property.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum StrokeAlignment {
+    /// The stroke is centered on the path.
+    Center,
+    /// The stroke is on the inside of each sub-path, determined by its orientation.
+    Inner,
+    /// The stroke is on the outside of each sub-path, determined by its orientation.
+    Outer,
+    /// The stroke is offset toward the left side of the path by a fraction of the
+    /// line width. `0.5` and `-0.5` respectively stroke only the left and right sides.
+    ///
+    /// Values outside of this range are clamped.
+    Offset(f32),
+}
+
+/// How the miter limit is interpreted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum MiterLimitMode {
+    /// The miter limit is the maximum ratio of the miter length to the stroke width.
+    ///
+    /// This is the definition of SVG, as well as PDF and PostScript.
+    Svg,
+    /// The miter limit is the maximum ratio of the miter length to half of the
+    /// stroke width, as in WPF and Direct2D.
+    HalfWidth,
+}
+
 /// Parameters for the tessellator.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -362,6 +484,11 @@ pub struct StrokeOptions {
     /// Default value: `StrokeOptions::DEFAULT_MITER_LIMIT`.
     pub miter_limit: f32,
 
+    /// How the miter limit is interpreted.
+    ///
+    /// Default value: `MiterLimitMode::Svg`.
+    pub miter_limit_mode: MiterLimitMode,
+
     /// Maximum allowed distance to the path when building an approximation.
     ///
     /// See [Flattening and tolerance](index.html#flattening-and-tolerance).
@@ -377,6 +504,30 @@ pub struct StrokeOptions {
     /// Default value: `true`.
     pub apply_line_width: bool,
 
+    /// Position of the stroke relative to the path.
+    ///
+    /// Default value: `StrokeAlignment::Center`.
+    pub alignment: StrokeAlignment,
+
+    /// Minimum number of segments used to approximate the arc of a round join or
+    /// of a quarter of a round cap.
+    ///
+    /// Default value: `StrokeOptions::DEFAULT_MIN_ROUND_SEGMENTS`.
+    pub min_round_segments: u32,
+
+    /// Maximum number of segments used to approximate the arc of a round join or
+    /// of a quarter of a round cap.
+    ///
+    /// Default value: `StrokeOptions::DEFAULT_MAX_ROUND_SEGMENTS`.
+    pub max_round_segments: u32,
+
+    /// Produce the same output on every platform, at the expense of performance.
+    ///
+    /// See [Deterministic output](index.html#deterministic-output).
+    ///
+    /// Default value: `false`.
+    pub deterministic: bool,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a StrokeOptions without calling the constructor.
     _private: (),
@@ -395,6 +546,8 @@ impl StrokeOptions {
     pub const DEFAULT_LINE_JOIN: LineJoin = LineJoin::Miter;
     pub const DEFAULT_LINE_WIDTH: f32 = 1.0;
     pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    pub const DEFAULT_MIN_ROUND_SEGMENTS: u32 = 1;
+    pub const DEFAULT_MAX_ROUND_SEGMENTS: u32 = 1024;
 
     pub const DEFAULT: Self = StrokeOptions {
         start_cap: Self::DEFAULT_LINE_CAP,
@@ -402,8 +555,13 @@ impl StrokeOptions {
         line_join: Self::DEFAULT_LINE_JOIN,
         line_width: Self::DEFAULT_LINE_WIDTH,
         miter_limit: Self::DEFAULT_MITER_LIMIT,
+        miter_limit_mode: MiterLimitMode::Svg,
         tolerance: Self::DEFAULT_TOLERANCE,
         apply_line_width: true,
+        alignment: StrokeAlignment::Center,
+        min_round_segments: Self::DEFAULT_MIN_ROUND_SEGMENTS,
+        max_round_segments: Self::DEFAULT_MAX_ROUND_SEGMENTS,
+        deterministic: false,
         _private: (),
     };
 
@@ -412,6 +570,64 @@ impl StrokeOptions {
         Self::DEFAULT.with_tolerance(tolerance)
     }
 
+    /// Options with the tolerance to use for a path rendered with the provided transformation,
+    /// so that the flattening error on screen is at most `device_tolerance`.
+    ///
+    /// See `tolerance_for_transform`.
+    #[inline]
+    pub fn tolerance_for_scale(device_tolerance: f32, transform: &math::Transform2D) -> Self {
+        Self::tolerance(tolerance_for_transform(device_tolerance, transform))
+    }
+
+    /// Number of segments used to approximate a circular arc of the given angle (in
+    /// radians) in round joins and caps.
+    ///
+    /// The arc is subdivided so that the distance between the segments and the arc
+    /// of radius `line_width / 2` (scaled according to the alignment) stays under the
+    /// tolerance, within the limits set by `min_round_segments` and `max_round_segments`.
+    pub fn round_segments(&self, angle: f32) -> u32 {
+        let min = self.min_round_segments;
+        let max = ::std::cmp::max(self.max_round_segments, min);
+
+        let scale = match self.alignment {
+            StrokeAlignment::Center => 1.0,
+            StrokeAlignment::Inner | StrokeAlignment::Outer => 2.0,
+            StrokeAlignment::Offset(offset) => 1.0 + 2.0 * offset.abs().min(0.5),
+        };
+        let radius = self.line_width.abs() * 0.5 * scale;
+        let tolerance = f32::min(self.tolerance, radius);
+        if radius == 0.0 || tolerance.is_nan() || tolerance <= 0.0 {
+            return min;
+        }
+
+        if self.deterministic {
+            // The smallest number of segments n such that cos(angle / 2n) >= 1 - tolerance / radius,
+            // which is equivalent to the computation below without acos.
+            let threshold = 1.0 - tolerance / radius;
+            let half_angle = angle.abs() * 0.5;
+            let (mut low, mut high) = (1, ::std::cmp::max(max, 1));
+            while low < high {
+                let n = low + (high - low) / 2;
+                if deterministic_sin_cos(half_angle / n as f32).1 >= threshold {
+                    high = n;
+                } else {
+                    low = n + 1;
+                }
+            }
+            return ::std::cmp::max(low, min);
+        }
+
+        // The maximum angle of a segment such that its distance to the arc is equal
+        // to the tolerance.
+        let max_segment_angle = 2.0 * (1.0 - tolerance / radius).acos();
+        let segments = (angle.abs() / max_segment_angle).ceil();
+        if !segments.is_finite() || segments >= max as f32 {
+            return max;
+        }
+
+        ::std::cmp::max(segments as u32, min)
+    }
+
     #[inline]
     pub fn with_tolerance(mut self, tolerance: f32) -> Self {
         self.tolerance = tolerance;
@@ -456,21 +672,70 @@ impl StrokeOptions {
         self
     }
 
+    #[inline]
+    pub fn with_miter_limit_mode(mut self, mode: MiterLimitMode) -> Self {
+        self.miter_limit_mode = mode;
+        self
+    }
+
+    #[inline]
+    pub fn with_round_segment_limits(mut self, min: u32, max: u32) -> Self {
+        self.min_round_segments = min;
+        self.max_round_segments = max;
+        self
+    }
+
+    #[inline]
+    pub fn with_alignment(mut self, alignment: StrokeAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Only stroke the given side of the path.
+    ///
+    /// The stroke then spans the full line width on that side of the path.
+    #[inline]
+    pub fn with_one_sided(mut self, side: Side) -> Self {
+        self.alignment = StrokeAlignment::Offset(side.sign() * 0.5);
+        self
+    }
+
     #[inline]
     pub fn dont_apply_line_width(mut self) -> Self {
         self.apply_line_width = false;
         self
     }
+
+    #[inline]
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
 }
 
-/// The fill rule defines how to determine what is inside and what is outside of the shape.
+/// Computes the tolerance to use in the local space of a path, so that the flattening error
+/// is at most `device_tolerance` once the path is transformed.
 ///
-/// See the SVG specification.
-#[derive(Copy, Clone, Debug, PartialEq)]
-#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub enum FillRule {
-    EvenOdd,
-    NonZero,
+/// With anisotropic transformations (non-uniform scales, skews), the error is scaled
+/// differently depending on its direction, so the largest scale factor is used. If the
+/// transformation collapses everything to a point, `device_tolerance` is returned.
+pub fn tolerance_for_transform(device_tolerance: f32, transform: &math::Transform2D) -> f32 {
+    let scale = max_scale_factor(transform);
+    if scale > 0.0 && scale.is_finite() {
+        device_tolerance / scale
+    } else {
+        device_tolerance
+    }
+}
+
+// The largest singular value of the linear part of the transformation.
+fn max_scale_factor(transform: &math::Transform2D) -> f32 {
+    let (a, b, c, d) = (transform.m11, transform.m12, transform.m21, transform.m22);
+    let sum = a * a + b * b + c * c + d * d;
+    let det = a * d - b * c;
+    let discriminant = (sum * sum - 4.0 * det * det).max(0.0);
+
+    ((sum + discriminant.sqrt()) * 0.5).sqrt()
 }
 
 /// Parameters for the fill tessellator.
@@ -514,6 +779,13 @@ pub struct FillOptions {
     /// What to do if the tessellator detects an error.
     pub on_error: OnError,
 
+    /// Produce the same output on every platform, at the expense of performance.
+    ///
+    /// See [Deterministic output](index.html#deterministic-output).
+    ///
+    /// Default value: `false`.
+    pub deterministic: bool,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a FillOptions without the calling constructor.
     _private: (),
@@ -535,6 +807,7 @@ impl FillOptions {
         compute_normals: true,
         assume_no_intersections: false,
         on_error: OnError::DEFAULT,
+        deterministic: false,
         _private: (),
     };
 
@@ -546,6 +819,15 @@ impl FillOptions {
         Self::DEFAULT.with_tolerance(tolerance)
     }
 
+    /// Options with the tolerance to use for a path rendered with the provided transformation,
+    /// so that the flattening error on screen is at most `device_tolerance`.
+    ///
+    /// See `tolerance_for_transform`.
+    #[inline]
+    pub fn tolerance_for_scale(device_tolerance: f32, transform: &math::Transform2D) -> Self {
+        Self::tolerance(tolerance_for_transform(device_tolerance, transform))
+    }
+
     #[inline]
     pub fn non_zero() -> Self {
         let mut options = Self::DEFAULT;
@@ -576,6 +858,12 @@ impl FillOptions {
         self.on_error = policy;
         self
     }
+
+    #[inline]
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
 }
 
 impl Default for FillOptions {
@@ -610,6 +898,119 @@ impl Default for OnError {
     fn default() -> Self { Self::DEFAULT }
 }
 
+/// Allows aborting a tessellation in progress.
+///
+/// The token can be cloned and shared with other threads. The tessellation is aborted as
+/// soon as `cancel` has been called on any of the clones, or when the deadline (if any) is
+/// reached.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::{FillTessellator, FillOptions, FillVertex, FillError, CancellationToken};
+/// # use tess::geometry_builder::{VertexBuffers, simple_builder};
+/// # use tess::path::default::Path;
+/// # use tess::path::builder::*;
+/// # use tess::math::point;
+/// # use std::time::Duration;
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.move_to(point(0.0, 0.0));
+/// builder.line_to(point(10.0, 0.0));
+/// builder.line_to(point(10.0, 10.0));
+/// builder.close();
+/// let path = builder.build();
+///
+/// let mut tessellator = FillTessellator::new();
+/// let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+///
+/// // Give up if the tessellation takes more than 50 milliseconds.
+/// tessellator.set_cancellation_token(Some(CancellationToken::with_timeout(Duration::from_millis(50))));
+/// let result = tessellator.tessellate_path(
+///     path.path_iter(),
+///     &FillOptions::default(),
+///     &mut simple_builder(&mut buffers),
+/// );
+///
+/// match result {
+///     Ok(_) => {}
+///     Err(FillError::Cancelled) => { println!("Too slow, giving up."); }
+///     Err(e) => { panic!("{:?}", e); }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Constructor.
+    pub fn new() -> Self {
+        CancellationToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+        }
+    }
+
+    /// A token that is automatically cancelled when the deadline is reached.
+    pub fn with_deadline(deadline: Instant) -> Self {
+        CancellationToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: Some(deadline),
+        }
+    }
+
+    /// A token that is automatically cancelled once `timeout` has elapsed.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken::with_deadline(Instant::now() + timeout)
+    }
+
+    /// Requests the tessellations using this token (or any of its clones) to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, AtomicOrdering::Relaxed);
+    }
+
+    /// Returns whether `cancel` was called or the deadline is reached.
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(AtomicOrdering::Relaxed) {
+            return true;
+        }
+
+        match self.deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        }
+    }
+}
+
+
+#[test]
+fn test_tolerance_for_transform() {
+    use math::{Transform2D, Angle};
+
+    let t = Transform2D::create_scale(4.0, 4.0);
+    assert!((tolerance_for_transform(0.1, &t) - 0.025).abs() < 1e-6);
+
+    // Anisotropic scale: the largest factor wins, regardless of the rotation.
+    let t = Transform2D::create_scale(2.0, 10.0).post_rotate(Angle::radians(0.7));
+    assert!((tolerance_for_transform(1.0, &t) - 0.1).abs() < 1e-5);
+
+    // Skew.
+    let t = Transform2D::row_major(1.0, 0.0, 1.0, 1.0, 5.0, 5.0);
+    let golden_ratio = (1.0 + 5.0f32.sqrt()) * 0.5;
+    assert!((tolerance_for_transform(1.0, &t) - 1.0 / golden_ratio).abs() < 1e-5);
+
+    let t = Transform2D::create_scale(0.0, 0.0);
+    assert_eq!(tolerance_for_transform(0.5, &t), 0.5);
+
+    let options = FillOptions::tolerance_for_scale(0.5, &Transform2D::create_scale(0.5, 0.25));
+    assert!((options.tolerance - 1.0).abs() < 1e-6);
+    let options = StrokeOptions::tolerance_for_scale(0.5, &Transform2D::create_scale(0.5, 0.25));
+    assert!((options.tolerance - 1.0).abs() < 1e-6);
+}
 
 #[test]
 fn test_without_miter_limit(){
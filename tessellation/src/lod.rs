@@ -0,0 +1,358 @@
+//! Tessellate a path at several levels of detail.
+//!
+//! Renderers that zoom over a large range of scales (maps, deep-zoom viewers) need the
+//! same shape with a coarse tessellation when it is small on screen and a precise one when
+//! it is large. The functions of this module produce a `LodChain`: one mesh per tolerance,
+//! from which `LodChain::select` picks the cheapest mesh that is precise enough for the
+//! current scale.
+//!
+//! The fill functions flatten the path only once, with the smallest tolerance. The coarser
+//! levels are obtained by simplifying the flattened curves (the end points of the curves and
+//! the line segments of the path are always kept), so that the distance between the curves
+//! and each level stays under the level's tolerance.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{FillTessellator, FillOptions};
+//! use tess::lod::{fill_lod_chain, lod_tolerances};
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.cubic_bezier_to(point(100.0, 0.0), point(100.0, 100.0), point(0.0, 100.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     // Tolerances of 0.01, 0.1, 1.0 and 10.0 (give or take rounding errors).
+//!     let tolerances = lod_tolerances(0.01, 10.0, 4);
+//!     let chain = fill_lod_chain(
+//!         &mut FillTessellator::new(),
+//!         path.as_slice(),
+//!         &FillOptions::default(),
+//!         &tolerances,
+//!     ).unwrap();
+//!
+//!     // Rendering at a scale of 0.1 with a tolerance of 0.25 pixels on screen only requires
+//!     // a tolerance of 2.5 in the path's coordinate space.
+//!     let level = chain.select(0.1, 0.25).unwrap();
+//!     assert_eq!(level.tolerance, tolerances[2]);
+//!     assert!(level.geometry.indices.len() < chain.levels[0].geometry.indices.len());
+//! }
+//! ```
+
+use path::default::PathSlice;
+use path::iterator::PathIterator;
+use path::{PathEvent, FlattenedEvent};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use math::Point;
+use math_utils::deterministic_flattening;
+use geometry_builder::{VertexBuffers, simple_builder};
+use {FillTessellator, FillOptions, FillVertex, FillError};
+use {StrokeTessellator, StrokeOptions, StrokeVertex};
+
+use std::cmp::Ordering;
+
+/// The tessellation of a path at a given tolerance.
+#[derive(Clone, Debug)]
+pub struct LodLevel<VertexType> {
+    pub tolerance: f32,
+    pub geometry: VertexBuffers<VertexType, u16>,
+}
+
+/// The tessellations of a path at several tolerances.
+#[derive(Clone, Debug)]
+pub struct LodChain<VertexType> {
+    /// Sorted by increasing tolerance, from the most detailed to the least detailed.
+    pub levels: Vec<LodLevel<VertexType>>,
+}
+
+impl<VertexType> LodChain<VertexType> {
+    /// Returns the least detailed level that is precise enough when the geometry is rendered
+    /// with the provided scale, so that the error on screen is at most `device_tolerance`.
+    ///
+    /// If none of the levels is precise enough, the most detailed one is returned. Returns
+    /// `None` if the chain is empty.
+    pub fn select(&self, scale: f32, device_tolerance: f32) -> Option<&LodLevel<VertexType>> {
+        self.levels.iter()
+            .rev()
+            .find(|level| level.tolerance * scale.abs() <= device_tolerance)
+            .or_else(|| self.levels.first())
+    }
+}
+
+/// Returns `num_levels` tolerances, starting with `finest_tolerance` and each one `ratio`
+/// times larger than the previous one.
+pub fn lod_tolerances(finest_tolerance: f32, ratio: f32, num_levels: usize) -> Vec<f32> {
+    let mut tolerances = Vec::with_capacity(num_levels);
+    let mut tolerance = finest_tolerance;
+    for _ in 0..num_levels {
+        tolerances.push(tolerance);
+        tolerance *= ratio;
+    }
+
+    tolerances
+}
+
+/// Fills a path at each of the provided tolerances.
+///
+/// The tolerance of `options` is ignored.
+pub fn fill_lod_chain(
+    tessellator: &mut FillTessellator,
+    path: PathSlice,
+    options: &FillOptions,
+    tolerances: &[f32],
+) -> Result<LodChain<FillVertex>, FillError> {
+    let tolerances = sorted_tolerances(tolerances);
+    let mut levels = Vec::with_capacity(tolerances.len());
+    if tolerances.is_empty() {
+        return Ok(LodChain { levels });
+    }
+
+    let finest = tolerances[0];
+    let flattened = flatten(path, finest, options.deterministic);
+    let mut events = Vec::with_capacity(flattened.len());
+    for &tolerance in &tolerances {
+        simplify(&flattened, tolerance - finest, &mut events);
+
+        let mut geometry = VertexBuffers::new();
+        tessellator.tessellate_flattened(
+            events.drain(..),
+            &options.with_tolerance(tolerance),
+            &mut simple_builder(&mut geometry),
+        )?;
+
+        levels.push(LodLevel { tolerance, geometry });
+    }
+
+    Ok(LodChain { levels })
+}
+
+/// Strokes a path at each of the provided tolerances.
+///
+/// The tolerance of `options` is ignored.
+pub fn stroke_lod_chain(
+    tessellator: &mut StrokeTessellator,
+    path: PathSlice,
+    options: &StrokeOptions,
+    tolerances: &[f32],
+) -> LodChain<StrokeVertex> {
+    // The stroke tessellator handles the joins between the flattened segments of curves
+    // differently from the ones between the segments of the path, so the path can't be
+    // flattened ahead of time.
+    let levels = sorted_tolerances(tolerances).into_iter().map(|tolerance| {
+        let mut geometry = VertexBuffers::new();
+        tessellator.tessellate_path(
+            path.path_iter(),
+            &options.with_tolerance(tolerance),
+            &mut simple_builder(&mut geometry),
+        );
+
+        LodLevel { tolerance, geometry }
+    }).collect();
+
+    LodChain { levels }
+}
+
+fn sorted_tolerances(tolerances: &[f32]) -> Vec<f32> {
+    let mut tolerances = tolerances.to_vec();
+    tolerances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    tolerances.dedup();
+
+    tolerances
+}
+
+// A flattened event, and whether it is an intermediate point of a flattened curve
+// which can be removed when simplifying.
+type FlattenedPoint = (FlattenedEvent, bool);
+
+fn flatten(path: PathSlice, tolerance: f32, deterministic: bool) -> Vec<FlattenedPoint> {
+    let mut result = Vec::new();
+    let mut curve = Vec::new();
+    let mut iter = path.path_iter();
+    loop {
+        let from = iter.get_state().current;
+        let event = match iter.next() {
+            Some(event) => event,
+            None => {
+                return result;
+            }
+        };
+
+        curve.clear();
+        match event {
+            PathEvent::MoveTo(to) => {
+                result.push((FlattenedEvent::MoveTo(to), false));
+                continue;
+            }
+            PathEvent::LineTo(to) => {
+                result.push((FlattenedEvent::LineTo(to), false));
+                continue;
+            }
+            PathEvent::Close => {
+                result.push((FlattenedEvent::Close, false));
+                continue;
+            }
+            _ if deterministic => {
+                deterministic_flattening(from, event, tolerance, &mut |p| curve.push(p));
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                QuadraticBezierSegment { from, ctrl, to }
+                    .for_each_flattened(tolerance, &mut |p| curve.push(p));
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                CubicBezierSegment { from, ctrl1, ctrl2, to }
+                    .for_each_flattened(tolerance, &mut |p| curve.push(p));
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                Arc { center, radii, start_angle, sweep_angle, x_rotation }
+                    .for_each_flattened(tolerance, &mut |p| curve.push(p));
+            }
+        }
+
+        // The end of the curve is kept.
+        let n = curve.len();
+        for (i, p) in curve.iter().enumerate() {
+            result.push((FlattenedEvent::LineTo(*p), i + 1 < n));
+        }
+    }
+}
+
+// Removes the intermediate points of the flattened curves that are within `tolerance` of
+// the simplified curves (Douglas-Peucker).
+fn simplify(flattened: &[FlattenedPoint], tolerance: f32, output: &mut Vec<FlattenedEvent>) {
+    if tolerance <= 0.0 {
+        output.extend(flattened.iter().map(|&(event, _)| event));
+        return;
+    }
+
+    let mut run = Vec::new();
+    let mut first = Point::new(0.0, 0.0);
+    let mut current = first;
+    for &(event, removable) in flattened {
+        match event {
+            FlattenedEvent::MoveTo(to) => {
+                output.push(event);
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::Close => {
+                output.push(event);
+                current = first;
+            }
+            FlattenedEvent::LineTo(to) => {
+                if run.is_empty() {
+                    run.push(current);
+                }
+                run.push(to);
+                if removable {
+                    continue;
+                }
+
+                simplify_run(&run, tolerance, output);
+                run.clear();
+                current = to;
+            }
+        }
+    }
+}
+
+// Pushes the points of the simplified polyline, except the first one.
+fn simplify_run(points: &[Point], tolerance: f32, output: &mut Vec<FlattenedEvent>) {
+    let last = points.len() - 1;
+    let mut keep = vec![false; points.len()];
+    keep[last] = true;
+
+    let mut stack = vec![(0, last)];
+    while let Some((first, last)) = stack.pop() {
+        let mut max_distance = tolerance;
+        let mut split = None;
+        for i in (first + 1)..last {
+            let d = distance_to_segment(points[i], points[first], points[last]);
+            if d > max_distance {
+                max_distance = d;
+                split = Some(i);
+            }
+        }
+        if let Some(i) = split {
+            keep[i] = true;
+            stack.push((first, i));
+            stack.push((i, last));
+        }
+    }
+
+    for (p, keep) in points.iter().zip(keep.iter()).skip(1) {
+        if *keep {
+            output.push(FlattenedEvent::LineTo(*p));
+        }
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let square_length = ab.square_length();
+    if square_length == 0.0 {
+        return (p - a).length();
+    }
+    let t = ((p - a).dot(ab) / square_length).clamp(0.0, 1.0);
+
+    (p - (a + ab * t)).length()
+}
+
+#[test]
+fn lod_chain() {
+    use math::{point, vector, Angle};
+    use path::default::Path;
+    use path::builder::*;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(50.0, 0.0));
+    builder.quadratic_bezier_to(point(100.0, 0.0), point(100.0, 50.0));
+    builder.arc(point(50.0, 50.0), vector(50.0, 50.0), Angle::radians(3.0), Angle::radians(0.0));
+    builder.close();
+    let path = builder.build();
+
+    let tolerances = lod_tolerances(0.01, 4.0, 5);
+    assert_eq!(tolerances.len(), 5);
+    let chain = fill_lod_chain(
+        &mut FillTessellator::new(),
+        path.as_slice(),
+        &FillOptions::default(),
+        &[tolerances[3], tolerances[0], tolerances[2], tolerances[1], tolerances[4]],
+    ).unwrap();
+
+    assert_eq!(chain.levels.len(), 5);
+    for pair in chain.levels.windows(2) {
+        assert!(pair[0].tolerance < pair[1].tolerance);
+        assert!(pair[0].geometry.vertices.len() >= pair[1].geometry.vertices.len());
+    }
+    assert!(chain.levels[0].geometry.vertices.len() > chain.levels[4].geometry.vertices.len());
+
+    // The coarser levels stay within their tolerance of the finest one: all of their
+    // vertices are on the path, and the line segments of the path are preserved.
+    for level in &chain.levels {
+        let vertices = &level.geometry.vertices;
+        assert!(vertices.iter().any(|v| v.position == point(50.0, 0.0)));
+        assert!(vertices.iter().any(|v| v.position == point(100.0, 50.0)));
+    }
+
+    assert_eq!(chain.select(1.0, 0.01).unwrap().tolerance, tolerances[0]);
+    assert_eq!(chain.select(1.0, 0.001).unwrap().tolerance, tolerances[0]);
+    assert_eq!(chain.select(0.01, 1.0).unwrap().tolerance, tolerances[4]);
+    assert_eq!(chain.select(0.5, 0.1).unwrap().tolerance, tolerances[2]);
+
+    let stroke_chain = stroke_lod_chain(
+        &mut StrokeTessellator::new(),
+        path.as_slice(),
+        &StrokeOptions::default(),
+        &tolerances,
+    );
+    assert_eq!(stroke_chain.levels.len(), 5);
+    assert!(stroke_chain.levels[0].geometry.vertices.len() > stroke_chain.levels[4].geometry.vertices.len());
+}
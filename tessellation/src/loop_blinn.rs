@@ -0,0 +1,356 @@
+//! Implicit curve coefficients for Loop-Blinn curve rendering.
+//!
+//! Following Loop and Blinn's "Resolution Independent Curve Rendering using Programmable
+//! Graphics Hardware", a curve segment can be rendered by a triangle (or the triangulated
+//! convex hull of its control points) with per-vertex coefficients that are interpolated
+//! across the primitive and evaluated per fragment:
+//!
+//! - Quadratic bézier curves use `(u, v)` coefficients and the implicit function
+//!   `u * u - v`.
+//! - Cubic bézier curves use `(k, l, m)` coefficients and the implicit function
+//!   `k * k * k - l * m`.
+//!
+//! In both cases the curve is where the implicit function is zero and fragments for which
+//! it is positive are outside of the shape.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::loop_blinn::{CurveCoefficients, for_each_curve};
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.quadratic_bezier_to(point(10.0, -10.0), point(20.0, 0.0));
+//!     builder.cubic_bezier_to(point(20.0, 10.0), point(10.0, 20.0), point(0.0, 20.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     let mut count = 0;
+//!     for_each_curve(path.path_iter(), &mut |curve| {
+//!         match *curve {
+//!             CurveCoefficients::Quadratic { ref uv, .. } => { assert_eq!(uv[2], point(1.0, 1.0)); }
+//!             CurveCoefficients::Cubic { .. } => {}
+//!         }
+//!         count += 1;
+//!     });
+//!
+//!     assert_eq!(count, 2);
+//! }
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use math::{Point, point};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::cubic_bezier::CubicBezierType;
+
+/// Returns the `(u, v)` coefficients at the `from`, `ctrl` and `to` points of any
+/// quadratic bézier curve.
+///
+/// `u * u - v` is negative on the side of the curve that contains the control point.
+pub fn quadratic_uv() -> [Point; 3] {
+    [point(0.0, 0.0), point(0.5, 0.0), point(1.0, 1.0)]
+}
+
+/// A curve segment and its Loop-Blinn coefficients.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CurveCoefficients {
+    /// The coefficients are the `(u, v)` coordinates at `from`, `ctrl` and `to`.
+    Quadratic {
+        curve: QuadraticBezierSegment<f32>,
+        uv: [Point; 3],
+    },
+    /// The coefficients are the `[k, l, m]` coordinates at `from`, `ctrl1`, `ctrl2` and `to`.
+    Cubic {
+        curve: CubicBezierSegment<f32>,
+        klm: [[f32; 3]; 4],
+    },
+}
+
+/// Calls the callback with the `[k, l, m]` coefficients of a cubic bézier curve
+/// at `from`, `ctrl1`, `ctrl2` and `to`.
+///
+/// The coefficients are oriented so that `k * k * k - l * m` is negative on the right
+/// side of the curve, that is for the points `p` where `curve.derivative(t).cross(p - curve.sample(t))`
+/// is negative.
+///
+/// Loops that intersect themselves within the curve would be rendered incorrectly, so the
+/// curve is split at its double point and the callback is invoked for each of the parts.
+/// Nothing is produced for curves that are straight lines or points.
+pub fn cubic_bezier_klm<F>(curve: &CubicBezierSegment<f32>, cb: &mut F)
+where F: FnMut(&CubicBezierSegment<f32>, &[[f32; 3]; 4]) {
+    let kind = curve.classify();
+
+    if let CubicBezierType::Loop { t1, t2 } = kind {
+        let margin = 0.0001;
+        let mut t0 = 0.0;
+        for &t in &[t1, t2] {
+            if t > margin && t < 1.0 - margin {
+                let part = curve.split_range(t0..t);
+                if let Some(klm) = klm_coefficients(&part, part.classify()) {
+                    cb(&part, &klm);
+                }
+                t0 = t;
+            }
+        }
+        if t0 > 0.0 {
+            let part = curve.split_range(t0..1.0);
+            if let Some(klm) = klm_coefficients(&part, part.classify()) {
+                cb(&part, &klm);
+            }
+            return;
+        }
+    }
+
+    if let Some(klm) = klm_coefficients(curve, kind) {
+        cb(curve, &klm);
+    }
+}
+
+/// Calls the callback with the Loop-Blinn coefficients of each curve segment of a path.
+///
+/// Arcs are approximated with quadratic bézier curves. Line segments, as well as cubic
+/// bézier curves that are straight lines, don't need any coefficients and are skipped.
+pub fn for_each_curve<Iter, F>(mut path: Iter, cb: &mut F)
+where
+    Iter: PathIterator,
+    F: FnMut(&CurveCoefficients),
+{
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let curve = QuadraticBezierSegment { from, ctrl, to };
+                cb(&CurveCoefficients::Quadratic { curve, uv: quadratic_uv() });
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                let curve = CubicBezierSegment { from, ctrl1, ctrl2, to };
+                cubic_bezier_klm(&curve, &mut |curve, klm| {
+                    cb(&CurveCoefficients::Cubic { curve: *curve, klm: *klm });
+                });
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    cb(&CurveCoefficients::Quadratic { curve: *curve, uv: quadratic_uv() });
+                });
+            }
+            Some(_) => {}
+            None => {
+                return;
+            }
+        }
+    }
+}
+
+// The d1, d2 and d3 terms of the paper, normalized.
+fn inflection_terms(curve: &CubicBezierSegment<f32>) -> (f32, f32, f32) {
+    let a1 = (curve.to - curve.from).cross(curve.ctrl2 - curve.from);
+    let a2 = (curve.from - curve.ctrl1).cross(curve.to - curve.ctrl1);
+    let a3 = (curve.ctrl1 - curve.ctrl2).cross(curve.from - curve.ctrl2);
+
+    let d1 = a1 - 2.0 * a2 + 3.0 * a3;
+    let d2 = 3.0 * a3 - a2;
+    let d3 = 3.0 * a3;
+
+    let max = d1.abs().max(d2.abs()).max(d3.abs());
+
+    (d1 / max, d2 / max, d3 / max)
+}
+
+fn klm_coefficients(curve: &CubicBezierSegment<f32>, kind: CubicBezierType<f32>) -> Option<[[f32; 3]; 4]> {
+    let (d1, d2, d3) = inflection_terms(curve);
+
+    let (mut klm, flip) = match kind {
+        CubicBezierType::Point | CubicBezierType::Line => {
+            return None;
+        }
+        CubicBezierType::Quadratic => {
+            let third = 1.0 / 3.0;
+            let klm = [
+                [0.0, 0.0, 0.0],
+                [third, 0.0, third],
+                [2.0 * third, third, 2.0 * third],
+                [1.0, 1.0, 1.0],
+            ];
+            (klm, d3 < 0.0)
+        }
+        CubicBezierType::CuspAtInfinity { .. } => {
+            let ls = d3;
+            let lt = 3.0 * d2;
+            let ls_lt = ls - lt;
+            let klm = [
+                [ls, ls * ls * ls, 1.0],
+                [ls - lt / 3.0, ls * ls * ls_lt, 1.0],
+                [ls - 2.0 * lt / 3.0, ls * ls_lt * ls_lt, 1.0],
+                [ls_lt, ls_lt * ls_lt * ls_lt, 1.0],
+            ];
+            (klm, false)
+        }
+        CubicBezierType::Serpentine { .. } | CubicBezierType::Cusp { .. } => {
+            let sqrt = (9.0 * d2 * d2 - 12.0 * d1 * d3).max(0.0).sqrt();
+            let ls = 3.0 * d2 - sqrt;
+            let ms = 3.0 * d2 + sqrt;
+            let lt = 6.0 * d1;
+            let mt = lt;
+            let l = lt - ls;
+            let m = mt - ms;
+            let klm = [
+                [ls * ms, ls * ls * ls, ms * ms * ms],
+                [(3.0 * ls * ms - ls * mt - lt * ms) / 3.0, -ls * ls * l, -ms * ms * m],
+                [(lt * (mt - 2.0 * ms) + ls * (3.0 * ms - 2.0 * mt)) / 3.0, l * l * ls, m * m * ms],
+                [l * m, -l * l * l, -m * m * m],
+            ];
+            (klm, d1 < 0.0)
+        }
+        CubicBezierType::Loop { .. } => {
+            let sqrt = (4.0 * d1 * d3 - 3.0 * d2 * d2).max(0.0).sqrt();
+            let ls = d2 - sqrt;
+            let ms = d2 + sqrt;
+            let lt = 2.0 * d1;
+            let mt = lt;
+            let l = lt - ls;
+            let m = mt - ms;
+            let klm = [
+                [ls * ms, ls * ls * ms, ls * ms * ms],
+                [
+                    (3.0 * ls * ms - ls * mt - lt * ms) / 3.0,
+                    -ls * (ls * (mt - 3.0 * ms) + 2.0 * lt * ms) / 3.0,
+                    -ms * (ls * (2.0 * mt - 3.0 * ms) + lt * ms) / 3.0,
+                ],
+                [
+                    (lt * (mt - 2.0 * ms) + ls * (3.0 * ms - 2.0 * mt)) / 3.0,
+                    l * (ls * (2.0 * mt - 3.0 * ms) + lt * ms) / 3.0,
+                    m * (ls * (mt - 3.0 * ms) + 2.0 * lt * ms) / 3.0,
+                ],
+                [l * m, -l * l * m, -l * m * m],
+            ];
+            let k1 = klm[1][0];
+            (klm, (d1 > 0.0 && k1 < 0.0) || (d1 < 0.0 && k1 > 0.0))
+        }
+    };
+
+    if flip {
+        for coeffs in &mut klm {
+            coeffs[0] = -coeffs[0];
+            coeffs[1] = -coeffs[1];
+        }
+    }
+
+    Some(klm)
+}
+
+// Evaluates the implicit function at a given position by interpolating the coefficients
+// over the triangle formed by three of the control points.
+#[cfg(test)]
+fn implicit_value(curve: &CubicBezierSegment<f32>, klm: &[[f32; 3]; 4], p: Point) -> f32 {
+    let points = [curve.from, curve.ctrl1, curve.ctrl2, curve.to];
+    let mut best = (0, 1, 2);
+    let mut best_area = 0.0;
+    for &(a, b, c) in &[(0, 1, 2), (0, 1, 3), (0, 2, 3), (1, 2, 3)] {
+        let area = (points[b] - points[a]).cross(points[c] - points[a]);
+        if area.abs() > best_area {
+            best_area = area.abs();
+            best = (a, b, c);
+        }
+    }
+
+    let (a, b, c) = best;
+    let area = (points[b] - points[a]).cross(points[c] - points[a]);
+    let wa = (points[c] - points[b]).cross(p - points[b]) / area;
+    let wb = (points[a] - points[c]).cross(p - points[c]) / area;
+    let wc = 1.0 - wa - wb;
+    let coeff = |i| klm[a][i] * wa + klm[b][i] * wb + klm[c][i] * wc;
+
+    coeff(0) * coeff(0) * coeff(0) - coeff(1) * coeff(2)
+}
+
+// Checks that the implicit function is positive on the left side of the curve and
+// negative on its right side, and returns the number of parts.
+#[cfg(test)]
+fn check_orientation(curve: &CubicBezierSegment<f32>) -> u32 {
+    use math::vector;
+
+    let mut count = 0;
+    cubic_bezier_klm(curve, &mut |part, klm| {
+        for &t in &[0.2, 0.5, 0.8] {
+            let p = part.sample(t);
+            let tangent = part.derivative(t).normalize();
+            let normal = vector(-tangent.y, tangent.x) * 0.1;
+            assert!(implicit_value(part, klm, p + normal) > 0.0);
+            assert!(implicit_value(part, klm, p - normal) < 0.0);
+        }
+        count += 1;
+    });
+
+    count
+}
+
+#[test]
+fn cubic_klm_orientation() {
+    let curves = [
+        // Serpentine.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(3.0, 2.0),
+            ctrl2: point(5.0, -4.0),
+            to: point(9.0, 1.0),
+        },
+        // Arch.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(0.0, 5.0),
+            ctrl2: point(5.0, 5.0),
+            to: point(5.0, 0.0),
+        },
+        // Cusp at infinity.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(1.0, 0.0),
+            ctrl2: point(0.0, 1.0),
+            to: point(1.0, 1.0),
+        },
+        // Quadratic.
+        QuadraticBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl: point(1.5, 3.0),
+            to: point(3.0, 0.0),
+        }.to_cubic(),
+    ];
+
+    for curve in &curves {
+        assert_eq!(check_orientation(curve), 1);
+        assert_eq!(check_orientation(&curve.flip()), 1);
+    }
+}
+
+#[test]
+fn cubic_klm_loop_split() {
+    let curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 5.0),
+        ctrl2: point(-5.0, 5.0),
+        to: point(5.0, 0.0),
+    };
+
+    assert_eq!(check_orientation(&curve), 3);
+    assert_eq!(check_orientation(&curve.flip()), 3);
+}
+
+#[test]
+fn cubic_klm_line() {
+    let curve = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 1.0),
+        ctrl2: point(2.0, 2.0),
+        to: point(3.0, 3.0),
+    };
+
+    assert_eq!(check_orientation(&curve), 0);
+}
@@ -4,7 +4,10 @@ use fixed;
 use geom::math::*;
 use path_fill::Edge;
 use geom::euclid;
+use path::{PathEvent, FlattenedEvent};
+use path::fixed::{FixedPath, FixedPoint, FixedEvent, FRACTIONAL_BITS};
 use std::f64;
+use std::f32::consts::FRAC_PI_4;
 
 pub type FixedPoint32 = fixed::Fp32<fixed::_16>;
 pub type FixedPoint64 = fixed::Fp64<fixed::_16>;
@@ -127,6 +130,167 @@ pub fn compute_normal(v1: Vector, v2: Vector) -> Vector {
     n / inv_len
 }
 
+/// Computes the sine and cosine of an angle using only basic arithmetic operations.
+///
+/// Unlike `f32::sin_cos` which depends on the platform's math library, the result is
+/// the same on every platform.
+pub fn deterministic_sin_cos(angle: f32) -> (f32, f32) {
+    let x = f64::from(angle);
+    // Reduce to [-pi/4, pi/4] and remember the quadrant.
+    let quadrant = (x / f64::consts::FRAC_PI_2).round();
+    let r = x - quadrant * f64::consts::FRAC_PI_2;
+    let r2 = r * r;
+    // Taylor series, precise enough for f32 on the reduced range.
+    let sin = r * (1.0 - r2 / 6.0 * (1.0 - r2 / 20.0 * (1.0 - r2 / 42.0 * (1.0 - r2 / 72.0 * (1.0 - r2 / 110.0)))));
+    let cos = 1.0 - r2 / 2.0 * (1.0 - r2 / 12.0 * (1.0 - r2 / 30.0 * (1.0 - r2 / 56.0 * (1.0 - r2 / 90.0))));
+    if !quadrant.is_finite() {
+        // NaN or infinite angle.
+        return (f32::NAN, f32::NAN);
+    }
+    let (sin, cos) = match (quadrant as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    };
+
+    (sin as f32, cos as f32)
+}
+
+/// Approximates a curve or an arc with line segments, producing the same output on
+/// every platform.
+///
+/// The callback receives the end of each line segment. The intermediate points are rounded
+/// to 1/64th of a unit, the last one is exactly the end of the curve. Returns the end of
+/// the curve.
+pub(crate) fn deterministic_flattening<F: FnMut(Point)>(
+    from: Point,
+    event: PathEvent,
+    tolerance: f32,
+    cb: &mut F,
+) -> Point {
+    let mut fixed_path = FixedPath::new();
+    fixed_path.push(FixedEvent::MoveTo(FixedPoint::from_point(from)));
+    let to = match event {
+        PathEvent::QuadraticTo(ctrl, to) => {
+            fixed_path.push(FixedEvent::QuadraticTo(
+                FixedPoint::from_point(ctrl),
+                FixedPoint::from_point(to),
+            ));
+            to
+        }
+        PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+            fixed_path.push(FixedEvent::CubicTo(
+                FixedPoint::from_point(ctrl1),
+                FixedPoint::from_point(ctrl2),
+                FixedPoint::from_point(to),
+            ));
+            to
+        }
+        PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+            deterministic_arc(from, center, radii, sweep_angle, x_rotation, &mut fixed_path)
+        }
+        PathEvent::LineTo(to) => {
+            cb(to);
+            return to;
+        }
+        PathEvent::MoveTo(_) | PathEvent::Close => {
+            return from;
+        }
+    };
+
+    let raw_tolerance = (tolerance * (1 << FRACTIONAL_BITS) as f32).round().max(1.0) as i32;
+    let flattened = fixed_path.flattened(raw_tolerance);
+    let points = flattened.points();
+    let mut prev = points[0];
+    for p in &points[1..points.len() - 1] {
+        if *p != prev {
+            cb(p.to_point());
+            prev = *p;
+        }
+    }
+    cb(to);
+
+    to
+}
+
+/// Flattens a path with `deterministic_flattening`.
+pub(crate) fn deterministic_flattened_events<Iter>(path: Iter, tolerance: f32) -> Vec<FlattenedEvent>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    let mut events = Vec::new();
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+    for event in path {
+        match event {
+            PathEvent::MoveTo(to) => {
+                events.push(FlattenedEvent::MoveTo(to));
+                first = to;
+                current = to;
+            }
+            PathEvent::Close => {
+                events.push(FlattenedEvent::Close);
+                current = first;
+            }
+            _ => {
+                current = deterministic_flattening(current, event, tolerance, &mut |p| {
+                    events.push(FlattenedEvent::LineTo(p));
+                });
+            }
+        }
+    }
+
+    events
+}
+
+// Approximates an arc with quadratic bézier curves of at most a quarter of a right angle
+// each, without relying on the platform's trigonometric functions.
+fn deterministic_arc(
+    from: Point,
+    center: Point,
+    radii: Vector,
+    sweep_angle: Angle,
+    x_rotation: Angle,
+    path: &mut FixedPath,
+) -> Point {
+    if radii.x == 0.0 || radii.y == 0.0 || !sweep_angle.radians.is_finite() {
+        return from;
+    }
+
+    let (sin_rotation, cos_rotation) = deterministic_sin_cos(x_rotation.radians);
+    let rotate = |v: Vector, sin: f32, cos: f32| vector(v.x * cos - v.y * sin, v.x * sin + v.y * cos);
+    let to_ellipse = |v: Vector| center + rotate(vector(v.x * radii.x, v.y * radii.y), sin_rotation, cos_rotation);
+
+    // The start of the arc on the unit circle.
+    let d = rotate(from - center, -sin_rotation, cos_rotation);
+    let u = vector(d.x / radii.x, d.y / radii.y);
+    let length = u.length();
+    if length == 0.0 {
+        return from;
+    }
+    let mut u = u / length;
+
+    let num_parts = (sweep_angle.radians.abs() / FRAC_PI_4).ceil().max(1.0);
+    let step = sweep_angle.radians / num_parts;
+    let (sin_step, cos_step) = deterministic_sin_cos(step);
+    let (sin_half_step, cos_half_step) = deterministic_sin_cos(step * 0.5);
+
+    let mut end = from;
+    for _ in 0..(num_parts as u32) {
+        let next = rotate(u, sin_step, cos_step);
+        let ctrl = rotate(u, sin_half_step, cos_half_step) / cos_half_step;
+        end = to_ellipse(next);
+        path.push(FixedEvent::QuadraticTo(
+            FixedPoint::from_point(to_ellipse(ctrl)),
+            FixedPoint::from_point(end),
+        ));
+        u = next;
+    }
+
+    end
+}
+
 #[test]
 fn test_compute_normal() {
     fn assert_almost_eq(a: Vector, b: Vector) {
@@ -138,3 +302,46 @@ fn test_compute_normal() {
     assert_almost_eq(compute_normal(vector(1.0, 0.0), vector(0.0, 1.0)), vector(-1.0, 1.0));
     assert_almost_eq(compute_normal(vector(1.0, 0.0), vector(1.0, 0.0)), vector(0.0, 1.0));
 }
+
+#[test]
+fn test_deterministic_sin_cos() {
+    // Golden values, computed once and expected to be bit-exact on every platform.
+    assert_eq!(deterministic_sin_cos(0.0), (0.0, 1.0));
+    assert_eq!(deterministic_sin_cos(1.0), (0.84147096, 0.5403023));
+    assert_eq!(deterministic_sin_cos(-2.5), (-0.5984721, -0.8011436));
+
+    // Large angles go through the range reduction.
+    let (sin, cos) = deterministic_sin_cos(100.0);
+    let (expected_sin, expected_cos) = 100.0f64.sin_cos();
+    assert!((sin as f64 - expected_sin).abs() < 1e-6);
+    assert!((cos as f64 - expected_cos).abs() < 1e-6);
+
+    for i in 0..1000 {
+        let angle = (i as f32 - 500.0) * 0.0137;
+        let (sin, cos) = deterministic_sin_cos(angle);
+        assert!((sin - angle.sin()).abs() < 1e-6);
+        assert!((cos - angle.cos()).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_deterministic_flattening() {
+    let events = deterministic_flattened_events(
+        vec![
+            PathEvent::MoveTo(point(0.0, 0.0)),
+            PathEvent::QuadraticTo(point(2.0, 4.0), point(4.0, 0.0)),
+            PathEvent::Close,
+        ].into_iter(),
+        0.25,
+    );
+
+    // Expected to be bit-exact on every platform.
+    assert_eq!(events, vec![
+        FlattenedEvent::MoveTo(point(0.0, 0.0)),
+        FlattenedEvent::LineTo(point(1.0, 1.5)),
+        FlattenedEvent::LineTo(point(2.0, 2.0)),
+        FlattenedEvent::LineTo(point(3.0, 1.5)),
+        FlattenedEvent::LineTo(point(4.0, 0.0)),
+        FlattenedEvent::Close,
+    ]);
+}
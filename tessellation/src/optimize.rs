@@ -0,0 +1,434 @@
+//! Reorder the output of the tessellators for better GPU performance.
+//!
+//! The tessellators emit triangles in the order in which they are discovered by the
+//! algorithm, which does not make good use of the GPU's vertex caches:
+//!
+//! - The post-transform cache avoids running the vertex shader again for vertices that
+//!   were used by recent triangles. `optimize_vertex_cache` reorders the triangles so that
+//!   they reuse recently used vertices, using Tom Forsyth's "Linear-Speed Vertex Cache
+//!   Optimisation" algorithm.
+//! - The pre-transform cache benefits from vertices being fetched in memory order.
+//!   `optimize_vertex_fetch` reorders the vertices in the order in which they are first
+//!   used by the triangles.
+//!
+//! `optimize_buffers` applies both, in that order. The geometry is unchanged: each triangle
+//! keeps its vertices and its winding order. This is meant to be done once for geometry
+//! that is drawn many times, for example large maps.
+//!
+//! Some inputs (collinear edges in particular) can also produce triangles with a zero or
+//! nearly zero area. They don't contribute to the rendered image, but some graphics drivers
+//! and validation layers don't like them. `remove_degenerate_triangles` removes them.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{FillTessellator, FillOptions, FillVertex, VertexBuffers, optimize_buffers};
+//! use tess::geometry_builder::simple_builder;
+//! use tess::optimize::average_cache_miss_ratio;
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.line_to(point(10.0, 0.0));
+//!     builder.line_to(point(10.0, 10.0));
+//!     builder.line_to(point(5.0, 5.0));
+//!     builder.line_to(point(0.0, 10.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     let mut buffers: VertexBuffers<FillVertex, u16> = VertexBuffers::new();
+//!     FillTessellator::new().tessellate_path(
+//!         path.path_iter(),
+//!         &FillOptions::default(),
+//!         &mut simple_builder(&mut buffers),
+//!     ).unwrap();
+//!
+//!     let before = average_cache_miss_ratio(&buffers.indices, 16);
+//!     optimize_buffers(&mut buffers);
+//!     assert!(average_cache_miss_ratio(&buffers.indices, 16) <= before);
+//! }
+//! ```
+
+use geometry_builder::VertexBuffers;
+use math::Point;
+
+use std::collections::VecDeque;
+use std::mem::swap;
+
+/// Index types that can be reordered by the functions of this module.
+pub trait BufferIndex: Copy {
+    fn to_usize(self) -> usize;
+    fn from_usize(idx: usize) -> Self;
+}
+
+impl BufferIndex for u16 {
+    #[inline]
+    fn to_usize(self) -> usize { self as usize }
+    #[inline]
+    fn from_usize(idx: usize) -> Self { idx as u16 }
+}
+
+impl BufferIndex for u32 {
+    #[inline]
+    fn to_usize(self) -> usize { self as usize }
+    #[inline]
+    fn from_usize(idx: usize) -> Self { idx as u32 }
+}
+
+/// Reorders the triangles and then the vertices of the buffers.
+///
+/// See `optimize_vertex_cache` and `optimize_vertex_fetch`.
+pub fn optimize_buffers<VertexType, IndexType: BufferIndex>(buffers: &mut VertexBuffers<VertexType, IndexType>) {
+    let num_vertices = buffers.vertices.len();
+    optimize_vertex_cache(&mut buffers.indices, num_vertices);
+    optimize_vertex_fetch(buffers);
+}
+
+// Parameters of the scoring function, from the original description of the algorithm.
+const CACHE_SIZE: usize = 32;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+fn vertex_score(cache_position: Option<usize>, remaining_triangles: usize) -> f32 {
+    if remaining_triangles == 0 {
+        return -1.0;
+    }
+
+    let mut score = match cache_position {
+        // The vertices of the last triangle get a fixed score, otherwise the algorithm
+        // would favor emitting the same edge twice in a row.
+        Some(position) if position < 3 => LAST_TRIANGLE_SCORE,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+        }
+        None => 0.0,
+    };
+
+    // Favor the vertices that have few triangles left to get rid of them.
+    score += VALENCE_BOOST_SCALE * (remaining_triangles as f32).powf(-VALENCE_BOOST_POWER);
+
+    score
+}
+
+/// Reorders the triangles to improve the hit rate of the GPU's post-transform vertex cache.
+///
+/// The winding order of the triangles is preserved. Trailing indices that don't form a
+/// complete triangle are left untouched.
+pub fn optimize_vertex_cache<IndexType: BufferIndex>(indices: &mut [IndexType], num_vertices: usize) {
+    let num_triangles = indices.len() / 3;
+    if num_triangles == 0 {
+        return;
+    }
+
+    // The triangles that use each vertex and have not been emitted yet are stored in
+    // adjacency[offsets[v]..offsets[v] + remaining[v]].
+    let mut offsets = vec![0; num_vertices + 1];
+    for idx in &indices[..num_triangles * 3] {
+        offsets[idx.to_usize() + 1] += 1;
+    }
+    for v in 0..num_vertices {
+        offsets[v + 1] += offsets[v];
+    }
+    let mut remaining = vec![0; num_vertices];
+    let mut adjacency = vec![0; num_triangles * 3];
+    for t in 0..num_triangles {
+        for corner in 0..3 {
+            let v = vertex(indices, t, corner);
+            adjacency[offsets[v] + remaining[v]] = t;
+            remaining[v] += 1;
+        }
+    }
+
+    let mut scores: Vec<f32> = remaining.iter().map(|&n| vertex_score(None, n)).collect();
+    let mut emitted = vec![false; num_triangles];
+    let mut cache = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut new_cache = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut output = Vec::with_capacity(num_triangles * 3);
+    let mut best_triangle = None;
+    let mut next_unemitted = 0;
+
+    for _ in 0..num_triangles {
+        let t = match best_triangle {
+            Some(t) => t,
+            None => {
+                // None of the triangles of the cached vertices are left, continue from
+                // the next triangle in the original order.
+                while emitted[next_unemitted] {
+                    next_unemitted += 1;
+                }
+                next_unemitted
+            }
+        };
+
+        emitted[t] = true;
+        output.extend_from_slice(&indices[t * 3..t * 3 + 3]);
+        let triangle = [vertex(indices, t, 0), vertex(indices, t, 1), vertex(indices, t, 2)];
+
+        for &v in &triangle {
+            let start = offsets[v];
+            let end = start + remaining[v];
+            if let Some(pos) = adjacency[start..end].iter().position(|&adj| adj == t) {
+                adjacency.swap(start + pos, end - 1);
+                remaining[v] -= 1;
+            }
+        }
+
+        // Move the vertices of the triangle to the front of the cache.
+        new_cache.clear();
+        for &v in &triangle {
+            if !new_cache.contains(&v) {
+                new_cache.push(v);
+            }
+        }
+        for &v in &cache {
+            if !triangle.contains(&v) {
+                new_cache.push(v);
+            }
+        }
+        for &v in new_cache.iter().skip(CACHE_SIZE) {
+            scores[v] = vertex_score(None, remaining[v]);
+        }
+        new_cache.truncate(CACHE_SIZE);
+        for (position, &v) in new_cache.iter().enumerate() {
+            scores[v] = vertex_score(Some(position), remaining[v]);
+        }
+
+        // The best candidate is one of the triangles of the cached vertices.
+        best_triangle = None;
+        let mut best_score = f32::MIN;
+        for &v in &new_cache {
+            for &adj in &adjacency[offsets[v]..offsets[v] + remaining[v]] {
+                let score = scores[vertex(indices, adj, 0)]
+                    + scores[vertex(indices, adj, 1)]
+                    + scores[vertex(indices, adj, 2)];
+                if score > best_score {
+                    best_score = score;
+                    best_triangle = Some(adj);
+                }
+            }
+        }
+
+        swap(&mut cache, &mut new_cache);
+    }
+
+    indices[..output.len()].copy_from_slice(&output);
+}
+
+fn vertex<IndexType: BufferIndex>(indices: &[IndexType], triangle: usize, corner: usize) -> usize {
+    indices[triangle * 3 + corner].to_usize()
+}
+
+/// Reorders the vertices in the order in which the triangles first use them, to improve
+/// the memory locality of vertex fetches.
+///
+/// Vertices that are not used by any triangle are moved to the end of the vertex buffer.
+pub fn optimize_vertex_fetch<VertexType, IndexType: BufferIndex>(buffers: &mut VertexBuffers<VertexType, IndexType>) {
+    let num_vertices = buffers.vertices.len();
+    let mut new_index = vec![None; num_vertices];
+    let mut order = Vec::with_capacity(num_vertices);
+
+    for idx in &mut buffers.indices {
+        let v = idx.to_usize();
+        let new = match new_index[v] {
+            Some(new) => new,
+            None => {
+                let new = order.len();
+                new_index[v] = Some(new);
+                order.push(v);
+                new
+            }
+        };
+        *idx = IndexType::from_usize(new);
+    }
+
+    for (v, new) in new_index.iter().enumerate() {
+        if new.is_none() {
+            order.push(v);
+        }
+    }
+
+    let mut vertices: Vec<Option<VertexType>> = buffers.vertices.drain(..).map(Some).collect();
+    buffers.vertices.extend(order.iter().map(|&v| vertices[v].take().unwrap()));
+}
+
+/// Removes the triangles that have an area smaller than or equal to `max_area`, and returns
+/// the number of removed triangles.
+///
+/// `position` returns the position of a vertex. With `max_area` set to zero, only the
+/// triangles with a zero area (with repeated vertices, or three collinear vertices) are
+/// removed. The vertex buffer is not modified.
+pub fn remove_degenerate_triangles<VertexType, IndexType, Position>(
+    buffers: &mut VertexBuffers<VertexType, IndexType>,
+    max_area: f32,
+    position: Position,
+) -> u32
+where
+    IndexType: BufferIndex,
+    Position: Fn(&VertexType) -> Point,
+{
+    let num_triangles = buffers.indices.len() / 3;
+    let mut num_kept = 0;
+    for t in 0..num_triangles {
+        let a = vertex(&buffers.indices, t, 0);
+        let b = vertex(&buffers.indices, t, 1);
+        let c = vertex(&buffers.indices, t, 2);
+        if a == b || b == c || a == c {
+            continue;
+        }
+
+        let pa = position(&buffers.vertices[a]);
+        let pb = position(&buffers.vertices[b]);
+        let pc = position(&buffers.vertices[c]);
+        let area = (pb - pa).cross(pc - pa).abs() * 0.5;
+        if area <= max_area {
+            continue;
+        }
+
+        for corner in 0..3 {
+            buffers.indices[num_kept * 3 + corner] = buffers.indices[t * 3 + corner];
+        }
+        num_kept += 1;
+    }
+
+    // Trailing indices that don't form a triangle are kept.
+    let trailing = buffers.indices.len() - num_triangles * 3;
+    for i in 0..trailing {
+        buffers.indices[num_kept * 3 + i] = buffers.indices[num_triangles * 3 + i];
+    }
+    buffers.indices.truncate(num_kept * 3 + trailing);
+
+    (num_triangles - num_kept) as u32
+}
+
+/// Returns the average number of vertex shader invocations per triangle with a FIFO
+/// post-transform cache of the given size.
+///
+/// This is a common measure of the efficiency of an index buffer. It is at most 3, and
+/// approaches 0.5 for the best orderings of large regular meshes.
+pub fn average_cache_miss_ratio<IndexType: BufferIndex>(indices: &[IndexType], cache_size: usize) -> f32 {
+    let num_triangles = indices.len() / 3;
+    if num_triangles == 0 {
+        return 0.0;
+    }
+
+    let mut cache = VecDeque::with_capacity(cache_size + 1);
+    let mut misses = 0;
+    for idx in &indices[..num_triangles * 3] {
+        let v = idx.to_usize();
+        if !cache.contains(&v) {
+            misses += 1;
+            cache.push_back(v);
+            if cache.len() > cache_size {
+                cache.pop_front();
+            }
+        }
+    }
+
+    misses as f32 / num_triangles as f32
+}
+
+#[test]
+fn optimize_grid() {
+    use math::{Point, point};
+
+    // A grid of 30x30 quads, with the triangles in a scrambled order.
+    let n = 31;
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    for y in 0..n {
+        for x in 0..n {
+            buffers.vertices.push(point(x as f32, y as f32));
+        }
+    }
+    let mut triangles = Vec::new();
+    for y in 0..(n - 1) {
+        for x in 0..(n - 1) {
+            let a = y * n + x;
+            triangles.push([a, a + 1, a + n + 1]);
+            triangles.push([a, a + n + 1, a + n]);
+        }
+    }
+    let mut seed: u32 = 1;
+    for i in (1..triangles.len()).rev() {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        triangles.swap(i, (seed >> 8) as usize % (i + 1));
+    }
+    for triangle in &triangles {
+        buffers.indices.extend_from_slice(triangle);
+    }
+
+    fn sorted_triangles(buffers: &VertexBuffers<Point, u32>) -> Vec<[(i32, i32); 3]> {
+        let mut result: Vec<[(i32, i32); 3]> = buffers.indices.chunks(3).map(|t| {
+            let p = |i: u32| {
+                let v = buffers.vertices[i as usize];
+                (v.x as i32, v.y as i32)
+            };
+            // Rotate so that the smallest vertex comes first, preserving the winding.
+            let mut t = [p(t[0]), p(t[1]), p(t[2])];
+            while t[0] > t[1] || t[0] > t[2] {
+                t = [t[1], t[2], t[0]];
+            }
+            t
+        }).collect();
+        result.sort();
+        result
+    }
+
+    let expected = sorted_triangles(&buffers);
+    let before = average_cache_miss_ratio(&buffers.indices, 16);
+
+    optimize_buffers(&mut buffers);
+
+    assert_eq!(sorted_triangles(&buffers), expected);
+    assert_eq!(buffers.vertices.len(), (n * n) as usize);
+
+    let after = average_cache_miss_ratio(&buffers.indices, 16);
+    assert!(before > 2.0);
+    assert!(after < 1.0);
+
+    // The vertices are sorted by first use.
+    let mut max_index = 0;
+    for idx in &buffers.indices {
+        assert!(*idx <= max_index + 1);
+        max_index = max_index.max(*idx);
+    }
+}
+
+#[test]
+fn degenerate_triangles() {
+    use math::point;
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices.extend_from_slice(&[
+        point(0.0, 0.0),
+        point(1.0, 0.0),
+        point(2.0, 0.0),
+        point(1.0, 1.0),
+        point(1.0, 0.001),
+    ]);
+    buffers.indices.extend_from_slice(&[
+        0, 1, 3,
+        // Collinear.
+        0, 1, 2,
+        // Repeated vertex.
+        1, 3, 1,
+        1, 2, 3,
+        // Thin.
+        0, 2, 4,
+    ]);
+
+    let mut thin = buffers.clone();
+
+    assert_eq!(remove_degenerate_triangles(&mut buffers, 0.0, |p| *p), 2);
+    assert_eq!(buffers.indices, vec![0, 1, 3, 1, 2, 3, 0, 2, 4]);
+    assert_eq!(buffers.vertices.len(), 5);
+
+    assert_eq!(remove_degenerate_triangles(&mut thin, 0.01, |p| *p), 3);
+    assert_eq!(thin.indices, vec![0, 1, 3, 1, 2, 3]);
+}
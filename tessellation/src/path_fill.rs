@@ -20,22 +20,26 @@
 // It's super slow right now.
 //
 
-use std::mem::{replace, swap};
+use std::mem::{replace, swap, size_of};
 use std::cmp::{PartialOrd, Ordering};
 
 use sid::{Id, IdVec};
 
 use FillVertex as Vertex;
-use {FillOptions, FillRule, Side, OnError};
+use {FillOptions, FillRule, Side, OnError, CancellationToken};
 use geom::math::*;
 use geom::euclid::{self, Trig};
 use math_utils::*;
 use geometry_builder::{GeometryBuilder, Count, VertexId};
-use path::PathEvent;
+use path::{PathEvent, FlattenedEvent};
+use path::polygon::Polygon;
 use path::builder::{FlatPathBuilder, PathBuilder};
 use path::iterator::PathIterator;
 use debugger::*;
 
+#[cfg(feature="profiling")]
+use std::time::{Duration, Instant};
+
 #[cfg(test)]
 use geometry_builder::{VertexBuffers, simple_builder};
 #[cfg(test)]
@@ -76,24 +80,61 @@ type EdgesBelow = IdVec<PendingEdgeId, PendingEdge>;
 pub mod dbg {
     pub const MONOTONE_SPLIT: u32 = 1;
     pub const INTERSECTION_POINT: u32 = 2;
+    pub const SWEEP_LINE: u32 = 4;
+    pub const ACTIVE_EDGE: u32 = 8;
+    pub const PENDING_EDGE: u32 = 16;
+    pub const ERROR: u32 = 32;
 }
 
+// Number of iterations of the sweep line loop between two checks of the cancellation token.
+const CANCELLATION_CHECK_INTERVAL: u32 = 64;
+
 /// The fill tessellator's result type.
 pub type FillResult = Result<Count, FillError>;
 
 /// The fill tessellator's error enumeration.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum FillError {
     UnsupportedParamater,
+    /// The input path contains NaN or infinite coordinates.
+    InvalidInput,
+    /// The tessellation was aborted through the tessellator's `CancellationToken`.
+    Cancelled,
+    /// The tessellation would produce more vertices than allowed by
+    /// `FillTessellator::set_max_vertices`.
+    TooManyVertices,
+    /// The tessellation would use more memory than allowed by
+    /// `FillTessellator::set_memory_budget`.
+    OutOfMemoryBudget,
     Internal(InternalError)
 }
 
-#[derive(Clone, Debug)]
-pub enum InternalError {
-    E01,
-    E02,
-    E03,
-    E04,
+/// An internal error of the fill tessellator, typically caused by precision issues.
+///
+/// Overflowing the capacity of the output is not reported here since it depends on
+/// the index type chosen by the `GeometryBuilder`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InternalError {
+    /// What went wrong.
+    pub kind: InternalErrorKind,
+    /// The number of sweep line positions that were processed before the error was detected.
+    pub event_index: u32,
+    /// The position of the sweep line when the error was detected.
+    pub position: Point,
+}
+
+/// The different kinds of internal errors of the fill tessellator.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InternalErrorKind {
+    /// The edges below a vertex could not be paired into spans.
+    UnbalancedEdgesBelow,
+    /// Some of the edges above or below a vertex could not be processed.
+    UnprocessedEdges,
+    /// The sweep line contains an edge that ends above the current position, which
+    /// means that the events were not processed in order.
+    UnsortedEvents,
+    /// The sweep line does not have the expected structure around the current position.
+    InvalidSweepLine,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -299,9 +340,46 @@ pub struct FillTessellator {
     tess_pool: Vec<MonotoneTessellator>,
 
     error: Option<FillError>,
+    // Number of sweep line positions processed so far, for error reporting.
+    event_index: u32,
+    cancellation_token: Option<CancellationToken>,
+    max_vertices: Option<u32>,
+    memory_budget: Option<usize>,
+    // Amount of geometry sent to the output so far, to enforce the limits above.
+    num_vertices: u32,
+    num_indices: u32,
 
     #[cfg(feature="debugger")]
     debugger: Option<Box<dyn Debugger2D>>,
+
+    #[cfg(feature="profiling")]
+    stats: FillStats,
+}
+
+/// Statistics about the last tessellation performed by a `FillTessellator`.
+///
+/// Only available with the `profiling` feature.
+#[cfg(feature="profiling")]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FillStats {
+    /// Number of sweep line positions that were processed.
+    pub num_events: u32,
+    /// Number of edges in the input, after flattening.
+    pub num_edges: u32,
+    /// Number of intersections found between edges.
+    pub num_intersections: u32,
+    /// Maximum number of edges intersecting the sweep line at the same time.
+    pub max_active_edges: u32,
+    /// Number of monotone polygons the shape was decomposed into.
+    pub num_monotone_spans: u32,
+    /// Number of triangles produced.
+    pub num_triangles: u32,
+    /// Time spent flattening and sorting the path events.
+    ///
+    /// Zero when tessellating pre-built `FillEvents`.
+    pub flattening_time: Duration,
+    /// Time spent in the sweep line algorithm.
+    pub tessellation_time: Duration,
 }
 
 impl FillTessellator {
@@ -315,15 +393,41 @@ impl FillTessellator {
             intersections: Vec::with_capacity(8),
             current_position: TessPoint::new(FixedPoint32::min_val(), FixedPoint32::min_val()),
             error: None,
+            event_index: 0,
+            cancellation_token: None,
+            max_vertices: None,
+            memory_budget: None,
+            num_vertices: 0,
+            num_indices: 0,
             options: FillOptions::DEFAULT,
             log: false,
             tess_pool: Vec::with_capacity(8),
 
             #[cfg(feature="debugger")]
             debugger: None,
+
+            #[cfg(feature="profiling")]
+            stats: FillStats::default(),
         }
     }
 
+    /// Constructor pre-allocating the memory for paths with up to `num_edges` edges
+    /// after flattening.
+    ///
+    /// The tessellator keeps its allocations between tessellations, so reusing the same
+    /// tessellator also avoids growing its internal buffers.
+    pub fn with_capacity(num_edges: usize) -> Self {
+        let mut tessellator = FillTessellator::new();
+        tessellator.reserve(num_edges);
+
+        tessellator
+    }
+
+    /// Reserves the memory for paths with at least `num_edges` more edges after flattening.
+    pub fn reserve(&mut self, num_edges: usize) {
+        self.events.reserve(num_edges);
+    }
+
     /// Compute the tessellation from a path iterator.
     pub fn tessellate_path<Iter>(
         &mut self,
@@ -334,12 +438,65 @@ impl FillTessellator {
     where
         Iter: PathIterator,
     {
+        if options.deterministic {
+            let flattened = deterministic_flattened_events(it, options.tolerance);
+            return self.tessellate_with(|events| events.set_flattened(flattened.into_iter()), options, output);
+        }
+
+        self.tessellate_with(|events| events.set_path(options.tolerance, it), options, output)
+    }
+
+    /// Compute the tessellation from an iterator of flattened events.
+    ///
+    /// This avoids the overhead of the flattening step for input that doesn't contain curves.
+    pub fn tessellate_flattened<Iter>(
+        &mut self,
+        it: Iter,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> FillResult
+    where
+        Iter: Iterator<Item = FlattenedEvent>,
+    {
+        self.tessellate_with(|events| events.set_flattened(it), options, output)
+    }
+
+    /// Compute the tessellation of a polygon.
+    pub fn tessellate_polygon(
+        &mut self,
+        polygon: Polygon,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> FillResult {
+        self.tessellate_flattened(polygon.iter(), options, output)
+    }
+
+    fn tessellate_with<F>(
+        &mut self,
+        build_events: F,
+        options: &FillOptions,
+        output: &mut dyn GeometryBuilder<Vertex>,
+    ) -> FillResult
+    where
+        F: FnOnce(&mut FillEvents),
+    {
+        #[cfg(feature="profiling")]
+        let flattening_start = Instant::now();
+
         let mut events = replace(&mut self.events, FillEvents::new());
         events.clear();
-        events.set_path(options.tolerance, it);
+        build_events(&mut events);
+
+        #[cfg(feature="profiling")]
+        let flattening_time = flattening_start.elapsed();
+
         let result = self.tessellate_events(&events, options, output);
         self.events = events;
 
+        #[cfg(feature="profiling")] {
+            self.stats.flattening_time = flattening_time;
+        }
+
         result
     }
 
@@ -359,12 +516,33 @@ impl FillTessellator {
             }
         }
 
+        if events.invalid_input {
+            match options.on_error {
+                OnError::Stop => { return Err(FillError::InvalidInput); }
+                OnError::Panic => { panic!("The input path contains NaN or infinite coordinates"); }
+                // The offending events have already been filtered out.
+                OnError::Recover => {}
+            }
+        }
+
         self.options = *options;
 
+        #[cfg(feature="profiling")]
+        let tessellation_start = Instant::now();
+        #[cfg(feature="profiling")] {
+            self.stats = FillStats::default();
+            self.stats.num_edges = events.edges.len() as u32;
+        }
+
         self.begin_tessellation(output);
 
         self.tessellator_loop(events, output);
 
+        #[cfg(feature="profiling")] {
+            self.stats.num_events = self.event_index;
+            self.stats.tessellation_time = tessellation_start.elapsed();
+        }
+
         let mut error = None;
         swap(&mut error, &mut self.error);
         if let Some(err) = error {
@@ -376,17 +554,84 @@ impl FillTessellator {
         let res = self.end_tessellation(output);
         self.reset();
 
+        #[cfg(feature="profiling")] {
+            self.stats.num_triangles = res.indices / 3;
+        }
+
         Ok(res)
     }
 
+    /// Computes a cheap estimation of the number of vertices and indices that the
+    /// tessellation of a path will produce, for example to pre-allocate buffers.
+    ///
+    /// The estimation is an upper bound if the path does not have self-intersections.
+    /// Each intersection may add a vertex and two triangles to the tessellation.
+    pub fn estimate_geometry<Iter>(it: Iter, options: &FillOptions) -> Count
+    where
+        Iter: PathIterator,
+    {
+        let mut counter = EdgeCounter::new().flattened(options.tolerance);
+        for evt in it {
+            if evt.is_finite() {
+                counter.path_event(evt);
+            }
+        }
+
+        let num_edges = counter.build();
+
+        // Each vertex added by the tessellator consumes at least two edge end points,
+        // and a polygon with n vertices and h holes is split into n + 2 * h - 2
+        // triangles, each hole having at least three edges.
+        Count {
+            vertices: num_edges,
+            indices: num_edges * 5,
+        }
+    }
+
     /// Enable some verbose logging during the tessellation, for debugging purposes.
     pub fn enable_logging(&mut self) { self.log = true; }
 
+    /// Installs a token that can abort the subsequent tessellations, in which case they
+    /// return `FillError::Cancelled`.
+    ///
+    /// The token is checked periodically during the sweep, so the tessellation stops shortly
+    /// after it is cancelled rather than immediately.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation_token = token;
+    }
+
+    /// Limits the number of vertices of the subsequent tessellations.
+    ///
+    /// Tessellations fail with `FillError::TooManyVertices` as soon as the limit is reached,
+    /// before processing the next vertex event. This is useful for example to make sure that
+    /// the output fits in a buffer with 16 bits indices.
+    pub fn set_max_vertices(&mut self, max_vertices: Option<u32>) {
+        self.max_vertices = max_vertices;
+    }
+
+    /// Limits the amount of memory in bytes used by the subsequent tessellations, in which
+    /// case they fail with `FillError::OutOfMemoryBudget` when exceeding it.
+    ///
+    /// The budget covers the events generated from the input path, the tessellator's internal
+    /// data structures and the output geometry, estimated with 32 bits indices. It is checked
+    /// once the path is flattened and then periodically during the sweep, so the actual
+    /// usage can slightly exceed the budget before the tessellation is aborted.
+    pub fn set_memory_budget(&mut self, bytes: Option<usize>) {
+        self.memory_budget = bytes;
+    }
+
     #[cfg(feature="debugger")]
     pub fn install_debugger(&mut self, dbg: Box<dyn Debugger2D>) {
         self.debugger = Some(dbg)
     }
 
+    /// Returns statistics about the last tessellation, useful to find out which paths
+    /// dominate the tessellation cost.
+    #[cfg(feature="profiling")]
+    pub fn stats(&self) -> &FillStats {
+        &self.stats
+    }
+
     fn panic_on_errors(&self) -> bool {
         self.options.on_error == OnError::Panic
     }
@@ -395,6 +640,36 @@ impl FillTessellator {
         self.active_edges.clear();
         self.monotone_tessellators.clear();
         self.pending_edges.clear();
+        self.intersections.clear();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        match self.cancellation_token {
+            Some(ref token) => token.is_cancelled(),
+            None => false,
+        }
+    }
+
+    fn exceeds_memory_budget(&self, events: &FillEvents) -> bool {
+        let budget = match self.memory_budget {
+            Some(budget) => budget,
+            None => { return false; }
+        };
+
+        let mut bytes = events.edges.capacity() * size_of::<OrientedEdge>()
+            + events.vertices.capacity() * size_of::<TessPoint>()
+            + self.active_edges.len() * size_of::<ActiveEdge>()
+            + self.pending_edges.capacity() * size_of::<PendingEdge>()
+            + self.intersections.capacity() * size_of::<OrientedEdge>()
+            + self.num_vertices as usize * size_of::<Vertex>()
+            + self.num_indices as usize * size_of::<u32>();
+
+        for tess in &self.monotone_tessellators {
+            bytes += tess.stack.capacity() * size_of::<MonotoneVertex>()
+                + tess.triangles.capacity() * size_of::<(VertexId, VertexId, VertexId)>();
+        }
+
+        bytes > budget
     }
 
     fn begin_tessellation(&mut self, output: &mut dyn GeometryBuilder<Vertex>) {
@@ -423,16 +698,36 @@ impl FillTessellator {
         output: &mut dyn GeometryBuilder<Vertex>,
     ) {
         self.current_position = TessPoint::new(FixedPoint32::min_val(), FixedPoint32::min_val());
+        self.event_index = 0;
+        self.num_vertices = 0;
+        self.num_indices = 0;
 
         let mut edge_iter = events.edges.iter();
         let mut vertex_iter = events.vertices.iter();
         let mut next_edge = edge_iter.next();
         let mut next_vertex = vertex_iter.next();
+        let mut iterations: u32 = 0;
         loop {
             if self.error.is_some() && self.options.on_error != OnError::Recover {
                 return;
             }
 
+            // Looking up the time for deadlines isn't free, so the token is only
+            // checked every few iterations.
+            if iterations.is_multiple_of(CANCELLATION_CHECK_INTERVAL) {
+                if self.is_cancelled() {
+                    tess_log!(self, " !! FillTessellator cancelled");
+                    self.error = Some(FillError::Cancelled);
+                    return;
+                }
+                if self.exceeds_memory_budget(events) {
+                    tess_log!(self, " !! FillTessellator out of memory budget");
+                    self.error = Some(FillError::OutOfMemoryBudget);
+                    return;
+                }
+            }
+            iterations = iterations.wrapping_add(1);
+
             let mut next_position = None;
             let mut pending_events = false;
 
@@ -504,8 +799,28 @@ impl FillTessellator {
             }
 
             if pending_events {
+                if let Some(max_vertices) = self.max_vertices {
+                    if self.num_vertices >= max_vertices {
+                        tess_log!(self, " !! FillTessellator too many vertices");
+                        self.error = Some(FillError::TooManyVertices);
+                        return;
+                    }
+                }
+
                 let num_intersections = self.intersections.len();
+
+                #[cfg(feature="debugger")]
+                self.debugger_sweep_line();
+
                 self.process_vertex(output);
+                self.event_index += 1;
+
+                #[cfg(feature="profiling")] {
+                    let num_active_edges = self.active_edges.len() as u32;
+                    if num_active_edges > self.stats.max_active_edges {
+                        self.stats.max_active_edges = num_active_edges;
+                    }
+                }
 
                 if num_intersections != self.intersections.len() {
 
@@ -599,6 +914,7 @@ impl FillTessellator {
             (next - position).normalize(),
         );
 
+        self.num_vertices += 1;
         output.add_vertex(Vertex { position, normal })
     }
 
@@ -643,6 +959,7 @@ impl FillTessellator {
 
         let mut vertex_id = if !self.options.compute_normals {
             let vector_position = to_f32_point(self.current_position);
+            self.num_vertices += 1;
             output.add_vertex(
                 Vertex {
                     position: vector_position,
@@ -660,7 +977,10 @@ impl FillTessellator {
         // Step 1, walk the sweep line, handle left/right events, handle the spans that end
         // at this vertex, as well as merge events.
         if self.active_edges.has_id(first_edge_above) && point_type == PointType::OnEdge(Side::Right) {
-            debug_assert!(odd(first_edge_above));
+            if even(first_edge_above) {
+                self.invalid_sweep_line();
+                return;
+            }
             if num_pending_edges == 0 {
                 // we are on the right side of a span but there is nothing below, it means
                 // that we are at a merge event.
@@ -674,7 +994,10 @@ impl FillTessellator {
                 // in the middle so we handle the merge event later. Since end
                 // events remove their spans, we don't need to remember the current
                 // span index to process the merge.
-                debug_assert!(num_edges_above >= 2);
+                if num_edges_above < 2 || !self.active_edges.has_id(first_edge_above + 2) {
+                    self.invalid_sweep_line();
+                    return;
+                }
                 pending_merge = true;
                 num_edges_above -= 2;
             } else {
@@ -685,8 +1008,11 @@ impl FillTessellator {
                 //   ....\
                 //
                 tess_log!(self, "(right event) {:?}", above_idx);
-                debug_assert!(num_edges_above > 0);
                 debug_assert!(num_pending_edges > 0);
+                if num_edges_above == 0 {
+                    self.invalid_sweep_line();
+                    return;
+                }
 
                 if self.options.compute_normals {
                     let vertex_above = self.active_edges[above_idx].points.upper;
@@ -774,7 +1100,7 @@ impl FillTessellator {
         // Since we took care of left and right events already we should not have
         // an odd number of pending edges to work with by now.
         if num_pending_edges % 2 != 0 {
-            if self.error(InternalError::E01) {
+            if self.error(InternalErrorKind::UnbalancedEdgesBelow) {
                 return;
             }
             // TODO - We are in an invalid state, and trying to continue tessellating
@@ -787,7 +1113,10 @@ impl FillTessellator {
         if num_pending_edges > 0 {
             if point_type == PointType::In {
                 debug_assert!(num_pending_edges >= 2);
-                debug_assert!(even(above_idx));
+                if odd(above_idx) {
+                    self.invalid_sweep_line();
+                    return;
+                }
                 // Split event.
                 //
                 // .....x.....
@@ -840,12 +1169,12 @@ impl FillTessellator {
             }
         }
 
-        self.debug_check_sl();
+        self.check_sl();
 
         self.pending_edges.clear();
 
         if num_edges_above != 0 || num_pending_edges != 0 {
-            self.error(InternalError::E02);
+            self.error(InternalErrorKind::UnprocessedEdges);
         }
     }
 
@@ -1056,6 +1385,37 @@ impl FillTessellator {
         }
     }
 
+    // Starts a new debugger frame with the state of the sweep line before the current
+    // position is processed.
+    #[cfg(feature="debugger")]
+    fn debugger_sweep_line(&self) {
+        if let Some(ref dbg) = self.debugger {
+            let current_position = to_f32_point(self.current_position);
+            dbg.new_frame(dbg::SWEEP_LINE);
+            dbg.string(
+                format!("event {} at {:?}", self.event_index, current_position),
+                dbg::SWEEP_LINE,
+            );
+            dbg.point(&current_position, BLUE, dbg::SWEEP_LINE);
+            for edge in self.active_edges.iter() {
+                dbg.edge(
+                    &to_f32_point(edge.points.upper),
+                    &to_f32_point(edge.points.lower),
+                    DARK_GREEN,
+                    dbg::ACTIVE_EDGE,
+                );
+            }
+            for edge in &self.pending_edges {
+                dbg.edge(
+                    &current_position,
+                    &to_f32_point(edge.lower),
+                    GREEN,
+                    dbg::PENDING_EDGE,
+                );
+            }
+        }
+    }
+
     #[cfg(feature="debugger")]
     fn debugger_monotone_split(&self, a: &TessPoint, b: &TessPoint) {
         if let Some(ref dbg) = self.debugger {
@@ -1193,6 +1553,10 @@ impl FillTessellator {
             active_edge_winding
         ));
 
+        #[cfg(feature="profiling")] {
+            self.stats.num_intersections += 1;
+        }
+
         #[cfg(feature="debugger")] {
             if let Some(ref mut dbg) = self.debugger {
                 dbg.point(&to_f32_point(intersection), RED, dbg::INTERSECTION_POINT);
@@ -1216,6 +1580,7 @@ impl FillTessellator {
         {
             let tess = &mut self.monotone_tessellators[span_idx];
             tess.end(vector_position, id);
+            self.num_indices += tess.triangles.len() as u32 * 3;
             tess.flush(output);
         }
 
@@ -1232,13 +1597,31 @@ impl FillTessellator {
         ).begin(to_f32_point(pos), vertex);
 
         self.monotone_tessellators.insert(span, tess);
+
+        #[cfg(feature="profiling")] {
+            self.stats.num_monotone_spans += 1;
+        }
     }
 
     #[inline(never)]
-    fn error(&mut self, err: InternalError) -> bool {
+    fn error(&mut self, kind: InternalErrorKind) -> bool {
+        let err = InternalError {
+            kind,
+            event_index: self.event_index,
+            position: to_f32_point(self.current_position),
+        };
         tess_log!(self, " !! FillTessellator Error {:?}", err);
+
+        #[cfg(feature="debugger")] {
+            if let Some(ref dbg) = self.debugger {
+                dbg.string(format!("{:?}", err), dbg::ERROR);
+                dbg.point(&err.position, RED, dbg::ERROR);
+                dbg.error(dbg::ERROR);
+            }
+        }
+
         if self.panic_on_errors() {
-            panic!();
+            panic!("Fill tessellator error: {:?}", err);
         }
         if self.error.is_none() {
             self.error = Some(FillError::Internal(err));
@@ -1247,11 +1630,16 @@ impl FillTessellator {
         self.options.on_error == OnError::Stop
     }
 
-    #[cfg(not(debug_assertions))]
-    fn debug_check_sl(&mut self) {}
+    // Unless the tessellation stops here, the invalid sweep line is dropped so that
+    // the remaining events are processed from a consistent (empty) state.
+    fn invalid_sweep_line(&mut self) {
+        if self.error(InternalErrorKind::InvalidSweepLine) {
+            return;
+        }
+        self.reset();
+    }
 
-    #[cfg(debug_assertions)]
-    fn debug_check_sl(&mut self) {
+    fn check_sl(&mut self) {
         let mut ok = true;
         for edge in &self.active_edges {
             if edge.merge {
@@ -1278,7 +1666,7 @@ impl FillTessellator {
         }
 
         if !ok {
-            self.error(InternalError::E03);
+            self.error(InternalErrorKind::UnsortedEvents);
         }
 
         self.log_sl_winding();
@@ -1311,7 +1699,7 @@ impl FillTessellator {
                 1 => "+",
                 -1 => "-",
                 0 => "*",
-                _ => panic!(),
+                _ => "?",
             });
         }
         println!("|");
@@ -1511,6 +1899,8 @@ fn edge_angle(v: TessVector) -> f32 {
 pub struct FillEvents {
     edges: Vec<OrientedEdge>,
     vertices: Vec<TessPoint>,
+    // Whether some path events were discarded because of NaN or infinite coordinates.
+    invalid_input: bool,
 }
 
 impl FillEvents {
@@ -1522,17 +1912,35 @@ impl FillEvents {
     }
 
     pub fn new() -> Self {
+        FillEvents::with_capacity(0)
+    }
+
+    /// Constructor pre-allocating the memory for paths with up to `num_edges` edges
+    /// after flattening.
+    pub fn with_capacity(num_edges: usize) -> Self {
         FillEvents {
-            edges: Vec::new(),
-            vertices: Vec::new(),
+            edges: Vec::with_capacity(num_edges),
+            vertices: Vec::with_capacity(num_edges),
+            invalid_input: false,
         }
     }
 
+    /// Reserves capacity for at least `num_edges` more edges.
+    pub fn reserve(&mut self, num_edges: usize) {
+        self.edges.reserve(num_edges);
+        self.vertices.reserve(num_edges);
+    }
+
     pub fn clear(&mut self) {
         self.edges.clear();
         self.vertices.clear();
+        self.invalid_input = false;
     }
 
+    /// Returns true if some of the path events were discarded because they contain
+    /// NaN or infinite coordinates.
+    pub fn has_invalid_input(&self) -> bool { self.invalid_input }
+
     pub fn set_path<Iter: Iterator<Item = PathEvent>>(&mut self, tolerance: f32, it: Iter) {
         self.clear();
         let mut tmp = FillEvents::new();
@@ -1541,12 +1949,42 @@ impl FillEvents {
         let mut builder = EventsBuilder::new();
         builder.recycle(tmp);
 
+        // Non-finite coordinates would cause the flattening code to loop forever
+        // and wreak havoc in the sweep line, so we filter them out early.
+        let mut invalid_input = false;
         let mut builder = builder.flattened(tolerance);
         for evt in it {
+            if !evt.is_finite() {
+                invalid_input = true;
+                continue;
+            }
             builder.path_event(evt);
         }
 
         swap(self, &mut builder.build());
+        self.invalid_input = invalid_input;
+    }
+
+    /// Same as `set_path` for input that doesn't contain curves, skipping the flattening step.
+    pub fn set_flattened<Iter: Iterator<Item = FlattenedEvent>>(&mut self, it: Iter) {
+        self.clear();
+        let mut tmp = FillEvents::new();
+        swap(self, &mut tmp);
+
+        let mut builder = EventsBuilder::new();
+        builder.recycle(tmp);
+
+        let mut invalid_input = false;
+        for evt in it {
+            if !evt.to_path_event().is_finite() {
+                invalid_input = true;
+                continue;
+            }
+            builder.flat_event(evt);
+        }
+
+        swap(self, &mut builder.build());
+        self.invalid_input = invalid_input;
     }
 }
 
@@ -1670,6 +2108,7 @@ impl FlatPathBuilder for EventsBuilder {
         FillEvents {
             edges: self.edges,
             vertices: self.vertices,
+            invalid_input: false,
         }
     }
 
@@ -1688,6 +2127,7 @@ impl FlatPathBuilder for EventsBuilder {
         FillEvents {
             edges: replace(&mut self.edges, Vec::new()),
             vertices: replace(&mut self.vertices, Vec::new()),
+            invalid_input: false,
         }
     }
 
@@ -1696,6 +2136,59 @@ impl FlatPathBuilder for EventsBuilder {
     }
 }
 
+// Counts the edges that the fill tessellator will see, see FillTessellator::estimate_geometry.
+struct EdgeCounter {
+    first: Point,
+    current: Point,
+    num_edges: u32,
+}
+
+impl EdgeCounter {
+    fn new() -> Self {
+        EdgeCounter {
+            first: point(0.0, 0.0),
+            current: point(0.0, 0.0),
+            num_edges: 0,
+        }
+    }
+}
+
+impl FlatPathBuilder for EdgeCounter {
+    type PathType = u32;
+
+    fn move_to(&mut self, to: Point) {
+        self.close();
+        self.first = to;
+        self.current = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if to != self.current {
+            self.num_edges += 1;
+        }
+        self.current = to;
+    }
+
+    fn close(&mut self) {
+        // Sub-paths are always closed when filling.
+        self.line_to(self.first);
+    }
+
+    fn build(mut self) -> u32 {
+        self.close();
+        self.num_edges
+    }
+
+    fn build_and_reset(&mut self) -> u32 {
+        self.close();
+        replace(&mut self.num_edges, 0)
+    }
+
+    fn current_position(&self) -> Point {
+        self.current
+    }
+}
+
 /// Helper class that generates a triangulation from a sequence of vertices describing a monotone
 /// polygon (used internally by the `FillTessellator`).
 struct MonotoneTessellator {
@@ -2867,3 +3360,76 @@ fn test_no_close() {
 fn test_empty_path() {
     test_path_and_count_triangles(Path::new().as_slice(), 0);
 }
+
+#[test]
+fn test_recover_from_invalid_sweep_line() {
+    // Counts the triangles, including the ones emitted before the geometry is aborted.
+    struct TriangleCounter {
+        vertices: u32,
+        triangles: u32,
+    }
+
+    impl GeometryBuilder<Vertex> for TriangleCounter {
+        fn begin_geometry(&mut self) {}
+        fn add_vertex(&mut self, _: Vertex) -> VertexId {
+            self.vertices += 1;
+            VertexId(self.vertices - 1)
+        }
+        fn add_triangle(&mut self, _: VertexId, _: VertexId, _: VertexId) {
+            self.triangles += 1;
+        }
+        fn end_geometry(&mut self) -> Count {
+            Count { vertices: self.vertices, indices: self.triangles * 3 }
+        }
+        fn abort_geometry(&mut self) {}
+    }
+
+    // A span starts at (0, 0) but only its right edge ends at (10, 10), with nothing
+    // below it. Path::builder can't produce this, so the events are built directly.
+    // The triangle further down is valid.
+    let p = |x, y| to_internal(point(x, y));
+    let events = FillEvents {
+        edges: vec![
+            OrientedEdge::new(p(0.0, 0.0), p(-10.0, 20.0)),
+            OrientedEdge::new(p(10.0, 10.0), p(0.0, 0.0)),
+            OrientedEdge::new(p(0.0, 30.0), p(-10.0, 40.0)),
+            OrientedEdge::new(p(10.0, 40.0), p(0.0, 30.0)),
+            OrientedEdge::new(p(-10.0, 40.0), p(10.0, 40.0)),
+        ],
+        vertices: vec![p(10.0, 10.0), p(-10.0, 20.0), p(10.0, 40.0)],
+        invalid_input: false,
+    };
+
+    let mut tess = FillTessellator::new();
+    let mut output = TriangleCounter { vertices: 0, triangles: 0 };
+    let result = tess.tessellate_events(
+        &events,
+        &FillOptions::default().on_error(OnError::Recover),
+        &mut output,
+    );
+
+    assert_eq!(
+        result,
+        Err(FillError::Internal(InternalError {
+            kind: InternalErrorKind::InvalidSweepLine,
+            event_index: 1,
+            position: point(10.0, 10.0),
+        }))
+    );
+    // The tessellator carried on after dropping the invalid sweep line.
+    assert_eq!(output.triangles, 1);
+
+    // And it is left in a consistent state for the next tessellation.
+    let mut path = Path::builder();
+    path.move_to(point(0.0, 0.0));
+    path.line_to(point(1.0, 0.0));
+    path.line_to(point(1.0, 1.0));
+    path.close();
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        path.build().path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+    assert_eq!(buffers.indices.len(), 3);
+}
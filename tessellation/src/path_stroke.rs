@@ -1,16 +1,20 @@
-use math_utils::compute_normal;
+use math_utils::{compute_normal, deterministic_flattening, deterministic_sin_cos};
 use geom::math::*;
 use geom::{QuadraticBezierSegment, CubicBezierSegment, LineSegment, Arc};
 use geom::utils::{normalized_tangent, directed_angle};
 use geom::euclid::Trig;
 use geometry_builder::{VertexId, GeometryBuilder, Count};
-use basic_shapes::circle_flattening_step;
 use path::builder::{FlatPathBuilder, PathBuilder};
 use path::iterator::PathIterator;
+use path::PathEvent;
 use StrokeVertex as Vertex;
-use {Side, LineCap, LineJoin, StrokeOptions};
+use {Side, LineCap, LineJoin, MiterLimitMode, StrokeAlignment, StrokeOptions, StrokeVertexKind};
 
 use std::f32::consts::PI;
+use std::cell::Cell;
+
+#[cfg(feature="profiling")]
+use std::time::{Duration, Instant};
 
 /// A Context object that can tessellate stroke operations for complex paths.
 ///
@@ -83,12 +87,56 @@ use std::f32::consts::PI;
 /// # }
 /// ```
 #[derive(Default)]
-pub struct StrokeTessellator {}
+pub struct StrokeTessellator {
+    #[cfg(feature="profiling")]
+    stats: StrokeStats,
+}
+
+/// Statistics about the last tessellation performed by a `StrokeTessellator`.
+///
+/// Only available with the `profiling` feature.
+#[cfg(feature="profiling")]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StrokeStats {
+    /// Number of path events that were processed, not counting the invalid ones.
+    pub num_events: u32,
+    /// Number of sub-paths.
+    pub num_sub_paths: u32,
+    /// Number of triangles produced.
+    pub num_triangles: u32,
+    /// Time spent tessellating the path.
+    ///
+    /// The curves are flattened while the stroke is built, so this includes the
+    /// flattening time.
+    pub tessellation_time: Duration,
+}
+
+#[cfg(feature="profiling")]
+impl StrokeStats {
+    fn record_event(&mut self, evt: &PathEvent) {
+        self.num_events += 1;
+        if let PathEvent::MoveTo(..) = *evt {
+            self.num_sub_paths += 1;
+        }
+    }
+}
 
 impl StrokeTessellator {
-    pub fn new() -> Self { StrokeTessellator {} }
+    pub fn new() -> Self { StrokeTessellator::default() }
+
+    /// Returns statistics about the last tessellation, useful to find out which paths
+    /// dominate the tessellation cost.
+    #[cfg(feature="profiling")]
+    pub fn stats(&self) -> &StrokeStats {
+        &self.stats
+    }
 
     /// Compute the tessellation from a path iterator.
+    ///
+    /// Path events containing NaN or infinite coordinates are ignored.
+    ///
+    /// Unlike the fill tessellator, the stroke tessellator has no internal failure
+    /// modes, which is why it does not return a `Result`.
     pub fn tessellate_path<Input>(
         &mut self,
         input: Input,
@@ -98,23 +146,180 @@ impl StrokeTessellator {
     where
         Input: PathIterator,
     {
+        #[cfg(feature="profiling")]
+        let start = Instant::now();
+        #[cfg(feature="profiling")] {
+            self.stats = StrokeStats::default();
+        }
+
         builder.begin_geometry();
         {
             let mut stroker = StrokeBuilder::new(options, builder);
 
             for evt in input {
+                if !evt.is_finite() {
+                    continue;
+                }
+                #[cfg(feature="profiling")] {
+                    self.stats.record_event(&evt);
+                }
                 stroker.path_event(evt);
             }
 
             stroker.build();
         }
-        builder.end_geometry()
+        let count = builder.end_geometry();
+        #[cfg(feature="profiling")]
+        self.record_result(count, start);
+
+        count
+    }
+
+    /// Compute the tessellation from a path iterator, with different line caps for
+    /// each sub-path.
+    ///
+    /// The `caps` callback is invoked at the beginning of each sub-path with the index
+    /// of the sub-path and returns its start and end caps. Closed sub-paths don't have
+    /// caps. Like with the path builders, an edge following a `Close` event starts a
+    /// new sub-path at the first point of the closed one.
+    ///
+    /// Path events containing NaN or infinite coordinates are ignored.
+    pub fn tessellate_path_with_caps<Input, F>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        mut caps: F,
+        builder: &mut dyn GeometryBuilder<Vertex>,
+    ) -> Count
+    where
+        Input: PathIterator,
+        F: FnMut(u32) -> (LineCap, LineCap),
+    {
+        #[cfg(feature="profiling")]
+        let start = Instant::now();
+        #[cfg(feature="profiling")] {
+            self.stats = StrokeStats::default();
+        }
+
+        builder.begin_geometry();
+        {
+            let mut stroker = StrokeBuilder::new(options, builder);
+            let mut sub_path = 0;
+            // Whether the next edge starts a new sub-path without a MoveTo event.
+            let mut after_close = true;
+
+            for evt in input {
+                if !evt.is_finite() {
+                    continue;
+                }
+                #[cfg(feature="profiling")] {
+                    self.stats.record_event(&evt);
+                }
+                let starts_sub_path = match evt {
+                    PathEvent::MoveTo(..) => true,
+                    PathEvent::Close => false,
+                    _ => after_close,
+                };
+                after_close = evt == PathEvent::Close;
+                stroker.path_event(evt);
+
+                if starts_sub_path {
+                    let (start, end) = caps(sub_path);
+                    stroker.set_caps(start, end);
+                    sub_path += 1;
+                }
+            }
+
+            stroker.build();
+        }
+        let count = builder.end_geometry();
+        #[cfg(feature="profiling")]
+        self.record_result(count, start);
+
+        count
+    }
+
+    #[cfg(feature="profiling")]
+    fn record_result(&mut self, count: Count, start: Instant) {
+        self.stats.num_triangles = count.indices / 3;
+        self.stats.tessellation_time = start.elapsed();
+    }
+
+    /// Compute the tessellation from a path iterator, forwarding an identifier with
+    /// each vertex.
+    ///
+    /// The `ids` callback is invoked at the beginning of each sub-path with the index
+    /// of the sub-path and returns the identifier of its vertices. Vertices produced
+    /// before the first `MoveTo` event have the identifier `0`.
+    ///
+    /// Path events containing NaN or infinite coordinates are ignored.
+    pub fn tessellate_path_with_ids<Input, F>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        mut ids: F,
+        builder: &mut dyn GeometryBuilder<(Vertex, u32)>,
+    ) -> Count
+    where
+        Input: PathIterator,
+        F: FnMut(u32) -> u32,
+    {
+        let id = Cell::new(0);
+        let mut output = SubPathIds { output: builder, id: &id };
+
+        output.begin_geometry();
+        {
+            let mut stroker = StrokeBuilder::new(options, &mut output);
+            let mut sub_path = 0;
+
+            for evt in input {
+                if !evt.is_finite() {
+                    continue;
+                }
+                let is_move_to = matches!(evt, PathEvent::MoveTo(..));
+                // Moving to a new sub-path finishes the previous one, so the identifier
+                // must only change afterwards.
+                stroker.path_event(evt);
+
+                if is_move_to {
+                    id.set(ids(sub_path));
+                    sub_path += 1;
+                }
+            }
+
+            stroker.build();
+        }
+        output.end_geometry()
     }
 }
 
+// Forwards the vertices along with an identifier that can change while the stroke
+// builder holds a reference to the output.
+struct SubPathIds<'l> {
+    output: &'l mut dyn GeometryBuilder<(Vertex, u32)>,
+    id: &'l Cell<u32>,
+}
+
+impl<'l> GeometryBuilder<Vertex> for SubPathIds<'l> {
+    fn begin_geometry(&mut self) { self.output.begin_geometry(); }
+
+    fn end_geometry(&mut self) -> Count { self.output.end_geometry() }
+
+    fn add_vertex(&mut self, vertex: Vertex) -> VertexId {
+        self.output.add_vertex((vertex, self.id.get()))
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) { self.output.abort_geometry(); }
+}
+
 macro_rules! add_vertex {
     ($builder: expr, $vertex: expr) => {{
         let mut v = $vertex;
+        v.normal *= $builder.normal_scale(v.side);
 
         if $builder.options.apply_line_width {
             v.position += v.normal * $builder.options.line_width / 2.0;
@@ -125,6 +330,9 @@ macro_rules! add_vertex {
 }
 
 /// A builder that tessellates a stroke directly without allocating any intermediate data structure.
+///
+/// The inner and outer stroke alignments are an exception: the points of each sub-path
+/// are buffered until the orientation of the sub-path is known.
 pub struct StrokeBuilder<'l> {
     first: Point,
     previous: Point,
@@ -140,6 +348,15 @@ pub struct StrokeBuilder<'l> {
     sub_path_start_length: f32,
     options: StrokeOptions,
     previous_command_was_move: bool,
+    vertex_kind: StrokeVertexKind,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    // Offset of the stroke toward the left side, as a fraction of the line width.
+    side_offset: f32,
+    // With inner and outer alignments the points of each sub-path are buffered until
+    // its orientation is known.
+    buffering: bool,
+    pending: Vec<(Point, bool)>,
     output: &'l mut dyn GeometryBuilder<Vertex>,
 }
 
@@ -149,6 +366,8 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
     fn move_to(&mut self, to: Point) {
         self.finish();
 
+        self.start_cap = self.options.start_cap;
+        self.end_cap = self.options.end_cap;
         self.first = to;
         self.current = to;
         self.nth = 0;
@@ -162,6 +381,10 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
     }
 
     fn close(&mut self) {
+        self.flush_pending();
+        let buffering = self.buffering;
+        self.buffering = false;
+
         // If we close almost at the first edge, then we have to
         // skip connecting the last and first edges otherwise the
         // normal will be plagued with floating point precision
@@ -183,6 +406,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
                     normal: self.prev_normal,
                     advancement: self.sub_path_start_length,
                     side: Side::Left,
+                    kind: StrokeVertexKind::Edge,
                 }
             );
             let first_right_id = add_vertex!(
@@ -192,6 +416,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
                     normal: -self.prev_normal,
                     advancement: self.sub_path_start_length,
                     side: Side::Right,
+                    kind: StrokeVertexKind::Edge,
                 }
             );
 
@@ -202,6 +427,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         self.current = self.first;
         self.sub_path_start_length = self.length;
         self.previous_command_was_move = false;
+        self.buffering = buffering;
     }
 
     fn current_position(&self) -> Point { self.current }
@@ -220,12 +446,17 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         self.length = 0.0;
         self.sub_path_start_length = 0.0;
         self.previous_command_was_move = false;
+        self.pending.clear();
     }
 }
 
 impl<'l> PathBuilder for StrokeBuilder<'l> {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
         self.previous_command_was_move = false;
+        if self.options.deterministic {
+            self.deterministic_curve(PathEvent::QuadraticTo(ctrl, to));
+            return;
+        }
         let mut first = true;
         QuadraticBezierSegment {
             from: self.current,
@@ -242,6 +473,10 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
 
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
         self.previous_command_was_move = false;
+        if self.options.deterministic {
+            self.deterministic_curve(PathEvent::CubicTo(ctrl1, ctrl2, to));
+            return;
+        }
         let mut first = true;
         CubicBezierSegment {
             from: self.current,
@@ -264,6 +499,10 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
         sweep_angle: Angle,
         x_rotation: Angle
     ) {
+        if self.options.deterministic {
+            self.deterministic_curve(PathEvent::Arc(center, radii, sweep_angle, x_rotation));
+            return;
+        }
         let start_angle = (self.current - center).angle_from_x_axis() - x_rotation;
         let mut first = true;
         Arc {
@@ -303,11 +542,33 @@ impl<'l> StrokeBuilder<'l> {
             sub_path_start_length: 0.0,
             options: *options,
             previous_command_was_move: false,
+            vertex_kind: StrokeVertexKind::Edge,
+            start_cap: options.start_cap,
+            end_cap: options.end_cap,
+            side_offset: alignment_offset(options.alignment),
+            buffering: alignment_depends_on_orientation(options.alignment),
+            pending: Vec::new(),
             output: builder,
         }
     }
 
-    pub fn set_options(&mut self, options: &StrokeOptions) { self.options = *options; }
+    pub fn set_options(&mut self, options: &StrokeOptions) {
+        self.flush_pending();
+        self.options = *options;
+        self.start_cap = options.start_cap;
+        self.end_cap = options.end_cap;
+        self.side_offset = alignment_offset(options.alignment);
+        self.buffering = alignment_depends_on_orientation(options.alignment);
+    }
+
+    /// Overrides the line caps of the current sub-path.
+    ///
+    /// The caps are reset to the ones of the stroke options at the next `move_to`.
+    /// Closed sub-paths don't have caps.
+    pub fn set_caps(&mut self, start: LineCap, end: LineCap) {
+        self.start_cap = start;
+        self.end_cap = end;
+    }
 
     fn tessellate_empty_square_cap(&mut self) {
         let a = add_vertex!(
@@ -315,8 +576,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(1.0, 1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         let b = add_vertex!(
@@ -324,8 +586,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(1.0, -1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         let c = add_vertex!(
@@ -333,8 +596,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(-1.0, -1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         let d = add_vertex!(
@@ -342,8 +606,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: self.current,
                 normal: vector(-1.0, 1.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         self.output.add_triangle(a, b, c);
@@ -357,8 +622,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: center,
                 normal: vector(-1.0, 0.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Left,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         let right_id = add_vertex!(
@@ -366,8 +632,9 @@ impl<'l> StrokeBuilder<'l> {
             Vertex {
                 position: center,
                 normal: vector(1.0, 0.0),
-                advancement: 0.0,
+                advancement: self.length,
                 side: Side::Right,
+                kind: StrokeVertexKind::StartCap,
             }
         );
         self.tessellate_round_cap(center, vector(0.0, -1.0), left_id, right_id, true);
@@ -375,8 +642,10 @@ impl<'l> StrokeBuilder<'l> {
     }
 
     fn finish(&mut self) {
+        self.flush_pending();
+
         if self.nth == 0 && self.previous_command_was_move {
-            match self.options.start_cap {
+            match self.start_cap {
                 LineCap::Square => {
                     // Even if there is no edge, if we are using square caps we have to place a square
                     // at the current position.
@@ -394,17 +663,18 @@ impl<'l> StrokeBuilder<'l> {
         if self.nth > 0 {
             let current = self.current;
             let d = self.current - self.previous;
-            if self.options.end_cap == LineCap::Square {
+            if self.end_cap == LineCap::Square {
                 // The easiest way to implement square caps is to lie about the current position
                 // and move it slightly to accommodate for the width/2 extra length.
                 self.current += d.normalize();
             }
             let p = self.current + d;
-            self.edge_to(p, true);
+            self.vertex_kind = StrokeVertexKind::EndCap;
+            self.edge_to_impl(p, true);
             // Restore the real current position.
             self.current = current;
 
-            if self.options.end_cap == LineCap::Round {
+            if self.end_cap == LineCap::Round {
                 let left_id = self.previous_left_id;
                 let right_id = self.previous_right_id;
                 self.tessellate_round_cap(current, d, left_id, right_id, false);
@@ -415,7 +685,7 @@ impl<'l> StrokeBuilder<'l> {
             let mut first = self.first;
             let d = first - self.second;
 
-            if self.options.start_cap == LineCap::Square {
+            if self.start_cap == LineCap::Square {
                 first += d.normalize();
             }
 
@@ -429,6 +699,7 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n1,
                     advancement: self.sub_path_start_length,
                     side: Side::Left,
+                    kind: StrokeVertexKind::StartCap,
                 }
             );
             let first_right_id = add_vertex!(
@@ -438,10 +709,11 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n2,
                     advancement: self.sub_path_start_length,
                     side: Side::Right,
+                    kind: StrokeVertexKind::StartCap,
                 }
             );
 
-            if self.options.start_cap == LineCap::Round {
+            if self.start_cap == LineCap::Round {
                 self.tessellate_round_cap(first, d, first_left_id, first_right_id, true);
             }
 
@@ -450,7 +722,67 @@ impl<'l> StrokeBuilder<'l> {
         }
     }
 
+    // Flattens a curve or an arc with deterministic_flattening.
+    fn deterministic_curve(&mut self, event: PathEvent) {
+        let from = self.current;
+        let tolerance = self.options.tolerance;
+        let mut first = true;
+        deterministic_flattening(from, event, tolerance, &mut |point| {
+            self.edge_to(point, first);
+            first = false;
+        });
+    }
+
     fn edge_to(&mut self, to: Point, with_join: bool) {
+        if self.buffering {
+            self.pending.push((to, with_join));
+            self.current = to;
+            return;
+        }
+
+        self.vertex_kind = if with_join { StrokeVertexKind::Join } else { StrokeVertexKind::Edge };
+        self.edge_to_impl(to, with_join);
+    }
+
+    // Resolves the inner or outer alignment of the current sub-path from its orientation
+    // and tessellates the buffered edges.
+    fn flush_pending(&mut self) {
+        if !self.buffering {
+            return;
+        }
+
+        let mut area = 0.0;
+        let mut prev = self.first;
+        for &(p, _) in &self.pending {
+            area += prev.x * p.y - p.x * prev.y;
+            prev = p;
+        }
+        area += prev.x * self.first.y - self.first.x * prev.y;
+
+        // The inside of a sub-path with a positive area is on its left side.
+        let inner = if area >= 0.0 { 0.5 } else { -0.5 };
+        self.side_offset = match self.options.alignment {
+            StrokeAlignment::Outer => -inner,
+            _ => inner,
+        };
+
+        let pending = ::std::mem::take(&mut self.pending);
+        self.buffering = false;
+        self.current = self.first;
+        for &(p, with_join) in &pending {
+            self.edge_to(p, with_join);
+        }
+        self.buffering = true;
+        self.pending = pending;
+        self.pending.clear();
+    }
+
+    fn normal_scale(&self, side: Side) -> f32 {
+        1.0 + 2.0 * self.side_offset * side.sign()
+    }
+
+    // Same as edge_to, using the current vertex_kind for the vertices of the join.
+    fn edge_to_impl(&mut self, to: Point, with_join: bool) {
         if to == self.current {
             return;
         }
@@ -515,26 +847,30 @@ impl<'l> StrokeBuilder<'l> {
             return;
         }
 
-        let arc_len = 0.5 * PI * radius;
-        let step = circle_flattening_step(radius, self.options.tolerance);
-        let num_segments = (arc_len / step).ceil();
-        let num_recursions = num_segments.log2() as u32 * 2;
+        // Number of segments for each quarter of the cap.
+        let num_segments = self.options.round_segments(PI * 0.5);
 
         let dir = dir.normalize();
-        let advancement = self.length;
+        let (advancement, kind) = if is_start {
+            (self.sub_path_start_length, StrokeVertexKind::StartCap)
+        } else {
+            (self.length, StrokeVertexKind::EndCap)
+        };
 
         let quarter_angle = if is_start { -PI * 0.5 } else { PI * 0.5 };
         let mid_angle = directed_angle(vector(1.0, 0.0), dir);
         let left_angle = mid_angle + quarter_angle;
         let right_angle = mid_angle - quarter_angle;
 
+        // When stroking a single side, the tip of the cap belongs to that side.
         let mid_vertex = add_vertex!(
             self,
             Vertex {
                 position: center,
                 normal: dir,
                 advancement,
-                side: Side::Left,
+                side: if self.side_offset < 0.0 { Side::Right } else { Side::Left },
+                kind,
             }
         );
 
@@ -554,25 +890,29 @@ impl<'l> StrokeBuilder<'l> {
         tess_round_cap(
             center,
             (left_angle, mid_angle),
-            radius,
-            left, mid_vertex,
-            num_recursions,
+            (left, mid_vertex),
+            num_segments,
             advancement,
             Side::Left,
+            kind,
             apply_width,
+            self.normal_scale(Side::Left),
             !is_start,
+            self.options.deterministic,
             self.output
         );
         tess_round_cap(
             center,
             (mid_angle, right_angle),
-            radius,
-            mid_vertex, right,
-            num_recursions,
+            (mid_vertex, right),
+            num_segments,
             advancement,
             Side::Right,
+            kind,
             apply_width,
+            self.normal_scale(Side::Right),
             !is_start,
+            self.options.deterministic,
             self.output
         );
     }
@@ -601,20 +941,22 @@ impl<'l> StrokeBuilder<'l> {
                 normal: -front_normal,
                 advancement: self.length,
                 side: front_side.opposite(),
+                kind: self.vertex_kind,
             }
         );
 
+        let miter_limit_is_exceeded = self.miter_limit_is_exceeded(prev_tangent, next_tangent);
         let threshold = 0.95; // TODO: look for a good constant here.
-        if prev_tangent.dot(next_tangent) >= threshold {
-            // The two edges are almost aligned, just use a simple miter join.
-            // TODO: the 0.95 threshold above is completely arbitrary and needs
-            // adjustments.
-            join_type = LineJoin::Miter;
-        } else if join_type == LineJoin::Miter && self.miter_limit_is_exceeded(normal) {
+        if join_type == LineJoin::Miter && miter_limit_is_exceeded {
             // Per SVG spec: If the stroke-miterlimit is exceeded, the line join
             // falls back to bevel.
             join_type = LineJoin::Bevel;
-        } else if join_type == LineJoin::MiterClip && !self.miter_limit_is_exceeded(normal) {
+        } else if join_type == LineJoin::MiterClip && !miter_limit_is_exceeded {
+            join_type = LineJoin::Miter;
+        } else if join_type != LineJoin::MiterClip && prev_tangent.dot(next_tangent) >= threshold {
+            // The two edges are almost aligned, just use a simple miter join.
+            // TODO: the 0.95 threshold above is completely arbitrary and needs
+            // adjustments.
             join_type = LineJoin::Miter;
         }
 
@@ -653,6 +995,7 @@ impl<'l> StrokeBuilder<'l> {
                         normal: front_normal,
                         advancement: self.length,
                         side: front_side,
+                        kind: self.vertex_kind,
                     }
                 );
                 self.prev_normal = normal;
@@ -685,6 +1028,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: prev_normal * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                kind: self.vertex_kind,
             }
         );
         let last_vertex = add_vertex!(
@@ -694,6 +1038,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: next_normal * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                kind: self.vertex_kind,
             }
         );
         self.prev_normal = next_normal;
@@ -717,9 +1062,9 @@ impl<'l> StrokeBuilder<'l> {
     ) -> (VertexId, VertexId) {
         let join_angle = get_join_angle(prev_tangent, next_tangent);
 
-        let max_radius_segment_angle = compute_max_radius_segment_angle(self.options.line_width / 2.0, self.options.tolerance);
-        let num_segments = (join_angle.abs() as f32 / max_radius_segment_angle).ceil() as u32;
-        debug_assert!(num_segments > 0);
+        // Degenerate joins (for example with a zero angle) still need one segment
+        // to avoid dividing by zero below.
+        let num_segments = ::std::cmp::max(1, self.options.round_segments(join_angle));
         // Calculate angle of each step
         let segment_angle = join_angle as f32 / num_segments as f32;
 
@@ -735,13 +1080,14 @@ impl<'l> StrokeBuilder<'l> {
                 normal: initial_normal,
                 advancement: self.length,
                 side: front_side,
+                kind: self.vertex_kind,
             }
         );
         let start_vertex = last_vertex;
 
         // Plot each point along the radius by using a matrix to
         // rotate the normal at each step
-        let (sin, cos) = segment_angle.sin_cos();
+        let (sin, cos) = sin_cos(segment_angle, self.options.deterministic);
         let rotation_matrix = [
             [cos, sin],
             [-sin, cos],
@@ -762,6 +1108,7 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n,
                     advancement: self.length,
                     side: front_side,
+                    kind: self.vertex_kind,
                 }
             );
 
@@ -801,6 +1148,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: v1 * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                kind: self.vertex_kind,
             }
         );
 
@@ -811,6 +1159,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: v2 * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                kind: self.vertex_kind,
             }
         );
 
@@ -826,13 +1175,31 @@ impl<'l> StrokeBuilder<'l> {
         (start_vertex, last_vertex)
     }
 
-    fn miter_limit_is_exceeded(&self, normal: Vector ) -> bool {
-        normal.square_length() > self.options.miter_limit * self.options.miter_limit
+    // The miter limit expressed as a ratio of the miter length to the stroke width.
+    fn miter_ratio_limit(&self) -> f32 {
+        match self.options.miter_limit_mode {
+            MiterLimitMode::Svg => self.options.miter_limit,
+            MiterLimitMode::HalfWidth => self.options.miter_limit * 0.5,
+        }
+    }
+
+    fn miter_limit_is_exceeded(&self, prev_tangent: Vector, next_tangent: Vector) -> bool {
+        // The ratio of the miter length to the stroke width is 1 / sin(theta / 2) where
+        // theta is the angle between the two segments, and sin(theta / 2)² is
+        // (1 + cos(angle between the tangents)) / 2.
+        //
+        // This doesn't rely on the join's normal which is degenerate when the path
+        // turns back on itself.
+        let sin_sq = (1.0 + prev_tangent.dot(next_tangent)) * 0.5;
+        let limit = self.miter_ratio_limit();
+        sin_sq * limit * limit < 1.0
     }
 
     fn get_clip_intersections(&self, prev_normal: Vector, next_normal: Vector, normal: Vector) -> (Vector, Vector) {
-        let miter_length = self.options.miter_limit * self.options.line_width;
-        let normal_limit = normal.normalize() * miter_length;
+        // Per SVG spec, the miter is clipped at a distance of half of the miter limit
+        // multiplied by the stroke width from the vertex, which is the miter limit in the
+        // space of the normals.
+        let normal_limit = normal.normalize() * self.miter_ratio_limit();
 
         let normal_limit_perp = LineSegment{
             from: point(normal_limit.x - normal_limit.y, normal_limit.y + normal_limit.x),
@@ -853,12 +1220,6 @@ impl<'l> StrokeBuilder<'l> {
     }
 }
 
-// Computes the max angle of a radius segment for a given tolerance
-fn compute_max_radius_segment_angle(radius: f32, tolerance: f32) -> f32 {
-    let t = radius - tolerance;
-    ((radius * radius - t * t) * 4.0).sqrt() / radius
-}
-
 fn get_join_angle(prev_tangent: Vector, next_tangent: Vector) -> f32 {
     let mut join_angle = Trig::fast_atan2(prev_tangent.y, prev_tangent.x) - Trig::fast_atan2(next_tangent.y, next_tangent.x);
 
@@ -872,67 +1233,82 @@ fn get_join_angle(prev_tangent: Vector, next_tangent: Vector) -> f32 {
     join_angle
 }
 
+fn sin_cos(angle: f32, deterministic: bool) -> (f32, f32) {
+    if deterministic {
+        deterministic_sin_cos(angle)
+    } else {
+        angle.sin_cos()
+    }
+}
+
+// The stroke is offset by scaling the normals of each side. For example when stroking a
+// single side of the path, the normals of the stroked side are doubled so that the stroke
+// has the full line width, and the ones of the other side collapse to the path.
+fn alignment_offset(alignment: StrokeAlignment) -> f32 {
+    match alignment {
+        StrokeAlignment::Offset(offset) => offset.clamp(-0.5, 0.5),
+        _ => 0.0,
+    }
+}
+
+fn alignment_depends_on_orientation(alignment: StrokeAlignment) -> bool {
+    matches!(alignment, StrokeAlignment::Inner | StrokeAlignment::Outer)
+}
+
+// Adds the vertices and triangles between va and vb (excluded) on a circular arc.
 fn tess_round_cap(
     center: Point,
     angle: (f32, f32),
-    radius: f32,
-    va: VertexId,
-    vb: VertexId,
-    num_recursions: u32,
+    (va, vb): (VertexId, VertexId),
+    num_segments: u32,
     advancement: f32,
     side: Side,
+    kind: StrokeVertexKind,
     line_width: f32,
+    normal_scale: f32,
     invert_winding: bool,
+    deterministic: bool,
     output: &mut dyn GeometryBuilder<Vertex>
 ) {
-    if num_recursions == 0 {
+    if num_segments < 2 {
         return;
     }
 
-    let mid_angle = (angle.0 + angle.1) * 0.5;
+    let step = (angle.1 - angle.0) / num_segments as f32;
 
-    let normal = vector(mid_angle.cos(), mid_angle.sin());
+    // Triangulate the arc as a fan around va.
+    let mut prev = va;
+    for i in 1..num_segments {
+        let a = angle.0 + step * i as f32;
+        let (sin, cos) = sin_cos(a, deterministic);
+        let normal = vector(cos, sin) * normal_scale;
 
-    let vertex = output.add_vertex(Vertex {
-        position: center + normal * line_width,
-        normal,
-        advancement,
-        side,
-    });
+        let vertex = output.add_vertex(Vertex {
+            position: center + normal * line_width,
+            normal,
+            advancement,
+            side,
+            kind,
+        });
+
+        if i > 1 {
+            let (v1, v2, v3) = if invert_winding {
+                (va, prev, vertex)
+            } else {
+                (va, vertex, prev)
+            };
+            output.add_triangle(v1, v2, v3);
+        }
+
+        prev = vertex;
+    }
 
     let (v1, v2, v3) = if invert_winding {
-        (vertex, vb, va)
+        (va, prev, vb)
     } else {
-        (vertex, va, vb)
+        (va, vb, prev)
     };
     output.add_triangle(v1, v2, v3);
-
-    tess_round_cap(
-        center,
-        (angle.0, mid_angle),
-        radius,
-        va,
-        vertex,
-        num_recursions - 1,
-        advancement,
-        side,
-        line_width,
-        invert_winding,
-        output
-    );
-    tess_round_cap(
-        center,
-        (mid_angle, angle.1),
-        radius,
-        vertex,
-        vb,
-        num_recursions - 1,
-        advancement,
-        side,
-        line_width,
-        invert_winding,
-        output
-    );
 }
 
 #[cfg(test)]
@@ -1104,3 +1480,425 @@ fn test_empty_caps() {
         None,
     );
 }
+
+#[cfg(feature = "profiling")]
+#[test]
+fn test_stats() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut tess = StrokeTessellator::new();
+    let count = tess.tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default(),
+        &mut simple_builder(&mut buffers),
+    );
+
+    let stats = tess.stats();
+    assert_eq!(stats.num_events, 6);
+    assert_eq!(stats.num_sub_paths, 2);
+    assert_eq!(stats.num_triangles, count.indices / 3);
+    assert!(stats.num_triangles > 0);
+}
+
+#[test]
+fn test_vertex_kinds() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::tolerance(0.01)
+            .with_line_cap(LineCap::Round)
+            .with_line_join(LineJoin::Round),
+        &mut simple_builder(&mut buffers),
+    );
+
+    let mut kinds = Vec::new();
+    for vertex in &buffers.vertices {
+        let expected_advancement = match vertex.kind {
+            StrokeVertexKind::StartCap => 0.0,
+            StrokeVertexKind::Join => 10.0,
+            StrokeVertexKind::EndCap => 20.0,
+            StrokeVertexKind::Edge => { continue; }
+        };
+        assert_eq!(vertex.advancement, expected_advancement);
+        if !kinds.contains(&vertex.kind) {
+            kinds.push(vertex.kind);
+        }
+    }
+
+    assert_eq!(kinds.len(), 3);
+}
+
+#[test]
+fn test_caps_per_sub_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.move_to(point(0.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 20.0));
+    builder.close();
+    builder.move_to(point(0.0, 30.0));
+    builder.line_to(point(10.0, 30.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut sub_paths = Vec::new();
+    StrokeTessellator::new().tessellate_path_with_caps(
+        path.path_iter(),
+        &StrokeOptions::default(),
+        |sub_path| {
+            sub_paths.push(sub_path);
+            if sub_path == 2 {
+                (LineCap::Butt, LineCap::Round)
+            } else {
+                (LineCap::Round, LineCap::Round)
+            }
+        },
+        &mut simple_builder(&mut buffers),
+    );
+
+    assert_eq!(sub_paths, vec![0, 1, 2]);
+
+    let mut start_caps = [0; 3];
+    let mut end_caps = [0; 3];
+    for vertex in &buffers.vertices {
+        let y = vertex.position.y;
+        let sub_path = if y < 5.0 { 0 } else if y < 25.0 { 1 } else { 2 };
+        match vertex.kind {
+            StrokeVertexKind::StartCap => { start_caps[sub_path] += 1; }
+            StrokeVertexKind::EndCap => { end_caps[sub_path] += 1; }
+            _ => {}
+        }
+    }
+
+    // Round caps have more than the two vertices of butt caps, and the closed
+    // sub-path has no cap.
+    assert!(start_caps[0] > 2);
+    assert!(end_caps[0] > 2);
+    assert_eq!(start_caps[1], 0);
+    assert_eq!(end_caps[1], 0);
+    assert_eq!(start_caps[2], 2);
+    assert!(end_caps[2] > 2);
+}
+
+#[test]
+fn test_caps_after_close() {
+    // The second sub-path starts at the first point of the closed one.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    builder.line_to(point(0.0, 20.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut sub_paths = Vec::new();
+    StrokeTessellator::new().tessellate_path_with_caps(
+        path.path_iter(),
+        &StrokeOptions::default(),
+        |sub_path| {
+            sub_paths.push(sub_path);
+            if sub_path == 1 {
+                (LineCap::Round, LineCap::Round)
+            } else {
+                (LineCap::Butt, LineCap::Butt)
+            }
+        },
+        &mut simple_builder(&mut buffers),
+    );
+
+    assert_eq!(sub_paths, vec![0, 1]);
+
+    let mut start_caps = 0;
+    let mut end_caps = 0;
+    for vertex in &buffers.vertices {
+        match vertex.kind {
+            StrokeVertexKind::StartCap => { start_caps += 1; }
+            StrokeVertexKind::EndCap => { end_caps += 1; }
+            _ => {}
+        }
+    }
+
+    // Round caps have more than the two vertices of butt caps.
+    assert!(start_caps > 2);
+    assert!(end_caps > 2);
+}
+
+#[test]
+fn test_round_segments() {
+    let thin = StrokeOptions::tolerance(0.1).with_line_width(1.0);
+    let fat = StrokeOptions::tolerance(0.1).with_line_width(100.0);
+    assert!(thin.round_segments(PI) < fat.round_segments(PI));
+    assert!(fat.round_segments(PI * 0.5) < fat.round_segments(PI));
+    assert_eq!(fat.with_round_segment_limits(1, 8).round_segments(PI), 8);
+    assert_eq!(thin.with_round_segment_limits(16, 32).round_segments(PI), 16);
+    assert_eq!(thin.with_line_width(0.0).round_segments(PI), 1);
+
+    // With a distance to the arc of at most the tolerance.
+    let n = fat.round_segments(PI) as f32;
+    let sagitta = 50.0 * (1.0 - (PI / n * 0.5).cos());
+    assert!(sagitta <= 0.1);
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    // Two triangles for the line and 1 + 3 + 3 triangles for each cap.
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default()
+            .with_line_cap(LineCap::Round)
+            .with_round_segment_limits(4, 4),
+        Some(16),
+    );
+}
+
+#[test]
+fn test_miter_limit() {
+    let mut builder = Path::builder();
+    builder.move_to(point(-1.0, 1.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(1.0, -1.0));
+    builder.line_to(point(-1.0, -1.0));
+    builder.close();
+    let path = builder.build();
+
+    // The miter length of right angles is sqrt(2) times the stroke width.
+    let miter = Some(8);
+    let bevel = Some(12);
+    let options = StrokeOptions::default().with_line_join(LineJoin::Miter);
+    test_path(path.as_slice(), &options.with_miter_limit(1.5), miter);
+    test_path(path.as_slice(), &options.with_miter_limit(1.4), bevel);
+
+    let options = options.with_miter_limit_mode(MiterLimitMode::HalfWidth);
+    test_path(path.as_slice(), &options.with_miter_limit(2.9), miter);
+    test_path(path.as_slice(), &options.with_miter_limit(2.8), bevel);
+
+    // A path that turns back on itself exceeds any miter limit.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(0.0, 0.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default().with_miter_limit(100.0),
+        &mut simple_builder(&mut buffers),
+    );
+    for vertex in &buffers.vertices {
+        assert!(vertex.position.x <= 10.5);
+    }
+}
+
+#[test]
+fn test_one_sided() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(20.0, 5.0));
+    let path = builder.build();
+
+    let mut sign = 0.0;
+    for &side in &[Side::Left, Side::Right] {
+        for &join in &[LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+            StrokeTessellator::new().tessellate_path(
+                path.path_iter(),
+                &StrokeOptions::default()
+                    .with_line_width(2.0)
+                    .with_line_join(join)
+                    .with_one_sided(side),
+                &mut simple_builder(&mut buffers),
+            );
+
+            // Look at the vertices of the first edge.
+            for vertex in &buffers.vertices {
+                if vertex.position.x > 5.0 {
+                    continue;
+                }
+                if vertex.side == side {
+                    if sign == 0.0 {
+                        sign = vertex.position.y.signum();
+                    }
+                    let expected = if side == Side::Left { sign * 2.0 } else { -sign * 2.0 };
+                    assert_eq!(vertex.position.y, expected);
+                } else {
+                    assert_eq!(vertex.position.y, 0.0);
+                }
+            }
+        }
+    }
+    assert!(sign != 0.0);
+}
+
+#[test]
+fn test_stroke_alignment() {
+    let mut cw = Path::builder();
+    cw.move_to(point(0.0, 0.0));
+    cw.line_to(point(10.0, 0.0));
+    cw.line_to(point(10.0, 10.0));
+    cw.line_to(point(0.0, 10.0));
+    cw.close();
+    let cw = cw.build();
+
+    let mut ccw = Path::builder();
+    ccw.move_to(point(0.0, 0.0));
+    ccw.line_to(point(0.0, 10.0));
+    ccw.line_to(point(10.0, 10.0));
+    ccw.line_to(point(10.0, 0.0));
+    ccw.close();
+    let ccw = ccw.build();
+
+    let hole = rect(0.01, 0.01, 9.98, 9.98);
+
+    for path in &[cw, ccw] {
+        for &join in &[LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let options = StrokeOptions::default().with_line_width(2.0).with_line_join(join);
+
+            let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+            StrokeTessellator::new().tessellate_path(
+                path.path_iter(),
+                &options.with_alignment(StrokeAlignment::Inner),
+                &mut simple_builder(&mut buffers),
+            );
+            for vertex in &buffers.vertices {
+                let p = vertex.position;
+                assert!(p.x >= -0.001 && p.y >= -0.001 && p.x <= 10.001 && p.y <= 10.001, "{:?}", p);
+            }
+            // Some vertices are on the inner side of the band.
+            assert!(buffers.vertices.iter().any(|v| hole.contains(&v.position)));
+
+            let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+            StrokeTessellator::new().tessellate_path(
+                path.path_iter(),
+                &options.with_alignment(StrokeAlignment::Outer),
+                &mut simple_builder(&mut buffers),
+            );
+            for vertex in &buffers.vertices {
+                let p = vertex.position;
+                assert!(!hole.contains(&p), "{:?}", p);
+                assert!(p.x >= -2.001 && p.y >= -2.001 && p.x <= 12.001 && p.y <= 12.001, "{:?}", p);
+            }
+            // Some vertices are on the outer side of the band.
+            assert!(buffers.vertices.iter().any(|v| v.position.x < -1.0 || v.position.y < -1.0));
+        }
+    }
+
+    // Offsetting by half of the line width is the same as stroking one side.
+    let mut a: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut b: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut path = Path::builder();
+    path.move_to(point(0.0, 0.0));
+    path.line_to(point(10.0, 0.0));
+    path.line_to(point(20.0, 5.0));
+    let path = path.build();
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default().with_alignment(StrokeAlignment::Offset(-0.5)),
+        &mut simple_builder(&mut a),
+    );
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default().with_one_sided(Side::Right),
+        &mut simple_builder(&mut b),
+    );
+    assert_eq!(a.vertices, b.vertices);
+    assert_eq!(a.indices, b.indices);
+
+    // Offsets outside of [-0.5, 0.5] are clamped.
+    let mut c: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default().with_alignment(StrokeAlignment::Offset(-3.0)),
+        &mut simple_builder(&mut c),
+    );
+    assert_eq!(a.vertices, c.vertices);
+    assert_eq!(a.indices, c.indices);
+}
+
+#[test]
+fn test_ids_per_sub_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.move_to(point(0.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 20.0));
+    builder.close();
+    builder.move_to(point(0.0, 30.0));
+    builder.line_to(point(10.0, 30.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Vertex, u32), u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path_with_ids(
+        path.path_iter(),
+        &StrokeOptions::default().with_line_cap(LineCap::Round),
+        |sub_path| 100 + sub_path,
+        &mut simple_builder(&mut buffers),
+    );
+
+    let mut counts = [0; 3];
+    for &(vertex, id) in &buffers.vertices {
+        let y = vertex.position.y;
+        let sub_path = if y < 5.0 { 0 } else if y < 25.0 { 1 } else { 2 };
+        assert_eq!(id, 100 + sub_path as u32);
+        counts[sub_path] += 1;
+    }
+
+    assert!(counts.iter().all(|&count| count > 0));
+}
+
+#[test]
+fn test_deterministic() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(15.0, 5.0), point(10.0, 10.0));
+    builder.cubic_bezier_to(point(5.0, 15.0), point(0.0, 5.0), point(3.0, 3.0));
+    builder.arc(point(0.0, 3.0), vector(3.0, 3.0), Angle::radians(2.0), Angle::radians(0.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Round)
+        .with_line_cap(LineCap::Round)
+        .deterministic();
+
+    let mut a: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    let mut b: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut a));
+    StrokeTessellator::new().tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut b));
+    assert_eq!(a.vertices, b.vertices);
+    assert_eq!(a.indices, b.indices);
+    assert!(!a.indices.is_empty());
+
+    // Same number of segments in round joins as without the deterministic mode, give or
+    // take rounding errors at the boundaries.
+    let mut default_options = options;
+    default_options.deterministic = false;
+    for i in 1..100 {
+        let angle = i as f32 * 0.06;
+        let n1 = options.round_segments(angle) as i32;
+        let n2 = default_options.round_segments(angle) as i32;
+        assert!((n1 - n2).abs() <= 1);
+    }
+}
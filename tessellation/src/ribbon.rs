@@ -0,0 +1,257 @@
+//! Cheap stroke-like meshes for paths with a large number of points.
+//!
+//! A ribbon is a strip of two triangles per flattened edge, with a pair of vertices on each
+//! side of every point of the path. The vertices are extruded along the average of the
+//! normals of the neighbouring edges, without the joins and caps that the stroke tessellator
+//! generates. This keeps the number of vertices and the amount of work per point constant,
+//! which is a good fit for the lines of plots and charts where the edges are short and
+//! mostly aligned, but produces visible artifacts at sharp angles.
+//!
+//! Like with the stroke tessellator, the vertices are positioned on the path and the
+//! consumer is expected to move them along the provided normal to give the ribbon a width,
+//! for example in a `VertexConstructor` or in a vertex shader.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::ribbon::tessellate_polyline_ribbon;
+//! use tess::{StrokeVertex, VertexBuffers};
+//! use tess::geometry_builder::simple_builder;
+//! use tess::math::point;
+//!
+//! fn main() {
+//!     let values = [1.0, 3.0, 2.0, 5.0, 4.0];
+//!     let points = values.iter().enumerate().map(|(i, v)| point(i as f32, *v));
+//!
+//!     let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+//!     tessellate_polyline_ribbon(points, false, &mut simple_builder(&mut buffers));
+//!
+//!     assert_eq!(buffers.vertices.len(), 10);
+//!     assert_eq!(buffers.indices.len(), 24);
+//! }
+//! ```
+
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use math::{Point, Vector, vector};
+use math_utils::compute_normal;
+use geometry_builder::{GeometryBuilder, Count};
+use {StrokeVertex, StrokeVertexKind, Side};
+
+// Limits the length of the normals at sharp angles, where they would otherwise grow
+// towards infinity.
+const MAX_NORMAL_LENGTH: f32 = 4.0;
+
+// Points closer to the previous one than this are ignored.
+const MIN_EDGE_LENGTH: f32 = 1e-5;
+
+/// Flattens a path and generates a ribbon along it.
+///
+/// The `advancement` of the vertices is the length of the path up to the vertex, including
+/// the previous sub-paths.
+pub fn tessellate_ribbon<Iter>(
+    path: Iter,
+    tolerance: f32,
+    output: &mut dyn GeometryBuilder<StrokeVertex>,
+) -> Count
+where
+    Iter: PathIterator,
+{
+    output.begin_geometry();
+
+    let mut points = Vec::new();
+    let mut advancement = 0.0;
+    for evt in path.flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                add_strip(&points, false, &mut advancement, output);
+                points.clear();
+                points.push(to);
+            }
+            FlattenedEvent::LineTo(to) => {
+                add_point(&mut points, to);
+            }
+            FlattenedEvent::Close => {
+                add_strip(&points, true, &mut advancement, output);
+                // Subsequent edges start at the beginning of the closed sub-path.
+                let first = points.first().cloned();
+                points.clear();
+                points.extend(first);
+            }
+        }
+    }
+    add_strip(&points, false, &mut advancement, output);
+
+    output.end_geometry()
+}
+
+/// Generates a ribbon along a sequence of points.
+///
+/// Convenient when the line is already flattened, for example the data points of a plot.
+pub fn tessellate_polyline_ribbon<Iter>(
+    polyline: Iter,
+    is_closed: bool,
+    output: &mut dyn GeometryBuilder<StrokeVertex>,
+) -> Count
+where
+    Iter: Iterator<Item = Point>,
+{
+    output.begin_geometry();
+
+    let mut points = Vec::with_capacity(polyline.size_hint().0);
+    for p in polyline {
+        add_point(&mut points, p);
+    }
+    let mut advancement = 0.0;
+    add_strip(&points, is_closed, &mut advancement, output);
+
+    output.end_geometry()
+}
+
+fn add_point(points: &mut Vec<Point>, p: Point) {
+    let is_duplicate = match points.last() {
+        Some(last) => (p - *last).square_length() < MIN_EDGE_LENGTH * MIN_EDGE_LENGTH,
+        None => false,
+    };
+    if !is_duplicate {
+        points.push(p);
+    }
+}
+
+fn add_strip(
+    points: &[Point],
+    is_closed: bool,
+    advancement: &mut f32,
+    output: &mut dyn GeometryBuilder<StrokeVertex>,
+) {
+    let mut n = points.len();
+    if is_closed && n > 1 && (points[n - 1] - points[0]).square_length() < MIN_EDGE_LENGTH * MIN_EDGE_LENGTH {
+        n -= 1;
+    }
+    if n < 2 {
+        return;
+    }
+
+    let tangent = |i: usize| (points[(i + 1) % n] - points[i]).normalize();
+
+    // Closed strips visit the first point again at the end so that the vertices of the
+    // last edge have the right advancement.
+    let count = if is_closed { n + 1 } else { n };
+    let mut previous_ids = None;
+    for i in 0..count {
+        let idx = i % n;
+        let normal = if is_closed {
+            compute_normal(tangent((idx + n - 1) % n), tangent(idx))
+        } else if idx == 0 {
+            left_normal(tangent(0))
+        } else if idx == n - 1 {
+            left_normal(tangent(n - 2))
+        } else {
+            compute_normal(tangent(idx - 1), tangent(idx))
+        };
+        let normal = clamp_length(normal, MAX_NORMAL_LENGTH);
+
+        if i > 0 {
+            *advancement += (points[idx] - points[i - 1]).length();
+        }
+
+        let left_id = output.add_vertex(StrokeVertex {
+            position: points[idx],
+            normal,
+            advancement: *advancement,
+            side: Side::Left,
+            kind: StrokeVertexKind::Edge,
+        });
+        let right_id = output.add_vertex(StrokeVertex {
+            position: points[idx],
+            normal: -normal,
+            advancement: *advancement,
+            side: Side::Right,
+            kind: StrokeVertexKind::Edge,
+        });
+
+        if let Some((previous_left_id, previous_right_id)) = previous_ids {
+            output.add_triangle(previous_right_id, previous_left_id, right_id);
+            output.add_triangle(previous_left_id, left_id, right_id);
+        }
+        previous_ids = Some((left_id, right_id));
+    }
+}
+
+fn left_normal(tangent: Vector) -> Vector {
+    vector(-tangent.y, tangent.x)
+}
+
+fn clamp_length(v: Vector, max_length: f32) -> Vector {
+    let length = v.length();
+    if length > max_length {
+        return v * (max_length / length);
+    }
+
+    v
+}
+
+#[test]
+fn ribbon_polyline() {
+    use geometry_builder::{VertexBuffers, simple_builder};
+    use math::point;
+
+    let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+    let count = tessellate_polyline_ribbon(
+        vec![
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(10.0, 0.0),
+            point(10.0, 10.0),
+        ].into_iter(),
+        false,
+        &mut simple_builder(&mut buffers),
+    );
+
+    // The duplicated point is skipped.
+    assert_eq!(count, Count { vertices: 6, indices: 12 });
+
+    let v = &buffers.vertices;
+    assert_eq!(v[0].normal, vector(0.0, 1.0));
+    assert_eq!(v[1].normal, vector(0.0, -1.0));
+    assert_eq!(v[1].side, Side::Right);
+    assert!((v[2].normal - vector(-1.0, 1.0)).length() < 1e-5);
+    assert_eq!(v[4].normal, vector(-1.0, 0.0));
+    assert_eq!(v[2].advancement, 10.0);
+    assert_eq!(v[5].advancement, 20.0);
+}
+
+#[test]
+fn ribbon_closed_path() {
+    use geometry_builder::{VertexBuffers, simple_builder};
+    use path::default::Path;
+    use path::builder::*;
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.line_to(point(3.0, 4.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+    let count = tessellate_ribbon(path.path_iter(), 0.1, &mut simple_builder(&mut buffers));
+
+    // 4 pairs of vertices for the closed triangle, 2 for the open line.
+    assert_eq!(count, Count { vertices: 12, indices: 24 });
+
+    let v = &buffers.vertices;
+    // The vertices at the end of the triangle are at the same position as the first ones.
+    assert_eq!(v[6].position, v[0].position);
+    assert_eq!(v[6].normal, v[0].normal);
+    assert_eq!(v[6].advancement, 12.0);
+    assert_eq!(v[8].advancement, 12.0);
+    assert_eq!(v[10].advancement, 13.0);
+    for vertex in v {
+        assert!(vertex.normal.length() <= MAX_NORMAL_LENGTH + 1e-5);
+    }
+}
@@ -0,0 +1,274 @@
+//! Geometry for stencil-then-cover path rendering.
+//!
+//! Instead of tessellating the interior of a path, this generates geometry that is
+//! rendered in two passes:
+//!
+//! - The stencil pass renders triangles fanning from a pivot point to each segment of
+//!   the path, along with a triangle for each curve, incrementing the stencil buffer for
+//!   front-facing triangles and decrementing it for back-facing ones (or inverting it
+//!   for the even-odd fill rule). Fragments for which `u * u - v > 0` must be discarded,
+//!   which carves the curves out of their triangles following Loop and Blinn's
+//!   "Resolution Independent Curve Rendering using Programmable Graphics Hardware".
+//! - The cover pass renders a quad that contains the whole path, shading the fragments
+//!   that have a non-zero stencil value (and resetting the stencil buffer).
+//!
+//! The geometry doesn't depend on the scale at which the path is rendered, since curves
+//! are evaluated per fragment.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{VertexBuffers, geometry_builder::simple_builder};
+//! use tess::stencil_cover::{StencilVertex, stencil_and_cover};
+//! use tess::math::{point, Point};
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.quadratic_bezier_to(point(10.0, -10.0), point(20.0, 0.0));
+//!     builder.line_to(point(10.0, 20.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     let mut stencil: VertexBuffers<StencilVertex, u16> = VertexBuffers::new();
+//!     let mut cover: VertexBuffers<Point, u16> = VertexBuffers::new();
+//!     stencil_and_cover(
+//!         path.path_iter(),
+//!         0.1,
+//!         &mut simple_builder(&mut stencil),
+//!         &mut simple_builder(&mut cover),
+//!     );
+//!
+//!     assert_eq!(cover.indices.len(), 6);
+//! }
+//! ```
+
+use path::PathEvent;
+use path::iterator::PathIterator;
+use math::{Point, point};
+use geom::{QuadraticBezierSegment, Arc};
+use geometry_builder::{GeometryBuilder, Count, VertexId};
+use loop_blinn::quadratic_uv;
+
+/// A vertex of the stencil geometry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct StencilVertex {
+    pub position: Point,
+    /// The coordinates in the space of the canonical quadratic curve `u * u - v = 0`.
+    ///
+    /// Fragments are inside of the path if `u * u - v <= 0`. The vertices of the fan
+    /// triangles use `(0, 1)` so that all of their fragments pass this test.
+    pub uv: Point,
+}
+
+/// Generates the stencil and cover geometry of a path.
+///
+/// Cubic bézier curves are approximated with quadratic ones with the provided tolerance.
+/// Sub-paths are implicitly closed. The cover geometry is a quad containing the control
+/// points of the path.
+///
+/// Returns the number of vertices and indices added to the stencil and cover outputs.
+pub fn stencil_and_cover<Iter: PathIterator>(
+    mut path: Iter,
+    tolerance: f32,
+    stencil: &mut dyn GeometryBuilder<StencilVertex>,
+    cover: &mut dyn GeometryBuilder<Point>,
+) -> (Count, Count) {
+    let mut output = StencilOutput {
+        builder: stencil,
+        pivot: None,
+        current: None,
+        min: point(f32::MAX, f32::MAX),
+        max: point(f32::MIN, f32::MIN),
+    };
+
+    output.builder.begin_geometry();
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                output.move_to(to);
+            }
+            Some(PathEvent::LineTo(to)) => {
+                output.edge(from, to);
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                output.quadratic_bezier(&QuadraticBezierSegment { from, ctrl, to });
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                let cubic = ::geom::CubicBezierSegment { from, ctrl1, ctrl2, to };
+                cubic.for_each_quadratic_bezier(tolerance, &mut |quad| {
+                    output.quadratic_bezier(quad);
+                });
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |quad| {
+                    output.quadratic_bezier(quad);
+                });
+            }
+            Some(PathEvent::Close) => {
+                // The edge that closes the sub-path ends at the pivot, so its fan
+                // triangle is empty.
+                output.current = None;
+            }
+            None => {
+                break;
+            }
+        }
+    }
+    let stencil_count = output.builder.end_geometry();
+
+    cover.begin_geometry();
+    if output.min.x <= output.max.x {
+        let (min, max) = (output.min, output.max);
+        let a = cover.add_vertex(min);
+        let b = cover.add_vertex(point(max.x, min.y));
+        let c = cover.add_vertex(max);
+        let d = cover.add_vertex(point(min.x, max.y));
+        cover.add_triangle(a, b, c);
+        cover.add_triangle(a, c, d);
+    }
+    let cover_count = cover.end_geometry();
+
+    (stencil_count, cover_count)
+}
+
+struct StencilOutput<'l> {
+    builder: &'l mut dyn GeometryBuilder<StencilVertex>,
+    pivot: Option<(Point, VertexId)>,
+    // The fan vertex at the current position.
+    current: Option<(Point, VertexId)>,
+    min: Point,
+    max: Point,
+}
+
+impl<'l> StencilOutput<'l> {
+    fn move_to(&mut self, to: Point) {
+        self.pivot = None;
+        self.current = None;
+        self.extend_bounds(to);
+    }
+
+    fn fan_vertex(&mut self, position: Point) -> VertexId {
+        self.builder.add_vertex(StencilVertex { position, uv: point(0.0, 1.0) })
+    }
+
+    fn edge(&mut self, from: Point, to: Point) {
+        self.extend_bounds(from);
+        self.extend_bounds(to);
+
+        let pivot = match self.pivot {
+            Some(pivot) => pivot,
+            None => {
+                let id = self.fan_vertex(from);
+                self.pivot = Some((from, id));
+                self.current = Some((from, id));
+                (from, id)
+            }
+        };
+
+        let a = match self.current {
+            Some((p, id)) if p == from => id,
+            _ => self.fan_vertex(from),
+        };
+        let b = self.fan_vertex(to);
+        self.current = Some((to, b));
+
+        if (from - pivot.0).cross(to - pivot.0) != 0.0 {
+            self.builder.add_triangle(pivot.1, a, b);
+        }
+    }
+
+    fn quadratic_bezier(&mut self, curve: &QuadraticBezierSegment<f32>) {
+        self.edge(curve.from, curve.to);
+        self.extend_bounds(curve.ctrl);
+
+        if (curve.ctrl - curve.from).cross(curve.to - curve.from) == 0.0 {
+            return;
+        }
+
+        let uv = quadratic_uv();
+        let a = self.builder.add_vertex(StencilVertex { position: curve.from, uv: uv[0] });
+        let b = self.builder.add_vertex(StencilVertex { position: curve.ctrl, uv: uv[1] });
+        let c = self.builder.add_vertex(StencilVertex { position: curve.to, uv: uv[2] });
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn extend_bounds(&mut self, p: Point) {
+        self.min = point(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = point(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+}
+
+#[cfg(test)]
+use geometry_builder::{VertexBuffers, simple_builder};
+
+// Evaluates the stencil value at a given position, with the non-zero fill rule.
+#[cfg(test)]
+fn stencil_value(buffers: &VertexBuffers<StencilVertex, u16>, p: Point) -> i32 {
+    let mut value = 0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        let area = (b.position - a.position).cross(c.position - a.position);
+        let wa = (c.position - b.position).cross(p - b.position) / area;
+        let wb = (a.position - c.position).cross(p - c.position) / area;
+        let wc = 1.0 - wa - wb;
+        if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+            continue;
+        }
+
+        let u = a.uv.x * wa + b.uv.x * wb + c.uv.x * wc;
+        let v = a.uv.y * wa + b.uv.y * wb + c.uv.y * wc;
+        if u * u - v > 0.0 {
+            continue;
+        }
+
+        value += if area > 0.0 { 1 } else { -1 };
+    }
+
+    value
+}
+
+#[test]
+fn stencil_cover_square_with_curve() {
+    use path::default::Path;
+    use path::builder::*;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(15.0, 5.0), point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut stencil: VertexBuffers<StencilVertex, u16> = VertexBuffers::new();
+    let mut cover: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let (stencil_count, cover_count) = stencil_and_cover(
+        path.path_iter(),
+        0.01,
+        &mut simple_builder(&mut stencil),
+        &mut simple_builder(&mut cover),
+    );
+
+    // Two fan triangles and one curve triangle.
+    assert_eq!(stencil_count.indices, 9);
+    assert_eq!(cover_count.vertices, 4);
+    assert_eq!(cover_count.indices, 6);
+    assert!(cover.vertices.contains(&point(0.0, 0.0)));
+    assert!(cover.vertices.contains(&point(15.0, 10.0)));
+
+    assert!(stencil_value(&stencil, point(5.0, 5.0)) != 0);
+    assert!(stencil_value(&stencil, point(12.0, 5.0)) != 0);
+    assert_eq!(stencil_value(&stencil, point(13.0, 5.0)), 0);
+    assert_eq!(stencil_value(&stencil, point(11.0, 1.0)), 0);
+    assert_eq!(stencil_value(&stencil, point(-1.0, 5.0)), 0);
+}
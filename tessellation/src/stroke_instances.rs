@@ -0,0 +1,264 @@
+//! Per-segment instance data for expanding strokes on the GPU.
+//!
+//! Instead of generating the triangles of a stroke, this produces one record per segment
+//! of the path containing the segment's control points, the line width and what happens at
+//! each of its ends (a join with the neighbouring segment or a cap). Each record is meant
+//! to be rendered as an instanced quad that is expanded and shaded in the shaders.
+//!
+//! Quadratic and cubic bézier curves are not flattened, arcs are approximated with
+//! quadratic bézier curves.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::stroke_instances::{SegmentEnd, stroke_instances};
+//! use tess::{StrokeOptions, LineCap};
+//! use tess::math::point;
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(point(0.0, 0.0));
+//!     builder.line_to(point(10.0, 0.0));
+//!     builder.quadratic_bezier_to(point(15.0, 5.0), point(10.0, 10.0));
+//!     let path = builder.build();
+//!
+//!     let options = StrokeOptions::default().with_line_cap(LineCap::Round);
+//!
+//!     let mut instances = Vec::new();
+//!     stroke_instances(path.path_iter(), &options, &mut |instance| {
+//!         instances.push(*instance);
+//!     });
+//!
+//!     assert_eq!(instances.len(), 2);
+//!     assert_eq!(instances[0].start, SegmentEnd::Cap(LineCap::Round));
+//!     assert_eq!(instances[1].end, SegmentEnd::Cap(LineCap::Round));
+//! }
+//! ```
+
+use path::{PathEvent, Segment};
+use path::iterator::PathIterator;
+use math::{Point, Vector, vector};
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc};
+use {StrokeOptions, LineCap, LineJoin};
+
+/// What happens at one of the ends of a segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SegmentEnd {
+    /// The end of an open sub-path.
+    Cap(LineCap),
+    /// The segment is connected to another one.
+    Join {
+        join: LineJoin,
+        /// The direction of the path on the other side of the join.
+        ///
+        /// At the start of a segment, this is the direction at the end of the previous
+        /// segment, and at the end of a segment, the direction at the start of the next one.
+        tangent: Vector,
+    },
+}
+
+/// The instance data of a segment of a stroke.
+#[derive(Copy, Clone, Debug)]
+pub struct StrokeInstance {
+    /// A line segment, a quadratic or a cubic bézier curve (never an arc).
+    pub segment: Segment,
+    pub line_width: f32,
+    pub start: SegmentEnd,
+    pub end: SegmentEnd,
+}
+
+impl StrokeInstance {
+    /// Returns the segment as a cubic bézier curve.
+    ///
+    /// Useful to render all segments with the same shader.
+    pub fn to_cubic(&self) -> CubicBezierSegment<f32> {
+        match self.segment {
+            Segment::Line(s) => CubicBezierSegment {
+                from: s.from,
+                ctrl1: s.sample(1.0 / 3.0),
+                ctrl2: s.sample(2.0 / 3.0),
+                to: s.to,
+            },
+            Segment::Quadratic(s) => s.to_cubic(),
+            Segment::Cubic(s) => s,
+            Segment::Arc(_) => unreachable!(),
+        }
+    }
+
+    /// The position at the start of the segment.
+    pub fn from(&self) -> Point {
+        segment_endpoints(&self.segment).0
+    }
+
+    /// The position at the end of the segment.
+    pub fn to(&self) -> Point {
+        segment_endpoints(&self.segment).1
+    }
+}
+
+/// Calls the callback with the instance data of each segment of the stroke of a path.
+///
+/// Segments of zero length are skipped, and so are sub-paths that only contain such
+/// segments.
+pub fn stroke_instances<Iter, F>(mut path: Iter, options: &StrokeOptions, cb: &mut F)
+where
+    Iter: PathIterator,
+    F: FnMut(&StrokeInstance),
+{
+    let mut segments = Vec::new();
+    let mut first = Point::new(0.0, 0.0);
+
+    loop {
+        let from = path.get_state().current;
+        match path.next() {
+            Some(PathEvent::MoveTo(to)) => {
+                end_sub_path(&mut segments, false, options, cb);
+                first = to;
+            }
+            Some(PathEvent::LineTo(to)) => {
+                add_segment(&mut segments, Segment::Line(LineSegment { from, to }));
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                add_segment(&mut segments, Segment::Quadratic(QuadraticBezierSegment { from, ctrl, to }));
+            }
+            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+                add_segment(&mut segments, Segment::Cubic(CubicBezierSegment { from, ctrl1, ctrl2, to }));
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let start_angle = (from - center).angle_from_x_axis() - x_rotation;
+                let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    add_segment(&mut segments, Segment::Quadratic(*curve));
+                });
+            }
+            Some(PathEvent::Close) => {
+                add_segment(&mut segments, Segment::Line(LineSegment { from, to: first }));
+                end_sub_path(&mut segments, true, options, cb);
+            }
+            None => {
+                end_sub_path(&mut segments, false, options, cb);
+                return;
+            }
+        }
+    }
+}
+
+fn add_segment(segments: &mut Vec<Segment>, segment: Segment) {
+    if start_tangent(&segment) != vector(0.0, 0.0) {
+        segments.push(segment);
+    }
+}
+
+fn end_sub_path<F>(segments: &mut Vec<Segment>, closed: bool, options: &StrokeOptions, cb: &mut F)
+where F: FnMut(&StrokeInstance) {
+    let n = segments.len();
+    for i in 0..n {
+        let start = if i > 0 {
+            SegmentEnd::Join { join: options.line_join, tangent: end_tangent(&segments[i - 1]) }
+        } else if closed {
+            SegmentEnd::Join { join: options.line_join, tangent: end_tangent(&segments[n - 1]) }
+        } else {
+            SegmentEnd::Cap(options.start_cap)
+        };
+
+        let end = if i + 1 < n {
+            SegmentEnd::Join { join: options.line_join, tangent: start_tangent(&segments[i + 1]) }
+        } else if closed {
+            SegmentEnd::Join { join: options.line_join, tangent: start_tangent(&segments[0]) }
+        } else {
+            SegmentEnd::Cap(options.end_cap)
+        };
+
+        cb(&StrokeInstance {
+            segment: segments[i],
+            line_width: options.line_width,
+            start,
+            end,
+        });
+    }
+
+    segments.clear();
+}
+
+fn segment_endpoints(segment: &Segment) -> (Point, Point) {
+    match *segment {
+        Segment::Line(s) => (s.from, s.to),
+        Segment::Quadratic(s) => (s.from, s.to),
+        Segment::Cubic(s) => (s.from, s.to),
+        Segment::Arc(s) => (s.from(), s.to()),
+    }
+}
+
+fn start_tangent(segment: &Segment) -> Vector {
+    match *segment {
+        Segment::Line(s) => s.to - s.from,
+        Segment::Quadratic(s) => first_non_zero(&[s.ctrl - s.from, s.to - s.from]),
+        Segment::Cubic(s) => first_non_zero(&[s.ctrl1 - s.from, s.ctrl2 - s.from, s.to - s.from]),
+        Segment::Arc(_) => unreachable!(),
+    }
+}
+
+fn end_tangent(segment: &Segment) -> Vector {
+    match *segment {
+        Segment::Line(s) => s.to - s.from,
+        Segment::Quadratic(s) => first_non_zero(&[s.to - s.ctrl, s.to - s.from]),
+        Segment::Cubic(s) => first_non_zero(&[s.to - s.ctrl2, s.to - s.ctrl1, s.to - s.from]),
+        Segment::Arc(_) => unreachable!(),
+    }
+}
+
+fn first_non_zero(vectors: &[Vector]) -> Vector {
+    for v in vectors {
+        if *v != vector(0.0, 0.0) {
+            return *v;
+        }
+    }
+
+    vector(0.0, 0.0)
+}
+
+#[test]
+fn stroke_instances_closed_path() {
+    use path::default::Path;
+    use path::builder::*;
+    use math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 5.0), point(5.0, 10.0), point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Round)
+        .with_line_cap(LineCap::Square);
+
+    let mut instances = Vec::new();
+    stroke_instances(path.path_iter(), &options, &mut |instance| {
+        instances.push(*instance);
+    });
+
+    // The zero length segment is skipped and the closing edge is added.
+    assert_eq!(instances.len(), 4);
+
+    assert_eq!(instances[0].from(), point(0.0, 0.0));
+    assert_eq!(instances[0].start, SegmentEnd::Join { join: LineJoin::Round, tangent: vector(0.0, -10.0) });
+    assert_eq!(instances[0].end, SegmentEnd::Join { join: LineJoin::Round, tangent: vector(0.0, 5.0) });
+    assert_eq!(instances[1].start, SegmentEnd::Join { join: LineJoin::Round, tangent: vector(10.0, 0.0) });
+    assert_eq!(instances[1].end, SegmentEnd::Join { join: LineJoin::Round, tangent: vector(0.0, -10.0) });
+    assert_eq!(instances[2].to(), point(0.0, 0.0));
+    assert_eq!(instances[2].end, SegmentEnd::Join { join: LineJoin::Round, tangent: vector(10.0, 0.0) });
+
+    assert_eq!(instances[3].start, SegmentEnd::Cap(LineCap::Square));
+    assert_eq!(instances[3].end, SegmentEnd::Cap(LineCap::Square));
+    assert_eq!(instances[3].line_width, 2.0);
+    assert_eq!(instances[3].to_cubic().sample(0.5), point(25.0, 0.0));
+}
@@ -0,0 +1,206 @@
+//! Helpers to generate texture coordinates for the tessellated geometry.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate lyon_tessellation as tess;
+//! use tess::{FillTessellator, FillOptions, FillVertex, VertexBuffers, BuffersBuilder};
+//! use tess::uv::{UvMapping, AspectRatio, WithUv};
+//! use tess::math::{Point, rect};
+//! use tess::path::default::Path;
+//! use tess::path::builder::*;
+//!
+//! #[derive(Copy, Clone, Debug)]
+//! struct MyVertex { position: [f32; 2], uv: [f32; 2] }
+//!
+//! fn main() {
+//!     let mut builder = Path::builder();
+//!     builder.move_to(Point::new(0.0, 0.0));
+//!     builder.line_to(Point::new(20.0, 0.0));
+//!     builder.line_to(Point::new(20.0, 10.0));
+//!     builder.close();
+//!     let path = builder.build();
+//!
+//!     // Map the bounding box of the path to the [0, 1] range, preserving the aspect ratio.
+//!     let mapping = UvMapping::from_rect(&rect(0.0, 0.0, 20.0, 10.0), AspectRatio::Fit);
+//!
+//!     let mut buffers: VertexBuffers<MyVertex, u16> = VertexBuffers::new();
+//!     FillTessellator::new().tessellate_path(
+//!         path.path_iter(),
+//!         &FillOptions::default(),
+//!         &mut BuffersBuilder::new(
+//!             &mut buffers,
+//!             WithUv::new(mapping, |vertex: FillVertex, uv: Point| MyVertex {
+//!                 position: vertex.position.to_array(),
+//!                 uv: uv.to_array(),
+//!             }),
+//!         ),
+//!     ).unwrap();
+//!
+//!     for vertex in &buffers.vertices {
+//!         assert!(vertex.uv[0] >= 0.0 && vertex.uv[0] <= 1.0);
+//!         assert!(vertex.uv[1] >= 0.25 && vertex.uv[1] <= 0.75);
+//!     }
+//! }
+//! ```
+
+use {FillVertex, StrokeVertex};
+use geometry_builder::VertexConstructor;
+use math::*;
+
+/// How to map a rectangle that isn't square to the [0, 1] texture coordinate range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum AspectRatio {
+    /// The rectangle is stretched to cover exactly the [0, 1] range on both axis.
+    Stretch,
+    /// The aspect ratio is preserved and the largest side of the rectangle spans
+    /// the [0, 1] range. The rectangle is centered on the other axis.
+    Fit,
+    /// The aspect ratio is preserved and the smallest side of the rectangle spans
+    /// the [0, 1] range. The rectangle is centered on the other axis.
+    Fill,
+}
+
+/// Computes texture coordinates from vertex positions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct UvMapping {
+    /// The transform from the space of the vertex positions to texture coordinates.
+    pub transform: Transform2D,
+}
+
+impl UvMapping {
+    /// Creates a mapping from a transform.
+    pub fn new(transform: Transform2D) -> Self {
+        UvMapping { transform }
+    }
+
+    /// Creates a mapping that maps the rectangle to the [0, 1] range.
+    ///
+    /// The rectangle is typically the bounding rectangle of the path, which can be
+    /// computed with `lyon_algorithms::aabb::bounding_rect`.
+    pub fn from_rect(rect: &Rect, aspect_ratio: AspectRatio) -> Self {
+        let w = rect.size.width;
+        let h = rect.size.height;
+        let inv = |v: f32| if v == 0.0 { 0.0 } else { 1.0 / v };
+        let (sx, sy) = match aspect_ratio {
+            AspectRatio::Stretch => (inv(w), inv(h)),
+            AspectRatio::Fit => {
+                let s = inv(w.max(h));
+                (s, s)
+            }
+            AspectRatio::Fill => {
+                let s = if w == 0.0 || h == 0.0 { inv(w.max(h)) } else { inv(w.min(h)) };
+                (s, s)
+            }
+        };
+
+        // Center the rectangle on the axis that doesn't span exactly the [0, 1] range.
+        let offset_x = (1.0 - w * sx) * 0.5;
+        let offset_y = (1.0 - h * sy) * 0.5;
+
+        UvMapping {
+            transform: Transform2D::row_major(
+                sx, 0.0,
+                0.0, sy,
+                offset_x - rect.origin.x * sx,
+                offset_y - rect.origin.y * sy,
+            ),
+        }
+    }
+
+    /// Creates a mapping from the bounding rectangle of a set of points.
+    pub fn from_points<Iter>(points: Iter, aspect_ratio: AspectRatio) -> Self
+    where
+        Iter: IntoIterator<Item = Point>,
+    {
+        Self::from_rect(&Rect::from_points(points), aspect_ratio)
+    }
+
+    /// Applies an additional transform to the texture coordinates.
+    pub fn then(&self, transform: &Transform2D) -> Self {
+        UvMapping { transform: self.transform.post_mul(transform) }
+    }
+
+    /// Computes the texture coordinates at a given position.
+    #[inline]
+    pub fn uv(&self, position: Point) -> Point {
+        self.transform.transform_point(&position)
+    }
+}
+
+/// Vertex types that have a position.
+pub trait VertexPosition {
+    fn position(&self) -> Point;
+}
+
+impl VertexPosition for Point {
+    fn position(&self) -> Point { *self }
+}
+
+impl VertexPosition for FillVertex {
+    fn position(&self) -> Point { self.position }
+}
+
+impl VertexPosition for StrokeVertex {
+    fn position(&self) -> Point { self.position }
+}
+
+/// A `VertexConstructor` that computes the texture coordinates of each vertex and
+/// forwards them along with the vertex to a function creating the output vertex.
+pub struct WithUv<F> {
+    mapping: UvMapping,
+    ctor: F,
+}
+
+impl<F> WithUv<F> {
+    pub fn new(mapping: UvMapping, ctor: F) -> Self {
+        WithUv { mapping, ctor }
+    }
+}
+
+impl<Input, VertexType, F> VertexConstructor<Input, VertexType> for WithUv<F>
+where
+    Input: VertexPosition,
+    F: Fn(Input, Point) -> VertexType,
+{
+    fn new_vertex(&mut self, input: Input) -> VertexType {
+        let uv = self.mapping.uv(input.position());
+        (self.ctor)(input, uv)
+    }
+}
+
+#[cfg(test)]
+fn assert_approx_eq(a: Point, b: Point) {
+    if (a - b).square_length() > 0.000001 {
+        panic!("{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn uv_from_rect() {
+    let r = rect(10.0, 20.0, 40.0, 20.0);
+
+    let stretch = UvMapping::from_rect(&r, AspectRatio::Stretch);
+    assert_approx_eq(stretch.uv(point(10.0, 20.0)), point(0.0, 0.0));
+    assert_approx_eq(stretch.uv(point(50.0, 40.0)), point(1.0, 1.0));
+    assert_approx_eq(stretch.uv(point(30.0, 30.0)), point(0.5, 0.5));
+
+    let fit = UvMapping::from_rect(&r, AspectRatio::Fit);
+    assert_approx_eq(fit.uv(point(10.0, 20.0)), point(0.0, 0.25));
+    assert_approx_eq(fit.uv(point(50.0, 40.0)), point(1.0, 0.75));
+
+    let fill = UvMapping::from_rect(&r, AspectRatio::Fill);
+    assert_approx_eq(fill.uv(point(10.0, 20.0)), point(-0.5, 0.0));
+    assert_approx_eq(fill.uv(point(50.0, 40.0)), point(1.5, 1.0));
+
+    let flipped = stretch.then(&Transform2D::row_major(1.0, 0.0, 0.0, -1.0, 0.0, 1.0));
+    assert_approx_eq(flipped.uv(point(10.0, 20.0)), point(0.0, 1.0));
+
+    let from_points = UvMapping::from_points(
+        vec![point(10.0, 30.0), point(50.0, 20.0), point(30.0, 40.0)],
+        AspectRatio::Stretch,
+    );
+    assert_approx_eq(from_points.uv(point(50.0, 40.0)), point(1.0, 1.0));
+}